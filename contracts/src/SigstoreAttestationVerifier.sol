@@ -2,7 +2,7 @@
 pragma solidity ^0.8.0;
 
 import "./interfaces/ISigstoreAttestationVerifier.sol";
-import {VerificationResultParser} from "./Types.sol";
+import {VerificationResultParser, FLAG_POLICY_VIOLATION} from "./Types.sol";
 import {Ownable} from "solady/auth/Ownable.sol";
 
 // ZK-Coprocessor imports:
@@ -13,15 +13,36 @@ import {IPicoVerifier} from "./zk/pico/interfaces/IPicoVerifier.sol";
 contract SigstoreAttestationVerifier is ISigstoreAttestationVerifier, Ownable {
     mapping(ZkCoProcessorType => ZkCoProcessorConfig) _zkConfig;
 
+    /// @notice Maximum number of blocks the journal's anchor block may be behind the block
+    ///         the proof is submitted in; 0 accepts journals without an anchor
+    uint64 public maxAnchorAge;
+
+    /// @notice Whether journals flagged FLAG_POLICY_VIOLATION (quarantine mode) are accepted;
+    ///         they are then reported with PolicyViolationSubmitted instead of AttestationSubmitted
+    bool public acceptPolicyViolations;
+
     // 20b15e84
     error InvalidZkCoProcessorType();
     // a18c0a0a
     error MissingZkVerifier();
     // bfec3ebd
     error MissingZkProgramId();
+    // 43667e58
+    error InvalidMaxAnchorAge();
+    // 665f0473
+    error MissingBlockAnchor();
+    // 28b3ddb7
+    error StaleBlockAnchor();
+    // a056b7dc
+    error UnknownBlockAnchor();
+    // a56d902f
+    error PolicyViolationRejected();
 
     event AttestationSubmitted(ZkCoProcessorType verifierType, bytes output);
     event ZkCoProcessorUpdated(ZkCoProcessorType indexed zkCoProcessor, bytes32 programIdentifier, address zkVerifier);
+    event MaxAnchorAgeUpdated(uint64 maxAnchorAge);
+    event PolicyViolationSubmitted(ZkCoProcessorType verifierType, bytes output);
+    event AcceptPolicyViolationsUpdated(bool acceptPolicyViolations);
 
     constructor(address owner) {
         _initializeOwner(owner);
@@ -36,6 +57,22 @@ contract SigstoreAttestationVerifier is ISigstoreAttestationVerifier, Ownable {
         emit ZkCoProcessorUpdated(_zkCoProcessor, _programIdentifier, _zkVerifier);
     }
 
+    /// @notice Require journals to be anchored to one of the last `_maxAnchorAge` blocks,
+    ///         bounding how long before submission their proofs were generated
+    /// @dev blockhash() only covers the 256 most recent blocks; 0 disables the check
+    function setMaxAnchorAge(uint64 _maxAnchorAge) external onlyOwner {
+        require(_maxAnchorAge <= 256, InvalidMaxAnchorAge());
+        maxAnchorAge = _maxAnchorAge;
+        emit MaxAnchorAgeUpdated(_maxAnchorAge);
+    }
+
+    /// @notice Accept journals of bundles that verified but violate their committed policy,
+    ///         so they can be quarantined by the consumers of PolicyViolationSubmitted
+    function setAcceptPolicyViolations(bool _acceptPolicyViolations) external onlyOwner {
+        acceptPolicyViolations = _acceptPolicyViolations;
+        emit AcceptPolicyViolationsUpdated(_acceptPolicyViolations);
+    }
+
     function programIdentifier(ZkCoProcessorType zkCoProcessorType) external view override returns (bytes32) {
         return _zkConfig[zkCoProcessorType].programIdentifier;
     }
@@ -66,8 +103,26 @@ contract SigstoreAttestationVerifier is ISigstoreAttestationVerifier, Ownable {
             revert InvalidZkCoProcessorType();
         }
 
-        emit AttestationSubmitted(zkCoProcessor, output);
         verifiedOutput = VerificationResultParser.parseVerificationResultBytes(output);
+        if (verifiedOutput.flags & FLAG_POLICY_VIOLATION != 0) {
+            require(acceptPolicyViolations, PolicyViolationRejected());
+            emit PolicyViolationSubmitted(zkCoProcessor, output);
+        } else {
+            emit AttestationSubmitted(zkCoProcessor, output);
+        }
+        _anchorCheck(verifiedOutput);
+    }
+
+    function _anchorCheck(VerificationResult memory result) private view {
+        if (maxAnchorAge == 0) {
+            return;
+        }
+        require(result.anchorBlockHash != bytes32(0), MissingBlockAnchor());
+        require(
+            result.anchorBlockNumber < block.number && block.number - result.anchorBlockNumber <= maxAnchorAge,
+            StaleBlockAnchor()
+        );
+        require(blockhash(result.anchorBlockNumber) == result.anchorBlockHash, UnknownBlockAnchor());
     }
 
     function _noneZkConfigCheck(ZkCoProcessorType zkCoProcessor) private pure {
@@ -8,31 +8,96 @@ pragma solidity >=0.8.0;
 // These types represent the output of Sigstore bundle verification.
 //
 // DigestAlgorithm: Hash algorithm identifier
-//   0 = Unknown, 1 = SHA256, 2 = SHA384
+//   0 = Unknown, 1 = SHA256, 2 = SHA384, 3 = SHA1 (git object ID)
 //
 // TimestampProofType: Type of timestamp proof used
 //   0 = None, 1 = RFC3161 (TSA), 2 = Rekor (transparency log)
 //
+// Journal versions: journals start with a version byte (JOURNAL_VERSION, 2)
+//   followed by the 8-byte timestamp, the proof type and the ABI data. Version 1
+//   journals have no version byte; their timestamp's leading byte is always 0.
+//   Their ABI data ends after rekorEntryIndex, so every later field is zero or empty.
+//
 // VerificationResult: Complete verification output including:
 //   - Certificate chain hashes (signing cert chain)
 //   - Subject digest (artifact hash from attestation)
 //   - OIDC identity (from Fulcio certificate)
 //   - Timestamp proof (RFC 3161 or Rekor)
+//   - Options hash (commitment to the expected digest/issuer/subject constraints)
+//   - Verifier crate and guest program versions that produced the journal
+//   - Annotations hash (commitment to caller-supplied key-value metadata)
+//   - Flags (verification modes, e.g. archival verification at the signing time)
+//   - Anchor block (a recent block the proof was generated after, if any)
+//   - Previous result hash (sha256 of the previous journal in a chain of custody, if any)
+//   - Verification options (the constraints optionsHash commits to, if committed)
+//   - Certificate timestamps (SCTs of the leaf certificate verified against CT log keys)
+//   - Input digest (sha256 of the prover input the guest read)
+//   - Predicate type and hash (what kind of attestation was proven, e.g. provenance or SBOM)
+//   - Policy owner hash (sha256 of the key that authorized the prover input, if signed)
 //
 // =============================================================================
 
 error InvalidDataLength();
 error InvalidCertificateHashesLength();
 error InvalidTimestampProofType();
+error OptionsHashMismatch();
+error UnsupportedJournalVersion();
+
+/// @notice Journal format version written by the current verifier
+uint8 constant JOURNAL_VERSION = 2;
 
 /// @notice Hash algorithm identifier
-/// @dev 0 = Unknown, 1 = SHA256, 2 = SHA384
+/// @dev 0 = Unknown, 1 = SHA256, 2 = SHA384, 3 = SHA1 (a git commit or tag object ID),
+///      4 = SHA512
 enum DigestAlgorithm {
     Unknown,
     Sha256,
-    Sha384
+    Sha384,
+    Sha1,
+    Sha512
+}
+
+/// @notice Kind of identity in the signing certificate's Subject Alternative Name
+/// @dev 0 = Unknown, 1 = URI (e.g. a workflow), 2 = Email (keyless signing by a human),
+///      3 = SPIFFE ID (workload identity)
+enum IdentityKind {
+    Unknown,
+    Uri,
+    Email,
+    Spiffe
 }
 
+/// @notice What the attested subject is, and so what its name and digest refer to
+/// @dev 0 = Unknown (version 1 journals), 1 = Artifact (a file or image),
+///      2 = Purl (subjectName is a package URL), 3 = Git (a commit or tag; with SHA1
+///      the digest is its object ID)
+enum SubjectKind {
+    Unknown,
+    Artifact,
+    Purl,
+    Git
+}
+
+/// @notice Bit of VerificationResult.flags set for archival verification, in which the
+///         validity of every certificate was evaluated at the signing time only
+uint8 constant FLAG_ARCHIVAL = 0x01;
+
+/// @notice Bit of VerificationResult.flags set when every DSSE signature of the envelope
+///         was verified with the leaf key, rather than only the first
+uint8 constant FLAG_ALL_SIGNATURES = 0x02;
+
+/// @notice Bit of VerificationResult.flags set when issuers were compared as normalized
+///         URLs (RFC 3986); the expected issuers are then committed normalized
+uint8 constant FLAG_NORMALIZED_ISSUER = 0x04;
+
+/// @notice Bit of VerificationResult.flags set when the subject was compared ignoring
+///         ASCII case; the expected subject is then committed in lowercase
+uint8 constant FLAG_CASE_INSENSITIVE_SUBJECT = 0x08;
+
+/// @notice Bit of VerificationResult.flags set in quarantine mode when the bundle verified but
+///         violates the policy committed in optionsHash, which was then not enforced
+uint8 constant FLAG_POLICY_VIOLATION = 0x10;
+
 /// @notice Timestamp proof type identifier
 /// @dev 0 = None, 1 = RFC3161 (TSA), 2 = Rekor (transparency log)
 enum TimestampProofType {
@@ -60,6 +125,84 @@ struct VerificationResult {
     bytes32 rekorLogId; // SHA256 of Rekor's public key
     uint64 rekorLogIndex; // Tree leaf index (for Merkle proof verification)
     uint64 rekorEntryIndex; // Entry index (for API queries)
+    // sha256(abi.encode(expectedDigest, expectedIssuer, expectedSubject, expectedSubjectName,
+    // expectedEmail, allowedEmailIssuers, expectedSpiffeTrustDomain, expectedSpiffePathPrefix,
+    // identityPolicyHash, expectedRepository, expectedWorkflowRef, expectedEventName,
+    // expectedPredicateType, maxSigningAge, verificationTime, profile, requiredTimestamp,
+    // clockSkew, repositoryPolicyHash, expectedTsaPolicy)), unset options are empty,
+    // expectedEmail is lowercase
+    bytes32 optionsHash;
+    string subjectName; // Name of the attested subject whose digest is reported
+    string verifierVersion; // sigstore-verifier crate version that ran the verification
+    string guestVersion; // zkVM guest program version that committed the journal
+    // sha256(abi.encode(string[] keys, string[] values)) over the annotations sorted by key,
+    // zero when there are none
+    bytes32 annotationsHash;
+    IdentityKind oidcSubjectKind; // What oidcSubject identifies
+    uint8 flags; // Verification modes, see the FLAG_* constants
+    // Recent block the prover was given; finding anchorBlockHash at anchorBlockNumber with
+    // blockhash() shows the proof was generated after that block. Zero when not anchored
+    uint64 anchorBlockNumber;
+    bytes32 anchorBlockHash;
+    // sha256 of the journal of the previous attestation about the same subject, linking
+    // the proofs into a chain of custody. Zero for the first link
+    bytes32 previousResultHash;
+    // abi.encode of the VerificationOptions that optionsHash is the sha256 of (see
+    // parseVerificationOptions). Empty when only the hash was committed
+    bytes verificationOptions;
+    // Signed certificate timestamps of the leaf certificate whose signature verified with
+    // a trusted certificate transparency log key. Empty when no CT log keys were given
+    CertificateTimestamp[] certificateTimestamps;
+    // sha256 of the encoded prover input the guest read, matching the digest of an input
+    // prepared (and signed) ahead of proving. Zero for version 1 journals
+    bytes32 inputDigest;
+    // predicateType of the in-toto statement, e.g. "https://slsa.dev/provenance/v1".
+    // Empty for message signatures and version 1 journals
+    string predicateType;
+    // sha256 of the statement's predicate JSON exactly as signed. Zero for message
+    // signatures and version 1 journals
+    bytes32 predicateSha256;
+    // What subjectName and subjectDigest refer to. Unknown for version 1 journals
+    SubjectKind subjectKind;
+    // sha256(bytes(subjectName)), to compare the name with a constant cheaply. Zero for
+    // version 1 journals
+    bytes32 subjectNameHash;
+    // sha256 of the Ed25519 key of the policy owner whose signature over the prover input
+    // the guest verified. Zero for unsigned inputs and version 1 journals
+    bytes32 policyOwnerHash;
+}
+
+/// @notice A certificate transparency log's promise to publish the leaf certificate
+struct CertificateTimestamp {
+    bytes32 logId; // SHA256 of the log's public key
+    uint64 timestamp; // Milliseconds since the Unix epoch
+}
+
+/// @notice The constraints a bundle was verified against, as committed in the journal
+/// @dev Unset options are empty or zero. expectedEmail is lowercase, and so are
+///      expectedSubject with FLAG_CASE_INSENSITIVE_SUBJECT and the issuers with
+///      FLAG_NORMALIZED_ISSUER
+struct VerificationOptions {
+    bytes expectedDigest;
+    string expectedIssuer;
+    string expectedSubject;
+    string expectedSubjectName;
+    string expectedEmail;
+    string[] allowedEmailIssuers;
+    string expectedSpiffeTrustDomain;
+    string expectedSpiffePathPrefix;
+    bytes32 identityPolicyHash; // sha256 of the identity policy's JSON encoding
+    string expectedRepository;
+    string expectedWorkflowRef;
+    string expectedEventName;
+    string expectedPredicateType;
+    uint64 maxSigningAge; // Seconds, checked against verificationTime
+    uint64 verificationTime;
+    string profile; // name@version of the verification profile applied, e.g. "github-strict@1"
+    uint8 requiredTimestamp; // 0 = any, 1 = RFC 3161, 2 = Rekor
+    uint64 clockSkew; // Seconds a signature may postdate verificationTime
+    bytes32 repositoryPolicyHash; // sha256 of the JSON encoding of the repository policy entry enforced
+    string expectedTsaPolicy; // Dotted OID of the TSA policy required of the RFC 3161 timestamp
 }
 
 library VerificationResultParser {
@@ -67,19 +210,100 @@ library VerificationResultParser {
         // Validate minimum data length (8 bytes timestamp + 1 byte proof type + 32 byte tuple offset + ABI data)
         if (data.length < 73) revert InvalidDataLength();
 
+        // Version 1 journals start with the timestamp, later ones with the version byte
+        uint8 version = uint8(data[0]);
+        uint256 offset;
+        if (version == JOURNAL_VERSION) {
+            offset = 1;
+            if (data.length < 74) revert InvalidDataLength();
+        } else if (version != 0) {
+            revert UnsupportedJournalVersion();
+        }
+
         // Extract timestamp and proof type from header, then parse ABI data
-        (result.timestamp, result.timestampProofType) = _parseHeader(data);
-        bytes memory abiData = _extractAbiData(data);
-        _decodeAbiData(abiData, result);
+        (result.timestamp, result.timestampProofType) = _parseHeader(data, offset);
+        bytes memory abiData = _extractAbiData(data, offset);
+        if (version == JOURNAL_VERSION) {
+            _decodeAbiData(abiData, result);
+            result.certificateTimestamps = _decodeCertificateTimestamps(abiData);
+            _decodeExtension(abiData, result);
+        } else {
+            _decodeLegacyAbiData(abiData, result);
+        }
 
         // Validate certificate hashes (minimum 2: leaf + root)
         if (result.certificateHashes.length < 2) revert InvalidCertificateHashesLength();
+
+        // The committed options must be the preimage of the options hash
+        if (result.verificationOptions.length != 0 && sha256(result.verificationOptions) != result.optionsHash) {
+            revert OptionsHashMismatch();
+        }
+    }
+
+    /// @notice Decode the constraints committed in result.verificationOptions
+    /// @dev Reverts if the options were only committed by hash
+    function parseVerificationOptions(VerificationResult memory result)
+        internal
+        pure
+        returns (VerificationOptions memory options)
+    {
+        if (result.verificationOptions.length == 0) revert InvalidDataLength();
+        (
+            options.expectedDigest,
+            options.expectedIssuer,
+            options.expectedSubject,
+            options.expectedSubjectName,
+            options.expectedEmail,
+            options.allowedEmailIssuers,
+            options.expectedSpiffeTrustDomain,
+            options.expectedSpiffePathPrefix,
+            options.identityPolicyHash,
+            options.expectedRepository,
+            options.expectedWorkflowRef,
+            options.expectedEventName,
+            options.expectedPredicateType,
+            options.maxSigningAge,
+            options.verificationTime,
+            options.profile,
+            options.requiredTimestamp,
+            options.clockSkew,
+            options.repositoryPolicyHash,
+            options.expectedTsaPolicy
+        ) = abi.decode(
+            result.verificationOptions,
+            (
+                bytes,
+                string,
+                string,
+                string,
+                string,
+                string[],
+                string,
+                string,
+                bytes32,
+                string,
+                string,
+                string,
+                string,
+                uint64,
+                uint64,
+                string,
+                uint8,
+                uint64,
+                bytes32,
+                string
+            )
+        );
     }
 
-    function _parseHeader(bytes memory data) private pure returns (uint64 signingTime, TimestampProofType proofType) {
+    function _parseHeader(bytes memory data, uint256 offset)
+        private
+        pure
+        returns (uint64 signingTime, TimestampProofType proofType)
+    {
         uint8 proofTypeRaw;
         assembly ("memory-safe") {
-            let rawData := mload(add(data, 32))
+            let rawData := mload(add(add(data, 32), offset))
             signingTime := shr(192, rawData)
             proofTypeRaw := and(shr(184, rawData), 0xff)
         }
@@ -95,13 +319,14 @@ library VerificationResultParser {
         }
     }
 
-    function _extractAbiData(bytes memory data) private pure returns (bytes memory abiData) {
-        // Skip: 9 bytes header (8 timestamp + 1 proof type) + 32 bytes tuple offset wrapper
+    function _extractAbiData(bytes memory data, uint256 offset) private pure returns (bytes memory abiData) {
+        // Skip: version byte (offset) + 9 bytes header (8 timestamp + 1 proof type) + 32 bytes tuple offset wrapper
         assembly ("memory-safe") {
-            let abiLength := sub(mload(data), 41)
+            let skip := add(offset, 41)
+            let abiLength := sub(mload(data), skip)
             abiData := mload(0x40)
             mstore(abiData, abiLength)
-            let src := add(add(data, 32), 41)
+            let src := add(add(data, 32), skip)
             let dest := add(abiData, 32)
             let remaining := abiLength
             for {} gt(remaining, 0) {} {
@@ -116,6 +341,93 @@ library VerificationResultParser {
 
     function _decodeAbiData(bytes memory abiData, VerificationResult memory result) private pure {
         // Decode all fields
+        (
+            bytes32[] memory certHashes,
+            bytes memory subjectDigest,
+            uint8 subjectDigestAlgRaw,
+            string memory oidcIssuer,
+            string memory oidcSubject,
+            string memory oidcWorkflowRef,
+            string memory oidcRepository,
+            string memory oidcEventName,
+            bytes32[] memory tsaChainHashes,
+            uint8 messageImprintAlgRaw,
+            bytes memory messageImprint,
+            bytes32 rekorLogId,
+            uint64 rekorLogIndex,
+            uint64 rekorEntryIndex,
+            bytes32 optionsHash,
+            string memory subjectName,
+            string memory verifierVersion,
+            string memory guestVersion,
+            bytes32 annotationsHash,
+            uint8 oidcSubjectKindRaw,
+            uint8 flags,
+            uint64 anchorBlockNumber,
+            bytes32 anchorBlockHash,
+            bytes32 previousResultHash,
+            bytes memory verificationOptions
+        ) = abi.decode(
+            abiData,
+            (
+                bytes32[],
+                bytes,
+                uint8,
+                string,
+                string,
+                string,
+                string,
+                string,
+                bytes32[],
+                uint8,
+                bytes,
+                bytes32,
+                uint64,
+                uint64,
+                bytes32,
+                string,
+                string,
+                string,
+                bytes32,
+                uint8,
+                uint8,
+                uint64,
+                bytes32,
+                bytes32,
+                bytes
+            )
+        );
+
+        // Assign to result struct
+        result.certificateHashes = certHashes;
+        result.subjectDigest = subjectDigest;
+        result.oidcIssuer = oidcIssuer;
+        result.oidcSubject = oidcSubject;
+        result.oidcWorkflowRef = oidcWorkflowRef;
+        result.oidcRepository = oidcRepository;
+        result.oidcEventName = oidcEventName;
+        result.tsaChainHashes = tsaChainHashes;
+        result.messageImprint = messageImprint;
+        result.rekorLogId = rekorLogId;
+        result.rekorLogIndex = rekorLogIndex;
+        result.rekorEntryIndex = rekorEntryIndex;
+        result.optionsHash = optionsHash;
+        result.subjectName = subjectName;
+        result.verifierVersion = verifierVersion;
+        result.guestVersion = guestVersion;
+        result.annotationsHash = annotationsHash;
+        result.oidcSubjectKind = _toIdentityKind(oidcSubjectKindRaw);
+        result.flags = flags;
+        result.anchorBlockNumber = anchorBlockNumber;
+        result.anchorBlockHash = anchorBlockHash;
+        result.previousResultHash = previousResultHash;
+        result.verificationOptions = verificationOptions;
+        result.subjectDigestAlgorithm = _toDigestAlgorithm(subjectDigestAlgRaw);
+        result.messageImprintAlgorithm = _toDigestAlgorithm(messageImprintAlgRaw);
+    }
+
+    /// @dev Decodes the ABI data of version 1 journals, which ends after rekorEntryIndex
+    function _decodeLegacyAbiData(bytes memory abiData, VerificationResult memory result) private pure {
         (
             bytes32[] memory certHashes,
             bytes memory subjectDigest,
@@ -151,7 +463,6 @@ library VerificationResultParser {
             )
         );
 
-        // Assign to result struct
         result.certificateHashes = certHashes;
         result.subjectDigest = subjectDigest;
         result.oidcIssuer = oidcIssuer;
@@ -168,9 +479,92 @@ library VerificationResultParser {
         result.messageImprintAlgorithm = _toDigestAlgorithm(messageImprintAlgRaw);
     }
 
+    /// @dev Decodes the field following verificationOptions, which the abi.decode in
+    ///      _decodeAbiData leaves out
+    function _decodeCertificateTimestamps(bytes memory abiData)
+        private
+        pure
+        returns (CertificateTimestamp[] memory timestamps)
+    {
+        // Head word 25 holds the offset of the array: its length, then (logId, timestamp) pairs
+        if (abiData.length < 832) revert InvalidDataLength();
+        uint256 arrayOffset;
+        assembly ("memory-safe") {
+            arrayOffset := mload(add(abiData, 832))
+        }
+        if (abiData.length < arrayOffset + 32) revert InvalidDataLength();
+        uint256 count;
+        assembly ("memory-safe") {
+            count := mload(add(add(abiData, 32), arrayOffset))
+        }
+        if (abiData.length < arrayOffset + 32 + count * 64) revert InvalidDataLength();
+
+        timestamps = new CertificateTimestamp[](count);
+        for (uint256 i = 0; i < count; i++) {
+            bytes32 logId;
+            uint256 timestamp;
+            assembly ("memory-safe") {
+                let entry := add(add(abiData, 64), add(arrayOffset, mul(i, 64)))
+                logId := mload(entry)
+                timestamp := mload(add(entry, 32))
+            }
+            if (timestamp > type(uint64).max) revert InvalidDataLength();
+            timestamps[i] = CertificateTimestamp(logId, uint64(timestamp));
+        }
+    }
+
+    /// @dev Decodes the extension tuple following certificateTimestamps, whose offset is in
+    ///      head word 26: (inputDigest, predicateType, predicateSha256, subjectKind,
+    ///      subjectNameHash, policyOwnerHash)
+    function _decodeExtension(bytes memory abiData, VerificationResult memory result) private pure {
+        if (abiData.length < 864) revert InvalidDataLength();
+        uint256 tupleOffset;
+        assembly ("memory-safe") {
+            tupleOffset := mload(add(abiData, 864))
+        }
+        if (abiData.length < tupleOffset + 192) revert InvalidDataLength();
+
+        // Copy the tuple out so abi.decode resolves the string offset relative to it
+        uint256 tupleLength = abiData.length - tupleOffset;
+        bytes memory tuple = new bytes(tupleLength);
+        assembly ("memory-safe") {
+            let src := add(add(abiData, 32), tupleOffset)
+            let dest := add(tuple, 32)
+            for { let i := 0 } lt(i, tupleLength) { i := add(i, 32) } {
+                mstore(add(dest, i), mload(add(src, i)))
+            }
+        }
+        uint8 subjectKindRaw;
+        (
+            result.inputDigest,
+            result.predicateType,
+            result.predicateSha256,
+            subjectKindRaw,
+            result.subjectNameHash,
+            result.policyOwnerHash
+        ) = abi.decode(tuple, (bytes32, string, bytes32, uint8, bytes32, bytes32));
+        result.subjectKind = _toSubjectKind(subjectKindRaw);
+    }
+
     function _toDigestAlgorithm(uint8 value) private pure returns (DigestAlgorithm) {
         if (value == 1) return DigestAlgorithm.Sha256;
         if (value == 2) return DigestAlgorithm.Sha384;
+        if (value == 3) return DigestAlgorithm.Sha1;
+        if (value == 4) return DigestAlgorithm.Sha512;
         return DigestAlgorithm.Unknown;
     }
+
+    function _toIdentityKind(uint8 value) private pure returns (IdentityKind) {
+        if (value == 1) return IdentityKind.Uri;
+        if (value == 2) return IdentityKind.Email;
+        if (value == 3) return IdentityKind.Spiffe;
+        return IdentityKind.Unknown;
+    }
+
+    function _toSubjectKind(uint8 value) private pure returns (SubjectKind) {
+        if (value == 1) return SubjectKind.Artifact;
+        if (value == 2) return SubjectKind.Purl;
+        if (value == 3) return SubjectKind.Git;
+        return SubjectKind.Unknown;
+    }
 }
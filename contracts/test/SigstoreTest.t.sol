@@ -53,6 +53,28 @@ contract SigstoreTest is Test {
         sigstoreVerifier.verifyAndAttestWithZKProof(output, ZkCoProcessorType.Succinct, proof);
     }
 
+    function testSetMaxAnchorAge() public {
+        vm.prank(admin);
+        sigstoreVerifier.setMaxAnchorAge(256);
+        assertEq(sigstoreVerifier.maxAnchorAge(), 256);
+
+        // Older blocks are out of reach of blockhash()
+        vm.prank(admin);
+        vm.expectRevert(SigstoreAttestationVerifier.InvalidMaxAnchorAge.selector);
+        sigstoreVerifier.setMaxAnchorAge(257);
+    }
+
+    function testSetAcceptPolicyViolations() public {
+        assertFalse(sigstoreVerifier.acceptPolicyViolations());
+
+        vm.prank(admin);
+        sigstoreVerifier.setAcceptPolicyViolations(true);
+        assertTrue(sigstoreVerifier.acceptPolicyViolations());
+
+        vm.expectRevert();
+        sigstoreVerifier.setAcceptPolicyViolations(false);
+    }
+
     function _readFixture(string memory path) private view returns (bytes memory output, bytes memory proof) {
         string memory json = vm.readFile(path);
         output = abi.decode(vm.parseJson(json, ".journal"), (bytes));
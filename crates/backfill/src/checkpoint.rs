@@ -0,0 +1,149 @@
+//! Backfill progress checkpoint
+//!
+//! The checkpoint lives next to the attestation store and records the
+//! discovered sources and which of them have been processed. It is rewritten
+//! atomically after discovery and after every batch, so an interrupted run loses
+//! at most one batch of work.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use sigstore_zkvm_traits::utils::write_atomic;
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::Path;
+
+/// File name of the checkpoint inside the store directory
+pub const CHECKPOINT_FILE: &str = "backfill-checkpoint.json";
+
+/// Persistent backfill progress
+///
+/// - target: What is being backfilled (`org:<name>` or `digests:<sha256 of list>`)
+/// - sources: Discovered sources, `None` until discovery has completed
+/// - completed: Sources that were downloaded and verified
+/// - failed: Sources whose download or verification failed
+/// - updated_at: Unix timestamp of the last write
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Checkpoint {
+    pub target: String,
+    pub sources: Option<Vec<String>>,
+    #[serde(default)]
+    pub completed: BTreeSet<String>,
+    #[serde(default)]
+    pub failed: BTreeSet<String>,
+    pub updated_at: u64,
+}
+
+impl Checkpoint {
+    /// Start a fresh checkpoint for a target
+    pub fn new(target: String) -> Self {
+        Self {
+            target,
+            sources: None,
+            completed: BTreeSet::new(),
+            failed: BTreeSet::new(),
+            updated_at: 0,
+        }
+    }
+
+    /// Load the checkpoint from a store directory, if one exists
+    pub fn load(store_dir: &Path) -> Result<Option<Self>> {
+        let path = store_dir.join(CHECKPOINT_FILE);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let content =
+            fs::read(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+        let checkpoint = serde_json::from_slice(&content)
+            .with_context(|| format!("Failed to parse {}", path.display()))?;
+        Ok(Some(checkpoint))
+    }
+
+    /// Persist the checkpoint into a store directory
+    pub fn save(&mut self, store_dir: &Path) -> Result<()> {
+        self.updated_at = sigstore_zkvm_traits::store::unix_now();
+        let json = serde_json::to_vec_pretty(self).context("Failed to serialize checkpoint")?;
+        write_atomic(&store_dir.join(CHECKPOINT_FILE), &json)
+    }
+
+    /// Record a source as downloaded and verified
+    pub fn mark_completed(&mut self, source: &str) {
+        self.failed.remove(source);
+        self.completed.insert(source.to_string());
+    }
+
+    /// Record a source whose download or verification failed
+    pub fn mark_failed(&mut self, source: &str) {
+        self.failed.insert(source.to_string());
+    }
+
+    /// Sources still to be processed, in discovery order
+    pub fn pending(&self, retry_failed: bool) -> Vec<String> {
+        self.sources
+            .iter()
+            .flatten()
+            .filter(|source| !self.completed.contains(*source))
+            .filter(|source| retry_failed || !self.failed.contains(*source))
+            .cloned()
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn store_dir(name: &str) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!("backfill-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn discovered(sources: &[&str]) -> Checkpoint {
+        let mut checkpoint = Checkpoint::new("digests:test".into());
+        checkpoint.sources = Some(sources.iter().map(ToString::to_string).collect());
+        checkpoint
+    }
+
+    #[test]
+    fn test_resume_skips_completed_sources() {
+        let dir = store_dir("resume");
+        assert!(Checkpoint::load(&dir).unwrap().is_none());
+
+        let mut checkpoint = discovered(&["sha256:aa", "sha256:bb", "sha256:cc"]);
+        checkpoint.mark_completed("sha256:aa");
+        checkpoint.save(&dir).unwrap();
+
+        let resumed = Checkpoint::load(&dir).unwrap().unwrap();
+        assert_eq!(resumed.target, "digests:test");
+        assert_eq!(resumed.pending(false), ["sha256:bb", "sha256:cc"]);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_failed_batch_is_persisted() {
+        let dir = store_dir("failed");
+        let mut checkpoint = discovered(&["sha256:aa", "sha256:bb", "sha256:cc"]);
+
+        // One batch with a failed download, saved before the run is interrupted
+        checkpoint.mark_failed("sha256:aa");
+        checkpoint.mark_completed("sha256:bb");
+        checkpoint.save(&dir).unwrap();
+
+        let mut resumed = Checkpoint::load(&dir).unwrap().unwrap();
+        assert_eq!(resumed.completed.len(), 1);
+        assert_eq!(resumed.pending(false), ["sha256:cc"]);
+        assert_eq!(resumed.pending(true), ["sha256:aa", "sha256:cc"]);
+
+        // A successful retry clears the failure
+        resumed.mark_completed("sha256:aa");
+        assert!(resumed.failed.is_empty());
+        assert_eq!(resumed.pending(true), ["sha256:cc"]);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_nothing_pending_before_discovery() {
+        assert!(Checkpoint::new("org:example".into()).pending(true).is_empty());
+    }
+}
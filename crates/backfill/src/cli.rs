@@ -0,0 +1,99 @@
+//! Command-line interface definitions for backfill
+//!
+//! Defines all CLI commands, subcommands, and arguments using clap.
+
+use clap::{ArgGroup, Args, Parser, Subcommand};
+use sigstore_zkvm_traits::source::InputSource;
+use std::path::PathBuf;
+
+#[derive(Parser, Debug)]
+#[command(
+    name = "backfill",
+    author,
+    version,
+    about = "Bulk verification of historical Sigstore attestations",
+    long_about = "Walk a GitHub organization (or a list of digests), download attestations, verify them in batches and record the results in an attestation store. Progress is checkpointed so interrupted runs resume where they stopped."
+)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Commands,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Commands {
+    /// Discover, download and verify attestations
+    Run(Box<RunArgs>),
+
+    /// Show the progress of the current backfill and the store contents
+    Status(StatusArgs),
+}
+
+#[derive(Args, Debug)]
+#[command(group(ArgGroup::new("target").required(true).args(["org", "digests"])))]
+pub struct RunArgs {
+    /// Attestation store directory
+    #[arg(long = "store", env = "SIGSTORE_STORE_DIR", value_name = "PATH")]
+    pub store: PathBuf,
+
+    /// Trusted root JSONL file (path or https:// URL)
+    #[arg(long = "trust-roots", value_name = "SOURCE", required = true)]
+    pub trust_roots: InputSource,
+
+    /// Expected SHA256 of the trusted root file (hex)
+    #[arg(long = "trust-roots-sha256", value_name = "HEX")]
+    pub trust_roots_sha256: Option<String>,
+
+    /// Public key (PEM) the trusted root must be signed with; the trusted root is
+    /// then expected to be a signed envelope. Repeat to accept several keys
+    #[arg(long = "trust-roots-key", value_name = "PEM")]
+    pub trust_roots_keys: Vec<PathBuf>,
+
+    /// GitHub organization whose release assets should be backfilled
+    #[arg(long = "org", value_name = "ORG")]
+    pub org: Option<String>,
+
+    /// File with one source per line (owner/repo@sha256:..., github://, oci://, maven://, https:// or a path)
+    #[arg(long = "digests", value_name = "PATH")]
+    pub digests: Option<PathBuf>,
+
+    /// Number of sources downloaded and verified between checkpoints
+    #[arg(long = "batch-size", default_value_t = 16, value_name = "N")]
+    pub batch_size: usize,
+
+    /// Maximum number of HTTP requests per second
+    #[arg(long = "rate-limit", default_value_t = 2.0, value_name = "RPS")]
+    pub rate_limit: f64,
+
+    /// Retries per source for failed downloads, with exponential backoff
+    #[arg(long = "max-retries", default_value_t = 3, value_name = "N")]
+    pub max_retries: u32,
+
+    /// Process sources that failed in a previous run again
+    #[arg(long = "retry-failed")]
+    pub retry_failed: bool,
+
+    /// Discard the existing checkpoint and start over
+    #[arg(long = "restart")]
+    pub restart: bool,
+
+    /// Archival verification: check every certificate against the signing time only,
+    /// for historical bundles whose chains have since expired
+    #[arg(long = "archival")]
+    pub archival: bool,
+
+    /// Record bundles that verify but do not attest the expected digest as policy
+    /// violations, with their flagged journal, instead of as failures
+    #[arg(long = "quarantine")]
+    pub quarantine: bool,
+
+    /// Directory for caching the downloaded trusted root
+    #[arg(long = "cache-dir", env = "SIGSTORE_CACHE_DIR", value_name = "PATH")]
+    pub cache_dir: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+pub struct StatusArgs {
+    /// Attestation store directory
+    #[arg(long = "store", env = "SIGSTORE_STORE_DIR", value_name = "PATH")]
+    pub store: PathBuf,
+}
@@ -0,0 +1,245 @@
+//! Rate-limited GitHub API client for discovering attested release assets
+//!
+//! Release assets carry a `digest` field (`sha256:...`) which is the subject
+//! digest that `actions/attest-build-provenance` attests. Walking an
+//! organization therefore means: list repositories, list their releases, and
+//! collect the asset digests.
+
+use anyhow::{bail, Context, Result};
+use reqwest::blocking::Client;
+use reqwest::header::{ACCEPT, AUTHORIZATION, RETRY_AFTER};
+use reqwest::StatusCode;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use std::time::{Duration, Instant};
+
+const GITHUB_API_URL: &str = "https://api.github.com";
+const USER_AGENT: &str = concat!("sigstore-backfill/", env!("CARGO_PKG_VERSION"));
+const PER_PAGE: usize = 100;
+/// Rate limit waits longer than this are reported instead of slept through
+const MAX_RATE_LIMIT_WAIT: Duration = Duration::from_secs(15 * 60);
+
+/// Spaces out requests to stay under a requests-per-second budget
+#[derive(Debug)]
+pub struct RateLimiter {
+    interval: Duration,
+    last: Option<Instant>,
+}
+
+impl RateLimiter {
+    /// Create a limiter allowing `per_second` requests per second (0 disables it)
+    pub fn new(per_second: f64) -> Self {
+        let interval = if per_second > 0.0 {
+            Duration::from_secs_f64(1.0 / per_second)
+        } else {
+            Duration::ZERO
+        };
+        Self {
+            interval,
+            last: None,
+        }
+    }
+
+    /// Block until the next request may be sent
+    pub fn wait(&mut self) {
+        if let Some(last) = self.last {
+            let elapsed = last.elapsed();
+            if elapsed < self.interval {
+                std::thread::sleep(self.interval - elapsed);
+            }
+        }
+        self.last = Some(Instant::now());
+    }
+}
+
+/// Repository returned by the organization listing
+#[derive(Debug, Deserialize)]
+pub struct Repository {
+    pub name: String,
+    pub owner: Owner,
+    #[serde(default)]
+    pub archived: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Owner {
+    pub login: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Release {
+    #[serde(default)]
+    assets: Vec<ReleaseAsset>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ReleaseAsset {
+    #[serde(default)]
+    digest: Option<String>,
+}
+
+/// Minimal GitHub REST client sharing one rate limiter
+pub struct GitHubClient<'a> {
+    client: Client,
+    token: Option<String>,
+    limiter: &'a mut RateLimiter,
+}
+
+impl<'a> GitHubClient<'a> {
+    /// Create a client; `GITHUB_TOKEN` is used when set
+    pub fn new(limiter: &'a mut RateLimiter) -> Result<Self> {
+        Ok(Self {
+            client: Client::builder()
+                .user_agent(USER_AGENT)
+                .build()
+                .context("Failed to create HTTP client")?,
+            token: std::env::var("GITHUB_TOKEN").ok(),
+            limiter,
+        })
+    }
+
+    /// List all repositories of an organization
+    pub fn org_repositories(&mut self, org: &str) -> Result<Vec<Repository>> {
+        self.paginate(&format!("{}/orgs/{}/repos?type=all", GITHUB_API_URL, org))
+    }
+
+    /// Collect the distinct digests of all release assets in a repository
+    pub fn release_asset_digests(&mut self, owner: &str, repo: &str) -> Result<Vec<String>> {
+        let releases: Vec<Release> =
+            self.paginate(&format!("{}/repos/{}/{}/releases?", GITHUB_API_URL, owner, repo))?;
+
+        let mut digests: Vec<String> = releases
+            .into_iter()
+            .flat_map(|release| release.assets)
+            .filter_map(|asset| asset.digest)
+            .filter(|digest| digest.starts_with("sha256:"))
+            .collect();
+        digests.sort();
+        digests.dedup();
+        Ok(digests)
+    }
+
+    fn paginate<T: DeserializeOwned>(&mut self, url: &str) -> Result<Vec<T>> {
+        let separator = if url.ends_with('?') { "" } else { "&" };
+        let mut items = Vec::new();
+
+        for page in 1.. {
+            let page_url = format!("{}{}per_page={}&page={}", url, separator, PER_PAGE, page);
+            let page_items: Vec<T> = self.get_json(&page_url)?;
+            let count = page_items.len();
+            items.extend(page_items);
+            if count < PER_PAGE {
+                break;
+            }
+        }
+
+        Ok(items)
+    }
+
+    fn get_json<T: DeserializeOwned>(&mut self, url: &str) -> Result<T> {
+        loop {
+            self.limiter.wait();
+
+            let mut request = self
+                .client
+                .get(url)
+                .header(ACCEPT, "application/vnd.github+json");
+            if let Some(token) = &self.token {
+                request = request.header(AUTHORIZATION, format!("Bearer {}", token));
+            }
+            let response = request
+                .send()
+                .with_context(|| format!("GET {} failed", url))?;
+
+            let status = response.status();
+            if status == StatusCode::FORBIDDEN || status == StatusCode::TOO_MANY_REQUESTS {
+                if let Some(wait) = rate_limit_wait(response.headers()) {
+                    if wait > MAX_RATE_LIMIT_WAIT {
+                        bail!(
+                            "GitHub rate limit exhausted for {}s; set GITHUB_TOKEN or resume later",
+                            wait.as_secs()
+                        );
+                    }
+                    println!("   GitHub rate limit reached, waiting {}s...", wait.as_secs());
+                    std::thread::sleep(wait);
+                    continue;
+                }
+            }
+            if !status.is_success() {
+                bail!("GET {} returned HTTP {}", url, status);
+            }
+
+            return response
+                .json()
+                .with_context(|| format!("Failed to parse response from {}", url));
+        }
+    }
+}
+
+/// How long GitHub asks us to wait, from `Retry-After` or the rate limit reset time
+fn rate_limit_wait(headers: &reqwest::header::HeaderMap) -> Option<Duration> {
+    let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok());
+
+    if let Some(seconds) = header(RETRY_AFTER.as_str()).and_then(|v| v.parse().ok()) {
+        return Some(Duration::from_secs(seconds));
+    }
+    if header("x-ratelimit-remaining") == Some("0") {
+        let reset: u64 = header("x-ratelimit-reset")?.parse().ok()?;
+        let now = sigstore_zkvm_traits::store::unix_now();
+        return Some(Duration::from_secs(reset.saturating_sub(now) + 1));
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::{HeaderMap, HeaderValue};
+
+    fn headers(pairs: &[(&'static str, &str)]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for (name, value) in pairs {
+            headers.insert(*name, HeaderValue::from_str(value).unwrap());
+        }
+        headers
+    }
+
+    #[test]
+    fn test_rate_limit_wait() {
+        let retry_after = headers(&[("retry-after", "30")]);
+        assert_eq!(rate_limit_wait(&retry_after), Some(Duration::from_secs(30)));
+
+        // Exhausted primary limit: wait until one second past the reset
+        let reset = sigstore_zkvm_traits::store::unix_now() + 60;
+        let exhausted = headers(&[
+            ("x-ratelimit-remaining", "0"),
+            ("x-ratelimit-reset", &reset.to_string()),
+        ]);
+        let wait = rate_limit_wait(&exhausted).unwrap();
+        assert!(wait > Duration::from_secs(59) && wait <= Duration::from_secs(61));
+
+        // A reset in the past still waits a second
+        let expired = headers(&[("x-ratelimit-remaining", "0"), ("x-ratelimit-reset", "1")]);
+        assert_eq!(rate_limit_wait(&expired), Some(Duration::from_secs(1)));
+
+        // A 403 with requests left is a permission error, not a rate limit
+        assert_eq!(rate_limit_wait(&headers(&[("x-ratelimit-remaining", "42")])), None);
+        assert_eq!(rate_limit_wait(&HeaderMap::new()), None);
+    }
+
+    #[test]
+    fn test_rate_limiter_spaces_requests() {
+        let mut limiter = RateLimiter::new(20.0);
+        let start = Instant::now();
+        for _ in 0..3 {
+            limiter.wait();
+        }
+        // The first request goes out at once, each later one 50ms after the previous
+        assert!(start.elapsed() >= Duration::from_millis(100));
+
+        let mut unlimited = RateLimiter::new(0.0);
+        assert_eq!(unlimited.interval, Duration::ZERO);
+        unlimited.wait();
+        unlimited.wait();
+    }
+}
@@ -0,0 +1,381 @@
+//! Bulk backfill of historical attestations
+//!
+//! Discovers attested release assets of a GitHub organization (or reads a list
+//! of sources), downloads their attestation bundles, verifies them in batches
+//! and records the results in an `AttestationStore`. Proofs for the stored
+//! bundles can then be generated with the zkVM hosts (`--store`), which link the
+//! proof artifacts into the same records.
+
+mod checkpoint;
+mod cli;
+mod github;
+
+use anyhow::{bail, Context, Result};
+use checkpoint::Checkpoint;
+use clap::Parser;
+use cli::{Commands, RunArgs, StatusArgs};
+use github::{GitHubClient, RateLimiter};
+use sigstore_verifier::crypto::hash::sha256;
+use sigstore_verifier::fetcher::http::default_client;
+use sigstore_verifier::fetcher::remote::{
+    fetch_github_attestation_bundles, fetch_maven_attestation_bundle, fetch_oci_referrer_bundle,
+    fetch_url,
+};
+use sigstore_verifier::types::result::{VerificationOptions, VerificationResult};
+use sigstore_zkvm_traits::failpoint::{self, Stage};
+use sigstore_zkvm_traits::source::{default_cache_dir, read_source_list, InputSource};
+use sigstore_zkvm_traits::store::{unix_now, AttestationRecord, AttestationStore, RecordStatus};
+use sigstore_zkvm_traits::workflow::{
+    load_trust_root_keys, prepare_guest_input_local, verify_guest_input, TrustSource,
+};
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+fn main() -> Result<()> {
+    let cli = cli::Cli::parse();
+
+    match cli.command {
+        Commands::Run(args) => handle_run(*args),
+        Commands::Status(args) => handle_status(args),
+    }
+}
+
+/// Handle the run command
+fn handle_run(args: RunArgs) -> Result<()> {
+    println!("Sigstore Attestation Backfill");
+    println!("=============================\n");
+
+    if args.batch_size == 0 {
+        bail!("--batch-size must be at least 1");
+    }
+
+    let store = AttestationStore::open(&args.store)?;
+    let mut limiter = RateLimiter::new(args.rate_limit);
+
+    // Step 1: Load or start the checkpoint for this target
+    let target = match (&args.org, &args.digests) {
+        (Some(org), _) => format!("org:{}", org),
+        (None, Some(path)) => {
+            let content =
+                fs::read(path).with_context(|| format!("Failed to read {}", path.display()))?;
+            format!("digests:{}", hex::encode(sha256(&content)))
+        }
+        (None, None) => unreachable!("clap requires --org or --digests"),
+    };
+
+    let mut checkpoint = match Checkpoint::load(store.root())? {
+        Some(existing) if !args.restart => {
+            if existing.target != target {
+                bail!(
+                    "Store has a checkpoint for '{}', not '{}'; pass --restart to discard it",
+                    existing.target,
+                    target
+                );
+            }
+            println!("↻ Resuming backfill of {}", target);
+            existing
+        }
+        _ => Checkpoint::new(target),
+    };
+
+    // Step 2: Discover sources (skipped when resuming after discovery)
+    if checkpoint.sources.is_none() {
+        println!("🔍 Discovering sources...");
+        let sources = match (&args.org, &args.digests) {
+            (Some(org), _) => discover_org(org, &mut limiter)?,
+            (None, Some(path)) => read_source_list(path)?,
+            (None, None) => unreachable!(),
+        };
+        println!("✓ Discovered {} source(s)\n", sources.len());
+        checkpoint.sources = Some(sources.iter().map(ToString::to_string).collect());
+        checkpoint.save(store.root())?;
+    }
+
+    // Step 3: Resolve the trusted root used for every verification
+    let cache_dir = args.cache_dir.clone().unwrap_or_else(default_cache_dir);
+    let trust = TrustSource::TrustedRoot {
+        path: args
+            .trust_roots
+            .resolve(&cache_dir, false, args.trust_roots_sha256.as_deref())
+            .context("Failed to resolve trusted root source")?,
+        keys: load_trust_root_keys(&args.trust_roots_keys)?,
+    };
+
+    // Step 4: Download and verify in batches, checkpointing after each
+    let pending = checkpoint.pending(args.retry_failed);
+    let total_batches = pending.len().div_ceil(args.batch_size);
+    println!(
+        "⚙️  {} pending, {} done, {} failed earlier",
+        pending.len(),
+        checkpoint.completed.len(),
+        checkpoint.failed.len()
+    );
+
+    for (index, batch) in pending.chunks(args.batch_size).enumerate() {
+        let mut verified = 0;
+        let mut failed = 0;
+
+        // Downloads are sequential to respect the rate limit; verification is
+        // CPU-bound and runs in parallel
+        let mut downloads = Vec::new();
+        for source_str in batch {
+            let source: InputSource = source_str.parse()?;
+            match fetch_with_retry(&source, &mut limiter, args.max_retries) {
+                Ok(bundles) => downloads.push((source_str, source, bundles)),
+                Err(e) => {
+                    println!("   ✗ {}: {:#}", source_str, e);
+                    checkpoint.mark_failed(source_str);
+                    failed += 1;
+                }
+            }
+        }
+
+        let results = std::thread::scope(|scope| {
+            let handles: Vec<_> = downloads
+                .iter()
+                .map(|(source_str, source, bundles)| {
+                    let store = &store;
+                    let trust = &trust;
+                    let archival = args.archival;
+                    let quarantine = args.quarantine;
+                    scope.spawn(move || {
+                        record_bundles(
+                            store,
+                            source_str,
+                            source,
+                            bundles,
+                            trust,
+                            archival,
+                            quarantine,
+                        )
+                    })
+                })
+                .collect();
+            handles
+                .into_iter()
+                .map(|handle| handle.join().expect("verification thread panicked"))
+                .collect::<Vec<_>>()
+        });
+
+        for ((source_str, _, _), result) in downloads.iter().zip(results) {
+            match result {
+                Ok(0) => {
+                    println!("   - {}: no attestations", source_str);
+                    checkpoint.mark_completed(source_str);
+                }
+                Ok(count) => {
+                    verified += count;
+                    checkpoint.mark_completed(source_str);
+                }
+                Err(e) => {
+                    println!("   ✗ {}: {:#}", source_str, e);
+                    checkpoint.mark_failed(source_str);
+                    failed += 1;
+                }
+            }
+        }
+
+        checkpoint.save(store.root())?;
+        println!(
+            "✓ Batch {}/{}: {} bundle(s) verified, {} source(s) failed",
+            index + 1,
+            total_batches,
+            verified,
+            failed
+        );
+    }
+
+    println!();
+    display_summary(&store, &checkpoint)?;
+    println!("\n✅ Backfill complete");
+    Ok(())
+}
+
+/// Handle the status command
+fn handle_status(args: StatusArgs) -> Result<()> {
+    let store = AttestationStore::open(&args.store)?;
+    let Some(checkpoint) = Checkpoint::load(store.root())? else {
+        println!("No backfill checkpoint in {}", store.root().display());
+        return Ok(());
+    };
+    display_summary(&store, &checkpoint)
+}
+
+/// List every attested release asset digest of an organization
+fn discover_org(org: &str, limiter: &mut RateLimiter) -> Result<Vec<InputSource>> {
+    let mut github = GitHubClient::new(limiter)?;
+    let mut sources = Vec::new();
+
+    for repo in github.org_repositories(org)? {
+        if repo.archived {
+            continue;
+        }
+        let digests = github
+            .release_asset_digests(&repo.owner.login, &repo.name)
+            .with_context(|| format!("Failed to list releases of {}/{}", repo.owner.login, repo.name))?;
+        println!("   {}/{}: {} asset digest(s)", repo.owner.login, repo.name, digests.len());
+
+        sources.extend(digests.into_iter().map(|digest| InputSource::GitHub {
+            owner: repo.owner.login.clone(),
+            repo: repo.name.clone(),
+            digest,
+        }));
+    }
+
+    Ok(sources)
+}
+
+/// Download all bundles for a source, retrying with exponential backoff
+fn fetch_with_retry(
+    source: &InputSource,
+    limiter: &mut RateLimiter,
+    max_retries: u32,
+) -> Result<Vec<Vec<u8>>> {
+    let client = default_client()?;
+    let mut attempt = 0;
+    loop {
+        limiter.wait();
+        let result = failpoint::inject(Stage::InputPrep).and_then(|_| match source {
+            InputSource::Local(path) => fs::read(path)
+                .map(|bundle| vec![bundle])
+                .with_context(|| format!("Failed to read {}", path.display())),
+            InputSource::Url(url) => fetch_url(&*client, url).map(|bundle| vec![bundle]).map_err(Into::into),
+            InputSource::Oci { registry, repository, digest } => {
+                fetch_oci_referrer_bundle(&*client, registry, repository, digest)
+                    .map(|bundle| vec![bundle])
+                    .map_err(Into::into)
+            }
+            InputSource::GitHub { owner, repo, digest } => {
+                fetch_github_attestation_bundles(&*client, owner, repo, digest).map_err(Into::into)
+            }
+            InputSource::Maven(purl) => fetch_maven_attestation_bundle(&*client, purl)
+                .map(|bundle| vec![bundle])
+                .map_err(Into::into),
+        });
+
+        match result {
+            Ok(bundles) => return Ok(bundles),
+            Err(e) if attempt < max_retries && source.is_remote() => {
+                let backoff = Duration::from_secs(1 << attempt);
+                println!("   ! {}: {:#}, retrying in {}s", source, e, backoff.as_secs());
+                std::thread::sleep(backoff);
+                attempt += 1;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// Verify the bundles of one source and record the results
+///
+/// Returns the number of bundles that verified, policy violations excluded. Individual verification
+/// failures are recorded in the store rather than returned, so that one bad
+/// attestation doesn't prevent the others from being recorded.
+fn record_bundles(
+    store: &AttestationStore,
+    source_str: &str,
+    source: &InputSource,
+    bundles: &[Vec<u8>],
+    trust: &TrustSource,
+    archival: bool,
+    quarantine: bool,
+) -> Result<usize> {
+    let subject_digest = match source {
+        InputSource::Oci { digest, .. } | InputSource::GitHub { digest, .. } => Some(digest.clone()),
+        InputSource::Local(_) | InputSource::Url(_) | InputSource::Maven(_) => None,
+    };
+    let mut verified = 0;
+
+    for bundle in bundles {
+        let key = store.put_bundle(bundle)?;
+        let outcome = verify_stored_bundle(
+            &store.bundle_path(&key),
+            trust,
+            subject_digest.as_deref(),
+            archival,
+            quarantine,
+        );
+
+        // Keep the link to an existing proof when re-verifying
+        let proof = store.get_record(&key)?.and_then(|record| record.proof);
+        let (status, journal, error) = match outcome {
+            Ok(result) if result.is_policy_violation() => {
+                (RecordStatus::PolicyViolation, Some(hex::encode(result.as_slice())), None)
+            }
+            Ok(result) => {
+                verified += 1;
+                (RecordStatus::Verified, Some(hex::encode(result.as_slice())), None)
+            }
+            Err(e) => (RecordStatus::Failed, None, Some(format!("{:#}", e))),
+        };
+
+        failpoint::inject(Stage::ArtifactWrite)?;
+        store.put_record(&AttestationRecord {
+            bundle_sha256: key,
+            source: source_str.to_string(),
+            subject_digest: subject_digest.clone(),
+            status,
+            journal,
+            error,
+            proof,
+            recorded_at: unix_now(),
+        })?;
+    }
+
+    Ok(verified)
+}
+
+/// Verify a stored bundle natively, exactly as the zkVM guests do, and return
+/// the result whose journal the guest would commit
+fn verify_stored_bundle(
+    bundle_path: &Path,
+    trust: &TrustSource,
+    subject_digest: Option<&str>,
+    archival: bool,
+    quarantine: bool,
+) -> Result<VerificationResult> {
+    let expected_digest = subject_digest
+        .map(|digest| hex::decode(digest.strip_prefix("sha256:").unwrap_or(digest)))
+        .transpose()
+        .context("Invalid subject digest")?;
+
+    let options = VerificationOptions {
+        expected_digest,
+        archival,
+        quarantine,
+        ..Default::default()
+    };
+    let input = prepare_guest_input_local(bundle_path, trust, options)?;
+
+    verify_guest_input(&input)
+}
+
+/// Print checkpoint progress and store statistics
+fn display_summary(store: &AttestationStore, checkpoint: &Checkpoint) -> Result<()> {
+    let records = store.records()?;
+    let count = |status: RecordStatus| records.iter().filter(|r| r.status == status).count();
+    let proven = records.iter().filter(|r| r.proof.is_some()).count();
+
+    println!("=== Backfill Status ===");
+    println!("Target:     {}", checkpoint.target);
+    match &checkpoint.sources {
+        Some(sources) => println!(
+            "Sources:    {} discovered, {} done, {} failed, {} pending",
+            sources.len(),
+            checkpoint.completed.len(),
+            checkpoint.failed.len(),
+            checkpoint.pending(false).len()
+        ),
+        None => println!("Sources:    discovery not finished"),
+    }
+    println!(
+        "Records:    {} total, {} verified, {} policy violations, {} failed, {} proven",
+        records.len(),
+        count(RecordStatus::Verified),
+        count(RecordStatus::PolicyViolation),
+        count(RecordStatus::Failed),
+        proven
+    );
+    Ok(())
+}
@@ -0,0 +1,82 @@
+//! Command-line interface definitions for indexer
+//!
+//! Defines all CLI commands, subcommands, and arguments using clap.
+
+use clap::{Args, Parser, Subcommand};
+use std::path::PathBuf;
+
+#[derive(Parser, Debug)]
+#[command(
+    name = "indexer",
+    author,
+    version,
+    about = "Index on-chain attestations and reconcile them with an attestation store",
+    long_about = "Follow the AttestationSubmitted events of a SigstoreAttestationVerifier contract, decode their journals and reconcile them with a local attestation store, flagging proofs that were generated but never landed on-chain and on-chain attestations missing from the store."
+)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Commands,
+
+    /// Disable colored output (also disabled when NO_COLOR is set or stdout is not a terminal)
+    #[arg(long = "no-color", global = true)]
+    pub no_color: bool,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Commands {
+    /// Index new events and reconcile them with the store
+    Watch(WatchArgs),
+}
+
+#[derive(Args, Debug)]
+pub struct WatchArgs {
+    /// Attestation store directory; the index is kept in its `onchain/` directory
+    #[arg(long = "store", env = "SIGSTORE_STORE_DIR", value_name = "PATH")]
+    pub store: PathBuf,
+
+    /// RPC URL of the chain the verifier contract is deployed on
+    #[arg(long = "rpc-url", env = "INDEXER_RPC_URL", value_name = "URL")]
+    pub rpc_url: String,
+
+    /// Address of the SigstoreAttestationVerifier contract
+    #[arg(long = "verifier-address", value_name = "ADDRESS")]
+    pub verifier_address: String,
+
+    /// First block to index when no index exists yet (e.g. the deployment block)
+    #[arg(long = "from-block", default_value_t = 0, value_name = "BLOCK")]
+    pub from_block: u64,
+
+    /// Maximum number of blocks per eth_getLogs request
+    #[arg(
+        long = "chunk-size",
+        default_value_t = 10_000,
+        value_name = "BLOCKS",
+        value_parser = clap::value_parser!(u64).range(1..)
+    )]
+    pub chunk_size: u64,
+
+    /// Number of blocks (including its own) after which an event is indexed
+    #[arg(
+        long = "confirmations",
+        default_value_t = 1,
+        value_name = "BLOCKS",
+        value_parser = clap::value_parser!(u64).range(1..)
+    )]
+    pub confirmations: u64,
+
+    /// Keep polling for new events instead of exiting after one pass
+    #[arg(long = "follow")]
+    pub follow: bool,
+
+    /// Seconds between passes with --follow
+    #[arg(long = "poll-interval", default_value_t = 12, value_name = "SECONDS")]
+    pub poll_interval: u64,
+
+    /// Path to write the reconciliation report JSON file (rewritten every pass)
+    #[arg(long = "report", value_name = "PATH")]
+    pub report: Option<PathBuf>,
+
+    /// Exit with an error if store and chain disagree (ignored with --follow)
+    #[arg(long = "fail-on-drift")]
+    pub fail_on_drift: bool,
+}
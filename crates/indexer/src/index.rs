@@ -0,0 +1,172 @@
+//! Local index of attestations submitted on-chain
+//!
+//! `verifyAndAttestWithZKProof` emits `AttestationSubmitted(verifierType, output)`
+//! for every proof it accepts, with the journal as `output`. The indexer pages
+//! through these events with `eth_getLogs` and keeps them in an index file next
+//! to the attestation store, together with the next block to read, so that every
+//! pass only fetches new blocks:
+//!
+//! ```text
+//! <store>/onchain/<chain id>-<verifier address>.json   # OnchainIndex
+//! ```
+
+use alloy::primitives::Address;
+use alloy::providers::Provider;
+use alloy::rpc::types::Filter;
+use alloy::sol;
+use alloy::sol_types::SolEvent;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use sigstore_journal::VerificationResult;
+use sigstore_zkvm_traits::utils::write_atomic;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+sol! {
+    /// `ZkCoProcessorType` is ABI-encoded as `uint8`
+    event AttestationSubmitted(uint8 verifierType, bytes output);
+}
+
+/// One `AttestationSubmitted` event
+///
+/// - tx_hash: Transaction that submitted the proof
+/// - block_number: Block the transaction was mined in
+/// - log_index: Index of the event in the block
+/// - zkvm: zkVM of the proof (`risc0`, `sp1`, `pico` or `unknown`)
+/// - journal: Hex-encoded journal
+/// - subject_digest: Hex subject digest, if the journal decodes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OnchainAttestation {
+    pub tx_hash: String,
+    pub block_number: u64,
+    pub log_index: u64,
+    pub zkvm: String,
+    pub journal: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub subject_digest: Option<String>,
+}
+
+/// Attestations submitted to one verifier contract
+///
+/// - chain_id: Chain the contract is deployed on
+/// - verifier: Address of the contract
+/// - next_block: First block not indexed yet
+/// - attestations: Indexed events, in chain order
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OnchainIndex {
+    pub chain_id: u64,
+    pub verifier: String,
+    pub next_block: u64,
+    pub attestations: Vec<OnchainAttestation>,
+}
+
+impl OnchainIndex {
+    /// Start an empty index reading from `from_block`
+    pub fn new(chain_id: u64, verifier: Address, from_block: u64) -> Self {
+        Self {
+            chain_id,
+            verifier: verifier.to_string(),
+            next_block: from_block,
+            attestations: Vec::new(),
+        }
+    }
+
+    /// Load an index, if one exists
+    pub fn load(path: &Path) -> Result<Option<Self>> {
+        if !path.exists() {
+            return Ok(None);
+        }
+        let content =
+            fs::read(path).with_context(|| format!("Failed to read {}", path.display()))?;
+        let index = serde_json::from_slice(&content)
+            .with_context(|| format!("Failed to parse {}", path.display()))?;
+        Ok(Some(index))
+    }
+
+    /// Write the index atomically
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        let json = serde_json::to_vec_pretty(self).context("Failed to serialize index")?;
+        write_atomic(path, &json)
+    }
+}
+
+/// Path of the index of `verifier` on `chain_id` inside a store directory
+pub fn index_path(store_root: &Path, chain_id: u64, verifier: Address) -> PathBuf {
+    store_root
+        .join("onchain")
+        .join(format!("{}-{}.json", chain_id, verifier))
+}
+
+/// Index all events up to and including `to_block`
+///
+/// Blocks are read in chunks of `chunk_size`, since RPC providers cap the range
+/// of `eth_getLogs`. The index is saved to `path` after every chunk, so an
+/// interrupted sync resumes from the last chunk read.
+///
+/// # Returns
+///
+/// The number of newly indexed events
+///
+/// # Errors
+///
+/// Returns an error if a log query fails or the index cannot be written
+pub async fn sync<P: Provider>(
+    provider: &P,
+    index: &mut OnchainIndex,
+    path: &Path,
+    to_block: u64,
+    chunk_size: u64,
+) -> Result<usize> {
+    let verifier: Address = index.verifier.parse().context("Invalid verifier address in index")?;
+    let mut added = 0;
+
+    while index.next_block <= to_block {
+        let chunk_end = to_block.min(index.next_block.saturating_add(chunk_size - 1));
+        let filter = Filter::new()
+            .address(verifier)
+            .event_signature(AttestationSubmitted::SIGNATURE_HASH)
+            .from_block(index.next_block)
+            .to_block(chunk_end);
+        let logs = provider.get_logs(&filter).await.with_context(|| {
+            format!("eth_getLogs failed for blocks {}-{}", index.next_block, chunk_end)
+        })?;
+
+        for log in logs {
+            // Events that don't decode were not emitted by a compatible verifier
+            let Ok(event) = log.log_decode::<AttestationSubmitted>() else {
+                continue;
+            };
+            let output = &event.inner.data.output;
+            index.attestations.push(OnchainAttestation {
+                tx_hash: log.transaction_hash.map(|hash| hash.to_string()).unwrap_or_default(),
+                block_number: log.block_number.unwrap_or(chunk_end),
+                log_index: log.log_index.unwrap_or_default(),
+                zkvm: zkvm_name(event.inner.data.verifierType).to_string(),
+                journal: hex::encode(output),
+                subject_digest: VerificationResult::from_slice(output)
+                    .ok()
+                    .map(|result| hex::encode(result.subject_digest)),
+            });
+            added += 1;
+        }
+
+        index.next_block = chunk_end + 1;
+        index.save(path)?;
+    }
+
+    Ok(added)
+}
+
+/// Name of a `ZkCoProcessorType` value
+fn zkvm_name(verifier_type: u8) -> &'static str {
+    match verifier_type {
+        1 => "risc0",
+        2 => "sp1",
+        3 => "pico",
+        _ => "unknown",
+    }
+}
@@ -0,0 +1,125 @@
+//! On-chain attestation indexer
+//!
+//! Follows the `AttestationSubmitted` events of a SigstoreAttestationVerifier
+//! contract into a local index and reconciles them with the attestation store,
+//! flagging proofs that were generated but never landed on-chain, and on-chain
+//! attestations the store knows nothing about.
+
+mod cli;
+mod index;
+mod reconcile;
+
+use alloy::primitives::Address;
+use alloy::providers::{Provider, ProviderBuilder};
+use anyhow::{bail, Context, Result};
+use clap::Parser;
+use cli::{Commands, WatchArgs};
+use index::{index_path, sync, OnchainIndex};
+use reconcile::{reconcile, Reconciliation};
+use sigstore_zkvm_traits::display::{self, paint, Style};
+use sigstore_zkvm_traits::store::AttestationStore;
+use sigstore_zkvm_traits::utils::write_atomic;
+use std::time::Duration;
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let cli = cli::Cli::parse();
+    if cli.no_color {
+        display::set_color(false);
+    }
+
+    match cli.command {
+        Commands::Watch(args) => handle_watch(args).await,
+    }
+}
+
+/// Handle the watch command
+async fn handle_watch(args: WatchArgs) -> Result<()> {
+    println!("Sigstore Attestation Indexer");
+    println!("============================\n");
+
+    // Step 1: Open the store and the index
+    let store = AttestationStore::open(&args.store)?;
+    let verifier: Address = args
+        .verifier_address
+        .parse()
+        .with_context(|| format!("Invalid verifier address: {}", args.verifier_address))?;
+    let provider = ProviderBuilder::new().connect_http(args.rpc_url.parse().context("Invalid RPC URL")?);
+    let chain_id = provider.get_chain_id().await.context("Failed to query chain ID")?;
+
+    let path = index_path(store.root(), chain_id, verifier);
+    let mut index = match OnchainIndex::load(&path)? {
+        Some(index) => index,
+        None => OnchainIndex::new(chain_id, verifier, args.from_block),
+    };
+    println!("Verifier:   {} (chain {})", verifier, chain_id);
+    println!("Index:      {} ({} events)", path.display(), index.attestations.len());
+    println!("Next block: {}\n", index.next_block);
+
+    let mut last_summary = None;
+    loop {
+        // Step 2: Index new events up to the last confirmed block
+        let latest = provider.get_block_number().await.context("Failed to query block number")?;
+        let confirmed = (latest + 1).saturating_sub(args.confirmations);
+        let added = sync(&provider, &mut index, &path, confirmed, args.chunk_size).await?;
+        if added > 0 || last_summary.is_none() {
+            println!("Indexed up to block {}: {} new events", confirmed, added);
+        }
+
+        // Step 3: Reconcile with the store
+        let reconciliation = reconcile(&store.records()?, &index);
+        let summary = (
+            reconciliation.matched.len(),
+            reconciliation.not_onchain.len(),
+            reconciliation.not_in_store.len(),
+        );
+        if last_summary != Some(summary) {
+            print_reconciliation(&reconciliation);
+            last_summary = Some(summary);
+        }
+        if let Some(ref report) = args.report {
+            let json = serde_json::to_vec_pretty(&reconciliation)
+                .context("Failed to serialize reconciliation")?;
+            write_atomic(report, &json)
+                .with_context(|| format!("Failed to write report to: {}", report.display()))?;
+        }
+
+        if !args.follow {
+            if args.fail_on_drift && reconciliation.has_drift() {
+                bail!("Attestation store and chain disagree");
+            }
+            return Ok(());
+        }
+        tokio::time::sleep(Duration::from_secs(args.poll_interval)).await;
+    }
+}
+
+/// Print the reconciliation summary and every disagreement
+fn print_reconciliation(reconciliation: &Reconciliation) {
+    let drift_style = if reconciliation.has_drift() { Style::Yellow } else { Style::Green };
+    println!(
+        "\nOn-chain and in store: {}  |  Proven but not on-chain: {}  |  On-chain but not in store: {}",
+        paint(reconciliation.matched.len(), Style::Green),
+        paint(reconciliation.not_onchain.len(), drift_style),
+        paint(reconciliation.not_in_store.len(), drift_style)
+    );
+    for proof in &reconciliation.not_onchain {
+        println!(
+            "   {}   {} ({}, {})",
+            paint("not on-chain:", Style::Yellow),
+            proof.bundle_sha256,
+            proof.source,
+            proof.proof
+        );
+    }
+    for attestation in &reconciliation.not_in_store {
+        println!(
+            "   {}   tx {} in block {} ({}, subject {})",
+            paint("not in store:", Style::Yellow),
+            attestation.tx_hash,
+            attestation.block_number,
+            attestation.zkvm,
+            attestation.subject_digest.as_deref().unwrap_or("undecodable journal")
+        );
+    }
+}
@@ -0,0 +1,190 @@
+//! Reconciliation of on-chain attestations with the attestation store
+//!
+//! Store records and on-chain events are matched by journal: a submitted proof
+//! emits exactly the journal that the record of the proven bundle carries.
+
+use crate::index::{OnchainAttestation, OnchainIndex};
+use serde::Serialize;
+use sigstore_zkvm_traits::store::AttestationRecord;
+use std::collections::{HashMap, HashSet};
+
+/// A store record whose journal was submitted on-chain
+#[derive(Debug, Clone, Serialize)]
+pub struct MatchedAttestation {
+    pub bundle_sha256: String,
+    pub tx_hash: String,
+    pub block_number: u64,
+}
+
+/// A proof in the store that never landed on-chain
+#[derive(Debug, Clone, Serialize)]
+pub struct UnsubmittedProof {
+    pub bundle_sha256: String,
+    pub source: String,
+    pub proof: String,
+}
+
+/// Result of reconciling the store with one verifier contract
+///
+/// - matched: Records whose journal was submitted, with the first submission
+/// - not_onchain: Proofs generated locally but never submitted
+/// - not_in_store: Events whose journal no store record carries
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Reconciliation {
+    pub matched: Vec<MatchedAttestation>,
+    pub not_onchain: Vec<UnsubmittedProof>,
+    pub not_in_store: Vec<OnchainAttestation>,
+}
+
+impl Reconciliation {
+    /// Whether store and chain disagree
+    pub fn has_drift(&self) -> bool {
+        !self.not_onchain.is_empty() || !self.not_in_store.is_empty()
+    }
+}
+
+/// Match the store records against the indexed events
+///
+/// Records without a proof only count towards matching, since a bundle that
+/// was verified but never proven is not expected on-chain.
+pub fn reconcile(records: &[AttestationRecord], index: &OnchainIndex) -> Reconciliation {
+    // First submission of every journal; later ones are resubmissions
+    let mut onchain: HashMap<String, &OnchainAttestation> = HashMap::new();
+    for attestation in &index.attestations {
+        onchain
+            .entry(attestation.journal.to_ascii_lowercase())
+            .or_insert(attestation);
+    }
+
+    let mut reconciliation = Reconciliation::default();
+    let mut known = HashSet::new();
+    for record in records {
+        let Some(journal) = record.journal.as_deref() else {
+            continue;
+        };
+        let journal = journal.trim_start_matches("0x").to_ascii_lowercase();
+
+        match (onchain.get(&journal), &record.proof) {
+            (Some(attestation), _) => reconciliation.matched.push(MatchedAttestation {
+                bundle_sha256: record.bundle_sha256.clone(),
+                tx_hash: attestation.tx_hash.clone(),
+                block_number: attestation.block_number,
+            }),
+            (None, Some(proof)) => reconciliation.not_onchain.push(UnsubmittedProof {
+                bundle_sha256: record.bundle_sha256.clone(),
+                source: record.source.clone(),
+                proof: proof.clone(),
+            }),
+            (None, None) => {}
+        }
+        known.insert(journal);
+    }
+
+    let mut reported = HashSet::new();
+    reconciliation.not_in_store = index
+        .attestations
+        .iter()
+        .filter(|attestation| {
+            let journal = attestation.journal.to_ascii_lowercase();
+            !known.contains(&journal) && reported.insert(journal)
+        })
+        .cloned()
+        .collect();
+
+    reconciliation
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sigstore_zkvm_traits::store::RecordStatus;
+
+    fn record(bundle: &str, journal: Option<&str>, proof: Option<&str>) -> AttestationRecord {
+        AttestationRecord {
+            bundle_sha256: bundle.into(),
+            source: format!("{}.sigstore.json", bundle),
+            subject_digest: None,
+            status: RecordStatus::Verified,
+            journal: journal.map(Into::into),
+            error: None,
+            proof: proof.map(Into::into),
+            recorded_at: 0,
+        }
+    }
+
+    fn event(journal: &str, block_number: u64) -> OnchainAttestation {
+        OnchainAttestation {
+            tx_hash: format!("0x{:064x}", block_number),
+            block_number,
+            log_index: 0,
+            zkvm: "sp1".into(),
+            journal: journal.into(),
+            subject_digest: None,
+        }
+    }
+
+    fn index(attestations: Vec<OnchainAttestation>) -> OnchainIndex {
+        OnchainIndex {
+            chain_id: 1,
+            verifier: "0x0000000000000000000000000000000000000001".into(),
+            next_block: 100,
+            attestations,
+        }
+    }
+
+    #[test]
+    fn test_reconcile_matches_first_submission() {
+        // Record journals may carry a prefix and upper case, event journals don't
+        let records = [record("a", Some("0xAABB"), Some("proofs/a.json"))];
+        let events = vec![event("aabb", 10), event("aabb", 20)];
+        let reconciliation = reconcile(&records, &index(events));
+
+        assert!(!reconciliation.has_drift());
+        assert_eq!(reconciliation.matched.len(), 1);
+        assert_eq!(reconciliation.matched[0].bundle_sha256, "a");
+        assert_eq!(reconciliation.matched[0].block_number, 10);
+    }
+
+    #[test]
+    fn test_reconcile_proof_not_onchain() {
+        let records = [
+            record("proven", Some("aabb"), Some("proofs/proven.json")),
+            // Verified but never proven: not expected on-chain
+            record("unproven", Some("ccdd"), None),
+            // Failed verification: no journal
+            record("failed", None, None),
+        ];
+        let reconciliation = reconcile(&records, &index(Vec::new()));
+
+        assert!(reconciliation.has_drift());
+        assert!(reconciliation.matched.is_empty());
+        assert!(reconciliation.not_in_store.is_empty());
+        assert_eq!(reconciliation.not_onchain.len(), 1);
+        assert_eq!(reconciliation.not_onchain[0].bundle_sha256, "proven");
+        assert_eq!(reconciliation.not_onchain[0].proof, "proofs/proven.json");
+    }
+
+    #[test]
+    fn test_reconcile_event_not_in_store() {
+        let records = [
+            record("a", Some("aabb"), Some("proofs/a.json")),
+            record("unproven", Some("ccdd"), None),
+        ];
+        let events = vec![
+            event("aabb", 10),
+            // Known to the store, if unproven there: not drift
+            event("ccdd", 11),
+            // Unknown journal, submitted twice: reported once
+            event("eeff", 12),
+            event("EEFF", 13),
+        ];
+        let reconciliation = reconcile(&records, &index(events));
+
+        assert!(reconciliation.has_drift());
+        assert!(reconciliation.not_onchain.is_empty());
+        assert_eq!(reconciliation.matched.len(), 2);
+        assert_eq!(reconciliation.not_in_store.len(), 1);
+        assert_eq!(reconciliation.not_in_store[0].journal, "eeff");
+        assert_eq!(reconciliation.not_in_store[0].block_number, 12);
+    }
+}
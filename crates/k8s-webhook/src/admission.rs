@@ -0,0 +1,212 @@
+//! Kubernetes `AdmissionReview` (admission.k8s.io/v1) types and pod spec parsing
+//!
+//! Only the fields the webhook reads or writes are modelled; the admitted
+//! object is kept as JSON so any workload kind embedding a pod spec can be
+//! handled without the Kubernetes API types.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Admission review request or response envelope
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AdmissionReview {
+    pub api_version: String,
+    pub kind: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request: Option<AdmissionRequest>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub response: Option<AdmissionResponse>,
+}
+
+impl AdmissionReview {
+    /// Wrap a response in a review of the same API version as the request
+    pub fn respond(api_version: String, response: AdmissionResponse) -> Self {
+        Self {
+            api_version,
+            kind: "AdmissionReview".to_string(),
+            request: None,
+            response: Some(response),
+        }
+    }
+}
+
+/// The object being admitted
+///
+/// - uid: Request identifier, echoed in the response
+/// - kind: Kind of the object (e.g. `Pod`, `Deployment`)
+/// - namespace: Namespace of the object, if namespaced
+/// - name: Name of the object (may be empty for generated names)
+/// - object: The object itself; absent for `DELETE`
+/// - user_info: User that sent the request to the API server
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AdmissionRequest {
+    pub uid: String,
+    pub kind: GroupVersionKind,
+    #[serde(default)]
+    pub namespace: Option<String>,
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub object: Option<Value>,
+    #[serde(default)]
+    pub user_info: Option<UserInfo>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UserInfo {
+    #[serde(default)]
+    pub username: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GroupVersionKind {
+    #[serde(default)]
+    pub group: String,
+    pub version: String,
+    pub kind: String,
+}
+
+/// Admission decision
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AdmissionResponse {
+    pub uid: String,
+    pub allowed: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<Status>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
+}
+
+impl AdmissionResponse {
+    /// Admit the object
+    pub fn allow(uid: String) -> Self {
+        Self {
+            uid,
+            allowed: true,
+            status: None,
+            warnings: Vec::new(),
+        }
+    }
+
+    /// Reject the object with a message shown to the client
+    pub fn deny(uid: String, message: String) -> Self {
+        Self {
+            uid,
+            allowed: false,
+            status: Some(Status { code: 403, message }),
+            warnings: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Status {
+    pub code: u16,
+    pub message: String,
+}
+
+/// Images of every container in the pod spec embedded in `object`
+///
+/// Handles pods and the pod templates of the built-in workload kinds
+/// (Deployment, ReplicaSet, StatefulSet, DaemonSet, Job, CronJob, ...), so that
+/// unattested images are rejected when the workload is created rather than when
+/// its controller fails to create pods. Init and ephemeral containers are
+/// included; duplicates are removed.
+///
+/// # Returns
+///
+/// The image references in spec order, or `None` if the object has no pod spec
+pub fn pod_images(kind: &str, object: &Value) -> Option<Vec<String>> {
+    let pod_spec = match kind {
+        "Pod" => object.get("spec"),
+        "CronJob" => object.pointer("/spec/jobTemplate/spec/template/spec"),
+        _ => object.pointer("/spec/template/spec"),
+    }?;
+
+    let mut images: Vec<String> = Vec::new();
+    for field in ["initContainers", "containers", "ephemeralContainers"] {
+        let containers = pod_spec.get(field).and_then(Value::as_array);
+        for container in containers.into_iter().flatten() {
+            if let Some(image) = container.get("image").and_then(Value::as_str) {
+                if !images.iter().any(|existing| existing == image) {
+                    images.push(image.to_string());
+                }
+            }
+        }
+    }
+
+    Some(images)
+}
+
+/// Image reference pinned by digest
+///
+/// - registry: Registry host used for the OCI distribution API
+/// - repository: Repository path within the registry
+/// - digest: Manifest digest (`sha256:...`)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImageRef {
+    pub registry: String,
+    pub repository: String,
+    pub digest: String,
+}
+
+/// Docker Hub's registry host, used for references without a registry
+const DOCKER_HUB_REGISTRY: &str = "registry-1.docker.io";
+
+impl ImageRef {
+    /// Parse `[registry/]repository[:tag]@sha256:<hex>`
+    ///
+    /// References without a registry resolve to Docker Hub, with the `library/`
+    /// namespace for single-component names, as the container runtime does.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the reference is not pinned by a SHA256 digest, since
+    /// a tag can be moved after admission
+    pub fn parse(image: &str) -> Result<Self, String> {
+        let (name, digest) = image.split_once('@').ok_or_else(|| {
+            format!("image {} is not pinned by digest (use name@sha256:...)", image)
+        })?;
+        let hex = digest
+            .strip_prefix("sha256:")
+            .ok_or_else(|| format!("image {} has an unsupported digest algorithm", image))?;
+        if hex.len() != 64 || !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return Err(format!("image {} has a malformed digest", image));
+        }
+
+        // Drop the tag; the digest alone identifies the image
+        let last_slash = name.rfind('/').map_or(0, |i| i + 1);
+        let name = match name[last_slash..].find(':') {
+            Some(colon) => &name[..last_slash + colon],
+            None => name,
+        };
+
+        let (registry, repository) = match name.split_once('/') {
+            Some((host, path))
+                if host.contains('.') || host.contains(':') || host == "localhost" =>
+            {
+                let host = if host == "docker.io" { DOCKER_HUB_REGISTRY } else { host };
+                (host.to_string(), path.to_string())
+            }
+            Some(_) => (DOCKER_HUB_REGISTRY.to_string(), name.to_string()),
+            None => (DOCKER_HUB_REGISTRY.to_string(), format!("library/{}", name)),
+        };
+        let repository = if registry == DOCKER_HUB_REGISTRY && !repository.contains('/') {
+            format!("library/{}", repository)
+        } else {
+            repository
+        };
+        if repository.is_empty() {
+            return Err(format!("image {} has no repository", image));
+        }
+
+        Ok(Self {
+            registry,
+            repository,
+            digest: format!("sha256:{}", hex.to_ascii_lowercase()),
+        })
+    }
+}
@@ -0,0 +1,412 @@
+//! Hash-chained audit log of admission decisions
+//!
+//! With `--audit-log`, every `AdmissionReview` the webhook answers is appended
+//! to a JSONL file as one [`AuditEntry`]: the requesting user and namespace,
+//! the options hash, each image with its digest, the digest of its bundle, the
+//! on-chain proof found for it or the error (with the verifier's error code)
+//! it was rejected with, and the decision. Each entry commits the hash of the
+//! one before it, so an entry cannot be altered, dropped or reordered without
+//! breaking the hashes of every later entry. The chain is checked when the
+//! webhook starts and when the log is exported.
+//!
+//! `export-audit` selects the entries of a time range and writes them as an
+//! [`AuditReport`] in a DSSE envelope signed with the operator's key.
+
+use crate::admission::{AdmissionRequest, AdmissionResponse};
+use crate::onchain::ProofReference;
+use crate::policy::Admission;
+use anyhow::{bail, Context, Result};
+use base64::prelude::*;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sigstore_verifier::crypto::hash::sha256;
+use sigstore_verifier::verifier::signature::pae;
+use sigstore_verifier::error::VerificationError;
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+use std::sync::Mutex;
+
+/// DSSE payload type of a signed audit report
+pub const AUDIT_REPORT_PAYLOAD_TYPE: &str = "application/vnd.sigstore-verifier.audit-report+json";
+
+/// The decision on one admission request
+///
+/// - operator: User that sent the request to the API server
+/// - tenant: Namespace of the admitted object
+/// - options_hash: Hex SHA256 of the verification options, before the expected
+///   digest is set per image
+/// - images: Outcome of each image checked; checking stops at the first rejection
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditRecord {
+    pub uid: String,
+    pub operator: String,
+    pub tenant: Option<String>,
+    pub kind: String,
+    pub name: Option<String>,
+    pub options_hash: String,
+    pub allowed: bool,
+    pub message: Option<String>,
+    pub images: Vec<ImageAudit>,
+}
+
+impl AuditRecord {
+    /// Start the record of `request`, before it is decided on
+    pub fn new(request: &AdmissionRequest, options_hash: [u8; 32]) -> Self {
+        Self {
+            uid: request.uid.clone(),
+            operator: request
+                .user_info
+                .as_ref()
+                .map(|user| user.username.clone())
+                .unwrap_or_default(),
+            tenant: request.namespace.clone(),
+            kind: request.kind.kind.clone(),
+            name: request.name.clone(),
+            options_hash: hex::encode(options_hash),
+            allowed: false,
+            message: None,
+            images: Vec::new(),
+        }
+    }
+
+    /// Complete the record with the decision `response` and the image outcomes
+    pub fn decided(self, response: &AdmissionResponse, images: Vec<ImageAudit>) -> Self {
+        Self {
+            allowed: response.allowed,
+            message: response.status.as_ref().map(|status| status.message.clone()),
+            images,
+            ..self
+        }
+    }
+}
+
+/// Outcome of one image
+///
+/// - digest: Image digest, `None` if the image is not pinned by digest
+/// - bundle_sha256: Hex SHA256 of the verified bundle
+/// - cached: Whether the admission was reused from an earlier request
+/// - outcome: `verified`, `policy_violation` (the bundle verifies but violates the
+///   options) or `invalid`, if the image got as far as verification
+/// - error_code: `VerificationError` code of a failed verification
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ImageAudit {
+    pub image: String,
+    pub digest: Option<String>,
+    pub bundle_sha256: Option<String>,
+    pub cached: bool,
+    pub proof: Option<ProofReference>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub outcome: Option<String>,
+    pub error_code: Option<u16>,
+    pub error: Option<String>,
+}
+
+impl ImageAudit {
+    pub fn admitted(image: &str, digest: Option<String>, admission: Admission) -> Self {
+        Self {
+            image: image.to_string(),
+            digest,
+            bundle_sha256: Some(hex::encode(admission.bundle_sha256)),
+            cached: admission.cached,
+            proof: admission.proof,
+            outcome: Some("verified".to_string()),
+            error_code: None,
+            error: None,
+        }
+    }
+
+    pub fn rejected(image: &str, digest: Option<String>, error: &anyhow::Error) -> Self {
+        let verification_error = error
+            .chain()
+            .find_map(|cause| cause.downcast_ref::<VerificationError>());
+        Self {
+            image: image.to_string(),
+            digest,
+            bundle_sha256: None,
+            cached: false,
+            proof: None,
+            outcome: verification_error.map(|error| {
+                let outcome = if error.is_policy_violation() { "policy_violation" } else { "invalid" };
+                outcome.to_string()
+            }),
+            error_code: verification_error.map(VerificationError::code),
+            error: Some(format!("{:#}", error)),
+        }
+    }
+}
+
+/// A record in the log
+///
+/// - sequence: Position in the log, starting at 0
+/// - previous_hash: `hash` of the previous entry, zero for the first
+/// - hash: Hex SHA256 of the JSON encoding of `(sequence, time, previous_hash, record)`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub sequence: u64,
+    pub time: DateTime<Utc>,
+    pub previous_hash: String,
+    #[serde(flatten)]
+    pub record: AuditRecord,
+    pub hash: String,
+}
+
+impl AuditEntry {
+    fn new(sequence: u64, time: DateTime<Utc>, previous_hash: [u8; 32], record: AuditRecord) -> Result<Self> {
+        let previous_hash = hex::encode(previous_hash);
+        let hash = entry_hash(sequence, &time, &previous_hash, &record)?;
+        Ok(Self {
+            sequence,
+            time,
+            previous_hash,
+            record,
+            hash: hex::encode(hash),
+        })
+    }
+}
+
+fn entry_hash(sequence: u64, time: &DateTime<Utc>, previous_hash: &str, record: &AuditRecord) -> Result<[u8; 32]> {
+    let encoded = serde_json::to_vec(&(sequence, time, previous_hash, record))?;
+    Ok(sha256(&encoded))
+}
+
+/// Append-only audit log file
+pub struct AuditLog {
+    state: Mutex<LogState>,
+}
+
+struct LogState {
+    file: File,
+    sequence: u64,
+    head: [u8; 32],
+}
+
+impl AuditLog {
+    /// Open the log at `path`, creating it if it doesn't exist
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be opened, or its entries don't form
+    /// an unbroken chain (see [`read_log`])
+    pub fn open(path: &Path) -> Result<Self> {
+        let entries = if path.exists() { read_log(path)? } else { Vec::new() };
+        let (sequence, head) = match entries.last() {
+            Some(last) => (last.sequence + 1, decode_hash(&last.hash)?),
+            None => (0, [0u8; 32]),
+        };
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .with_context(|| format!("Failed to open audit log: {}", path.display()))?;
+
+        Ok(Self {
+            state: Mutex::new(LogState { file, sequence, head }),
+        })
+    }
+
+    /// Append `record` and flush it to disk
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the entry cannot be written
+    pub fn append(&self, record: AuditRecord) -> Result<AuditEntry> {
+        let mut state = self.state.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let entry = AuditEntry::new(state.sequence, Utc::now(), state.head, record)?;
+
+        let line = serde_json::to_string(&entry)? + "\n";
+        state.file.write_all(line.as_bytes()).context("Failed to write audit log")?;
+        state.file.sync_data().context("Failed to flush audit log")?;
+
+        state.sequence += 1;
+        state.head = decode_hash(&entry.hash)?;
+        Ok(entry)
+    }
+}
+
+/// Read the log at `path` and check its chain
+///
+/// # Errors
+///
+/// Returns an error naming the first line that doesn't parse, is out of
+/// sequence, doesn't link to the previous entry or doesn't match its hash
+pub fn read_log(path: &Path) -> Result<Vec<AuditEntry>> {
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read audit log: {}", path.display()))?;
+    parse_log(&content).with_context(|| format!("Invalid audit log: {}", path.display()))
+}
+
+fn parse_log(content: &str) -> Result<Vec<AuditEntry>> {
+    let mut entries: Vec<AuditEntry> = Vec::new();
+    let mut head = hex::encode([0u8; 32]);
+
+    for (index, line) in content.lines().enumerate() {
+        let entry: AuditEntry =
+            serde_json::from_str(line).with_context(|| format!("line {}: malformed entry", index + 1))?;
+        if entry.sequence != entries.len() as u64 {
+            bail!("line {}: entry {} is out of sequence", index + 1, entry.sequence);
+        }
+        if entry.previous_hash != head {
+            bail!("line {}: entry doesn't link to the previous entry", index + 1);
+        }
+        let hash = entry_hash(entry.sequence, &entry.time, &entry.previous_hash, &entry.record)?;
+        if hex::encode(hash) != entry.hash {
+            bail!("line {}: entry doesn't match its hash", index + 1);
+        }
+
+        head = entry.hash.clone();
+        entries.push(entry);
+    }
+
+    Ok(entries)
+}
+
+fn decode_hash(hash: &str) -> Result<[u8; 32]> {
+    hex::decode(hash)
+        .ok()
+        .and_then(|bytes| bytes.try_into().ok())
+        .with_context(|| format!("Invalid entry hash: {}", hash))
+}
+
+/// Entries of a time range, with the log they were taken from
+///
+/// - log_head: `hash` of the last entry of the log when the report was made,
+///   so that later reports can be checked to extend the same chain
+/// - entries: Entries with `from <= time < until`, in log order
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditReport {
+    pub from: Option<DateTime<Utc>>,
+    pub until: DateTime<Utc>,
+    pub generated_at: DateTime<Utc>,
+    pub log_head: String,
+    pub entries: Vec<AuditEntry>,
+}
+
+impl AuditReport {
+    /// Select the entries of `log` signed in `[from, until)`
+    pub fn new(log: Vec<AuditEntry>, from: Option<DateTime<Utc>>, until: DateTime<Utc>) -> Self {
+        let log_head = log
+            .last()
+            .map_or_else(|| hex::encode([0u8; 32]), |entry| entry.hash.clone());
+        let entries = log
+            .into_iter()
+            .filter(|entry| from.is_none_or(|from| entry.time >= from) && entry.time < until)
+            .collect();
+
+        Self {
+            from,
+            until,
+            generated_at: Utc::now(),
+            log_head,
+            entries,
+        }
+    }
+
+    /// Wrap the report in a DSSE envelope signed with `key_pem`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the key is not a PKCS#8 P-256 or Ed25519 private key
+    pub fn sign(&self, key_pem: &str, keyid: String) -> Result<SignedAuditReport> {
+        let payload = serde_json::to_vec(self)?;
+        let signature = sign_message(key_pem, &pae(AUDIT_REPORT_PAYLOAD_TYPE, &payload))?;
+
+        Ok(SignedAuditReport {
+            payload_type: AUDIT_REPORT_PAYLOAD_TYPE.to_string(),
+            payload: BASE64_STANDARD.encode(payload),
+            signatures: vec![ReportSignature {
+                keyid,
+                sig: BASE64_STANDARD.encode(signature),
+            }],
+        })
+    }
+}
+
+/// DSSE envelope around an [`AuditReport`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignedAuditReport {
+    pub payload_type: String,
+    pub payload: String, // Base64-encoded JSON report
+    pub signatures: Vec<ReportSignature>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReportSignature {
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub keyid: String,
+    pub sig: String, // Base64-encoded
+}
+
+/// Sign `message` with a PKCS#8 P-256 (DER signature) or Ed25519 private key
+fn sign_message(key_pem: &str, message: &[u8]) -> Result<Vec<u8>> {
+    use p256::ecdsa::signature::Signer;
+    use p256::pkcs8::DecodePrivateKey;
+
+    if let Ok(key) = p256::ecdsa::SigningKey::from_pkcs8_pem(key_pem) {
+        let signature: p256::ecdsa::Signature = key.sign(message);
+        return Ok(signature.to_der().as_bytes().to_vec());
+    }
+    if let Ok(key) = ed25519_dalek::SigningKey::from_pkcs8_pem(key_pem) {
+        return Ok(key.sign(message).to_bytes().to_vec());
+    }
+    bail!("Unsupported private key: expected a PKCS#8 P-256 or Ed25519 key")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(uid: &str, allowed: bool) -> AuditRecord {
+        AuditRecord {
+            uid: uid.to_string(),
+            operator: "system:serviceaccount:kube-system:replicaset-controller".to_string(),
+            tenant: Some("payments".to_string()),
+            kind: "Pod".to_string(),
+            name: None,
+            options_hash: hex::encode([7u8; 32]),
+            allowed,
+            message: None,
+            images: vec![ImageAudit {
+                image: format!("ghcr.io/acme/app@sha256:{}", "ab".repeat(32)),
+                digest: Some(format!("sha256:{}", "ab".repeat(32))),
+                bundle_sha256: allowed.then(|| hex::encode([9u8; 32])),
+                cached: false,
+                proof: None,
+                outcome: Some(if allowed { "verified" } else { "invalid" }.to_string()),
+                error_code: (!allowed).then_some(101),
+                error: None,
+            }],
+        }
+    }
+
+    #[test]
+    fn test_log_is_hash_chained() {
+        let path = std::env::temp_dir().join(format!("k8s-webhook-audit-{}.jsonl", std::process::id()));
+        let _ = fs::remove_file(&path);
+
+        let log = AuditLog::open(&path).unwrap();
+        let first = log.append(record("a", true)).unwrap();
+        drop(log);
+        // Reopening resumes the chain
+        let second = AuditLog::open(&path).unwrap().append(record("b", false)).unwrap();
+        assert_eq!(second.sequence, 1);
+        assert_eq!(second.previous_hash, first.hash);
+        assert_eq!(read_log(&path).unwrap(), vec![first.clone(), second.clone()]);
+
+        let content = fs::read_to_string(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        // Altered, dropped and reordered entries break the chain
+        let altered = content.replacen("\"allowed\":false", "\"allowed\":true", 1);
+        let error = parse_log(&altered).unwrap_err().to_string();
+        assert!(error.contains("line 2: entry doesn't match its hash"), "{}", error);
+        let lines: Vec<&str> = content.lines().collect();
+        assert!(parse_log(lines[1]).is_err());
+        assert!(parse_log(&format!("{}\n{}\n", lines[1], lines[0])).is_err());
+
+        let report = AuditReport::new(vec![first, second.clone()], Some(second.time), Utc::now());
+        assert_eq!(report.log_head, second.hash);
+        assert_eq!(report.entries, vec![second]);
+    }
+}
@@ -0,0 +1,136 @@
+//! Command-line interface definitions for k8s-webhook
+//!
+//! Defines all CLI commands, subcommands, and arguments using clap.
+
+use chrono::{DateTime, Utc};
+use clap::{Args, Parser, Subcommand};
+use sigstore_zkvm_traits::source::InputSource;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+
+#[derive(Parser, Debug)]
+#[command(
+    name = "k8s-webhook",
+    author,
+    version,
+    about = "Kubernetes admission webhook gating pods on Sigstore attestations",
+    long_about = "Serve a ValidatingWebhook that extracts the image digests of admitted pods (and pod templates), fetches their Sigstore bundles from the registry's OCI referrers, verifies them, and optionally requires a proof of the attestation to have been submitted on-chain."
+)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Commands,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Commands {
+    /// Serve the admission webhook
+    Serve(Box<ServeArgs>),
+
+    /// Check an audit log and export the entries of a time range as a signed report
+    ExportAudit(ExportAuditArgs),
+}
+
+#[derive(Args, Debug)]
+pub struct ServeArgs {
+    /// Address to listen on
+    #[arg(long = "listen", default_value = "0.0.0.0:8443", value_name = "ADDR")]
+    pub listen: SocketAddr,
+
+    /// TLS certificate (PEM); the API server only calls webhooks over HTTPS
+    #[arg(long = "tls-cert", value_name = "PATH", requires = "tls_key")]
+    pub tls_cert: Option<PathBuf>,
+
+    /// TLS private key (PEM)
+    #[arg(long = "tls-key", value_name = "PATH", requires = "tls_cert")]
+    pub tls_key: Option<PathBuf>,
+
+    /// Trusted root JSONL file (path or https:// URL)
+    #[arg(long = "trust-roots", value_name = "SOURCE", required = true)]
+    pub trust_roots: InputSource,
+
+    /// Expected SHA256 of the trusted root file (hex)
+    #[arg(long = "trust-roots-sha256", value_name = "HEX")]
+    pub trust_roots_sha256: Option<String>,
+
+    /// Public key (PEM) the trusted root must be signed with; the trusted root is
+    /// then expected to be a signed envelope. Repeat to accept several keys
+    #[arg(long = "trust-roots-key", value_name = "PEM")]
+    pub trust_roots_keys: Vec<PathBuf>,
+
+    /// Expected OIDC issuer of the signing certificate
+    #[arg(long = "expected-issuer", value_name = "ISSUER")]
+    pub expected_issuer: Option<String>,
+
+    /// Expected OIDC subject of the signing certificate
+    #[arg(long = "expected-subject", value_name = "SUBJECT")]
+    pub expected_subject: Option<String>,
+
+    /// Compare issuers as normalized URLs (case, default port, trailing slash)
+    #[arg(long = "normalize-issuer")]
+    pub normalize_issuer: bool,
+
+    /// Compare the expected subject ignoring ASCII case
+    #[arg(long = "case-insensitive-subject", requires = "expected_subject")]
+    pub case_insensitive_subject: bool,
+
+    /// Identity policy (JSON) the signer must satisfy, e.g. a regex over the workflow
+    /// identities of an organization or a list of allowed identities
+    #[arg(long = "identity-policy", value_name = "PATH")]
+    pub identity_policy: Option<PathBuf>,
+
+    /// Also require an `AttestationSubmitted` event for the image digest on-chain
+    #[arg(long = "require-onchain-proof", requires_all = ["rpc_url", "verifier_address"])]
+    pub require_onchain_proof: bool,
+
+    /// RPC URL of the chain the verifier contract is deployed on
+    #[arg(long = "rpc-url", env = "WEBHOOK_RPC_URL", value_name = "URL")]
+    pub rpc_url: Option<String>,
+
+    /// Address of the SigstoreAttestationVerifier contract
+    #[arg(long = "verifier-address", value_name = "ADDRESS")]
+    pub verifier_address: Option<String>,
+
+    /// First block to search for submitted attestations
+    #[arg(long = "proof-from-block", default_value_t = 0, value_name = "BLOCK")]
+    pub proof_from_block: u64,
+
+    /// Seconds an admission decision for an image digest is reused
+    #[arg(long = "decision-ttl", default_value_t = 300, value_name = "SECS")]
+    pub decision_ttl: u64,
+
+    /// Directory for caching the trusted root and downloaded bundles
+    #[arg(long = "cache-dir", env = "SIGSTORE_CACHE_DIR", value_name = "PATH")]
+    pub cache_dir: Option<PathBuf>,
+
+    /// Append every admission decision to this hash-chained JSONL audit log; a
+    /// decision that cannot be recorded is turned into a rejection
+    #[arg(long = "audit-log", env = "WEBHOOK_AUDIT_LOG", value_name = "PATH")]
+    pub audit_log: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+pub struct ExportAuditArgs {
+    /// Audit log written by `serve --audit-log`
+    #[arg(long = "audit-log", env = "WEBHOOK_AUDIT_LOG", value_name = "PATH", required = true)]
+    pub audit_log: PathBuf,
+
+    /// First time to include (RFC 3339); defaults to the start of the log
+    #[arg(long = "from", value_name = "TIME")]
+    pub from: Option<DateTime<Utc>>,
+
+    /// Time to stop at, exclusive (RFC 3339); defaults to now
+    #[arg(long = "until", value_name = "TIME")]
+    pub until: Option<DateTime<Utc>>,
+
+    /// Private key (PKCS#8 PEM, P-256 or Ed25519) to sign the report with
+    #[arg(long = "key", value_name = "PEM", required = true)]
+    pub key: PathBuf,
+
+    /// Key identifier recorded in the envelope as a hint
+    #[arg(long = "keyid", value_name = "ID", default_value = "")]
+    pub keyid: String,
+
+    /// Signed report to write
+    #[arg(long = "output", value_name = "PATH", required = true)]
+    pub output: PathBuf,
+}
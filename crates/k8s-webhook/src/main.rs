@@ -0,0 +1,219 @@
+//! Kubernetes admission webhook for Sigstore attestations
+//!
+//! Serves a ValidatingWebhook (`POST /validate`) that admits pods, and workloads
+//! with pod templates, only when every container image is pinned by digest and
+//! carries a Sigstore attestation that verifies against the trusted root. With
+//! `--require-onchain-proof`, a proof of that attestation must also have been
+//! submitted to the SigstoreAttestationVerifier contract. With `--audit-log`,
+//! every decision is appended to a hash-chained audit log.
+
+mod admission;
+mod audit;
+mod cli;
+mod onchain;
+mod policy;
+
+use admission::{AdmissionResponse, AdmissionReview};
+use anyhow::{bail, Context, Result};
+use audit::{AuditLog, AuditRecord, AuditReport};
+use axum::extract::State;
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use axum_server::tls_rustls::RustlsConfig;
+use clap::Parser;
+use chrono::Utc;
+use cli::{Commands, ExportAuditArgs, ServeArgs};
+use onchain::ProofRegistry;
+use policy::ImagePolicy;
+use sigstore_verifier::types::result::VerificationOptions;
+use sigstore_verifier::verifier::policy::IdentityPolicy;
+use sigstore_zkvm_traits::source::default_cache_dir;
+use sigstore_zkvm_traits::workflow::{load_trust_root_keys, TrustSource};
+use std::sync::Arc;
+use std::time::Duration;
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let cli = cli::Cli::parse();
+
+    match cli.command {
+        Commands::Serve(args) => handle_serve(*args).await,
+        Commands::ExportAudit(args) => handle_export_audit(args),
+    }
+}
+
+/// Shared state of the request handlers
+struct AppState {
+    policy: ImagePolicy,
+    audit: Option<AuditLog>,
+}
+
+/// Handle the serve command
+async fn handle_serve(args: ServeArgs) -> Result<()> {
+    println!("Sigstore Admission Webhook");
+    println!("==========================\n");
+
+    // Step 1: Resolve the trusted root once; bundles are fetched per image
+    let cache_dir = args.cache_dir.clone().unwrap_or_else(default_cache_dir);
+    let trust = TrustSource::TrustedRoot {
+        path: args
+            .trust_roots
+            .resolve(&cache_dir, false, args.trust_roots_sha256.as_deref())
+            .context("Failed to resolve trusted root source")?,
+        keys: load_trust_root_keys(&args.trust_roots_keys)?,
+    };
+    println!("Trusted root: {}", args.trust_roots);
+
+    // Step 2: Build the policy
+    let identity_policy = args
+        .identity_policy
+        .as_ref()
+        .map(|path| -> Result<IdentityPolicy> {
+            let content = std::fs::read(path)
+                .with_context(|| format!("Failed to read identity policy: {}", path.display()))?;
+            let policy: IdentityPolicy = serde_json::from_slice(&content)
+                .with_context(|| format!("Invalid identity policy: {}", path.display()))?;
+            policy.validate()?;
+            Ok(policy)
+        })
+        .transpose()?;
+    let options = VerificationOptions {
+        expected_issuer: args.expected_issuer.clone(),
+        expected_subject: args.expected_subject.clone(),
+        identity_policy,
+        normalize_issuer: args.normalize_issuer,
+        case_insensitive_subject: args.case_insensitive_subject,
+        ..Default::default()
+    };
+
+    let registry = match (args.require_onchain_proof, args.rpc_url, args.verifier_address) {
+        (true, Some(rpc_url), Some(verifier_address)) => {
+            println!("On-chain proofs required from verifier {}", verifier_address);
+            Some(ProofRegistry::new(rpc_url, verifier_address, args.proof_from_block)?)
+        }
+        _ => None,
+    };
+
+    let policy = ImagePolicy::new(
+        trust,
+        cache_dir,
+        options,
+        registry,
+        Duration::from_secs(args.decision_ttl),
+    );
+    let audit = args
+        .audit_log
+        .as_ref()
+        .map(|path| {
+            println!("Audit log: {}", path.display());
+            AuditLog::open(path)
+        })
+        .transpose()?;
+
+    // Step 3: Serve
+    let app = Router::new()
+        .route("/validate", post(validate))
+        .route("/healthz", get(|| async { "ok" }))
+        .with_state(Arc::new(AppState { policy, audit }));
+
+    match (args.tls_cert, args.tls_key) {
+        (Some(cert), Some(key)) => {
+            let tls = RustlsConfig::from_pem_file(&cert, &key)
+                .await
+                .context("Failed to load TLS certificate and key")?;
+            println!("Listening on https://{}", args.listen);
+            axum_server::bind_rustls(args.listen, tls)
+                .serve(app.into_make_service())
+                .await?;
+        }
+        _ => {
+            println!("Listening on http://{} (no TLS; terminate TLS in front of the webhook)", args.listen);
+            axum_server::bind(args.listen)
+                .serve(app.into_make_service())
+                .await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Handle an `AdmissionReview` request
+///
+/// Verification fetches bundles and runs the verifier, both blocking, so it
+/// runs on the blocking thread pool, and so does appending to the audit log.
+async fn validate(
+    State(state): State<Arc<AppState>>,
+    Json(review): Json<AdmissionReview>,
+) -> Json<AdmissionReview> {
+    let api_version = review.api_version;
+    let Some(request) = review.request else {
+        let response = AdmissionResponse::deny(String::new(), "missing request".to_string());
+        return Json(AdmissionReview::respond(api_version, response));
+    };
+
+    let uid = request.uid.clone();
+    let record = AuditRecord::new(&request, state.policy.options_hash());
+    let worker = state.clone();
+    let (mut response, images) = tokio::task::spawn_blocking(move || worker.policy.review(&request))
+        .await
+        .unwrap_or_else(|e| {
+            let response = AdmissionResponse::deny(uid, format!("policy check failed: {}", e));
+            (response, Vec::new())
+        });
+
+    if state.audit.is_some() {
+        let record = record.decided(&response, images);
+        let worker = state.clone();
+        let appended = tokio::task::spawn_blocking(move || match worker.audit {
+            Some(ref audit) => audit.append(record).map(|_| ()),
+            None => Ok(()),
+        })
+        .await;
+        // A decision that is not on record is not handed out
+        let error = match appended {
+            Ok(Ok(())) => None,
+            Ok(Err(e)) => Some(format!("{:#}", e)),
+            Err(e) => Some(e.to_string()),
+        };
+        if let Some(error) = error {
+            eprintln!("audit log: {}", error);
+            response = AdmissionResponse::deny(response.uid, "admission decision could not be audited".to_string());
+        }
+    }
+
+    if !response.allowed {
+        if let Some(ref status) = response.status {
+            println!("denied {}: {}", response.uid, status.message);
+        }
+    }
+
+    Json(AdmissionReview::respond(api_version, response))
+}
+
+/// Handle the export-audit command
+fn handle_export_audit(args: ExportAuditArgs) -> Result<()> {
+    // Step 1: Read the whole log, checking its chain
+    let entries = audit::read_log(&args.audit_log)?;
+    let until = args.until.unwrap_or_else(Utc::now);
+    if args.from.is_some_and(|from| from >= until) {
+        bail!("--from must be before --until");
+    }
+
+    // Step 2: Sign the entries of the range
+    let report = AuditReport::new(entries, args.from, until);
+    let key_pem = std::fs::read_to_string(&args.key)
+        .with_context(|| format!("Failed to read {}", args.key.display()))?;
+    let signed = report.sign(&key_pem, args.keyid)?;
+    std::fs::write(&args.output, serde_json::to_string(&signed)? + "\n")
+        .with_context(|| format!("Failed to write {}", args.output.display()))?;
+
+    let rejected = report.entries.iter().filter(|entry| !entry.record.allowed).count();
+    println!(
+        "Exported {} audit entries ({} rejected) up to log head {}",
+        report.entries.len(),
+        rejected,
+        report.log_head
+    );
+    println!("Wrote signed audit report {}", args.output.display());
+    Ok(())
+}
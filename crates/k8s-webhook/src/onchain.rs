@@ -0,0 +1,127 @@
+//! Lookup of attestations submitted to the SigstoreAttestationVerifier contract
+//!
+//! `verifyAndAttestWithZKProof` emits `AttestationSubmitted(verifierType, output)`
+//! for every proof it accepts, with the journal as `output`. An image counts as
+//! having a registered proof when one of these journals attests its digest.
+
+use alloy_sol_types::{sol, SolEvent};
+use anyhow::{bail, Context, Result};
+use reqwest::blocking::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sigstore_verifier::types::result::VerificationResult;
+
+sol! {
+    /// `ZkCoProcessorType` is ABI-encoded as `uint8`
+    event AttestationSubmitted(uint8 verifierType, bytes output);
+}
+
+/// Transaction that submitted a proof, recorded in the audit log
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProofReference {
+    pub transaction_hash: String,
+    pub block_number: u64,
+}
+
+/// JSON-RPC client scoped to one verifier contract
+pub struct ProofRegistry {
+    client: Client,
+    rpc_url: String,
+    verifier_address: String,
+    from_block: u64,
+}
+
+impl ProofRegistry {
+    /// Create a registry client for the verifier contract at `verifier_address`
+    pub fn new(rpc_url: String, verifier_address: String, from_block: u64) -> Result<Self> {
+        let address = verifier_address.strip_prefix("0x").unwrap_or(&verifier_address);
+        if address.len() != 40 || hex::decode(address).is_err() {
+            bail!("Invalid verifier address: {}", verifier_address);
+        }
+
+        Ok(Self {
+            client: Client::new(),
+            rpc_url,
+            verifier_address: format!("0x{}", address.to_ascii_lowercase()),
+            from_block,
+        })
+    }
+
+    /// Find a proof attesting `subject_digest` that was submitted on-chain
+    ///
+    /// # Returns
+    ///
+    /// The transaction of the first matching `AttestationSubmitted` event, or
+    /// `None` if no proof was submitted
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the RPC request fails
+    pub fn find_proof(&self, subject_digest: &[u8]) -> Result<Option<ProofReference>> {
+        #[derive(Deserialize)]
+        struct RpcResponse {
+            result: Option<Vec<Log>>,
+            error: Option<serde_json::Value>,
+        }
+
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct Log {
+            data: String,
+            #[serde(default)]
+            transaction_hash: Option<String>,
+            #[serde(default)]
+            block_number: Option<String>,
+        }
+
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "eth_getLogs",
+            "params": [{
+                "address": self.verifier_address,
+                "fromBlock": format!("0x{:x}", self.from_block),
+                "toBlock": "latest",
+                "topics": [format!("0x{}", hex::encode(AttestationSubmitted::SIGNATURE_HASH))],
+            }],
+        });
+
+        let response: RpcResponse = self
+            .client
+            .post(&self.rpc_url)
+            .json(&request)
+            .send()
+            .and_then(|response| response.error_for_status())
+            .context("eth_getLogs request failed")?
+            .json()
+            .context("Invalid eth_getLogs response")?;
+        if let Some(error) = response.error {
+            bail!("eth_getLogs returned an error: {}", error);
+        }
+
+        for log in response.result.unwrap_or_default() {
+            let data = hex::decode(log.data.trim_start_matches("0x"))
+                .context("Invalid log data")?;
+            // Events that don't decode were not emitted by a compatible verifier
+            let Ok((_, output)) = AttestationSubmitted::abi_decode_data(&data) else {
+                continue;
+            };
+            let Ok(result) = VerificationResult::from_slice(&output) else {
+                continue;
+            };
+            if result.subject_digest == subject_digest {
+                // Pending logs have neither a transaction hash nor a block number
+                let block_number = log
+                    .block_number
+                    .and_then(|block| u64::from_str_radix(block.trim_start_matches("0x"), 16).ok())
+                    .unwrap_or_default();
+                return Ok(Some(ProofReference {
+                    transaction_hash: log.transaction_hash.unwrap_or_default(),
+                    block_number,
+                }));
+            }
+        }
+
+        Ok(None)
+    }
+}
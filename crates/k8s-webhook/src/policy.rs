@@ -0,0 +1,193 @@
+//! Admission policy: every image must carry a verified Sigstore attestation
+//!
+//! For each image of an admitted pod spec the policy fetches the Sigstore
+//! bundle attached to the image digest (OCI referrers API), verifies it exactly
+//! as the zkVM guests do with the image digest as the expected subject, and,
+//! when a proof registry is configured, requires a proof of the attestation to
+//! have been submitted on-chain.
+
+use crate::admission::{pod_images, AdmissionRequest, AdmissionResponse, ImageRef};
+use crate::audit::ImageAudit;
+use crate::onchain::{ProofReference, ProofRegistry};
+use anyhow::{bail, Context, Result};
+use sigstore_verifier::crypto::hash::sha256;
+use sigstore_verifier::types::result::VerificationOptions;
+use sigstore_zkvm_traits::source::InputSource;
+use sigstore_verifier::outcome::VerificationOutcome;
+use sigstore_zkvm_traits::workflow::{prepare_guest_input_local, verify_guest_input_outcome, TrustSource};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Attestation requirements applied to every admitted image
+pub struct ImagePolicy {
+    trust: TrustSource,
+    cache_dir: PathBuf,
+    options: VerificationOptions,
+    registry: Option<ProofRegistry>,
+    decision_ttl: Duration,
+    /// Image digests admitted within the last `decision_ttl`
+    admitted: Mutex<HashMap<String, (Instant, Admission)>>,
+}
+
+/// What an image was admitted on
+///
+/// - bundle_sha256: SHA256 of the verified bundle
+/// - proof: On-chain proof of the attestation, if proofs are required
+/// - cached: Whether the decision was reused from an earlier request
+#[derive(Debug, Clone)]
+pub struct Admission {
+    pub bundle_sha256: [u8; 32],
+    pub proof: Option<ProofReference>,
+    pub cached: bool,
+}
+
+impl ImagePolicy {
+    /// Create a policy
+    ///
+    /// # Arguments
+    ///
+    /// * `trust` - Local trusted root JSONL file and the governance keys it must be signed with
+    /// * `cache_dir` - Directory for downloaded bundles
+    /// * `options` - Identity constraints; the expected digest is set per image
+    /// * `registry` - Proof registry to consult, if on-chain proofs are required
+    /// * `decision_ttl` - How long an admitted image digest is reused without re-verification
+    pub fn new(
+        trust: TrustSource,
+        cache_dir: PathBuf,
+        options: VerificationOptions,
+        registry: Option<ProofRegistry>,
+        decision_ttl: Duration,
+    ) -> Self {
+        Self {
+            trust,
+            cache_dir,
+            options,
+            registry,
+            decision_ttl,
+            admitted: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// SHA256 of the verification options every image is checked with, before
+    /// the expected digest is set
+    pub fn options_hash(&self) -> [u8; 32] {
+        self.options.hash()
+    }
+
+    /// Decide on an admission request
+    ///
+    /// Objects without a pod spec are admitted. An object with a pod spec is
+    /// admitted only if every image is pinned by digest and passes [`Self::check_image`];
+    /// otherwise the response names the first image that failed and why.
+    ///
+    /// # Returns
+    ///
+    /// The response and the outcome of each image checked, for the audit log
+    pub fn review(&self, request: &AdmissionRequest) -> (AdmissionResponse, Vec<ImageAudit>) {
+        let images = request
+            .object
+            .as_ref()
+            .and_then(|object| pod_images(&request.kind.kind, object));
+        let Some(images) = images else {
+            return (AdmissionResponse::allow(request.uid.clone()), Vec::new());
+        };
+
+        let mut audits = Vec::new();
+        for image in &images {
+            let image_ref = ImageRef::parse(image).map_err(anyhow::Error::msg);
+            let digest = image_ref.as_ref().ok().map(|image_ref| image_ref.digest.clone());
+            match image_ref.and_then(|image_ref| self.check_image(&image_ref)) {
+                Ok(admission) => audits.push(ImageAudit::admitted(image, digest, admission)),
+                Err(e) => {
+                    let response = AdmissionResponse::deny(
+                        request.uid.clone(),
+                        format!("image {} rejected: {:#}", image, e),
+                    );
+                    audits.push(ImageAudit::rejected(image, digest, &e));
+                    return (response, audits);
+                }
+            }
+        }
+
+        (AdmissionResponse::allow(request.uid.clone()), audits)
+    }
+
+    /// Verify the attestation of one image, reusing recent admissions
+    ///
+    /// Only admissions are remembered, so a rejected image is checked again on
+    /// the next request (e.g. once its attestation has been pushed).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no bundle is attached to the digest, the bundle fails
+    /// verification or violates the policy, or a required on-chain proof is missing
+    pub fn check_image(&self, image: &ImageRef) -> Result<Admission> {
+        if let Some(admission) = self.admitted(&image.digest) {
+            return Ok(admission);
+        }
+
+        let digest = hex::decode(&image.digest["sha256:".len()..]).context("Invalid image digest")?;
+
+        // Fetch the bundle attached to the image digest
+        let source = InputSource::Oci {
+            registry: image.registry.clone(),
+            repository: image.repository.clone(),
+            digest: image.digest.clone(),
+        };
+        let bundle_path = source
+            .resolve(&self.cache_dir, false, None)
+            .context("Failed to fetch attestation bundle")?;
+
+        // Verify it with the image digest as the expected subject
+        let options = VerificationOptions {
+            expected_digest: Some(digest.clone()),
+            ..self.options.clone()
+        };
+        let input = prepare_guest_input_local(
+            &bundle_path,
+            &self.trust,
+            options,
+        )?;
+        match verify_guest_input_outcome(&input) {
+            VerificationOutcome::Verified(_) => {}
+            VerificationOutcome::PolicyViolation { violation, .. } => {
+                return Err(anyhow::Error::new(violation).context("attestation verifies but violates the policy"));
+            }
+            VerificationOutcome::Invalid { error } => {
+                return Err(anyhow::Error::new(error).context("Native verification failed"));
+            }
+        }
+
+        let proof = match self.registry {
+            Some(ref registry) => match registry.find_proof(&digest)? {
+                Some(proof) => Some(proof),
+                None => bail!("no proof of the attestation has been submitted on-chain"),
+            },
+            None => None,
+        };
+
+        let admission = Admission {
+            bundle_sha256: sha256(&input.bundle_json),
+            proof,
+            cached: false,
+        };
+        self.admitted
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .insert(image.digest.clone(), (Instant::now(), admission.clone()));
+        Ok(admission)
+    }
+
+    fn admitted(&self, digest: &str) -> Option<Admission> {
+        let admitted = self.admitted.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        admitted
+            .get(digest)
+            .filter(|(at, _)| at.elapsed() < self.decision_ttl)
+            .map(|(_, admission)| Admission {
+                cached: true,
+                ..admission.clone()
+            })
+    }
+}
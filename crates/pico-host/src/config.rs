@@ -2,9 +2,20 @@
 //!
 //! Defines configuration structures for Pico zkVM prover.
 
-use crate::cli::ProveArgs;
+use crate::cli::ProverArgs;
+use anyhow::{bail, Context, Result};
+use sigstore_verifier::crypto::hash::sha256;
+use sigstore_zkvm_traits::paths::{self, long_path};
+use sigstore_zkvm_traits::utils::ProofArtifact;
+use std::collections::BTreeMap;
 use std::path::PathBuf;
 
+/// Artifact metadata key of the field type
+pub const FIELD_TYPE_KEY: &str = "field_type";
+
+/// Artifact metadata key of the artifacts directory fingerprint
+pub const ARTIFACTS_FINGERPRINT_KEY: &str = "artifacts_fingerprint";
+
 /// Pico prover configuration
 #[derive(Debug, Clone)]
 pub struct PicoConfig {
@@ -41,10 +52,82 @@ impl PicoConfig {
     }
 
     /// Build a PicoConfig from CLI arguments
-    pub fn from_cli_args(args: &ProveArgs) -> Self {
+    ///
+    /// The artifacts path is made absolute and normalized, since the Pico SDK
+    /// hands it on to its EVM proving backend, which does not share the current
+    /// directory or understand mixed separators.
+    pub fn from_cli_args(args: &ProverArgs) -> Self {
         PicoConfig {
-            artifacts_path: args.artifacts_path.clone(),
+            artifacts_path: paths::resolve(&args.artifacts_path)
+                .unwrap_or_else(|_| args.artifacts_path.clone()),
             field_type: args.field_type.as_str().to_string(),
         }
     }
+
+    /// Fingerprint of the artifacts directory: the SHA256 of its `vm_vk`
+    ///
+    /// The EVM verifying key determines the on-chain Groth16 verifier a proof
+    /// checks against. `None` before the trusted setup has written it (and in
+    /// DEV_MODE, which skips the setup).
+    pub fn artifacts_fingerprint(&self) -> Result<Option<String>> {
+        let vk_path = long_path(&self.artifacts_path.join("vm_vk"));
+        if !vk_path.exists() {
+            return Ok(None);
+        }
+        let vk = std::fs::read(&vk_path)
+            .with_context(|| format!("Failed to read {}", vk_path.display()))?;
+        Ok(Some(format!("0x{}", hex::encode(sha256(&vk)))))
+    }
+
+    /// Proof artifact metadata recording the field type and artifacts fingerprint
+    pub fn artifact_metadata(&self) -> Result<BTreeMap<String, String>> {
+        let mut metadata = BTreeMap::from([(FIELD_TYPE_KEY.to_string(), self.field_type.clone())]);
+        if let Some(fingerprint) = self.artifacts_fingerprint()? {
+            metadata.insert(ARTIFACTS_FINGERPRINT_KEY.to_string(), fingerprint);
+        }
+        Ok(metadata)
+    }
+
+    /// Check that a proof artifact was generated with this configuration
+    ///
+    /// KoalaBear and BabyBear proofs, and proofs of different trusted setups,
+    /// check against different on-chain verifiers, so an artifact recording
+    /// another field type or artifacts fingerprint is refused. Returns the
+    /// metadata keys the artifact doesn't record (artifacts written before
+    /// they were), which could not be checked.
+    pub fn check_artifact(&self, artifact: &ProofArtifact) -> Result<Vec<&'static str>> {
+        if artifact.zkvm != "pico" {
+            bail!("Proof artifact is for {}, not pico", artifact.zkvm);
+        }
+
+        let mut unchecked = Vec::new();
+        match artifact.metadata.get(FIELD_TYPE_KEY) {
+            Some(field_type) if *field_type != self.field_type => bail!(
+                "Proof artifact was generated with field type {}, not {} (pass --field-type {})",
+                field_type,
+                self.field_type,
+                field_type
+            ),
+            Some(_) => {}
+            None => unchecked.push(FIELD_TYPE_KEY),
+        }
+        match artifact.metadata.get(ARTIFACTS_FINGERPRINT_KEY) {
+            Some(fingerprint) => match self.artifacts_fingerprint()? {
+                Some(ref local) if local == fingerprint => {}
+                Some(local) => bail!(
+                    "Proof artifact was generated with artifacts fingerprint {}, but {} has {}",
+                    fingerprint,
+                    self.artifacts_path.display(),
+                    local
+                ),
+                None => bail!(
+                    "Proof artifact records artifacts fingerprint {}, but {} has no vm_vk to compare it with",
+                    fingerprint,
+                    self.artifacts_path.display()
+                ),
+            },
+            None => unchecked.push(ARTIFACTS_FINGERPRINT_KEY),
+        }
+        Ok(unchecked)
+    }
 }
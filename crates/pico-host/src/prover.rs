@@ -9,10 +9,61 @@ use p3_field::PrimeField;
 use pico_sdk::client::{DefaultProverClient, KoalaBearProverClient};
 use pico_sdk::HashableKey;
 use sigstore_pico_methods::PICO_SIGSTORE_ELF;
+use sigstore_zkvm_traits::env;
 use sigstore_zkvm_traits::error::ZkVmError;
+use sigstore_zkvm_traits::failpoint::{self, Stage};
+use sigstore_zkvm_traits::paths::long_path;
+use sigstore_zkvm_traits::setup::SetupLock;
 use sigstore_zkvm_traits::traits::ZkVmProver;
 use sigstore_zkvm_traits::types::ProverInput;
 
+/// Encode the proof in `proof.data` as `uint256[8]`
+///
+/// The file holds comma-separated hex values: the 8 proof values followed by
+/// the witness. Whitespace around values, CRLF line endings, a UTF-8 byte
+/// order mark and a trailing comma are tolerated, as the file may have been
+/// written or edited on Windows.
+fn parse_proof_data(proof_data: &str) -> Result<Vec<u8>, ZkVmError> {
+    let hex_strings: Vec<&str> = proof_data
+        .trim_start_matches('\u{feff}')
+        .split(',')
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .collect();
+
+    if hex_strings.len() < 8 {
+        return Err(ZkVmError::ProofGenerationError(format!(
+            "Invalid proof.data: expected at least 8 values, got {}",
+            hex_strings.len()
+        )));
+    }
+
+    // Take first 8 values (the proof), last 2 are witness
+    let proof_values = &hex_strings[0..8];
+
+    // Encode as uint256[8]: just concatenate 8 * 32 bytes
+    let mut encoded = Vec::with_capacity(8 * 32);
+
+    // Concatenate the 8 proof values (each already 32 bytes)
+    for hex_str in proof_values {
+        let hex_str = hex_str.trim_start_matches("0x");
+        let bytes = hex::decode(hex_str).map_err(|e| {
+            ZkVmError::ProofGenerationError(format!("Failed to decode proof hex string: {}", e))
+        })?;
+
+        if bytes.len() != 32 {
+            return Err(ZkVmError::ProofGenerationError(format!(
+                "Invalid proof value: expected 32 bytes, got {}",
+                bytes.len()
+            )));
+        }
+
+        encoded.extend_from_slice(&bytes);
+    }
+
+    Ok(encoded)
+}
+
 pub struct PicoProver {
     elf: &'static [u8],
 }
@@ -50,25 +101,39 @@ impl ZkVmProver for PicoProver {
 
         // Emulate first to get public buffer
         println!("Emulating program...");
+        failpoint::inject(Stage::Emulation)?;
         let (reports, public_buffer) = client.emulate(stdin_builder.clone());
         let total_cycles: u64 = reports.iter().map(|r| r.current_cycle).sum();
         println!("Emulation cycles: {}", total_cycles);
 
+        // The setup keys and proof.data are shared by every job using the artifacts
+        // directory, so jobs take turns with it until proof.data has been read
+        let setup_lock = SetupLock::acquire(&config.artifacts_path)
+            .map_err(|e| ZkVmError::ProofGenerationError(format!("{:#}", e)))?;
+
         // Generate proof if not in dev mode
-        if std::env::var("DEV_MODE").is_err() || std::env::var("DEV_MODE").unwrap().is_empty() {
+        if !env::config().dev_mode {
             println!(
                 "Begin proving with Pico zkVM (field: {})",
                 config.field_type
             );
 
-            // Check if trusted setup is needed (vm_pk exists)
-            let proving_key_path = config.artifacts_path.join("vm_pk");
-            let need_setup = !proving_key_path.exists();
+            // Check if trusted setup is needed (vm_pk exists), now that no other job can be
+            // performing it; a setup that was interrupted is performed again
+            let proving_key_path = long_path(&config.artifacts_path.join("vm_pk"));
+            let need_setup = !proving_key_path.exists() || setup_lock.interrupted();
 
             if need_setup {
-                println!("Performing trusted setup (first time)...");
+                if setup_lock.interrupted() {
+                    println!("Previous trusted setup was interrupted; performing it again...");
+                } else {
+                    println!("Performing trusted setup (first time)...");
+                }
+                setup_lock
+                    .begin_setup()
+                    .map_err(|e| ZkVmError::ProofGenerationError(format!("{:#}", e)))?;
             } else {
-                println!("Using existing proving key at {:?}", proving_key_path);
+                println!("Using existing proving key at {}", proving_key_path.display());
             }
 
             client
@@ -82,6 +147,13 @@ impl ZkVmProver for PicoProver {
                     ZkVmError::ProofGenerationError(format!("Failed to generate Pico proof: {}", e))
                 })?;
 
+            // Setup and proof are one call, so a setup is complete once its proof is
+            if need_setup {
+                setup_lock
+                    .finish_setup()
+                    .map_err(|e| ZkVmError::ProofGenerationError(format!("{:#}", e)))?;
+            }
+
             println!("Proof generated successfully");
         } else {
             println!("DEV_MODE enabled, skipping proof generation");
@@ -91,57 +163,34 @@ impl ZkVmProver for PicoProver {
         let journal = public_buffer.to_vec();
 
         // Read and encode proof from proof.data
-        let proof_data_path = config.artifacts_path.join("proof.data");
+        let proof_data_path = long_path(&config.artifacts_path.join("proof.data"));
         let proof_bytes = if proof_data_path.exists() {
             let proof_data = std::fs::read_to_string(&proof_data_path).map_err(|e| {
                 ZkVmError::ProofGenerationError(format!("Failed to read proof.data: {}", e))
             })?;
-
-            // Parse comma-separated hex strings
-            let hex_strings: Vec<&str> = proof_data.split(',').collect();
-
-            if hex_strings.len() < 8 {
-                return Err(ZkVmError::ProofGenerationError(format!(
-                    "Invalid proof.data: expected at least 8 values, got {}",
-                    hex_strings.len()
-                )));
-            }
-
-            // Take first 8 values (the proof), last 2 are witness
-            let proof_values = &hex_strings[0..8];
-
-            // Encode as uint256[8]: just concatenate 8 * 32 bytes
-            let mut encoded = Vec::with_capacity(8 * 32);
-
-            // Concatenate the 8 proof values (each already 32 bytes)
-            for hex_str in proof_values {
-                let hex_str = hex_str.trim().trim_start_matches("0x");
-                let bytes = hex::decode(hex_str).map_err(|e| {
-                    ZkVmError::ProofGenerationError(format!(
-                        "Failed to decode proof hex string: {}",
-                        e
-                    ))
-                })?;
-
-                if bytes.len() != 32 {
-                    return Err(ZkVmError::ProofGenerationError(format!(
-                        "Invalid proof value: expected 32 bytes, got {}",
-                        bytes.len()
-                    )));
-                }
-
-                encoded.extend_from_slice(&bytes);
-            }
-
-            encoded
+            parse_proof_data(&proof_data)?
         } else {
             println!("proof.data not found, returning empty proof");
             Vec::new()
         };
+        drop(setup_lock);
 
         Ok((journal, proof_bytes))
     }
 
+    fn execute(&self, input: &ProverInput) -> Result<Vec<u8>, ZkVmError> {
+        let input_bytes = input
+            .encode_input()
+            .map_err(|e| ZkVmError::InvalidInput(format!("Failed to encode ProverInput: {}", e)))?;
+
+        let client = DefaultProverClient::new(self.elf);
+        let mut stdin_builder = client.new_stdin_builder();
+        stdin_builder.write_slice(&input_bytes);
+
+        let (_, public_buffer) = client.emulate(stdin_builder);
+        Ok(public_buffer.to_vec())
+    }
+
     fn program_identifier(&self) -> Result<String, ZkVmError> {
         // Create KoalaBear client to compute VK
         let client = KoalaBearProverClient::new(self.elf);
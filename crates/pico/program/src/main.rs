@@ -3,16 +3,34 @@ pico_sdk::entrypoint!(main);
 
 use pico_sdk::io::{commit_bytes, read_vec};
 
-use sigstore_verifier::{AttestationVerifier, types::result::VerificationResult};
+use sigstore_verifier::{AttestationVerifier, types::result::{annotations_hash, VerificationResult}};
+use sigstore_verifier::crypto::hash::sha256;
 use sigstore_zkvm_traits::types::ProverInput;
 
+#[cfg(feature = "alloc-profile")]
+#[global_allocator]
+static ALLOCATOR: sigstore_verifier::profile::CountingAllocator =
+    sigstore_verifier::profile::CountingAllocator::new();
+
 fn main() {
+    // Mark each verification step for the emulator's cycle tracker
+    #[cfg(feature = "cycle-profile")]
+    sigstore_verifier::profile::set_cycle_source(sigstore_verifier::profile::CycleSource::Markers {
+        start: "cycle-tracker-start: ",
+        end: "cycle-tracker-end: ",
+    });
+
     // Read input from host
     let input_bytes: Vec<u8> = read_vec();
 
     let input: ProverInput = ProverInput::parse_input(&input_bytes)
         .expect("Failed to parse ProverInput");
 
+    // A guest built with SIGSTORE_POLICY_OWNER_KEY only proves inputs signed by that key
+    let policy_owner_hash = input
+        .verify_policy_owner(option_env!("SIGSTORE_POLICY_OWNER_KEY"))
+        .unwrap_or_else(|e| panic!("Failed to verify policy owner: {}", e));
+
     let verifier = AttestationVerifier::new();
 
     let output = verifier.verify_bundle_bytes(
@@ -22,8 +40,21 @@ fn main() {
         input.tsa_cert_chain.as_ref(),
     );
 
-    assert!(output.is_ok(), "Failed to verify bundle");
-
-    let verification_result: VerificationResult = output.unwrap();
+    // Abort with the error's stable code, so that failed proving runs can be told apart
+    let mut verification_result: VerificationResult = match output {
+        Ok(result) => result,
+        Err(error) => panic!("Failed to verify bundle (error {}): {}", error.code(), error),
+    };
+    verification_result.guest_version = env!("CARGO_PKG_VERSION").to_string();
+    verification_result.annotations_hash = annotations_hash(&input.annotations);
+    verification_result.block_anchor = input.block_anchor;
+    verification_result.previous_result_hash = input.previous_result_hash;
+    verification_result.input_digest = sha256(&input_bytes);
+    verification_result.policy_owner_hash = policy_owner_hash;
     commit_bytes(&verification_result.as_slice());
+
+    // The allocation profile is a side channel: stderr, not the journal (the cycle
+    // tracker's counts are reported by the executor)
+    #[cfg(feature = "alloc-profile")]
+    eprintln!("{}", sigstore_verifier::profile::take_report());
 }
@@ -1,2 +1,68 @@
 /// The compiled ELF binary for the Pico Sigstore verifier guest program
 pub const PICO_SIGSTORE_ELF: &[u8] = include_bytes!("../program/elf/riscv32im-pico-zkvm-elf");
+
+#[cfg(test)]
+mod tests {
+    use sha2::{Digest, Sha256};
+    use std::path::{Path, PathBuf};
+
+    /// Inputs of the guest build, relative to the workspace root: the guest
+    /// program and the crates compiled into it
+    const GUEST_SOURCES: &[&str] = &[
+        "Cargo.toml",
+        "crates/pico/program/Cargo.toml",
+        "crates/pico/program/Cargo.lock",
+        "crates/pico/program/src",
+        "crates/sigstore-verifier/Cargo.toml",
+        "crates/sigstore-verifier/src",
+        "crates/sigstore-journal/Cargo.toml",
+        "crates/sigstore-journal/src",
+        "crates/sigstore-zkvm-traits/Cargo.toml",
+        "crates/sigstore-zkvm-traits/src",
+    ];
+
+    fn collect(root: &Path, path: &Path, files: &mut Vec<PathBuf>) {
+        if path.is_dir() {
+            for entry in std::fs::read_dir(path).unwrap() {
+                collect(root, &entry.unwrap().path(), files);
+            }
+        } else {
+            files.push(path.strip_prefix(root).unwrap().to_path_buf());
+        }
+    }
+
+    /// SHA256 over the path, length and content of every guest source file, in path order
+    fn guest_sources_digest() -> String {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("../..");
+        let mut files = Vec::new();
+        for source in GUEST_SOURCES {
+            collect(&root, &root.join(source), &mut files);
+        }
+        files.sort();
+
+        let mut hasher = Sha256::new();
+        for file in files {
+            let content = std::fs::read(root.join(&file)).unwrap();
+            let path: Vec<_> = file.components().map(|c| c.as_os_str().to_str().unwrap()).collect();
+            hasher.update(path.join("/").as_bytes());
+            hasher.update([0]);
+            hasher.update((content.len() as u64).to_le_bytes());
+            hasher.update(&content);
+        }
+        format!("{:x}", hasher.finalize())
+    }
+
+    #[test]
+    fn test_elf_built_from_guest_sources() {
+        // The ELF is prebuilt, so nothing else notices when the guest or the
+        // verifier changes without it being rebuilt
+        let recorded = include_str!("../program/elf/riscv32im-pico-zkvm-elf.sources").trim();
+        let current = guest_sources_digest();
+        assert_eq!(
+            recorded, current,
+            "the Pico guest ELF is stale: rebuild it with `cargo pico build` in crates/pico/program, \
+             then write {} to program/elf/riscv32im-pico-zkvm-elf.sources",
+            current
+        );
+    }
+}
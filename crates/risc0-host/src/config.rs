@@ -2,12 +2,14 @@
 //!
 //! Defines configuration structures for different proving strategies.
 
-use crate::cli::{BoundlessArgs, BoundlessProofType, ProveArgs, ProveStrategy};
+use crate::cli::{BoundlessArgs, BoundlessProofType, ProveStrategy};
+use sigstore_zkvm_traits::source::default_cache_dir;
+use std::path::{Path, PathBuf};
 
 /// Proving strategy enum
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ProvingStrategy {
-    /// Local proving (not yet supported)
+    /// Local proving, with segments sized to the execution and available memory
     Local,
     /// Boundless network proving
     Boundless,
@@ -31,6 +33,8 @@ pub struct BoundlessConfig {
     pub max_price: Option<u128>,
     pub timeout: Option<u32>,
     pub ramp_up_period: Option<u32>,
+    pub max_retries: u32,
+    pub job_store: PathBuf,
 }
 
 impl Risc0Config {
@@ -38,13 +42,13 @@ impl Risc0Config {
     ///
     /// # Arguments
     ///
-    /// * `args` - The prove command arguments
+    /// * `strategy` - The proving strategy of the prove or prove-from-input command
     ///
     /// # Returns
     ///
     /// Returns a Risc0Config with the appropriate strategy and parameters.
-    pub fn from_cli_args(args: &ProveArgs) -> Self {
-        match &args.strategy {
+    pub fn from_cli_args(strategy: &ProveStrategy) -> Self {
+        match strategy {
             ProveStrategy::Local => Risc0Config {
                 proving_strategy: ProvingStrategy::Local,
                 boundless: None,
@@ -77,6 +81,14 @@ impl BoundlessConfig {
             max_price: args.max_price,
             timeout: args.timeout,
             ramp_up_period: args.ramp_up_period,
+            max_retries: args.max_retries,
+            job_store: job_store_path(args.job_store.as_deref()),
         }
     }
 }
+
+/// Job store directory: `path`, or `<cache dir>/jobs`
+pub fn job_store_path(path: Option<&Path>) -> PathBuf {
+    path.map(Path::to_path_buf)
+        .unwrap_or_else(|| default_cache_dir().join("jobs"))
+}
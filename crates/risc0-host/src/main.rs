@@ -8,14 +8,43 @@ mod config;
 mod prover;
 mod proving {
     pub mod boundless;
+    pub mod local;
 }
 
-use anyhow::{Context, Result};
-use clap::Parser;
-use sigstore_verifier::types::result::{VerificationOptions, VerificationResult};
+use anyhow::{bail, Context, Result};
+use clap::error::ErrorKind;
+use clap::{CommandFactory, Parser};
+use crate::config::job_store_path;
+use crate::proving::boundless::cancel_job;
+use sigstore_verifier::types::result::custody::check_link;
+use sigstore_verifier::types::result::{result_hash, VerificationOptions, VerificationResult};
+use sigstore_zkvm_traits::display::{self, display_proof_result, display_verification_result};
+use sigstore_zkvm_traits::env;
+use sigstore_zkvm_traits::failpoint::{self, Stage};
+use sigstore_zkvm_traits::freshness::check_trust_root_freshness;
+use sigstore_zkvm_traits::jobs::JobStore;
+use sigstore_zkvm_traits::resolver::{ArtifactRef, ResolverRegistry};
+use sigstore_zkvm_traits::pipeline::{run_pipeline, PipelineOptions};
+use sigstore_zkvm_traits::policies::RepositoryPolicies;
+use sigstore_zkvm_traits::prepared::{
+    load_input_keys, read_prepared_input, write_prepared_input, PreparedInput,
+};
+use sigstore_zkvm_traits::source::{default_cache_dir, read_source_list};
 use sigstore_zkvm_traits::traits::ZkVmProver;
-use sigstore_zkvm_traits::utils::{display_proof_result, display_verification_result, write_proof_artifact, ProofArtifact};
-use sigstore_zkvm_traits::workflow::prepare_guest_input_local;
+use sigstore_zkvm_traits::store::{unix_now, AttestationStore};
+use sigstore_zkvm_traits::tee::attach_tee_attestation;
+use sigstore_zkvm_traits::types::{collect_annotations, ProverInput};
+use sigstore_zkvm_traits::utils::{
+    read_proof_artifact, read_proof_bundle, write_proof_artifact, write_proof_bundle, ProofArtifact,
+    ProofBundle,
+};
+use sigstore_zkvm_traits::workflow::{
+    check_journal_consistency, load_policy_owner_key, load_trust_root_keys, preflight_guest_input,
+    pin_artifact_digest, prepare_guest_input_for_artifact, prepare_guest_input_local,
+    reproduce_proof_bundle, TrustSource,
+};
+use std::path::Path;
+use std::time::Duration;
 
 #[tokio::main]
 async fn main() -> Result<()> {
@@ -24,14 +53,39 @@ async fn main() -> Result<()> {
 
     // Parse CLI arguments
     let cli = crate::cli::Cli::parse();
+    if cli.help_env {
+        print!("{}", env::describe(crate::cli::ENV_VARS));
+        return Ok(());
+    }
+    let Some(command) = cli.command else {
+        crate::cli::Cli::command()
+            .error(ErrorKind::MissingSubcommand, "a subcommand is required")
+            .exit();
+    };
+    env::init(crate::cli::ENV_VARS)?;
+    if cli.no_color {
+        display::set_color(false);
+    }
 
-    match cli.command {
+    match command {
         crate::cli::Commands::ImageId => {
             handle_image_id()?;
         }
         crate::cli::Commands::Prove(args) => {
             handle_prove(args).await?;
         }
+        crate::cli::Commands::Prepare(args) => {
+            handle_prepare(*args)?;
+        }
+        crate::cli::Commands::ProveFromInput(args) => {
+            handle_prove_from_input(*args).await?;
+        }
+        crate::cli::Commands::Reproduce(args) => {
+            handle_reproduce(args)?;
+        }
+        crate::cli::Commands::Jobs(command) => {
+            handle_jobs(command).await?;
+        }
     }
 
     Ok(())
@@ -60,73 +114,559 @@ fn handle_image_id() -> Result<()> {
 ///
 /// Generates a proof of Sigstore attestation verification.
 async fn handle_prove(args: crate::cli::ProveArgs) -> Result<()> {
+    if let Some(ref bundle_list) = args.bundle_list {
+        return handle_prove_batch(&args, bundle_list).await;
+    }
+
     println!("RISC0 Sigstore Proof Generation");
     println!("================================\n");
 
-    // Step 1: Prepare guest input
+    let guest_input = prepare_input(&args.input)?;
+    let config = crate::config::Risc0Config::from_cli_args(&args.strategy);
+    prove_input(
+        &guest_input.prover_input,
+        &guest_input.source,
+        guest_input.previous_journal.as_deref(),
+        &config,
+        &args.output,
+    )
+    .await?;
+
+    println!("\n✅ Success!");
+
+    Ok(())
+}
+
+/// Guest input prepared from the input flags
+struct GuestInput {
+    prover_input: ProverInput,
+    /// Where the bundle came from, recorded with the proof
+    source: String,
+    /// Journal of --previous-artifact, whose hash the input links to
+    previous_journal: Option<Vec<u8>>,
+}
+
+/// Print the trusted root, or the PEM chains given instead of it
+fn print_trust_source(args: &crate::cli::InputArgs) {
+    if let Some(ref trust_roots) = args.trust_roots {
+        println!("   Trusted Root: {}", trust_roots);
+    }
+    if let Some(ref path) = args.fulcio_chain_pem {
+        println!("   Fulcio chain: {}", path.display());
+    }
+    if let Some(ref path) = args.tsa_chain_pem {
+        println!("   TSA chain:    {}", path.display());
+    }
+    if let Some(ref path) = args.rekor_key_pem {
+        println!("   Rekor key:    {}", path.display());
+    }
+}
+
+/// Resolve the trusted root, or load the PEM chains given instead of it
+///
+/// Stale trusted roots are refused unless explicitly allowed; PEM chains have
+/// no signing time to select by and are used as given.
+fn load_trust_source(args: &crate::cli::InputArgs, cache_dir: &Path) -> Result<TrustSource> {
+    if let Some(ref fulcio_chain) = args.fulcio_chain_pem {
+        return TrustSource::from_pem_files(
+            fulcio_chain,
+            args.tsa_chain_pem.as_deref(),
+            args.rekor_key_pem.as_deref(),
+        );
+    }
+    let Some(ref trust_roots) = args.trust_roots else {
+        unreachable!("clap requires --trust-roots or --fulcio-chain-pem");
+    };
+    let trust_roots_path = trust_roots
+        .resolve(cache_dir, args.refresh_cache, args.trust_roots_sha256.as_deref())
+        .context("Failed to resolve trusted root source")?;
+    let trust_root_keys = load_trust_root_keys(&args.trust_roots_keys)?;
+
+    // Refuse stale trusted roots unless explicitly allowed
+    let max_age = Duration::from_secs(args.max_trust_root_age * 3600);
+    if let Some(reason) = check_trust_root_freshness(&trust_roots_path, max_age, unix_now())? {
+        if !args.allow_stale_roots {
+            bail!(
+                "Refusing to prove: {} (refresh it, e.g. with --refresh-cache, or pass --allow-stale-roots)",
+                reason
+            );
+        }
+        println!("⚠️  Proving with a stale trusted root: {}", reason);
+    }
+
+    Ok(TrustSource::TrustedRoot {
+        path: trust_roots_path,
+        keys: trust_root_keys,
+    })
+}
+
+/// Prepare the guest input for a bundle (step 1 of prove and prepare)
+///
+/// Locates and downloads the bundle and trusted root, refuses stale trusted
+/// roots and builds the verification options, annotations, block anchor and
+/// chain of custody link the guest commits.
+fn prepare_input(args: &crate::cli::InputArgs) -> Result<GuestInput> {
     println!("📦 Preparing guest input...");
-    println!("   Bundle:       {}", args.bundle_path.display());
-    println!("   Trusted Root: {}", args.trust_roots_path.display());
+
+    // Artifact flags locate the bundle through the artifact resolvers; with
+    // --bundle, a local artifact only pins the expected digest
+    let artifact_ref = ArtifactRef::from_args(
+        args.purl.as_deref(),
+        args.gav.as_deref(),
+        args.artifact_file.as_deref(),
+    )?;
+
+    if let Some(ref bundle) = args.bundle {
+        println!("   Bundle:       {}", bundle);
+    }
+    if let Some(ref artifact_ref) = artifact_ref {
+        println!("   Artifact:     {}", artifact_ref);
+    }
+    if let Some(ref path) = args.detached_payload {
+        println!("   Statement:    {}", path.display());
+    }
+    print_trust_source(args);
+    for (key, value) in &args.annotations {
+        println!("   Annotation:   {}={}", key, value);
+    }
+    if let Some(anchor) = &args.anchor_block {
+        println!("   Anchor block: {}", anchor.number);
+    }
+    if let Some(ref path) = args.previous_artifact {
+        println!("   Previous:     {}", path.display());
+    }
+
+    let detached_payload = args
+        .detached_payload
+        .as_ref()
+        .map(|path| {
+            std::fs::read(path)
+                .with_context(|| format!("Failed to read detached payload: {}", path.display()))
+        })
+        .transpose()?;
 
     let verification_options = VerificationOptions {
-        expected_digest: None,
-        expected_issuer: None,
-        expected_subject: None,
+        expected_digest: artifact_ref
+            .as_ref()
+            .and_then(|artifact_ref| artifact_ref.digest)
+            .map(|digest| digest.to_vec()),
+        expected_repository: args.expected_repository.clone(),
+        expected_workflow_ref: args.expected_workflow_ref.clone(),
+        expected_event_name: args.expected_event_name.clone(),
+        expected_predicate_type: args.expected_predicate_type.clone(),
+        max_signing_age: args.max_signing_age,
+        verification_time: args.max_signing_age.map(|_| unix_now()),
+        profile: args.profile.clone(),
+        expected_tsa_policy: args.expected_tsa_policy.clone(),
+        archival: args.archival,
+        verify_all_signatures: args.all_signatures,
+        quarantine: args.quarantine,
+        detached_payload,
+        ..Default::default()
     };
 
-    let prover_input = prepare_guest_input_local(
-        &args.bundle_path,
-        &args.trust_roots_path,
-        verification_options,
-    )
-    .context("Failed to prepare guest input")?;
+    failpoint::inject(Stage::InputPrep)?;
+
+    // Download remote inputs (if any) into the cache
+    let cache_dir = args.cache_dir.clone().unwrap_or_else(default_cache_dir);
+    let trust = load_trust_source(args, &cache_dir)?;
+
+    let (mut prover_input, source) = match (&args.bundle, &artifact_ref) {
+        (Some(bundle), _) => {
+            let bundle_path = bundle
+                .resolve(&cache_dir, args.refresh_cache, args.bundle_sha256.as_deref())
+                .context("Failed to resolve bundle source")?;
+            let mut prover_input = prepare_guest_input_local(
+                &bundle_path,
+                &trust,
+                verification_options,
+            )
+            .context("Failed to prepare guest input")?;
+            // Hash the local artifact as the bundle attests it, which may not be SHA256
+            if let Some(ref path) = args.artifact_file {
+                pin_artifact_digest(&mut prover_input, path)?;
+            }
+            (prover_input, bundle.to_string())
+        }
+        (None, Some(artifact_ref)) => {
+            let prover_input = prepare_guest_input_for_artifact(
+                &ResolverRegistry::with_defaults(),
+                artifact_ref,
+                &trust,
+                verification_options,
+            )
+            .context("Failed to locate a verifying bundle for the artifact")?;
+            (prover_input, artifact_ref.to_string())
+        }
+        (None, None) => unreachable!("clap requires --bundle, --purl or --gav"),
+    };
+    prover_input.annotations = collect_annotations(&args.annotations).map_err(anyhow::Error::msg)?;
+    prover_input.block_anchor = args.anchor_block;
+    let previous_journal = args
+        .previous_artifact
+        .as_ref()
+        .map(|path| read_proof_artifact(path)?.journal())
+        .transpose()
+        .context("Failed to read the previous proof artifact")?;
+    prover_input.previous_result_hash = previous_journal.as_deref().map(result_hash);
+    if let Some(ref path) = args.policies {
+        let policies = RepositoryPolicies::load(path)?;
+        let policy = policies.apply(&mut prover_input, unix_now())?;
+        println!("   Policy:       {} ({})", policy.repository, hex::encode(policy.hash()));
+    }
+    // Signed last, as the signature covers everything above
+    if let Some(ref path) = args.policy_owner_key {
+        prover_input
+            .sign_policy_owner(&load_policy_owner_key(path)?)
+            .map_err(anyhow::Error::msg)?;
+        println!("   Policy owner: {}", path.display());
+    }
 
     println!("✓ Guest input prepared\n");
 
-    // Step 2: Create prover
+    Ok(GuestInput {
+        prover_input,
+        source,
+        previous_journal,
+    })
+}
+
+/// Prove a guest input and write the proof artifact (the steps after input preparation)
+///
+/// The journal is checked against native verification of the input, and
+/// against `previous_journal` if given, before any artifact is written.
+async fn prove_input(
+    prover_input: &ProverInput,
+    source: &str,
+    previous_journal: Option<&[u8]>,
+    config: &crate::config::Risc0Config,
+    output: &crate::cli::OutputArgs,
+) -> Result<()> {
+    // Step 1: Create prover
     println!("🔧 Initializing RISC0 prover...");
     let prover = crate::prover::Risc0Prover::new()
         .context("Failed to create RISC0 prover")?;
     println!("✓ Prover initialized\n");
 
-    // Step 3: Build config
-    let config = crate::config::Risc0Config::from_cli_args(&args);
-
-    // Step 4: Generate proof
+    // Step 2: Generate proof
     println!("⚙️  Generating proof...");
     let (journal, seal) = prover
-        .prove(&config, &prover_input)
+        .prove(config, prover_input)
         .await
         .context("Failed to generate proof")?;
 
     println!("✓ Proof generated successfully\n");
 
-    // Step 5: Display proof result
+    // Step 3: Display proof result
     display_proof_result(&journal, &seal);
 
-    // Step 6: Decode and display verification result
+    // Step 4: Decode and display verification result
     println!("\n🔍 Decoding verification result...");
     let verification_result = VerificationResult::from_slice(&journal)
         .map_err(|e| anyhow::anyhow!("Failed to decode verification result from journal: {}", e))?;
 
     display_verification_result(&verification_result);
 
-    // Step 7: Write artifact if output path provided
-    if let Some(ref output_path) = args.output_path {
+    // Step 5: Check the journal against native verification (guest/host version skew)
+    println!("\n🔁 Checking journal against native verification...");
+    check_journal_consistency(prover_input, &journal)?;
+    println!("✓ Journal matches native verification");
+    if let Some(previous_journal) = previous_journal {
+        check_link(previous_journal, &journal)
+            .map_err(anyhow::Error::msg)
+            .context("Proof doesn't extend the chain of custody of the previous artifact")?;
+        println!("✓ Linked to the previous attestation");
+    }
+
+    // Step 6: Write artifact if output path, store or proof bundle provided
+    if output.output_path.is_some() || output.store.is_some() || output.proof_bundle.is_some() {
         println!("\n💾 Writing proof artifact...");
+        failpoint::inject(Stage::ArtifactWrite)?;
     
-        let artifact = ProofArtifact {
+        let mut artifact = ProofArtifact {
             zkvm: "risc0".to_string(),
             program_id: format!("0x{}", prover.program_identifier()?),
             circuit_version: crate::prover::Risc0Prover::circuit_version(),
             journal: format!("0x{}", hex::encode(&journal)),
             proof: format!("0x{}", hex::encode(&seal)),
+            tee_attestation: None,
+            annotations: prover_input.annotations.clone(),
+            metadata: prover.last_plan().map(|plan| plan.metadata()).unwrap_or_default(),
         };
+
+        if let Some(tee) = output.tee_quote {
+            println!("🔒 Attaching {} quote...", tee);
+            attach_tee_attestation(&mut artifact, tee)
+                .context("Failed to generate TEE quote")?;
+        }
         
-        write_proof_artifact(output_path, &artifact)
-            .context("Failed to write proof artifact")?;
+        if let Some(ref output_path) = output.output_path {
+            write_proof_artifact(output_path, &artifact, output.artifact_format, output.checksum)
+                .context("Failed to write proof artifact")?;
+        }
+
+        if let Some(ref store_dir) = output.store {
+            let store = AttestationStore::open(store_dir)?;
+            let record = store
+                .put_proof(&prover_input.bundle_json, source, &artifact)
+                .context("Failed to record proof in attestation store")?;
+            println!("✓ Proof recorded in store: {}", record.bundle_sha256);
+        }
+
+        if let Some(ref proof_bundle_path) = output.proof_bundle {
+            let bundle = ProofBundle::new(artifact.clone(), source.to_string(), prover_input)?;
+            write_proof_bundle(proof_bundle_path, &bundle, output.artifact_format, output.checksum)
+                .context("Failed to write proof bundle")?;
+        }
     }
 
+    Ok(())
+}
+
+/// Handle the prepare command
+///
+/// Prepares the guest input as `prove` does, but writes it (signed with the
+/// policy owner's key, if given) instead of proving it.
+fn handle_prepare(args: crate::cli::PrepareArgs) -> Result<()> {
+    println!("RISC0 Sigstore Input Preparation");
+    println!("================================\n");
+
+    let guest_input = prepare_input(&args.input)?;
+
+    let mut prepared = PreparedInput::new(&guest_input.prover_input)?;
+    if let Some(ref key_path) = args.signing_key {
+        let key_pem = std::fs::read_to_string(key_path)
+            .with_context(|| format!("Failed to read signing key: {}", key_path.display()))?;
+        prepared.sign(&key_pem, args.keyid.clone().unwrap_or_default())?;
+        println!("✓ Input signed with {}", key_path.display());
+    }
+    write_prepared_input(&args.output_path, &prepared)?;
+    println!("✓ Prepared input written to: {}", args.output_path.display());
+    println!("   Input digest: {}", prepared.digest);
+
+    println!("\n✅ Success!");
+
+    Ok(())
+}
+
+/// Handle the prove-from-input command
+///
+/// Proves an input written by `prepare`. With input keys, the input must be
+/// signed by one of them; the guest commits the input's digest, so a proof of
+/// an altered input doesn't match the prepared digest.
+async fn handle_prove_from_input(args: crate::cli::ProveFromInputArgs) -> Result<()> {
+    println!("RISC0 Sigstore Proof Generation from a Prepared Input");
+    println!("=====================================================\n");
+
+    println!("📦 Loading prepared input...");
+    let prepared = read_prepared_input(&args.input)?;
+    let input_keys = load_input_keys(&args.input_keys)?;
+    let prover_input = prepared
+        .open(&input_keys)
+        .with_context(|| format!("Refusing to prove prepared input {}", args.input.display()))?;
+    println!("   Input:        {}", args.input.display());
+    println!("   Input digest: {}", prepared.digest);
+    if input_keys.is_empty() {
+        println!("⚠️  Input signature not checked (no --input-key given)");
+    } else {
+        println!("✓ Input signature verified");
+    }
+    println!("✓ Prepared input loaded\n");
+
+    let config = crate::config::Risc0Config::from_cli_args(&args.strategy);
+    prove_input(&prover_input, &args.input.display().to_string(), None, &config, &args.output).await?;
+
+    println!("\n✅ Success!");
+
+    Ok(())
+}
+
+/// Handle the jobs commands
+///
+/// Lists Boundless proving jobs, or cancels one and reports what it has cost.
+async fn handle_jobs(command: crate::cli::JobsCommand) -> Result<()> {
+    match command {
+        crate::cli::JobsCommand::List(args) => {
+            let jobs = JobStore::open(&job_store_path(args.job_store.as_deref()))?;
+            for job in jobs.list()? {
+                println!(
+                    "{}  {:<9}  {:<14}  attempts: {}  request: {}",
+                    job.key,
+                    format!("{:?}", job.status).to_lowercase(),
+                    job.network,
+                    job.attempts,
+                    job.request_id.as_deref().unwrap_or("-"),
+                );
+            }
+        }
+        crate::cli::JobsCommand::Cancel(args) => {
+            cancel_job(
+                &job_store_path(args.store.job_store.as_deref()),
+                &args.job_id,
+                args.rpc_url.as_deref(),
+            )
+            .await?;
+        }
+    }
+    Ok(())
+}
+
+/// Handle the prove command for a list of bundles
+///
+/// Runs input preparation, guest emulation, proving and the store writes as a
+/// pipeline, so that the next bundles are prepared and emulated while one is
+/// being proven. A bundle that fails is reported and doesn't stop the others.
+async fn handle_prove_batch(args: &crate::cli::ProveArgs, bundle_list: &Path) -> Result<()> {
+    println!("RISC0 Sigstore Batch Proof Generation");
+    println!("=====================================\n");
+
+    let sources = read_source_list(bundle_list)?;
+    println!("   Bundles:      {} from {}", sources.len(), bundle_list.display());
+    print_trust_source(&args.input);
+    for (key, value) in &args.input.annotations {
+        println!("   Annotation:   {}={}", key, value);
+    }
+    if let Some(anchor) = &args.input.anchor_block {
+        println!("   Anchor block: {}", anchor.number);
+    }
+    println!("   Workers:      {} (queue depth {})\n", args.workers, args.queue_depth);
+
+    let cache_dir = args.input.cache_dir.clone().unwrap_or_else(default_cache_dir);
+    let trust = load_trust_source(&args.input, &cache_dir)?;
+
+    let annotations = collect_annotations(&args.input.annotations).map_err(anyhow::Error::msg)?;
+    let policies = args.input.policies.as_deref().map(RepositoryPolicies::load).transpose()?;
+    let policy_owner_key = args.input.policy_owner_key.as_deref().map(load_policy_owner_key).transpose()?;
+    let verification_options = VerificationOptions {
+        expected_repository: args.input.expected_repository.clone(),
+        expected_workflow_ref: args.input.expected_workflow_ref.clone(),
+        expected_event_name: args.input.expected_event_name.clone(),
+        expected_predicate_type: args.input.expected_predicate_type.clone(),
+        max_signing_age: args.input.max_signing_age,
+        verification_time: args.input.max_signing_age.map(|_| unix_now()),
+        profile: args.input.profile.clone(),
+        expected_tsa_policy: args.input.expected_tsa_policy.clone(),
+        archival: args.input.archival,
+        verify_all_signatures: args.input.all_signatures,
+        quarantine: args.input.quarantine,
+        ..Default::default()
+    };
+
+    let prover = crate::prover::Risc0Prover::new()
+        .context("Failed to create RISC0 prover")?;
+    let config = crate::config::Risc0Config::from_cli_args(&args.strategy);
+    let program_id = format!("0x{}", prover.program_identifier()?);
+    let store_dir = args.output.store.as_ref().context("--bundle-list requires --store")?;
+    let store = AttestationStore::open(store_dir)?;
+    let options = PipelineOptions {
+        workers: args.workers,
+        queue_depth: args.queue_depth,
+    };
+
+    // Proving runs on this thread; blocking it is fine on the multi-threaded runtime
+    println!("⚙️  Proving {} bundle(s)...", sources.len());
+    let runtime = tokio::runtime::Handle::current();
+    let report = tokio::task::block_in_place(|| {
+        run_pipeline(
+            &sources,
+            &options,
+            |source| {
+                failpoint::inject(Stage::InputPrep)?;
+                let bundle_path = source
+                    .resolve(&cache_dir, args.input.refresh_cache, None)
+                    .context("Failed to resolve bundle source")?;
+                let mut input = prepare_guest_input_local(
+                    &bundle_path,
+                    &trust,
+                    verification_options.clone(),
+                )?;
+                input.annotations = annotations.clone();
+                input.block_anchor = args.input.anchor_block;
+                if let Some(ref policies) = policies {
+                    policies.apply(&mut input, unix_now())?;
+                }
+                if let Some(ref key_pem) = policy_owner_key {
+                    input.sign_policy_owner(key_pem).map_err(anyhow::Error::msg)?;
+                }
+                Ok((source, input))
+            },
+            |(source, input)| {
+                preflight_guest_input(&prover, &input)?;
+                Ok((source, input))
+            },
+            |(source, input)| {
+                let (journal, proof) = runtime.block_on(prover.prove(&config, &input))?;
+                check_journal_consistency(&input, &journal)?;
+                // The next bundle's proof may replace the plan before this artifact is written
+                let metadata = prover.last_plan().map(|plan| plan.metadata()).unwrap_or_default();
+                Ok((source, input, journal, proof, metadata))
+            },
+            |(source, input, journal, proof, metadata)| {
+                failpoint::inject(Stage::ArtifactWrite)?;
+                let artifact = ProofArtifact {
+                    zkvm: "risc0".to_string(),
+                    program_id: program_id.clone(),
+                    circuit_version: crate::prover::Risc0Prover::circuit_version(),
+                    journal: format!("0x{}", hex::encode(&journal)),
+                    proof: format!("0x{}", hex::encode(&proof)),
+                    tee_attestation: None,
+                    annotations: input.annotations.clone(),
+                    metadata,
+                };
+                let record = store.put_proof(&input.bundle_json, &source.to_string(), &artifact)?;
+                println!("✓ {}: recorded as {}", source, record.bundle_sha256);
+                Ok(record.bundle_sha256)
+            },
+        )
+    });
+
+    println!(
+        "\n{} of {} bundle(s) proven in {:.1}s",
+        report.succeeded(),
+        sources.len(),
+        report.elapsed.as_secs_f64()
+    );
+    for (index, failure) in report.failures() {
+        println!("   ✗ {}: {}", sources[index], failure);
+    }
+    if report.succeeded() < sources.len() {
+        bail!("{} of {} bundle(s) failed", sources.len() - report.succeeded(), sources.len());
+    }
+
+    println!("\n✅ Success!");
+
+    Ok(())
+}
+
+/// Handle the reproduce command
+///
+/// Re-executes the guest program on the input recorded in a proof bundle and
+/// checks that it commits the proven journal.
+fn handle_reproduce(args: crate::cli::ReproduceArgs) -> Result<()> {
+    println!("RISC0 Sigstore Proof Reproduction");
+    println!("=================================\n");
+
+    // Step 1: Load the proof bundle
+    println!("📦 Loading proof bundle...");
+    let bundle = read_proof_bundle(&args.proof_bundle)?;
+    println!("   Proof Bundle: {}", args.proof_bundle.display());
+    println!("   Source:       {}", bundle.source);
+    println!("   Program ID:   {}", bundle.artifact.program_id);
+    println!("✓ Proof bundle loaded\n");
+
+    // Step 2: Create prover
+    println!("🔧 Initializing RISC0 prover...");
+    let prover = crate::prover::Risc0Prover::new()
+        .context("Failed to create RISC0 prover")?;
+    println!("✓ Prover initialized\n");
+
+    // Step 3: Re-execute the guest and compare journals
+    println!("🔁 Re-executing guest program...");
+    let verification_result = reproduce_proof_bundle(&prover, &bundle)?;
+    println!("✓ Journal reproduced\n");
+
+    display_verification_result(&verification_result);
+
     println!("\n✅ Success!");
 
     Ok(())
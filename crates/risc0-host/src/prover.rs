@@ -5,15 +5,41 @@
 
 use crate::config::{ProvingStrategy, Risc0Config};
 use crate::proving::boundless::prove_with_boundless;
+use crate::proving::local::prove_locally;
 use async_trait::async_trait;
-use risc0_zkvm::{compute_image_id, default_executor, ExecutorEnv};
+use risc0_zkvm::{compute_image_id, default_executor, ExecutorEnv, SessionInfo};
 use sigstore_risc0_methods::SIGSTORE_RISC0_GUEST_ELF;
+use sigstore_zkvm_traits::env;
 use sigstore_zkvm_traits::error::ZkVmError;
+use sigstore_zkvm_traits::failpoint::{self, Stage};
+use sigstore_zkvm_traits::sizing::{self, ProvingPlan};
 use sigstore_zkvm_traits::traits::ZkVmProver;
 use sigstore_zkvm_traits::types::ProverInput;
+use std::sync::Mutex;
 
 pub struct Risc0Prover {
     elf: &'static [u8],
+    /// Segment sizing of the last local proof
+    last_plan: Mutex<Option<ProvingPlan>>,
+}
+
+impl Risc0Prover {
+    /// Segment sizing of the last proof generated locally, for its artifact metadata
+    pub fn last_plan(&self) -> Option<ProvingPlan> {
+        *self.last_plan.lock().unwrap()
+    }
+
+    /// Execute the guest on serialized input
+    fn session(&self, input_bytes: &[u8]) -> Result<SessionInfo, ZkVmError> {
+        let env = ExecutorEnv::builder()
+            .write_slice(input_bytes)
+            .build()
+            .map_err(|e| ZkVmError::ProofGenerationError(format!("Failed to build executor env: {}", e)))?;
+
+        default_executor()
+            .execute(env, self.elf)
+            .map_err(|e| ZkVmError::ProofGenerationError(format!("Failed to execute guest program: {}", e)))
+    }
 }
 
 #[async_trait]
@@ -23,6 +49,7 @@ impl ZkVmProver for Risc0Prover {
     fn new() -> Result<Self, ZkVmError> {
         Ok(Risc0Prover {
             elf: SIGSTORE_RISC0_GUEST_ELF,
+            last_plan: Mutex::new(None),
         })
     }
 
@@ -41,20 +68,15 @@ impl ZkVmProver for Risc0Prover {
         println!("Image ID: {}", image_id.to_string());
         println!("RISC0 Version: {}", Self::circuit_version());
 
-        // Execute locally to get journal
-        let env = ExecutorEnv::builder()
-            .write_slice(&input_bytes)
-            .build()
-            .map_err(|e| ZkVmError::ProofGenerationError(format!("Failed to build executor env: {}", e)))?;
-
-        let session_info = default_executor()
-            .execute(env, self.elf)
-            .map_err(|e| ZkVmError::ProofGenerationError(format!("Failed to execute guest program: {}", e)))?;
-
-        let journal = session_info.journal.bytes.to_vec();
+        // Execute locally to get journal and cycle count
+        failpoint::inject(Stage::Emulation)?;
+        let session = self.session(&input_bytes)?;
+        let journal = session.journal.bytes.to_vec();
+        *self.last_plan.lock().unwrap() = None;
 
         // Check for DEV_MODE
-        if std::env::var("DEV_MODE").is_ok() || std::env::var("RISC0_DEV_MODE").is_ok() {
+        let env = env::config();
+        if env.dev_mode || env.flag("RISC0_DEV_MODE") {
             println!("⚠ Running in DEV_MODE - no proof will be generated");
             return Ok((journal, vec![]));
         }
@@ -62,9 +84,12 @@ impl ZkVmProver for Risc0Prover {
         // Generate proof based on strategy
         let seal = match config.proving_strategy {
             ProvingStrategy::Local => {
-                return Err(ZkVmError::ProofGenerationError(
-                    "Local proving is not yet supported. Use Boundless or set DEV_MODE=1 for testing.".to_string()
-                ));
+                let cycles = session.segments.iter().map(|segment| u64::from(segment.cycles)).sum();
+                let plan = ProvingPlan::risc0(cycles, sizing::available_memory());
+                *self.last_plan.lock().unwrap() = Some(plan);
+
+                prove_locally(self.elf, &input_bytes, &plan)
+                    .map_err(|e| ZkVmError::ProofGenerationError(format!("Local proving failed: {:#}", e)))?
             }
             ProvingStrategy::Boundless => {
                 let boundless_config = config.boundless.as_ref()
@@ -79,6 +104,13 @@ impl ZkVmProver for Risc0Prover {
         Ok((journal, seal))
     }
 
+    fn execute(&self, input: &ProverInput) -> Result<Vec<u8>, ZkVmError> {
+        let input_bytes = input.encode_input()
+            .map_err(|e| ZkVmError::InvalidInput(format!("Failed to encode ProverInput: {}", e)))?;
+
+        Ok(self.session(&input_bytes)?.journal.bytes.to_vec())
+    }
+
     fn program_identifier(&self) -> Result<String, ZkVmError> {
         let image_id = compute_image_id(self.elf)
             .map_err(|e| ZkVmError::ProofGenerationError(format!("Failed to compute image ID: {}", e)))?;
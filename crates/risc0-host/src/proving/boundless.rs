@@ -1,26 +1,46 @@
 //! Boundless network proving integration
 //!
-//! Provides functionality to generate proofs using the Boundless proving network.
+//! Provides functionality to generate proofs using the Boundless proving network,
+//! and to cancel proving jobs that are no longer needed.
 
 use crate::cli::BoundlessProofType;
 use crate::config::BoundlessConfig;
-use anyhow::{Context, Result};
+use anyhow::{anyhow, bail, Context, Result};
 use boundless_market::{
     alloy::{
-        primitives::{U256, utils::parse_units},
+        primitives::{Address, U256, utils::parse_units},
         providers::{Provider, ProviderBuilder},
         signers::local::PrivateKeySigner,
         transports::http::reqwest::Url,
     },
     client::Client,
+    contracts::{boundless_market::BoundlessMarketService, RequestStatus},
     request_builder::OfferParams,
     storage::storage_provider_from_env,
     Deployment,
 };
+use sigstore_zkvm_traits::failpoint::{self, Stage};
+use sigstore_zkvm_traits::jobs::{job_key, JobStatus, JobStore, ProvingJob};
+use sigstore_zkvm_traits::store::unix_now;
+use std::path::Path;
 use std::time::Duration;
 
+/// Upper bound for the backoff between retries
+const MAX_BACKOFF_SECS: u64 = 60;
+
+/// Interval at which a waiting prover checks the job store for cancellation
+const CANCEL_POLL_SECS: u64 = 5;
+
 /// Generate a proof using the Boundless proving network
 ///
+/// Requests are tracked in the job store under a key derived from the program
+/// and input, so a rerun with the same input resumes waiting on a live request,
+/// or returns the seal of a fulfilled one, instead of submitting again.
+/// Transient RPC failures are retried with exponential backoff and expired
+/// requests are resubmitted, up to `config.max_retries` times; on-chain reverts
+/// fail immediately. Cancelling the job (see [`cancel_job`]) stops the wait; a
+/// rerun after cancellation submits a new request.
+///
 /// # Arguments
 ///
 /// * `elf` - The guest program ELF binary
@@ -36,8 +56,10 @@ use std::time::Duration;
 /// Returns an error if:
 /// - RPC URL or private key is missing/invalid
 /// - Boundless deployment is not found for the chain
-/// - Proof request submission fails
-/// - Proof generation times out
+/// - The job store cannot be read or written
+/// - Submission or fulfillment fails with a revert or an unclassified error
+/// - Transient failures or expiries persist beyond `config.max_retries` retries
+/// - The job is cancelled while in flight
 pub async fn prove_with_boundless(
     elf: &'static [u8],
     input_bytes: &[u8],
@@ -102,88 +124,353 @@ pub async fn prove_with_boundless(
         .await
         .context("Failed to build Boundless client")?;
 
-    println!("📝 Creating proof request...");
-
-    // Build request
-    let mut request_builder = client.new_request().with_stdin(input_bytes);
-
-    // Set program (either URL or ELF)
+    // Print the request parameters once; the request is rebuilt for every submission
     if let Some(ref program_url) = config.program_url {
         println!("📦 Using program URL: {}", program_url);
-        request_builder = request_builder
-            .with_program_url(program_url.as_str())
-            .context("Failed to set program URL")?;
     } else {
         println!("📦 Using embedded ELF ({} bytes)", elf.len());
-        request_builder = request_builder.with_program(elf.to_vec());
     }
-
-    // Set proof type
     match config.proof_type {
-        BoundlessProofType::Groth16 => {
-            println!("🔐 Proof type: Groth16");
-            request_builder = request_builder.with_groth16_proof();
+        BoundlessProofType::Groth16 => println!("🔐 Proof type: Groth16"),
+        BoundlessProofType::Merkle => println!("🌳 Proof type: Merkle"),
+    }
+    if let Some(min_price) = config.min_price {
+        println!("💰 Min price: {} wei", min_price);
+    }
+    if let Some(max_price) = config.max_price {
+        println!("💰 Max price: {} wei", max_price);
+    }
+    if let Some(timeout) = config.timeout {
+        println!("⏱️  Lock Timeout: {} seconds", timeout);
+        println!("⏱️  Order Expiration Timeout: {} seconds", timeout + 600);
+    }
+    if let Some(ramp_up_period) = config.ramp_up_period {
+        println!("📈 Ramp-up period: {} seconds", ramp_up_period);
+    }
+
+    let build_request = || -> Result<_> {
+        let mut request_builder = client.new_request().with_stdin(input_bytes);
+
+        // Set program (either URL or ELF)
+        if let Some(ref program_url) = config.program_url {
+            request_builder = request_builder
+                .with_program_url(program_url.as_str())
+                .context("Failed to set program URL")?;
+        } else {
+            request_builder = request_builder.with_program(elf.to_vec());
         }
-        BoundlessProofType::Merkle => {
-            println!("🌳 Proof type: Merkle");
-            // Merkle is the default, no special flag needed
+
+        // Set proof type (Merkle is the default, no special flag needed)
+        if config.proof_type == BoundlessProofType::Groth16 {
+            request_builder = request_builder.with_groth16_proof();
         }
-    }
 
-    // Set offer params if any are provided
-    let mut offer_builder = OfferParams::builder();
-    if config.min_price.is_some()
-        || config.max_price.is_some()
-        || config.timeout.is_some()
-        || config.ramp_up_period.is_some()
-    {
+        // Set offer params if any are provided
+        let mut offer_builder = OfferParams::builder();
         if let Some(min_price) = config.min_price {
-            println!("💰 Min price: {} wei", min_price);
             offer_builder.min_price(U256::from(min_price));
         }
-
         if let Some(max_price) = config.max_price {
-            println!("💰 Max price: {} wei", max_price);
             offer_builder.max_price(U256::from(max_price));
         }
-
         if let Some(timeout) = config.timeout {
-            println!("⏱️  Lock Timeout: {} seconds", timeout);
-            println!("⏱️  Order Expiration Timeout: {} seconds", timeout + 600);
             offer_builder.lock_timeout(timeout);
             offer_builder.timeout(timeout + 600);
         }
-
         if let Some(ramp_up_period) = config.ramp_up_period {
-            println!("📈 Ramp-up period: {} seconds", ramp_up_period);
             offer_builder.ramp_up_period(ramp_up_period);
         }
+
+        // hardcode collateral default at 10 $ZKC
+        let collateral_amount = parse_units("10", "ether").unwrap();
+        offer_builder.lock_collateral(collateral_amount);
+
+        Ok(request_builder.with_offer(offer_builder.build()?))
+    };
+
+    // Look up an earlier job for the same program and input
+    let program: &[u8] = match config.program_url {
+        Some(ref program_url) => program_url.as_bytes(),
+        None => elf,
+    };
+    let jobs = JobStore::open(&config.job_store)?;
+    let proof_kind = match config.proof_type {
+        BoundlessProofType::Groth16 => "groth16",
+        BoundlessProofType::Merkle => "merkle",
+    };
+    let key = job_key(program, input_bytes, proof_kind);
+    let network = format!("boundless:{}", chain_id);
+    let mut job = jobs
+        .get(&key)?
+        .filter(|job| job.network == network)
+        .unwrap_or_else(|| ProvingJob::new(key.clone(), network));
+
+    if let (JobStatus::Fulfilled, Some(seal)) = (job.status, job.seal.as_deref()) {
+        println!("♻️  Reusing fulfilled request {}", job.request_id.as_deref().unwrap_or("?"));
+        return hex::decode(seal).context("Invalid seal in job store");
     }
 
-    // hardcode collateral default at 10 $ZKC
-    let collateral_amount = parse_units("10", "ether").unwrap();
-    offer_builder.lock_collateral(collateral_amount);
+    // Proving again after a cancellation asks for a new request
+    if job.status == JobStatus::Cancelled {
+        println!("↩️  Job was cancelled; submitting a new request");
+        job.status = JobStatus::Pending;
+        jobs.put(&mut job)?;
+    }
 
-    request_builder = request_builder.with_offer(offer_builder.build()?);
+    let mut retries = 0;
+    loop {
+        if jobs.is_cancelled(&job.key)? {
+            bail!("Job {} was cancelled", job.key);
+        }
 
-    println!("🚀 Submitting proof request to Boundless...");
+        // Resume a live request for this input instead of paying for a second one
+        let (request_id, expires_at) = match job.live_request(unix_now()) {
+            Some((request_id, expires_at)) => {
+                println!("♻️  Resuming request {}", request_id);
+                (parse_request_id(request_id)?, expires_at)
+            }
+            None => {
+                println!("🚀 Submitting proof request to Boundless...");
+                job.attempts += 1;
+                let submitted = async {
+                    failpoint::inject(Stage::Submission)?;
+                    client
+                        .submit_onchain(build_request()?)
+                        .await
+                        .map_err(|e| anyhow!("Failed to submit proof request to Boundless: {:?}", e))
+                }
+                .await;
 
-    // Submit request
-    let (request_id, expires_at) = client
-        .submit_onchain(request_builder)
-        .await
-        .map_err(|e| anyhow::anyhow!("Failed to submit proof request to Boundless: {:?}", e))?;
+                match submitted {
+                    Ok((request_id, expires_at)) => {
+                        println!("✓ Request submitted! ID: {:x}", request_id);
+                        job.status = JobStatus::Submitted;
+                        job.request_id = Some(format!("0x{:x}", request_id));
+                        job.expires_at = Some(expires_at);
+                        job.max_price = config.max_price.map(|max_price| max_price.to_string());
+                        job.last_error = None;
+                        jobs.put(&mut job)?;
+                        (request_id, expires_at)
+                    }
+                    Err(e) => {
+                        retry_or_fail(&jobs, &mut job, e, &mut retries, config.max_retries).await?;
+                        continue;
+                    }
+                }
+            }
+        };
+
+        println!("⏳ Waiting for proof generation...");
+
+        // Wait for fulfillment, or for the job to be cancelled from another process
+        let fulfilled = async {
+            failpoint::inject(Stage::FulfillmentWait)?;
+            let fulfillment =
+                client.wait_for_request_fulfillment(request_id, Duration::from_secs(5), expires_at);
+            tokio::select! {
+                fulfillment = fulfillment => {
+                    fulfillment.map(Some).context("Failed to wait for proof fulfillment")
+                }
+                cancelled = watch_cancellation(&jobs, &job.key) => cancelled.map(|()| None),
+            }
+        }
+        .await;
+
+        match fulfilled {
+            Ok(None) => bail!("Job {} was cancelled while waiting for request 0x{:x}", job.key, request_id),
+            Ok(Some(fulfillment)) => {
+                println!("✓ Proof generated successfully!");
+                let seal = fulfillment.seal.to_vec();
+                job.status = JobStatus::Fulfilled;
+                job.seal = Some(hex::encode(&seal));
+                job.last_error = None;
+                jobs.put(&mut job)?;
+                return Ok(seal);
+            }
+            Err(e) => retry_or_fail(&jobs, &mut job, e, &mut retries, config.max_retries).await?,
+        }
+    }
+}
+
+/// Resolve once the job has been cancelled in the job store
+async fn watch_cancellation(jobs: &JobStore, key: &str) -> Result<()> {
+    loop {
+        tokio::time::sleep(Duration::from_secs(CANCEL_POLL_SECS)).await;
+        if jobs.is_cancelled(key)? {
+            return Ok(());
+        }
+    }
+}
+
+/// Cancel a Boundless proving job and report the costs it incurred
+///
+/// The Boundless market has no way to withdraw a submitted request, so
+/// cancelling abandons it: a prover waiting on the job stops, and later runs
+/// submit a new request instead of resuming this one. What the request may
+/// still cost depends on whether a prover has locked it, which is looked up on
+/// the market if `rpc_url` is given.
+///
+/// # Arguments
+///
+/// * `job_store` - Job store directory
+/// * `job_id` - Job key, or a prefix matching exactly one job
+/// * `rpc_url` - RPC URL of the chain the request was submitted to
+///
+/// # Errors
+///
+/// Returns an error if no single job matches, the job is already fulfilled,
+/// or the market status cannot be queried
+pub async fn cancel_job(job_store: &Path, job_id: &str, rpc_url: Option<&str>) -> Result<()> {
+    let jobs = JobStore::open(job_store)?;
+    let mut job = jobs.find(job_id)?;
+    let previous = job.status;
+    jobs.cancel(&mut job)?;
+
+    println!("🛑 Cancelled job {} (was {:?})", job.key, previous);
+    println!("   Network:      {}", job.network);
+    println!("   Submissions:  {} (transaction fees already paid)", job.attempts);
 
-    println!("✓ Request submitted! ID: {:x}", request_id);
-    println!("⏳ Waiting for proof generation...");
+    let Some(ref request_id) = job.request_id else {
+        println!("   No request was submitted; nothing is owed for proving");
+        return Ok(());
+    };
+    println!("   Request:      {}", request_id);
+    if let Some(ref max_price) = job.max_price {
+        println!("   Max price:    {} wei", max_price);
+    }
+
+    let Some(rpc_url) = rpc_url else {
+        println!("   Pass --boundless-rpc-url to check whether a prover locked the request");
+        return Ok(());
+    };
+    let status = request_status(rpc_url, parse_request_id(request_id)?, job.expires_at).await?;
+    let expiry = job
+        .expires_at
+        .map(|expires_at| format!("its expiry (unix time {})", expires_at))
+        .unwrap_or_else(|| "it expires".to_string());
+    match status {
+        RequestStatus::Unknown => println!(
+            "   Market:       open, not locked; nothing has been paid for proving, but a prover can still lock it until {}",
+            expiry
+        ),
+        RequestStatus::Locked => println!(
+            "   Market:       locked by a prover; its price (at most the max price) is paid if it is fulfilled before {}",
+            expiry
+        ),
+        RequestStatus::Fulfilled => println!(
+            "   Market:       already fulfilled; its price was paid, and proving again pays for a new request"
+        ),
+        RequestStatus::Expired => println!("   Market:       expired unfulfilled; nothing was paid for proving"),
+    }
+    Ok(())
+}
+
+/// Status of a request on the Boundless market of the chain behind `rpc_url`
+async fn request_status(rpc_url: &str, request_id: U256, expires_at: Option<u64>) -> Result<RequestStatus> {
+    let rpc_url: Url = rpc_url.parse().context("Failed to parse Boundless RPC URL")?;
+    let provider = ProviderBuilder::new().connect_http(rpc_url);
+    let chain_id = provider
+        .get_chain_id()
+        .await
+        .context("Failed to get chain ID from RPC")?;
+    let deployment = Deployment::from_chain_id(chain_id)
+        .with_context(|| format!("No Boundless deployment found for chain {}", chain_id))?;
 
-    // Wait for fulfillment
-    let fulfillment = client
-        .wait_for_request_fulfillment(request_id, Duration::from_secs(5), expires_at)
+    let market = BoundlessMarketService::new(deployment.boundless_market_address, provider, Address::ZERO);
+    market
+        .get_status(request_id, expires_at)
         .await
-        .context("Failed to wait for proof fulfillment")?;
+        .map_err(|e| anyhow!("Failed to query status of request 0x{:x}: {:?}", request_id, e))
+}
+
+/// Classified Boundless failure, deciding whether a retry can succeed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FailureKind {
+    /// Connection, timeout or rate limit errors; repeat the same step
+    Transient,
+    /// The request expired unfulfilled; submit a new request
+    Expired,
+    /// The transaction reverted on-chain; repeating it would revert again
+    Reverted,
+    /// Anything else
+    Fatal,
+}
+
+impl FailureKind {
+    fn classify(error: &anyhow::Error) -> Self {
+        let message = format!("{:?}", error).to_ascii_lowercase();
+        let contains_any = |needles: &[&str]| needles.iter().any(|needle| message.contains(needle));
+
+        if contains_any(&["expired"]) {
+            FailureKind::Expired
+        } else if contains_any(&["revert"]) {
+            FailureKind::Reverted
+        } else if contains_any(&[
+            "connect",
+            "timed out",
+            "timeout",
+            "error sending request",
+            "connection reset",
+            "too many requests",
+            "429",
+            "502",
+            "503",
+            "504",
+            "temporarily unavailable",
+            "injected failure",
+        ]) {
+            FailureKind::Transient
+        } else {
+            FailureKind::Fatal
+        }
+    }
+}
+
+/// Record a failure in the job and sleep before the next attempt, or give up
+///
+/// Transient errors repeat the failed step (a live request is resumed rather
+/// than resubmitted); expired requests are resubmitted. Reverts and unknown
+/// errors, and any error once `max_retries` is used up, fail the job.
+async fn retry_or_fail(
+    jobs: &JobStore,
+    job: &mut ProvingJob,
+    error: anyhow::Error,
+    retries: &mut u32,
+    max_retries: u32,
+) -> Result<()> {
+    let kind = FailureKind::classify(&error);
+    job.last_error = Some(format!("{:#}", error));
 
-    println!("✓ Proof generated successfully!");
+    let retryable = matches!(kind, FailureKind::Transient | FailureKind::Expired);
+    if !retryable || *retries >= max_retries {
+        // A transient failure leaves a submitted request live for the next run
+        if !(kind == FailureKind::Transient && job.status == JobStatus::Submitted) {
+            job.status = JobStatus::Failed;
+        }
+        jobs.put(job)?;
+        return Err(error.context(format!("Boundless request failed ({:?})", kind)));
+    }
+
+    if kind == FailureKind::Expired {
+        job.status = JobStatus::Expired;
+    }
+    jobs.put(job)?;
+
+    *retries += 1;
+    let backoff = Duration::from_secs((1u64 << *retries).min(MAX_BACKOFF_SECS));
+    println!(
+        "⚠️  {:?} failure: {:#}; retry {}/{} in {}s",
+        kind,
+        error,
+        retries,
+        max_retries,
+        backoff.as_secs()
+    );
+    tokio::time::sleep(backoff).await;
+    Ok(())
+}
 
-    Ok(fulfillment.seal.to_vec())
+fn parse_request_id(request_id: &str) -> Result<U256> {
+    U256::from_str_radix(request_id.trim_start_matches("0x"), 16)
+        .map_err(|e| anyhow!("Invalid request ID in job store {}: {:?}", request_id, e))
 }
@@ -0,0 +1,50 @@
+//! Local RISC0 proving
+//!
+//! Proves the guest on this machine with the default prover, sized by a
+//! [`ProvingPlan`] of the preflight execution.
+
+use anyhow::{Context, Result};
+use risc0_ethereum_contracts::encode_seal;
+use risc0_zkvm::{default_prover, ExecutorEnv, ProverOpts};
+use sigstore_zkvm_traits::sizing::ProvingPlan;
+
+/// Generate a Groth16 proof locally
+///
+/// The execution is split into segments of `1 << plan.segment_po2` cycles,
+/// proven one at a time and compressed to a Groth16 receipt.
+///
+/// # Arguments
+///
+/// * `elf` - The guest program ELF binary
+/// * `input_bytes` - Serialized input data for the guest program
+/// * `plan` - Segment sizing for this input
+///
+/// # Returns
+///
+/// Returns the proof seal bytes, encoded for the RISC Zero verifier contracts.
+///
+/// # Errors
+///
+/// Returns an error if the executor environment cannot be built, proving
+/// fails (e.g. runs out of memory) or the receipt cannot be encoded as a seal.
+pub fn prove_locally(elf: &[u8], input_bytes: &[u8], plan: &ProvingPlan) -> Result<Vec<u8>> {
+    println!(
+        "🖥  Proving locally: {} cycles in {} segment(s) of 2^{} cycles",
+        plan.cycles,
+        plan.segments(),
+        plan.segment_po2
+    );
+
+    let env = ExecutorEnv::builder()
+        .write_slice(input_bytes)
+        .segment_limit_po2(plan.segment_po2)
+        .build()
+        .context("Failed to build executor env")?;
+
+    let prove_info = default_prover()
+        .prove_with_opts(env, elf, &ProverOpts::groth16())
+        .context("Local proving failed")?;
+    println!("✓ Proven locally in {} segment(s)", prove_info.stats.segments);
+
+    encode_seal(&prove_info.receipt).context("Failed to encode seal")
+}
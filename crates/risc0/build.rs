@@ -13,6 +13,20 @@ fn main() {
 
         builder.use_docker(docker_options);
     }
+    // Profiling changes the ImageID, so it is opt-in at build time
+    println!("cargo:rerun-if-env-changed=SIGSTORE_ALLOC_PROFILE");
+    println!("cargo:rerun-if-env-changed=SIGSTORE_CYCLE_PROFILE");
+    let features: Vec<String> = [
+        ("SIGSTORE_ALLOC_PROFILE", "alloc-profile"),
+        ("SIGSTORE_CYCLE_PROFILE", "cycle-profile"),
+    ]
+    .into_iter()
+    .filter(|(var, _)| std::env::var(var).is_ok())
+    .map(|(_, feature)| feature.to_string())
+    .collect();
+    if !features.is_empty() {
+        builder.features(features);
+    }
     let guest_options = builder.build().unwrap();
     embed_methods_with_options(HashMap::from([("sigstore-risc0-guest", guest_options)]));
 }
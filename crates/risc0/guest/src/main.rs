@@ -7,11 +7,23 @@ risc0_zkvm::guest::entry!(main);
 
 use sigstore_verifier::{
     AttestationVerifier,
-    types::result::VerificationResult
+    types::result::{annotations_hash, VerificationResult},
 };
+use sigstore_verifier::crypto::hash::sha256;
 use sigstore_zkvm_traits::types::ProverInput;
 
+#[cfg(feature = "alloc-profile")]
+#[global_allocator]
+static ALLOCATOR: sigstore_verifier::profile::CountingAllocator =
+    sigstore_verifier::profile::CountingAllocator::new();
+
 fn main() {
+    // Count cycles around each verification step with the zkVM cycle counter
+    #[cfg(feature = "cycle-profile")]
+    sigstore_verifier::profile::set_cycle_source(
+        sigstore_verifier::profile::CycleSource::Counter(env::cycle_count),
+    );
+
     // read the values passed from host
     let mut input_bytes: Vec<u8> = vec![];
     env::stdin().read_to_end(&mut input_bytes).unwrap();
@@ -19,6 +31,11 @@ fn main() {
     let input: ProverInput = ProverInput::parse_input(&input_bytes)
         .expect("Failed to parse ProverInput");
 
+    // A guest built with SIGSTORE_POLICY_OWNER_KEY only proves inputs signed by that key
+    let policy_owner_hash = input
+        .verify_policy_owner(option_env!("SIGSTORE_POLICY_OWNER_KEY"))
+        .unwrap_or_else(|e| panic!("Failed to verify policy owner: {}", e));
+
     let verifier = AttestationVerifier::new();
 
     let output = verifier.verify_bundle_bytes(
@@ -28,8 +45,22 @@ fn main() {
         input.tsa_cert_chain.as_ref(),
     );
 
-    assert!(output.is_ok(), "Failed to verify bundle");
-
-    let verification_result: VerificationResult = output.unwrap();
+    // Abort with the error's stable code, so that failed proving runs can be told apart
+    let mut verification_result: VerificationResult = match output {
+        Ok(result) => result,
+        Err(error) => panic!("Failed to verify bundle (error {}): {}", error.code(), error),
+    };
+    verification_result.guest_version = env!("CARGO_PKG_VERSION").to_string();
+    verification_result.annotations_hash = annotations_hash(&input.annotations);
+    verification_result.block_anchor = input.block_anchor;
+    verification_result.previous_result_hash = input.previous_result_hash;
+    verification_result.input_digest = sha256(&input_bytes);
+    verification_result.policy_owner_hash = policy_owner_hash;
     env::commit_slice(&verification_result.as_slice());
+
+    // The profiles are side channels: stderr, not the journal
+    #[cfg(feature = "alloc-profile")]
+    eprintln!("{}", sigstore_verifier::profile::take_report());
+    #[cfg(feature = "cycle-profile")]
+    eprintln!("{}", sigstore_verifier::profile::take_cycle_report());
 }
\ No newline at end of file
@@ -0,0 +1,210 @@
+//! Chains of custody between attestations about the same subject
+//!
+//! Successive attestations about one artifact (built, then scanned, then
+//! deployed) are proven one by one and linked: each journal commits the
+//! [`result_hash`] of the previous one as `previousResultHash`, and the first
+//! commits zero. [`validate_chain`] checks a sequence of journals off-chain;
+//! [`CustodyChain`] builds one up and hands out the hash the next prover must
+//! be given.
+//!
+//! Only the links are checked here. Whether each journal comes with a valid
+//! proof is up to the caller.
+
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use crate::{result_hash, VerificationResult};
+
+/// A chain of custody whose links have been checked
+#[derive(Debug, Clone, Default)]
+pub struct CustodyChain {
+    results: Vec<VerificationResult>,
+    head: Option<[u8; 32]>,
+}
+
+impl CustodyChain {
+    /// Create an empty chain
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Decode `journal` and append it to the chain
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the journal doesn't decode, or doesn't link to the
+    /// last journal of the chain (see [`check_link`]). The first journal must
+    /// start a chain, i.e. commit no previous result.
+    pub fn push(&mut self, journal: &[u8]) -> Result<&VerificationResult, String> {
+        let result = VerificationResult::from_slice(journal)?;
+        match (self.results.last(), self.head) {
+            (Some(previous), Some(head)) => link_error(previous, head, &result)?,
+            _ => {
+                if result.previous_result_hash.is_some() {
+                    return Err("First journal doesn't start a chain: it links to a previous result".to_string());
+                }
+            }
+        }
+
+        self.head = Some(result_hash(journal));
+        self.results.push(result);
+        Ok(self.results.last().unwrap()) // Safe: just pushed
+    }
+
+    /// Hash the next journal must commit as its previous result, `None` while
+    /// the chain is empty
+    pub fn head(&self) -> Option<[u8; 32]> {
+        self.head
+    }
+
+    /// The decoded journals, oldest first
+    pub fn results(&self) -> &[VerificationResult] {
+        &self.results
+    }
+
+    pub fn len(&self) -> usize {
+        self.results.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.results.is_empty()
+    }
+}
+
+/// Check that the journals, oldest first, form a chain of custody
+///
+/// # Errors
+///
+/// Returns an error naming the first journal that doesn't decode or doesn't
+/// link to its predecessor
+pub fn validate_chain<J: AsRef<[u8]>>(journals: &[J]) -> Result<CustodyChain, String> {
+    let mut chain = CustodyChain::new();
+    for (index, journal) in journals.iter().enumerate() {
+        chain
+            .push(journal.as_ref())
+            .map_err(|e| format!("Journal {} of the chain: {}", index, e))?;
+    }
+    Ok(chain)
+}
+
+/// Check that `next` is a valid successor of `previous` in a chain of custody
+///
+/// `next` must commit the [`result_hash`] of `previous`, attest to the same
+/// subject digest and not have been signed before it.
+///
+/// # Errors
+///
+/// Returns an error if either journal doesn't decode or the link is invalid
+pub fn check_link(previous: &[u8], next: &[u8]) -> Result<(), String> {
+    let previous_result = VerificationResult::from_slice(previous)?;
+    let next_result = VerificationResult::from_slice(next)?;
+    link_error(&previous_result, result_hash(previous), &next_result)
+}
+
+fn link_error(previous: &VerificationResult, previous_hash: [u8; 32], next: &VerificationResult) -> Result<(), String> {
+    match next.previous_result_hash {
+        None => return Err("Journal starts a new chain instead of linking to the previous result".to_string()),
+        Some(hash) if hash != previous_hash => {
+            return Err(format!(
+                "Journal links to previous result {}, expected {}",
+                hex(&hash),
+                hex(&previous_hash)
+            ))
+        }
+        Some(_) => {}
+    }
+    if next.subject_digest != previous.subject_digest
+        || next.subject_digest_algorithm != previous.subject_digest_algorithm
+    {
+        return Err(format!(
+            "Journal attests to subject {}, expected {}",
+            hex(&next.subject_digest),
+            hex(&previous.subject_digest)
+        ));
+    }
+    if next.signing_time < previous.signing_time {
+        return Err(format!(
+            "Journal was signed at {}, before the previous result ({})",
+            next.signing_time, previous.signing_time
+        ));
+    }
+    Ok(())
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CertificateChainHashes, DigestAlgorithm, SubjectKind, TimestampProof};
+    use chrono::DateTime;
+    use alloc::vec;
+
+    fn journal(signing_time: i64, subject: u8, previous: Option<&[u8]>) -> Vec<u8> {
+        VerificationResult {
+            certificate_hashes: CertificateChainHashes {
+                leaf: [1u8; 32],
+                intermediates: vec![],
+                root: [2u8; 32],
+            },
+            signing_time: DateTime::from_timestamp(signing_time, 0).unwrap(),
+            subject_digest: vec![subject; 32],
+            subject_digest_algorithm: DigestAlgorithm::Sha256,
+            oidc_identity: None,
+            timestamp_proof: TimestampProof::None,
+            options_hash: [0u8; 32],
+            subject_name: "app.tar.gz".to_string(),
+            verifier_version: "0.1.0".to_string(),
+            guest_version: String::new(),
+            annotations_hash: [0u8; 32],
+            flags: 0,
+            block_anchor: None,
+            previous_result_hash: previous.map(result_hash),
+            verification_options: vec![],
+            certificate_timestamps: vec![],
+            input_digest: [0u8; 32],
+            predicate_type: String::new(),
+            predicate_sha256: [0u8; 32],
+            subject_kind: SubjectKind::Unknown,
+            policy_owner_hash: None,
+        }
+        .as_slice()
+    }
+
+    #[test]
+    fn test_build_and_validate_chain() {
+        let build = journal(1_700_000_000, 3, None);
+        let scan = journal(1_700_000_100, 3, Some(&build));
+        let deploy = journal(1_700_000_200, 3, Some(&scan));
+
+        let mut chain = CustodyChain::new();
+        assert_eq!(chain.head(), None);
+        chain.push(&build).unwrap();
+        chain.push(&scan).unwrap();
+        assert_eq!(chain.head(), Some(result_hash(&scan)));
+
+        let chain = validate_chain(&[&build, &scan, &deploy]).unwrap();
+        assert_eq!(chain.len(), 3);
+        assert_eq!(chain.results()[2].previous_result_hash, Some(result_hash(&scan)));
+        assert!(check_link(&scan, &deploy).is_ok());
+    }
+
+    #[test]
+    fn test_validate_chain_rejects_broken_links() {
+        let build = journal(1_700_000_000, 3, None);
+        let scan = journal(1_700_000_100, 3, Some(&build));
+
+        // Skipped, reordered and unlinked journals
+        let deploy = journal(1_700_000_200, 3, Some(&build));
+        let error = validate_chain(&[&build, &scan, &deploy]).unwrap_err();
+        assert!(error.starts_with("Journal 2 of the chain: Journal links to previous result"), "{}", error);
+        assert!(validate_chain(&[&scan, &build]).is_err());
+        assert!(validate_chain(&[&build, &journal(1_700_000_100, 3, None)]).is_err());
+
+        // Another subject, or signed before its predecessor
+        assert!(check_link(&build, &journal(1_700_000_100, 4, Some(&build))).is_err());
+        assert!(check_link(&scan, &journal(1_700_000_050, 3, Some(&scan))).is_err());
+    }
+}
@@ -0,0 +1,1764 @@
+//! Journal format of the Sigstore verifier
+//!
+//! Encoding and decoding of the `VerificationResult` committed by the zkVM
+//! guests, together with the Solidity ABI structs it is built from. The crate
+//! only depends on `alloy-sol-types`, `serde`, `chrono` and `sha2`, so block explorers
+//! and indexers can decode journals without the x509, HTTP and zkVM
+//! dependencies of `sigstore-verifier`, which re-exports everything here.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use sigstore_journal::VerificationResult;
+//!
+//! let result = VerificationResult::from_slice(&journal)?;
+//! println!("Signed at {}", result.signing_time);
+//! ```
+//!
+//! The crate is `no_std` and only needs `alloc`, so it also builds for zkVM
+//! guests and other targets without an operating system.
+
+#![no_std]
+
+extern crate alloc;
+
+use alloc::collections::BTreeMap;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use alloy_sol_types::{sol, SolValue};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+pub mod custody;
+
+// =============================================================================
+// Solidity ABI Encoding Format
+// =============================================================================
+//
+// The serialized VerificationResult has the following binary format:
+//
+// ┌─────────────────────────────────────────────────────────────────────────────┐
+// │ [1 byte]   version               - CURRENT_VERSION (2)                      │
+// │ [8 bytes]  signing_time          - uint64 big-endian Unix timestamp         │
+// │ [1 byte]   timestamp_proof_type  - 0=None, 1=RFC3161, 2=Rekor               │
+// │ [N bytes]  ABI-encoded VerificationResultEncoded struct                     │
+// └─────────────────────────────────────────────────────────────────────────────┘
+//
+// Version 1 journals, written before the format was versioned, have no version
+// byte and start with the signing time. Its first byte is zero for any time
+// before the year 2^56 seconds, so a leading zero identifies version 1, and
+// version bytes start at 2. Version 1 encodes VerificationResultEncodedV1, whose
+// fields are the first fields of VerificationResultEncoded up to the Rekor entry,
+// and version 2 VerificationResultEncoded.
+//
+// Field descriptions:
+//
+// - certificateHashes: SHA256 hashes of the signing certificate chain
+//   Format: [leaf_hash, ...intermediate_hashes, root_hash]
+//
+// - subjectDigest: The artifact digest from the attestation (typically SHA256)
+//
+// - subjectDigestAlgorithm: Hash algorithm for subjectDigest
+//   0 = Unknown, 1 = SHA256, 2 = SHA384, 3 = SHA1 (the object ID of a signed git
+//   commit or tag), 4 = SHA512
+//
+// - oidcIssuer: OIDC token issuer (e.g., "https://token.actions.githubusercontent.com")
+//
+// - oidcSubject: OIDC subject claim
+//
+// - oidcWorkflowRef: GitHub workflow reference (GitHub Actions specific)
+//
+// - oidcRepository: Source repository (GitHub Actions specific)
+//
+// - oidcEventName: Trigger event name (GitHub Actions specific)
+//
+// - tsaChainHashes: For RFC 3161 timestamps, SHA256 hashes of TSA certificate chain
+//   Format: [leaf_hash, ...intermediate_hashes, root_hash]. Empty for Rekor.
+//
+// - messageImprintAlgorithm: For RFC 3161, the hash algorithm used in the timestamp
+//   0 = Unknown, 1 = SHA256, 2 = SHA384. Set to 0 for Rekor.
+//
+// - messageImprint: For RFC 3161, the hash of the DSSE signature that was timestamped.
+//   This proves the timestamp was generated for this specific signature. Empty for Rekor.
+//
+// - rekorEntry (logId, logIndex, entryIndex): For Rekor, the SHA256 hash of Rekor's
+//   public key (identifies the log instance), the tree leaf index (for Merkle proof
+//   verification) and the entry index (for API queries to fetch the full entry).
+//   Zero for RFC 3161. Being a static struct, it is encoded in place, exactly as the
+//   three fields rekorLogId, rekorLogIndex and rekorEntryIndex it replaced.
+//
+// - optionsHash: sha256(abi.encode(expectedDigest, expectedIssuer, expectedSubject,
+//   expectedSubjectName, expectedEmail, allowedEmailIssuers, expectedSpiffeTrustDomain,
+//   expectedSpiffePathPrefix, identityPolicyHash, expectedRepository, expectedWorkflowRef,
+//   expectedEventName, expectedPredicateType, maxSigningAge, verificationTime, profile,
+//   requiredTimestamp, clockSkew, repositoryPolicyHash, expectedTsaPolicy)), i.e. a
+//   commitment to the constraints the bundle was checked against. profile is the
+//   name@version of the verification profile applied, requiredTimestamp 0 = any,
+//   1 = RFC 3161, 2 = Rekor, repositoryPolicyHash the sha256 of the JSON encoding of
+//   the repository policy entry enforced (zero without one), and expectedTsaPolicy the
+//   dotted OID of the TSA policy the RFC 3161 timestamp was required to be issued under.
+//   Unset options are encoded as empty values, so a verifier run without any
+//   constraints always commits to the same hash.
+//
+// - subjectName: Name of the attested subject whose digest is reported in subjectDigest
+//
+// - verifierVersion: Version of the sigstore-verifier crate that ran the verification
+//
+// - guestVersion: Version of the zkVM guest program that committed the journal.
+//   Empty when the result was produced natively rather than inside a guest.
+//
+// - annotationsHash: sha256(abi.encode(keys, values)) over the caller's key-value
+//   annotations sorted by key (see annotations_hash). Zero bytes when there are none.
+//
+// - oidcSubjectKind: What the SAN in oidcSubject identifies
+//   0 = Unknown, 1 = URI (e.g. a workflow), 2 = Email (keyless signing by a human),
+//   3 = SPIFFE ID (workload identity, e.g. SPIRE)
+//
+// - flags: Bit field of the verification modes in effect (see the flags module)
+//   Bit 0 = archival: every certificate's validity was evaluated at signingTime
+//   Bit 1 = all signatures: every DSSE signature verified, not only the first
+//   Bit 2 = normalized issuer: issuers compared as normalized URLs
+//   Bit 3 = case-insensitive subject: subject compared ignoring ASCII case
+//   Bit 4 = policy violation: the bundle verified but violates the policy in
+//           optionsHash (quarantine mode), which was not enforced
+//
+// - anchorBlock (number, hash): A recent block the prover was given (see BlockAnchor).
+//   A contract that finds the hash at that block number on its chain knows the proof
+//   was generated after that block. Zero when not anchored. Being a static struct, it
+//   is encoded in place, exactly as the two fields anchorBlockNumber and anchorBlockHash.
+//
+// - previousResultHash: result_hash of the journal of the previous attestation about
+//   the same subject (see the custody module). Zero for the first link of a chain.
+//
+// - verificationOptions: The abi.encode of VerificationOptionsEncoded that optionsHash
+//   is the sha256 of, so a contract can read which constraints were enforced inside
+//   the guest instead of recomputing the hash. Empty when not committed.
+//
+// - certificateTimestamps (logId, timestamp)[]: Signed certificate timestamps (SCTs)
+//   embedded in the leaf certificate whose signature verified with a trusted CT log
+//   key: the SHA256 of the log's key and the time, in milliseconds since the Unix
+//   epoch, the log promised to include the certificate by. Empty when no CT log
+//   keys were given.
+//
+// - extension (inputDigest, predicateType, predicateSha256, subjectKind,
+//   subjectNameHash, policyOwnerHash): Fields following certificateTimestamps, nested to stay within
+//   the 24 fields `sol!` supports. Being a dynamic struct, its head word is an
+//   offset to the fields.
+//
+//   - inputDigest: SHA256 of the encoded ProverInput the guest read, so a proof can
+//     be matched to the input prepared (and possibly signed) by the policy owner.
+//     Zero outside a zkVM.
+//
+//   - predicateType: predicateType of the in-toto statement (e.g.
+//     "https://slsa.dev/provenance/v1" or "https://spdx.dev/Document"), telling a
+//     provenance from an SBOM or a VSA. Empty for message signatures.
+//
+//   - predicateSha256: SHA256 of the predicate JSON exactly as it appears in the
+//     statement, so the attested predicate can be matched to its proof. Zero for
+//     message signatures.
+//
+//   - subjectKind: What subjectName and subjectDigest refer to (see SubjectKind)
+//     0 = Unknown, 1 = Artifact (a file or image, digested by content),
+//     2 = Purl (subjectName is a package URL), 3 = Git (a commit or tag; with
+//     SHA1 the digest is its object ID)
+//
+//   - subjectNameHash: sha256(bytes(subjectName)), so a contract can compare the
+//     name with a constant without hashing the string itself
+//
+//   - policyOwnerHash: SHA256 of the Ed25519 public key of the policy owner whose
+//     signature over the prover input the guest verified, proving that the input
+//     was authorized and not only well-formed. Zero for unsigned inputs.
+//
+// =============================================================================
+
+sol! {
+    #[derive(Debug, PartialEq)]
+    struct VerificationResultEncoded {
+        bytes32[] certificateHashes;
+        bytes subjectDigest;
+        uint8 subjectDigestAlgorithm;
+        string oidcIssuer;
+        string oidcSubject;
+        string oidcWorkflowRef;
+        string oidcRepository;
+        string oidcEventName;
+        bytes32[] tsaChainHashes;
+        uint8 messageImprintAlgorithm;
+        bytes messageImprint;
+        RekorEntryEncoded rekorEntry;
+        bytes32 optionsHash;
+        string subjectName;
+        string verifierVersion;
+        string guestVersion;
+        bytes32 annotationsHash;
+        uint8 oidcSubjectKind;
+        uint8 flags;
+        BlockAnchorEncoded anchorBlock;
+        bytes32 previousResultHash;
+        bytes verificationOptions;
+        CertificateTimestampEncoded[] certificateTimestamps;
+        ResultExtensionEncoded extension;
+    }
+
+    // Structs rather than separate fields only to stay within the 24 fields `sol!` supports
+    #[derive(Debug, PartialEq)]
+    struct BlockAnchorEncoded {
+        uint64 number;
+        bytes32 hash;
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct RekorEntryEncoded {
+        bytes32 logId;
+        uint64 logIndex;
+        uint64 entryIndex;
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct CertificateTimestampEncoded {
+        bytes32 logId;
+        uint64 timestamp;
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct ResultExtensionEncoded {
+        bytes32 inputDigest;
+        string predicateType;
+        bytes32 predicateSha256;
+        uint8 subjectKind;
+        bytes32 subjectNameHash;
+        bytes32 policyOwnerHash;
+    }
+
+    // Layout of version 1 journals, decoded by `VerificationResult::from_slice`
+    #[derive(Debug, PartialEq)]
+    struct VerificationResultEncodedV1 {
+        bytes32[] certificateHashes;
+        bytes subjectDigest;
+        uint8 subjectDigestAlgorithm;
+        string oidcIssuer;
+        string oidcSubject;
+        string oidcWorkflowRef;
+        string oidcRepository;
+        string oidcEventName;
+        bytes32[] tsaChainHashes;
+        uint8 messageImprintAlgorithm;
+        bytes messageImprint;
+        bytes32 rekorLogId;
+        uint64 rekorLogIndex;
+        uint64 rekorEntryIndex;
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct VerificationOptionsEncoded {
+        bytes expectedDigest;
+        string expectedIssuer;
+        string expectedSubject;
+        string expectedSubjectName;
+        string expectedEmail;
+        string[] allowedEmailIssuers;
+        string expectedSpiffeTrustDomain;
+        string expectedSpiffePathPrefix;
+        bytes32 identityPolicyHash;
+        string expectedRepository;
+        string expectedWorkflowRef;
+        string expectedEventName;
+        string expectedPredicateType;
+        uint64 maxSigningAge;
+        uint64 verificationTime;
+        string profile;
+        uint8 requiredTimestamp;
+        uint64 clockSkew;
+        bytes32 repositoryPolicyHash;
+        string expectedTsaPolicy;
+    }
+}
+
+/// Journal format version written by [`VerificationResult::as_slice`]
+pub const CURRENT_VERSION: u8 = 2;
+
+/// Version of the unprefixed journals written before the format was versioned
+pub const LEGACY_VERSION: u8 = 1;
+
+/// Frozen type string of [`VerificationResultEncoded`] (field types, names and order)
+///
+/// Deployed contracts decode the journal positionally, so reordering, renaming or
+/// retyping a field is a breaking change. The tests compare this constant with the
+/// type generated by `sol!`; update it only together with `contracts/src/Types.sol`.
+pub const VERIFICATION_RESULT_ENCODED_TYPE: &str = "VerificationResultEncoded(\
+bytes32[] certificateHashes,\
+bytes subjectDigest,\
+uint8 subjectDigestAlgorithm,\
+string oidcIssuer,\
+string oidcSubject,\
+string oidcWorkflowRef,\
+string oidcRepository,\
+string oidcEventName,\
+bytes32[] tsaChainHashes,\
+uint8 messageImprintAlgorithm,\
+bytes messageImprint,\
+RekorEntryEncoded rekorEntry,\
+bytes32 optionsHash,\
+string subjectName,\
+string verifierVersion,\
+string guestVersion,\
+bytes32 annotationsHash,\
+uint8 oidcSubjectKind,\
+uint8 flags,\
+BlockAnchorEncoded anchorBlock,\
+bytes32 previousResultHash,\
+bytes verificationOptions,\
+CertificateTimestampEncoded[] certificateTimestamps,\
+ResultExtensionEncoded extension)\
+BlockAnchorEncoded(\
+uint64 number,\
+bytes32 hash)\
+CertificateTimestampEncoded(\
+bytes32 logId,\
+uint64 timestamp)\
+RekorEntryEncoded(\
+bytes32 logId,\
+uint64 logIndex,\
+uint64 entryIndex)\
+ResultExtensionEncoded(\
+bytes32 inputDigest,\
+string predicateType,\
+bytes32 predicateSha256,\
+uint8 subjectKind,\
+bytes32 subjectNameHash,\
+bytes32 policyOwnerHash)";
+
+/// Frozen type string of [`VerificationOptionsEncoded`], whose ABI encoding is hashed
+/// into `optionsHash`
+pub const VERIFICATION_OPTIONS_ENCODED_TYPE: &str = "VerificationOptionsEncoded(\
+bytes expectedDigest,\
+string expectedIssuer,\
+string expectedSubject,\
+string expectedSubjectName,\
+string expectedEmail,\
+string[] allowedEmailIssuers,\
+string expectedSpiffeTrustDomain,\
+string expectedSpiffePathPrefix,\
+bytes32 identityPolicyHash,\
+string expectedRepository,\
+string expectedWorkflowRef,\
+string expectedEventName,\
+string expectedPredicateType,\
+uint64 maxSigningAge,\
+uint64 verificationTime,\
+string profile,\
+uint8 requiredTimestamp,\
+uint64 clockSkew,\
+bytes32 repositoryPolicyHash,\
+string expectedTsaPolicy)";
+
+/// Hash algorithm identifier for Solidity encoding
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[repr(u8)]
+pub enum DigestAlgorithm {
+    Unknown = 0,
+    Sha256 = 1,
+    Sha384 = 2,
+    /// Git object ID of a signed commit or tag
+    Sha1 = 3,
+    Sha512 = 4,
+}
+
+impl DigestAlgorithm {
+    pub fn from_u8(value: u8) -> Self {
+        match value {
+            1 => DigestAlgorithm::Sha256,
+            2 => DigestAlgorithm::Sha384,
+            3 => DigestAlgorithm::Sha1,
+            4 => DigestAlgorithm::Sha512,
+            _ => DigestAlgorithm::Unknown,
+        }
+    }
+}
+
+/// Timestamp proof type identifier
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[repr(u8)]
+pub enum TimestampProofType {
+    None = 0,
+    Rfc3161 = 1,
+    Rekor = 2,
+}
+
+impl TimestampProofType {
+    pub fn from_u8(value: u8) -> Self {
+        match value {
+            1 => TimestampProofType::Rfc3161,
+            2 => TimestampProofType::Rekor,
+            _ => TimestampProofType::None,
+        }
+    }
+}
+
+/// Kind of identity in the certificate's Subject Alternative Name
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[repr(u8)]
+pub enum IdentityKind {
+    #[default]
+    Unknown = 0,
+    /// URI SAN, e.g. a CI workflow identity
+    Uri = 1,
+    /// Email SAN, from a human signing through an OAuth provider
+    Email = 2,
+    /// `spiffe://` URI SAN, from a workload (e.g. SPIRE-backed)
+    Spiffe = 3,
+}
+
+impl IdentityKind {
+    pub fn from_u8(value: u8) -> Self {
+        match value {
+            1 => IdentityKind::Uri,
+            2 => IdentityKind::Email,
+            3 => IdentityKind::Spiffe,
+            _ => IdentityKind::Unknown,
+        }
+    }
+}
+
+/// What the attested subject is, and so what its name and digest refer to
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[repr(u8)]
+pub enum SubjectKind {
+    /// Not recorded, as in version 1 journals
+    #[default]
+    Unknown = 0,
+    /// A file or image named plainly, digested by content
+    Artifact = 1,
+    /// A package, named by its package URL (`pkg:...`)
+    Purl = 2,
+    /// A git commit or tag, named by a `git+` URL or its tag name; with
+    /// [`DigestAlgorithm::Sha1`] the digest is its object ID
+    Git = 3,
+}
+
+impl SubjectKind {
+    pub fn from_u8(value: u8) -> Self {
+        match value {
+            1 => SubjectKind::Artifact,
+            2 => SubjectKind::Purl,
+            3 => SubjectKind::Git,
+            _ => SubjectKind::Unknown,
+        }
+    }
+}
+
+/// Bits of [`VerificationResult::flags`]
+pub mod flags {
+    /// Archival verification: the validity of every certificate in the signing
+    /// and TSA chains was evaluated strictly at the signing time
+    pub const ARCHIVAL: u8 = 1 << 0;
+    /// Every signature of the DSSE envelope was verified with the leaf key and
+    /// none names another key, rather than only the first signature
+    pub const ALL_SIGNATURES: u8 = 1 << 1;
+    /// Issuers were compared as normalized URLs
+    pub const NORMALIZED_ISSUER: u8 = 1 << 2;
+    /// The subject was compared ignoring ASCII case
+    pub const CASE_INSENSITIVE_SUBJECT: u8 = 1 << 3;
+    /// Quarantine mode: the bundle verified but violates the policy committed in
+    /// the options hash, so the result was produced without it
+    pub const POLICY_VIOLATION: u8 = 1 << 4;
+}
+
+/// Timestamp proof data - proves when the signature was created
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub enum TimestampProof {
+    /// No timestamp proof available
+    #[default]
+    None,
+
+    /// RFC 3161 Timestamp Authority proof
+    Rfc3161 {
+        /// SHA256 hashes of TSA certificate chain [leaf, ...intermediates, root]
+        tsa_chain_hashes: CertificateChainHashes,
+        /// Hash algorithm used for the message imprint
+        message_imprint_algorithm: DigestAlgorithm,
+        /// The message imprint (hash of the DSSE signature)
+        message_imprint: Vec<u8>,
+    },
+
+    /// Sigstore Rekor transparency log proof
+    Rekor {
+        /// SHA256 of Rekor's public key (identifies the log instance)
+        log_id: [u8; 32],
+        /// Tree leaf index (for Merkle proof verification against checkpoint)
+        log_index: u64,
+        /// Entry index (for API queries to fetch the full entry)
+        entry_index: u64,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VerificationResult {
+    pub certificate_hashes: CertificateChainHashes,
+    pub signing_time: DateTime<Utc>,
+    pub subject_digest: Vec<u8>,
+    pub subject_digest_algorithm: DigestAlgorithm,
+    pub oidc_identity: Option<OidcIdentity>,
+    pub timestamp_proof: TimestampProof,
+    /// SHA256 of the verification options the bundle was checked against
+    pub options_hash: [u8; 32],
+    /// Name of the subject the digest was taken from
+    pub subject_name: String,
+    /// Version of the `sigstore-verifier` crate that produced the result
+    pub verifier_version: String,
+    /// Version of the zkVM guest program that committed the journal (empty outside a guest)
+    pub guest_version: String,
+    /// Commitment to the caller's annotations (see [`annotations_hash`])
+    pub annotations_hash: [u8; 32],
+    /// Verification modes in effect (see [`flags`])
+    #[serde(default)]
+    pub flags: u8,
+    /// Recent block the proof was generated after, if the prover was given one
+    #[serde(default)]
+    pub block_anchor: Option<BlockAnchor>,
+    /// [`result_hash`] of the previous journal in a chain of custody, if any
+    #[serde(default)]
+    pub previous_result_hash: Option<[u8; 32]>,
+    /// ABI encoding of the [`VerificationOptionsEncoded`] hashed into `options_hash`,
+    /// empty if the options were only committed by hash
+    #[serde(default)]
+    pub verification_options: Vec<u8>,
+    /// SCTs of the leaf certificate verified against trusted CT log keys, empty
+    /// if none were checked
+    #[serde(default)]
+    pub certificate_timestamps: Vec<CertificateTimestamp>,
+    /// SHA256 of the encoded prover input the guest read, zero outside a zkVM
+    #[serde(default)]
+    pub input_digest: [u8; 32],
+    /// predicateType of the in-toto statement, empty for message signatures
+    #[serde(default)]
+    pub predicate_type: String,
+    /// SHA256 of the statement's predicate JSON as signed, zero for message signatures
+    #[serde(default)]
+    pub predicate_sha256: [u8; 32],
+    /// What `subject_name` and `subject_digest` refer to
+    #[serde(default)]
+    pub subject_kind: SubjectKind,
+    /// SHA256 of the policy owner key that signed the prover input, if it was signed
+    #[serde(default)]
+    pub policy_owner_hash: Option<[u8; 32]>,
+}
+
+/// A signed certificate timestamp (SCT): a certificate transparency log's
+/// promise to publish the leaf certificate
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CertificateTimestamp {
+    /// SHA256 of the log's public key (DER SubjectPublicKeyInfo)
+    pub log_id: [u8; 32],
+    /// Time the log received the certificate, in milliseconds since the Unix epoch
+    pub timestamp: u64,
+}
+
+impl From<VerificationResultEncodedV1> for VerificationResultEncoded {
+    fn from(v1: VerificationResultEncodedV1) -> Self {
+        Self {
+            certificateHashes: v1.certificateHashes,
+            subjectDigest: v1.subjectDigest,
+            subjectDigestAlgorithm: v1.subjectDigestAlgorithm,
+            oidcIssuer: v1.oidcIssuer,
+            oidcSubject: v1.oidcSubject,
+            oidcWorkflowRef: v1.oidcWorkflowRef,
+            oidcRepository: v1.oidcRepository,
+            oidcEventName: v1.oidcEventName,
+            tsaChainHashes: v1.tsaChainHashes,
+            messageImprintAlgorithm: v1.messageImprintAlgorithm,
+            messageImprint: v1.messageImprint,
+            rekorEntry: RekorEntryEncoded {
+                logId: v1.rekorLogId,
+                logIndex: v1.rekorLogIndex,
+                entryIndex: v1.rekorEntryIndex,
+            },
+            optionsHash: [0u8; 32].into(),
+            subjectName: String::new(),
+            verifierVersion: String::new(),
+            guestVersion: String::new(),
+            annotationsHash: [0u8; 32].into(),
+            oidcSubjectKind: 0,
+            flags: 0,
+            anchorBlock: BlockAnchorEncoded {
+                number: 0,
+                hash: [0u8; 32].into(),
+            },
+            previousResultHash: [0u8; 32].into(),
+            verificationOptions: Vec::new().into(),
+            certificateTimestamps: Vec::new(),
+            extension: ResultExtensionEncoded {
+                inputDigest: [0u8; 32].into(),
+                predicateType: String::new(),
+                predicateSha256: [0u8; 32].into(),
+                subjectKind: SubjectKind::Unknown as u8,
+                subjectNameHash: [0u8; 32].into(),
+                policyOwnerHash: [0u8; 32].into(),
+            },
+        }
+    }
+}
+
+/// A block of the chain the proof is submitted to, committed by the guest
+///
+/// The prover is handed a recent block number and hash and the guest commits
+/// them unchanged. Nothing inside the guest checks them; a contract that finds
+/// `hash` at `number` with `blockhash` knows the proof was generated after that
+/// block was produced, without trusting the prover's clock.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlockAnchor {
+    pub number: u64,
+    pub hash: [u8; 32],
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CertificateChainHashes {
+    pub leaf: [u8; 32],
+    pub intermediates: Vec<[u8; 32]>,
+    pub root: [u8; 32],
+}
+
+impl CertificateChainHashes {
+    pub fn as_tuple(&self) -> ([u8; 32], Vec<[u8; 32]>, [u8; 32]) {
+        (self.leaf, self.intermediates.clone(), self.root)
+    }
+}
+
+/// Compute the commitment to a set of key-value annotations
+///
+/// Annotations (build numbers, environment names, ...) are not verified; the
+/// guest commits this hash so the plaintext recorded next to a proof can be
+/// checked against the journal. The map's key order makes the encoding
+/// canonical, and Solidity can recompute it as
+/// `sha256(abi.encode(string[] keys, string[] values))`.
+///
+/// # Arguments
+///
+/// * `annotations` - The annotations, keyed by name
+///
+/// # Returns
+///
+/// Zero bytes when `annotations` is empty, the SHA256 described above otherwise
+pub fn annotations_hash(annotations: &BTreeMap<String, String>) -> [u8; 32] {
+    if annotations.is_empty() {
+        return [0u8; 32];
+    }
+    let keys: Vec<String> = annotations.keys().cloned().collect();
+    let values: Vec<String> = annotations.values().cloned().collect();
+    Sha256::digest((keys, values).abi_encode_params()).into()
+}
+
+/// Commitment to the subject name: `sha256(bytes(subjectName))`
+pub fn subject_name_hash(subject_name: &str) -> [u8; 32] {
+    Sha256::digest(subject_name.as_bytes()).into()
+}
+
+/// Format version of a serialized journal
+///
+/// # Errors
+///
+/// Returns an error if `journal` is empty or starts with an unknown version byte
+pub fn journal_version(journal: &[u8]) -> Result<u8, String> {
+    match journal.first() {
+        None => Err("Empty journal".to_string()),
+        Some(0) => Ok(LEGACY_VERSION),
+        Some(&CURRENT_VERSION) => Ok(CURRENT_VERSION),
+        Some(version) => Err(format!("Unsupported journal version {}", version)),
+    }
+}
+
+/// Compute the hash that identifies a journal in a chain of custody
+///
+/// This is the SHA256 of the journal bytes, i.e. the journal digest RISC Zero
+/// receipts commit to, so contracts can link proofs without re-encoding them.
+pub fn result_hash(journal: &[u8]) -> [u8; 32] {
+    Sha256::digest(journal).into()
+}
+
+/// OIDC identity of the signer, taken from the Fulcio certificate extensions
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OidcIdentity {
+    pub issuer: Option<String>,
+    pub subject: Option<String>,
+    pub workflow_ref: Option<String>,
+    pub repository: Option<String>,
+    pub event_name: Option<String>,
+    /// What `subject` identifies
+    #[serde(default)]
+    pub subject_kind: IdentityKind,
+}
+
+impl VerificationResult {
+    /// Whether the result was produced in archival verification mode
+    pub fn is_archival(&self) -> bool {
+        self.flags & flags::ARCHIVAL != 0
+    }
+
+    /// Whether every DSSE signature was verified, not only the first
+    pub fn all_signatures_verified(&self) -> bool {
+        self.flags & flags::ALL_SIGNATURES != 0
+    }
+
+    /// Whether issuers were compared as normalized URLs
+    pub fn issuer_normalized(&self) -> bool {
+        self.flags & flags::NORMALIZED_ISSUER != 0
+    }
+
+    /// Whether the subject was compared ignoring ASCII case
+    pub fn subject_case_insensitive(&self) -> bool {
+        self.flags & flags::CASE_INSENSITIVE_SUBJECT != 0
+    }
+
+    /// Whether the bundle verified but violates the committed policy (quarantine mode)
+    pub fn is_policy_violation(&self) -> bool {
+        self.flags & flags::POLICY_VIOLATION != 0
+    }
+
+    /// Serialize the VerificationResult into a Solidity-compatible byte array
+    ///
+    /// See the module-level documentation for the complete binary format specification.
+    ///
+    /// # Returns
+    ///
+    /// A byte vector containing the serialized data suitable for Solidity smart contract consumption.
+    pub fn as_slice(&self) -> Vec<u8> {
+        // Convert signing_time to u64 Unix timestamp (big-endian)
+        let timestamp = self.signing_time.timestamp() as u64;
+        let timestamp_bytes = timestamp.to_be_bytes();
+
+        // Determine timestamp proof type
+        let proof_type: u8 = match &self.timestamp_proof {
+            TimestampProof::None => TimestampProofType::None as u8,
+            TimestampProof::Rfc3161 { .. } => TimestampProofType::Rfc3161 as u8,
+            TimestampProof::Rekor { .. } => TimestampProofType::Rekor as u8,
+        };
+
+        // Build certificate hashes array: [leaf, ...intermediates, root]
+        let mut cert_hashes = Vec::with_capacity(2 + self.certificate_hashes.intermediates.len());
+        cert_hashes.push(self.certificate_hashes.leaf.into());
+        for intermediate in &self.certificate_hashes.intermediates {
+            cert_hashes.push((*intermediate).into());
+        }
+        cert_hashes.push(self.certificate_hashes.root.into());
+
+        // Extract OIDC fields, using empty strings for None
+        let (issuer, subject, workflow_ref, repository, event_name, subject_kind) = if let Some(ref oidc) = self.oidc_identity {
+            (
+                oidc.issuer.clone().unwrap_or_default(),
+                oidc.subject.clone().unwrap_or_default(),
+                oidc.workflow_ref.clone().unwrap_or_default(),
+                oidc.repository.clone().unwrap_or_default(),
+                oidc.event_name.clone().unwrap_or_default(),
+                oidc.subject_kind as u8,
+            )
+        } else {
+            (String::new(), String::new(), String::new(), String::new(), String::new(), IdentityKind::Unknown as u8)
+        };
+
+        // Extract timestamp proof fields based on type
+        let (tsa_chain_hashes, message_imprint_algorithm, message_imprint, rekor_log_id, rekor_log_index, rekor_entry_index) =
+            match &self.timestamp_proof {
+                TimestampProof::None => {
+                    (vec![], 0u8, vec![], [0u8; 32], 0u64, 0u64)
+                }
+                TimestampProof::Rfc3161 {
+                    tsa_chain_hashes,
+                    message_imprint_algorithm,
+                    message_imprint,
+                } => {
+                    let mut hashes = Vec::with_capacity(2 + tsa_chain_hashes.intermediates.len());
+                    hashes.push(tsa_chain_hashes.leaf.into());
+                    for intermediate in &tsa_chain_hashes.intermediates {
+                        hashes.push((*intermediate).into());
+                    }
+                    hashes.push(tsa_chain_hashes.root.into());
+                    (
+                        hashes,
+                        *message_imprint_algorithm as u8,
+                        message_imprint.clone(),
+                        [0u8; 32],
+                        0u64,
+                        0u64,
+                    )
+                }
+                TimestampProof::Rekor { log_id, log_index, entry_index } => {
+                    (vec![], 0u8, vec![], *log_id, *log_index, *entry_index)
+                }
+            };
+
+        // Create the Solidity-compatible struct
+        let encoded_struct = VerificationResultEncoded {
+            certificateHashes: cert_hashes,
+            subjectDigest: self.subject_digest.clone().into(),
+            subjectDigestAlgorithm: self.subject_digest_algorithm as u8,
+            oidcIssuer: issuer,
+            oidcSubject: subject,
+            oidcWorkflowRef: workflow_ref,
+            oidcRepository: repository,
+            oidcEventName: event_name,
+            tsaChainHashes: tsa_chain_hashes,
+            messageImprintAlgorithm: message_imprint_algorithm,
+            messageImprint: message_imprint.into(),
+            rekorEntry: RekorEntryEncoded {
+                logId: rekor_log_id.into(),
+                logIndex: rekor_log_index,
+                entryIndex: rekor_entry_index,
+            },
+            optionsHash: self.options_hash.into(),
+            subjectName: self.subject_name.clone(),
+            verifierVersion: self.verifier_version.clone(),
+            guestVersion: self.guest_version.clone(),
+            annotationsHash: self.annotations_hash.into(),
+            oidcSubjectKind: subject_kind,
+            flags: self.flags,
+            anchorBlock: BlockAnchorEncoded {
+                number: self.block_anchor.map_or(0, |anchor| anchor.number),
+                hash: self.block_anchor.map_or([0u8; 32], |anchor| anchor.hash).into(),
+            },
+            previousResultHash: self.previous_result_hash.unwrap_or_default().into(),
+            verificationOptions: self.verification_options.clone().into(),
+            certificateTimestamps: self
+                .certificate_timestamps
+                .iter()
+                .map(|sct| CertificateTimestampEncoded {
+                    logId: sct.log_id.into(),
+                    timestamp: sct.timestamp,
+                })
+                .collect(),
+            extension: ResultExtensionEncoded {
+                inputDigest: self.input_digest.into(),
+                predicateType: self.predicate_type.clone(),
+                predicateSha256: self.predicate_sha256.into(),
+                subjectKind: self.subject_kind as u8,
+                subjectNameHash: subject_name_hash(&self.subject_name).into(),
+                policyOwnerHash: self.policy_owner_hash.unwrap_or_default().into(),
+            },
+        };
+
+        // Encode using standard ABI encoding
+        let abi_encoded = encoded_struct.abi_encode();
+
+        // Build result: [version (1 byte)] || [timestamp (8 bytes)] || [proof_type (1 byte)] || [ABI-encoded data]
+        let mut result = Vec::with_capacity(10 + abi_encoded.len());
+        result.push(CURRENT_VERSION);
+        result.extend_from_slice(&timestamp_bytes);
+        result.push(proof_type);
+        result.extend_from_slice(&abi_encoded);
+
+        result
+    }
+
+    /// Deserialize a VerificationResult from a Solidity-compatible byte array
+    ///
+    /// This is the inverse operation of `as_slice()`. It parses the byte array
+    /// and reconstructs the VerificationResult. Journals of every version are
+    /// accepted (see [`journal_version`]); version 1 journals decode with only
+    /// the certificate, subject, OIDC and timestamp fields set.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The byte slice to deserialize
+    ///
+    /// # Returns
+    ///
+    /// Returns `Ok(VerificationResult)` if deserialization succeeds, or an error message
+    /// describing what went wrong.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - The data is shorter than 9 bytes (minimum size for timestamp + proof type)
+    /// - The data starts with an unknown version byte
+    /// - ABI decoding fails
+    /// - The certificate hashes array has fewer than 2 elements
+    /// - The committed verification options don't hash to the options hash
+    pub fn from_slice(data: &[u8]) -> Result<Self, String> {
+        // Need at least 9 bytes for timestamp (8) + proof type (1), after the version byte
+        if data.len() < 9 {
+            return Err(format!("Data too short: expected at least 9 bytes, got {}", data.len()));
+        }
+        let version = journal_version(data)?;
+        let header_len = if version == LEGACY_VERSION { 9 } else { 10 };
+        if data.len() < header_len {
+            return Err(format!(
+                "Data too short: expected at least {} bytes, got {}",
+                header_len,
+                data.len()
+            ));
+        }
+        let data = &data[header_len - 9..];
+
+        // Extract timestamp (first 8 bytes, big-endian)
+        let timestamp_bytes: [u8; 8] = data[0..8].try_into().unwrap();
+        let timestamp = u64::from_be_bytes(timestamp_bytes);
+
+        // Extract proof type (byte 9)
+        let proof_type = TimestampProofType::from_u8(data[8]);
+
+        // Decode the remaining ABI-encoded data
+        let abi_data = &data[9..];
+        let decoded = match version {
+            CURRENT_VERSION => VerificationResultEncoded::abi_decode(abi_data),
+            _ => VerificationResultEncodedV1::abi_decode(abi_data).map(VerificationResultEncoded::from),
+        }
+        .map_err(|e| format!("Failed to ABI decode: {}", e))?;
+
+        // Extract certificate hashes: first is leaf, last is root, middle are intermediates
+        if decoded.certificateHashes.len() < 2 {
+            return Err(format!(
+                "Certificate hashes array must have at least 2 elements (leaf and root), got {}",
+                decoded.certificateHashes.len()
+            ));
+        }
+
+        let leaf = decoded.certificateHashes[0].0;
+        let root = decoded.certificateHashes[decoded.certificateHashes.len() - 1].0;
+        let intermediates: Vec<[u8; 32]> = decoded.certificateHashes[1..decoded.certificateHashes.len() - 1]
+            .iter()
+            .map(|h| h.0)
+            .collect();
+
+        // Reconstruct OIDC identity (only if any field is non-empty)
+        let oidc_identity = if decoded.oidcIssuer.is_empty()
+            && decoded.oidcSubject.is_empty()
+            && decoded.oidcWorkflowRef.is_empty()
+            && decoded.oidcRepository.is_empty()
+            && decoded.oidcEventName.is_empty()
+        {
+            None
+        } else {
+            Some(OidcIdentity {
+                issuer: if decoded.oidcIssuer.is_empty() { None } else { Some(decoded.oidcIssuer) },
+                subject: if decoded.oidcSubject.is_empty() { None } else { Some(decoded.oidcSubject) },
+                workflow_ref: if decoded.oidcWorkflowRef.is_empty() { None } else { Some(decoded.oidcWorkflowRef) },
+                repository: if decoded.oidcRepository.is_empty() { None } else { Some(decoded.oidcRepository) },
+                event_name: if decoded.oidcEventName.is_empty() { None } else { Some(decoded.oidcEventName) },
+                subject_kind: IdentityKind::from_u8(decoded.oidcSubjectKind),
+            })
+        };
+
+        // Reconstruct timestamp proof based on type
+        let timestamp_proof = match proof_type {
+            TimestampProofType::None => TimestampProof::None,
+            TimestampProofType::Rfc3161 => {
+                // Extract TSA chain hashes
+                if decoded.tsaChainHashes.len() < 2 {
+                    return Err(format!(
+                        "TSA chain hashes must have at least 2 elements for RFC 3161, got {}",
+                        decoded.tsaChainHashes.len()
+                    ));
+                }
+                let tsa_leaf = decoded.tsaChainHashes[0].0;
+                let tsa_root = decoded.tsaChainHashes[decoded.tsaChainHashes.len() - 1].0;
+                let tsa_intermediates: Vec<[u8; 32]> = decoded.tsaChainHashes[1..decoded.tsaChainHashes.len() - 1]
+                    .iter()
+                    .map(|h| h.0)
+                    .collect();
+
+                TimestampProof::Rfc3161 {
+                    tsa_chain_hashes: CertificateChainHashes {
+                        leaf: tsa_leaf,
+                        intermediates: tsa_intermediates,
+                        root: tsa_root,
+                    },
+                    message_imprint_algorithm: DigestAlgorithm::from_u8(decoded.messageImprintAlgorithm),
+                    message_imprint: decoded.messageImprint.to_vec(),
+                }
+            }
+            TimestampProofType::Rekor => {
+                TimestampProof::Rekor {
+                    log_id: decoded.rekorEntry.logId.0,
+                    log_index: decoded.rekorEntry.logIndex,
+                    entry_index: decoded.rekorEntry.entryIndex,
+                }
+            }
+        };
+
+        // A zero hash means the prover was not given a block
+        let block_anchor = if decoded.anchorBlock.hash.0 == [0u8; 32] {
+            None
+        } else {
+            Some(BlockAnchor {
+                number: decoded.anchorBlock.number,
+                hash: decoded.anchorBlock.hash.0,
+            })
+        };
+
+        // A zero hash starts a chain of custody
+        let previous_result_hash = Some(decoded.previousResultHash.0).filter(|hash| *hash != [0u8; 32]);
+
+        // Like the options, the subject name is committed twice from version 2 on
+        if version == CURRENT_VERSION && decoded.extension.subjectNameHash.0 != subject_name_hash(&decoded.subjectName) {
+            return Err("Committed subject name doesn't match the subject name hash".to_string());
+        }
+
+        // The options are committed twice; a journal whose copies disagree is malformed
+        let verification_options = decoded.verificationOptions.to_vec();
+        if !verification_options.is_empty() && Sha256::digest(&verification_options)[..] != decoded.optionsHash.0 {
+            return Err("Committed verification options don't match the options hash".to_string());
+        }
+
+        // Convert timestamp to DateTime<Utc>
+        let signing_time = DateTime::from_timestamp(timestamp as i64, 0)
+            .ok_or_else(|| format!("Invalid timestamp: {}", timestamp))?;
+
+        Ok(VerificationResult {
+            certificate_hashes: CertificateChainHashes {
+                leaf,
+                intermediates,
+                root,
+            },
+            signing_time,
+            subject_digest: decoded.subjectDigest.to_vec(),
+            subject_digest_algorithm: DigestAlgorithm::from_u8(decoded.subjectDigestAlgorithm),
+            oidc_identity,
+            timestamp_proof,
+            options_hash: decoded.optionsHash.0,
+            subject_name: decoded.subjectName,
+            verifier_version: decoded.verifierVersion,
+            guest_version: decoded.guestVersion,
+            annotations_hash: decoded.annotationsHash.0,
+            flags: decoded.flags,
+            block_anchor,
+            previous_result_hash,
+            verification_options,
+            certificate_timestamps: decoded
+                .certificateTimestamps
+                .iter()
+                .map(|sct| CertificateTimestamp {
+                    log_id: sct.logId.0,
+                    timestamp: sct.timestamp,
+                })
+                .collect(),
+            input_digest: decoded.extension.inputDigest.0,
+            predicate_type: decoded.extension.predicateType,
+            predicate_sha256: decoded.extension.predicateSha256.0,
+            subject_kind: SubjectKind::from_u8(decoded.extension.subjectKind),
+            policy_owner_hash: Some(decoded.extension.policyOwnerHash.0).filter(|hash| *hash != [0u8; 32]),
+        })
+    }
+
+    /// Decode the committed verification options
+    ///
+    /// # Returns
+    ///
+    /// `Ok(None)` if the journal only commits the options hash, the constraints
+    /// the bundle was checked against otherwise
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the committed options are not a valid ABI encoding
+    pub fn decode_verification_options(&self) -> Result<Option<VerificationOptionsEncoded>, String> {
+        if self.verification_options.is_empty() {
+            return Ok(None);
+        }
+        VerificationOptionsEncoded::abi_decode_params(&self.verification_options)
+            .map(Some)
+            .map_err(|e| format!("Failed to ABI decode the verification options: {}", e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_sol_types::SolStruct;
+
+    #[test]
+    fn test_as_slice_from_slice_roundtrip_with_rfc3161() {
+        // Create a test VerificationResult with RFC 3161 timestamp proof
+        let original = VerificationResult {
+            certificate_hashes: CertificateChainHashes {
+                leaf: [1u8; 32],
+                intermediates: vec![[2u8; 32], [3u8; 32]],
+                root: [4u8; 32],
+            },
+            signing_time: DateTime::from_timestamp(1700000000, 0).unwrap(),
+            subject_digest: vec![5u8; 32],
+            subject_digest_algorithm: DigestAlgorithm::Sha256,
+            oidc_identity: Some(OidcIdentity {
+                issuer: Some("https://token.actions.githubusercontent.com".to_string()),
+                subject: Some("repo:owner/repo:ref:refs/heads/main".to_string()),
+                workflow_ref: Some("owner/repo/.github/workflows/ci.yml@refs/heads/main".to_string()),
+                repository: Some("owner/repo".to_string()),
+                event_name: Some("push".to_string()),
+                subject_kind: IdentityKind::Uri,
+            }),
+            timestamp_proof: TimestampProof::Rfc3161 {
+                tsa_chain_hashes: CertificateChainHashes {
+                    leaf: [10u8; 32],
+                    intermediates: vec![[11u8; 32]],
+                    root: [12u8; 32],
+                },
+                message_imprint_algorithm: DigestAlgorithm::Sha256,
+                message_imprint: vec![13u8; 32],
+            },
+            options_hash: [14u8; 32],
+            subject_name: "artifact.tar.gz".to_string(),
+            verifier_version: "0.1.0".to_string(),
+            guest_version: "0.2.0".to_string(),
+            annotations_hash: [15u8; 32],
+            flags: flags::ARCHIVAL,
+            block_anchor: Some(BlockAnchor { number: 21_000_000, hash: [16u8; 32] }),
+            previous_result_hash: Some([17u8; 32]),
+            verification_options: vec![],
+            certificate_timestamps: vec![],
+            input_digest: [0u8; 32],
+            predicate_type: String::new(),
+            predicate_sha256: [0u8; 32],
+            subject_kind: SubjectKind::Unknown,
+            policy_owner_hash: None,
+        };
+
+        let encoded = original.as_slice();
+        let decoded = VerificationResult::from_slice(&encoded).expect("Failed to decode");
+
+        // Verify all fields match
+        assert_eq!(original.certificate_hashes.leaf, decoded.certificate_hashes.leaf);
+        assert_eq!(original.certificate_hashes.intermediates, decoded.certificate_hashes.intermediates);
+        assert_eq!(original.certificate_hashes.root, decoded.certificate_hashes.root);
+        assert_eq!(original.signing_time.timestamp(), decoded.signing_time.timestamp());
+        assert_eq!(original.subject_digest, decoded.subject_digest);
+        assert_eq!(original.subject_digest_algorithm, decoded.subject_digest_algorithm);
+        assert_eq!(original.oidc_identity, decoded.oidc_identity);
+        assert_eq!(original.options_hash, decoded.options_hash);
+        assert_eq!(original.subject_name, decoded.subject_name);
+        assert_eq!(original.verifier_version, decoded.verifier_version);
+        assert_eq!(original.guest_version, decoded.guest_version);
+        assert_eq!(original.annotations_hash, decoded.annotations_hash);
+        assert_eq!(original.flags, decoded.flags);
+        assert!(decoded.is_archival());
+        assert_eq!(original.block_anchor, decoded.block_anchor);
+        assert_eq!(original.previous_result_hash, decoded.previous_result_hash);
+
+        // Verify RFC 3161 timestamp proof
+        match (&original.timestamp_proof, &decoded.timestamp_proof) {
+            (
+                TimestampProof::Rfc3161 { tsa_chain_hashes: orig_tsa, message_imprint_algorithm: orig_alg, message_imprint: orig_imprint },
+                TimestampProof::Rfc3161 { tsa_chain_hashes: dec_tsa, message_imprint_algorithm: dec_alg, message_imprint: dec_imprint },
+            ) => {
+                assert_eq!(orig_tsa.leaf, dec_tsa.leaf);
+                assert_eq!(orig_tsa.intermediates, dec_tsa.intermediates);
+                assert_eq!(orig_tsa.root, dec_tsa.root);
+                assert_eq!(orig_alg, dec_alg);
+                assert_eq!(orig_imprint, dec_imprint);
+            }
+            _ => panic!("Expected RFC 3161 timestamp proof"),
+        }
+    }
+
+    #[test]
+    fn test_as_slice_from_slice_roundtrip_with_rekor() {
+        // Create a test VerificationResult with Rekor timestamp proof
+        let original = VerificationResult {
+            certificate_hashes: CertificateChainHashes {
+                leaf: [1u8; 32],
+                intermediates: vec![],
+                root: [2u8; 32],
+            },
+            signing_time: DateTime::from_timestamp(1700000000, 0).unwrap(),
+            subject_digest: vec![3u8; 32],
+            subject_digest_algorithm: DigestAlgorithm::Sha256,
+            oidc_identity: None,
+            timestamp_proof: TimestampProof::Rekor {
+                log_id: [20u8; 32],
+                log_index: 12345678,
+                entry_index: 87654321,
+            },
+            options_hash: [0u8; 32],
+            subject_name: "artifact.tar.gz".to_string(),
+            verifier_version: "0.1.0".to_string(),
+            guest_version: String::new(),
+            annotations_hash: [0u8; 32],
+            flags: 0,
+            block_anchor: None,
+            previous_result_hash: None,
+            verification_options: vec![],
+            certificate_timestamps: vec![],
+            input_digest: [0u8; 32],
+            predicate_type: String::new(),
+            predicate_sha256: [0u8; 32],
+            subject_kind: SubjectKind::Unknown,
+            policy_owner_hash: None,
+        };
+
+        let encoded = original.as_slice();
+        let decoded = VerificationResult::from_slice(&encoded).expect("Failed to decode");
+
+        // Verify Rekor timestamp proof
+        match (&original.timestamp_proof, &decoded.timestamp_proof) {
+            (
+                TimestampProof::Rekor { log_id: orig_id, log_index: orig_idx, entry_index: orig_entry },
+                TimestampProof::Rekor { log_id: dec_id, log_index: dec_idx, entry_index: dec_entry },
+            ) => {
+                assert_eq!(orig_id, dec_id);
+                assert_eq!(orig_idx, dec_idx);
+                assert_eq!(orig_entry, dec_entry);
+            }
+            _ => panic!("Expected Rekor timestamp proof"),
+        }
+        // Not anchored: zero words decode back to no anchor
+        assert_eq!(decoded.block_anchor, None);
+    }
+
+    #[test]
+    fn test_as_slice_from_slice_roundtrip_no_timestamp_proof() {
+        // Test with no timestamp proof
+        let original = VerificationResult {
+            certificate_hashes: CertificateChainHashes {
+                leaf: [10u8; 32],
+                intermediates: vec![],
+                root: [20u8; 32],
+            },
+            signing_time: DateTime::from_timestamp(1600000000, 0).unwrap(),
+            subject_digest: vec![30u8; 32],
+            subject_digest_algorithm: DigestAlgorithm::Sha384,
+            oidc_identity: None,
+            timestamp_proof: TimestampProof::None,
+            options_hash: [0u8; 32],
+            subject_name: "artifact.tar.gz".to_string(),
+            verifier_version: "0.1.0".to_string(),
+            guest_version: String::new(),
+            annotations_hash: [0u8; 32],
+            flags: 0,
+            block_anchor: None,
+            previous_result_hash: None,
+            verification_options: vec![],
+            certificate_timestamps: vec![],
+            input_digest: [0u8; 32],
+            predicate_type: String::new(),
+            predicate_sha256: [0u8; 32],
+            subject_kind: SubjectKind::Unknown,
+            policy_owner_hash: None,
+        };
+
+        let encoded = original.as_slice();
+        let decoded = VerificationResult::from_slice(&encoded).expect("Failed to decode");
+
+        assert_eq!(original.certificate_hashes.leaf, decoded.certificate_hashes.leaf);
+        assert_eq!(original.certificate_hashes.intermediates.len(), 0);
+        assert_eq!(decoded.certificate_hashes.intermediates.len(), 0);
+        assert_eq!(original.certificate_hashes.root, decoded.certificate_hashes.root);
+        assert_eq!(original.subject_digest_algorithm, decoded.subject_digest_algorithm);
+        assert!(matches!(decoded.timestamp_proof, TimestampProof::None));
+    }
+
+    #[test]
+    fn test_as_slice_from_slice_roundtrip_partial_oidc() {
+        // Test with partial OIDC identity (some fields None)
+        let original = VerificationResult {
+            certificate_hashes: CertificateChainHashes {
+                leaf: [100u8; 32],
+                intermediates: vec![[101u8; 32]],
+                root: [102u8; 32],
+            },
+            signing_time: DateTime::from_timestamp(1650000000, 0).unwrap(),
+            subject_digest: vec![103u8; 32],
+            subject_digest_algorithm: DigestAlgorithm::Sha256,
+            oidc_identity: Some(OidcIdentity {
+                issuer: Some("https://example.com".to_string()),
+                subject: Some("test-subject".to_string()),
+                workflow_ref: None,
+                repository: None,
+                event_name: None,
+                subject_kind: IdentityKind::Email,
+            }),
+            timestamp_proof: TimestampProof::None,
+            options_hash: [0u8; 32],
+            subject_name: "artifact.tar.gz".to_string(),
+            verifier_version: "0.1.0".to_string(),
+            guest_version: String::new(),
+            annotations_hash: [0u8; 32],
+            flags: 0,
+            block_anchor: None,
+            previous_result_hash: None,
+            verification_options: vec![],
+            certificate_timestamps: vec![],
+            input_digest: [0u8; 32],
+            predicate_type: String::new(),
+            predicate_sha256: [0u8; 32],
+            subject_kind: SubjectKind::Unknown,
+            policy_owner_hash: None,
+        };
+
+        let encoded = original.as_slice();
+        let decoded = VerificationResult::from_slice(&encoded).expect("Failed to decode");
+
+        assert_eq!(original.oidc_identity, decoded.oidc_identity);
+    }
+
+    #[test]
+    fn test_from_slice_error_too_short() {
+        // Test with data that's too short (less than 9 bytes)
+        let short_data = vec![1u8, 2, 3, 4];
+        let result = VerificationResult::from_slice(&short_data);
+        assert!(result.is_err());
+        assert!(result.unwrap_err().contains("Data too short"));
+    }
+
+    #[test]
+    fn test_from_slice_error_invalid_abi_encoding() {
+        // Test with valid timestamp + proof type but invalid ABI encoding
+        let mut invalid_data = vec![0u8; 9]; // Valid timestamp (8) + proof type (1)
+        invalid_data.extend_from_slice(&[255u8; 32]); // Invalid ABI data
+        let result = VerificationResult::from_slice(&invalid_data);
+        assert!(result.is_err());
+        assert!(result.unwrap_err().contains("Failed to ABI decode"));
+    }
+
+    #[test]
+    fn test_committed_verification_options() {
+        let options = VerificationOptionsEncoded {
+            expectedDigest: vec![5u8; 32].into(),
+            expectedIssuer: "https://token.actions.githubusercontent.com".to_string(),
+            expectedSubject: String::new(),
+            expectedSubjectName: String::new(),
+            expectedEmail: String::new(),
+            allowedEmailIssuers: vec![],
+            expectedSpiffeTrustDomain: String::new(),
+            expectedSpiffePathPrefix: String::new(),
+            identityPolicyHash: [0u8; 32].into(),
+            expectedRepository: "https://github.com/acme/app".to_string(),
+            expectedWorkflowRef: String::new(),
+            expectedEventName: String::new(),
+            expectedPredicateType: String::new(),
+            maxSigningAge: 3600,
+            verificationTime: 1_700_000_000,
+            profile: "github-strict@1".to_string(),
+            requiredTimestamp: 2,
+            clockSkew: 0,
+            repositoryPolicyHash: [0u8; 32].into(),
+            expectedTsaPolicy: String::new(),
+        };
+        let encoded_options = options.abi_encode_params();
+        let mut result = VerificationResult {
+            certificate_hashes: CertificateChainHashes {
+                leaf: [1u8; 32],
+                intermediates: vec![],
+                root: [2u8; 32],
+            },
+            signing_time: DateTime::from_timestamp(1_700_000_000, 0).unwrap(),
+            subject_digest: vec![5u8; 32],
+            subject_digest_algorithm: DigestAlgorithm::Sha256,
+            oidc_identity: None,
+            timestamp_proof: TimestampProof::None,
+            options_hash: Sha256::digest(&encoded_options).into(),
+            subject_name: "app.tar.gz".to_string(),
+            verifier_version: "0.1.0".to_string(),
+            guest_version: String::new(),
+            annotations_hash: [0u8; 32],
+            flags: 0,
+            block_anchor: None,
+            previous_result_hash: None,
+            verification_options: encoded_options,
+            certificate_timestamps: vec![],
+            input_digest: [0u8; 32],
+            predicate_type: String::new(),
+            predicate_sha256: [0u8; 32],
+            subject_kind: SubjectKind::Unknown,
+            policy_owner_hash: None,
+        };
+
+        let decoded = VerificationResult::from_slice(&result.as_slice()).unwrap();
+        assert_eq!(decoded, result);
+        assert_eq!(decoded.decode_verification_options().unwrap(), Some(options));
+
+        // Options that don't hash to the committed hash are rejected
+        result.options_hash = [0u8; 32];
+        let error = VerificationResult::from_slice(&result.as_slice()).unwrap_err();
+        assert!(error.contains("don't match the options hash"), "{}", error);
+
+        result.verification_options = vec![];
+        let decoded = VerificationResult::from_slice(&result.as_slice()).unwrap();
+        assert_eq!(decoded.decode_verification_options().unwrap(), None);
+    }
+
+    #[test]
+    fn test_as_slice_format() {
+        // Verify the format: first 8 bytes should be timestamp, byte 9 is proof type
+        let original = VerificationResult {
+            certificate_hashes: CertificateChainHashes {
+                leaf: [1u8; 32],
+                intermediates: vec![],
+                root: [2u8; 32],
+            },
+            signing_time: DateTime::from_timestamp(1700000000, 0).unwrap(),
+            subject_digest: vec![3u8; 32],
+            subject_digest_algorithm: DigestAlgorithm::Sha256,
+            oidc_identity: None,
+            timestamp_proof: TimestampProof::Rekor {
+                log_id: [4u8; 32],
+                log_index: 999,
+                entry_index: 1000,
+            },
+            options_hash: [0u8; 32],
+            subject_name: "artifact.tar.gz".to_string(),
+            verifier_version: "0.1.0".to_string(),
+            guest_version: String::new(),
+            annotations_hash: [0u8; 32],
+            flags: 0,
+            block_anchor: None,
+            previous_result_hash: None,
+            verification_options: vec![],
+            certificate_timestamps: vec![],
+            input_digest: [0u8; 32],
+            predicate_type: String::new(),
+            predicate_sha256: [0u8; 32],
+            subject_kind: SubjectKind::Unknown,
+            policy_owner_hash: None,
+        };
+
+        let encoded = original.as_slice();
+
+        // First byte is the format version
+        assert_eq!(encoded[0], CURRENT_VERSION);
+
+        // Next 8 bytes should be the timestamp in big-endian
+        let timestamp_bytes: [u8; 8] = encoded[1..9].try_into().unwrap();
+        let timestamp = u64::from_be_bytes(timestamp_bytes);
+        assert_eq!(timestamp, 1700000000);
+
+        // Byte 10 should be proof type (2 = Rekor)
+        assert_eq!(encoded[9], TimestampProofType::Rekor as u8);
+
+        // Remaining bytes should be ABI-encoded
+        assert!(encoded.len() > 10);
+    }
+
+    #[test]
+    fn test_from_slice_decodes_every_version() {
+        let original = VerificationResult {
+            certificate_hashes: CertificateChainHashes {
+                leaf: [1u8; 32],
+                intermediates: vec![],
+                root: [2u8; 32],
+            },
+            signing_time: DateTime::from_timestamp(1700000000, 0).unwrap(),
+            subject_digest: vec![3u8; 32],
+            subject_digest_algorithm: DigestAlgorithm::Sha256,
+            oidc_identity: None,
+            timestamp_proof: TimestampProof::None,
+            options_hash: [0u8; 32],
+            subject_name: "app.tar.gz".to_string(),
+            verifier_version: "0.1.0".to_string(),
+            guest_version: String::new(),
+            annotations_hash: [0u8; 32],
+            flags: 0,
+            block_anchor: None,
+            previous_result_hash: None,
+            verification_options: vec![],
+            certificate_timestamps: vec![CertificateTimestamp {
+                log_id: [4u8; 32],
+                timestamp: 1700000000123,
+            }],
+            input_digest: [5u8; 32],
+            predicate_type: "https://slsa.dev/provenance/v1".to_string(),
+            predicate_sha256: [6u8; 32],
+            subject_kind: SubjectKind::Artifact,
+            policy_owner_hash: Some([7u8; 32]),
+        };
+        let encoded = original.as_slice();
+        assert_eq!(journal_version(&encoded), Ok(CURRENT_VERSION));
+        assert_eq!(VerificationResult::from_slice(&encoded).unwrap(), original);
+
+        // Version 1 encodes the same result without anything after the Rekor entry
+        let v1_abi = VerificationResultEncodedV1 {
+            certificateHashes: vec![[1u8; 32].into(), [2u8; 32].into()],
+            subjectDigest: vec![3u8; 32].into(),
+            subjectDigestAlgorithm: DigestAlgorithm::Sha256 as u8,
+            oidcIssuer: String::new(),
+            oidcSubject: String::new(),
+            oidcWorkflowRef: String::new(),
+            oidcRepository: String::new(),
+            oidcEventName: String::new(),
+            tsaChainHashes: vec![],
+            messageImprintAlgorithm: 0,
+            messageImprint: vec![].into(),
+            rekorLogId: [0u8; 32].into(),
+            rekorLogIndex: 0,
+            rekorEntryIndex: 0,
+        }
+        .abi_encode();
+        let mut legacy = encoded[1..10].to_vec();
+        legacy.extend_from_slice(&v1_abi);
+        assert_eq!(journal_version(&legacy), Ok(LEGACY_VERSION));
+        let expected = VerificationResult {
+            subject_name: String::new(),
+            verifier_version: String::new(),
+            certificate_timestamps: vec![],
+            input_digest: [0u8; 32],
+            predicate_type: String::new(),
+            predicate_sha256: [0u8; 32],
+            subject_kind: SubjectKind::Unknown,
+            policy_owner_hash: None,
+            ..original.clone()
+        };
+        assert_eq!(VerificationResult::from_slice(&legacy).unwrap(), expected);
+
+        let mut unknown = encoded.clone();
+        unknown[0] = 3;
+        let error = VerificationResult::from_slice(&unknown).unwrap_err();
+        assert!(error.contains("Unsupported journal version 3"), "{}", error);
+    }
+
+    #[test]
+    fn test_from_slice_decodes_released_legacy_journal() {
+        let fixture = include_str!("../../../contracts/test/fixtures/boundless-public.json");
+        let start = fixture.find("\"journal\": \"0x").unwrap() + 14;
+        let hex = &fixture[start..start + fixture[start..].find('"').unwrap()];
+        let journal: Vec<u8> = (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+            .collect();
+        assert_eq!(journal_version(&journal), Ok(LEGACY_VERSION));
+
+        let result = VerificationResult::from_slice(&journal).unwrap();
+        assert_eq!(result.signing_time.timestamp(), 0x691c2eeb);
+        assert!(matches!(result.timestamp_proof, TimestampProof::Rekor { .. }));
+        assert_eq!(result.subject_digest_algorithm, DigestAlgorithm::Sha256);
+        assert_eq!(result.options_hash, [0u8; 32]);
+        assert!(result.verification_options.is_empty());
+    }
+
+    #[test]
+    fn test_subject_name_hash_is_checked() {
+        let mut encoded = VerificationResultEncoded {
+            certificateHashes: vec![[1u8; 32].into(), [2u8; 32].into()],
+            subjectDigest: vec![3u8; 32].into(),
+            subjectDigestAlgorithm: DigestAlgorithm::Sha256 as u8,
+            oidcIssuer: String::new(),
+            oidcSubject: String::new(),
+            oidcWorkflowRef: String::new(),
+            oidcRepository: String::new(),
+            oidcEventName: String::new(),
+            tsaChainHashes: vec![],
+            messageImprintAlgorithm: 0,
+            messageImprint: vec![].into(),
+            rekorEntry: RekorEntryEncoded {
+                logId: [0u8; 32].into(),
+                logIndex: 0,
+                entryIndex: 0,
+            },
+            optionsHash: [0u8; 32].into(),
+            subjectName: "pkg:cargo/app@1.0.0".to_string(),
+            verifierVersion: "0.1.0".to_string(),
+            guestVersion: String::new(),
+            annotationsHash: [0u8; 32].into(),
+            oidcSubjectKind: 0,
+            flags: 0,
+            anchorBlock: BlockAnchorEncoded {
+                number: 0,
+                hash: [0u8; 32].into(),
+            },
+            previousResultHash: [0u8; 32].into(),
+            verificationOptions: vec![].into(),
+            certificateTimestamps: vec![],
+            extension: ResultExtensionEncoded {
+                inputDigest: [0u8; 32].into(),
+                predicateType: String::new(),
+                predicateSha256: [0u8; 32].into(),
+                subjectKind: SubjectKind::Purl as u8,
+                subjectNameHash: subject_name_hash("pkg:cargo/app@1.0.0").into(),
+                policyOwnerHash: [0u8; 32].into(),
+            },
+        };
+        let journal = |encoded: &VerificationResultEncoded| {
+            let mut journal = vec![CURRENT_VERSION, 0, 0, 0, 0, 101, 83, 241, 0, 0];
+            journal.extend_from_slice(&encoded.abi_encode());
+            journal
+        };
+        let decoded = VerificationResult::from_slice(&journal(&encoded)).unwrap();
+        assert_eq!(decoded.subject_kind, SubjectKind::Purl);
+
+        encoded.subjectName = "pkg:cargo/app@1.0.1".to_string();
+        let error = VerificationResult::from_slice(&journal(&encoded)).unwrap_err();
+        assert!(error.contains("subject name hash"), "{}", error);
+    }
+
+    #[test]
+    fn test_certificate_hashes_ordering() {
+        // Verify that certificate hashes are encoded in the correct order
+        let original = VerificationResult {
+            certificate_hashes: CertificateChainHashes {
+                leaf: [11u8; 32],
+                intermediates: vec![[22u8; 32], [33u8; 32], [44u8; 32]],
+                root: [55u8; 32],
+            },
+            signing_time: DateTime::from_timestamp(1700000000, 0).unwrap(),
+            subject_digest: vec![66u8; 32],
+            subject_digest_algorithm: DigestAlgorithm::Sha256,
+            oidc_identity: None,
+            timestamp_proof: TimestampProof::None,
+            options_hash: [0u8; 32],
+            subject_name: "artifact.tar.gz".to_string(),
+            verifier_version: "0.1.0".to_string(),
+            guest_version: String::new(),
+            annotations_hash: [0u8; 32],
+            flags: 0,
+            block_anchor: None,
+            previous_result_hash: None,
+            verification_options: vec![],
+            certificate_timestamps: vec![],
+            input_digest: [0u8; 32],
+            predicate_type: String::new(),
+            predicate_sha256: [0u8; 32],
+            subject_kind: SubjectKind::Unknown,
+            policy_owner_hash: None,
+        };
+
+        let encoded = original.as_slice();
+        let decoded = VerificationResult::from_slice(&encoded).expect("Failed to decode");
+
+        // Verify order: leaf, intermediates (in order), root
+        assert_eq!(decoded.certificate_hashes.leaf, [11u8; 32]);
+        assert_eq!(decoded.certificate_hashes.intermediates, vec![[22u8; 32], [33u8; 32], [44u8; 32]]);
+        assert_eq!(decoded.certificate_hashes.root, [55u8; 32]);
+    }
+
+    #[test]
+    fn test_digest_algorithm_roundtrip() {
+        // Test all digest algorithm values
+        assert_eq!(DigestAlgorithm::from_u8(0), DigestAlgorithm::Unknown);
+        assert_eq!(DigestAlgorithm::from_u8(1), DigestAlgorithm::Sha256);
+        assert_eq!(DigestAlgorithm::from_u8(2), DigestAlgorithm::Sha384);
+        assert_eq!(DigestAlgorithm::from_u8(3), DigestAlgorithm::Sha1);
+        assert_eq!(DigestAlgorithm::from_u8(4), DigestAlgorithm::Sha512);
+        assert_eq!(DigestAlgorithm::from_u8(255), DigestAlgorithm::Unknown);
+    }
+
+    #[test]
+    fn test_encoded_struct_types_are_frozen() {
+        assert_eq!(
+            VerificationResultEncoded::eip712_encode_type(),
+            VERIFICATION_RESULT_ENCODED_TYPE
+        );
+        assert_eq!(
+            VerificationOptionsEncoded::eip712_encode_type(),
+            VERIFICATION_OPTIONS_ENCODED_TYPE
+        );
+    }
+
+    #[test]
+    fn test_abi_head_layout() {
+        // Give every static field a distinct value and read it back from its head slot,
+        // so swapping two fields of the same type is caught as well
+        let encoded = VerificationResultEncoded {
+            certificateHashes: vec![],
+            subjectDigest: vec![].into(),
+            subjectDigestAlgorithm: 1,
+            oidcIssuer: String::new(),
+            oidcSubject: String::new(),
+            oidcWorkflowRef: String::new(),
+            oidcRepository: String::new(),
+            oidcEventName: String::new(),
+            tsaChainHashes: vec![],
+            messageImprintAlgorithm: 2,
+            messageImprint: vec![].into(),
+            rekorEntry: RekorEntryEncoded {
+                logId: [3u8; 32].into(),
+                logIndex: 4,
+                entryIndex: 5,
+            },
+            optionsHash: [6u8; 32].into(),
+            subjectName: String::new(),
+            verifierVersion: String::new(),
+            guestVersion: String::new(),
+            annotationsHash: [7u8; 32].into(),
+            oidcSubjectKind: 8,
+            flags: 9,
+            anchorBlock: BlockAnchorEncoded {
+                number: 10,
+                hash: [11u8; 32].into(),
+            },
+            previousResultHash: [12u8; 32].into(),
+            verificationOptions: vec![].into(),
+            certificateTimestamps: vec![],
+            extension: ResultExtensionEncoded {
+                inputDigest: [13u8; 32].into(),
+                predicateType: String::new(),
+                predicateSha256: [14u8; 32].into(),
+                subjectKind: 15,
+                subjectNameHash: [16u8; 32].into(),
+                policyOwnerHash: [17u8; 32].into(),
+            },
+        }
+        .abi_encode();
+
+        // Skip the offset of the (dynamic) tuple itself
+        let head = &encoded[32..];
+        let word = |slot: usize| &head[slot * 32..(slot + 1) * 32];
+        let uint = |value: u8| {
+            let mut expected = [0u8; 32];
+            expected[31] = value;
+            expected
+        };
+
+        assert_eq!(word(2), uint(1), "subjectDigestAlgorithm");
+        assert_eq!(word(9), uint(2), "messageImprintAlgorithm");
+        assert_eq!(word(11), [3u8; 32], "rekorEntry.logId");
+        assert_eq!(word(12), uint(4), "rekorEntry.logIndex");
+        assert_eq!(word(13), uint(5), "rekorEntry.entryIndex");
+        assert_eq!(word(14), [6u8; 32], "optionsHash");
+        assert_eq!(word(18), [7u8; 32], "annotationsHash");
+        assert_eq!(word(19), uint(8), "oidcSubjectKind");
+        assert_eq!(word(20), uint(9), "flags");
+        assert_eq!(word(21), uint(10), "anchorBlock.number");
+        assert_eq!(word(22), [11u8; 32], "anchorBlock.hash");
+        assert_eq!(word(23), [12u8; 32], "previousResultHash");
+
+        // The extension is dynamic: its head slot holds the offset of its fields
+        let extension = u64::from_be_bytes(word(26)[24..].try_into().unwrap()) as usize / 32;
+        assert_eq!(word(extension), [13u8; 32], "extension.inputDigest");
+        assert_eq!(word(extension + 2), [14u8; 32], "extension.predicateSha256");
+        assert_eq!(word(extension + 3), uint(15), "extension.subjectKind");
+        assert_eq!(word(extension + 4), [16u8; 32], "extension.subjectNameHash");
+        assert_eq!(word(extension + 5), [17u8; 32], "extension.policyOwnerHash");
+    }
+
+    #[test]
+    fn test_annotations_hash_is_canonical() {
+        assert_eq!(annotations_hash(&BTreeMap::new()), [0u8; 32]);
+
+        let mut forward = BTreeMap::new();
+        forward.insert("build".to_string(), "1234".to_string());
+        forward.insert("env".to_string(), "prod".to_string());
+        let mut reverse = BTreeMap::new();
+        reverse.insert("env".to_string(), "prod".to_string());
+        reverse.insert("build".to_string(), "1234".to_string());
+        assert_eq!(annotations_hash(&forward), annotations_hash(&reverse));
+
+        // Moving bytes between key and value changes the commitment
+        let mut shifted = BTreeMap::new();
+        shifted.insert("build1".to_string(), "234".to_string());
+        shifted.insert("env".to_string(), "prod".to_string());
+        assert_ne!(annotations_hash(&forward), annotations_hash(&shifted));
+    }
+
+    #[test]
+    fn test_subject_kind_roundtrip() {
+        for kind in [SubjectKind::Unknown, SubjectKind::Artifact, SubjectKind::Purl, SubjectKind::Git] {
+            assert_eq!(SubjectKind::from_u8(kind as u8), kind);
+        }
+        assert_eq!(SubjectKind::from_u8(255), SubjectKind::Unknown);
+    }
+
+    #[test]
+    fn test_identity_kind_roundtrip() {
+        assert_eq!(IdentityKind::from_u8(0), IdentityKind::Unknown);
+        assert_eq!(IdentityKind::from_u8(1), IdentityKind::Uri);
+        assert_eq!(IdentityKind::from_u8(2), IdentityKind::Email);
+        assert_eq!(IdentityKind::from_u8(3), IdentityKind::Spiffe);
+        assert_eq!(IdentityKind::from_u8(255), IdentityKind::Unknown);
+    }
+
+    #[test]
+    fn test_timestamp_proof_type_roundtrip() {
+        // Test all timestamp proof type values
+        assert_eq!(TimestampProofType::from_u8(0), TimestampProofType::None);
+        assert_eq!(TimestampProofType::from_u8(1), TimestampProofType::Rfc3161);
+        assert_eq!(TimestampProofType::from_u8(2), TimestampProofType::Rekor);
+        assert_eq!(TimestampProofType::from_u8(255), TimestampProofType::None);
+    }
+}
@@ -0,0 +1,89 @@
+//! Replay the mutated-bundle corpus of a failing `corpus_test` run
+//!
+//! Regenerates the corpus of the given seed, verifies every entry and writes the
+//! entries that were accepted or panicked, or the one picked with `--index`, to the
+//! output directory.
+
+#[path = "../tests/corpus/mod.rs"]
+mod corpus;
+
+use corpus::{load_base_bundles, mutated_bundle, DEFAULT_SIZE};
+use std::env;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::path::PathBuf;
+
+fn usage(program: &str) -> ! {
+    eprintln!(
+        "Usage: {} --seed <seed> [--size <entries>] [--index <entry>] [--out <dir>]",
+        program
+    );
+    eprintln!();
+    eprintln!("Example:");
+    eprintln!("  {} --seed 1234567890 --out corpus", program);
+    std::process::exit(1);
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+
+    let mut seed = None;
+    let mut size = DEFAULT_SIZE;
+    let mut index = None;
+    let mut out = PathBuf::from("corpus");
+
+    let mut rest = args[1..].iter();
+    while let Some(arg) = rest.next() {
+        let Some(value) = rest.next() else { usage(&args[0]) };
+        match arg.as_str() {
+            "--seed" => seed = Some(value.parse::<u64>().unwrap_or_else(|_| usage(&args[0]))),
+            "--size" => size = value.parse().unwrap_or_else(|_| usage(&args[0])),
+            "--index" => index = Some(value.parse::<usize>().unwrap_or_else(|_| usage(&args[0]))),
+            "--out" => out = PathBuf::from(value),
+            _ => usage(&args[0]),
+        }
+    }
+    let Some(seed) = seed else { usage(&args[0]) };
+
+    let bases = load_base_bundles();
+    let indices: Vec<usize> = match index {
+        Some(index) => vec![index],
+        None => (0..size).collect(),
+    };
+
+    std::fs::create_dir_all(&out).expect("Failed to create output directory");
+    println!("Replaying corpus seed {} ({} entries)", seed, indices.len());
+    println!();
+
+    let mut offending = 0;
+    for i in indices {
+        let entry = mutated_bundle(&bases, seed, i);
+        let base = &bases[entry.base];
+        let (outcome, offends) = match catch_unwind(AssertUnwindSafe(|| base.verify(&entry.bundle_json))) {
+            Ok(Ok(_)) => ("ACCEPTED".to_string(), true),
+            Ok(Err(e)) => (format!("rejected (error {}): {}", e.code(), e), false),
+            Err(_) => ("PANICKED".to_string(), true),
+        };
+        println!(
+            "[{:04}] {} of {}, {}: {}",
+            entry.index,
+            entry.mutation.name(),
+            base.name,
+            entry.detail,
+            outcome
+        );
+
+        if offends || index.is_some() {
+            let path = out.join(entry.file_name(seed));
+            std::fs::write(&path, &entry.bundle_json).expect("Failed to write bundle");
+            println!("       written to {}", path.display());
+        }
+        offending += usize::from(offends);
+    }
+
+    println!();
+    if offending > 0 {
+        println!("✗ {} mutated bundle(s) were not rejected", offending);
+        std::process::exit(1);
+    }
+    println!("✓ All mutated bundles were rejected");
+}
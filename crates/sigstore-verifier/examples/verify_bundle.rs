@@ -4,6 +4,9 @@
 // For an alternative approach using local trust root bundles (JSONL format),
 // see the verify_bundle_with_trusted_root example.
 
+use sigstore_verifier::fetcher::http::default_client;
+use sigstore_verifier::fetcher::jsonl::parser::rekor_key_from_pem;
+use sigstore_verifier::fetcher::remote::fetch_url;
 use sigstore_verifier::fetcher::trust_bundle::{
     fetch_fulcio_trust_bundle, fetch_trust_bundle_from_url,
 };
@@ -37,18 +40,16 @@ fn main() {
 
     let verifier = AttestationVerifier::new();
 
-    let options = VerificationOptions {
-        expected_digest: None,
-        expected_issuer: None,
-        expected_subject: None,
-    };
+    let options = VerificationOptions::default();
 
-    let fulcio_issuer_chain =
-        fetch_fulcio_trust_bundle(&fulcio_instance).expect("Failed to fetch Fulcio trust bundle");
+    let client = default_client().expect("Failed to create HTTP client");
+    let fulcio_issuer_chain = fetch_fulcio_trust_bundle(&*client, &fulcio_instance)
+        .expect("Failed to fetch Fulcio trust bundle");
 
     let tsa_trust_bundle = match fulcio_instance {
         FulcioInstance::GitHub => Some(
             fetch_trust_bundle_from_url(
+                &*client,
                 "https://timestamp.githubapp.com/api/v1/timestamp/certchain",
             )
             .expect("Failed to fetch TSA trust bundle"),
@@ -56,6 +57,17 @@ fn main() {
         _ => None,
     };
 
+    // Public-good bundles are timestamped by Rekor, whose entries only verify with the log's key
+    let rekor_keys = match fulcio_instance {
+        FulcioInstance::PublicGood => {
+            let pem_data = fetch_url(&*client, "https://rekor.sigstore.dev/api/v1/log/publicKey")
+                .expect("Failed to fetch Rekor public key");
+            vec![rekor_key_from_pem(&String::from_utf8_lossy(&pem_data)).expect("Invalid Rekor public key")]
+        }
+        _ => Vec::new(),
+    };
+    let options = VerificationOptions { rekor_keys, ..options };
+
     match verifier.verify_bundle(
         &bundle_path,
         options,
@@ -1,5 +1,5 @@
 use sigstore_verifier::fetcher::jsonl::parser::{
-    load_trusted_root_from_jsonl, select_certificate_authority, select_timestamp_authority,
+    load_trusted_root_from_jsonl, select_certificate_authority, select_rekor_keys, select_timestamp_authority,
 };
 use sigstore_verifier::parser::bundle::{extract_bundle_timestamp, parse_bundle_from_path};
 use sigstore_verifier::types::certificate::FulcioInstance;
@@ -76,6 +76,9 @@ fn main() {
     let tsa_chain = select_timestamp_authority(&trust_roots, &fulcio_instance, timestamp)
         .expect("Failed to select timestamp authority");
 
+    // Rekor entries are only trusted if signed by a log of the trusted root
+    let rekor_keys = select_rekor_keys(&trust_roots, timestamp).expect("Failed to select Rekor keys");
+
     println!("Selected certificate authority and timestamp authority from trusted root");
     println!();
 
@@ -83,9 +86,8 @@ fn main() {
     let verifier = AttestationVerifier::new();
 
     let options = VerificationOptions {
-        expected_digest: None,
-        expected_issuer: None,
-        expected_subject: None,
+        rekor_keys,
+        ..Default::default()
     };
 
     match verifier.verify_bundle(&bundle_path, options, &fulcio_chain, Some(&tsa_chain)) {
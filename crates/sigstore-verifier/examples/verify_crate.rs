@@ -0,0 +1,100 @@
+// This example verifies the provenance of a crate published on crates.io.
+//
+// It downloads the .crate archive, hashes it, looks up the attestations for
+// that digest and verifies them against a local trusted root, selecting the
+// subject by package URL (pkg:cargo/<name>@<version>).
+
+use sigstore_verifier::crypto::hash::sha256;
+use sigstore_verifier::error::VerificationError;
+use sigstore_verifier::fetcher::jsonl::parser::{
+    load_trusted_root_from_jsonl, select_certificate_authority, select_rekor_keys, select_timestamp_authority,
+};
+use sigstore_verifier::fetcher::http::default_client;
+use sigstore_verifier::fetcher::remote::{fetch_crate, fetch_crate_attestation_bundles};
+use sigstore_verifier::parser::bundle::{extract_bundle_timestamp, parse_bundle_from_bytes};
+use sigstore_verifier::types::certificate::FulcioInstance;
+use sigstore_verifier::types::purl::CargoPurl;
+use sigstore_verifier::types::result::VerificationOptions;
+use sigstore_verifier::AttestationVerifier;
+use std::env;
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+
+    if args.len() < 3 {
+        eprintln!("Usage: {} <pkg:cargo/name@version> <path-to-trusted_root.jsonl>", args[0]);
+        eprintln!();
+        eprintln!("Example:");
+        eprintln!("  {} pkg:cargo/my-crate@0.1.0 samples/trusted_root.jsonl", args[0]);
+        std::process::exit(1);
+    }
+
+    let purl = CargoPurl::parse(&args[1]).unwrap_or_else(|| {
+        eprintln!("Error: Not a cargo package URL: {}", args[1]);
+        std::process::exit(1);
+    });
+
+    let trusted_root_content =
+        std::fs::read_to_string(&args[2]).expect("Failed to read trusted root file");
+    let trust_roots = load_trusted_root_from_jsonl(&trusted_root_content)
+        .expect("Failed to parse trusted root JSONL");
+
+    // Download and hash the crate archive
+    println!("Downloading {}", purl.crate_file_name());
+    let client = default_client().expect("Failed to create HTTP client");
+    let archive = fetch_crate(&*client, &purl).expect("Failed to download crate");
+    let digest = sha256(&archive);
+    println!("Crate digest: sha256:{}", hex::encode(digest));
+
+    // Locate the attestations for the archive digest
+    let bundles = fetch_crate_attestation_bundles(&*client, &purl, &digest)
+        .expect("Failed to fetch attestations");
+    println!("Found {} attestation(s)", bundles.len());
+    println!();
+
+    let verifier = AttestationVerifier::new();
+
+    for (i, bundle_json) in bundles.iter().enumerate() {
+        let options = VerificationOptions {
+            expected_digest: Some(digest.to_vec()),
+            expected_subject_name: Some(purl.to_string()),
+            ..Default::default()
+        };
+
+        let outcome = (|| {
+            let bundle = parse_bundle_from_bytes(bundle_json)?;
+            let timestamp = extract_bundle_timestamp(&bundle)?;
+            let fulcio_instance =
+                FulcioInstance::from_bundle_json(&String::from_utf8_lossy(bundle_json))
+                    .map_err(VerificationError::InvalidBundleFormat)?;
+            let fulcio_chain =
+                select_certificate_authority(&trust_roots, &fulcio_instance, timestamp)?;
+            let tsa_chain = select_timestamp_authority(&trust_roots, &fulcio_instance, timestamp)?;
+            let rekor_keys = select_rekor_keys(&trust_roots, timestamp)?;
+            let options = VerificationOptions { rekor_keys, ..options };
+            verifier.verify_bundle_bytes(bundle_json, options, &fulcio_chain, Some(&tsa_chain))
+        })();
+
+        match outcome {
+            Ok(result) => {
+                println!("✓ Verification SUCCESS (attestation {})\n", i);
+                println!("Subject:        {}", result.subject_name);
+                println!("Signing Time:   {}", result.signing_time.to_rfc3339());
+
+                if let Some(ref identity) = result.oidc_identity {
+                    if let Some(ref issuer) = identity.issuer {
+                        println!("OIDC Issuer:    {}", issuer);
+                    }
+                    if let Some(ref subject) = identity.subject {
+                        println!("OIDC Subject:   {}", subject);
+                    }
+                }
+                return;
+            }
+            Err(e) => eprintln!("✗ Attestation {}: {}", i, e),
+        }
+    }
+
+    eprintln!("\n✗ Verification FAILED: no attestation verified for {}", purl);
+    std::process::exit(1);
+}
@@ -0,0 +1,348 @@
+//! Signature verification backend
+//!
+//! Keys and signatures are always parsed with the pure-Rust RustCrypto crates,
+//! so malformed input fails with the same `SignatureError` regardless of the
+//! backend. Only the final verification step is dispatched:
+//!
+//! - default: `p256` / `p384` / `rsa`, which compile for every zkVM guest
+//! - `crypto-backend` feature on non-zkVM targets: `ring`, which is
+//!   considerably faster for services verifying many bundles on the host
+//!
+//! secp256k1 (`secp256k1` feature) is not supported by `ring` and always uses
+//! the pure-Rust `k256` crate. Ed25519 uses `ed25519-dalek` or `ring`; both
+//! reject non-canonical `S` and check the cofactorless equation. RSA keys
+//! below 2048 bits and RSASSA-PSS salts not as long as the digest are not
+//! supported by `ring` either and use the `rsa` crate.
+//!
+//! Both backends return `SignatureError::InvalidSignature` when a well-formed
+//! signature does not verify.
+
+use alloc::string::ToString;
+use ed25519_dalek::{Signature as Ed25519Signature, VerifyingKey as Ed25519VerifyingKey};
+use p256::ecdsa::{Signature as P256Signature, VerifyingKey as P256VerifyingKey};
+use p384::ecdsa::{Signature as P384Signature, VerifyingKey as P384VerifyingKey};
+use rsa::RsaPublicKey;
+
+use crate::error::SignatureError;
+
+/// Message digest used by an RSA signature or a prehashed message signature
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DigestAlgorithm {
+    Sha256,
+    Sha384,
+    Sha512,
+}
+
+/// Name of the active backend, for diagnostics
+pub fn backend_name() -> &'static str {
+    if cfg!(all(feature = "crypto-backend", not(target_os = "zkvm"))) {
+        "ring"
+    } else {
+        "rustcrypto"
+    }
+}
+
+/// Verify an ECDSA P-256 / SHA-256 signature over `message`
+pub fn verify_p256(
+    key: &P256VerifyingKey,
+    message: &[u8],
+    signature: &P256Signature,
+) -> Result<(), SignatureError> {
+    imp::verify_p256(key, message, signature)
+}
+
+/// Verify an ECDSA P-384 / SHA-384 signature over `message`
+pub fn verify_p384(
+    key: &P384VerifyingKey,
+    message: &[u8],
+    signature: &P384Signature,
+) -> Result<(), SignatureError> {
+    imp::verify_p384(key, message, signature)
+}
+
+/// Verify an ECDSA secp256k1 / SHA-256 signature over `message`
+///
+/// `k256` only accepts low-S signatures, as Bitcoin and Ethereum require.
+/// X.509 and DSSE signers are free to produce either form, so the signature
+/// is normalized first.
+#[cfg(feature = "secp256k1")]
+pub fn verify_k256(
+    key: &k256::ecdsa::VerifyingKey,
+    message: &[u8],
+    signature: &k256::ecdsa::Signature,
+) -> Result<(), SignatureError> {
+    use ecdsa::signature::Verifier;
+
+    let signature = signature.normalize_s().unwrap_or(*signature);
+    key.verify(message, &signature)
+        .map_err(|_| SignatureError::InvalidSignature)
+}
+
+/// Verify an Ed25519 signature over `message`
+pub fn verify_ed25519(
+    key: &Ed25519VerifyingKey,
+    message: &[u8],
+    signature: &Ed25519Signature,
+) -> Result<(), SignatureError> {
+    imp::verify_ed25519(key, message, signature)
+}
+
+/// Verify an RSA PKCS#1 v1.5 signature over `message`
+pub fn verify_rsa_pkcs1v15(
+    key: &RsaPublicKey,
+    hash: DigestAlgorithm,
+    message: &[u8],
+    signature: &[u8],
+) -> Result<(), SignatureError> {
+    imp::verify_rsa_pkcs1v15(key, hash, message, signature)
+}
+
+/// Verify an RSASSA-PSS signature over `message`, with MGF1 over the same
+/// digest and a salt of `salt_len` bytes
+pub fn verify_rsa_pss(
+    key: &RsaPublicKey,
+    hash: DigestAlgorithm,
+    salt_len: usize,
+    message: &[u8],
+    signature: &[u8],
+) -> Result<(), SignatureError> {
+    imp::verify_rsa_pss(key, hash, salt_len, message, signature)
+}
+
+impl DigestAlgorithm {
+    /// Digest size in bytes
+    pub fn output_size(self) -> usize {
+        match self {
+            DigestAlgorithm::Sha256 => 32,
+            DigestAlgorithm::Sha384 => 48,
+            DigestAlgorithm::Sha512 => 64,
+        }
+    }
+}
+
+/// RSASSA-PSS verification with the `rsa` crate
+fn verify_rsa_pss_rustcrypto(
+    key: &RsaPublicKey,
+    hash: DigestAlgorithm,
+    salt_len: usize,
+    message: &[u8],
+    signature: &rsa::pss::Signature,
+) -> Result<(), SignatureError> {
+    use rsa::pss::VerifyingKey;
+    use rsa::signature::Verifier;
+    use sha2::{Sha256, Sha384, Sha512};
+
+    match hash {
+        DigestAlgorithm::Sha256 => {
+            VerifyingKey::<Sha256>::new_with_salt_len(key.clone(), salt_len).verify(message, signature)
+        }
+        DigestAlgorithm::Sha384 => {
+            VerifyingKey::<Sha384>::new_with_salt_len(key.clone(), salt_len).verify(message, signature)
+        }
+        DigestAlgorithm::Sha512 => {
+            VerifyingKey::<Sha512>::new_with_salt_len(key.clone(), salt_len).verify(message, signature)
+        }
+    }
+    .map_err(|_| SignatureError::InvalidSignature)
+}
+
+#[cfg(not(all(feature = "crypto-backend", not(target_os = "zkvm"))))]
+mod imp {
+    use super::*;
+    use ecdsa::signature::Verifier;
+    use sha2::{Sha256, Sha384, Sha512};
+
+    pub fn verify_p256(
+        key: &P256VerifyingKey,
+        message: &[u8],
+        signature: &P256Signature,
+    ) -> Result<(), SignatureError> {
+        key.verify(message, signature)
+            .map_err(|_| SignatureError::InvalidSignature)
+    }
+
+    pub fn verify_p384(
+        key: &P384VerifyingKey,
+        message: &[u8],
+        signature: &P384Signature,
+    ) -> Result<(), SignatureError> {
+        key.verify(message, signature)
+            .map_err(|_| SignatureError::InvalidSignature)
+    }
+
+    pub fn verify_ed25519(
+        key: &Ed25519VerifyingKey,
+        message: &[u8],
+        signature: &Ed25519Signature,
+    ) -> Result<(), SignatureError> {
+        key.verify(message, signature)
+            .map_err(|_| SignatureError::InvalidSignature)
+    }
+
+    pub fn verify_rsa_pkcs1v15(
+        key: &RsaPublicKey,
+        hash: DigestAlgorithm,
+        message: &[u8],
+        signature: &[u8],
+    ) -> Result<(), SignatureError> {
+        use rsa::pkcs1v15::{Signature, VerifyingKey};
+
+        let sig = Signature::try_from(signature)
+            .map_err(|e| SignatureError::InvalidFormat(e.to_string()))?;
+        match hash {
+            DigestAlgorithm::Sha256 => VerifyingKey::<Sha256>::new(key.clone()).verify(message, &sig),
+            DigestAlgorithm::Sha384 => VerifyingKey::<Sha384>::new(key.clone()).verify(message, &sig),
+            DigestAlgorithm::Sha512 => VerifyingKey::<Sha512>::new(key.clone()).verify(message, &sig),
+        }
+        .map_err(|_| SignatureError::InvalidSignature)
+    }
+
+    pub fn verify_rsa_pss(
+        key: &RsaPublicKey,
+        hash: DigestAlgorithm,
+        salt_len: usize,
+        message: &[u8],
+        signature: &[u8],
+    ) -> Result<(), SignatureError> {
+        let sig = rsa::pss::Signature::try_from(signature)
+            .map_err(|e| SignatureError::InvalidFormat(e.to_string()))?;
+        verify_rsa_pss_rustcrypto(key, hash, salt_len, message, &sig)
+    }
+}
+
+#[cfg(all(feature = "crypto-backend", not(target_os = "zkvm")))]
+mod imp {
+    use super::*;
+    use ring::signature::{
+        RsaPublicKeyComponents, UnparsedPublicKey, ECDSA_P256_SHA256_FIXED,
+        ECDSA_P384_SHA384_FIXED, ED25519, RSA_PKCS1_2048_8192_SHA256, RSA_PKCS1_2048_8192_SHA384,
+        RSA_PKCS1_2048_8192_SHA512, RSA_PSS_2048_8192_SHA256, RSA_PSS_2048_8192_SHA384,
+        RSA_PSS_2048_8192_SHA512,
+    };
+    use rsa::traits::PublicKeyParts;
+
+    /// Smallest modulus ring accepts; shorter keys fall back to the `rsa` crate
+    /// so both backends accept the same keys
+    const RING_MIN_RSA_BITS: usize = 2048;
+
+    pub fn verify_p256(
+        key: &P256VerifyingKey,
+        message: &[u8],
+        signature: &P256Signature,
+    ) -> Result<(), SignatureError> {
+        let point = key.to_encoded_point(false);
+        UnparsedPublicKey::new(&ECDSA_P256_SHA256_FIXED, point.as_bytes())
+            .verify(message, &signature.to_bytes())
+            .map_err(|_| SignatureError::InvalidSignature)
+    }
+
+    pub fn verify_p384(
+        key: &P384VerifyingKey,
+        message: &[u8],
+        signature: &P384Signature,
+    ) -> Result<(), SignatureError> {
+        let point = key.to_encoded_point(false);
+        UnparsedPublicKey::new(&ECDSA_P384_SHA384_FIXED, point.as_bytes())
+            .verify(message, &signature.to_bytes())
+            .map_err(|_| SignatureError::InvalidSignature)
+    }
+
+    pub fn verify_ed25519(
+        key: &Ed25519VerifyingKey,
+        message: &[u8],
+        signature: &Ed25519Signature,
+    ) -> Result<(), SignatureError> {
+        UnparsedPublicKey::new(&ED25519, key.as_bytes())
+            .verify(message, &signature.to_bytes())
+            .map_err(|_| SignatureError::InvalidSignature)
+    }
+
+    pub fn verify_rsa_pkcs1v15(
+        key: &RsaPublicKey,
+        hash: DigestAlgorithm,
+        message: &[u8],
+        signature: &[u8],
+    ) -> Result<(), SignatureError> {
+        use rsa::pkcs1v15::Signature;
+        use rsa::signature::Verifier;
+        use sha2::{Sha256, Sha384, Sha512};
+
+        // Same format check as the pure-Rust backend, for identical errors
+        let sig = Signature::try_from(signature)
+            .map_err(|e| SignatureError::InvalidFormat(e.to_string()))?;
+
+        if key.n().bits() < RING_MIN_RSA_BITS {
+            return match hash {
+                DigestAlgorithm::Sha256 => {
+                    rsa::pkcs1v15::VerifyingKey::<Sha256>::new(key.clone()).verify(message, &sig)
+                }
+                DigestAlgorithm::Sha384 => {
+                    rsa::pkcs1v15::VerifyingKey::<Sha384>::new(key.clone()).verify(message, &sig)
+                }
+                DigestAlgorithm::Sha512 => {
+                    rsa::pkcs1v15::VerifyingKey::<Sha512>::new(key.clone()).verify(message, &sig)
+                }
+            }
+            .map_err(|_| SignatureError::InvalidSignature);
+        }
+
+        let params = match hash {
+            DigestAlgorithm::Sha256 => &RSA_PKCS1_2048_8192_SHA256,
+            DigestAlgorithm::Sha384 => &RSA_PKCS1_2048_8192_SHA384,
+            DigestAlgorithm::Sha512 => &RSA_PKCS1_2048_8192_SHA512,
+        };
+        RsaPublicKeyComponents {
+            n: key.n().to_bytes_be(),
+            e: key.e().to_bytes_be(),
+        }
+        .verify(params, message, signature)
+        .map_err(|_| SignatureError::InvalidSignature)
+    }
+
+    pub fn verify_rsa_pss(
+        key: &RsaPublicKey,
+        hash: DigestAlgorithm,
+        salt_len: usize,
+        message: &[u8],
+        signature: &[u8],
+    ) -> Result<(), SignatureError> {
+        let sig = rsa::pss::Signature::try_from(signature)
+            .map_err(|e| SignatureError::InvalidFormat(e.to_string()))?;
+
+        // ring only verifies salts as long as the digest
+        if key.n().bits() < RING_MIN_RSA_BITS || salt_len != hash.output_size() {
+            return verify_rsa_pss_rustcrypto(key, hash, salt_len, message, &sig);
+        }
+
+        let params = match hash {
+            DigestAlgorithm::Sha256 => &RSA_PSS_2048_8192_SHA256,
+            DigestAlgorithm::Sha384 => &RSA_PSS_2048_8192_SHA384,
+            DigestAlgorithm::Sha512 => &RSA_PSS_2048_8192_SHA512,
+        };
+        RsaPublicKeyComponents {
+            n: key.n().to_bytes_be(),
+            e: key.e().to_bytes_be(),
+        }
+        .verify(params, message, signature)
+        .map_err(|_| SignatureError::InvalidSignature)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use p256::ecdsa::{signature::Signer, SigningKey};
+
+    #[test]
+    fn test_p256_roundtrip_and_error_mapping() {
+        let signing_key = SigningKey::from_slice(&[7u8; 32]).unwrap();
+        let verifying_key = P256VerifyingKey::from(&signing_key);
+        let signature: P256Signature = signing_key.sign(b"message");
+
+        assert!(verify_p256(&verifying_key, b"message", &signature).is_ok());
+        assert!(matches!(
+            verify_p256(&verifying_key, b"tampered", &signature),
+            Err(SignatureError::InvalidSignature)
+        ));
+    }
+}
@@ -1,4 +1,14 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use std::io::{self, Read};
+
 use sha2::{Digest, Sha256};
+#[cfg(feature = "std")]
+use sha2::{Sha384, Sha512};
+
+#[cfg(feature = "std")]
+use crate::types::result::DigestAlgorithm;
 
 pub fn sha256(data: &[u8]) -> [u8; 32] {
     let mut hasher = Sha256::new();
@@ -6,6 +16,36 @@ pub fn sha256(data: &[u8]) -> [u8; 32] {
     hasher.finalize().into()
 }
 
+/// Digest of everything read from `reader`, hashed in chunks so large files
+/// are never held in memory
+#[cfg(feature = "std")]
+pub fn digest_reader(reader: impl Read, algorithm: DigestAlgorithm) -> io::Result<Vec<u8>> {
+    match algorithm {
+        DigestAlgorithm::Sha256 => hash_reader::<Sha256>(reader),
+        DigestAlgorithm::Sha384 => hash_reader::<Sha384>(reader),
+        DigestAlgorithm::Sha512 => hash_reader::<Sha512>(reader),
+        DigestAlgorithm::Sha1 => hash_reader::<sha1::Sha1>(reader),
+        DigestAlgorithm::Unknown => Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "unknown digest algorithm",
+        )),
+    }
+}
+
+#[cfg(feature = "std")]
+fn hash_reader<D: Digest>(mut reader: impl Read) -> io::Result<Vec<u8>> {
+    let mut hasher = D::new();
+    let mut buffer = [0u8; 64 * 1024];
+    loop {
+        match reader.read(&mut buffer) {
+            Ok(0) => return Ok(hasher.finalize().to_vec()),
+            Ok(read) => hasher.update(&buffer[..read]),
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        }
+    }
+}
+
 pub fn hex_encode(bytes: &[u8]) -> String {
     hex::encode(bytes)
 }
@@ -17,6 +57,7 @@ pub fn hex_decode(s: &str) -> Result<Vec<u8>, hex::FromHexError> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use alloc::vec;
 
     #[test]
     fn test_sha256() {
@@ -26,6 +67,20 @@ mod tests {
         assert_eq!(hex_encode(&hash), expected);
     }
 
+    #[test]
+    fn test_digest_reader() {
+        let data = vec![7u8; 200 * 1024];
+        assert_eq!(
+            digest_reader(&data[..], DigestAlgorithm::Sha256).unwrap(),
+            sha256(&data).to_vec()
+        );
+        assert_eq!(
+            digest_reader(&data[..], DigestAlgorithm::Sha512).unwrap(),
+            Sha512::digest(&data).to_vec()
+        );
+        assert!(digest_reader(&data[..], DigestAlgorithm::Unknown).is_err());
+    }
+
     #[test]
     fn test_hex_roundtrip() {
         let original = b"test data";
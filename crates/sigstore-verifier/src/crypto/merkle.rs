@@ -1,3 +1,4 @@
+use alloc::vec::Vec;
 use crate::crypto::hash::sha256;
 use crate::error::TransparencyError;
 
@@ -58,6 +59,7 @@ pub fn compute_leaf_hash(data: &[u8]) -> [u8; 32] {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use alloc::vec;
 
     #[test]
     fn test_compute_leaf_hash() {
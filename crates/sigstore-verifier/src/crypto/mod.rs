@@ -1,3 +1,4 @@
+pub mod backend;
 pub mod hash;
 pub mod merkle;
 pub mod signature;
@@ -1,21 +1,106 @@
-use ecdsa::signature::Verifier;
+use alloc::format;
+use alloc::string::ToString;
+use ed25519_dalek::{Signature as Ed25519Signature, VerifyingKey as Ed25519VerifyingKey};
 use p256::ecdsa::{Signature as P256Signature, VerifyingKey as P256VerifyingKey};
 use p384::ecdsa::{Signature as P384Signature, VerifyingKey as P384VerifyingKey};
+use rsa::pkcs1::DecodeRsaPublicKey;
+use rsa::RsaPublicKey;
+use serde::{Deserialize, Serialize};
 use x509_parser::prelude::*;
+use x509_parser::oid_registry::Oid;
+use x509_parser::signature_algorithm::{RsaSsaPssParams, SignatureAlgorithm};
 
+use crate::crypto::backend::{self, DigestAlgorithm};
 use crate::error::SignatureError;
 
+/// Encoding of an ECDSA signature
+///
+/// cosign and the GitHub attestation tooling emit ASN.1 DER signatures, while
+/// some other producers emit the fixed-size `r || s` form (64 bytes for P-256
+/// and secp256k1, 96 bytes for P-384). Ed25519 signatures only have the fixed
+/// 64-byte form and RSA signatures are as long as the modulus, so the encoding
+/// does not apply to them.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SignatureEncoding {
+    /// DER, falling back to raw `r || s` when DER parsing fails and the length matches the curve
+    #[default]
+    Auto,
+    /// ASN.1 DER only
+    Der,
+    /// Fixed-size `r || s` only
+    Raw,
+}
+
 #[derive(Debug, Clone)]
 pub enum PublicKey {
     P256(P256VerifyingKey),
     P384(P384VerifyingKey),
+    #[cfg(feature = "secp256k1")]
+    K256(k256::ecdsa::VerifyingKey),
+    Ed25519(Ed25519VerifyingKey),
+    Rsa(RsaPublicKey),
 }
 
+/// Ed25519 public key algorithm (RFC 8410)
+const ED25519_OID: &str = "1.3.101.112";
+
+/// RSA public key algorithms: rsaEncryption and id-RSASSA-PSS (RFC 4055)
+const RSA_OIDS: [&str; 2] = ["1.2.840.113549.1.1.1", "1.2.840.113549.1.1.10"];
+
+/// MGF1 mask generation function (RFC 8017)
+const MGF1_OID: &str = "1.2.840.113549.1.1.8";
+
 impl PublicKey {
     pub fn from_certificate(cert: &X509Certificate) -> Result<Self, SignatureError> {
-        let spki = cert.public_key();
+        Self::from_spki(cert.public_key())
+    }
+
+    /// Parse a PEM `PUBLIC KEY` block (DER SubjectPublicKeyInfo), as written by
+    /// `openssl pkey -pubout`
+    pub fn from_pem(pem: &str) -> Result<Self, SignatureError> {
+        let block = ::pem::parse(pem).map_err(|e| SignatureError::PublicKeyParse(e.to_string()))?;
+        if block.tag() != "PUBLIC KEY" {
+            return Err(SignatureError::PublicKeyParse(format!(
+                "expected a PUBLIC KEY block, got {}",
+                block.tag()
+            )));
+        }
+        Self::from_spki_der(block.contents())
+    }
+
+    /// Parse a DER SubjectPublicKeyInfo, e.g. a Rekor key from a trusted root
+    pub fn from_spki_der(der: &[u8]) -> Result<Self, SignatureError> {
+        let (_, spki) = SubjectPublicKeyInfo::from_der(der)
+            .map_err(|e| SignatureError::PublicKeyParse(e.to_string()))?;
+        Self::from_spki(&spki)
+    }
+
+    /// Parse the key of a SubjectPublicKeyInfo
+    pub fn from_spki(spki: &SubjectPublicKeyInfo) -> Result<Self, SignatureError> {
         let algorithm_oid = &spki.algorithm.algorithm;
 
+        // Ed25519 keys have no parameters; the key is the raw 32-byte point
+        if algorithm_oid.to_id_string() == ED25519_OID {
+            let key_bytes: &[u8; 32] = spki.subject_public_key.data.as_ref().try_into().map_err(|_| {
+                SignatureError::PublicKeyParse(format!(
+                    "Ed25519 key must be 32 bytes, got {}",
+                    spki.subject_public_key.data.len()
+                ))
+            })?;
+            let verifying_key = Ed25519VerifyingKey::from_bytes(key_bytes)
+                .map_err(|e| SignatureError::PublicKeyParse(e.to_string()))?;
+            return Ok(PublicKey::Ed25519(verifying_key));
+        }
+
+        // RSA keys are a PKCS#1 RSAPublicKey; PSS key parameters only restrict
+        // the signature algorithm, which is checked per signature
+        if RSA_OIDS.contains(&algorithm_oid.to_id_string().as_str()) {
+            let key = RsaPublicKey::from_pkcs1_der(&spki.subject_public_key.data)
+                .map_err(|e| SignatureError::PublicKeyParse(e.to_string()))?;
+            return Ok(PublicKey::Rsa(key));
+        }
+
         // Check if this is an EC public key (1.2.840.10045.2.1)
         if algorithm_oid.to_id_string() == "1.2.840.10045.2.1" {
             // For EC keys, the curve is specified in the parameters
@@ -36,6 +121,14 @@ impl PublicKey {
                                 .map_err(|e| SignatureError::PublicKeyParse(e.to_string()))?;
                             return Ok(PublicKey::P384(verifying_key));
                         }
+                        #[cfg(feature = "secp256k1")]
+                        "1.3.132.0.10" => {
+                            // secp256k1
+                            let key_bytes = &spki.subject_public_key.data;
+                            let verifying_key = k256::ecdsa::VerifyingKey::from_sec1_bytes(key_bytes)
+                                .map_err(|e| SignatureError::PublicKeyParse(e.to_string()))?;
+                            return Ok(PublicKey::K256(verifying_key));
+                        }
                         oid => return Err(SignatureError::UnsupportedAlgorithm(format!("EC curve: {}", oid))),
                     }
                 }
@@ -63,25 +156,260 @@ impl PublicKey {
         }
     }
 
+    /// Verify a DER-encoded signature, as used in X.509 certificates
     pub fn verify_signature(&self, message: &[u8], signature: &[u8]) -> Result<(), SignatureError> {
+        self.verify_signature_with_encoding(message, signature, SignatureEncoding::Der)
+    }
+
+    /// Verify a signature made with the given X.509 signature algorithm
+    ///
+    /// ECDSA and Ed25519 signatures are verified as by [`Self::verify_signature`],
+    /// with the digest implied by the curve. RSA signatures take the padding
+    /// (PKCS#1 v1.5 or PSS) and the digest from `algorithm`.
+    pub fn verify_x509_signature(
+        &self,
+        message: &[u8],
+        signature: &[u8],
+        algorithm: &AlgorithmIdentifier,
+    ) -> Result<(), SignatureError> {
+        let PublicKey::Rsa(key) = self else {
+            return self.verify_signature(message, signature);
+        };
+
+        let unsupported = || {
+            SignatureError::UnsupportedAlgorithm(format!("{} with an RSA key", algorithm.algorithm))
+        };
+        match SignatureAlgorithm::try_from(algorithm).map_err(|_| unsupported())? {
+            SignatureAlgorithm::RSA => {
+                let hash = match algorithm.algorithm.to_id_string().as_str() {
+                    "1.2.840.113549.1.1.11" => DigestAlgorithm::Sha256, // sha256WithRSAEncryption
+                    "1.2.840.113549.1.1.12" => DigestAlgorithm::Sha384, // sha384WithRSAEncryption
+                    _ => return Err(unsupported()),
+                };
+                backend::verify_rsa_pkcs1v15(key, hash, message, signature)
+            }
+            SignatureAlgorithm::RSASSA_PSS(params) => {
+                let (hash, salt_len) = rsa_pss_parameters(&params).ok_or_else(unsupported)?;
+                backend::verify_rsa_pss(key, hash, salt_len, message, signature)
+            }
+            _ => Err(unsupported()),
+        }
+    }
+
+    /// Verify a signature over a message of which only the digest is known
+    ///
+    /// ECDSA and RSA sign the digest of the message, so the signature of a blob
+    /// can be checked against the digest recorded in a bundle without the blob.
+    /// Ed25519 signs the whole message and is rejected. `ring` cannot verify a
+    /// bare digest, so this always uses the pure-Rust crates.
+    ///
+    /// # Arguments
+    ///
+    /// * `hash` - Algorithm `digest` was computed with
+    /// * `digest` - Digest of the signed message
+    /// * `signature` - The signature; RSA accepts PKCS#1 v1.5 and PSS with a digest-length salt
+    /// * `encoding` - Encoding of ECDSA signatures
+    pub fn verify_prehash(
+        &self,
+        hash: DigestAlgorithm,
+        digest: &[u8],
+        signature: &[u8],
+        encoding: SignatureEncoding,
+    ) -> Result<(), SignatureError> {
+        use ecdsa::signature::hazmat::PrehashVerifier;
+
+        if digest.len() != hash.output_size() {
+            return Err(SignatureError::InvalidFormat(format!(
+                "{:?} digest must be {} bytes, got {}",
+                hash,
+                hash.output_size(),
+                digest.len()
+            )));
+        }
+
         match self {
             PublicKey::P256(key) => {
-                let sig = P256Signature::from_der(signature)
-                    .map_err(|e| SignatureError::InvalidFormat(e.to_string()))?;
-                key.verify(message, &sig)
-                    .map_err(|_| SignatureError::InvalidSignature)?;
+                let sig = decode_signature(
+                    signature,
+                    encoding,
+                    64,
+                    P256Signature::from_der,
+                    P256Signature::from_slice,
+                )?;
+                key.verify_prehash(digest, &sig)
             }
             PublicKey::P384(key) => {
-                let sig = P384Signature::from_der(signature)
+                let sig = decode_signature(
+                    signature,
+                    encoding,
+                    96,
+                    P384Signature::from_der,
+                    P384Signature::from_slice,
+                )?;
+                key.verify_prehash(digest, &sig)
+            }
+            #[cfg(feature = "secp256k1")]
+            PublicKey::K256(key) => {
+                let sig = decode_signature(
+                    signature,
+                    encoding,
+                    64,
+                    k256::ecdsa::Signature::from_der,
+                    k256::ecdsa::Signature::from_slice,
+                )?;
+                // Same as the whole-message path: high-S signatures are accepted
+                key.verify_prehash(digest, &sig.normalize_s().unwrap_or(sig))
+            }
+            PublicKey::Ed25519(_) => {
+                return Err(SignatureError::UnsupportedAlgorithm(
+                    "Ed25519 signs the whole message; it cannot be verified against a digest".to_string(),
+                ))
+            }
+            PublicKey::Rsa(key) => {
+                use rsa::{pkcs1v15, pss};
+                use sha2::{Sha256, Sha384, Sha512};
+
+                let pkcs1_sig = pkcs1v15::Signature::try_from(signature)
+                    .map_err(|e| SignatureError::InvalidFormat(e.to_string()))?;
+                let pss_sig = pss::Signature::try_from(signature)
                     .map_err(|e| SignatureError::InvalidFormat(e.to_string()))?;
-                key.verify(message, &sig)
-                    .map_err(|_| SignatureError::InvalidSignature)?;
+                match hash {
+                    DigestAlgorithm::Sha256 => pkcs1v15::VerifyingKey::<Sha256>::new(key.clone())
+                        .verify_prehash(digest, &pkcs1_sig)
+                        .or_else(|_| pss::VerifyingKey::<Sha256>::new(key.clone()).verify_prehash(digest, &pss_sig)),
+                    DigestAlgorithm::Sha384 => pkcs1v15::VerifyingKey::<Sha384>::new(key.clone())
+                        .verify_prehash(digest, &pkcs1_sig)
+                        .or_else(|_| pss::VerifyingKey::<Sha384>::new(key.clone()).verify_prehash(digest, &pss_sig)),
+                    DigestAlgorithm::Sha512 => pkcs1v15::VerifyingKey::<Sha512>::new(key.clone())
+                        .verify_prehash(digest, &pkcs1_sig)
+                        .or_else(|_| pss::VerifyingKey::<Sha512>::new(key.clone()).verify_prehash(digest, &pss_sig)),
+                }
+            }
+        }
+        .map_err(|_| SignatureError::InvalidSignature)
+    }
+
+    /// Verify a signature in the given encoding
+    pub fn verify_signature_with_encoding(
+        &self,
+        message: &[u8],
+        signature: &[u8],
+        encoding: SignatureEncoding,
+    ) -> Result<(), SignatureError> {
+        match self {
+            PublicKey::P256(key) => {
+                let sig = decode_signature(
+                    signature,
+                    encoding,
+                    64,
+                    P256Signature::from_der,
+                    P256Signature::from_slice,
+                )?;
+                backend::verify_p256(key, message, &sig)?;
+            }
+            PublicKey::P384(key) => {
+                let sig = decode_signature(
+                    signature,
+                    encoding,
+                    96,
+                    P384Signature::from_der,
+                    P384Signature::from_slice,
+                )?;
+                backend::verify_p384(key, message, &sig)?;
+            }
+            #[cfg(feature = "secp256k1")]
+            PublicKey::K256(key) => {
+                let sig = decode_signature(
+                    signature,
+                    encoding,
+                    64,
+                    k256::ecdsa::Signature::from_der,
+                    k256::ecdsa::Signature::from_slice,
+                )?;
+                backend::verify_k256(key, message, &sig)?;
+            }
+            PublicKey::Ed25519(key) => {
+                let sig = Ed25519Signature::from_slice(signature).map_err(|_| {
+                    SignatureError::InvalidFormat(format!(
+                        "Ed25519 signature must be 64 bytes, got {}",
+                        signature.len()
+                    ))
+                })?;
+                backend::verify_ed25519(key, message, &sig)?;
+            }
+            PublicKey::Rsa(key) => {
+                // Sigstore's RSA algorithms all use SHA-256; a bare signature
+                // doesn't say which padding it uses, so both are accepted
+                match backend::verify_rsa_pkcs1v15(key, DigestAlgorithm::Sha256, message, signature) {
+                    Err(SignatureError::InvalidSignature) => backend::verify_rsa_pss(
+                        key,
+                        DigestAlgorithm::Sha256,
+                        DigestAlgorithm::Sha256.output_size(),
+                        message,
+                        signature,
+                    )?,
+                    result => result?,
+                }
             }
         }
         Ok(())
     }
 }
 
+/// Digest and salt length of RSASSA-PSS parameters (RFC 4055)
+///
+/// Only MGF1 over the same digest, SHA-256 or SHA-384, with the standard
+/// trailer field is supported.
+pub(crate) fn rsa_pss_parameters(params: &RsaSsaPssParams) -> Option<(DigestAlgorithm, usize)> {
+    let hash = digest_algorithm(params.hash_algorithm_oid())?;
+    let mask = params.mask_gen_algorithm().ok()?;
+    if mask.mgf.to_id_string() != MGF1_OID || digest_algorithm(&mask.hash) != Some(hash) || params.trailer_field() != 1 {
+        return None;
+    }
+    Some((hash, params.salt_length() as usize))
+}
+
+/// Digest of a SHA-2 algorithm OID, if supported
+fn digest_algorithm(oid: &Oid) -> Option<DigestAlgorithm> {
+    match oid.to_id_string().as_str() {
+        "2.16.840.1.101.3.4.2.1" => Some(DigestAlgorithm::Sha256),
+        "2.16.840.1.101.3.4.2.2" => Some(DigestAlgorithm::Sha384),
+        _ => None,
+    }
+}
+
+/// Decode an ECDSA signature whose raw `r || s` form is `raw_len` bytes
+fn decode_signature<S>(
+    signature: &[u8],
+    encoding: SignatureEncoding,
+    raw_len: usize,
+    from_der: impl Fn(&[u8]) -> Result<S, ecdsa::Error>,
+    from_raw: impl Fn(&[u8]) -> Result<S, ecdsa::Error>,
+) -> Result<S, SignatureError> {
+    let decode_raw = |bytes: &[u8]| {
+        if bytes.len() != raw_len {
+            return Err(SignatureError::InvalidFormat(format!(
+                "raw signature must be {} bytes, got {}",
+                raw_len,
+                bytes.len()
+            )));
+        }
+        from_raw(bytes).map_err(|e| SignatureError::InvalidFormat(e.to_string()))
+    };
+
+    match encoding {
+        SignatureEncoding::Der => {
+            from_der(signature).map_err(|e| SignatureError::InvalidFormat(e.to_string()))
+        }
+        SignatureEncoding::Raw => decode_raw(signature),
+        SignatureEncoding::Auto => match from_der(signature) {
+            Ok(sig) => Ok(sig),
+            Err(_) if signature.len() == raw_len => decode_raw(signature),
+            Err(e) => Err(SignatureError::InvalidFormat(e.to_string())),
+        },
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -94,4 +422,166 @@ mod tests {
             Err(SignatureError::UnsupportedAlgorithm("1.2.3.4".to_string()));
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_signature_encodings() {
+        use p256::ecdsa::{signature::Signer, SigningKey};
+
+        let signing_key = SigningKey::from_slice(&[3u8; 32]).unwrap();
+        let public_key = PublicKey::P256(*signing_key.verifying_key());
+        let signature: P256Signature = signing_key.sign(b"payload");
+        let der = signature.to_der();
+        let raw = signature.to_bytes();
+
+        let verify = |sig: &[u8], encoding| {
+            public_key.verify_signature_with_encoding(b"payload", sig, encoding)
+        };
+
+        assert!(verify(der.as_bytes(), SignatureEncoding::Der).is_ok());
+        assert!(verify(der.as_bytes(), SignatureEncoding::Auto).is_ok());
+        assert!(verify(&raw, SignatureEncoding::Raw).is_ok());
+        assert!(verify(&raw, SignatureEncoding::Auto).is_ok());
+
+        assert!(matches!(
+            verify(&raw, SignatureEncoding::Der),
+            Err(SignatureError::InvalidFormat(_))
+        ));
+        assert!(matches!(
+            verify(der.as_bytes(), SignatureEncoding::Raw),
+            Err(SignatureError::InvalidFormat(_))
+        ));
+        // Wrong-length garbage is still a format error, not a signature mismatch
+        assert!(matches!(
+            verify(&raw[..63], SignatureEncoding::Auto),
+            Err(SignatureError::InvalidFormat(_))
+        ));
+        // Certificates always use DER
+        assert!(public_key.verify_signature(b"payload", &raw).is_err());
+    }
+
+    #[test]
+    fn test_verify_prehash() {
+        use p256::ecdsa::{signature::Signer, SigningKey};
+        use sha2::{Digest, Sha256};
+
+        let signing_key = SigningKey::from_slice(&[3u8; 32]).unwrap();
+        let public_key = PublicKey::P256(*signing_key.verifying_key());
+        let signature: P256Signature = signing_key.sign(b"blob");
+        let digest = Sha256::digest(b"blob");
+
+        let verify = |digest: &[u8]| {
+            public_key.verify_prehash(DigestAlgorithm::Sha256, digest, &signature.to_der().to_bytes(), SignatureEncoding::Auto)
+        };
+        assert!(verify(&digest).is_ok());
+        assert!(matches!(verify(&Sha256::digest(b"other")), Err(SignatureError::InvalidSignature)));
+        assert!(matches!(verify(&digest[..31]), Err(SignatureError::InvalidFormat(_))));
+
+        let ed25519 = PublicKey::Ed25519(ed25519_dalek::SigningKey::from_bytes(&[3u8; 32]).verifying_key());
+        assert!(matches!(
+            ed25519.verify_prehash(DigestAlgorithm::Sha256, &digest, &[0u8; 64], SignatureEncoding::Auto),
+            Err(SignatureError::UnsupportedAlgorithm(_))
+        ));
+    }
+
+    #[test]
+    fn test_public_key_from_pem() {
+        use p256::ecdsa::SigningKey;
+        use p256::pkcs8::{EncodePublicKey, LineEnding};
+
+        let verifying_key = *SigningKey::from_slice(&[3u8; 32]).unwrap().verifying_key();
+        let pem = verifying_key.to_public_key_pem(LineEnding::LF).unwrap();
+        match PublicKey::from_pem(&pem).unwrap() {
+            PublicKey::P256(key) => assert_eq!(key, verifying_key),
+            other => panic!("expected a P-256 key, got {:?}", other),
+        }
+
+        // A certificate is not a bare public key
+        let cert = include_str!("../../testdata/ed25519/leaf.pem");
+        assert!(matches!(
+            PublicKey::from_pem(cert),
+            Err(SignatureError::PublicKeyParse(_))
+        ));
+    }
+
+    #[cfg(feature = "secp256k1")]
+    #[test]
+    fn test_k256_signature_accepts_high_s() {
+        use k256::ecdsa::{signature::Signer, Signature, SigningKey};
+
+        let signing_key = SigningKey::from_slice(&[9u8; 32]).unwrap();
+        let public_key = PublicKey::K256(*signing_key.verifying_key());
+        let signature: Signature = signing_key.sign(b"payload");
+
+        // k256 signs low-S; other signers may emit the equivalent high-S form
+        let high_s = Signature::from_scalars(signature.r(), -*signature.s()).unwrap();
+        for sig in [signature, high_s] {
+            let der = sig.to_der();
+            assert!(public_key.verify_signature(b"payload", der.as_bytes()).is_ok());
+            assert!(matches!(
+                public_key.verify_signature(b"tampered", der.as_bytes()),
+                Err(SignatureError::InvalidSignature)
+            ));
+        }
+    }
+
+    #[test]
+    fn test_ed25519_certificate_key() {
+        use ed25519_dalek::{Signer, SigningKey};
+
+        // The test leaf key is derived from the seed [3; 32]
+        let pem = ::pem::parse(include_str!("../../testdata/ed25519/leaf.pem")).unwrap();
+        let (_, cert) = X509Certificate::from_der(pem.contents()).unwrap();
+        let public_key = PublicKey::from_certificate(&cert).unwrap();
+        assert!(matches!(public_key, PublicKey::Ed25519(_)));
+
+        let signature = SigningKey::from_bytes(&[3u8; 32]).sign(b"payload").to_bytes();
+        for encoding in [SignatureEncoding::Auto, SignatureEncoding::Der, SignatureEncoding::Raw] {
+            assert!(public_key
+                .verify_signature_with_encoding(b"payload", &signature, encoding)
+                .is_ok());
+        }
+        assert!(matches!(
+            public_key.verify_signature(b"tampered", &signature),
+            Err(SignatureError::InvalidSignature)
+        ));
+        assert!(matches!(
+            public_key.verify_signature(b"payload", &signature[..63]),
+            Err(SignatureError::InvalidFormat(_))
+        ));
+    }
+
+    #[test]
+    fn test_rsa_certificate_key() {
+        use rsa::pkcs1v15::SigningKey;
+        use rsa::pkcs8::DecodePrivateKey;
+        use rsa::signature::{SignatureEncoding as _, Signer};
+        use rsa::RsaPrivateKey;
+
+        let pem = ::pem::parse(include_str!("../../testdata/rsa/leaf.pem")).unwrap();
+        let (_, cert) = X509Certificate::from_der(pem.contents()).unwrap();
+        let public_key = PublicKey::from_certificate(&cert).unwrap();
+        assert!(matches!(public_key, PublicKey::Rsa(_)));
+
+        let private_key = RsaPrivateKey::from_pkcs8_pem(include_str!("../../testdata/rsa/leaf.key")).unwrap();
+        let signature = SigningKey::<sha2::Sha256>::new(private_key).sign(b"payload").to_vec();
+        assert!(public_key
+            .verify_signature_with_encoding(b"payload", &signature, SignatureEncoding::Auto)
+            .is_ok());
+        // Neither padding matches a tampered message
+        assert!(matches!(
+            public_key.verify_signature(b"tampered", &signature),
+            Err(SignatureError::InvalidSignature)
+        ));
+    }
+
+    #[test]
+    fn test_ed25519_self_signed_certificate() {
+        let pem = ::pem::parse(include_str!("../../testdata/ed25519/root.pem")).unwrap();
+        let (_, cert) = X509Certificate::from_der(pem.contents()).unwrap();
+        let public_key = PublicKey::from_certificate(&cert).unwrap();
+
+        assert!(public_key
+            .verify_signature(cert.tbs_certificate.as_ref(), &cert.signature_value.data)
+            .is_ok());
+    }
 }
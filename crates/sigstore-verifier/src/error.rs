@@ -1,3 +1,6 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
 use thiserror::Error;
 
 #[derive(Debug, Error)]
@@ -23,15 +26,174 @@ pub enum VerificationError {
     #[error("Subject digest mismatch: expected {expected}, got {actual}")]
     SubjectDigestMismatch { expected: String, actual: String },
 
-    #[cfg(feature = "fetcher")]
+    #[error("No subject matches name '{0}'")]
+    SubjectNotFound(String),
+
+    #[error("Multiple subjects match name '{0}'")]
+    AmbiguousSubject(String),
+
+    #[error("{limit} of {actual} exceeds the limit of {max}")]
+    LimitExceeded {
+        limit: &'static str,
+        actual: usize,
+        max: usize,
+    },
+
+    #[cfg(feature = "fetcher-core")]
     #[error("HTTP request failed: {0}")]
-    HttpError(#[from] reqwest::Error),
+    HttpError(String),
+
+    #[cfg(feature = "fetcher-core")]
+    #[error("Remote source error: {0}")]
+    RemoteSource(String),
+
+    #[cfg(feature = "fetcher-core")]
+    #[error("TUF update failed: {0}")]
+    Tuf(String),
 
     #[error("Base64 decode error: {0}")]
-    Base64Decode(#[from] base64::DecodeError),
+    // base64 only implements `Error` with its std feature; without it the conversion is by hand
+    Base64Decode(#[cfg_attr(feature = "std", from)] base64::DecodeError),
 
     #[error("Invalid bundle format: {0}")]
     InvalidBundleFormat(String),
+
+    #[error("Invalid identity policy: {0}")]
+    InvalidIdentityPolicy(String),
+
+    #[error("Signer identity is not allowed by the identity policy: {0}")]
+    IdentityPolicyRejected(String),
+
+    #[error("Signed at {signing_time}, more than {max_age} seconds before the verification time {verification_time}")]
+    SignatureTooOld {
+        signing_time: String,
+        verification_time: String,
+        max_age: u64,
+    },
+
+    #[error("Signed at {signing_time}, after the verification time {verification_time}")]
+    SignedAfterVerificationTime {
+        signing_time: String,
+        verification_time: String,
+    },
+
+    #[error("Predicate type mismatch: expected '{expected}', got '{actual}'")]
+    PredicateTypeMismatch { expected: String, actual: String },
+
+    #[error("Unknown verification profile '{0}'")]
+    UnknownProfile(String),
+
+    #[error("Options conflict with verification profile '{profile}': {reason}")]
+    ProfileConflict { profile: String, reason: String },
+
+    #[error("Options conflict with the policy of repositories '{repository}': {reason}")]
+    RepositoryPolicyConflict { repository: String, reason: String },
+
+    #[error("Signer is not allowed by the repository policy: {0}")]
+    RepositoryPolicyRejected(String),
+
+    #[error("Invalid git object: {0}")]
+    InvalidGitObject(String),
+
+    #[error("Failed to read artifact: {0}")]
+    ArtifactRead(String),
+
+    #[error("DSSE envelope has no payload and no detached statement was supplied")]
+    MissingPayload,
+
+    #[error("Detached statement differs from the payload embedded in the DSSE envelope")]
+    DetachedPayloadMismatch,
+
+    #[error("DSSE signatures {failed:?} of {total} do not verify with the leaf certificate key or name another key")]
+    DsseSignaturesFailed { failed: Vec<usize>, total: usize },
+
+    #[error("Message signature was made with an Ed25519 key, which signs the artifact itself; the artifact must be supplied")]
+    MissingArtifact,
+
+    #[error("Trusted root is wrapped in a signed envelope, but no trust-root key is configured to verify it")]
+    UnverifiedTrustRoot,
+
+    #[error("Trusted root is not signed, but trust-root keys are configured")]
+    UnsignedTrustRoot,
+
+    #[error("No signature on the trusted root envelope verifies with the configured trust-root keys")]
+    TrustRootSignature,
+
+    #[error("Unsupported bundle media type '{media_type}' (version {version}); nearest supported version is {nearest}")]
+    UnsupportedMediaType {
+        media_type: String,
+        version: String,
+        nearest: String,
+    },
+
+    #[error("Expected OIDC identity but could not extract from certificate")]
+    OidcIdentityMissing,
+
+    #[error("Identity {field} mismatch: expected '{expected}', got '{actual}'")]
+    IdentityMismatch {
+        field: IdentityField,
+        expected: String,
+        actual: String,
+    },
+
+    #[error("Expected identity {0} but none found in certificate")]
+    IdentityFieldMissing(IdentityField),
+
+    #[error("Issuer '{0}' is not allowed for email identities")]
+    EmailIssuerNotAllowed(String),
+
+    #[error("Invalid verification options: {0}")]
+    InvalidOptions(String),
+
+    #[error("Statement has no subjects")]
+    NoSubjects,
+}
+
+#[cfg(not(feature = "std"))]
+impl From<base64::DecodeError> for VerificationError {
+    fn from(err: base64::DecodeError) -> Self {
+        VerificationError::Base64Decode(err)
+    }
+}
+
+/// Signer identity field an identity constraint failed on
+///
+/// Named as in [`IdentityField::name`], e.g. `workflow_ref`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum IdentityField {
+    Issuer,
+    Subject,
+    Repository,
+    WorkflowRef,
+    EventName,
+    Email,
+    SpiffeId,
+    SpiffeTrustDomain,
+    /// Path of the SPIFFE ID; the expected value is the path prefix it must be under
+    SpiffePath,
+}
+
+impl IdentityField {
+    /// Stable name of the field
+    pub fn name(&self) -> &'static str {
+        match self {
+            IdentityField::Issuer => "issuer",
+            IdentityField::Subject => "subject",
+            IdentityField::Repository => "repository",
+            IdentityField::WorkflowRef => "workflow_ref",
+            IdentityField::EventName => "event_name",
+            IdentityField::Email => "email",
+            IdentityField::SpiffeId => "spiffe_id",
+            IdentityField::SpiffeTrustDomain => "spiffe_trust_domain",
+            IdentityField::SpiffePath => "spiffe_path",
+        }
+    }
+}
+
+impl fmt::Display for IdentityField {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
 }
 
 #[derive(Debug, Error)]
@@ -63,6 +225,12 @@ pub enum CertificateError {
 
     #[error("Self-signed certificate verification failed")]
     SelfSignedVerificationFailed,
+
+    #[error("Certificate has no signed certificate timestamp from a trusted CT log")]
+    MissingSct,
+
+    #[error("Invalid signed certificate timestamp: {0}")]
+    InvalidSct(String),
 }
 
 #[derive(Debug, Error)]
@@ -106,7 +274,7 @@ pub enum TimestampError {
     #[error("Unsupported hash algorithm in RFC3161 timestamp: {0}")]
     UnsupportedHashAlgorithm(String),
 
-    #[error("TSA certificate chain is required but not provided. RFC3161 timestamp does not contain embedded certificates.")]
+    #[error("TSA certificate chain is required but not provided. RFC3161 timestamp does not contain a trusted embedded chain.")]
     MissingTSAChain,
 
     #[error("Invalid TSA certificate: {0}")]
@@ -114,6 +282,21 @@ pub enum TimestampError {
 
     #[error("Invalid integrated time")]
     InvalidIntegratedTime,
+
+    #[error("Bundle is timestamped with {actual}, but {required} is required")]
+    UnexpectedMechanism { required: String, actual: String },
+
+    #[error("Invalid signed attributes in RFC3161 timestamp: {0}")]
+    Rfc3161SignedAttributes(String),
+
+    #[error("RFC3161 timestamp is not bound to the TSA certificate: {0}")]
+    Rfc3161SigningCertificate(String),
+
+    #[error("RFC3161 timestamp was issued under TSA policy {actual}, but {expected} is required")]
+    Rfc3161PolicyMismatch { expected: String, actual: String },
+
+    #[error("TSA certificate chain embedded in the RFC3161 timestamp does not root in a trusted TSA root")]
+    UntrustedTSARoot,
 }
 
 #[derive(Debug, Error)]
@@ -129,4 +312,227 @@ pub enum TransparencyError {
 
     #[error("Signed entry timestamp verification failed")]
     SignedEntryTimestampInvalid,
+
+    #[error("No trusted Rekor key for log ID {0}")]
+    UnknownLogKey(String),
+
+    #[error("Signed entry timestamp signature does not verify with the Rekor key for log ID {0}")]
+    SignedEntryTimestampSignature(String),
+
+    #[error("Invalid checkpoint: {0}")]
+    InvalidCheckpoint(String),
+
+    #[error("Checkpoint does not match the inclusion proof: {0}")]
+    CheckpointMismatch(String),
+
+    #[error("No checkpoint signature verifies with a trusted Rekor key")]
+    CheckpointSignature,
+
+    #[error("Invalid Rekor entry body: {0}")]
+    InvalidEntryBody(String),
+
+    #[error("Rekor entry body does not match the bundle: {0}")]
+    EntryBodyMismatch(String),
+
+    #[error("No trusted Rekor keys to verify the signed entry timestamp and checkpoint with")]
+    MissingRekorKeys,
+
+    #[error("Rekor entry has no signed entry timestamp to vouch for its integrated time")]
+    MissingSignedEntryTimestamp,
+}
+
+impl VerificationError {
+    /// Stable numeric code of the error
+    ///
+    /// The zkVM guests abort with this code, so failures stay distinguishable
+    /// once only the guest's panic message is left. Codes are grouped by kind:
+    /// 1xx bundle contents and options, 2xx certificates, 3xx signatures, 4xx
+    /// timestamps, 5xx the transparency log and 9xx fetching. A variant keeps its
+    /// code; new variants get new codes and removed codes are not reused.
+    pub fn code(&self) -> u16 {
+        // Every variant is listed, so a new one cannot be added without a code
+        match self {
+            VerificationError::BundleParse(_) => 100,
+            VerificationError::ZeroSubjectDigest => 101,
+            VerificationError::SubjectDigestMismatch { .. } => 102,
+            VerificationError::SubjectNotFound(_) => 103,
+            VerificationError::AmbiguousSubject(_) => 104,
+            VerificationError::LimitExceeded { .. } => 105,
+            VerificationError::Base64Decode(_) => 106,
+            VerificationError::InvalidBundleFormat(_) => 107,
+            VerificationError::MissingPayload => 108,
+            VerificationError::DetachedPayloadMismatch => 109,
+            VerificationError::DsseSignaturesFailed { .. } => 110,
+            VerificationError::MissingArtifact => 111,
+            VerificationError::UnverifiedTrustRoot => 112,
+            VerificationError::UnsignedTrustRoot => 113,
+            VerificationError::TrustRootSignature => 114,
+            VerificationError::UnsupportedMediaType { .. } => 115,
+            VerificationError::InvalidIdentityPolicy(_) => 116,
+            VerificationError::IdentityPolicyRejected(_) => 117,
+            VerificationError::SignatureTooOld { .. } => 118,
+            VerificationError::SignedAfterVerificationTime { .. } => 119,
+            VerificationError::PredicateTypeMismatch { .. } => 120,
+            VerificationError::UnknownProfile(_) => 121,
+            VerificationError::ProfileConflict { .. } => 122,
+            VerificationError::RepositoryPolicyConflict { .. } => 123,
+            VerificationError::RepositoryPolicyRejected(_) => 124,
+            VerificationError::InvalidGitObject(_) => 125,
+            VerificationError::ArtifactRead(_) => 126,
+            VerificationError::OidcIdentityMissing => 127,
+            VerificationError::IdentityMismatch { .. } => 128,
+            VerificationError::IdentityFieldMissing(_) => 129,
+            VerificationError::EmailIssuerNotAllowed(_) => 130,
+            VerificationError::InvalidOptions(_) => 131,
+            VerificationError::NoSubjects => 132,
+            VerificationError::Certificate(error) => error.code(),
+            VerificationError::Signature(error) => error.code(),
+            VerificationError::Timestamp(error) => error.code(),
+            VerificationError::Transparency(error) => error.code(),
+            #[cfg(feature = "fetcher-core")]
+            VerificationError::HttpError(_) => 900,
+            #[cfg(feature = "fetcher-core")]
+            VerificationError::RemoteSource(_) => 901,
+            #[cfg(feature = "fetcher-core")]
+            VerificationError::Tuf(_) => 902,
+        }
+    }
+
+    /// Whether the bundle was rejected by the policy rather than found invalid
+    ///
+    /// The policy is every constraint on what was signed, by whom and when (see
+    /// `VerificationOptions::without_policy`): a bundle rejected with one of these
+    /// errors may still verify without it, which quarantine mode tells apart.
+    pub fn is_policy_violation(&self) -> bool {
+        matches!(
+            self,
+            VerificationError::SubjectDigestMismatch { .. }
+                | VerificationError::IdentityPolicyRejected(_)
+                | VerificationError::SignatureTooOld { .. }
+                | VerificationError::SignedAfterVerificationTime { .. }
+                | VerificationError::PredicateTypeMismatch { .. }
+                | VerificationError::RepositoryPolicyRejected(_)
+                | VerificationError::OidcIdentityMissing
+                | VerificationError::IdentityMismatch { .. }
+                | VerificationError::IdentityFieldMissing(_)
+                | VerificationError::EmailIssuerNotAllowed(_)
+                | VerificationError::Timestamp(TimestampError::UnexpectedMechanism { .. })
+                | VerificationError::Timestamp(TimestampError::Rfc3161PolicyMismatch { .. })
+        )
+    }
+}
+
+impl CertificateError {
+    /// Stable numeric code of the error (see [`VerificationError::code`])
+    pub fn code(&self) -> u16 {
+        match self {
+            CertificateError::ParseError(_) => 200,
+            CertificateError::ChainVerificationFailed(_) => 201,
+            CertificateError::ValidityPeriod => 202,
+            CertificateError::SigningTimeOutsideValidity { .. } => 203,
+            CertificateError::UnknownIssuer(_) => 204,
+            CertificateError::MissingCertificate => 205,
+            CertificateError::TrustBundleFetch(_) => 206,
+            CertificateError::SelfSignedVerificationFailed => 207,
+            CertificateError::MissingSct => 208,
+            CertificateError::InvalidSct(_) => 209,
+        }
+    }
+}
+
+impl SignatureError {
+    /// Stable numeric code of the error (see [`VerificationError::code`])
+    pub fn code(&self) -> u16 {
+        match self {
+            SignatureError::UnsupportedAlgorithm(_) => 300,
+            SignatureError::InvalidFormat(_) => 301,
+            SignatureError::InvalidSignature => 302,
+            SignatureError::PublicKeyParse(_) => 303,
+            SignatureError::DerError(_) => 304,
+        }
+    }
+}
+
+impl TimestampError {
+    /// Stable numeric code of the error (see [`VerificationError::code`])
+    pub fn code(&self) -> u16 {
+        match self {
+            TimestampError::NoTimestamp => 400,
+            TimestampError::BothTimestampMechanisms => 401,
+            TimestampError::Rfc3161NotSupported => 402,
+            TimestampError::Rfc3161Parse(_) => 403,
+            TimestampError::Rfc3161SignatureInvalid => 404,
+            TimestampError::MessageImprintMismatch { .. } => 405,
+            TimestampError::UnsupportedHashAlgorithm(_) => 406,
+            TimestampError::MissingTSAChain => 407,
+            TimestampError::InvalidTSACertificate(_) => 408,
+            TimestampError::InvalidIntegratedTime => 409,
+            TimestampError::UnexpectedMechanism { .. } => 410,
+            TimestampError::Rfc3161SignedAttributes(_) => 411,
+            TimestampError::Rfc3161SigningCertificate(_) => 412,
+            TimestampError::Rfc3161PolicyMismatch { .. } => 413,
+            TimestampError::UntrustedTSARoot => 414,
+        }
+    }
+}
+
+impl TransparencyError {
+    /// Stable numeric code of the error (see [`VerificationError::code`])
+    pub fn code(&self) -> u16 {
+        match self {
+            TransparencyError::NoRekorEntry => 500,
+            TransparencyError::InvalidEntryHash => 501,
+            TransparencyError::InclusionProofFailed => 502,
+            TransparencyError::SignedEntryTimestampInvalid => 503,
+            TransparencyError::UnknownLogKey(_) => 504,
+            TransparencyError::SignedEntryTimestampSignature(_) => 505,
+            TransparencyError::InvalidCheckpoint(_) => 506,
+            TransparencyError::CheckpointMismatch(_) => 507,
+            TransparencyError::CheckpointSignature => 508,
+            TransparencyError::InvalidEntryBody(_) => 509,
+            TransparencyError::EntryBodyMismatch(_) => 510,
+            TransparencyError::MissingRekorKeys => 511,
+            TransparencyError::MissingSignedEntryTimestamp => 512,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+
+    #[test]
+    fn test_error_codes_are_stable() {
+        // Codes are part of the guests' interface: changing one breaks consumers
+        let cases: [(VerificationError, u16); 7] = [
+            (VerificationError::ZeroSubjectDigest, 101),
+            (VerificationError::IdentityPolicyRejected(String::new()), 117),
+            (VerificationError::IdentityFieldMissing(IdentityField::Email), 129),
+            (CertificateError::ValidityPeriod.into(), 202),
+            (SignatureError::InvalidSignature.into(), 302),
+            (TimestampError::MissingTSAChain.into(), 407),
+            (TransparencyError::InclusionProofFailed.into(), 502),
+        ];
+        for (error, code) in cases {
+            assert_eq!(error.code(), code, "{}", error);
+        }
+    }
+
+    #[test]
+    fn test_policy_violations_are_told_apart() {
+        let violation = TimestampError::UnexpectedMechanism {
+            required: "RFC 3161".to_string(),
+            actual: "Rekor".to_string(),
+        };
+        assert!(VerificationError::from(violation).is_policy_violation());
+        assert!(VerificationError::IdentityFieldMissing(IdentityField::Email).is_policy_violation());
+
+        // Invalid bundles and options stay invalid
+        assert!(!VerificationError::ZeroSubjectDigest.is_policy_violation());
+        assert!(!VerificationError::SubjectNotFound("app".to_string()).is_policy_violation());
+        assert!(!VerificationError::UnknownProfile("release".to_string()).is_policy_violation());
+        assert!(!VerificationError::from(TimestampError::MissingTSAChain).is_policy_violation());
+        assert!(!VerificationError::from(CertificateError::ValidityPeriod).is_policy_violation());
+    }
 }
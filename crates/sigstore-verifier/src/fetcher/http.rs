@@ -0,0 +1,232 @@
+//! HTTP client used by the remote fetchers
+//!
+//! The fetchers only need blocking GET requests, so they are written against the
+//! small [`HttpClient`] trait rather than a particular HTTP stack. Two clients
+//! are provided behind features:
+//!
+//! - [`ReqwestClient`] (`http-reqwest`, enabled by `fetcher`) - reqwest's
+//!   blocking client with its default TLS
+//! - [`UreqClient`] (`http-ureq`) - ureq with rustls and the bundled web PKI
+//!   roots, for static musl builds without a system TLS library
+//!
+//! With only `fetcher-core`, no HTTP stack is compiled in, and targets neither
+//! supports (e.g. wasm hosts with their own fetch API) implement [`HttpClient`]
+//! and pass it to the fetchers.
+
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use crate::error::VerificationError;
+
+/// `User-Agent` sent by the provided clients
+pub const USER_AGENT: &str = concat!("sigstore-verifier/", env!("CARGO_PKG_VERSION"));
+
+/// Response to a GET request
+///
+/// - status: HTTP status code
+/// - headers: Response headers as sent, names in any case
+/// - body: Complete response body
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HttpResponse {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl HttpResponse {
+    /// Whether the status is 2xx
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+
+    /// Value of the first header named `name`, compared case-insensitively
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// Blocking HTTP client the fetchers send their requests with
+///
+/// Implementations follow redirects and return every response, whatever its
+/// status; only failures to get a response at all (DNS, TLS, connection) are
+/// errors, reported as `VerificationError::HttpError`.
+pub trait HttpClient {
+    /// Send a GET request for `url` with the given headers
+    fn get(&self, url: &str, headers: &[(&str, &str)]) -> Result<HttpResponse, VerificationError>;
+}
+
+impl<C: HttpClient + ?Sized> HttpClient for &C {
+    fn get(&self, url: &str, headers: &[(&str, &str)]) -> Result<HttpResponse, VerificationError> {
+        (**self).get(url, headers)
+    }
+}
+
+impl<C: HttpClient + ?Sized> HttpClient for Box<C> {
+    fn get(&self, url: &str, headers: &[(&str, &str)]) -> Result<HttpResponse, VerificationError> {
+        (**self).get(url, headers)
+    }
+}
+
+/// The client of the enabled HTTP stack, reqwest if both are
+#[cfg(any(feature = "http-reqwest", feature = "http-ureq"))]
+pub fn default_client() -> Result<Box<dyn HttpClient>, VerificationError> {
+    #[cfg(feature = "http-reqwest")]
+    return Ok(Box::new(ReqwestClient::new()?));
+    #[cfg(not(feature = "http-reqwest"))]
+    return Ok(Box::new(UreqClient::new()));
+}
+
+/// Append `params` to `url` as a percent-encoded query string
+pub fn with_query(url: &str, params: &[(String, String)]) -> String {
+    fn encode(value: &str) -> String {
+        value
+            .bytes()
+            .map(|b| match b {
+                b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => (b as char).to_string(),
+                _ => format!("%{:02X}", b),
+            })
+            .collect()
+    }
+
+    if params.is_empty() {
+        return url.to_string();
+    }
+    let query: Vec<String> = params
+        .iter()
+        .map(|(key, value)| format!("{}={}", encode(key), encode(value)))
+        .collect();
+    let separator = if url.contains('?') { '&' } else { '?' };
+    format!("{}{}{}", url, separator, query.join("&"))
+}
+
+/// [`HttpClient`] backed by reqwest's blocking client
+#[cfg(feature = "http-reqwest")]
+#[derive(Debug, Clone)]
+pub struct ReqwestClient {
+    client: reqwest::blocking::Client,
+}
+
+#[cfg(feature = "http-reqwest")]
+impl ReqwestClient {
+    pub fn new() -> Result<Self, VerificationError> {
+        let client = reqwest::blocking::Client::builder()
+            .user_agent(USER_AGENT)
+            .build()
+            .map_err(|e| VerificationError::HttpError(e.to_string()))?;
+        Ok(Self { client })
+    }
+}
+
+#[cfg(feature = "http-reqwest")]
+impl HttpClient for ReqwestClient {
+    fn get(&self, url: &str, headers: &[(&str, &str)]) -> Result<HttpResponse, VerificationError> {
+        let mut request = self.client.get(url);
+        for (name, value) in headers {
+            request = request.header(*name, *value);
+        }
+        let response = request
+            .send()
+            .map_err(|e| VerificationError::HttpError(e.to_string()))?;
+        let status = response.status().as_u16();
+        let headers = response
+            .headers()
+            .iter()
+            .filter_map(|(name, value)| Some((name.to_string(), value.to_str().ok()?.to_string())))
+            .collect();
+        let body = response
+            .bytes()
+            .map_err(|e| VerificationError::HttpError(e.to_string()))?
+            .to_vec();
+        Ok(HttpResponse { status, headers, body })
+    }
+}
+
+/// [`HttpClient`] backed by ureq
+#[cfg(feature = "http-ureq")]
+#[derive(Debug, Clone)]
+pub struct UreqClient {
+    agent: ureq::Agent,
+}
+
+#[cfg(feature = "http-ureq")]
+impl UreqClient {
+    pub fn new() -> Self {
+        let config = ureq::Agent::config_builder()
+            .user_agent(USER_AGENT)
+            .http_status_as_error(false)
+            .build();
+        Self {
+            agent: ureq::Agent::new_with_config(config),
+        }
+    }
+}
+
+#[cfg(feature = "http-ureq")]
+impl Default for UreqClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "http-ureq")]
+impl HttpClient for UreqClient {
+    fn get(&self, url: &str, headers: &[(&str, &str)]) -> Result<HttpResponse, VerificationError> {
+        let mut request = self.agent.get(url);
+        for (name, value) in headers {
+            request = request.header(*name, *value);
+        }
+        let mut response = request
+            .call()
+            .map_err(|e| VerificationError::HttpError(e.to_string()))?;
+        let status = response.status().as_u16();
+        let headers = response
+            .headers()
+            .iter()
+            .filter_map(|(name, value)| Some((name.to_string(), value.to_str().ok()?.to_string())))
+            .collect();
+        // Trusted roots and bundles are small, but artifacts may exceed ureq's default limit
+        let body = response
+            .body_mut()
+            .with_config()
+            .limit(u64::MAX)
+            .read_to_vec()
+            .map_err(|e| VerificationError::HttpError(e.to_string()))?;
+        Ok(HttpResponse { status, headers, body })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    #[test]
+    fn test_response_header_is_case_insensitive() {
+        let response = HttpResponse {
+            status: 401,
+            headers: vec![("www-authenticate".to_string(), "Bearer realm=\"x\"".to_string())],
+            body: Vec::new(),
+        };
+        assert_eq!(response.header("WWW-Authenticate"), Some("Bearer realm=\"x\""));
+        assert_eq!(response.header("Accept"), None);
+        assert!(!response.is_success());
+    }
+
+    #[test]
+    fn test_with_query() {
+        let params = vec![
+            ("service".to_string(), "ghcr.io".to_string()),
+            ("scope".to_string(), "repository:owner/image:pull".to_string()),
+        ];
+        assert_eq!(
+            with_query("https://ghcr.io/token", &params),
+            "https://ghcr.io/token?service=ghcr.io&scope=repository%3Aowner%2Fimage%3Apull"
+        );
+        assert_eq!(with_query("https://x/t?a=1", &params[..1]), "https://x/t?a=1&service=ghcr.io");
+        assert_eq!(with_query("https://x/t", &[]), "https://x/t");
+    }
+}
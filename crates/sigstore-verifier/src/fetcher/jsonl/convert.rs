@@ -0,0 +1,463 @@
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use base64::prelude::*;
+use chrono::DateTime;
+use x509_parser::prelude::*;
+
+use crate::crypto::hash::sha256;
+use crate::fetcher::jsonl::types::{
+    CertChain as JsonlCertChain, Certificate, CertificateAuthority, Subject, TimestampAuthority,
+    TransparencyLogInstance, TrustedRoot, ValidityPeriod,
+};
+use crate::fetcher::trusted_root::parse_trusted_root_json;
+use crate::parser::certificate::parse_der_certificate;
+use crate::VerificationError;
+
+/// Media type of the trusted root documents produced by the conversions
+pub const TRUSTED_ROOT_MEDIA_TYPE: &str = "application/vnd.dev.sigstore.trustedroot+json;version=0.1";
+
+/// Kind of authority listed in a trusted root
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuthorityKind {
+    /// Fulcio certificate authority (chain excludes the signing leaf)
+    CertificateAuthority,
+    /// RFC 3161 timestamp authority (chain starts with the TSA signing certificate)
+    TimestampAuthority,
+}
+
+impl core::fmt::Display for AuthorityKind {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            AuthorityKind::CertificateAuthority => write!(f, "CA"),
+            AuthorityKind::TimestampAuthority => write!(f, "TSA"),
+        }
+    }
+}
+
+/// Kind of log listed in a trusted root
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogKind {
+    /// Rekor transparency log (`tlogs`)
+    Transparency,
+    /// Certificate transparency log (`ctlogs`)
+    CertificateTransparency,
+}
+
+impl core::fmt::Display for LogKind {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            LogKind::Transparency => write!(f, "tlog"),
+            LogKind::CertificateTransparency => write!(f, "ctlog"),
+        }
+    }
+}
+
+/// Human-readable description of one certificate in an authority chain
+#[derive(Debug, Clone)]
+pub struct CertificateSummary {
+    pub subject: String,
+    pub issuer: String,
+    pub not_before: String,
+    pub not_after: String,
+    /// SHA256 of the DER, as committed in the journal's certificate hashes
+    pub sha256: [u8; 32],
+}
+
+/// Human-readable description of one authority in a trusted root
+#[derive(Debug, Clone)]
+pub struct AuthoritySummary {
+    pub kind: AuthorityKind,
+    pub uri: String,
+    pub subject: String,
+    pub valid_from: Option<String>,
+    pub valid_until: Option<String>,
+    pub certificates: Vec<CertificateSummary>,
+}
+
+/// Human-readable description of one log in a trusted root
+#[derive(Debug, Clone)]
+pub struct LogSummary {
+    pub kind: LogKind,
+    pub base_url: String,
+    /// SHA256 of the DER public key, i.e. the log ID; `None` without a key
+    pub key_hash: Option<[u8; 32]>,
+    pub valid_from: Option<String>,
+    pub valid_until: Option<String>,
+}
+
+/// Parse a single (possibly pretty-printed) `trusted_root.json` document.
+///
+/// See [`parse_trusted_root_json`](crate::fetcher::trusted_root::parse_trusted_root_json).
+///
+/// # Arguments
+/// * `content` - Contents of a trusted_root.json file as distributed via TUF
+///
+/// # Returns
+/// The parsed TrustedRoot
+pub fn load_trusted_root_json(content: &str) -> Result<TrustedRoot, VerificationError> {
+    parse_trusted_root_json(content)
+}
+
+/// Serialize trusted roots to the JSONL format, one compact document per line.
+pub fn trusted_roots_to_jsonl(roots: &[TrustedRoot]) -> Result<String, VerificationError> {
+    let mut output = String::new();
+    for root in roots {
+        output.push_str(&serde_json::to_string(root)?);
+        output.push('\n');
+    }
+    Ok(output)
+}
+
+/// Merge several trusted roots (e.g. the lines of a JSONL file) into one document.
+///
+/// Authorities and logs are concatenated in order; the media type of the first
+/// root is kept.
+pub fn merge_trusted_roots(roots: &[TrustedRoot]) -> Option<TrustedRoot> {
+    let mut merged = roots.first()?.clone();
+    for root in &roots[1..] {
+        merged.tlogs.extend(root.tlogs.iter().cloned());
+        merged.certificate_authorities.extend(root.certificate_authorities.iter().cloned());
+        merged.ctlogs.extend(root.ctlogs.iter().cloned());
+        merged.timestamp_authorities.extend(root.timestamp_authorities.iter().cloned());
+    }
+    Some(merged)
+}
+
+/// Encode an authority certificate chain as concatenated PEM blocks, in chain order.
+pub fn cert_chain_to_pem(chain: &JsonlCertChain) -> Result<String, VerificationError> {
+    let mut output = String::new();
+    for der in decode_chain(chain)? {
+        output.push_str(&::pem::encode(&::pem::Pem::new("CERTIFICATE", der)));
+    }
+    Ok(output)
+}
+
+/// Build an authority certificate chain from concatenated PEM certificates.
+///
+/// The certificates are kept in the order given, which must match the trusted
+/// root convention: `[intermediates..., root]` for CAs and `[leaf, intermediates..., root]`
+/// for TSAs.
+pub fn cert_chain_from_pem(pem_data: &str) -> Result<JsonlCertChain, VerificationError> {
+    let blocks = ::pem::parse_many(pem_data.as_bytes())
+        .map_err(|e| VerificationError::InvalidBundleFormat(format!("Failed to parse PEM: {}", e)))?;
+
+    let certificates: Vec<Certificate> = blocks
+        .into_iter()
+        .filter(|block| block.tag() == "CERTIFICATE")
+        .map(|block| Certificate {
+            raw_bytes: BASE64_STANDARD.encode(block.contents()),
+        })
+        .collect();
+
+    if certificates.is_empty() {
+        return Err(VerificationError::InvalidBundleFormat(
+            "No certificates found in PEM data".to_string(),
+        ));
+    }
+
+    Ok(JsonlCertChain { certificates })
+}
+
+/// Build a trusted root containing a single authority from a PEM chain.
+///
+/// The authority subject is taken from the last (root) certificate and the
+/// validity window starts when every certificate in the chain is valid, with
+/// no end date.
+///
+/// # Arguments
+/// * `pem_data` - Concatenated PEM certificates in trusted root order
+/// * `kind` - Whether the chain belongs to a CA or a TSA
+/// * `uri` - Authority URI (e.g. `https://fulcio.sigstore.dev`)
+pub fn trusted_root_from_pem(
+    pem_data: &str,
+    kind: AuthorityKind,
+    uri: &str,
+) -> Result<TrustedRoot, VerificationError> {
+    let cert_chain = cert_chain_from_pem(pem_data)?;
+    let ders = decode_chain(&cert_chain)?;
+
+    let mut start = None;
+    for der in &ders {
+        let cert = parse_der_certificate(der)?;
+        let not_before = cert.validity().not_before.timestamp();
+        start = Some(start.map_or(not_before, |s: i64| s.max(not_before)));
+    }
+    let root = parse_der_certificate(ders.last().unwrap())?;
+
+    let subject = Subject {
+        organization: first_attribute(root.subject().iter_organization()),
+        common_name: first_attribute(root.subject().iter_common_name()),
+    };
+    let valid_for = ValidityPeriod {
+        start: start
+            .and_then(|s| DateTime::from_timestamp(s, 0))
+            .map(|dt| dt.to_rfc3339_opts(chrono::SecondsFormat::Secs, true)),
+        end: None,
+    };
+
+    let mut trusted_root = TrustedRoot {
+        media_type: TRUSTED_ROOT_MEDIA_TYPE.to_string(),
+        tlogs: Vec::new(),
+        certificate_authorities: Vec::new(),
+        ctlogs: Vec::new(),
+        timestamp_authorities: Vec::new(),
+    };
+    match kind {
+        AuthorityKind::CertificateAuthority => {
+            trusted_root.certificate_authorities.push(CertificateAuthority {
+                subject,
+                uri: uri.to_string(),
+                cert_chain,
+                valid_for,
+            })
+        }
+        AuthorityKind::TimestampAuthority => {
+            trusted_root.timestamp_authorities.push(TimestampAuthority {
+                subject,
+                uri: uri.to_string(),
+                cert_chain,
+                valid_for,
+            })
+        }
+    }
+
+    Ok(trusted_root)
+}
+
+/// Iterate over all authorities of a trusted root with their kind
+pub fn authorities(
+    root: &TrustedRoot,
+) -> impl Iterator<Item = (AuthorityKind, &str, &Subject, &JsonlCertChain, &ValidityPeriod)> {
+    let cas = root.certificate_authorities.iter().map(|ca| {
+        (AuthorityKind::CertificateAuthority, ca.uri.as_str(), &ca.subject, &ca.cert_chain, &ca.valid_for)
+    });
+    let tsas = root.timestamp_authorities.iter().map(|tsa| {
+        (AuthorityKind::TimestampAuthority, tsa.uri.as_str(), &tsa.subject, &tsa.cert_chain, &tsa.valid_for)
+    });
+    cas.chain(tsas)
+}
+
+/// Check that a trusted root is usable for verification.
+///
+/// Every authority must have a non-empty chain of parseable DER certificates
+/// where each certificate is issued by the next one, and a well-formed validity
+/// window whose start precedes its end.
+pub fn validate_trusted_root(root: &TrustedRoot) -> Result<(), VerificationError> {
+    if !root.media_type.contains("sigstore.trustedroot") {
+        return Err(VerificationError::InvalidBundleFormat(format!(
+            "Unexpected trusted root media type: {}",
+            root.media_type
+        )));
+    }
+
+    for (kind, uri, _, chain, valid_for) in authorities(root) {
+        let context = |msg: String| VerificationError::InvalidBundleFormat(format!("{} {}: {}", kind, uri, msg));
+
+        let ders = decode_chain(chain).map_err(|e| context(e.to_string()))?;
+        let certs = ders
+            .iter()
+            .map(|der| parse_der_certificate(der))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| context(e.to_string()))?;
+
+        for pair in certs.windows(2) {
+            if pair[0].issuer() != pair[1].subject() {
+                return Err(context(format!(
+                    "certificate '{}' is not issued by the next certificate '{}'",
+                    pair[0].subject(),
+                    pair[1].subject()
+                )));
+            }
+        }
+
+        let start = valid_for
+            .start
+            .as_deref()
+            .map(DateTime::parse_from_rfc3339)
+            .transpose()
+            .map_err(|e| context(format!("invalid validFor.start: {}", e)))?;
+        let end = valid_for
+            .end
+            .as_deref()
+            .map(DateTime::parse_from_rfc3339)
+            .transpose()
+            .map_err(|e| context(format!("invalid validFor.end: {}", e)))?;
+        if start.is_none() {
+            return Err(context("missing validFor.start".to_string()));
+        }
+        if let (Some(start), Some(end)) = (start, end) {
+            if start > end {
+                return Err(context("validFor.start is after validFor.end".to_string()));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Summarize the authorities in a trusted root and their validity windows.
+pub fn summarize_trusted_root(root: &TrustedRoot) -> Result<Vec<AuthoritySummary>, VerificationError> {
+    let mut summaries = Vec::new();
+
+    for (kind, uri, subject, chain, valid_for) in authorities(root) {
+        let mut certificates = Vec::new();
+        for der in decode_chain(chain)? {
+            let cert = parse_der_certificate(&der)?;
+            certificates.push(CertificateSummary {
+                subject: cert.subject().to_string(),
+                issuer: cert.issuer().to_string(),
+                not_before: cert.validity().not_before.to_string(),
+                not_after: cert.validity().not_after.to_string(),
+                sha256: sha256(&der),
+            });
+        }
+
+        summaries.push(AuthoritySummary {
+            kind,
+            uri: uri.to_string(),
+            subject: format!("{} / {}", subject.organization, subject.common_name),
+            valid_from: valid_for.start.clone(),
+            valid_until: valid_for.end.clone(),
+            certificates,
+        });
+    }
+
+    Ok(summaries)
+}
+
+/// Summarize the transparency and certificate transparency logs in a trusted root.
+pub fn summarize_logs(root: &TrustedRoot) -> Result<Vec<LogSummary>, VerificationError> {
+    let tlogs = root.tlogs.iter().map(|log| (LogKind::Transparency, log));
+    let ctlogs = root.ctlogs.iter().map(|log| (LogKind::CertificateTransparency, log));
+
+    tlogs
+        .chain(ctlogs)
+        .map(|(kind, log): (LogKind, &TransparencyLogInstance)| {
+            let public_key = log.public_key.as_ref();
+            let key_hash = public_key
+                .and_then(|key| key.raw_bytes.as_deref())
+                .map(|raw| BASE64_STANDARD.decode(raw))
+                .transpose()
+                .map_err(|e| {
+                    VerificationError::InvalidBundleFormat(format!(
+                        "Failed to decode public key of {} {}: {}",
+                        kind, log.base_url, e
+                    ))
+                })?
+                .map(|der| sha256(&der));
+            let valid_for = public_key.and_then(|key| key.valid_for.as_ref());
+
+            Ok(LogSummary {
+                kind,
+                base_url: log.base_url.clone(),
+                key_hash,
+                valid_from: valid_for.and_then(|period| period.start.clone()),
+                valid_until: valid_for.and_then(|period| period.end.clone()),
+            })
+        })
+        .collect()
+}
+
+fn decode_chain(chain: &JsonlCertChain) -> Result<Vec<Vec<u8>>, VerificationError> {
+    if chain.certificates.is_empty() {
+        return Err(VerificationError::InvalidBundleFormat(
+            "Certificate chain is empty".to_string(),
+        ));
+    }
+
+    chain
+        .certificates
+        .iter()
+        .map(|cert| {
+            BASE64_STANDARD.decode(&cert.raw_bytes).map_err(|e| {
+                VerificationError::InvalidBundleFormat(format!("Failed to decode certificate: {}", e))
+            })
+        })
+        .collect()
+}
+
+fn first_attribute<'a>(mut attrs: impl Iterator<Item = &'a AttributeTypeAndValue<'a>>) -> String {
+    attrs
+        .next()
+        .and_then(|attr| attr.as_str().ok())
+        .unwrap_or_default()
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fetcher::jsonl::parser::load_trusted_root_from_jsonl;
+
+    fn sample_roots() -> Vec<TrustedRoot> {
+        let mut path = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        path.push("../../samples/trusted_root.jsonl");
+        let content = std::fs::read_to_string(path).expect("Failed to read trusted_root.jsonl");
+        load_trusted_root_from_jsonl(&content).unwrap()
+    }
+
+    #[test]
+    fn test_jsonl_json_roundtrip() {
+        let roots = sample_roots();
+        let merged = merge_trusted_roots(&roots).unwrap();
+        let total_cas: usize = roots.iter().map(|r| r.certificate_authorities.len()).sum();
+        assert_eq!(merged.certificate_authorities.len(), total_cas);
+
+        let pretty = serde_json::to_string_pretty(&merged).unwrap();
+        let reparsed = load_trusted_root_json(&pretty).unwrap();
+        let jsonl = trusted_roots_to_jsonl(&[reparsed]).unwrap();
+        assert_eq!(jsonl.lines().count(), 1);
+        assert_eq!(load_trusted_root_from_jsonl(&jsonl).unwrap()[0].timestamp_authorities.len(),
+            merged.timestamp_authorities.len());
+    }
+
+    #[test]
+    fn test_pem_roundtrip() {
+        let roots = sample_roots();
+        let ca = roots[1].certificate_authorities.last().unwrap();
+
+        let pem_chain = cert_chain_to_pem(&ca.cert_chain).unwrap();
+        let rebuilt = trusted_root_from_pem(&pem_chain, AuthorityKind::CertificateAuthority, &ca.uri).unwrap();
+
+        let rebuilt_ca = &rebuilt.certificate_authorities[0];
+        assert_eq!(rebuilt_ca.subject.common_name, ca.subject.common_name);
+        let original: Vec<_> = ca.cert_chain.certificates.iter().map(|c| &c.raw_bytes).collect();
+        let converted: Vec<_> = rebuilt_ca.cert_chain.certificates.iter().map(|c| &c.raw_bytes).collect();
+        assert_eq!(original, converted);
+        assert!(validate_trusted_root(&rebuilt).is_ok());
+    }
+
+    #[test]
+    fn test_validate_and_summarize_sample() {
+        for root in sample_roots() {
+            assert!(validate_trusted_root(&root).is_ok());
+            let summary = summarize_trusted_root(&root).unwrap();
+            assert_eq!(
+                summary.len(),
+                root.certificate_authorities.len() + root.timestamp_authorities.len()
+            );
+        }
+    }
+
+    #[test]
+    fn test_summarize_logs_hashes_keys() {
+        let root = sample_roots().remove(0);
+        let logs = summarize_logs(&root).unwrap();
+        assert_eq!(logs.len(), root.tlogs.len() + root.ctlogs.len());
+
+        // The key hash of a Rekor key is its log ID
+        let rekor = &root.tlogs[0];
+        let log_id = BASE64_STANDARD.decode(&rekor.log_id.as_ref().unwrap().key_id).unwrap();
+        assert_eq!(logs[0].kind, LogKind::Transparency);
+        assert_eq!(logs[0].key_hash.map(|hash| hash.to_vec()), Some(log_id));
+    }
+
+    #[test]
+    fn test_validate_rejects_reordered_chain() {
+        let mut root = sample_roots().remove(1);
+        root.certificate_authorities[0].cert_chain.certificates.reverse();
+        root.timestamp_authorities.clear();
+        assert!(validate_trusted_root(&root).is_err());
+    }
+}
@@ -0,0 +1,159 @@
+//! Differences between two versions of a trusted root
+//!
+//! A trusted root update changes which certificates and logs the provers
+//! accept, so operators review it before rolling it out. [`diff_trusted_roots`]
+//! compares the authorities and logs of the old and new files, across all of
+//! their documents:
+//!
+//! - Authorities are identified by kind, URI and the SHA256 of every
+//!   certificate of their chain, so a re-issued chain shows up as one removed
+//!   and one added authority
+//! - Logs are identified by kind, base URL and the SHA256 of their key
+//!
+//! An entry present in both with another validity window is reported as changed.
+
+use alloc::vec::Vec;
+use crate::fetcher::jsonl::convert::{summarize_logs, summarize_trusted_root, AuthoritySummary, LogSummary};
+use crate::fetcher::jsonl::types::TrustedRoot;
+use crate::VerificationError;
+
+/// One difference between two trusted roots
+#[derive(Debug, Clone)]
+pub enum TrustRootChange {
+    AuthorityAdded(AuthoritySummary),
+    AuthorityRemoved(AuthoritySummary),
+    AuthorityValidityChanged { old: AuthoritySummary, new: AuthoritySummary },
+    LogAdded(LogSummary),
+    LogRemoved(LogSummary),
+    LogValidityChanged { old: LogSummary, new: LogSummary },
+}
+
+/// Compare the authorities and logs of two trusted roots
+///
+/// # Arguments
+/// * `old` - Documents of the trusted root in use
+/// * `new` - Documents of the candidate trusted root
+///
+/// # Returns
+/// Removed and changed entries in the order of `old`, followed by added entries
+/// in the order of `new`. Empty if both accept exactly the same authorities and
+/// logs for the same periods.
+pub fn diff_trusted_roots(old: &[TrustedRoot], new: &[TrustedRoot]) -> Result<Vec<TrustRootChange>, VerificationError> {
+    let mut changes = Vec::new();
+
+    let authority_key = |authority: &AuthoritySummary| {
+        let fingerprints: Vec<[u8; 32]> = authority.certificates.iter().map(|cert| cert.sha256).collect();
+        (authority.kind, authority.uri.clone(), fingerprints)
+    };
+    let (removed, changed, added) = diff_by_key(
+        summarize_all(old, summarize_trusted_root)?,
+        summarize_all(new, summarize_trusted_root)?,
+        authority_key,
+        |old, new| old.valid_from == new.valid_from && old.valid_until == new.valid_until,
+    );
+    changes.extend(removed.into_iter().map(TrustRootChange::AuthorityRemoved));
+    changes.extend(
+        changed
+            .into_iter()
+            .map(|(old, new)| TrustRootChange::AuthorityValidityChanged { old, new }),
+    );
+    changes.extend(added.into_iter().map(TrustRootChange::AuthorityAdded));
+
+    let log_key = |log: &LogSummary| (log.kind, log.base_url.clone(), log.key_hash);
+    let (removed, changed, added) = diff_by_key(
+        summarize_all(old, summarize_logs)?,
+        summarize_all(new, summarize_logs)?,
+        log_key,
+        |old, new| old.valid_from == new.valid_from && old.valid_until == new.valid_until,
+    );
+    changes.extend(removed.into_iter().map(TrustRootChange::LogRemoved));
+    changes.extend(
+        changed
+            .into_iter()
+            .map(|(old, new)| TrustRootChange::LogValidityChanged { old, new }),
+    );
+    changes.extend(added.into_iter().map(TrustRootChange::LogAdded));
+
+    Ok(changes)
+}
+
+fn summarize_all<T>(
+    roots: &[TrustedRoot],
+    summarize: fn(&TrustedRoot) -> Result<Vec<T>, VerificationError>,
+) -> Result<Vec<T>, VerificationError> {
+    let mut summaries = Vec::new();
+    for root in roots {
+        summaries.extend(summarize(root)?);
+    }
+    Ok(summaries)
+}
+
+/// Split two lists into the entries only in `old`, the pairs present in both
+/// that differ, and the entries only in `new`
+fn diff_by_key<T, K: PartialEq>(
+    old: Vec<T>,
+    mut new: Vec<T>,
+    key: impl Fn(&T) -> K,
+    same: impl Fn(&T, &T) -> bool,
+) -> (Vec<T>, Vec<(T, T)>, Vec<T>) {
+    let mut removed = Vec::new();
+    let mut changed = Vec::new();
+    for entry in old {
+        let entry_key = key(&entry);
+        match new.iter().position(|candidate| key(candidate) == entry_key) {
+            Some(index) => {
+                let counterpart = new.remove(index);
+                if !same(&entry, &counterpart) {
+                    changed.push((entry, counterpart));
+                }
+            }
+            None => removed.push(entry),
+        }
+    }
+    (removed, changed, new)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fetcher::jsonl::parser::load_trusted_root_from_jsonl;
+    use alloc::string::ToString;
+
+    fn sample_roots() -> Vec<TrustedRoot> {
+        let mut path = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        path.push("../../samples/trusted_root.jsonl");
+        let content = std::fs::read_to_string(path).expect("Failed to read trusted_root.jsonl");
+        load_trusted_root_from_jsonl(&content).unwrap()
+    }
+
+    #[test]
+    fn test_identical_roots_have_no_changes() {
+        let roots = sample_roots();
+        assert!(diff_trusted_roots(&roots, &roots).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_diff_reports_added_removed_and_changed_entries() {
+        let old = sample_roots();
+        let mut new = old.clone();
+        let expired = "2030-01-01T00:00:00Z".to_string();
+        new[1].certificate_authorities[0].valid_for.end = Some(expired.clone());
+        let rekor = new[0].tlogs.remove(0);
+
+        let changes = diff_trusted_roots(&old, &new).unwrap();
+        assert_eq!(changes.len(), 2, "{:?}", changes);
+        assert!(matches!(
+            &changes[0],
+            TrustRootChange::AuthorityValidityChanged { new, .. } if new.valid_until == Some(expired)
+        ));
+        assert!(matches!(&changes[1], TrustRootChange::LogRemoved(log) if log.base_url == rekor.base_url));
+
+        // A re-issued chain is another authority
+        let changes = diff_trusted_roots(&new, &old).unwrap();
+        assert!(matches!(changes.last(), Some(TrustRootChange::LogAdded(_))));
+        new[1].certificate_authorities[0].cert_chain.certificates.pop();
+        let changes = diff_trusted_roots(&old, &new).unwrap();
+        assert!(matches!(changes[0], TrustRootChange::AuthorityRemoved(_)));
+        assert!(changes.iter().any(|change| matches!(change, TrustRootChange::AuthorityAdded(_))));
+    }
+}
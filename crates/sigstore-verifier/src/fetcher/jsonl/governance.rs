@@ -0,0 +1,314 @@
+//! Signed trusted roots
+//!
+//! The trusted root decides which CAs and TSAs a prover accepts, so anyone who
+//! can edit the file on a prover host can have it prove bundles from a CA of
+//! their choosing. A trusted root can therefore be shipped wrapped in a DSSE
+//! envelope signed by a governance key, whose public key is configured on the
+//! host separately from the file:
+//!
+//! ```text
+//! {"payloadType":"application/vnd.sigstore-verifier.trusted-root+jsonl",
+//!  "payload":"<base64 JSONL>",
+//!  "signatures":[{"keyid":"<optional hint>","sig":"<base64>"}]}
+//! ```
+//!
+//! The envelope is verified before the JSONL inside it is parsed. With keys
+//! configured, unsigned files are refused; without keys, envelopes are refused
+//! instead of being unwrapped unverified.
+
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use base64::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::crypto::signature::{PublicKey, SignatureEncoding};
+use crate::error::SignatureError;
+use crate::fetcher::jsonl::types::TrustedRoot;
+use crate::fetcher::trusted_root::load_trusted_roots;
+use crate::verifier::signature::pae;
+use crate::VerificationError;
+
+/// DSSE payload type of a signed trusted root
+pub const TRUSTED_ROOT_PAYLOAD_TYPE: &str = "application/vnd.sigstore-verifier.trusted-root+jsonl";
+
+/// DSSE envelope around a trusted root JSONL file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrustRootEnvelope {
+    pub payload_type: String,
+    pub payload: String, // Base64-encoded JSONL
+    pub signatures: Vec<TrustRootSignature>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrustRootSignature {
+    // Informational; every signature is checked against every configured key
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub keyid: String,
+    pub sig: String, // Base64-encoded
+}
+
+impl TrustRootEnvelope {
+    /// Wrap `jsonl` with a signature over [`trust_root_pae`]`(jsonl)`
+    pub fn new(jsonl: &str, keyid: String, signature: &[u8]) -> Self {
+        Self {
+            payload_type: TRUSTED_ROOT_PAYLOAD_TYPE.to_string(),
+            payload: BASE64_STANDARD.encode(jsonl),
+            signatures: vec![TrustRootSignature {
+                keyid,
+                sig: BASE64_STANDARD.encode(signature),
+            }],
+        }
+    }
+
+    /// Parse `content` as an envelope, if it is one
+    ///
+    /// A single-line JSONL trusted root is a JSON object too; it is told apart
+    /// by the envelope's `payloadType`, `payload` and `signatures` fields.
+    pub fn parse(content: &str) -> Option<Self> {
+        serde_json::from_str(content.trim()).ok()
+    }
+
+    /// Verify the envelope and return the JSONL it carries
+    ///
+    /// # Arguments
+    /// * `keys` - Governance keys, one valid signature by any of them suffices
+    ///
+    /// # Errors
+    /// Returns `VerificationError::UnverifiedTrustRoot` if `keys` is empty,
+    /// `VerificationError::TrustRootSignature` if no signature verifies, and
+    /// `VerificationError::InvalidBundleFormat` for a malformed envelope
+    pub fn verify(&self, keys: &TrustRootKeys) -> Result<String, VerificationError> {
+        if keys.is_empty() {
+            return Err(VerificationError::UnverifiedTrustRoot);
+        }
+        if self.payload_type != TRUSTED_ROOT_PAYLOAD_TYPE {
+            return Err(VerificationError::InvalidBundleFormat(format!(
+                "Trusted root envelope has payload type '{}', expected '{}'",
+                self.payload_type, TRUSTED_ROOT_PAYLOAD_TYPE
+            )));
+        }
+
+        let payload = BASE64_STANDARD.decode(&self.payload)?;
+        let message = pae(&self.payload_type, &payload);
+        if !keys.verify_any(&message, &self.signatures) {
+            return Err(VerificationError::TrustRootSignature);
+        }
+
+        String::from_utf8(payload).map_err(|_| {
+            VerificationError::InvalidBundleFormat("Trusted root payload is not UTF-8".to_string())
+        })
+    }
+}
+
+/// Public keys allowed to sign trusted roots
+///
+/// P-256, P-384, Ed25519 and RSA keys are supported. Several keys can be configured
+/// to rotate the governance key without a flag day.
+#[derive(Debug, Clone, Default)]
+pub struct TrustRootKeys {
+    keys: Vec<PublicKey>,
+}
+
+impl TrustRootKeys {
+    /// Parse every `PUBLIC KEY` block in `pem`
+    pub fn from_pem(pem: &str) -> Result<Self, SignatureError> {
+        let blocks = ::pem::parse_many(pem).map_err(|e| SignatureError::PublicKeyParse(e.to_string()))?;
+        if blocks.is_empty() {
+            return Err(SignatureError::PublicKeyParse("no PEM block found".to_string()));
+        }
+        let keys = blocks
+            .iter()
+            .map(|block| PublicKey::from_pem(&::pem::encode(block)))
+            .collect::<Result<_, _>>()?;
+        Ok(Self { keys })
+    }
+
+    /// Add a key
+    pub fn push(&mut self, key: PublicKey) {
+        self.keys.push(key);
+    }
+
+    /// Move all keys of `other` into these
+    pub fn append(&mut self, other: &mut TrustRootKeys) {
+        self.keys.append(&mut other.keys);
+    }
+
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// Whether any of the keys verifies any of the (base64) DSSE `signatures`
+    /// over `message`
+    pub fn verify_any(&self, message: &[u8], signatures: &[TrustRootSignature]) -> bool {
+        signatures.iter().any(|signature| {
+            let Ok(sig) = BASE64_STANDARD.decode(&signature.sig) else {
+                return false;
+            };
+            self.keys.iter().any(|key| {
+                key.verify_signature_with_encoding(message, &sig, SignatureEncoding::Auto)
+                    .is_ok()
+            })
+        })
+    }
+}
+
+/// DSSE pre-authentication encoding a trusted root signature is made over
+pub fn trust_root_pae(jsonl: &str) -> Vec<u8> {
+    pae(TRUSTED_ROOT_PAYLOAD_TYPE, jsonl.as_bytes())
+}
+
+/// Load a trusted root that is signed by one of `keys`
+///
+/// Without keys this is [`load_trusted_roots`], which refuses envelopes. With
+/// keys, `content` must be a [`TrustRootEnvelope`] and is only parsed once its
+/// signature verifies.
+///
+/// # Arguments
+/// * `content` - Envelope JSON, or a `trusted_root.json` document or JSONL
+///   content when no keys are configured
+/// * `keys` - Governance keys
+///
+/// # Returns
+/// Vector of parsed TrustedRoot objects, one per JSONL line
+///
+/// # Errors
+/// Returns `VerificationError::UnsignedTrustRoot` for a plain JSONL file when
+/// keys are configured, plus the errors of [`TrustRootEnvelope::verify`] and
+/// [`load_trusted_roots`]
+pub fn load_signed_trusted_root(
+    content: &str,
+    keys: &TrustRootKeys,
+) -> Result<Vec<TrustedRoot>, VerificationError> {
+    if keys.is_empty() {
+        return load_trusted_roots(content);
+    }
+    let envelope = TrustRootEnvelope::parse(content).ok_or(VerificationError::UnsignedTrustRoot)?;
+    let jsonl = envelope.verify(keys)?;
+    load_trusted_roots(&jsonl)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fetcher::jsonl::parser::load_trusted_root_from_jsonl;
+
+    fn sample_jsonl() -> String {
+        let mut path = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        path.push("../../samples/trusted_root.jsonl");
+        std::fs::read_to_string(path).expect("Failed to read trusted_root.jsonl")
+    }
+
+    fn p256_signed(jsonl: &str, seed: u8) -> (TrustRootEnvelope, TrustRootKeys) {
+        use p256::ecdsa::{signature::Signer, Signature, SigningKey};
+
+        let signing_key = SigningKey::from_slice(&[seed; 32]).unwrap();
+        let signature: Signature = signing_key.sign(&trust_root_pae(jsonl));
+        let mut keys = TrustRootKeys::default();
+        keys.push(PublicKey::P256(*signing_key.verifying_key()));
+        let envelope = TrustRootEnvelope::new(jsonl, "governance".to_string(), signature.to_der().as_bytes());
+        (envelope, keys)
+    }
+
+    #[test]
+    fn test_signed_trusted_root_roundtrip() {
+        let jsonl = sample_jsonl();
+        let (envelope, keys) = p256_signed(&jsonl, 3);
+        let content = serde_json::to_string(&envelope).unwrap();
+
+        let roots = load_signed_trusted_root(&content, &keys).unwrap();
+        assert_eq!(roots.len(), load_trusted_root_from_jsonl(&jsonl).unwrap().len());
+    }
+
+    #[test]
+    fn test_ed25519_signed_trusted_root() {
+        use ed25519_dalek::{Signer, SigningKey};
+
+        let jsonl = sample_jsonl();
+        let signing_key = SigningKey::from_bytes(&[1u8; 32]);
+        let signature = signing_key.sign(&trust_root_pae(&jsonl));
+        let envelope = TrustRootEnvelope::new(&jsonl, String::new(), &signature.to_bytes());
+
+        // Rotation: the signing key is the second configured key
+        let (_, mut keys) = p256_signed(&jsonl, 3);
+        keys.push(PublicKey::Ed25519(signing_key.verifying_key()));
+        assert!(envelope.verify(&keys).is_ok());
+    }
+
+    #[test]
+    fn test_tampered_trusted_root_rejected() {
+        let jsonl = sample_jsonl();
+        let (mut envelope, keys) = p256_signed(&jsonl, 3);
+        envelope.payload = BASE64_STANDARD.encode(jsonl.replacen("fulcio", "fulcio-evil", 1));
+
+        assert!(matches!(envelope.verify(&keys), Err(VerificationError::TrustRootSignature)));
+    }
+
+    #[test]
+    fn test_wrong_key_rejected() {
+        let jsonl = sample_jsonl();
+        let (envelope, _) = p256_signed(&jsonl, 3);
+        let (_, other_keys) = p256_signed(&jsonl, 4);
+
+        assert!(matches!(envelope.verify(&other_keys), Err(VerificationError::TrustRootSignature)));
+    }
+
+    #[test]
+    fn test_wrong_payload_type_rejected() {
+        let jsonl = sample_jsonl();
+        let (mut envelope, keys) = p256_signed(&jsonl, 3);
+        envelope.payload_type = "application/vnd.in-toto+json".to_string();
+
+        assert!(matches!(envelope.verify(&keys), Err(VerificationError::InvalidBundleFormat(_))));
+    }
+
+    #[test]
+    fn test_envelope_requires_keys() {
+        let jsonl = sample_jsonl();
+        let (envelope, _) = p256_signed(&jsonl, 3);
+        let content = serde_json::to_string(&envelope).unwrap();
+
+        // Neither loader unwraps an envelope it cannot verify
+        assert!(matches!(
+            load_trusted_root_from_jsonl(&content),
+            Err(VerificationError::UnverifiedTrustRoot)
+        ));
+        assert!(matches!(
+            load_signed_trusted_root(&content, &TrustRootKeys::default()),
+            Err(VerificationError::UnverifiedTrustRoot)
+        ));
+    }
+
+    #[test]
+    fn test_keys_require_envelope() {
+        let jsonl = sample_jsonl();
+        let (_, keys) = p256_signed(&jsonl, 3);
+
+        assert!(matches!(
+            load_signed_trusted_root(&jsonl, &keys),
+            Err(VerificationError::UnsignedTrustRoot)
+        ));
+    }
+
+    #[test]
+    fn test_keys_from_pem() {
+        use p256::ecdsa::SigningKey;
+        use p256::pkcs8::{EncodePublicKey, LineEnding};
+
+        let pem: String = [3u8, 4]
+            .iter()
+            .map(|seed| {
+                let signing_key = SigningKey::from_slice(&[*seed; 32]).unwrap();
+                signing_key.verifying_key().to_public_key_pem(LineEnding::LF).unwrap()
+            })
+            .collect();
+        assert_eq!(TrustRootKeys::from_pem(&pem).unwrap().len(), 2);
+        assert!(TrustRootKeys::from_pem("").is_err());
+    }
+}
@@ -0,0 +1,544 @@
+//! Schema validation for JSONL trusted roots
+//!
+//! Trusted root files are often edited by hand, and a serde error such as
+//! "missing field `start` at line 1 column 4023" says little about which
+//! authority is broken. The checks here walk each line as plain JSON and report
+//! every problem with its line number and field path, e.g.
+//! `line 2: certificateAuthorities[3].validFor.start: invalid RFC3339 timestamp`.
+//!
+//! Fields are visited in schema order, so the same file always produces the
+//! same issues in the same order.
+
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use base64::prelude::*;
+use chrono::DateTime;
+use serde_json::{Map, Value};
+use core::fmt;
+
+use crate::parser::certificate::parse_der_certificate;
+
+/// Severity of a lint issue
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    /// The file is usable but likely not what was intended
+    Warning,
+    /// The file will be rejected or fail during verification
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Severity::Warning => write!(f, "warning"),
+            Severity::Error => write!(f, "error"),
+        }
+    }
+}
+
+/// A single problem found in a JSONL trusted root
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LintIssue {
+    /// 1-based line number in the JSONL content
+    pub line: usize,
+    /// Field path within the line, e.g. `timestampAuthorities[0].certChain`
+    pub path: String,
+    pub severity: Severity,
+    pub message: String,
+}
+
+impl fmt::Display for LintIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.path.is_empty() {
+            write!(f, "line {}: {}", self.line, self.message)
+        } else {
+            write!(f, "line {}: {}: {}", self.line, self.path, self.message)
+        }
+    }
+}
+
+const ROOT_FIELDS: &[&str] = &[
+    "mediaType",
+    "tlogs",
+    "certificateAuthorities",
+    "ctlogs",
+    "timestampAuthorities",
+];
+const AUTHORITY_FIELDS: &[&str] = &["subject", "uri", "certChain", "validFor"];
+const LOG_FIELDS: &[&str] = &["baseUrl", "hashAlgorithm", "publicKey", "logId"];
+
+/// Validate JSONL trusted root content line by line.
+///
+/// Blank lines are skipped. An empty result means the content is well formed;
+/// otherwise issues are returned in line and field order.
+///
+/// # Arguments
+/// * `content` - JSONL content where each line is a separate trusted root
+///
+/// # Returns
+/// All issues found, errors and warnings
+pub fn lint_trusted_root_jsonl(content: &str) -> Vec<LintIssue> {
+    let mut issues = Vec::new();
+    let mut found_root = false;
+
+    for (index, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        found_root = true;
+        issues.extend(lint_trusted_root_line(line, index + 1));
+    }
+
+    if !found_root {
+        issues.push(LintIssue {
+            line: 1,
+            path: String::new(),
+            severity: Severity::Error,
+            message: "no trusted roots found".to_string(),
+        });
+    }
+
+    issues
+}
+
+/// Validate a single JSONL line holding one trusted root.
+///
+/// # Arguments
+/// * `line` - The JSON document on the line
+/// * `line_num` - 1-based line number used in the reported issues
+pub fn lint_trusted_root_line(line: &str, line_num: usize) -> Vec<LintIssue> {
+    let mut lint = Linter {
+        line: line_num,
+        issues: Vec::new(),
+    };
+
+    match serde_json::from_str::<Value>(line) {
+        Ok(value) => lint.root(&value),
+        Err(e) => lint.error("", format!("invalid JSON at column {}: {}", e.column(), e)),
+    }
+
+    lint.issues
+}
+
+struct Linter {
+    line: usize,
+    issues: Vec<LintIssue>,
+}
+
+impl Linter {
+    fn error(&mut self, path: &str, message: impl Into<String>) {
+        self.push(path, Severity::Error, message.into());
+    }
+
+    fn warning(&mut self, path: &str, message: impl Into<String>) {
+        self.push(path, Severity::Warning, message.into());
+    }
+
+    fn push(&mut self, path: &str, severity: Severity, message: String) {
+        self.issues.push(LintIssue {
+            line: self.line,
+            path: path.to_string(),
+            severity,
+            message,
+        });
+    }
+
+    fn root(&mut self, value: &Value) {
+        let Some(obj) = self.object("", value) else {
+            return;
+        };
+        self.unknown_fields("", obj, ROOT_FIELDS);
+
+        if let Some(media_type) = self.required_str("", obj, "mediaType") {
+            if !media_type.contains("sigstore.trustedroot") {
+                self.error(
+                    "mediaType",
+                    format!("expected a sigstore.trustedroot media type, got '{}'", media_type),
+                );
+            }
+        }
+
+        for field in ["tlogs", "ctlogs"] {
+            for (path, log) in self.optional_array("", obj, field) {
+                self.transparency_log(&path, log);
+            }
+        }
+
+        let mut authorities = 0;
+        for field in ["certificateAuthorities", "timestampAuthorities"] {
+            for (path, authority) in self.optional_array("", obj, field) {
+                self.authority(&path, authority, field == "timestampAuthorities");
+                authorities += 1;
+            }
+        }
+        if authorities == 0 {
+            self.warning("", "trusted root lists no certificate or timestamp authorities");
+        }
+    }
+
+    fn transparency_log(&mut self, path: &str, value: &Value) {
+        let Some(obj) = self.object(path, value) else {
+            return;
+        };
+        self.unknown_fields(path, obj, LOG_FIELDS);
+        self.required_str(path, obj, "baseUrl");
+        self.optional_str(path, obj, "hashAlgorithm");
+
+        if let Some(public_key) = obj.get("publicKey") {
+            let key_path = join(path, "publicKey");
+            if let Some(key) = self.object(&key_path, public_key) {
+                if let Some(raw) = self.optional_str(&key_path, key, "rawBytes") {
+                    self.base64(&join(&key_path, "rawBytes"), raw);
+                }
+                self.optional_str(&key_path, key, "keyDetails");
+                if let Some(valid_for) = key.get("validFor") {
+                    self.validity(&join(&key_path, "validFor"), valid_for, false);
+                }
+            }
+        }
+
+        if let Some(log_id) = obj.get("logId") {
+            let id_path = join(path, "logId");
+            if let Some(id) = self.object(&id_path, log_id) {
+                if let Some(key_id) = self.required_str(&id_path, id, "keyId") {
+                    self.base64(&join(&id_path, "keyId"), key_id);
+                }
+            }
+        }
+    }
+
+    fn authority(&mut self, path: &str, value: &Value, is_tsa: bool) {
+        let Some(obj) = self.object(path, value) else {
+            return;
+        };
+        self.unknown_fields(path, obj, AUTHORITY_FIELDS);
+
+        match obj.get("subject") {
+            Some(subject) => {
+                let subject_path = join(path, "subject");
+                if let Some(subject) = self.object(&subject_path, subject) {
+                    self.required_str(&subject_path, subject, "organization");
+                    self.required_str(&subject_path, subject, "commonName");
+                }
+            }
+            None => self.error(path, "missing field 'subject'"),
+        }
+
+        if let Some(uri) = self.required_str(path, obj, "uri") {
+            // Authorities are matched by host, so an empty URI can never be selected
+            if uri.trim().is_empty() {
+                self.error(&join(path, "uri"), "URI is empty");
+            }
+        }
+
+        match obj.get("certChain") {
+            Some(chain) => self.cert_chain(&join(path, "certChain"), chain, is_tsa),
+            None => self.error(path, "missing field 'certChain'"),
+        }
+
+        match obj.get("validFor") {
+            Some(valid_for) => self.validity(&join(path, "validFor"), valid_for, true),
+            None => self.error(path, "missing field 'validFor'"),
+        }
+    }
+
+    fn cert_chain(&mut self, path: &str, value: &Value, is_tsa: bool) {
+        let Some(obj) = self.object(path, value) else {
+            return;
+        };
+        let Some(certificates) = obj.get("certificates") else {
+            self.error(path, "missing field 'certificates'");
+            return;
+        };
+        let certs_path = join(path, "certificates");
+        let Some(certificates) = self.array(&certs_path, certificates) else {
+            return;
+        };
+        if certificates.is_empty() {
+            self.error(&certs_path, "certificate chain is empty");
+            return;
+        }
+
+        let mut ders = Vec::new();
+        for (index, cert) in certificates.iter().enumerate() {
+            let cert_path = format!("{}[{}]", certs_path, index);
+            let Some(cert) = self.object(&cert_path, cert) else {
+                continue;
+            };
+            let Some(raw) = self.required_str(&cert_path, cert, "rawBytes") else {
+                continue;
+            };
+            let raw_path = join(&cert_path, "rawBytes");
+            if let Some(der) = self.base64(&raw_path, raw) {
+                match parse_der_certificate(&der) {
+                    Ok(_) => ders.push(der),
+                    Err(e) => self.error(&raw_path, format!("not a DER certificate: {}", e)),
+                }
+            }
+        }
+
+        // Only check the ordering when every certificate could be decoded
+        if ders.len() != certificates.len() {
+            return;
+        }
+        let parsed: Vec<_> = ders.iter().filter_map(|der| parse_der_certificate(der).ok()).collect();
+        for (index, pair) in parsed.windows(2).enumerate() {
+            if pair[0].issuer() != pair[1].subject() {
+                self.error(
+                    &format!("{}[{}]", certs_path, index),
+                    format!(
+                        "issued by '{}', but the next certificate is '{}' (expected {} order)",
+                        pair[0].issuer(),
+                        pair[1].subject(),
+                        if is_tsa { "[leaf, intermediates..., root]" } else { "[intermediates..., root]" }
+                    ),
+                );
+            }
+        }
+    }
+
+    fn validity(&mut self, path: &str, value: &Value, require_start: bool) {
+        let Some(obj) = self.object(path, value) else {
+            return;
+        };
+
+        let start = match obj.get("start") {
+            Some(start) => self.timestamp(&join(path, "start"), start),
+            None => {
+                if require_start {
+                    self.error(
+                        path,
+                        "missing field 'start' (authorities without a start are never selected)",
+                    );
+                }
+                None
+            }
+        };
+        let end = obj.get("end").and_then(|end| self.timestamp(&join(path, "end"), end));
+
+        if let (Some(start), Some(end)) = (start, end) {
+            if start > end {
+                self.error(path, "start is after end");
+            }
+        }
+    }
+
+    fn timestamp(&mut self, path: &str, value: &Value) -> Option<i64> {
+        let Some(s) = value.as_str() else {
+            self.error(path, format!("expected an RFC3339 string, got {}", type_name(value)));
+            return None;
+        };
+        match DateTime::parse_from_rfc3339(s) {
+            Ok(dt) => Some(dt.timestamp()),
+            Err(e) => {
+                self.error(path, format!("invalid RFC3339 timestamp '{}': {}", s, e));
+                None
+            }
+        }
+    }
+
+    fn base64(&mut self, path: &str, value: &str) -> Option<Vec<u8>> {
+        match BASE64_STANDARD.decode(value) {
+            Ok(bytes) => Some(bytes),
+            Err(e) => {
+                self.error(path, format!("invalid base64: {}", e));
+                None
+            }
+        }
+    }
+
+    fn object<'a>(&mut self, path: &str, value: &'a Value) -> Option<&'a Map<String, Value>> {
+        let obj = value.as_object();
+        if obj.is_none() {
+            self.error(path, format!("expected an object, got {}", type_name(value)));
+        }
+        obj
+    }
+
+    fn array<'a>(&mut self, path: &str, value: &'a Value) -> Option<&'a Vec<Value>> {
+        let array = value.as_array();
+        if array.is_none() {
+            self.error(path, format!("expected an array, got {}", type_name(value)));
+        }
+        array
+    }
+
+    fn optional_array<'a>(
+        &mut self,
+        path: &str,
+        obj: &'a Map<String, Value>,
+        field: &str,
+    ) -> Vec<(String, &'a Value)> {
+        let field_path = join(path, field);
+        obj.get(field)
+            .and_then(|value| self.array(&field_path, value))
+            .map(|items| {
+                items
+                    .iter()
+                    .enumerate()
+                    .map(|(index, item)| (format!("{}[{}]", field_path, index), item))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    fn required_str<'a>(
+        &mut self,
+        path: &str,
+        obj: &'a Map<String, Value>,
+        field: &str,
+    ) -> Option<&'a str> {
+        if !obj.contains_key(field) {
+            self.error(path, format!("missing field '{}'", field));
+            return None;
+        }
+        self.optional_str(path, obj, field)
+    }
+
+    fn optional_str<'a>(
+        &mut self,
+        path: &str,
+        obj: &'a Map<String, Value>,
+        field: &str,
+    ) -> Option<&'a str> {
+        let value = obj.get(field)?;
+        let s = value.as_str();
+        if s.is_none() {
+            self.error(
+                &join(path, field),
+                format!("expected a string, got {}", type_name(value)),
+            );
+        }
+        s
+    }
+
+    /// Flag fields that only differ from a known field by case, which serde
+    /// would silently ignore (e.g. `validfor` instead of `validFor`)
+    fn unknown_fields(&mut self, path: &str, obj: &Map<String, Value>, known: &[&str]) {
+        for key in obj.keys() {
+            if known.contains(&key.as_str()) {
+                continue;
+            }
+            if let Some(expected) = known.iter().find(|k| k.eq_ignore_ascii_case(key)) {
+                self.error(
+                    &join(path, key),
+                    format!("unknown field, did you mean '{}'?", expected),
+                );
+            }
+        }
+    }
+}
+
+fn join(path: &str, field: &str) -> String {
+    if path.is_empty() {
+        field.to_string()
+    } else {
+        format!("{}.{}", path, field)
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "a boolean",
+        Value::Number(_) => "a number",
+        Value::String(_) => "a string",
+        Value::Array(_) => "an array",
+        Value::Object(_) => "an object",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    fn sample_jsonl() -> String {
+        let mut path = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        path.push("../../samples/trusted_root.jsonl");
+        std::fs::read_to_string(path).expect("Failed to read trusted_root.jsonl")
+    }
+
+    /// Apply `edit` to the second line of the sample file
+    fn edit_line_two(edit: impl FnOnce(&mut Value)) -> String {
+        let content = sample_jsonl();
+        let mut lines: Vec<String> = content.lines().map(str::to_string).collect();
+        let mut value: Value = serde_json::from_str(&lines[1]).unwrap();
+        edit(&mut value);
+        lines[1] = serde_json::to_string(&value).unwrap();
+        lines.join("\n")
+    }
+
+    #[test]
+    fn test_sample_is_clean() {
+        assert_eq!(lint_trusted_root_jsonl(&sample_jsonl()), vec![]);
+    }
+
+    #[test]
+    fn test_reports_line_and_field_path() {
+        let content = edit_line_two(|root| {
+            root["certificateAuthorities"][3]["validFor"]["start"] = Value::from("2025-13-01");
+            root["timestampAuthorities"][0]["certChain"]["certificates"][1]["rawBytes"] =
+                Value::from("not base64!");
+        });
+
+        let issues = lint_trusted_root_jsonl(&content);
+        let rendered: Vec<String> = issues.iter().map(|i| i.to_string()).collect();
+        assert_eq!(issues.len(), 2, "{:?}", rendered);
+        assert!(rendered[0].starts_with("line 2: certificateAuthorities[3].validFor.start: invalid RFC3339"));
+        assert!(rendered[1].starts_with(
+            "line 2: timestampAuthorities[0].certChain.certificates[1].rawBytes: invalid base64"
+        ));
+    }
+
+    #[test]
+    fn test_missing_and_misspelled_fields() {
+        let content = edit_line_two(|root| {
+            let ca = root["certificateAuthorities"][0].as_object_mut().unwrap();
+            let valid_for = ca.remove("validFor").unwrap();
+            ca.insert("validfor".to_string(), valid_for);
+            ca.remove("uri");
+        });
+
+        let paths: Vec<(String, String)> = lint_trusted_root_jsonl(&content)
+            .into_iter()
+            .map(|i| (i.path, i.message))
+            .collect();
+        assert_eq!(
+            paths,
+            vec![
+                (
+                    "certificateAuthorities[0].validfor".to_string(),
+                    "unknown field, did you mean 'validFor'?".to_string()
+                ),
+                ("certificateAuthorities[0]".to_string(), "missing field 'uri'".to_string()),
+                ("certificateAuthorities[0]".to_string(), "missing field 'validFor'".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_chain_order_and_invalid_json() {
+        let content = edit_line_two(|root| {
+            let certs = root["certificateAuthorities"][0]["certChain"]["certificates"]
+                .as_array_mut()
+                .unwrap();
+            certs.reverse();
+        });
+        let paths: Vec<String> = lint_trusted_root_jsonl(&content).into_iter().map(|i| i.path).collect();
+        assert_eq!(
+            paths,
+            vec![
+                "certificateAuthorities[0].certChain.certificates[0]",
+                "certificateAuthorities[0].certChain.certificates[1]",
+            ]
+        );
+
+        let issues = lint_trusted_root_jsonl("\n{\"mediaType\": ");
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].line, 2);
+        assert!(issues[0].message.starts_with("invalid JSON"));
+    }
+}
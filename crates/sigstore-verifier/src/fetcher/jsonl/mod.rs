@@ -1,2 +1,6 @@
+pub mod convert;
+pub mod diff;
+pub mod governance;
+pub mod lint;
 pub mod parser;
 pub mod types;
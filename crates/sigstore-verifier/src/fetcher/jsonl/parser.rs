@@ -1,6 +1,13 @@
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
 use base64::prelude::*;
 use chrono::DateTime;
-use crate::fetcher::jsonl::types::{CertChain as JsonlCertChain, TrustedRoot};
+use crate::crypto::signature::PublicKey;
+use crate::fetcher::jsonl::convert::cert_chain_from_pem;
+use crate::fetcher::jsonl::governance::TrustRootEnvelope;
+use crate::fetcher::jsonl::lint::{lint_trusted_root_line, Severity};
+use crate::fetcher::jsonl::types::{CertChain as JsonlCertChain, TransparencyLogInstance, TrustedRoot};
 use crate::types::certificate::{CertificateChain, FulcioInstance};
 use crate::VerificationError;
 
@@ -14,6 +21,12 @@ fn parse_rfc3339_timestamp(s: &str) -> Result<i64, VerificationError> {
 
 /// Load and parse Sigstore TrustedRoot bundles from JSONL format.
 /// Each line in the input should be a valid JSON object representing a TrustedRoot.
+/// When a line fails to parse, the error lists the offending fields with their
+/// paths (see [`lint_trusted_root_line`]).
+///
+/// Signed trusted roots are refused with `VerificationError::UnverifiedTrustRoot`;
+/// they are loaded with [`load_signed_trusted_root`](crate::fetcher::jsonl::governance::load_signed_trusted_root), which verifies the
+/// envelope first.
 ///
 /// # Arguments
 /// * `content` - JSONL content where each line is a separate trust bundle
@@ -21,6 +34,10 @@ fn parse_rfc3339_timestamp(s: &str) -> Result<i64, VerificationError> {
 /// # Returns
 /// Vector of parsed TrustedRoot objects, one per line
 pub fn load_trusted_root_from_jsonl(content: &str) -> Result<Vec<TrustedRoot>, VerificationError> {
+    if TrustRootEnvelope::parse(content).is_some() {
+        return Err(VerificationError::UnverifiedTrustRoot);
+    }
+
     let mut roots = Vec::new();
 
     for (line_num, line) in content.lines().enumerate() {
@@ -30,10 +47,16 @@ pub fn load_trusted_root_from_jsonl(content: &str) -> Result<Vec<TrustedRoot>, V
         }
 
         let root: TrustedRoot = serde_json::from_str(line).map_err(|e| {
+            let issues: Vec<String> = lint_trusted_root_line(line, line_num + 1)
+                .into_iter()
+                .filter(|issue| issue.severity == Severity::Error)
+                .map(|issue| issue.to_string())
+                .collect();
+            let detail = if issues.is_empty() { e.to_string() } else { issues.join("; ") };
             VerificationError::InvalidBundleFormat(format!(
                 "Failed to parse JSONL line {}: {}",
                 line_num + 1,
-                e
+                detail
             ))
         })?;
 
@@ -178,6 +201,160 @@ pub fn select_timestamp_authority(
     }
 }
 
+/// Collect the root certificates of the timestamp authorities valid at `timestamp`.
+/// Roots are DER certificates; a TSA chain embedded in an RFC 3161 timestamp is
+/// trusted if it roots in one of them, whichever TSA issued it
+/// (see [`VerificationOptions::tsa_roots`](crate::types::result::VerificationOptions::tsa_roots)).
+///
+/// # Arguments
+/// * `roots` - Parsed trust root bundles
+/// * `timestamp` - Signature timestamp in Unix seconds
+///
+/// # Returns
+/// The distinct roots, in trusted root order; empty if no TSA is valid at `timestamp`
+pub fn select_tsa_roots(roots: &[TrustedRoot], timestamp: i64) -> Result<Vec<Vec<u8>>, VerificationError> {
+    let mut tsa_roots: Vec<Vec<u8>> = Vec::new();
+
+    for tsa in roots.iter().flat_map(|root| &root.timestamp_authorities) {
+        let Some(start) = &tsa.valid_for.start else {
+            continue;
+        };
+        if timestamp < parse_rfc3339_timestamp(start)? {
+            continue;
+        }
+        if let Some(end) = &tsa.valid_for.end {
+            if timestamp > parse_rfc3339_timestamp(end)? {
+                continue;
+            }
+        }
+
+        let root = extract_tsa_cert_chain_from_authority(&tsa.cert_chain)?.root;
+        if !tsa_roots.contains(&root) {
+            tsa_roots.push(root);
+        }
+    }
+
+    Ok(tsa_roots)
+}
+
+/// Collect the public keys of the transparency logs valid at `timestamp`.
+/// Keys are DER SubjectPublicKeyInfo, the form whose SHA256 is a Rekor log ID, so the
+/// verifier can pick the key for an entry by its log ID
+/// (see [`VerificationOptions::rekor_keys`](crate::types::result::VerificationOptions::rekor_keys)).
+///
+/// # Arguments
+/// * `roots` - Parsed trust root bundles
+/// * `timestamp` - Signature timestamp in Unix seconds
+///
+/// # Returns
+/// The distinct keys, in trusted root order; empty if no log key is valid at `timestamp`
+pub fn select_rekor_keys(roots: &[TrustedRoot], timestamp: i64) -> Result<Vec<Vec<u8>>, VerificationError> {
+    select_log_keys(roots.iter().flat_map(|root| &root.tlogs), timestamp)
+}
+
+/// Collect the public keys of the certificate transparency logs valid at `timestamp`.
+/// Keys are DER SubjectPublicKeyInfo, whose SHA256 is the log ID an SCT names.
+///
+/// # Arguments
+/// * `roots` - Parsed trust root bundles
+/// * `timestamp` - Signature timestamp in Unix seconds
+///
+/// # Returns
+/// The distinct keys, in trusted root order; empty if no log key is valid at `timestamp`
+pub fn select_ctlog_keys(roots: &[TrustedRoot], timestamp: i64) -> Result<Vec<Vec<u8>>, VerificationError> {
+    select_log_keys(roots.iter().flat_map(|root| &root.ctlogs), timestamp)
+}
+
+/// Decode the keys of `logs` that are valid at `timestamp`, without duplicates
+fn select_log_keys<'a>(
+    logs: impl Iterator<Item = &'a TransparencyLogInstance>,
+    timestamp: i64,
+) -> Result<Vec<Vec<u8>>, VerificationError> {
+    let mut keys: Vec<Vec<u8>> = Vec::new();
+
+    for log in logs {
+        let Some(public_key) = &log.public_key else {
+            continue;
+        };
+        let Some(raw_bytes) = &public_key.raw_bytes else {
+            continue;
+        };
+
+        // Validate timestamp falls within validity period, if any
+        if let Some(valid_for) = &public_key.valid_for {
+            if let Some(start_str) = &valid_for.start {
+                if timestamp < parse_rfc3339_timestamp(start_str)? {
+                    continue; // Not yet valid
+                }
+            }
+            if let Some(end_str) = &valid_for.end {
+                if timestamp > parse_rfc3339_timestamp(end_str)? {
+                    continue; // Expired
+                }
+            }
+        }
+
+        let der = BASE64_STANDARD.decode(raw_bytes).map_err(|e| {
+            VerificationError::InvalidBundleFormat(format!("Failed to decode transparency log key: {}", e))
+        })?;
+        if !keys.contains(&der) {
+            keys.push(der);
+        }
+    }
+
+    Ok(keys)
+}
+
+/// Build a Fulcio certificate chain from concatenated PEM certificates, without
+/// selecting it from a trusted root.
+/// The certificates must be ordered `[intermediates..., root]`, as served by
+/// Fulcio's `/api/v1/rootCert` endpoint.
+///
+/// # Arguments
+/// * `pem_data` - PEM `CERTIFICATE` blocks
+///
+/// # Returns
+/// CertificateChain with leaf=empty, intermediates, and root
+pub fn certificate_authority_from_pem(pem_data: &str) -> Result<CertificateChain, VerificationError> {
+    extract_cert_chain_from_authority(&cert_chain_from_pem(pem_data)?)
+}
+
+/// Build a TSA certificate chain from concatenated PEM certificates, without
+/// selecting it from a trusted root.
+/// The certificates must be ordered `[leaf, intermediates..., root]`, as served by
+/// a timestamp authority's `/api/v1/timestamp/certchain` endpoint.
+///
+/// # Arguments
+/// * `pem_data` - PEM `CERTIFICATE` blocks
+///
+/// # Returns
+/// CertificateChain with leaf, intermediates, and root
+pub fn timestamp_authority_from_pem(pem_data: &str) -> Result<CertificateChain, VerificationError> {
+    extract_tsa_cert_chain_from_authority(&cert_chain_from_pem(pem_data)?)
+}
+
+/// Decode a Rekor public key from PEM, without selecting it from a trusted root.
+/// The key must be a `PUBLIC KEY` block, as served by Rekor's `/api/v1/log/publicKey`
+/// endpoint.
+///
+/// # Arguments
+/// * `pem_data` - PEM `PUBLIC KEY` block
+///
+/// # Returns
+/// The DER SubjectPublicKeyInfo, as in `VerificationOptions::rekor_keys`
+pub fn rekor_key_from_pem(pem_data: &str) -> Result<Vec<u8>, VerificationError> {
+    let block = ::pem::parse(pem_data)
+        .map_err(|e| VerificationError::InvalidBundleFormat(format!("Failed to parse PEM: {}", e)))?;
+    if block.tag() != "PUBLIC KEY" {
+        return Err(VerificationError::InvalidBundleFormat(format!(
+            "Expected a PUBLIC KEY block, found {}",
+            block.tag()
+        )));
+    }
+    PublicKey::from_spki_der(block.contents())?;
+    Ok(block.into_contents())
+}
+
 /// Convert JSONL cert chain to verifier's CertificateChain format for Fulcio CAs.
 /// Decodes base64-encoded DER certificates.
 /// For Fulcio chains, the leaf certificate is in the bundle, not in the trust bundle.
@@ -302,4 +479,48 @@ mod tests {
         let result = load_trusted_root_from_jsonl("not a json");
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_load_reports_field_path() {
+        let content = concat!(
+            r#"{"mediaType":"application/vnd.dev.sigstore.trustedroot+json;version=0.1"}"#,
+            "\n",
+            r#"{"mediaType":"application/vnd.dev.sigstore.trustedroot+json;version=0.1","#,
+            r#""certificateAuthorities":[{"subject":{"organization":"o","commonName":"c"},"#,
+            r#""uri":"https://fulcio.sigstore.dev","certChain":{"certificates":[]}}]}"#,
+        );
+        let err = load_trusted_root_from_jsonl(content).unwrap_err().to_string();
+        assert!(
+            err.contains("line 2: certificateAuthorities[0]: missing field 'validFor'"),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn test_select_tsa_roots() {
+        let content =
+            std::fs::read_to_string(concat!(env!("CARGO_MANIFEST_DIR"), "/../../samples/trusted_root.jsonl")).unwrap();
+        let roots = load_trusted_root_from_jsonl(&content).unwrap();
+        let timestamp = 1_745_000_000;
+
+        let tsa_roots = select_tsa_roots(&roots, timestamp).unwrap();
+        let chain = select_timestamp_authority(&roots, &FulcioInstance::GitHub, timestamp).unwrap();
+        assert!(tsa_roots.contains(&chain.root));
+        assert!(select_tsa_roots(&roots, 0).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_rekor_key_from_pem() {
+        let content =
+            std::fs::read_to_string(concat!(env!("CARGO_MANIFEST_DIR"), "/../../samples/trusted_root.jsonl")).unwrap();
+        let roots = load_trusted_root_from_jsonl(&content).unwrap();
+        let key = select_rekor_keys(&roots, 1_745_000_000).unwrap().remove(0);
+
+        let pem_data = ::pem::encode(&::pem::Pem::new("PUBLIC KEY", key.clone()));
+        assert_eq!(rekor_key_from_pem(&pem_data).unwrap(), key);
+
+        let certificate = ::pem::encode(&::pem::Pem::new("CERTIFICATE", key));
+        assert!(rekor_key_from_pem(&certificate).is_err());
+    }
 }
@@ -1,12 +1,23 @@
 //! Fetcher utilities for retrieving trust bundles
 //!
 //! This module provides helper functions for fetching Fulcio certificate chains
-//! from external sources. These are utility functions that clients can use to
+//! and Sigstore bundles from external sources. These are utility functions that clients can use to
 //! obtain the necessary trust bundles for verification.
 //!
 //! **Note**: The verification library itself does not fetch data. Clients are
 //! responsible for fetching and providing certificate chains to the verifier.
+//!
+//! The remote fetchers (`fetcher-core` feature) send their requests through an
+//! [`http::HttpClient`]; `fetcher` also enables the reqwest client, and
+//! `http-ureq` a ureq one (see the [`http`] module).
 
+#[cfg(feature = "fetcher-core")]
+pub mod http;
 pub mod jsonl;
-#[cfg(feature = "fetcher")]
+#[cfg(feature = "fetcher-core")]
+pub mod remote;
+#[cfg(feature = "fetcher-core")]
 pub mod trust_bundle;
+pub mod trusted_root;
+#[cfg(feature = "fetcher-core")]
+pub mod tuf;
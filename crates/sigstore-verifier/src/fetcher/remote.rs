@@ -0,0 +1,540 @@
+//! Download Sigstore bundles and trusted roots from remote locations
+//!
+//! Supports plain HTTP(S) URLs, the GitHub attestations API, OCI registries
+//! exposing the referrers API, crate archives published on crates.io and
+//! artifacts (with their bundles) on Maven Central. Like the rest of the fetcher module these are
+//! client-side helpers: the verifier only ever sees the downloaded bytes.
+//!
+//! Requests are sent with the [`HttpClient`] passed in, e.g.
+//! [`default_client`](crate::fetcher::http::default_client).
+
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use crate::crypto::hash::{hex_encode, sha256};
+use crate::error::VerificationError;
+use crate::fetcher::http::{with_query, HttpClient, HttpResponse};
+use crate::types::purl::{CargoPurl, MavenPurl};
+use serde::Deserialize;
+
+const ACCEPT: &str = "Accept";
+const AUTHORIZATION: &str = "Authorization";
+const WWW_AUTHENTICATE: &str = "WWW-Authenticate";
+
+const HTTP_UNAUTHORIZED: u16 = 401;
+const HTTP_NOT_FOUND: u16 = 404;
+
+/// Prefix shared by every Sigstore bundle media type / OCI artifact type
+const SIGSTORE_BUNDLE_MEDIA_TYPE_PREFIX: &str = "application/vnd.dev.sigstore.bundle";
+
+const OCI_INDEX_MEDIA_TYPE: &str = "application/vnd.oci.image.index.v1+json";
+const OCI_MANIFEST_MEDIA_TYPE: &str = "application/vnd.oci.image.manifest.v1+json";
+
+const GITHUB_API_URL: &str = "https://api.github.com";
+const CRATES_IO_API_URL: &str = "https://crates.io/api/v1";
+const CRATES_IO_DOWNLOAD_URL: &str = "https://static.crates.io/crates";
+const MAVEN_CENTRAL_URL: &str = "https://repo1.maven.org/maven2";
+
+/// Suffix of the Sigstore bundle published next to a Maven artifact
+const MAVEN_BUNDLE_SUFFIX: &str = ".sigstore.json";
+
+/// Download the raw bytes served at an HTTP(S) URL
+pub fn fetch_url(client: &dyn HttpClient, url: &str) -> Result<Vec<u8>, VerificationError> {
+    read_body(url, client.get(url, &[])?)
+}
+
+/// Fetch an attestation bundle from the GitHub attestations API
+///
+/// Queries `GET /repos/{owner}/{repo}/attestations/{digest}` and returns the first
+/// bundle attached to the subject digest. Set `GITHUB_TOKEN` to authenticate, which
+/// is required for private repositories and raises the rate limit.
+///
+/// # Arguments
+/// * `client` - HTTP client to send the request with
+/// * `owner` - Repository owner
+/// * `repo` - Repository name
+/// * `digest` - Subject digest in `algorithm:hex` form (e.g. `sha256:abc...`)
+///
+/// # Returns
+/// * The bundle re-serialized as JSON bytes
+pub fn fetch_github_attestation_bundle(
+    client: &dyn HttpClient,
+    owner: &str,
+    repo: &str,
+    digest: &str,
+) -> Result<Vec<u8>, VerificationError> {
+    fetch_github_attestation_bundles(client, owner, repo, digest)?
+        .into_iter()
+        .next()
+        .ok_or_else(|| {
+            VerificationError::RemoteSource(format!(
+                "No attestations found for {} in {}/{}",
+                digest, owner, repo
+            ))
+        })
+}
+
+/// Fetch every attestation bundle stored for a subject digest
+///
+/// Same endpoint as [`fetch_github_attestation_bundle`], returning all bundles
+/// (e.g. build provenance and SBOM attestations) in API order. An empty list
+/// means the digest has no attestations.
+pub fn fetch_github_attestation_bundles(
+    client: &dyn HttpClient,
+    owner: &str,
+    repo: &str,
+    digest: &str,
+) -> Result<Vec<Vec<u8>>, VerificationError> {
+    #[derive(Deserialize)]
+    struct AttestationsResponse {
+        attestations: Vec<Attestation>,
+    }
+
+    #[derive(Deserialize)]
+    struct Attestation {
+        bundle: serde_json::Value,
+    }
+
+    let url = format!("{}/repos/{}/{}/attestations/{}", GITHUB_API_URL, owner, repo, digest);
+    let authorization = std::env::var("GITHUB_TOKEN").map(|token| format!("Bearer {}", token));
+    let mut headers = vec![(ACCEPT, "application/vnd.github+json")];
+    if let Ok(ref authorization) = authorization {
+        headers.push((AUTHORIZATION, authorization));
+    }
+
+    let response = client.get(&url, &headers)?;
+    if response.status == HTTP_NOT_FOUND {
+        return Ok(Vec::new());
+    }
+    let body = read_body(&url, response)?;
+    let response: AttestationsResponse = serde_json::from_slice(&body)?;
+
+    response
+        .attestations
+        .into_iter()
+        .map(|attestation| Ok(serde_json::to_vec(&attestation.bundle)?))
+        .collect()
+}
+
+/// Fetch a Sigstore bundle attached to an OCI artifact via the referrers API
+///
+/// Lists the referrers of `digest` in `registry/repository`, picks the first one
+/// whose artifact type is a Sigstore bundle, and downloads its single layer.
+/// Anonymous bearer tokens are requested automatically for public registries.
+///
+/// # Arguments
+/// * `client` - HTTP client to send the requests with
+/// * `registry` - Registry host (e.g. `ghcr.io`)
+/// * `repository` - Repository path within the registry (e.g. `owner/image`)
+/// * `digest` - Digest of the attested artifact (`sha256:...`)
+pub fn fetch_oci_referrer_bundle(
+    client: &dyn HttpClient,
+    registry: &str,
+    repository: &str,
+    digest: &str,
+) -> Result<Vec<u8>, VerificationError> {
+    #[derive(Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct Descriptor {
+        digest: String,
+        artifact_type: Option<String>,
+    }
+
+    #[derive(Deserialize)]
+    struct Index {
+        #[serde(default)]
+        manifests: Vec<Descriptor>,
+    }
+
+    #[derive(Deserialize)]
+    struct Manifest {
+        #[serde(default)]
+        layers: Vec<Descriptor>,
+    }
+
+    let mut registry = OciRegistry::new(client, registry, repository);
+
+    let index: Index = serde_json::from_slice(&registry.get(
+        &format!("referrers/{}", digest),
+        OCI_INDEX_MEDIA_TYPE,
+    )?)?;
+    let referrer = index
+        .manifests
+        .iter()
+        .find(|m| {
+            m.artifact_type
+                .as_deref()
+                .is_some_and(|t| t.starts_with(SIGSTORE_BUNDLE_MEDIA_TYPE_PREFIX))
+        })
+        .ok_or_else(|| {
+            VerificationError::RemoteSource(format!(
+                "No Sigstore bundle referrers for {}@{}",
+                repository, digest
+            ))
+        })?;
+
+    let manifest: Manifest = serde_json::from_slice(&registry.get(
+        &format!("manifests/{}", referrer.digest),
+        OCI_MANIFEST_MEDIA_TYPE,
+    )?)?;
+    let layer = manifest.layers.first().ok_or_else(|| {
+        VerificationError::RemoteSource(format!("Referrer {} has no layers", referrer.digest))
+    })?;
+
+    let blob = registry.get(&format!("blobs/{}", layer.digest), "*/*")?;
+    verify_content_digest(&layer.digest, &blob)?;
+    Ok(blob)
+}
+
+/// Download a crate archive published on crates.io
+///
+/// The archive is checked against the SHA256 checksum crates.io records for the
+/// version (the one in the registry index), so a tampered mirror or CDN response
+/// is rejected before it is attested against.
+///
+/// # Arguments
+/// * `client` - HTTP client to send the requests with
+/// * `purl` - Crate name and version
+///
+/// # Returns
+/// * The `.crate` archive bytes
+pub fn fetch_crate(client: &dyn HttpClient, purl: &CargoPurl) -> Result<Vec<u8>, VerificationError> {
+    #[derive(Deserialize)]
+    struct VersionResponse {
+        version: Version,
+    }
+
+    #[derive(Deserialize)]
+    struct Version {
+        checksum: String,
+    }
+
+    let url = format!("{}/crates/{}/{}", CRATES_IO_API_URL, purl.name, purl.version);
+    let body = read_body(&url, client.get(&url, &[])?)?;
+    let response: VersionResponse = serde_json::from_slice(&body)?;
+
+    let url = format!("{}/{}/{}", CRATES_IO_DOWNLOAD_URL, purl.name, purl.crate_file_name());
+    let archive = read_body(&url, client.get(&url, &[])?)?;
+    verify_content_digest(&format!("sha256:{}", response.version.checksum), &archive)?;
+    Ok(archive)
+}
+
+/// Fetch the attestation bundles for a crate archive
+///
+/// crates.io does not serve attestations itself, so the crate's declared
+/// repository is looked up and, if it is hosted on GitHub, the attestations
+/// stored there for the archive digest are returned (e.g. those created by
+/// `actions/attest-build-provenance` in the publishing workflow). An empty list
+/// means none were found.
+///
+/// # Arguments
+/// * `client` - HTTP client to send the requests with
+/// * `purl` - Crate name and version
+/// * `digest` - SHA256 of the `.crate` archive
+///
+/// # Errors
+/// Returns `RemoteSource` if the crate declares no GitHub repository
+pub fn fetch_crate_attestation_bundles(
+    client: &dyn HttpClient,
+    purl: &CargoPurl,
+    digest: &[u8],
+) -> Result<Vec<Vec<u8>>, VerificationError> {
+    #[derive(Deserialize)]
+    struct CrateResponse {
+        #[serde(rename = "crate")]
+        krate: Crate,
+    }
+
+    #[derive(Deserialize)]
+    struct Crate {
+        repository: Option<String>,
+    }
+
+    let url = format!("{}/crates/{}", CRATES_IO_API_URL, purl.name);
+    let body = read_body(&url, client.get(&url, &[])?)?;
+    let response: CrateResponse = serde_json::from_slice(&body)?;
+
+    let repository = response.krate.repository.unwrap_or_default();
+    let (owner, repo) = parse_github_repository(&repository).ok_or_else(|| {
+        VerificationError::RemoteSource(format!(
+            "{} does not declare a GitHub repository to look up attestations in (repository: '{}')",
+            purl.name, repository
+        ))
+    })?;
+
+    fetch_github_attestation_bundles(client, &owner, &repo, &format!("sha256:{}", hex_encode(digest)))
+}
+
+/// Download an artifact from Maven Central
+///
+/// # Arguments
+/// * `client` - HTTP client to send the request with
+/// * `purl` - Artifact coordinates
+///
+/// # Returns
+/// * The artifact bytes
+pub fn fetch_maven_artifact(client: &dyn HttpClient, purl: &MavenPurl) -> Result<Vec<u8>, VerificationError> {
+    fetch_url(client, &format!("{}/{}", MAVEN_CENTRAL_URL, purl.repository_path()))
+}
+
+/// Download the Sigstore bundle published for a Maven Central artifact
+///
+/// Maven Central serves the bundle of an artifact next to it, under the
+/// artifact's file name with `.sigstore.json` appended (as produced by the
+/// Sigstore Maven plugin and accepted by the Central Portal).
+///
+/// # Arguments
+/// * `client` - HTTP client to send the request with
+/// * `purl` - Artifact coordinates
+///
+/// # Errors
+/// Returns `RemoteSource` if no bundle was published for the artifact
+pub fn fetch_maven_attestation_bundle(
+    client: &dyn HttpClient,
+    purl: &MavenPurl,
+) -> Result<Vec<u8>, VerificationError> {
+    let url = format!("{}/{}{}", MAVEN_CENTRAL_URL, purl.repository_path(), MAVEN_BUNDLE_SUFFIX);
+    let response = client.get(&url, &[])?;
+    if response.status == HTTP_NOT_FOUND {
+        return Err(VerificationError::RemoteSource(format!(
+            "No Sigstore bundle published for {}",
+            purl
+        )));
+    }
+    read_body(&url, response)
+}
+
+/// Extract `(owner, repo)` from a `https://github.com/owner/repo[.git][/...]` URL
+fn parse_github_repository(url: &str) -> Option<(String, String)> {
+    let path = url
+        .strip_prefix("https://github.com/")
+        .or_else(|| url.strip_prefix("http://github.com/"))?;
+    let mut segments = path.split('/');
+    let owner = segments.next().filter(|s| !s.is_empty())?;
+    let repo = segments.next().filter(|s| !s.is_empty())?;
+    let repo = repo.strip_suffix(".git").unwrap_or(repo);
+    Some((owner.to_string(), repo.to_string()))
+}
+
+/// Minimal OCI distribution client scoped to a single repository
+struct OciRegistry<'a> {
+    client: &'a dyn HttpClient,
+    base_url: String,
+    token: Option<String>,
+}
+
+impl<'a> OciRegistry<'a> {
+    fn new(client: &'a dyn HttpClient, registry: &str, repository: &str) -> Self {
+        Self {
+            client,
+            base_url: format!("https://{}/v2/{}", registry, repository),
+            token: None,
+        }
+    }
+
+    fn request(&self, url: &str, accept: &str) -> Result<HttpResponse, VerificationError> {
+        match self.token.as_ref() {
+            Some(token) => {
+                let authorization = format!("Bearer {}", token);
+                self.client.get(url, &[(ACCEPT, accept), (AUTHORIZATION, &authorization)])
+            }
+            None => self.client.get(url, &[(ACCEPT, accept)]),
+        }
+    }
+
+    fn get(&mut self, path: &str, accept: &str) -> Result<Vec<u8>, VerificationError> {
+        let url = format!("{}/{}", self.base_url, path);
+        let response = self.request(&url, accept)?;
+
+        // Public registries answer the first request with a bearer challenge
+        if response.status == HTTP_UNAUTHORIZED && self.token.is_none() {
+            let challenge = response.header(WWW_AUTHENTICATE).map(str::to_string).ok_or_else(|| {
+                VerificationError::RemoteSource(format!("{} requires authentication", url))
+            })?;
+            self.token = Some(self.anonymous_token(&challenge)?);
+            return read_body(&url, self.request(&url, accept)?);
+        }
+
+        read_body(&url, response)
+    }
+
+    fn anonymous_token(&self, challenge: &str) -> Result<String, VerificationError> {
+        #[derive(Deserialize)]
+        struct TokenResponse {
+            token: Option<String>,
+            access_token: Option<String>,
+        }
+
+        let params = parse_bearer_challenge(challenge).ok_or_else(|| {
+            VerificationError::RemoteSource(format!("Unsupported auth challenge: {}", challenge))
+        })?;
+        let realm = params
+            .iter()
+            .find(|(k, _)| k == "realm")
+            .map(|(_, v)| v.clone())
+            .ok_or_else(|| VerificationError::RemoteSource("Auth challenge has no realm".into()))?;
+        let query: Vec<(String, String)> =
+            params.into_iter().filter(|(k, _)| k != "realm").collect();
+
+        let response = self.client.get(&with_query(&realm, &query), &[])?;
+        let body = read_body(&realm, response)?;
+        let token: TokenResponse = serde_json::from_slice(&body)?;
+        token.token.or(token.access_token).ok_or_else(|| {
+            VerificationError::RemoteSource(format!("No token returned by {}", realm))
+        })
+    }
+}
+
+/// Parse `Bearer realm="...",service="...",scope="..."` into key/value pairs
+fn parse_bearer_challenge(challenge: &str) -> Option<Vec<(String, String)>> {
+    let params = challenge.strip_prefix("Bearer ")?;
+    let mut result = Vec::new();
+    let mut rest = params.trim();
+
+    while !rest.is_empty() {
+        let (key, after_key) = rest.split_once('=')?;
+        let after_key = after_key.strip_prefix('"')?;
+        let (value, after_value) = after_key.split_once('"')?;
+        result.push((key.trim().to_string(), value.to_string()));
+        rest = after_value.trim_start_matches(',').trim();
+    }
+
+    Some(result)
+}
+
+/// Check that downloaded content matches its `sha256:<hex>` content address
+fn verify_content_digest(digest: &str, content: &[u8]) -> Result<(), VerificationError> {
+    let expected = digest.strip_prefix("sha256:").ok_or_else(|| {
+        VerificationError::RemoteSource(format!("Unsupported digest algorithm: {}", digest))
+    })?;
+    let actual = hex_encode(&sha256(content));
+    if !actual.eq_ignore_ascii_case(expected) {
+        return Err(VerificationError::RemoteSource(format!(
+            "Content digest mismatch: expected {}, got sha256:{}",
+            digest, actual
+        )));
+    }
+    Ok(())
+}
+
+pub(crate) fn read_body(url: &str, response: HttpResponse) -> Result<Vec<u8>, VerificationError> {
+    if !response.is_success() {
+        return Err(VerificationError::RemoteSource(format!(
+            "GET {} returned HTTP {}",
+            url, response.status
+        )));
+    }
+    Ok(response.body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Answers requests with a function of the URL and the `Authorization` header
+    struct FnClient<F>(F);
+
+    impl<F: Fn(&str, Option<&str>) -> HttpResponse> HttpClient for FnClient<F> {
+        fn get(&self, url: &str, headers: &[(&str, &str)]) -> Result<HttpResponse, VerificationError> {
+            let authorization = headers.iter().find(|(name, _)| *name == AUTHORIZATION).map(|(_, value)| *value);
+            Ok((self.0)(url, authorization))
+        }
+    }
+
+    fn response(status: u16, body: &[u8]) -> HttpResponse {
+        HttpResponse {
+            status,
+            headers: Vec::new(),
+            body: body.to_vec(),
+        }
+    }
+
+    #[test]
+    fn test_fetch_oci_referrer_bundle_with_anonymous_token() {
+        let bundle = br#"{"mediaType":"application/vnd.dev.sigstore.bundle.v0.3+json"}"#;
+        let bundle_digest = format!("sha256:{}", hex_encode(&sha256(bundle)));
+        let client = FnClient(|url: &str, authorization: Option<&str>| {
+            if url.starts_with("https://ghcr.io/token?") {
+                assert_eq!(url, "https://ghcr.io/token?service=ghcr.io&scope=repository%3Aowner%2Fimage%3Apull");
+                return response(200, br#"{"token":"anonymous"}"#);
+            }
+            if authorization != Some("Bearer anonymous") {
+                let mut challenge = response(401, b"");
+                challenge.headers.push((
+                    "www-authenticate".to_string(),
+                    r#"Bearer realm="https://ghcr.io/token",service="ghcr.io",scope="repository:owner/image:pull""#
+                        .to_string(),
+                ));
+                return challenge;
+            }
+            let path = url.strip_prefix("https://ghcr.io/v2/owner/image/").unwrap();
+            match path {
+                "referrers/sha256:aa" => response(
+                    200,
+                    br#"{"manifests":[{"digest":"sha256:bb","artifactType":"application/vnd.dev.sigstore.bundle.v0.3+json"}]}"#,
+                ),
+                "manifests/sha256:bb" => {
+                    response(200, format!(r#"{{"layers":[{{"digest":"{}"}}]}}"#, bundle_digest).as_bytes())
+                }
+                _ if path == format!("blobs/{}", bundle_digest) => response(200, bundle),
+                _ => response(404, b""),
+            }
+        });
+
+        let fetched = fetch_oci_referrer_bundle(&client, "ghcr.io", "owner/image", "sha256:aa").unwrap();
+        assert_eq!(fetched, bundle);
+    }
+
+    #[test]
+    fn test_fetch_github_attestation_bundles_not_found() {
+        let client = FnClient(|_: &str, _: Option<&str>| response(404, b""));
+        assert!(fetch_github_attestation_bundles(&client, "owner", "repo", "sha256:aa")
+            .unwrap()
+            .is_empty());
+        assert!(matches!(
+            fetch_url(&client, "https://example.com/bundle.json"),
+            Err(VerificationError::RemoteSource(_))
+        ));
+    }
+
+    #[test]
+    fn test_parse_bearer_challenge() {
+        let params = parse_bearer_challenge(
+            r#"Bearer realm="https://ghcr.io/token",service="ghcr.io",scope="repository:owner/image:pull""#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            params,
+            vec![
+                ("realm".to_string(), "https://ghcr.io/token".to_string()),
+                ("service".to_string(), "ghcr.io".to_string()),
+                ("scope".to_string(), "repository:owner/image:pull".to_string()),
+            ]
+        );
+        assert!(parse_bearer_challenge("Basic realm=\"x\"").is_none());
+    }
+
+    #[test]
+    fn test_parse_github_repository() {
+        assert_eq!(
+            parse_github_repository("https://github.com/serde-rs/json"),
+            Some(("serde-rs".to_string(), "json".to_string()))
+        );
+        assert_eq!(
+            parse_github_repository("https://github.com/owner/repo.git/tree/main/crates/x"),
+            Some(("owner".to_string(), "repo".to_string()))
+        );
+        assert!(parse_github_repository("https://gitlab.com/owner/repo").is_none());
+        assert!(parse_github_repository("https://github.com/owner").is_none());
+    }
+
+    #[test]
+    fn test_verify_content_digest() {
+        let digest = format!("sha256:{}", hex_encode(&sha256(b"bundle")));
+        assert!(verify_content_digest(&digest, b"bundle").is_ok());
+        assert!(verify_content_digest(&digest, b"tampered").is_err());
+        assert!(verify_content_digest("sha512:00", b"bundle").is_err());
+    }
+}
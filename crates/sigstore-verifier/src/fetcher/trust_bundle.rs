@@ -1,18 +1,24 @@
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
 use crate::error::CertificateError;
+use crate::fetcher::http::HttpClient;
 use crate::parser::certificate::parse_pem_certificate;
 use crate::types::certificate::{CertificateChain, FulcioInstance, TrustBundle};
 
 /// Fetch Fulcio trust bundle for a specific Fulcio instance
 ///
 /// # Arguments
+/// * `client` - HTTP client to download the bundle with
 /// * `instance` - The Fulcio instance (GitHub or PublicGood)
 ///
 /// # Returns
 /// * `CertificateChain` with intermediates and root populated (leaf is empty)
 pub fn fetch_fulcio_trust_bundle(
+    client: &dyn HttpClient,
     instance: &FulcioInstance,
 ) -> Result<CertificateChain, CertificateError> {
-    fetch_trust_bundle_from_url(instance.trust_bundle_url())
+    fetch_trust_bundle_from_url(client, instance.trust_bundle_url())
 }
 
 /// Fetch certificate trust bundle from a custom URL
@@ -25,6 +31,7 @@ pub fn fetch_fulcio_trust_bundle(
 /// Useful for fetching TSA certificate chains or custom certificate authorities.
 ///
 /// # Arguments
+/// * `client` - HTTP client to download the bundle with
 /// * `url` - URL to fetch the trust bundle from
 ///
 /// # Returns
@@ -32,26 +39,30 @@ pub fn fetch_fulcio_trust_bundle(
 ///
 /// # Example
 /// ```ignore
+/// use sigstore_verifier::fetcher::http::default_client;
 /// use sigstore_verifier::fetcher::trust_bundle::fetch_trust_bundle_from_url;
 ///
 /// // Fetch TSA trust bundle (GitHub format - raw PEM)
 /// let tsa_url = "https://timestamp.githubapp.com/api/v1/timestamp/certchain";
-/// let tsa_chain = fetch_trust_bundle_from_url(tsa_url).unwrap();
+/// let tsa_chain = fetch_trust_bundle_from_url(&*default_client()?, tsa_url).unwrap();
 /// ```
-pub fn fetch_trust_bundle_from_url(url: &str) -> Result<CertificateChain, CertificateError> {
-    let response = reqwest::blocking::get(url)
+pub fn fetch_trust_bundle_from_url(
+    client: &dyn HttpClient,
+    url: &str,
+) -> Result<CertificateChain, CertificateError> {
+    let response = client
+        .get(url, &[])
         .map_err(|e| CertificateError::TrustBundleFetch(e.to_string()))?;
 
-    if !response.status().is_success() {
+    if !response.is_success() {
         return Err(CertificateError::TrustBundleFetch(format!(
             "HTTP error: {}",
-            response.status()
+            response.status
         )));
     }
 
     // Get response body as text to detect format
-    let body = response
-        .text()
+    let body = String::from_utf8(response.body)
         .map_err(|e| CertificateError::TrustBundleFetch(e.to_string()))?;
 
     // Try to detect format: if it starts with "-----BEGIN", it's PEM format
@@ -145,11 +156,52 @@ fn parse_pem_chain(pem_data: &str) -> Result<CertificateChain, CertificateError>
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::error::VerificationError;
+    use crate::fetcher::http::HttpResponse;
+
+    /// Serves one body for every URL
+    struct StaticClient(HttpResponse);
+
+    impl HttpClient for StaticClient {
+        fn get(&self, _url: &str, _headers: &[(&str, &str)]) -> Result<HttpResponse, VerificationError> {
+            Ok(self.0.clone())
+        }
+    }
+
+    #[test]
+    fn test_fetch_trust_bundle_with_custom_client() {
+        let pem_chain = [
+            include_str!("../../testdata/rsa/leaf.pem"),
+            include_str!("../../testdata/rsa/intermediate.pem"),
+            include_str!("../../testdata/rsa/root.pem"),
+        ]
+        .concat();
+        let client = StaticClient(HttpResponse {
+            status: 200,
+            headers: Vec::new(),
+            body: pem_chain.into_bytes(),
+        });
+        let chain = fetch_trust_bundle_from_url(&client, "https://tsa.example/certchain").unwrap();
+        assert!(!chain.leaf.is_empty());
+        assert_eq!(chain.intermediates.len(), 1);
+        assert!(!chain.root.is_empty());
+
+        let not_found = StaticClient(HttpResponse {
+            status: 404,
+            ..Default::default()
+        });
+        assert!(matches!(
+            fetch_trust_bundle_from_url(&not_found, "https://tsa.example/certchain"),
+            Err(CertificateError::TrustBundleFetch(_))
+        ));
+    }
 
     #[test]
     #[ignore] // Requires network access
+    #[cfg(any(feature = "http-reqwest", feature = "http-ureq"))]
     fn test_fetch_github_trust_bundle() {
-        let result = fetch_fulcio_trust_bundle(&FulcioInstance::GitHub);
+        let client = crate::fetcher::http::default_client().unwrap();
+        let result = fetch_fulcio_trust_bundle(&*client, &FulcioInstance::GitHub);
         assert!(result.is_ok());
 
         let chain = result.unwrap();
@@ -159,8 +211,10 @@ mod tests {
 
     #[test]
     #[ignore] // Requires network access
+    #[cfg(any(feature = "http-reqwest", feature = "http-ureq"))]
     fn test_fetch_public_trust_bundle() {
-        let result = fetch_fulcio_trust_bundle(&FulcioInstance::PublicGood);
+        let client = crate::fetcher::http::default_client().unwrap();
+        let result = fetch_fulcio_trust_bundle(&*client, &FulcioInstance::PublicGood);
         assert!(result.is_ok());
 
         let chain = result.unwrap();
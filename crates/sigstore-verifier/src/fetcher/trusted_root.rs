@@ -0,0 +1,284 @@
+//! Parser for the official Sigstore `trusted_root.json`
+//!
+//! Sigstore distributes its trust anchors as the TUF target `trusted_root.json`,
+//! the protobuf-JSON encoding of `dev.sigstore.trustroot.v1.TrustedRoot`
+//! (<https://github.com/sigstore/protobuf-specs/blob/main/protos/sigstore_trustroot.proto>):
+//!
+//! - `certificateAuthorities`: Fulcio chains, `[intermediates..., root]`
+//! - `timestampAuthorities`: RFC 3161 TSA chains, `[leaf, intermediates..., root]`
+//! - `tlogs`: Rekor log keys
+//! - `ctlogs`: certificate transparency log keys
+//!
+//! Every line of the JSONL files read by [`load_trusted_root_from_jsonl`] is such
+//! a document, but that parser insists on fields that protobuf-JSON leaves out
+//! when they have their default value, such as an authority's `subject` or
+//! `uri`. [`parse_trusted_root_json`] accepts the document as published, in the
+//! `0.1` and `v0.2` media types, and checks that its certificates and keys
+//! decode. It yields the same [`TrustedRoot`] as the JSONL parser, so
+//! `select_certificate_authority`, `select_timestamp_authority`,
+//! `select_rekor_keys` and `select_ctlog_keys` work over it unchanged.
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use base64::prelude::*;
+use chrono::DateTime;
+use serde::Deserialize;
+
+use crate::fetcher::jsonl::convert::TRUSTED_ROOT_MEDIA_TYPE;
+use crate::fetcher::jsonl::governance::TrustRootEnvelope;
+use crate::fetcher::jsonl::parser::load_trusted_root_from_jsonl;
+use crate::fetcher::jsonl::types::{
+    CertChain, CertificateAuthority, Subject, TimestampAuthority, TransparencyLogInstance,
+    TrustedRoot, ValidityPeriod,
+};
+use crate::parser::certificate::parse_der_certificate;
+use crate::VerificationError;
+
+/// Media type of `trusted_root.json` documents since protobuf-specs 0.4
+pub const TRUSTED_ROOT_MEDIA_TYPE_V0_2: &str = "application/vnd.dev.sigstore.trustedroot.v0.2+json";
+
+/// `TrustedRoot` as encoded in protobuf-JSON, with every field optional
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TrustedRootDocument {
+    #[serde(default)]
+    media_type: String,
+    #[serde(default)]
+    tlogs: Vec<TransparencyLogInstance>,
+    #[serde(default)]
+    certificate_authorities: Vec<AuthorityDocument>,
+    #[serde(default)]
+    ctlogs: Vec<TransparencyLogInstance>,
+    #[serde(default)]
+    timestamp_authorities: Vec<AuthorityDocument>,
+}
+
+/// `CertificateAuthority`, which TSAs are described by as well
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AuthorityDocument {
+    #[serde(default)]
+    subject: SubjectDocument,
+    #[serde(default)]
+    uri: String,
+    #[serde(default)]
+    cert_chain: Option<CertChain>,
+    #[serde(default)]
+    valid_for: Option<ValidityPeriod>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SubjectDocument {
+    #[serde(default)]
+    organization: String,
+    #[serde(default)]
+    common_name: String,
+}
+
+/// A checked authority: subject, URI, chain and validity
+type Authority = (Subject, String, CertChain, ValidityPeriod);
+
+/// Parse an official `trusted_root.json` document
+///
+/// # Arguments
+/// * `content` - Contents of a trusted_root.json file as distributed via TUF
+///
+/// # Returns
+/// The trusted root, with the same layout as a JSONL line
+///
+/// # Errors
+/// Returns `VerificationError::InvalidBundleFormat` naming the offending field
+/// for an unknown media type, an authority without a chain or a validity start,
+/// and certificates, keys or timestamps that do not decode
+pub fn parse_trusted_root_json(content: &str) -> Result<TrustedRoot, VerificationError> {
+    let document: TrustedRootDocument = serde_json::from_str(content).map_err(|e| {
+        VerificationError::InvalidBundleFormat(format!("Failed to parse trusted root JSON: {}", e))
+    })?;
+
+    if ![TRUSTED_ROOT_MEDIA_TYPE, TRUSTED_ROOT_MEDIA_TYPE_V0_2].contains(&document.media_type.as_str()) {
+        return Err(invalid(format!("mediaType: unsupported '{}'", document.media_type)));
+    }
+
+    let mut certificate_authorities = Vec::new();
+    for (index, authority) in document.certificate_authorities.into_iter().enumerate() {
+        let (subject, uri, cert_chain, valid_for) =
+            check_authority(authority, &format!("certificateAuthorities[{}]", index))?;
+        certificate_authorities.push(CertificateAuthority { subject, uri, cert_chain, valid_for });
+    }
+
+    let mut timestamp_authorities = Vec::new();
+    for (index, authority) in document.timestamp_authorities.into_iter().enumerate() {
+        let (subject, uri, cert_chain, valid_for) =
+            check_authority(authority, &format!("timestampAuthorities[{}]", index))?;
+        timestamp_authorities.push(TimestampAuthority { subject, uri, cert_chain, valid_for });
+    }
+
+    for (field, logs) in [("tlogs", &document.tlogs), ("ctlogs", &document.ctlogs)] {
+        for (index, log) in logs.iter().enumerate() {
+            check_log(log, &format!("{}[{}]", field, index))?;
+        }
+    }
+
+    Ok(TrustedRoot {
+        media_type: document.media_type,
+        tlogs: document.tlogs,
+        certificate_authorities,
+        ctlogs: document.ctlogs,
+        timestamp_authorities,
+    })
+}
+
+/// Load trusted roots from a `trusted_root.json` document or JSONL content
+///
+/// Content that is a single JSON document is parsed with
+/// [`parse_trusted_root_json`], anything else as JSONL.
+///
+/// # Errors
+/// Returns `VerificationError::UnverifiedTrustRoot` for a signed trusted root,
+/// plus the errors of the respective parser
+pub fn load_trusted_roots(content: &str) -> Result<Vec<TrustedRoot>, VerificationError> {
+    if TrustRootEnvelope::parse(content).is_some() {
+        return Err(VerificationError::UnverifiedTrustRoot);
+    }
+    match serde_json::from_str::<serde_json::Value>(content) {
+        Ok(serde_json::Value::Object(_)) => Ok(vec![parse_trusted_root_json(content)?]),
+        _ => load_trusted_root_from_jsonl(content),
+    }
+}
+
+fn invalid(message: String) -> VerificationError {
+    VerificationError::InvalidBundleFormat(format!("Invalid trusted root: {}", message))
+}
+
+/// Check that an authority has a decodable chain and a validity start
+fn check_authority(authority: AuthorityDocument, path: &str) -> Result<Authority, VerificationError> {
+    let cert_chain = authority
+        .cert_chain
+        .filter(|chain| !chain.certificates.is_empty())
+        .ok_or_else(|| invalid(format!("{}.certChain: no certificates", path)))?;
+    for (index, certificate) in cert_chain.certificates.iter().enumerate() {
+        let decodes = BASE64_STANDARD
+            .decode(&certificate.raw_bytes)
+            .is_ok_and(|der| parse_der_certificate(&der).is_ok());
+        if !decodes {
+            return Err(invalid(format!(
+                "{}.certChain.certificates[{}]: not a base64 DER certificate",
+                path, index
+            )));
+        }
+    }
+
+    let valid_for = authority
+        .valid_for
+        .filter(|valid_for| valid_for.start.is_some())
+        .ok_or_else(|| invalid(format!("{}.validFor.start: missing", path)))?;
+    check_validity(&valid_for, &format!("{}.validFor", path))?;
+
+    let subject = Subject {
+        organization: authority.subject.organization,
+        common_name: authority.subject.common_name,
+    };
+    Ok((subject, authority.uri, cert_chain, valid_for))
+}
+
+/// Check that a log's key and log ID decode
+fn check_log(log: &TransparencyLogInstance, path: &str) -> Result<(), VerificationError> {
+    if let Some(public_key) = &log.public_key {
+        if let Some(raw_bytes) = &public_key.raw_bytes {
+            if BASE64_STANDARD.decode(raw_bytes).is_err() {
+                return Err(invalid(format!("{}.publicKey.rawBytes: not base64", path)));
+            }
+        }
+        if let Some(valid_for) = &public_key.valid_for {
+            check_validity(valid_for, &format!("{}.publicKey.validFor", path))?;
+        }
+    }
+    if let Some(log_id) = &log.log_id {
+        if BASE64_STANDARD.decode(&log_id.key_id).is_err() {
+            return Err(invalid(format!("{}.logId.keyId: not base64", path)));
+        }
+    }
+    Ok(())
+}
+
+fn check_validity(valid_for: &ValidityPeriod, path: &str) -> Result<(), VerificationError> {
+    for (field, timestamp) in [("start", &valid_for.start), ("end", &valid_for.end)] {
+        if let Some(timestamp) = timestamp {
+            if DateTime::parse_from_rfc3339(timestamp).is_err() {
+                return Err(invalid(format!("{}.{}: invalid RFC3339 timestamp", path, field)));
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+    use crate::fetcher::jsonl::convert::merge_trusted_roots;
+    use crate::fetcher::jsonl::parser::{
+        select_certificate_authority, select_ctlog_keys, select_rekor_keys, select_timestamp_authority,
+    };
+    use crate::types::certificate::FulcioInstance;
+    use serde_json::Value;
+
+    /// The sample roots as one pretty-printed document, as TUF distributes it
+    fn sample_document() -> Value {
+        let mut path = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        path.push("../../samples/trusted_root.jsonl");
+        let content = std::fs::read_to_string(path).expect("Failed to read trusted_root.jsonl");
+        let roots = load_trusted_root_from_jsonl(&content).unwrap();
+        serde_json::to_value(merge_trusted_roots(&roots).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_parse_official_document() {
+        let mut document = sample_document();
+        document["mediaType"] = TRUSTED_ROOT_MEDIA_TYPE_V0_2.into();
+        // protobuf-JSON omits default values; v0.2 adds fields of its own
+        document["timestampAuthorities"][0].as_object_mut().unwrap().remove("subject");
+        document["tlogs"][0]["checkpointKeyId"] = serde_json::json!({ "keyId": "AAAA" });
+        document["certificateAuthorities"][0]["operator"] = "sigstore.dev".into();
+        let content = serde_json::to_string_pretty(&document).unwrap();
+
+        let root = parse_trusted_root_json(&content).unwrap();
+        assert_eq!(root.media_type, TRUSTED_ROOT_MEDIA_TYPE_V0_2);
+        assert_eq!(root.timestamp_authorities[0].subject.common_name, "");
+        assert_eq!(root.ctlogs.len(), 2);
+
+        // The JSONL selection functions work over the document
+        let roots = load_trusted_roots(&content).unwrap();
+        assert_eq!(roots.len(), 1);
+        let june_2025 = 1_750_000_000;
+        assert!(select_certificate_authority(&roots, &FulcioInstance::GitHub, june_2025).is_ok());
+        assert!(select_timestamp_authority(&roots, &FulcioInstance::GitHub, june_2025).is_ok());
+        assert_eq!(select_rekor_keys(&roots, june_2025).unwrap().len(), 1);
+        assert_eq!(select_ctlog_keys(&roots, june_2025).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_parse_official_document_rejects_malformed_fields() {
+        let parse = |edit: fn(&mut Value)| {
+            let mut document = sample_document();
+            edit(&mut document);
+            parse_trusted_root_json(&document.to_string()).unwrap_err().to_string()
+        };
+
+        let err = parse(|doc| doc["mediaType"] = "application/json".into());
+        assert!(err.contains("mediaType"), "{}", err);
+        let err = parse(|doc| {
+            doc["certificateAuthorities"][1]["certChain"]["certificates"][0]["rawBytes"] = "AAAA".into();
+        });
+        assert!(err.contains("certificateAuthorities[1].certChain.certificates[0]"), "{}", err);
+        let err = parse(|doc| {
+            doc["timestampAuthorities"][0].as_object_mut().unwrap().remove("validFor");
+        });
+        assert!(err.contains("timestampAuthorities[0].validFor.start"), "{}", err);
+        let err = parse(|doc| doc["ctlogs"][1]["publicKey"]["rawBytes"] = "not base64!".into());
+        assert!(err.contains("ctlogs[1].publicKey.rawBytes"), "{}", err);
+    }
+}
@@ -0,0 +1,619 @@
+//! TUF client for the Sigstore trusted root
+//!
+//! Sigstore publishes `trusted_root.json` as a target of its TUF repository.
+//! Downloading the target alone trusts whoever serves it; the TUF metadata lets
+//! a client check that the repository's keys vouch for the exact bytes, and that
+//! old metadata is not replayed forever. [`update_trusted_root`] runs the client
+//! workflow of the TUF specification
+//! (<https://theupdateframework.github.io/specification/latest/#detailed-client-workflow>):
+//!
+//! 1. Root: starting from a trusted `root.json`, fetch `N+1.root.json` until
+//!    there is none; each must be signed by a threshold of both the previous
+//!    root's keys and its own
+//! 2. Timestamp: `timestamp.json`, signed by the timestamp role
+//! 3. Snapshot: the version named by the timestamp, matching the hashes it
+//!    records and signed by the snapshot role
+//! 4. Targets: the version named by the snapshot, signed by the targets role
+//! 5. Target: `trusted_root.json`, matching the length and hashes recorded in
+//!    the targets metadata
+//!
+//! Every metadata file must be unexpired. The client keeps no state but the
+//! root: store [`TufUpdate::root`] and pass it to the next update, so that a
+//! repository serving older root keys is refused. The first root must come from
+//! a trusted source, such as the `root.json` of the sigstore/root-signing
+//! repository pinned by the application.
+//!
+//! Delegated targets roles are not supported: `trusted_root.json` must be a
+//! target of the top-level targets role, as it is in the Sigstore repository.
+
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use chrono::DateTime;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::Value;
+use sha2::{Digest, Sha256, Sha512};
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::crypto::signature::PublicKey;
+use crate::error::SignatureError;
+use crate::fetcher::jsonl::types::TrustedRoot;
+use crate::fetcher::http::HttpClient;
+use crate::fetcher::remote::read_body;
+use crate::fetcher::trusted_root::parse_trusted_root_json;
+use crate::VerificationError;
+
+/// Sigstore's public-good TUF repository
+pub const SIGSTORE_TUF_URL: &str = "https://tuf-repo-cdn.sigstore.dev";
+
+/// Name of the trusted root target
+pub const TRUSTED_ROOT_TARGET: &str = "trusted_root.json";
+
+/// Upper bound on the root versions followed in one update
+const MAX_ROOT_ROTATIONS: u64 = 1024;
+
+/// Outcome of a TUF update
+#[derive(Debug, Clone)]
+pub struct TufUpdate {
+    /// The newest root metadata, to be trusted by the next update
+    pub root: Vec<u8>,
+    /// Version of `root`
+    pub root_version: u64,
+    /// Contents of the `trusted_root.json` target, as verified
+    pub trusted_root_json: Vec<u8>,
+    /// The parsed target
+    pub trusted_root: TrustedRoot,
+}
+
+/// A metadata file: the signed role and its signatures
+#[derive(Debug, Deserialize)]
+struct SignedMetadata {
+    signed: Value,
+    signatures: Vec<MetadataSignature>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MetadataSignature {
+    keyid: String,
+    sig: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Root {
+    version: u64,
+    expires: String,
+    keys: BTreeMap<String, Key>,
+    roles: BTreeMap<String, Role>,
+    #[serde(default)]
+    consistent_snapshot: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct Key {
+    keytype: String,
+    scheme: String,
+    keyval: KeyValue,
+}
+
+#[derive(Debug, Deserialize)]
+struct KeyValue {
+    public: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Role {
+    keyids: Vec<String>,
+    threshold: usize,
+}
+
+/// Timestamp and snapshot metadata, which both list metadata files
+#[derive(Debug, Deserialize)]
+struct MetaListing {
+    version: u64,
+    expires: String,
+    meta: BTreeMap<String, MetaFile>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MetaFile {
+    version: u64,
+    #[serde(default)]
+    length: Option<u64>,
+    #[serde(default)]
+    hashes: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Targets {
+    version: u64,
+    expires: String,
+    targets: BTreeMap<String, TargetFile>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TargetFile {
+    length: u64,
+    hashes: BTreeMap<String, String>,
+}
+
+/// Fetch the trusted root from a TUF repository over HTTPS
+///
+/// # Arguments
+/// * `client` - HTTP client to download the metadata with
+/// * `repository_url` - Base URL of the repository, e.g. [`SIGSTORE_TUF_URL`]
+/// * `root_json` - Trusted root metadata: the pinned initial `root.json`, or the
+///   [`TufUpdate::root`] of the previous update
+///
+/// # Example
+/// ```ignore
+/// use sigstore_verifier::fetcher::http::default_client;
+/// use sigstore_verifier::fetcher::tuf::{fetch_trusted_root_via_tuf, SIGSTORE_TUF_URL};
+///
+/// let update = fetch_trusted_root_via_tuf(&*default_client()?, SIGSTORE_TUF_URL, &std::fs::read("root.json")?)?;
+/// std::fs::write("root.json", &update.root)?;
+/// let roots = vec![update.trusted_root];
+/// ```
+pub fn fetch_trusted_root_via_tuf(
+    client: &dyn HttpClient,
+    repository_url: &str,
+    root_json: &[u8],
+) -> Result<TufUpdate, VerificationError> {
+    let base = repository_url.trim_end_matches('/');
+    update_trusted_root(root_json, chrono::Utc::now().timestamp(), |path| {
+        let url = format!("{}/{}", base, path);
+        let response = client.get(&url, &[])?;
+        if response.status == 404 {
+            return Ok(None);
+        }
+        read_body(&url, response).map(Some)
+    })
+}
+
+/// Run a TUF update with a custom transport
+///
+/// # Arguments
+/// * `root_json` - Trusted root metadata
+/// * `now` - Current time in Unix seconds, against which expiry is checked
+/// * `fetch` - Downloads a path relative to the repository, returning `None`
+///   when it does not exist
+///
+/// # Errors
+/// Returns `VerificationError::Tuf` if any metadata is malformed, expired, not
+/// signed by a threshold of its role's keys or not the version its parent names,
+/// or if the target does not match its recorded length and hashes
+pub fn update_trusted_root(
+    root_json: &[u8],
+    now: i64,
+    mut fetch: impl FnMut(&str) -> Result<Option<Vec<u8>>, VerificationError>,
+) -> Result<TufUpdate, VerificationError> {
+    // Step 1: Follow root rotations, each signed by the old and the new keys
+    let (initial, mut root): (_, Root) = parse_metadata(root_json, "root")?;
+    verify_role(&initial, &root, "root")?;
+    let mut root_bytes = root_json.to_vec();
+    for _ in 0..MAX_ROOT_ROTATIONS {
+        let version = root.version + 1;
+        let Some(bytes) = fetch(&format!("{}.root.json", version))? else {
+            break;
+        };
+        let (signed, next): (_, Root) = parse_metadata(&bytes, "root")?;
+        verify_role(&signed, &root, "root")?;
+        verify_role(&signed, &next, "root")?;
+        if next.version != version {
+            return Err(tuf(format!("{}.root.json has version {}", version, next.version)));
+        }
+        root = next;
+        root_bytes = bytes;
+    }
+    check_expiry("root", &root.expires, now)?;
+
+    let mut fetch_required = |path: &str| {
+        fetch(path)?.ok_or_else(|| tuf(format!("{} not found in the repository", path)))
+    };
+
+    // Step 2: Timestamp
+    let bytes = fetch_required("timestamp.json")?;
+    let (signed, timestamp): (_, MetaListing) = parse_metadata(&bytes, "timestamp")?;
+    verify_role(&signed, &root, "timestamp")?;
+    check_expiry("timestamp", &timestamp.expires, now)?;
+    let snapshot_meta = listed(&timestamp, "snapshot.json")?;
+
+    // Step 3: Snapshot
+    let bytes = fetch_required(&metadata_path(&root, snapshot_meta.version, "snapshot.json"))?;
+    check_meta_file("snapshot.json", &bytes, snapshot_meta)?;
+    let (signed, snapshot): (_, MetaListing) = parse_metadata(&bytes, "snapshot")?;
+    verify_role(&signed, &root, "snapshot")?;
+    check_version("snapshot.json", snapshot.version, snapshot_meta.version)?;
+    check_expiry("snapshot", &snapshot.expires, now)?;
+    let targets_meta = listed(&snapshot, "targets.json")?;
+
+    // Step 4: Targets
+    let bytes = fetch_required(&metadata_path(&root, targets_meta.version, "targets.json"))?;
+    check_meta_file("targets.json", &bytes, targets_meta)?;
+    let (signed, targets): (_, Targets) = parse_metadata(&bytes, "targets")?;
+    verify_role(&signed, &root, "targets")?;
+    check_version("targets.json", targets.version, targets_meta.version)?;
+    check_expiry("targets", &targets.expires, now)?;
+
+    // Step 5: The trusted root target
+    let target = targets
+        .targets
+        .get(TRUSTED_ROOT_TARGET)
+        .ok_or_else(|| tuf(format!("{} is not a target of the top-level targets role", TRUSTED_ROOT_TARGET)))?;
+    let path = if root.consistent_snapshot {
+        let hash = target
+            .hashes
+            .get("sha256")
+            .or_else(|| target.hashes.values().next())
+            .ok_or_else(|| tuf(format!("{} has no hashes", TRUSTED_ROOT_TARGET)))?;
+        format!("targets/{}.{}", hash, TRUSTED_ROOT_TARGET)
+    } else {
+        format!("targets/{}", TRUSTED_ROOT_TARGET)
+    };
+    let content = fetch_required(&path)?;
+    if content.len() as u64 != target.length {
+        return Err(tuf(format!(
+            "{} is {} bytes, targets metadata records {}",
+            TRUSTED_ROOT_TARGET,
+            content.len(),
+            target.length
+        )));
+    }
+    check_hashes(TRUSTED_ROOT_TARGET, &content, &target.hashes, true)?;
+
+    let text = std::str::from_utf8(&content)
+        .map_err(|_| tuf(format!("{} is not UTF-8", TRUSTED_ROOT_TARGET)))?;
+    let trusted_root = parse_trusted_root_json(text)?;
+
+    Ok(TufUpdate {
+        root: root_bytes,
+        root_version: root.version,
+        trusted_root_json: content,
+        trusted_root,
+    })
+}
+
+/// Encode a JSON value in the canonical form TUF signatures are made over
+///
+/// OLPC canonical JSON, as used by python-tuf and go-tuf: object keys sorted,
+/// no insignificant whitespace, and only `"` and `\` escaped in strings.
+pub fn canonical_json(value: &Value) -> Vec<u8> {
+    let mut out = Vec::new();
+    write_canonical(value, &mut out);
+    out
+}
+
+fn write_canonical(value: &Value, out: &mut Vec<u8>) {
+    match value {
+        Value::Null | Value::Bool(_) | Value::Number(_) => out.extend_from_slice(value.to_string().as_bytes()),
+        Value::String(s) => write_canonical_string(s, out),
+        Value::Array(items) => {
+            out.push(b'[');
+            for (index, item) in items.iter().enumerate() {
+                if index > 0 {
+                    out.push(b',');
+                }
+                write_canonical(item, out);
+            }
+            out.push(b']');
+        }
+        Value::Object(map) => {
+            let mut entries: Vec<_> = map.iter().collect();
+            entries.sort_by(|a, b| a.0.as_bytes().cmp(b.0.as_bytes()));
+            out.push(b'{');
+            for (index, (key, item)) in entries.into_iter().enumerate() {
+                if index > 0 {
+                    out.push(b',');
+                }
+                write_canonical_string(key, out);
+                out.push(b':');
+                write_canonical(item, out);
+            }
+            out.push(b'}');
+        }
+    }
+}
+
+fn write_canonical_string(s: &str, out: &mut Vec<u8>) {
+    out.push(b'"');
+    for byte in s.bytes() {
+        if byte == b'"' || byte == b'\\' {
+            out.push(b'\\');
+        }
+        out.push(byte);
+    }
+    out.push(b'"');
+}
+
+/// Parse a metadata file and its signed role, checking the role's `_type`
+fn parse_metadata<T: DeserializeOwned>(bytes: &[u8], role: &str) -> Result<(SignedMetadata, T), VerificationError> {
+    let metadata: SignedMetadata = serde_json::from_slice(bytes)
+        .map_err(|e| tuf(format!("Invalid {} metadata: {}", role, e)))?;
+    if metadata.signed["_type"] != role {
+        return Err(tuf(format!("{} metadata has _type {}", role, metadata.signed["_type"])));
+    }
+    let signed = serde_json::from_value(metadata.signed.clone())
+        .map_err(|e| tuf(format!("Invalid {} metadata: {}", role, e)))?;
+    Ok((metadata, signed))
+}
+
+/// Check that a threshold of the keys `root` assigns to `role` signed `metadata`
+fn verify_role(metadata: &SignedMetadata, root: &Root, role: &str) -> Result<(), VerificationError> {
+    let assigned = root
+        .roles
+        .get(role)
+        .ok_or_else(|| tuf(format!("Root metadata defines no {} role", role)))?;
+    if assigned.threshold == 0 {
+        return Err(tuf(format!("{} role has a threshold of 0", role)));
+    }
+
+    let message = canonical_json(&metadata.signed);
+    let mut verified = BTreeSet::new();
+    for signature in &metadata.signatures {
+        if !assigned.keyids.contains(&signature.keyid) || verified.contains(signature.keyid.as_str()) {
+            continue;
+        }
+        let Some(key) = root.keys.get(&signature.keyid) else {
+            continue;
+        };
+        let Ok(sig) = hex::decode(&signature.sig) else {
+            continue;
+        };
+        if key.public_key()?.verify_signature(&message, &sig).is_ok() {
+            verified.insert(signature.keyid.as_str());
+        }
+    }
+
+    if verified.len() < assigned.threshold {
+        return Err(tuf(format!(
+            "{} metadata is signed by {} of the {} required {} keys",
+            role,
+            verified.len(),
+            assigned.threshold,
+            role
+        )));
+    }
+    Ok(())
+}
+
+impl Key {
+    /// The verifying key, for the key types Sigstore's repository uses
+    fn public_key(&self) -> Result<PublicKey, SignatureError> {
+        match (self.keytype.as_str(), self.scheme.as_str()) {
+            ("ecdsa" | "ecdsa-sha2-nistp256", "ecdsa-sha2-nistp256") => match PublicKey::from_pem(&self.keyval.public)? {
+                key @ PublicKey::P256(_) => Ok(key),
+                _ => Err(SignatureError::PublicKeyParse("ecdsa-sha2-nistp256 key is not on P-256".to_string())),
+            },
+            ("ed25519", "ed25519") => {
+                let bytes: [u8; 32] = hex::decode(&self.keyval.public)
+                    .ok()
+                    .and_then(|bytes| bytes.try_into().ok())
+                    .ok_or_else(|| SignatureError::PublicKeyParse("Ed25519 key must be 32 hex bytes".to_string()))?;
+                ed25519_dalek::VerifyingKey::from_bytes(&bytes)
+                    .map(PublicKey::Ed25519)
+                    .map_err(|e| SignatureError::PublicKeyParse(e.to_string()))
+            }
+            (keytype, scheme) => Err(SignatureError::UnsupportedAlgorithm(format!("TUF key {}/{}", keytype, scheme))),
+        }
+    }
+}
+
+/// Path of a snapshot or targets metadata file, prefixed with its version under consistent snapshots
+fn metadata_path(root: &Root, version: u64, name: &str) -> String {
+    if root.consistent_snapshot {
+        format!("{}.{}", version, name)
+    } else {
+        name.to_string()
+    }
+}
+
+fn listed<'a>(listing: &'a MetaListing, name: &str) -> Result<&'a MetaFile, VerificationError> {
+    listing
+        .meta
+        .get(name)
+        .ok_or_else(|| tuf(format!("{} is not listed in its parent metadata", name)))
+}
+
+fn check_version(name: &str, actual: u64, expected: u64) -> Result<(), VerificationError> {
+    if actual != expected {
+        return Err(tuf(format!("{} has version {}, expected {}", name, actual, expected)));
+    }
+    Ok(())
+}
+
+fn check_expiry(role: &str, expires: &str, now: i64) -> Result<(), VerificationError> {
+    let expires_at = DateTime::parse_from_rfc3339(expires)
+        .map_err(|e| tuf(format!("{} metadata has an invalid expiry '{}': {}", role, expires, e)))?;
+    if now >= expires_at.timestamp() {
+        return Err(tuf(format!("{} metadata expired at {}", role, expires)));
+    }
+    Ok(())
+}
+
+/// Check a downloaded metadata file against the length and hashes its parent records, if any
+fn check_meta_file(name: &str, bytes: &[u8], meta: &MetaFile) -> Result<(), VerificationError> {
+    if let Some(length) = meta.length {
+        if bytes.len() as u64 != length {
+            return Err(tuf(format!("{} is {} bytes, expected {}", name, bytes.len(), length)));
+        }
+    }
+    check_hashes(name, bytes, &meta.hashes, false)
+}
+
+/// Compare `bytes` with every SHA256 and SHA512 in `hashes`; with `required`, at least one must be present
+fn check_hashes(
+    name: &str,
+    bytes: &[u8],
+    hashes: &BTreeMap<String, String>,
+    required: bool,
+) -> Result<(), VerificationError> {
+    let mut checked = 0;
+    for (algorithm, expected) in hashes {
+        let actual = match algorithm.as_str() {
+            "sha256" => hex::encode(Sha256::digest(bytes)),
+            "sha512" => hex::encode(Sha512::digest(bytes)),
+            _ => continue,
+        };
+        if !actual.eq_ignore_ascii_case(expected) {
+            return Err(tuf(format!("{} does not match its {} hash", name, algorithm)));
+        }
+        checked += 1;
+    }
+    if required && checked == 0 {
+        return Err(tuf(format!("{} has no sha256 or sha512 hash", name)));
+    }
+    Ok(())
+}
+
+fn tuf(message: String) -> VerificationError {
+    VerificationError::Tuf(message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fetcher::jsonl::convert::merge_trusted_roots;
+    use crate::fetcher::jsonl::parser::load_trusted_root_from_jsonl;
+    use p256::ecdsa::{signature::Signer, Signature, SigningKey};
+    use p256::pkcs8::{EncodePublicKey, LineEnding};
+    use serde_json::json;
+    use std::collections::HashMap;
+
+    const NOW: i64 = 1_750_000_000;
+    const FUTURE: &str = "2030-01-01T00:00:00Z";
+
+    fn key(seed: u8) -> (String, SigningKey) {
+        (format!("key-{}", seed), SigningKey::from_slice(&[seed; 32]).unwrap())
+    }
+
+    fn key_entry(key: &SigningKey) -> Value {
+        let pem = key.verifying_key().to_public_key_pem(LineEnding::LF).unwrap();
+        json!({ "keytype": "ecdsa", "scheme": "ecdsa-sha2-nistp256", "keyval": { "public": pem } })
+    }
+
+    fn sign(signed: Value, keys: &[&(String, SigningKey)]) -> Vec<u8> {
+        let message = canonical_json(&signed);
+        let signatures: Vec<Value> = keys
+            .iter()
+            .map(|(keyid, key)| {
+                let signature: Signature = key.sign(&message);
+                json!({ "keyid": keyid, "sig": hex::encode(signature.to_der()) })
+            })
+            .collect();
+        serde_json::to_vec_pretty(&json!({ "signed": signed, "signatures": signatures })).unwrap()
+    }
+
+    fn root(version: u64, root_key: &(String, SigningKey), others: &[(String, SigningKey); 3]) -> Value {
+        let mut keys = serde_json::Map::new();
+        keys.insert(root_key.0.clone(), key_entry(&root_key.1));
+        let mut roles = serde_json::Map::new();
+        roles.insert("root".to_string(), json!({ "keyids": [root_key.0], "threshold": 1 }));
+        for (role, (keyid, key)) in ["timestamp", "snapshot", "targets"].iter().zip(others) {
+            keys.insert(keyid.clone(), key_entry(key));
+            roles.insert(role.to_string(), json!({ "keyids": [keyid], "threshold": 1 }));
+        }
+        json!({
+            "_type": "root", "spec_version": "1.0", "version": version, "expires": FUTURE,
+            "keys": keys, "roles": roles, "consistent_snapshot": true
+        })
+    }
+
+    /// A repository with a rotated root: paths and contents, and the initial root
+    fn repository() -> (HashMap<String, Vec<u8>>, Vec<u8>) {
+        let (old_root, new_root) = (key(1), key(2));
+        let roles = [key(3), key(4), key(5)];
+        let mut files = HashMap::new();
+
+        let initial = sign(root(1, &old_root, &roles), &[&old_root]);
+        files.insert("2.root.json".to_string(), sign(root(2, &new_root, &roles), &[&old_root, &new_root]));
+
+        let mut path = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        path.push("../../samples/trusted_root.jsonl");
+        let roots = load_trusted_root_from_jsonl(&std::fs::read_to_string(path).unwrap()).unwrap();
+        let target = serde_json::to_vec_pretty(&merge_trusted_roots(&roots).unwrap()).unwrap();
+        let target_hash = hex::encode(Sha256::digest(&target));
+
+        let targets = sign(
+            json!({
+                "_type": "targets", "spec_version": "1.0", "version": 3, "expires": FUTURE,
+                "targets": { TRUSTED_ROOT_TARGET: { "length": target.len(), "hashes": { "sha256": target_hash } } }
+            }),
+            &[&roles[2]],
+        );
+        let snapshot = sign(
+            json!({
+                "_type": "snapshot", "spec_version": "1.0", "version": 5, "expires": FUTURE,
+                "meta": { "targets.json": { "version": 3 } }
+            }),
+            &[&roles[1]],
+        );
+        let timestamp = sign(
+            json!({
+                "_type": "timestamp", "spec_version": "1.0", "version": 9, "expires": FUTURE,
+                "meta": { "snapshot.json": {
+                    "version": 5, "length": snapshot.len(), "hashes": { "sha256": hex::encode(Sha256::digest(&snapshot)) }
+                } }
+            }),
+            &[&roles[0]],
+        );
+
+        files.insert(format!("targets/{}.{}", target_hash, TRUSTED_ROOT_TARGET), target);
+        files.insert("3.targets.json".to_string(), targets);
+        files.insert("5.snapshot.json".to_string(), snapshot);
+        files.insert("timestamp.json".to_string(), timestamp);
+        (files, initial)
+    }
+
+    fn update(files: &HashMap<String, Vec<u8>>, initial: &[u8], now: i64) -> Result<TufUpdate, VerificationError> {
+        update_trusted_root(initial, now, |path| Ok(files.get(path).cloned()))
+    }
+
+    #[test]
+    fn test_update_follows_rotation_and_verifies_target() {
+        let (files, initial) = repository();
+        let result = update(&files, &initial, NOW).unwrap();
+
+        assert_eq!(result.root_version, 2);
+        assert_eq!(result.root, files["2.root.json"]);
+        assert_eq!(result.trusted_root.ctlogs.len(), 2);
+
+        // The stored root carries on without the initial one
+        assert_eq!(update(&files, &result.root, NOW).unwrap().root_version, 2);
+    }
+
+    #[test]
+    fn test_update_rejects_tampered_or_expired_metadata() {
+        let (files, initial) = repository();
+        let error = |files: &HashMap<String, Vec<u8>>, now: i64| update(files, &initial, now).unwrap_err().to_string();
+
+        // A rotation signed by the new root key only
+        let (old_root, new_root) = (key(1), key(2));
+        let roles = [key(3), key(4), key(5)];
+        let mut rotated = files.clone();
+        rotated.insert("2.root.json".to_string(), sign(root(2, &new_root, &roles), &[&new_root]));
+        assert!(error(&rotated, NOW).contains("root metadata is signed by 0 of the 1"));
+
+        // A target swapped after signing
+        let mut swapped = files.clone();
+        let path = swapped.keys().find(|path| path.starts_with("targets/")).unwrap().clone();
+        swapped.get_mut(&path).unwrap()[0] = b' ';
+        assert!(error(&swapped, NOW).contains("does not match its sha256 hash"));
+
+        // A timestamp signed by the root key rather than the timestamp key
+        let mut resigned = files.clone();
+        let timestamp: Value = serde_json::from_slice(&files["timestamp.json"]).unwrap();
+        resigned.insert("timestamp.json".to_string(), sign(timestamp["signed"].clone(), &[&old_root]));
+        assert!(error(&resigned, NOW).contains("timestamp metadata is signed by 0"));
+
+        assert!(error(&files, 1_900_000_000).contains("root metadata expired"));
+    }
+
+    #[test]
+    fn test_canonical_json() {
+        let value = json!({ "b": [1, true, null], "a": "quote\" back\\slash\nnewline" });
+        assert_eq!(
+            canonical_json(&value),
+            b"{\"a\":\"quote\\\" back\\\\slash\nnewline\",\"b\":[1,true,null]}".to_vec()
+        );
+    }
+}
@@ -1,27 +1,76 @@
+// The verifier only needs `alloc`; the `std` feature adds the file-based
+// helpers, report step timings, profiling, the fetchers and the service.
+#![no_std]
+
+extern crate alloc;
+#[cfg(any(feature = "std", test))]
+extern crate std;
+
 pub mod crypto;
 pub mod error;
 pub mod fetcher;
+pub mod outcome;
 pub mod parser;
+pub mod profile;
+pub mod report;
+#[cfg(feature = "service")]
+pub mod service;
 pub mod types;
 pub mod verifier;
 
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
 use std::path::Path;
 
 use base64::engine::general_purpose::STANDARD as BASE64;
 use base64::Engine;
+#[cfg(feature = "std")]
+use crypto::backend;
+#[cfg(feature = "std")]
+use crypto::hash::digest_reader;
+use crypto::hash::sha256;
 use error::VerificationError;
-use parser::bundle::{parse_bundle_from_bytes, parse_bundle_from_path, parse_dsse_payload};
-use parser::certificate::{certs_to_chain, parse_der_certificate};
+use parser::bundle::{
+    attach_detached_payload, check_bundle_media_type, parse_bundle_from_bytes, parse_bundle_from_protobuf,
+    parse_statement, predicate_sha256, DecodedContent,
+};
+#[cfg(feature = "std")]
+use parser::bundle::parse_bundle_from_path;
+use parser::certificate::parse_der_certificate;
+use parser::git::parse_git_object;
 use parser::identity::extract_oidc_identity;
 use parser::rfc3161::parse_rfc3161_timestamp;
+use outcome::VerificationOutcome;
+use report::{AuditCheck, StepRecorder, VerificationReport};
 use types::certificate::CertificateChain;
-use types::result::{CertificateChainHashes, DigestAlgorithm, TimestampProof, VerificationOptions, VerificationResult};
-use verifier::certificate::{verify_certificate_chain, verify_tsa_certificate_chain};
-use verifier::rfc3161::verify_rfc3161_timestamp;
-use verifier::signature::verify_dsse_signature;
-use verifier::subject::verify_subject_digest;
-use verifier::timestamp::{get_integrated_time, get_rfc3161_time, verify_signing_time_in_validity};
-use verifier::transparency::verify_transparency_log;
+use types::git::{GitObject, GitObjectKind};
+use types::subject::subject_kind;
+use types::result::{
+    flags, CertificateChainHashes, DigestAlgorithm, TimestampKind, TimestampProof,
+    VerificationOptions, VerificationResult,
+};
+use verifier::certificate::{verify_certificate_chain, verify_tsa_certificate_chain_with};
+use verifier::identity::verify_oidc_identity;
+use verifier::limits::{check_bundle_limits, check_chain_length};
+use verifier::profiles::VerificationProfile;
+use verifier::rfc3161::{detect_or_validate_tsa_chain, verify_rfc3161_timestamp, verify_tsa_policy};
+use verifier::sct::verify_embedded_scts;
+use verifier::signature::{
+    verify_all_dsse_signatures, verify_dsse_signature, verify_message_signature,
+};
+#[cfg(feature = "std")]
+use verifier::subject::subject_digest_algorithm;
+use verifier::subject::{verify_git_object_digest, verify_message_digest, verify_subject_digest};
+use verifier::timestamp::{
+    get_integrated_time, get_rfc3161_time, verify_chain_valid_at, verify_signing_age,
+    verify_signing_time_in_validity,
+};
+use verifier::transparency::verify_transparency_log_with;
+
+/// Version of this crate, committed in every `VerificationResult`
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
 
 /// Main attestation verifier
 #[derive(Debug, Clone, Default)]
@@ -49,6 +98,7 @@ impl AttestationVerifier {
     /// - Signing time
     /// - Subject digest
     /// - OIDC identity (if present)
+    #[cfg(feature = "std")]
     pub fn verify_bundle(
         &self,
         bundle_path: &Path,
@@ -57,7 +107,7 @@ impl AttestationVerifier {
         tsa_cert_chain: Option<&CertificateChain>,
     ) -> Result<VerificationResult, VerificationError> {
         let bundle = parse_bundle_from_path(bundle_path)?;
-        self.verify_bundle_internal(&bundle, options, trust_bundle, tsa_cert_chain)
+        self.verify_bundle_internal(&bundle, options, trust_bundle, tsa_cert_chain, None, &StepRecorder::default())
     }
 
     /// Verify a sigstore bundle from raw JSON bytes
@@ -83,8 +133,242 @@ impl AttestationVerifier {
         trust_bundle: &CertificateChain,
         tsa_cert_chain: Option<&CertificateChain>,
     ) -> Result<VerificationResult, VerificationError> {
+        let bundle = profile::step("parse_bundle", || parse_bundle_from_bytes(bundle_json))?;
+        self.verify_bundle_internal(&bundle, options, trust_bundle, tsa_cert_chain, None, &StepRecorder::default())
+    }
+
+    /// Verify a sigstore bundle from raw JSON bytes, reporting every step
+    ///
+    /// Verification is identical to [`Self::verify_bundle_bytes`], whose result
+    /// the report holds, but each step is recorded with its duration and
+    /// outcome, also when the bundle is rejected (see [`report`]). Steps are
+    /// timed with the system clock, so this is for hosts, not zkVM guests.
+    ///
+    /// # Arguments
+    ///
+    /// * `bundle_json` - Raw JSON bytes of the sigstore bundle
+    /// * `options` - Verification options
+    /// * `trust_bundle` - Certificate chain (intermediates and root) for verification
+    /// * `tsa_cert_chain` - Optional TSA certificate chain for RFC 3161 timestamp verification
+    pub fn verify_bundle_report(
+        &self,
+        bundle_json: &[u8],
+        options: VerificationOptions,
+        trust_bundle: &CertificateChain,
+        tsa_cert_chain: Option<&CertificateChain>,
+    ) -> VerificationReport {
+        self.verify_bundle_audit(bundle_json, options, trust_bundle, tsa_cert_chain, &[])
+    }
+
+    /// Verify a sigstore bundle in audit mode, reporting the failure of the
+    /// `relaxed` checks as warnings
+    ///
+    /// Verification is that of [`Self::verify_bundle_report`], except that a
+    /// relaxed [`AuditCheck`] that fails is listed in the report's `warnings`
+    /// and verification goes on. The report's `result` then tells whether the
+    /// bundle passes without those checks, and [`VerificationReport::is_ok`]
+    /// whether it passes with them.
+    ///
+    /// # Arguments
+    ///
+    /// * `bundle_json` - Raw JSON bytes of the sigstore bundle
+    /// * `options` - Verification options
+    /// * `trust_bundle` - Certificate chain (intermediates and root) for verification
+    /// * `tsa_cert_chain` - Optional TSA certificate chain for RFC 3161 timestamp verification
+    /// * `relaxed` - Checks to report as warnings instead of failing on
+    pub fn verify_bundle_audit(
+        &self,
+        bundle_json: &[u8],
+        options: VerificationOptions,
+        trust_bundle: &CertificateChain,
+        tsa_cert_chain: Option<&CertificateChain>,
+        relaxed: &[AuditCheck],
+    ) -> VerificationReport {
+        let steps = StepRecorder::recording(relaxed);
+        let result = steps
+            .run("parse_bundle", || parse_bundle_from_bytes(bundle_json))
+            .and_then(|bundle| {
+                self.verify_bundle_internal(&bundle, options, trust_bundle, tsa_cert_chain, None, &steps)
+            });
+        steps.finish(result)
+    }
+
+    /// Verify a sigstore bundle from raw JSON bytes, telling policy violations
+    /// apart from invalid bundles
+    ///
+    /// Verification is that of [`Self::verify_bundle_bytes`]. A bundle rejected by
+    /// the policy (see [`VerificationError::is_policy_violation`]) is verified
+    /// again without it, and is a [`VerificationOutcome::PolicyViolation`] if it
+    /// then verifies. The outcome is the same whether `options.quarantine` is set
+    /// or not (see [`outcome`]).
+    ///
+    /// # Arguments
+    ///
+    /// * `bundle_json` - Raw JSON bytes of the sigstore bundle
+    /// * `options` - Verification options
+    /// * `trust_bundle` - Certificate chain (intermediates and root) for verification
+    /// * `tsa_cert_chain` - Optional TSA certificate chain for RFC 3161 timestamp verification
+    pub fn verify_bundle_outcome(
+        &self,
+        bundle_json: &[u8],
+        options: VerificationOptions,
+        trust_bundle: &CertificateChain,
+        tsa_cert_chain: Option<&CertificateChain>,
+    ) -> VerificationOutcome {
+        match profile::step("parse_bundle", || parse_bundle_from_bytes(bundle_json)) {
+            Ok(bundle) => self.verify_bundle_classified(
+                &bundle,
+                options,
+                trust_bundle,
+                tsa_cert_chain,
+                None,
+                &StepRecorder::default(),
+            ),
+            Err(error) => VerificationOutcome::Invalid { error },
+        }
+    }
+
+    /// Verify a sigstore bundle from its binary protobuf encoding
+    ///
+    /// Verification is identical to [`Self::verify_bundle_bytes`] on the JSON
+    /// encoding of the same bundle.
+    ///
+    /// # Arguments
+    ///
+    /// * `bundle_protobuf` - Serialized `dev.sigstore.bundle.v1.Bundle` message
+    /// * `options` - Verification options
+    /// * `trust_bundle` - Certificate chain (intermediates and root) for verification
+    /// * `tsa_cert_chain` - Optional TSA certificate chain for RFC 3161 timestamp verification
+    pub fn verify_bundle_protobuf(
+        &self,
+        bundle_protobuf: &[u8],
+        options: VerificationOptions,
+        trust_bundle: &CertificateChain,
+        tsa_cert_chain: Option<&CertificateChain>,
+    ) -> Result<VerificationResult, VerificationError> {
+        let bundle = profile::step("parse_bundle", || parse_bundle_from_protobuf(bundle_protobuf))?;
+        self.verify_bundle_internal(&bundle, options, trust_bundle, tsa_cert_chain, None, &StepRecorder::default())
+    }
+
+    /// Verify a sigstore bundle against a local copy of the attested artifact
+    ///
+    /// The file is hashed in chunks, so it is never read into memory, with the
+    /// algorithm it is attested with (see [`Self::artifact_digest`]). Its digest becomes
+    /// `expected_digest`, so verification succeeds only if the bundle attests
+    /// this very file; the result is that of [`Self::verify_bundle_bytes`].
+    ///
+    /// # Arguments
+    ///
+    /// * `artifact_path` - Path to the attested artifact
+    /// * `bundle_json` - Raw JSON bytes of the sigstore bundle
+    /// * `options` - Verification options
+    /// * `trust_bundle` - Certificate chain (intermediates and root) for verification
+    /// * `tsa_cert_chain` - Optional TSA certificate chain for RFC 3161 timestamp verification
+    ///
+    /// # Errors
+    ///
+    /// Returns `VerificationError::ArtifactRead` if the file can't be read,
+    /// `VerificationError::SubjectDigestMismatch` if `options.expected_digest` is
+    /// set to another digest than the file's, and any error of
+    /// [`Self::verify_bundle_bytes`]
+    #[cfg(feature = "std")]
+    pub fn verify_artifact(
+        &self,
+        artifact_path: &Path,
+        bundle_json: &[u8],
+        options: VerificationOptions,
+        trust_bundle: &CertificateChain,
+        tsa_cert_chain: Option<&CertificateChain>,
+    ) -> Result<VerificationResult, VerificationError> {
+        let bundle = profile::step("parse_bundle", || parse_bundle_from_bytes(bundle_json))?;
+        let (digest, _) = artifact_digest(artifact_path, &bundle, &options)?;
+        if let Some(expected) = options.expected_digest.as_deref() {
+            if expected != digest {
+                return Err(VerificationError::SubjectDigestMismatch {
+                    expected: hex::encode(expected),
+                    actual: hex::encode(&digest),
+                });
+            }
+        }
+
+        let options = VerificationOptions {
+            expected_digest: Some(digest),
+            ..options
+        };
+        self.verify_bundle_internal(&bundle, options, trust_bundle, tsa_cert_chain, None, &StepRecorder::default())
+    }
+
+    /// Digest of a local copy of the artifact a bundle attests, as
+    /// [`Self::verify_artifact`] computes it
+    ///
+    /// The file is hashed with the algorithm it is attested with: that of the
+    /// blob digest of a message signature, or the preferred one carried by the
+    /// subjects of a statement that `expected_subject_name` selects. Hosts that
+    /// verify the bundle elsewhere, e.g. in a zkVM guest, pin `expected_digest`
+    /// to it.
+    ///
+    /// # Returns
+    ///
+    /// The digest of the file and the algorithm it was hashed with
+    #[cfg(feature = "std")]
+    pub fn artifact_digest(
+        &self,
+        artifact_path: &Path,
+        bundle_json: &[u8],
+        options: &VerificationOptions,
+    ) -> Result<(Vec<u8>, DigestAlgorithm), VerificationError> {
         let bundle = parse_bundle_from_bytes(bundle_json)?;
-        self.verify_bundle_internal(&bundle, options, trust_bundle, tsa_cert_chain)
+        artifact_digest(artifact_path, &bundle, options)
+    }
+
+    /// Verify a git commit or annotated tag signed with `gitsign`
+    ///
+    /// The signature is verified like that of a blob-signing bundle, with the
+    /// object without its signature (see [`parser::git`]) as the blob: the
+    /// certificate chain, timestamp and transparency log are checked exactly as
+    /// by [`Self::verify_bundle_bytes`]. The subject is the object itself: its
+    /// digest is the object ID (`DigestAlgorithm::Sha1`) and its name the tag
+    /// name, empty for commits. `expected_digest` and `expected_subject_name`
+    /// are checked against those.
+    ///
+    /// # Arguments
+    ///
+    /// * `kind` - Whether `object` is a commit or a tag
+    /// * `object` - Raw object, as printed by `git cat-file commit|tag <object>`
+    /// * `bundle_json` - Sigstore bundle of the object's signature
+    /// * `options` - Verification options
+    /// * `trust_bundle` - Certificate chain (intermediates and root) for verification
+    /// * `tsa_cert_chain` - Optional TSA certificate chain for RFC 3161 timestamp verification
+    ///
+    /// # Errors
+    ///
+    /// Returns `VerificationError::InvalidGitObject` for an unsigned or malformed
+    /// object, `VerificationError::InvalidBundleFormat` if the bundle holds a DSSE
+    /// envelope, `VerificationError::InvalidOptions` if the options hold a detached
+    /// payload, and any error of
+    /// [`Self::verify_bundle_bytes`]
+    pub fn verify_git_object(
+        &self,
+        kind: GitObjectKind,
+        object: &[u8],
+        bundle_json: &[u8],
+        options: VerificationOptions,
+        trust_bundle: &CertificateChain,
+        tsa_cert_chain: Option<&CertificateChain>,
+    ) -> Result<VerificationResult, VerificationError> {
+        let object = parse_git_object(kind, object)?;
+        let bundle = profile::step("parse_bundle", || parse_bundle_from_bytes(bundle_json))?;
+        if bundle.dsse_envelope().is_some() {
+            return Err(VerificationError::InvalidBundleFormat(
+                "A git object signature must be a message signature, not a DSSE envelope".to_string(),
+            ));
+        }
+        if options.detached_payload.is_some() {
+            return Err(VerificationError::InvalidOptions(
+                "A git object is its own signed payload; no detached payload can be supplied".to_string(),
+            ));
+        }
+        self.verify_bundle_internal(&bundle, options, trust_bundle, tsa_cert_chain, Some(&object), &StepRecorder::default())
     }
 
     fn verify_bundle_internal(
@@ -93,10 +377,132 @@ impl AttestationVerifier {
         options: VerificationOptions,
         trust_bundle: &CertificateChain,
         tsa_cert_chain: Option<&CertificateChain>,
+        git_object: Option<&GitObject>,
+        steps: &StepRecorder,
     ) -> Result<VerificationResult, VerificationError> {
-        // Step 1: Parse and verify subject digest
-        let statement = parse_dsse_payload(&bundle.dsse_envelope)?;
-        let subject_digest = verify_subject_digest(&statement, options.expected_digest.as_deref())?;
+        if options.quarantine {
+            return self
+                .verify_bundle_classified(bundle, options, trust_bundle, tsa_cert_chain, git_object, steps)
+                .into_result();
+        }
+        self.verify_bundle_strict(bundle, options, trust_bundle, tsa_cert_chain, git_object, steps)
+    }
+
+    /// Verify a bundle, and without the policy if the policy rejects it
+    fn verify_bundle_classified(
+        &self,
+        bundle: &types::bundle::SigstoreBundle,
+        options: VerificationOptions,
+        trust_bundle: &CertificateChain,
+        tsa_cert_chain: Option<&CertificateChain>,
+        git_object: Option<&GitObject>,
+        steps: &StepRecorder,
+    ) -> VerificationOutcome {
+        let requested = options.clone();
+        let violation =
+            match self.verify_bundle_strict(bundle, options, trust_bundle, tsa_cert_chain, git_object, steps) {
+                Ok(result) => return VerificationOutcome::Verified(result),
+                Err(error) if error.is_policy_violation() => error,
+                Err(error) => return VerificationOutcome::Invalid { error },
+            };
+
+        // The policy checks run after the options resolved
+        let options = match resolve_options(requested) {
+            Ok(options) => options,
+            Err(error) => return VerificationOutcome::Invalid { error },
+        };
+        // The second run raises the relaxed checks' warnings again
+        steps.clear_warnings();
+        let relaxed = options.without_policy();
+        match self.verify_bundle_strict(bundle, relaxed, trust_bundle, tsa_cert_chain, git_object, steps) {
+            Ok(mut result) => {
+                // Commit the violated policy, not the one the result was produced with
+                result.verification_options = options.encode();
+                result.options_hash = sha256(&result.verification_options);
+                result.flags |= flags::POLICY_VIOLATION;
+                VerificationOutcome::PolicyViolation { result, violation }
+            }
+            Err(error) => VerificationOutcome::Invalid { error },
+        }
+    }
+
+    fn verify_bundle_strict(
+        &self,
+        bundle: &types::bundle::SigstoreBundle,
+        options: VerificationOptions,
+        trust_bundle: &CertificateChain,
+        tsa_cert_chain: Option<&CertificateChain>,
+        git_object: Option<&GitObject>,
+        steps: &StepRecorder,
+    ) -> Result<VerificationResult, VerificationError> {
+        // A profile or repository policy adds its constraints to the caller's, and
+        // all are committed
+        let options = steps.run("options", || resolve_options(options))?;
+
+        // Commit to the constraints before any of them are applied
+        let verification_options = options.encode();
+        let options_hash = sha256(&verification_options);
+
+        // A payload-less envelope is verified over the detached statement; the parser
+        // accepts every supported bundle version, and the options may narrow them
+        let bundle = steps.run("bundle", || {
+            let bundle = attach_detached_payload(bundle, options.detached_payload.as_deref())?;
+            if !options.allowed_bundle_versions.is_empty() {
+                check_bundle_media_type(&bundle.media_type, &options.allowed_bundle_versions)?;
+            }
+            Ok(bundle)
+        })?;
+        let bundle: &types::bundle::SigstoreBundle = &bundle;
+
+        // Step 0: Bound the cryptographic work before doing any of it
+        steps.run("limits", || check_bundle_limits(bundle, trust_bundle, &options.limits))?;
+
+        // Decode the payload and signatures once; the steps below share the bytes
+        let content = steps.run("decode_content", || DecodedContent::decode(&bundle.content))?;
+
+        // Step 1: Parse and verify subject digest; a blob signature's subject is the blob,
+        // or the git object it signs, and there is no predicate
+        let (subject_name, subject_digest, subject_digest_algorithm, predicate_type, predicate_sha256) =
+            steps.run("subject", || match &content {
+                DecodedContent::DsseEnvelope(envelope) => {
+                    let statement = parse_statement(&envelope.payload)?;
+                    if let Some(ref expected) = options.expected_predicate_type {
+                        if statement.predicate_type != *expected {
+                            return Err(VerificationError::PredicateTypeMismatch {
+                                expected: expected.clone(),
+                                actual: statement.predicate_type.clone(),
+                            });
+                        }
+                    }
+                    let (name, digest, algorithm) = verify_subject_digest(
+                        &statement,
+                        options.expected_subject_name.as_deref(),
+                        options.expected_digest.as_deref(),
+                    )?;
+                    let predicate_sha256 = predicate_sha256(&envelope.payload)?;
+                    Ok((name, digest, algorithm, statement.predicate_type, predicate_sha256))
+                }
+                DecodedContent::MessageSignature(_) if options.expected_predicate_type.is_some() => {
+                    Err(VerificationError::InvalidBundleFormat(
+                        "Expected a predicate type, but a message signature has no statement".to_string(),
+                    ))
+                }
+                DecodedContent::MessageSignature(message_signature) => {
+                    let (name, digest, algorithm) = match git_object {
+                        Some(object) => verify_git_object_digest(
+                            object,
+                            options.expected_subject_name.as_deref(),
+                            options.expected_digest.as_deref(),
+                        )?,
+                        None => verify_message_digest(
+                            message_signature,
+                            options.expected_subject_name.as_deref(),
+                            options.expected_digest.as_deref(),
+                        )?,
+                    };
+                    Ok((name, digest, algorithm, String::new(), [0u8; 32]))
+                }
+            })?;
 
         // Step 2: Validate exactly one timestamp mechanism and get signing time
         let has_rfc3161 = bundle
@@ -114,85 +520,151 @@ impl AttestationVerifier {
             .map(|entries| !entries.is_empty())
             .unwrap_or(false);
 
-        // Validate we have a TSA chain for RFC 3161 path
-        if has_rfc3161 && tsa_cert_chain.is_none() {
-            return Err(error::TimestampError::MissingTSAChain.into());
-        }
+        let signing_time = steps.run("signing_time", || {
+            // Get signing time from appropriate mechanism
+            let signing_time = match (has_rfc3161, has_tlog) {
+                (true, true) => return Err(error::TimestampError::BothTimestampMechanisms.into()),
+                (false, false) => return Err(error::TimestampError::NoTimestamp.into()),
+                (true, false) => get_rfc3161_time(bundle)?,
+                (false, true) => get_integrated_time(
+                    &bundle.verification_material.tlog_entries.as_ref().unwrap()[0],
+                )?,
+            };
 
-        // Get signing time from appropriate mechanism
-        let signing_time = match (has_rfc3161, has_tlog) {
-            (true, true) => return Err(error::TimestampError::BothTimestampMechanisms.into()),
-            (false, false) => return Err(error::TimestampError::NoTimestamp.into()),
-            (true, false) => get_rfc3161_time(bundle)?,
-            (false, true) => get_integrated_time(
-                &bundle.verification_material.tlog_entries.as_ref().unwrap()[0],
-            )?,
-        };
+            // Exactly one mechanism is present; the options may require a specific one,
+            // and a TSA policy requires RFC 3161
+            let required = match (options.required_timestamp, &options.expected_tsa_policy) {
+                (Some(TimestampKind::Rekor), Some(_)) => {
+                    return Err(VerificationError::InvalidOptions(
+                        "expected_tsa_policy requires an RFC 3161 timestamp".to_string(),
+                    ))
+                }
+                (None, Some(_)) => Some(TimestampKind::Rfc3161),
+                (required, _) => required,
+            };
+            if let Some(required) = required {
+                let actual = if has_rfc3161 { TimestampKind::Rfc3161 } else { TimestampKind::Rekor };
+                if actual != required {
+                    return Err(error::TimestampError::UnexpectedMechanism {
+                        required: required.to_string(),
+                        actual: actual.to_string(),
+                    }
+                    .into());
+                }
+            }
+            Ok(signing_time)
+        })?;
 
         // Step 3: Verify certificate chain and get hashes
-        let (chain, certificate_hashes) = verify_certificate_chain(bundle, trust_bundle)?;
+        let (chain, certificate_hashes) =
+            steps.run("certificate_chain", || Ok(verify_certificate_chain(bundle, trust_bundle)?))?;
+
+        let leaf_cert = steps.run("certificate_validity", || {
+            // Step 3b: Verify signing time is within certificate validity period
+            let leaf_cert = parse_der_certificate(&chain.leaf)
+                .map_err(|e| VerificationError::InvalidBundleFormat(e.to_string()))?;
+            verify_signing_time_in_validity(&signing_time, &leaf_cert)?;
+
+            // Step 3c: Bound the age of the signature at the caller's verification time
+            match (options.max_signing_age, options.verification_time) {
+                (Some(max_age), Some(verification_time)) => {
+                    verify_signing_age(&signing_time, verification_time, max_age, options.clock_skew)
+                        .or_else(|e| steps.relax(AuditCheck::SigningAge, e))?
+                }
+                (Some(_), None) => {
+                    return Err(VerificationError::InvalidOptions(
+                        "max_signing_age requires a verification_time".to_string(),
+                    ))
+                }
+                _ => {}
+            }
+
+            // Archival mode: the rest of the chain is held to the signing time as well
+            if options.archival {
+                verify_chain_valid_at(&signing_time, &chain)?;
+            }
+            Ok(leaf_cert)
+        })?;
 
-        // Step 3b: Verify signing time is within certificate validity period
-        let leaf_cert = parse_der_certificate(&chain.leaf)
-            .map_err(|e| VerificationError::InvalidBundleFormat(e.to_string()))?;
-        verify_signing_time_in_validity(&signing_time, &leaf_cert)?;
+        // Step 3d: Verify the leaf's signed certificate timestamps (if CT log keys are given)
+        let certificate_timestamps = if options.ctlog_keys.is_empty() {
+            Vec::new()
+        } else {
+            steps.run("certificate_transparency", || {
+                verify_embedded_scts(&chain, &options.ctlog_keys)
+                    .or_else(|e| steps.relax(AuditCheck::CertificateTransparency, e).map(|_| Vec::new()))
+            })?
+        };
 
-        // Step 4: Verify DSSE signature
-        verify_dsse_signature(&bundle.dsse_envelope, &chain)?;
+        // Step 4: Verify the DSSE or message signature
+        steps.run("dsse_signature", || match &content {
+            DecodedContent::DsseEnvelope(envelope) if options.verify_all_signatures => {
+                verify_all_dsse_signatures(envelope, &chain, options.signature_encoding).map(|_| ())
+            }
+            DecodedContent::DsseEnvelope(envelope) => {
+                verify_dsse_signature(envelope, &chain, options.signature_encoding)
+            }
+            DecodedContent::MessageSignature(message_signature) => verify_message_signature(
+                message_signature,
+                &chain,
+                git_object
+                    .map(|object| object.signed_payload.as_slice())
+                    .or(options.detached_payload.as_deref()),
+                options.signature_encoding,
+            ),
+        })?;
 
         // Step 5: Verify timestamp mechanism (RFC 3161 OR Rekor, mutually exclusive)
         // and collect timestamp proof data
         let timestamp_proof = if has_rfc3161 {
             // RFC 3161 path: verify TSA chain and timestamp signature
-            let timestamp_data = &bundle
-                .verification_material
-                .timestamp_verification_data
-                .as_ref()
-                .unwrap() // Safe: checked by has_rfc3161
-                .rfc3161_timestamps
-                .as_ref()
-                .unwrap()[0]; // Safe: has_rfc3161 validates non-empty
-
-            // Decode and parse RFC 3161 timestamp
-            let timestamp_der = BASE64
-                .decode(&timestamp_data.signed_timestamp)
-                .map_err(|e| {
-                    VerificationError::InvalidBundleFormat(format!(
-                        "Failed to decode timestamp: {}",
-                        e
-                    ))
-                })?;
-
-            let parsed_timestamp = parse_rfc3161_timestamp(&timestamp_der)?;
-
-            // Try to extract embedded certificates (takes precedence)
-            let tsa_chain = if let Some(embedded_certs) = parsed_timestamp.certificates.clone() {
-                if !embedded_certs.is_empty() {
-                    // Embedded certs found - use them
-                    certs_to_chain(embedded_certs).map_err(|e| {
-                        error::TimestampError::InvalidTSACertificate(format!(
-                            "Failed to parse embedded TSA certs: {}",
+            let (parsed_timestamp, tsa_chain) = steps.run("tsa_certificate_chain", || {
+                let timestamp_data = &bundle
+                    .verification_material
+                    .timestamp_verification_data
+                    .as_ref()
+                    .unwrap() // Safe: checked by has_rfc3161
+                    .rfc3161_timestamps
+                    .as_ref()
+                    .unwrap()[0]; // Safe: has_rfc3161 validates non-empty
+
+                // Decode and parse RFC 3161 timestamp
+                let timestamp_der = BASE64
+                    .decode(&timestamp_data.signed_timestamp)
+                    .map_err(|e| {
+                        VerificationError::InvalidBundleFormat(format!(
+                            "Failed to decode timestamp: {}",
                             e
                         ))
-                    })?
-                } else {
-                    // Empty embedded cert list - fall back to user-provided
-                    tsa_cert_chain.cloned().unwrap()
-                }
-            } else {
-                // No embedded certs field at all - use user-provided
-                tsa_cert_chain.cloned().unwrap()
-            };
+                    })?;
+
+                let parsed_timestamp = parse_rfc3161_timestamp(&timestamp_der)?;
 
-            // Verify TSA certificate chain and EKU
-            verify_tsa_certificate_chain(&tsa_chain)?;
+                // A trusted chain embedded in the timestamp takes precedence over the provided one
+                let tsa_chain =
+                    detect_or_validate_tsa_chain(&parsed_timestamp, tsa_cert_chain, &options.tsa_roots)?;
+
+                // Embedded TSA chains come from the bundle, so they are capped too
+                check_chain_length(&tsa_chain, &options.limits)?;
+
+                // Verify TSA certificate chain and EKU
+                verify_tsa_certificate_chain_with(&tsa_chain, |e| steps.relax(AuditCheck::TsaEku, e.into()))?;
+                if options.archival {
+                    verify_chain_valid_at(&signing_time, &tsa_chain)?;
+                }
+                Ok((parsed_timestamp, tsa_chain))
+            })?;
 
             // Verify RFC 3161 timestamp token (message imprint + PKCS7 signature)
-            let signature_b64 = &bundle.dsse_envelope.signatures[0].sig;
-            verify_rfc3161_timestamp(bundle, signature_b64, &tsa_chain)?;
+            steps.run("rfc3161_timestamp", || {
+                verify_rfc3161_timestamp(bundle, content.signature()?, &tsa_chain)?;
+                if let Some(ref expected) = options.expected_tsa_policy {
+                    verify_tsa_policy(&parsed_timestamp.tst_info, expected)?;
+                }
+                Ok(())
+            })?;
 
             // Compute TSA chain hashes for the timestamp proof
-            use crate::crypto::hash::sha256;
             let tsa_leaf_hash = sha256(&tsa_chain.leaf);
             let tsa_intermediate_hashes: Vec<[u8; 32]> = tsa_chain
                 .intermediates
@@ -205,6 +677,7 @@ impl AttestationVerifier {
             let message_imprint_algorithm = match parsed_timestamp.tst_info.message_imprint.hash_algorithm {
                 parser::rfc3161::HashAlgorithm::Sha256 => DigestAlgorithm::Sha256,
                 parser::rfc3161::HashAlgorithm::Sha384 => DigestAlgorithm::Sha384,
+                parser::rfc3161::HashAlgorithm::Sha512 => DigestAlgorithm::Sha512,
             };
 
             TimestampProof::Rfc3161 {
@@ -218,22 +691,25 @@ impl AttestationVerifier {
             }
         } else {
             // Rekor path: verify transparency log
-            verify_transparency_log(bundle)?;
+            steps.run("transparency_log", || {
+                verify_transparency_log_with(bundle, &options.rekor_keys, &|check, e| steps.relax(check, e))
+            })?;
 
             // Extract log_id, log_index (tree), and entry_index from tlog entry
             let tlog_entry = &bundle.verification_material.tlog_entries.as_ref().unwrap()[0];
 
-            let log_id: [u8; 32] = if let Some(ref log_id_struct) = tlog_entry.log_id {
-                let log_id_bytes = parser::bundle::decode_base64(&log_id_struct.key_id)
-                    .map_err(|e| VerificationError::InvalidBundleFormat(format!(
-                        "Failed to decode log_id: {}", e
-                    )))?;
-                log_id_bytes.try_into().map_err(|_| {
-                    VerificationError::InvalidBundleFormat("log_id is not 32 bytes".to_string())
-                })?
-            } else {
-                [0u8; 32]
-            };
+            let log_id: [u8; 32] = steps.run("log_id", || match tlog_entry.log_id {
+                Some(ref log_id_struct) => {
+                    let log_id_bytes = parser::bundle::decode_base64(&log_id_struct.key_id)
+                        .map_err(|e| VerificationError::InvalidBundleFormat(format!(
+                            "Failed to decode log_id: {}", e
+                        )))?;
+                    log_id_bytes.try_into().map_err(|_| {
+                        VerificationError::InvalidBundleFormat("log_id is not 32 bytes".to_string())
+                    })
+                }
+                None => Ok([0u8; 32]),
+            })?;
 
             // Tree leaf index (for Merkle proof verification against checkpoint)
             let log_index: u64 = tlog_entry
@@ -256,49 +732,105 @@ impl AttestationVerifier {
         let oidc_identity = extract_oidc_identity(&leaf_cert).ok();
 
         // Step 7: Verify OIDC identity against expected values (if specified)
-        if let Some(ref identity) = oidc_identity {
-            if let Some(ref expected_issuer) = options.expected_issuer {
-                if let Some(ref actual_issuer) = identity.issuer {
-                    if actual_issuer != expected_issuer {
-                        return Err(VerificationError::InvalidBundleFormat(format!(
-                            "OIDC issuer mismatch: expected '{}', got '{}'",
-                            expected_issuer, actual_issuer
-                        )));
-                    }
-                } else {
-                    return Err(VerificationError::InvalidBundleFormat(
-                        "Expected OIDC issuer but none found in certificate".to_string(),
-                    ));
-                }
-            }
+        steps.run("identity", || verify_oidc_identity(oidc_identity.as_ref(), &options))?;
 
-            if let Some(ref expected_subject) = options.expected_subject {
-                if let Some(ref actual_subject) = identity.subject {
-                    if actual_subject != expected_subject {
-                        return Err(VerificationError::InvalidBundleFormat(format!(
-                            "OIDC subject mismatch: expected '{}', got '{}'",
-                            expected_subject, actual_subject
-                        )));
-                    }
-                } else {
-                    return Err(VerificationError::InvalidBundleFormat(
-                        "Expected OIDC subject but none found in certificate".to_string(),
-                    ));
-                }
-            }
-        } else if options.expected_issuer.is_some() || options.expected_subject.is_some() {
-            return Err(VerificationError::InvalidBundleFormat(
-                "Expected OIDC identity but could not extract from certificate".to_string(),
-            ));
+        // Step 8: Evaluate the identity policy (if specified)
+        if let Some(ref policy) = options.identity_policy {
+            steps.run("identity_policy", || {
+                policy.evaluate(oidc_identity.as_ref(), options.normalize_issuer)
+            })?;
+        }
+
+        // Step 9: Check the repository and workflow against the repository policy (if specified)
+        if let Some(ref policy) = options.repository_policy {
+            steps.run("repository_policy", || policy.check(oidc_identity.as_ref()))?;
         }
 
+        let subject_kind = subject_kind(&subject_name, subject_digest_algorithm);
         Ok(VerificationResult {
             certificate_hashes,
             signing_time,
             subject_digest,
-            subject_digest_algorithm: DigestAlgorithm::Sha256, // Currently hardcoded to SHA256
+            subject_digest_algorithm,
             oidc_identity,
             timestamp_proof,
+            options_hash,
+            subject_name,
+            verifier_version: VERSION.to_string(),
+            // Set by the zkVM guest before committing the journal
+            guest_version: String::new(),
+            // Set by the zkVM guest from the prover input
+            annotations_hash: [0u8; 32],
+            flags: verification_flags(&options),
+            // Set by the zkVM guest from the prover input
+            block_anchor: None,
+            // Set by the zkVM guest from the prover input
+            previous_result_hash: None,
+            verification_options,
+            certificate_timestamps,
+            // Set by the zkVM guest from the prover input
+            input_digest: [0u8; 32],
+            predicate_type,
+            predicate_sha256,
+            subject_kind,
+            // Set by the zkVM guest from the prover input
+            policy_owner_hash: None,
         })
     }
 }
+
+/// Apply the profile and repository policy of `options` to them
+fn resolve_options(options: VerificationOptions) -> Result<VerificationOptions, VerificationError> {
+    let options = match options.profile.as_deref() {
+        Some(profile) => VerificationProfile::resolve(profile)?.apply(options)?,
+        None => options,
+    };
+    match options.repository_policy.clone() {
+        Some(policy) => policy.apply(options),
+        None => Ok(options),
+    }
+}
+
+/// Journal flags recording the verification modes in `options`
+fn verification_flags(options: &VerificationOptions) -> u8 {
+    let mut bits = 0;
+    if options.archival {
+        bits |= flags::ARCHIVAL;
+    }
+    if options.verify_all_signatures {
+        bits |= flags::ALL_SIGNATURES;
+    }
+    if options.normalize_issuer {
+        bits |= flags::NORMALIZED_ISSUER;
+    }
+    if options.case_insensitive_subject {
+        bits |= flags::CASE_INSENSITIVE_SUBJECT;
+    }
+    bits
+}
+
+/// Hash a local artifact with the algorithm `bundle` attests it with
+#[cfg(feature = "std")]
+fn artifact_digest(
+    artifact_path: &Path,
+    bundle: &types::bundle::SigstoreBundle,
+    options: &VerificationOptions,
+) -> Result<(Vec<u8>, DigestAlgorithm), VerificationError> {
+    let bundle = attach_detached_payload(bundle, options.detached_payload.as_deref())?;
+    let algorithm = match DecodedContent::decode(&bundle.content)? {
+        DecodedContent::DsseEnvelope(envelope) => subject_digest_algorithm(
+            &parse_statement(&envelope.payload)?,
+            options.expected_subject_name.as_deref(),
+        ),
+        DecodedContent::MessageSignature(message_signature) => match message_signature.algorithm {
+            backend::DigestAlgorithm::Sha256 => DigestAlgorithm::Sha256,
+            backend::DigestAlgorithm::Sha384 => DigestAlgorithm::Sha384,
+            backend::DigestAlgorithm::Sha512 => DigestAlgorithm::Sha512,
+        },
+    };
+
+    let read_error = |e: std::io::Error| VerificationError::ArtifactRead(format!("{}: {}", artifact_path.display(), e));
+    let artifact = std::fs::File::open(artifact_path).map_err(read_error)?;
+    let digest = digest_reader(artifact, algorithm).map_err(read_error)?;
+    Ok((digest, algorithm))
+}
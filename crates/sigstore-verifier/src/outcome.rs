@@ -0,0 +1,97 @@
+//! Verification outcomes that tell policy violations apart
+//!
+//! A bundle can be rejected for being invalid (a certificate chain, signature,
+//! timestamp or inclusion proof that does not verify) or for violating the
+//! policy (an expected digest, identity, repository, predicate type, signing age
+//! or timestamp mechanism it does not meet, see
+//! [`VerificationOptions::without_policy`](crate::types::result::VerificationOptions::without_policy)).
+//! [`AttestationVerifier::verify_bundle_outcome`](crate::AttestationVerifier::verify_bundle_outcome)
+//! tells the two apart: a bundle rejected by the policy is verified again
+//! without it, and is a [`VerificationOutcome::PolicyViolation`] if it then
+//! verifies.
+//!
+//! In quarantine mode (`VerificationOptions::quarantine`) the other verification
+//! methods, and thus the zkVM guests, accept policy violations with the result
+//! of [`VerificationOutcome::into_result`]: the journal carries the
+//! `POLICY_VIOLATION` flag, so dashboards and contracts can quarantine the
+//! attestation instead of losing it.
+
+use core::fmt;
+
+use crate::error::VerificationError;
+use crate::types::result::VerificationResult;
+
+/// Outcome of verifying a bundle: verified, verified but violating the policy, or invalid
+#[derive(Debug)]
+pub enum VerificationOutcome {
+    /// The bundle verified and satisfies the policy
+    Verified(VerificationResult),
+    /// The bundle verified, but violates the policy
+    ///
+    /// `result` is that of verifying the bundle without the policy. Its options
+    /// hash still commits the violated policy, and its flags have
+    /// `POLICY_VIOLATION` set. `violation` is the error the policy rejected the
+    /// bundle with.
+    PolicyViolation {
+        result: VerificationResult,
+        violation: VerificationError,
+    },
+    /// The bundle failed verification, whatever the policy
+    Invalid { error: VerificationError },
+}
+
+impl VerificationOutcome {
+    /// Name of the outcome: `verified`, `policy_violation` or `invalid`
+    pub fn name(&self) -> &'static str {
+        match self {
+            VerificationOutcome::Verified(_) => "verified",
+            VerificationOutcome::PolicyViolation { .. } => "policy_violation",
+            VerificationOutcome::Invalid { .. } => "invalid",
+        }
+    }
+
+    /// Whether the bundle verified and satisfies the policy
+    pub fn is_verified(&self) -> bool {
+        matches!(self, VerificationOutcome::Verified(_))
+    }
+
+    /// Result of the verification, unless the bundle is invalid
+    pub fn result(&self) -> Option<&VerificationResult> {
+        match self {
+            VerificationOutcome::Verified(result) | VerificationOutcome::PolicyViolation { result, .. } => {
+                Some(result)
+            }
+            VerificationOutcome::Invalid { .. } => None,
+        }
+    }
+
+    /// Policy violation or verification error, unless the bundle verified
+    pub fn error(&self) -> Option<&VerificationError> {
+        match self {
+            VerificationOutcome::Verified(_) => None,
+            VerificationOutcome::PolicyViolation { violation, .. } => Some(violation),
+            VerificationOutcome::Invalid { error } => Some(error),
+        }
+    }
+
+    /// The result as quarantine mode returns it: policy violations are accepted
+    /// with their flagged result, invalid bundles are rejected
+    pub fn into_result(self) -> Result<VerificationResult, VerificationError> {
+        match self {
+            VerificationOutcome::Verified(result) | VerificationOutcome::PolicyViolation { result, .. } => Ok(result),
+            VerificationOutcome::Invalid { error } => Err(error),
+        }
+    }
+}
+
+impl fmt::Display for VerificationOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VerificationOutcome::Verified(_) => write!(f, "verified"),
+            VerificationOutcome::PolicyViolation { violation, .. } => {
+                write!(f, "policy violation (error {}): {}", violation.code(), violation)
+            }
+            VerificationOutcome::Invalid { error } => write!(f, "invalid (error {}): {}", error.code(), error),
+        }
+    }
+}
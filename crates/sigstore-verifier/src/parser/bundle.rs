@@ -1,42 +1,169 @@
+use alloc::borrow::Cow;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
 use std::path::Path;
 
 use base64::prelude::*;
+use serde::Deserialize;
+use serde_json::value::RawValue;
+use crate::crypto::backend::DigestAlgorithm;
+use crate::crypto::hash::sha256;
 use crate::error::VerificationError;
+use crate::parser::legacy::parse_legacy_bundle_from_bytes;
+use crate::parser::protobuf::decode_bundle;
 use crate::parser::rfc3161::parse_rfc3161_timestamp;
 use crate::parser::timestamp::parse_integrated_time;
-use crate::types::bundle::{DsseEnvelope, SigstoreBundle};
+use crate::types::bundle::{
+    bundle_media_type_version, BundleContent, Certificate, DsseEnvelope, MessageDigest,
+    MessageSignature, SigstoreBundle, TimestampVerificationData, TransparencyLogEntry,
+    VerificationMaterial, SUPPORTED_BUNDLE_VERSIONS,
+};
 use crate::types::dsse::Statement;
 
+#[cfg(feature = "std")]
 pub fn parse_bundle_from_path(path: &Path) -> Result<SigstoreBundle, VerificationError> {
     let contents = std::fs::read_to_string(path)
         .map_err(|e| VerificationError::InvalidBundleFormat(e.to_string()))?;
     parse_bundle_from_str(&contents)
 }
 
+/// Parse a Sigstore bundle, or a legacy cosign bundle mapped onto one
+///
+/// v0.1 and v0.2 bundles are mapped onto the v0.3 layout and keep their media type.
 pub fn parse_bundle_from_bytes(bytes: &[u8]) -> Result<SigstoreBundle, VerificationError> {
-    let bundle: SigstoreBundle = serde_json::from_slice(bytes)?;
+    let bundle: SigstoreBundle = match serde_json::from_slice(bytes) {
+        Ok(bundle) => bundle,
+        Err(_) if is_pre_v03_bundle(bytes) => parse_pre_v03_bundle(bytes)?,
+        Err(e) if is_legacy_bundle(bytes) => {
+            parse_legacy_bundle_from_bytes(bytes).map_err(|legacy| match legacy {
+                // Not legacy either: the original error is the useful one
+                VerificationError::BundleParse(_) => VerificationError::BundleParse(e),
+                legacy => legacy,
+            })?
+        }
+        Err(e) => return Err(e.into()),
+    };
     validate_bundle(&bundle)?;
     Ok(bundle)
 }
 
 pub fn parse_bundle_from_str(json: &str) -> Result<SigstoreBundle, VerificationError> {
-    let bundle: SigstoreBundle = serde_json::from_str(json)?;
+    parse_bundle_from_bytes(json.as_bytes())
+}
+
+/// Parse a Sigstore bundle in its binary protobuf encoding
+///
+/// The counterpart of [`parse_bundle_from_bytes`] for
+/// `application/vnd.dev.sigstore.bundle+protobuf` files. The result is the same
+/// [`SigstoreBundle`] the JSON encoding of the bundle parses to, and v0.1/v0.2
+/// bundles are mapped onto the v0.3 layout in the same way.
+///
+/// # Errors
+///
+/// Returns `VerificationError::InvalidBundleFormat` if the bytes are not a
+/// protobuf bundle, and the errors of [`parse_bundle_from_bytes`] for bundles
+/// that are not acceptable
+pub fn parse_bundle_from_protobuf(bytes: &[u8]) -> Result<SigstoreBundle, VerificationError> {
+    let decoded = decode_bundle(bytes)?;
+    if decoded.certificate.is_none()
+        && !decoded.x509_certificate_chain.is_empty()
+        && bundle_media_type_version(&decoded.media_type) == Some("0.3")
+    {
+        return Err(VerificationError::InvalidBundleFormat(
+            "v0.3 bundles carry the signing certificate in verificationMaterial.certificate, not x509CertificateChain"
+                .to_string(),
+        ));
+    }
+    let bundle = decoded.into_bundle()?;
     validate_bundle(&bundle)?;
     Ok(bundle)
 }
 
-fn validate_bundle(bundle: &SigstoreBundle) -> Result<(), VerificationError> {
-    if !bundle
-        .media_type
-        .starts_with("application/vnd.dev.sigstore.bundle")
-    {
-        return Err(VerificationError::InvalidBundleFormat(format!(
-            "Unsupported media type: {}",
-            bundle.media_type
-        )));
+/// Cheap check for the legacy cosign layout, so current bundles are parsed once
+fn is_legacy_bundle(bytes: &[u8]) -> bool {
+    const MARKER: &[u8] = b"\"rekorBundle\"";
+    bytes.windows(MARKER.len()).any(|window| window == MARKER)
+}
+
+/// Cheap check for the certificate layout of bundles before v0.3
+fn is_pre_v03_bundle(bytes: &[u8]) -> bool {
+    const MARKER: &[u8] = b"\"x509CertificateChain\"";
+    bytes.windows(MARKER.len()).any(|window| window == MARKER)
+}
+
+/// Bundle layout of v0.1 and v0.2
+///
+/// Before v0.3 the signing certificate was shipped as a chain, leaf first, under
+/// `verificationMaterial.x509CertificateChain`. Timestamps, tlog entries and the
+/// signed content are laid out as in v0.3.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BundleV02 {
+    media_type: String,
+    verification_material: VerificationMaterialV02,
+    #[serde(flatten)]
+    content: BundleContent,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct VerificationMaterialV02 {
+    #[serde(default, alias = "timestamp_verification_data")]
+    timestamp_verification_data: Option<TimestampVerificationData>,
+    #[serde(alias = "x509_certificate_chain")]
+    x509_certificate_chain: X509CertificateChain,
+    #[serde(default, alias = "tlog_entries")]
+    tlog_entries: Option<Vec<TransparencyLogEntry>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct X509CertificateChain {
+    certificates: Vec<Certificate>,
+}
+
+/// Parse a v0.1 or v0.2 bundle and map it onto the v0.3 layout
+///
+/// Only the leaf of the shipped chain is kept: intermediates and root come from
+/// the trust bundle, as for v0.3 bundles.
+///
+/// # Errors
+///
+/// Returns `VerificationError::InvalidBundleFormat` if the chain is empty or
+/// the media type says v0.3, whose bundles carry a single `certificate`
+fn parse_pre_v03_bundle(bytes: &[u8]) -> Result<SigstoreBundle, VerificationError> {
+    let bundle: BundleV02 = serde_json::from_slice(bytes)?;
+    if bundle_media_type_version(&bundle.media_type) == Some("0.3") {
+        return Err(VerificationError::InvalidBundleFormat(
+            "v0.3 bundles carry the signing certificate in verificationMaterial.certificate, not x509CertificateChain"
+                .to_string(),
+        ));
     }
 
-    if bundle.dsse_envelope.signatures.is_empty() {
+    let material = bundle.verification_material;
+    let certificate = material
+        .x509_certificate_chain
+        .certificates
+        .into_iter()
+        .next()
+        .ok_or_else(|| VerificationError::InvalidBundleFormat("Empty x509CertificateChain".to_string()))?;
+
+    Ok(SigstoreBundle {
+        media_type: bundle.media_type,
+        verification_material: VerificationMaterial {
+            timestamp_verification_data: material.timestamp_verification_data,
+            certificate,
+            tlog_entries: material.tlog_entries,
+        },
+        content: bundle.content,
+    })
+}
+
+fn validate_bundle(bundle: &SigstoreBundle) -> Result<(), VerificationError> {
+    check_bundle_media_type(&bundle.media_type, SUPPORTED_BUNDLE_VERSIONS)?;
+
+    if bundle.signature().is_none() {
         return Err(VerificationError::InvalidBundleFormat(
             "No signatures in DSSE envelope".to_string(),
         ));
@@ -45,12 +172,285 @@ fn validate_bundle(bundle: &SigstoreBundle) -> Result<(), VerificationError> {
     Ok(())
 }
 
+/// Check that a bundle media type names one of the allowed format versions
+///
+/// Fails closed: media types without a recognizable version are rejected too.
+///
+/// # Arguments
+///
+/// * `media_type` - The bundle's `mediaType`
+/// * `allowed` - Allowed versions (e.g. `["0.3"]`)
+///
+/// # Errors
+///
+/// Returns `VerificationError::UnsupportedMediaType` naming the version found and
+/// the allowed version closest to it
+pub fn check_bundle_media_type<S: AsRef<str>>(
+    media_type: &str,
+    allowed: &[S],
+) -> Result<(), VerificationError> {
+    let version = bundle_media_type_version(media_type);
+    if let Some(version) = version {
+        if allowed.iter().any(|allowed| allowed.as_ref() == version) {
+            return Ok(());
+        }
+    }
+
+    Err(VerificationError::UnsupportedMediaType {
+        media_type: media_type.to_string(),
+        version: version.unwrap_or("unknown").to_string(),
+        nearest: nearest_version(version, allowed),
+    })
+}
+
+/// Pick the allowed version closest to `version`: same major version first,
+/// then the smallest distance, preferring the newer version on ties. Without a
+/// version to compare, the newest allowed version is returned.
+fn nearest_version<S: AsRef<str>>(version: Option<&str>, allowed: &[S]) -> String {
+    fn numbers(version: &str) -> (u64, u64) {
+        let mut parts = version.split('.').map(|part| part.parse().unwrap_or(0));
+        (parts.next().unwrap_or(0), parts.next().unwrap_or(0))
+    }
+
+    let target = version.map(numbers);
+    allowed
+        .iter()
+        .map(|allowed| allowed.as_ref())
+        .max_by_key(|allowed| {
+            let (major, minor) = numbers(allowed);
+            match target {
+                Some((target_major, target_minor)) => (
+                    core::cmp::Reverse(major.abs_diff(target_major)),
+                    core::cmp::Reverse(minor.abs_diff(target_minor)),
+                    (major, minor),
+                ),
+                None => (core::cmp::Reverse(0), core::cmp::Reverse(0), (major, minor)),
+            }
+        })
+        .unwrap_or("none")
+        .to_string()
+}
+
+/// Attach a detached statement to a payload-less DSSE envelope
+///
+/// Some DSSE producers sign a statement stored elsewhere and ship the envelope
+/// without it. The statement is put back into the envelope, so the PAE, the
+/// subject and everything else are computed over it exactly as for an embedded
+/// payload. The signature binds the statement, so a substituted one fails
+/// signature verification.
+///
+/// Message signature bundles are returned unchanged; their detached artifact is
+/// checked against the message digest when the signature is verified.
+///
+/// # Arguments
+///
+/// * `bundle` - The parsed bundle
+/// * `detached` - The statement bytes, if supplied separately
+///
+/// # Returns
+///
+/// The bundle unchanged if its envelope carries a payload, otherwise a copy with
+/// the detached statement as payload
+///
+/// # Errors
+///
+/// Returns `VerificationError::MissingPayload` if the envelope has no payload and
+/// none is supplied, or `VerificationError::DetachedPayloadMismatch` if both are
+/// present and differ
+pub fn attach_detached_payload<'a>(
+    bundle: &'a SigstoreBundle,
+    detached: Option<&[u8]>,
+) -> Result<Cow<'a, SigstoreBundle>, VerificationError> {
+    let Some(envelope) = bundle.dsse_envelope() else {
+        return Ok(Cow::Borrowed(bundle));
+    };
+
+    match (envelope.payload.is_empty(), detached) {
+        (true, Some(statement)) => {
+            let mut bundle = bundle.clone();
+            if let BundleContent::DsseEnvelope(ref mut envelope) = bundle.content {
+                envelope.payload = BASE64_STANDARD.encode(statement);
+            }
+            Ok(Cow::Owned(bundle))
+        }
+        (true, None) => Err(VerificationError::MissingPayload),
+        (false, Some(statement)) => {
+            if decode_base64(&envelope.payload)? != statement {
+                return Err(VerificationError::DetachedPayloadMismatch);
+            }
+            Ok(Cow::Borrowed(bundle))
+        }
+        (false, None) => Ok(Cow::Borrowed(bundle)),
+    }
+}
+
 pub fn parse_dsse_payload(envelope: &DsseEnvelope) -> Result<Statement, VerificationError> {
     let payload_bytes = BASE64_STANDARD.decode(&envelope.payload)?;
-    let statement: Statement = serde_json::from_slice(&payload_bytes)?;
+    parse_statement(&payload_bytes)
+}
+
+/// Parse a decoded DSSE payload as an in-toto statement
+pub fn parse_statement(payload: &[u8]) -> Result<Statement, VerificationError> {
+    let statement: Statement = serde_json::from_slice(payload)?;
     Ok(statement)
 }
 
+/// SHA256 of the predicate of a decoded DSSE payload, over its JSON as signed
+///
+/// Hashing the signed bytes rather than a re-serialization keeps the digest
+/// independent of how a JSON library orders keys or formats numbers.
+pub fn predicate_sha256(payload: &[u8]) -> Result<[u8; 32], VerificationError> {
+    #[derive(Deserialize)]
+    struct RawStatement<'a> {
+        #[serde(borrow)]
+        predicate: &'a RawValue,
+    }
+
+    let statement: RawStatement = serde_json::from_slice(payload)?;
+    Ok(sha256(statement.predicate.get().as_bytes()))
+}
+
+/// Bundle content with its base64 fields decoded
+///
+/// The payload and signatures are needed by several verification steps; decoding
+/// them once up front spares each step its own copy.
+#[derive(Debug, Clone)]
+pub enum DecodedContent<'a> {
+    DsseEnvelope(DecodedEnvelope<'a>),
+    MessageSignature(DecodedMessageSignature),
+}
+
+impl<'a> DecodedContent<'a> {
+    /// Decode the content of a bundle
+    ///
+    /// # Errors
+    ///
+    /// Returns `VerificationError::Base64Decode` if the envelope payload, message
+    /// digest or message signature is not base64, and
+    /// `VerificationError::InvalidBundleFormat` for an unsupported digest algorithm
+    pub fn decode(content: &'a BundleContent) -> Result<Self, VerificationError> {
+        Ok(match content {
+            BundleContent::DsseEnvelope(envelope) => {
+                DecodedContent::DsseEnvelope(DecodedEnvelope::decode(envelope)?)
+            }
+            BundleContent::MessageSignature(message_signature) => {
+                DecodedContent::MessageSignature(DecodedMessageSignature::decode(message_signature)?)
+            }
+        })
+    }
+
+    /// Signature that timestamps are made over: the first DSSE signature, or the
+    /// message signature
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the envelope has no signatures or the first is not base64
+    pub fn signature(&self) -> Result<&[u8], VerificationError> {
+        match self {
+            DecodedContent::DsseEnvelope(envelope) => envelope.first_signature(),
+            DecodedContent::MessageSignature(message_signature) => Ok(&message_signature.signature),
+        }
+    }
+}
+
+/// A DSSE envelope with its payload and signatures decoded
+#[derive(Debug, Clone)]
+pub struct DecodedEnvelope<'a> {
+    pub envelope: &'a DsseEnvelope,
+    pub payload: Vec<u8>,
+    /// Decoded signatures, in envelope order
+    ///
+    /// Kept as results, as only the first signature has to decode unless every
+    /// signature is verified.
+    pub signatures: Vec<Result<Vec<u8>, base64::DecodeError>>,
+}
+
+impl<'a> DecodedEnvelope<'a> {
+    /// Decode the payload and signatures of an envelope
+    ///
+    /// # Errors
+    ///
+    /// Returns `VerificationError::Base64Decode` if the payload is not base64
+    pub fn decode(envelope: &'a DsseEnvelope) -> Result<Self, VerificationError> {
+        Ok(Self {
+            envelope,
+            payload: decode_base64(&envelope.payload)?,
+            signatures: envelope
+                .signatures
+                .iter()
+                .map(|signature| BASE64_STANDARD.decode(&signature.sig))
+                .collect(),
+        })
+    }
+
+    /// The first signature, which is the one timestamped and logged
+    ///
+    /// # Errors
+    ///
+    /// Returns `VerificationError::InvalidBundleFormat` for an envelope without
+    /// signatures, or `VerificationError::Base64Decode` if it is not base64
+    pub fn first_signature(&self) -> Result<&[u8], VerificationError> {
+        match self.signatures.first() {
+            Some(Ok(signature)) => Ok(signature),
+            Some(Err(e)) => Err(e.clone().into()),
+            None => Err(VerificationError::InvalidBundleFormat(
+                "No signatures in envelope".to_string(),
+            )),
+        }
+    }
+}
+
+/// A message signature with its digest and signature decoded
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecodedMessageSignature {
+    pub algorithm: DigestAlgorithm,
+    pub digest: Vec<u8>,
+    pub signature: Vec<u8>,
+}
+
+impl DecodedMessageSignature {
+    /// Decode the digest and signature of a message signature
+    ///
+    /// # Errors
+    ///
+    /// See [`decode_message_digest`]; the signature must be base64 as well
+    pub fn decode(message_signature: &MessageSignature) -> Result<Self, VerificationError> {
+        let (algorithm, digest) = decode_message_digest(&message_signature.message_digest)?;
+        Ok(Self {
+            algorithm,
+            digest,
+            signature: decode_base64(&message_signature.signature)?,
+        })
+    }
+}
+
+/// Decode the digest of a message signature
+///
+/// # Returns
+///
+/// The hash algorithm and the raw digest
+///
+/// # Errors
+///
+/// Returns `VerificationError::InvalidBundleFormat` for hash algorithms other
+/// than `SHA2_256`, `SHA2_384` and `SHA2_512`
+pub fn decode_message_digest(
+    message_digest: &MessageDigest,
+) -> Result<(DigestAlgorithm, Vec<u8>), VerificationError> {
+    let algorithm = match message_digest.algorithm.as_str() {
+        "SHA2_256" => DigestAlgorithm::Sha256,
+        "SHA2_384" => DigestAlgorithm::Sha384,
+        "SHA2_512" => DigestAlgorithm::Sha512,
+        other => {
+            return Err(VerificationError::InvalidBundleFormat(format!(
+                "Unsupported message digest algorithm: {}",
+                other
+            )))
+        }
+    };
+    Ok((algorithm, decode_base64(&message_digest.digest)?))
+}
+
 pub fn decode_base64(input: &str) -> Result<Vec<u8>, VerificationError> {
     BASE64_STANDARD.decode(input).map_err(|e| e.into())
 }
@@ -100,6 +500,7 @@ pub fn extract_bundle_timestamp(bundle: &SigstoreBundle) -> Result<i64, Verifica
 #[cfg(test)]
 mod tests {
     use super::*;
+    use alloc::vec;
 
     #[test]
     fn test_validate_bundle_invalid_media_type() {
@@ -114,13 +515,14 @@ mod tests {
                 },
                 tlog_entries: None,
             },
-            dsse_envelope: DsseEnvelope {
+            content: BundleContent::DsseEnvelope(DsseEnvelope {
                 payload: String::new(),
                 payload_type: String::new(),
                 signatures: vec![Signature {
                     sig: String::new(),
+                    keyid: String::new(),
                 }],
-            },
+            }),
         };
 
         let result = validate_bundle(&bundle);
@@ -128,5 +530,213 @@ mod tests {
 
         bundle.media_type = "application/vnd.dev.sigstore.bundle.v0.3+json".to_string();
         assert!(validate_bundle(&bundle).is_ok());
+
+        bundle.media_type = "application/vnd.dev.sigstore.bundle+json;version=0.3".to_string();
+        assert!(validate_bundle(&bundle).is_ok());
+    }
+
+    fn bundle_with_payload(payload: &str) -> SigstoreBundle {
+        use crate::types::bundle::{Certificate, Signature, VerificationMaterial};
+
+        SigstoreBundle {
+            media_type: "application/vnd.dev.sigstore.bundle.v0.3+json".to_string(),
+            verification_material: VerificationMaterial {
+                timestamp_verification_data: None,
+                certificate: Certificate {
+                    raw_bytes: String::new(),
+                },
+                tlog_entries: None,
+            },
+            content: BundleContent::DsseEnvelope(DsseEnvelope {
+                payload: payload.to_string(),
+                payload_type: "application/vnd.in-toto+json".to_string(),
+                signatures: vec![Signature {
+                    sig: String::new(),
+                    keyid: String::new(),
+                }],
+            }),
+        }
+    }
+
+    #[test]
+    fn test_attach_detached_payload() {
+        let statement = br#"{"_type":"https://in-toto.io/Statement/v1"}"#;
+
+        let detached = bundle_with_payload("");
+        let attached = attach_detached_payload(&detached, Some(statement)).unwrap();
+        assert!(matches!(attached, Cow::Owned(_)));
+        assert_eq!(decode_base64(&attached.dsse_envelope().unwrap().payload).unwrap(), statement);
+
+        assert!(matches!(
+            attach_detached_payload(&detached, None),
+            Err(VerificationError::MissingPayload)
+        ));
+
+        let embedded = bundle_with_payload(&BASE64_STANDARD.encode(statement));
+        assert!(matches!(
+            attach_detached_payload(&embedded, Some(statement)).unwrap(),
+            Cow::Borrowed(_)
+        ));
+        assert!(matches!(
+            attach_detached_payload(&embedded, Some(b"{}")),
+            Err(VerificationError::DetachedPayloadMismatch)
+        ));
+        assert!(matches!(attach_detached_payload(&embedded, None).unwrap(), Cow::Borrowed(_)));
+    }
+
+    #[test]
+    fn test_predicate_sha256_hashes_signed_bytes() {
+        let statement = br#"{"_type":"https://in-toto.io/Statement/v1","predicate": {"b": 1, "a": [2]},"subject":[]}"#;
+        assert_eq!(predicate_sha256(statement).unwrap(), sha256(br#"{"b": 1, "a": [2]}"#));
+        assert!(predicate_sha256(br#"{"_type":"https://in-toto.io/Statement/v1"}"#).is_err());
+    }
+
+    #[test]
+    fn test_payload_less_envelope_parses() {
+        let mut json = serde_json::to_value(bundle_with_payload("")).unwrap();
+        assert!(json["dsseEnvelope"].get("payload").is_none());
+        json["dsseEnvelope"]["payload"] = serde_json::Value::String(String::new());
+        assert!(parse_bundle_from_bytes(json.to_string().as_bytes()).is_ok());
+    }
+
+    #[test]
+    fn test_message_signature_bundle_parses() {
+        let mut json = serde_json::to_value(bundle_with_payload("")).unwrap();
+        let object = json.as_object_mut().unwrap();
+        object.remove("dsseEnvelope");
+        object.insert(
+            "messageSignature".to_string(),
+            serde_json::json!({
+                "messageDigest": { "algorithm": "SHA2_256", "digest": BASE64_STANDARD.encode([1u8; 32]) },
+                "signature": "c2ln"
+            }),
+        );
+
+        let bundle = parse_bundle_from_bytes(json.to_string().as_bytes()).unwrap();
+        let message_signature = bundle.message_signature().unwrap();
+        assert!(bundle.dsse_envelope().is_none());
+        assert_eq!(bundle.signature(), Some("c2ln"));
+        let (algorithm, digest) = decode_message_digest(&message_signature.message_digest).unwrap();
+        assert_eq!((algorithm, digest), (DigestAlgorithm::Sha256, vec![1u8; 32]));
+
+        // Nothing signed at all
+        json.as_object_mut().unwrap().remove("messageSignature");
+        assert!(parse_bundle_from_bytes(json.to_string().as_bytes()).is_err());
+    }
+
+    /// `bundle` in the v0.2 layout, with the certificate in a chain
+    fn as_v02_json(bundle: &SigstoreBundle) -> serde_json::Value {
+        let mut json = serde_json::to_value(bundle).unwrap();
+        json["mediaType"] = "application/vnd.dev.sigstore.bundle+json;version=0.2".into();
+        let material = json["verificationMaterial"].as_object_mut().unwrap();
+        let certificate = material.remove("certificate").unwrap();
+        material.insert(
+            "x509CertificateChain".to_string(),
+            serde_json::json!({ "certificates": [certificate, { "rawBytes": "aW50ZXJtZWRpYXRl" }] }),
+        );
+        json
+    }
+
+    #[test]
+    fn test_v02_bundle_maps_onto_v03_layout() {
+        let mut v03 = bundle_with_payload("cGF5bG9hZA==");
+        v03.verification_material.certificate.raw_bytes = "bGVhZg==".to_string();
+        let json = as_v02_json(&v03);
+
+        let bundle = parse_bundle_from_bytes(json.to_string().as_bytes()).unwrap();
+        assert_eq!(bundle.media_type, "application/vnd.dev.sigstore.bundle+json;version=0.2");
+        // The leaf is kept, the rest of the chain comes from the trust bundle
+        assert_eq!(bundle.verification_material.certificate.raw_bytes, "bGVhZg==");
+        assert_eq!(bundle.dsse_envelope().unwrap().payload, "cGF5bG9hZA==");
+    }
+
+    #[test]
+    fn test_pre_v03_layout_rejected_when_malformed() {
+        let mut json = as_v02_json(&bundle_with_payload("cGF5bG9hZA=="));
+        json["verificationMaterial"]["x509CertificateChain"]["certificates"] = serde_json::json!([]);
+        assert!(matches!(
+            parse_bundle_from_bytes(json.to_string().as_bytes()),
+            Err(VerificationError::InvalidBundleFormat(_))
+        ));
+
+        // v0.3 bundles carry a single certificate
+        let mut json = as_v02_json(&bundle_with_payload("cGF5bG9hZA=="));
+        json["mediaType"] = "application/vnd.dev.sigstore.bundle.v0.3+json".into();
+        assert!(matches!(
+            parse_bundle_from_bytes(json.to_string().as_bytes()),
+            Err(VerificationError::InvalidBundleFormat(_))
+        ));
+    }
+
+    #[test]
+    fn test_tlog_entry_proto_field_names_parse() {
+        let mut json = as_v02_json(&bundle_with_payload("cGF5bG9hZA=="));
+        let material = json["verificationMaterial"].as_object_mut().unwrap();
+        material.remove("tlogEntries");
+        material.insert("tlog_entries".to_string(), serde_json::json!([{
+            "log_index": "7",
+            "log_id": { "key_id": "a2V5" },
+            "kind_version": { "kind": "dsse", "version": "0.0.1" },
+            "integrated_time": "1700000000",
+            "inclusion_promise": { "signed_entry_timestamp": "c2V0" },
+            "inclusion_proof": {
+                "log_index": "3",
+                "root_hash": "cm9vdA==",
+                "tree_size": "8",
+                "hashes": [],
+                "checkpoint": { "envelope": "" }
+            },
+            "canonicalized_body": "Ym9keQ=="
+        }]));
+
+        let bundle = parse_bundle_from_bytes(json.to_string().as_bytes()).unwrap();
+        let entry = &bundle.verification_material.tlog_entries.as_ref().unwrap()[0];
+        assert_eq!(entry.log_index.as_deref(), Some("7"));
+        assert_eq!(entry.integrated_time, "1700000000");
+        assert_eq!(entry.inclusion_promise.as_ref().unwrap().signed_entry_timestamp, "c2V0");
+        assert_eq!(entry.inclusion_proof.as_ref().unwrap().tree_size, "8");
+        assert_eq!(entry.canonicalized_body, "Ym9keQ==");
+    }
+
+    #[test]
+    fn test_unsupported_media_type_names_nearest_version() {
+        let err = check_bundle_media_type("application/vnd.dev.sigstore.bundle.v0.4+json", &["0.1", "0.3"])
+            .unwrap_err();
+        match err {
+            VerificationError::UnsupportedMediaType { version, nearest, .. } => {
+                assert_eq!(version, "0.4");
+                assert_eq!(nearest, "0.3");
+            }
+            other => panic!("unexpected error: {:?}", other),
+        }
+
+        let err = check_bundle_media_type("application/vnd.dev.sigstore.bundle+json;version=0.2", &["0.1", "0.3"])
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            VerificationError::UnsupportedMediaType { ref nearest, .. } if nearest == "0.3"
+        ));
+
+        let err = check_bundle_media_type("application/vnd.dev.sigstore.bundle.v1.0+json", &["0.3"])
+            .unwrap_err();
+        assert!(err.to_string().contains("version 1.0"));
+        assert!(err.to_string().contains("nearest supported version is 0.3"));
+    }
+
+    #[test]
+    fn test_media_type_without_version_is_rejected() {
+        for media_type in [
+            "application/vnd.dev.sigstore.bundle+json",
+            "application/vnd.dev.sigstore.bundle.v0.3+json;foo",
+            "application/vnd.dev.sigstore.bundlex.v0.3+json",
+            "invalid",
+        ] {
+            let err = check_bundle_media_type(media_type, SUPPORTED_BUNDLE_VERSIONS).unwrap_err();
+            assert!(matches!(
+                err,
+                VerificationError::UnsupportedMediaType { ref version, ref nearest, .. }
+                    if version == "unknown" && nearest == "0.3"
+            ));
+        }
     }
 }
@@ -1,9 +1,28 @@
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use der::asn1::{AnyRef, BitStringRef};
+use der::{Decode, Sequence};
+use x509_cert::spki::AlgorithmIdentifierRef;
 use x509_parser::prelude::*;
 
 use crate::error::CertificateError;
-use crate::types::certificate::{CertificateChain, FulcioInstance};
+use crate::types::certificate::{CertificateChain, EmbeddedSct, FulcioInstance};
+
+/// `Certificate ::= SEQUENCE { tbsCertificate, signatureAlgorithm, signatureValue BIT STRING }`
+///
+/// Only the TBS certificate is signed; the rest must be strict DER, which
+/// x509-parser does not enforce (it accepts e.g. any tag for the signature value).
+#[derive(Sequence)]
+struct CertificateEnvelope<'a> {
+    tbs_certificate: AnyRef<'a>,
+    signature_algorithm: AlgorithmIdentifierRef<'a>,
+    signature_value: BitStringRef<'a>,
+}
 
 pub fn parse_der_certificate(der: &[u8]) -> Result<X509Certificate<'_>, CertificateError> {
+    CertificateEnvelope::from_der(der).map_err(|e| CertificateError::ParseError(e.to_string()))?;
     let (_, cert) = X509Certificate::from_der(der)
         .map_err(|e| CertificateError::ParseError(e.to_string()))?;
     Ok(cert)
@@ -37,7 +56,7 @@ pub fn extract_issuer_cn(cert: &X509Certificate) -> Result<String, CertificateEr
                     .or_else(|_| {
                         // If as_str() fails, try to convert the raw bytes to UTF-8
                         let bytes = attr.as_slice();
-                        std::str::from_utf8(bytes)
+                        core::str::from_utf8(bytes)
                             .map(|s| s.to_string())
                             .map_err(|e| CertificateError::ParseError(e.to_string()))
                     })
@@ -61,6 +80,39 @@ pub fn extract_subject_public_key_info<'a>(cert: &'a X509Certificate) -> &'a Sub
     cert.public_key()
 }
 
+/// Extract the signed certificate timestamps embedded in a certificate
+///
+/// Fulcio embeds the SCTs of the precertificate it submitted to CT logs in an
+/// extension (OID 1.3.6.1.4.1.11129.2.4.2) of the issued certificate.
+///
+/// # Returns
+/// The SCTs in extension order; empty if the certificate has no SCT extension
+pub fn extract_embedded_scts(cert: &X509Certificate) -> Result<Vec<EmbeddedSct>, CertificateError> {
+    for extension in cert.extensions() {
+        match extension.parsed_extension() {
+            ParsedExtension::SCT(scts) => {
+                return Ok(scts
+                    .iter()
+                    .map(|sct| EmbeddedSct {
+                        version: sct.version.0,
+                        log_id: *sct.id.key_id,
+                        timestamp: sct.timestamp,
+                        extensions: sct.extensions.0.to_vec(),
+                        hash_algorithm: sct.signature.hash_alg_id,
+                        signature_algorithm: sct.signature.sign_alg_id,
+                        signature: sct.signature.data.to_vec(),
+                    })
+                    .collect())
+            }
+            ParsedExtension::ParseError { error } if extension.oid == oid_registry::OID_CT_LIST_SCT => {
+                return Err(CertificateError::InvalidSct(format!("malformed SCT list: {}", error)))
+            }
+            _ => {}
+        }
+    }
+    Ok(Vec::new())
+}
+
 /// Convert a vector of DER-encoded certificates to CertificateChain structure
 ///
 /// Organizes certificates into the expected chain structure with leaf,
@@ -130,4 +182,21 @@ mod tests {
         let result = parse_pem_certificate(pem);
         assert!(result.is_ok());
     }
+
+    #[test]
+    fn test_parse_der_certificate_is_strict_outside_tbs() {
+        let der = ::pem::parse(include_str!("../../testdata/ed25519/leaf.pem")).unwrap().into_contents();
+        assert!(parse_der_certificate(&der).is_ok());
+
+        // Signature value with a context-specific tag instead of BIT STRING
+        let ed25519 = [0x06, 0x03, 0x2b, 0x65, 0x70];
+        let outer = der.windows(ed25519.len()).rposition(|window| window == ed25519).unwrap();
+        let mut retagged = der.clone();
+        retagged[outer + ed25519.len()] = 0x83;
+        assert!(matches!(parse_der_certificate(&retagged), Err(CertificateError::ParseError(_))));
+
+        let mut trailing = der.clone();
+        trailing.push(0);
+        assert!(matches!(parse_der_certificate(&trailing), Err(CertificateError::ParseError(_))));
+    }
 }
@@ -0,0 +1,134 @@
+//! Parser for Rekor checkpoints (signed tree heads)
+//!
+//! An inclusion proof's checkpoint is a signed note
+//! (<https://github.com/transparency-dev/formats/blob/main/log/README.md>):
+//!
+//! ```text
+//! rekor.sigstore.dev - 1193050959916656506     origin
+//! 585383803                                    tree size
+//! r/bTFC+gN/oyGdCqEBRUuOvBsDWm4p86X6DohvgjbD4= root hash (base64)
+//!                                              blank line
+//! — rekor.sigstore.dev wNI9ajBGAiEA...         signature lines
+//! ```
+//!
+//! The signatures are over the text above the blank line, including its final
+//! newline. Each signature line names the signer and carries a base64 4-byte key
+//! hint followed by the signature.
+
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use crate::error::TransparencyError;
+use crate::parser::bundle::decode_base64;
+
+/// Prefix of a note signature line: an em dash and a space
+const SIGNATURE_PREFIX: &str = "\u{2014} ";
+
+/// A parsed checkpoint note
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignedCheckpoint {
+    /// Log origin, e.g. `rekor.sigstore.dev - 1193050959916656506`
+    pub origin: String,
+    pub tree_size: u64,
+    pub root_hash: Vec<u8>,
+    /// Optional extension lines after the root hash
+    pub other_content: Vec<String>,
+    /// The signed text
+    pub body: String,
+    pub signatures: Vec<NoteSignature>,
+}
+
+/// One signature line of a note
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NoteSignature {
+    /// Name of the signer
+    pub name: String,
+    /// First 4 bytes identifying the signing key
+    pub key_hint: [u8; 4],
+    pub signature: Vec<u8>,
+}
+
+/// Parse the `envelope` of an inclusion proof's checkpoint
+///
+/// # Errors
+///
+/// Returns `TransparencyError::InvalidCheckpoint` if the note is malformed
+pub fn parse_checkpoint(envelope: &str) -> Result<SignedCheckpoint, TransparencyError> {
+    let invalid = |reason: &str| TransparencyError::InvalidCheckpoint(reason.to_string());
+
+    let (text, signature_block) = envelope
+        .split_once("\n\n")
+        .ok_or_else(|| invalid("missing blank line before the signatures"))?;
+
+    let mut lines = text.split('\n');
+    let origin = lines
+        .next()
+        .filter(|origin| !origin.is_empty())
+        .ok_or_else(|| invalid("missing origin"))?;
+    let tree_size = lines
+        .next()
+        .and_then(|size| size.parse::<u64>().ok())
+        .ok_or_else(|| invalid("missing or invalid tree size"))?;
+    let root_hash = lines
+        .next()
+        .and_then(|hash| decode_base64(hash).ok())
+        .ok_or_else(|| invalid("missing or invalid root hash"))?;
+    let other_content: Vec<String> = lines.map(str::to_string).collect();
+
+    let mut signatures = Vec::new();
+    for line in signature_block.lines().filter(|line| !line.is_empty()) {
+        let (name, encoded) = line
+            .strip_prefix(SIGNATURE_PREFIX)
+            .and_then(|line| line.split_once(' '))
+            .ok_or_else(|| invalid("malformed signature line"))?;
+        let decoded = decode_base64(encoded).map_err(|_| invalid("signature is not base64"))?;
+        if decoded.len() <= 4 {
+            return Err(invalid("signature too short"));
+        }
+        signatures.push(NoteSignature {
+            name: name.to_string(),
+            key_hint: decoded[..4].try_into().unwrap(), // Safe: length checked above
+            signature: decoded[4..].to_vec(),
+        });
+    }
+    if signatures.is_empty() {
+        return Err(invalid("no signatures"));
+    }
+
+    Ok(SignedCheckpoint {
+        origin: origin.to_string(),
+        tree_size,
+        root_hash,
+        other_content,
+        body: format!("{}\n", text),
+        signatures,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CHECKPOINT: &str = "rekor.sigstore.dev - 1193050959916656506\n585383803\nr/bTFC+gN/oyGdCqEBRUuOvBsDWm4p86X6DohvgjbD4=\n\n\u{2014} rekor.sigstore.dev wNI9ajBGAiEA7OPgR1BW8KLlRclsnr7toj7o8woUDhtinWp1rOQ+4TICIQDBx1aYHc5/V6KavBrfVUg1IluJjYWhNr13MJd0Il59NA==\n";
+
+    #[test]
+    fn test_parse_checkpoint() {
+        let checkpoint = parse_checkpoint(CHECKPOINT).unwrap();
+        assert_eq!(checkpoint.origin, "rekor.sigstore.dev - 1193050959916656506");
+        assert_eq!(checkpoint.tree_size, 585383803);
+        assert_eq!(checkpoint.root_hash.len(), 32);
+        assert!(checkpoint.other_content.is_empty());
+        assert!(checkpoint.body.ends_with("=\n"));
+        assert_eq!(checkpoint.signatures.len(), 1);
+        assert_eq!(checkpoint.signatures[0].name, "rekor.sigstore.dev");
+        assert_eq!(checkpoint.signatures[0].key_hint, [0xc0, 0xd2, 0x3d, 0x6a]);
+    }
+
+    #[test]
+    fn test_parse_checkpoint_rejects_malformed_notes() {
+        let unsigned = "rekor.sigstore.dev - 1\n1\nr/bTFC+gN/oyGdCqEBRUuOvBsDWm4p86X6DohvgjbD4=\n";
+        assert!(parse_checkpoint(unsigned).is_err());
+        assert!(parse_checkpoint(&CHECKPOINT.replace("585383803", "-1")).is_err());
+        assert!(parse_checkpoint(&CHECKPOINT.replace('\u{2014}', "-")).is_err());
+    }
+}
@@ -0,0 +1,158 @@
+//! Signed git objects (`git cat-file commit|tag <object>`)
+//!
+//! `gitsign` signs a commit over the commit object without its `gpgsig`
+//! header, and a tag over the tag object without the signature appended to its
+//! message. The object ID, which names the commit or tag, is the SHA-1 of the
+//! full object including the signature.
+
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use sha1::{Digest, Sha1};
+
+use crate::error::VerificationError;
+use crate::types::git::{GitObject, GitObjectKind};
+
+/// Headers carrying a commit signature, the second in SHA-256 repositories
+const SIGNATURE_HEADERS: [&[u8]; 2] = [b"gpgsig ", b"gpgsig-sha256 "];
+
+/// Split a raw commit or tag object into its signed payload and object ID
+///
+/// # Errors
+///
+/// Returns `VerificationError::InvalidGitObject` if the object carries no
+/// signature or its headers are not terminated
+pub fn parse_git_object(kind: GitObjectKind, raw: &[u8]) -> Result<GitObject, VerificationError> {
+    let header_end = find(raw, b"\n\n")
+        .ok_or_else(|| VerificationError::InvalidGitObject(format!("{} has no message", kind.as_str())))?
+        + 1;
+
+    let (name, signed_payload) = match kind {
+        GitObjectKind::Commit => (String::new(), strip_signature_header(raw, header_end)?),
+        GitObjectKind::Tag => {
+            let name = lines(&raw[..header_end])
+                .find_map(|line| line.strip_prefix(b"tag "))
+                .map(|name| String::from_utf8_lossy(name).into_owned())
+                .ok_or_else(|| VerificationError::InvalidGitObject("tag has no name".to_string()))?;
+            (name, strip_trailing_signature(raw, header_end)?)
+        }
+    };
+
+    let mut hasher = Sha1::new();
+    hasher.update(format!("{} {}\0", kind.as_str(), raw.len()));
+    hasher.update(raw);
+
+    Ok(GitObject {
+        kind,
+        id: hasher.finalize().into(),
+        name,
+        signed_payload,
+    })
+}
+
+/// The commit without its signature header and the header's continuation lines
+fn strip_signature_header(raw: &[u8], header_end: usize) -> Result<Vec<u8>, VerificationError> {
+    let mut payload = Vec::with_capacity(raw.len());
+    let mut in_signature = false;
+    let mut found = false;
+    for line in raw[..header_end].split_inclusive(|&byte| byte == b'\n') {
+        let continuation = line.first() == Some(&b' ');
+        if SIGNATURE_HEADERS.iter().any(|header| line.starts_with(header)) {
+            in_signature = true;
+            found = true;
+        } else if !(in_signature && continuation) {
+            in_signature = false;
+            payload.extend_from_slice(line);
+        }
+    }
+    if !found {
+        return Err(VerificationError::InvalidGitObject("commit is not signed".to_string()));
+    }
+    payload.extend_from_slice(&raw[header_end..]);
+    Ok(payload)
+}
+
+/// The tag up to the signature block that ends its message
+fn strip_trailing_signature(raw: &[u8], header_end: usize) -> Result<Vec<u8>, VerificationError> {
+    let mut offset = header_end;
+    for line in raw[header_end..].split_inclusive(|&byte| byte == b'\n') {
+        if line.starts_with(b"-----BEGIN ") {
+            return Ok(raw[..offset].to_vec());
+        }
+        offset += line.len();
+    }
+    Err(VerificationError::InvalidGitObject("tag is not signed".to_string()))
+}
+
+fn lines(bytes: &[u8]) -> impl Iterator<Item = &[u8]> {
+    bytes.split(|&byte| byte == b'\n')
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).position(|window| window == needle)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COMMIT: &str = "tree 4b825dc642cb6eb9a060e54bf8d69288fbee4904\n\
+author Jane Doe <jane@example.com> 1700000000 +0000\n\
+committer Jane Doe <jane@example.com> 1700000000 +0000\n\
+gpgsig -----BEGIN SIGNED MESSAGE-----\n \n MIIE\n -----END SIGNED MESSAGE-----\n\
+\n\
+Initial commit\n";
+
+    const TAG: &str = "object 0123456789abcdef0123456789abcdef01234567\n\
+type commit\n\
+tag v1.0.0\n\
+tagger Jane Doe <jane@example.com> 1700000000 +0000\n\
+\n\
+Release v1.0.0\n\
+-----BEGIN SIGNED MESSAGE-----\n\
+MIIE\n\
+-----END SIGNED MESSAGE-----\n";
+
+    #[test]
+    fn test_commit_signed_payload_and_id() {
+        let commit = parse_git_object(GitObjectKind::Commit, COMMIT.as_bytes()).unwrap();
+        assert_eq!(
+            commit.signed_payload,
+            b"tree 4b825dc642cb6eb9a060e54bf8d69288fbee4904\n\
+author Jane Doe <jane@example.com> 1700000000 +0000\n\
+committer Jane Doe <jane@example.com> 1700000000 +0000\n\
+\n\
+Initial commit\n"
+        );
+        assert!(commit.name.is_empty());
+
+        // As `git hash-object -t commit`
+        let mut hasher = Sha1::new();
+        hasher.update(format!("commit {}\0{}", COMMIT.len(), COMMIT));
+        assert_eq!(commit.id, <[u8; 20]>::from(hasher.finalize()));
+    }
+
+    #[test]
+    fn test_tag_signed_payload_and_name() {
+        let tag = parse_git_object(GitObjectKind::Tag, TAG.as_bytes()).unwrap();
+        assert_eq!(tag.name, "v1.0.0");
+        assert!(tag.signed_payload.ends_with(b"\n\nRelease v1.0.0\n"));
+        assert_ne!(tag.id, parse_git_object(GitObjectKind::Commit, COMMIT.as_bytes()).unwrap().id);
+    }
+
+    #[test]
+    fn test_unsigned_objects_rejected() {
+        let unsigned_commit = "tree 4b825dc642cb6eb9a060e54bf8d69288fbee4904\n\nInitial commit\n";
+        let unsigned_tag = TAG.split("-----BEGIN").next().unwrap();
+        for (kind, raw) in [
+            (GitObjectKind::Commit, unsigned_commit),
+            (GitObjectKind::Tag, unsigned_tag),
+            (GitObjectKind::Commit, "tree 4b825dc642cb6eb9a060e54bf8d69288fbee4904\n"),
+        ] {
+            assert!(matches!(
+                parse_git_object(kind, raw.as_bytes()),
+                Err(VerificationError::InvalidGitObject(_))
+            ));
+        }
+    }
+}
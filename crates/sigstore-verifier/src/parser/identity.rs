@@ -1,8 +1,9 @@
+use alloc::string::{String, ToString};
 use x509_parser::prelude::*;
 use x509_parser::oid_registry::Oid;
 
 use crate::error::CertificateError;
-use crate::types::certificate::OidcIdentity;
+use crate::types::certificate::{IdentityKind, OidcIdentity};
 
 // OIDC token claim OIDs (1.3.6.1.4.1.57264.1.x)
 const OID_ISSUER: [u64; 9] = [1, 3, 6, 1, 4, 1, 57264, 1, 8]; // Issuer (v2)
@@ -22,6 +23,7 @@ pub fn extract_oidc_identity(cert: &X509Certificate) -> Result<OidcIdentity, Cer
         workflow_ref: None,
         repository: None,
         event_name: None,
+        subject_kind: IdentityKind::Unknown,
     };
 
     // Extract subject from SAN (Subject Alternative Name)
@@ -30,10 +32,16 @@ pub fn extract_oidc_identity(cert: &X509Certificate) -> Result<OidcIdentity, Cer
         for name in &san_ext.value.general_names {
             if let x509_parser::extensions::GeneralName::RFC822Name(email) = name {
                 identity.subject = Some(email.to_string());
+                identity.subject_kind = IdentityKind::Email;
             } else if let x509_parser::extensions::GeneralName::URI(uri) = name {
                 // For some OIDC providers, subject is in URI
                 if identity.subject.is_none() {
                     identity.subject = Some(uri.to_string());
+                    identity.subject_kind = if parse_spiffe_id(uri).is_some() {
+                        IdentityKind::Spiffe
+                    } else {
+                        IdentityKind::Uri
+                    };
                 }
             }
         }
@@ -58,6 +66,49 @@ pub fn extract_oidc_identity(cert: &X509Certificate) -> Result<OidcIdentity, Cer
     Ok(identity)
 }
 
+/// Split a SPIFFE ID into trust domain and path
+///
+/// Follows the SPIFFE ID format: `spiffe://` followed by a lowercase trust
+/// domain (`a-z`, `0-9`, `.`, `-`, `_`) and an optional path of non-empty
+/// segments (`A-Z`, `a-z`, `0-9`, `.`, `-`, `_`, but not `.` or `..`).
+/// Query strings, fragments, ports and user info are not allowed.
+///
+/// # Returns
+///
+/// `(trust_domain, path)` with `path` empty or starting with `/`, or `None` if
+/// `uri` is not a valid SPIFFE ID.
+pub fn parse_spiffe_id(uri: &str) -> Option<(&str, &str)> {
+    let rest = uri.strip_prefix("spiffe://")?;
+    let (trust_domain, path) = match rest.find('/') {
+        Some(index) => rest.split_at(index),
+        None => (rest, ""),
+    };
+
+    let valid_trust_domain = !trust_domain.is_empty()
+        && trust_domain
+            .bytes()
+            .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || matches!(b, b'.' | b'-' | b'_'));
+    if !valid_trust_domain {
+        return None;
+    }
+
+    if !path.is_empty() {
+        for segment in path[1..].split('/') {
+            let valid_segment = !segment.is_empty()
+                && segment != "."
+                && segment != ".."
+                && segment
+                    .bytes()
+                    .all(|b| b.is_ascii_alphanumeric() || matches!(b, b'.' | b'-' | b'_'));
+            if !valid_segment {
+                return None;
+            }
+        }
+    }
+
+    Some((trust_domain, path))
+}
+
 fn oid_equals(oid: &Oid, expected: &[u64]) -> bool {
     if let Some(mut iter) = oid.iter() {
         for &expected_val in expected {
@@ -89,16 +140,40 @@ fn extract_string_from_extension(ext: &X509Extension) -> Result<Option<String>,
 
         // UTF8String (0x0C) or IA5String (0x16) or PrintableString (0x13)
         if (tag == 0x0C || tag == 0x16 || tag == 0x13) && len + 2 <= bytes.len() {
-            if let Ok(s) = std::str::from_utf8(&bytes[2..2 + len]) {
+            if let Ok(s) = core::str::from_utf8(&bytes[2..2 + len]) {
                 return Ok(Some(s.to_string()));
             }
         }
     }
 
     // Fallback: try to parse as direct UTF-8 (in case it's not DER-encoded)
-    if let Ok(s) = std::str::from_utf8(bytes) {
+    if let Ok(s) = core::str::from_utf8(bytes) {
         return Ok(Some(s.to_string()));
     }
 
     Ok(None)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_spiffe_id() {
+        assert_eq!(
+            parse_spiffe_id("spiffe://prod.example.org/ns/release/sa/builder"),
+            Some(("prod.example.org", "/ns/release/sa/builder"))
+        );
+        assert_eq!(parse_spiffe_id("spiffe://example.org"), Some(("example.org", "")));
+
+        // Not SPIFFE, or malformed
+        assert_eq!(parse_spiffe_id("https://github.com/owner/repo/.github/workflows/ci.yml@refs/heads/main"), None);
+        assert_eq!(parse_spiffe_id("spiffe://"), None);
+        assert_eq!(parse_spiffe_id("spiffe://Example.org/workload"), None);
+        assert_eq!(parse_spiffe_id("spiffe://example.org:8443/workload"), None);
+        assert_eq!(parse_spiffe_id("spiffe://example.org/workload/"), None);
+        assert_eq!(parse_spiffe_id("spiffe://example.org/ns//workload"), None);
+        assert_eq!(parse_spiffe_id("spiffe://example.org/ns/../workload"), None);
+        assert_eq!(parse_spiffe_id("spiffe://example.org/workload?x=1"), None);
+    }
+}
@@ -0,0 +1,217 @@
+//! Legacy cosign bundles (`cosign attest-blob --bundle`)
+//!
+//! Before the Sigstore bundle format, cosign wrote a flat JSON file with the
+//! base64 signature, the base64 PEM signing certificate and the Rekor entry
+//! with its SignedEntryTimestamp:
+//!
+//! ```json
+//! {
+//!   "base64Signature": "<base64 DSSE envelope JSON>",
+//!   "cert": "<base64 PEM certificate>",
+//!   "rekorBundle": {
+//!     "SignedEntryTimestamp": "<base64>",
+//!     "Payload": { "body": "<base64>", "integratedTime": 0, "logIndex": 0, "logID": "<hex>" }
+//!   }
+//! }
+//! ```
+//!
+//! These files are mapped onto [`SigstoreBundle`] so they verify without
+//! re-signing. For attestations the "signature" is the whole DSSE envelope;
+//! legacy bundles of plain blob signatures are rejected. Legacy Rekor entries
+//! carry no inclusion proof, only the inclusion promise.
+
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use base64::prelude::*;
+use serde::Deserialize;
+
+use crate::error::VerificationError;
+use crate::types::bundle::{
+    BundleContent, Certificate, DsseEnvelope, InclusionPromise, KindVersion, LogId, SigstoreBundle,
+    TransparencyLogEntry, VerificationMaterial,
+};
+
+/// Media type given to converted bundles, whose layout matches v0.3
+pub const LEGACY_BUNDLE_MEDIA_TYPE: &str = "application/vnd.dev.sigstore.bundle.v0.3+json";
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LegacyCosignBundle {
+    pub base64_signature: String,
+    #[serde(default)]
+    pub cert: String, // Base64-encoded PEM (or PEM); empty for key-based signatures
+    pub rekor_bundle: LegacyRekorBundle,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct LegacyRekorBundle {
+    #[serde(rename = "SignedEntryTimestamp")]
+    pub signed_entry_timestamp: String, // Base64-encoded
+    #[serde(rename = "Payload")]
+    pub payload: LegacyRekorPayload,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LegacyRekorPayload {
+    pub body: String, // Base64-encoded canonicalized entry
+    pub integrated_time: i64,
+    pub log_index: i64,
+    #[serde(rename = "logID")]
+    pub log_id: String, // Hex-encoded
+}
+
+/// Parse a legacy cosign bundle and map it onto a [`SigstoreBundle`]
+///
+/// # Errors
+///
+/// Returns an error if the JSON is not a legacy cosign bundle, it has no signing
+/// certificate, or it signs a blob rather than a DSSE envelope
+pub fn parse_legacy_bundle_from_bytes(bytes: &[u8]) -> Result<SigstoreBundle, VerificationError> {
+    let legacy: LegacyCosignBundle = serde_json::from_slice(bytes)?;
+    legacy.into_bundle()
+}
+
+impl LegacyCosignBundle {
+    /// Map onto the current bundle layout
+    pub fn into_bundle(self) -> Result<SigstoreBundle, VerificationError> {
+        let invalid = |msg: &str| VerificationError::InvalidBundleFormat(format!("Legacy cosign bundle: {}", msg));
+
+        let envelope_json = BASE64_STANDARD.decode(&self.base64_signature)?;
+        let dsse_envelope: DsseEnvelope = serde_json::from_slice(&envelope_json)
+            .map_err(|_| invalid("signature is not a DSSE envelope (blob signatures are not attestations)"))?;
+
+        if self.cert.is_empty() {
+            return Err(invalid("no signing certificate (key-based signatures are not supported)"));
+        }
+        let cert_pem = if self.cert.starts_with("-----BEGIN") {
+            self.cert.into_bytes()
+        } else {
+            BASE64_STANDARD.decode(&self.cert)?
+        };
+        let cert = pem::parse(&cert_pem).map_err(|e| invalid(&format!("invalid certificate PEM: {}", e)))?;
+
+        let payload = self.rekor_bundle.payload;
+        let log_id = hex::decode(&payload.log_id).map_err(|_| invalid("logID is not hex"))?;
+
+        // Kind and version of the entry are recorded in the entry body itself
+        let body: serde_json::Value = serde_json::from_slice(&BASE64_STANDARD.decode(&payload.body)?)
+            .map_err(|_| invalid("Rekor entry body is not JSON"))?;
+        let kind_version = match (body["kind"].as_str(), body["apiVersion"].as_str()) {
+            (Some(kind), Some(version)) => Some(KindVersion {
+                kind: kind.to_string(),
+                version: version.to_string(),
+            }),
+            _ => None,
+        };
+
+        Ok(SigstoreBundle {
+            media_type: LEGACY_BUNDLE_MEDIA_TYPE.to_string(),
+            verification_material: VerificationMaterial {
+                timestamp_verification_data: None,
+                certificate: Certificate {
+                    raw_bytes: BASE64_STANDARD.encode(cert.contents()),
+                },
+                tlog_entries: Some(vec![TransparencyLogEntry {
+                    log_index: Some(payload.log_index.to_string()),
+                    log_id: Some(LogId {
+                        key_id: BASE64_STANDARD.encode(log_id),
+                    }),
+                    kind_version,
+                    integrated_time: payload.integrated_time.to_string(),
+                    inclusion_promise: Some(InclusionPromise {
+                        signed_entry_timestamp: self.rekor_bundle.signed_entry_timestamp,
+                    }),
+                    inclusion_proof: None,
+                    canonicalized_body: payload.body,
+                }]),
+            },
+            content: BundleContent::DsseEnvelope(dsse_envelope),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::bundle::{parse_bundle_from_bytes, parse_bundle_from_str};
+    use serde_json::json;
+
+    fn sample_bundle() -> SigstoreBundle {
+        let mut path = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        path.push("../../samples/actions-attest-build-provenance-attestation-13532655.sigstore.json");
+        parse_bundle_from_bytes(&std::fs::read(path).unwrap()).unwrap()
+    }
+
+    /// The legacy file cosign would have written for the same signature
+    fn legacy_json(bundle: &SigstoreBundle) -> serde_json::Value {
+        let entry = &bundle.verification_material.tlog_entries.as_ref().unwrap()[0];
+        let envelope = serde_json::to_vec(bundle.dsse_envelope().unwrap()).unwrap();
+        let cert_der = BASE64_STANDARD
+            .decode(&bundle.verification_material.certificate.raw_bytes)
+            .unwrap();
+        let cert_pem = pem::encode(&pem::Pem::new("CERTIFICATE", cert_der));
+        let log_id = BASE64_STANDARD
+            .decode(&entry.log_id.as_ref().unwrap().key_id)
+            .unwrap();
+
+        json!({
+            "base64Signature": BASE64_STANDARD.encode(envelope),
+            "cert": BASE64_STANDARD.encode(cert_pem),
+            "rekorBundle": {
+                "SignedEntryTimestamp": entry.inclusion_promise.as_ref().unwrap().signed_entry_timestamp,
+                "Payload": {
+                    "body": entry.canonicalized_body,
+                    "integratedTime": entry.integrated_time.parse::<i64>().unwrap(),
+                    "logIndex": entry.log_index.as_ref().unwrap().parse::<i64>().unwrap(),
+                    "logID": hex::encode(log_id),
+                }
+            }
+        })
+    }
+
+    #[test]
+    fn test_legacy_bundle_maps_onto_bundle() {
+        let bundle = sample_bundle();
+        let converted = parse_bundle_from_str(&legacy_json(&bundle).to_string()).unwrap();
+
+        assert_eq!(converted.media_type, LEGACY_BUNDLE_MEDIA_TYPE);
+        let (converted_envelope, envelope) = (converted.dsse_envelope().unwrap(), bundle.dsse_envelope().unwrap());
+        assert_eq!(converted_envelope.payload, envelope.payload);
+        assert_eq!(converted_envelope.signatures[0].sig, envelope.signatures[0].sig);
+        assert_eq!(
+            converted.verification_material.certificate.raw_bytes,
+            bundle.verification_material.certificate.raw_bytes
+        );
+
+        let expected = &bundle.verification_material.tlog_entries.as_ref().unwrap()[0];
+        let entry = &converted.verification_material.tlog_entries.as_ref().unwrap()[0];
+        assert_eq!(entry.log_index, expected.log_index);
+        assert_eq!(entry.log_id.as_ref().unwrap().key_id, expected.log_id.as_ref().unwrap().key_id);
+        assert_eq!(entry.integrated_time, expected.integrated_time);
+        assert_eq!(entry.canonicalized_body, expected.canonicalized_body);
+        let kind_version = entry.kind_version.as_ref().unwrap();
+        assert_eq!(kind_version.kind, "dsse");
+        assert_eq!(kind_version.version, "0.0.1");
+        assert!(entry.inclusion_proof.is_none());
+    }
+
+    #[test]
+    fn test_legacy_blob_signature_is_rejected() {
+        let mut legacy = legacy_json(&sample_bundle());
+        legacy["base64Signature"] = json!(BASE64_STANDARD.encode(b"\x30\x45\x02\x21"));
+
+        let err = parse_legacy_bundle_from_bytes(legacy.to_string().as_bytes()).unwrap_err();
+        assert!(err.to_string().contains("not a DSSE envelope"));
+    }
+
+    #[test]
+    fn test_legacy_bundle_without_certificate_is_rejected() {
+        let mut legacy = legacy_json(&sample_bundle());
+        legacy.as_object_mut().unwrap().remove("cert");
+
+        let err = parse_legacy_bundle_from_bytes(legacy.to_string().as_bytes()).unwrap_err();
+        assert!(err.to_string().contains("no signing certificate"));
+    }
+}
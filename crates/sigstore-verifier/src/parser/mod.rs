@@ -1,5 +1,10 @@
 pub mod bundle;
 pub mod certificate;
+pub mod checkpoint;
+pub mod git;
 pub mod identity;
+pub mod legacy;
+pub mod protobuf;
+pub mod rekor_body;
 pub mod rfc3161;
 pub mod timestamp;
@@ -0,0 +1,644 @@
+//! Binary protobuf bundles (`application/vnd.dev.sigstore.bundle+protobuf`)
+//!
+//! Sigstore bundles are defined as protobuf messages; the JSON files are their
+//! canonical JSON encoding. This module decodes the binary encoding directly
+//! onto [`SigstoreBundle`], with `bytes` fields base64-encoded and `int64`
+//! fields as decimal strings, exactly as protobuf JSON renders them. Only the
+//! fields the verifier reads are decoded; unknown fields are skipped as
+//! protobuf requires, so newer writers remain readable.
+//!
+//! The wire format is decoded by hand rather than with generated code, which
+//! keeps the guest free of a protobuf runtime. Field numbers follow
+//! `sigstore_bundle.proto`, `sigstore_common.proto`, `sigstore_rekor.proto`
+//! and `envelope.proto` of protobuf-specs.
+
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use base64::prelude::*;
+
+use crate::error::VerificationError;
+use crate::types::bundle::{
+    BundleContent, Certificate, Checkpoint, DsseEnvelope, InclusionPromise, InclusionProof,
+    KindVersion, LogId, MessageDigest, MessageSignature, Rfc3161Timestamp, Signature,
+    SigstoreBundle, TimestampVerificationData, TransparencyLogEntry, VerificationMaterial,
+};
+
+/// Value of one field on the wire
+#[derive(Debug, Clone, Copy)]
+enum Value<'a> {
+    Varint(u64),
+    // No field the verifier reads is fixed-width; these are only skipped
+    Fixed64,
+    Bytes(&'a [u8]),
+    Fixed32,
+}
+
+/// Sequential reader over the fields of one message
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+fn invalid(msg: &str) -> VerificationError {
+    VerificationError::InvalidBundleFormat(format!("Protobuf bundle: {}", msg))
+}
+
+impl<'a> Reader<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes }
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8], VerificationError> {
+        if len > self.bytes.len() {
+            return Err(invalid("truncated message"));
+        }
+        let (head, rest) = self.bytes.split_at(len);
+        self.bytes = rest;
+        Ok(head)
+    }
+
+    fn varint(&mut self) -> Result<u64, VerificationError> {
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let byte = self.take(1)?[0];
+            value |= u64::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err(invalid("varint longer than 10 bytes"))
+    }
+
+    /// Next field number and value, or `None` at the end of the message
+    fn next_field(&mut self) -> Result<Option<(u32, Value<'a>)>, VerificationError> {
+        if self.bytes.is_empty() {
+            return Ok(None);
+        }
+        let key = self.varint()?;
+        let number = u32::try_from(key >> 3)
+            .ok()
+            .filter(|&number| number != 0)
+            .ok_or_else(|| invalid("invalid field number"))?;
+        let value = match key & 0x7 {
+            0 => Value::Varint(self.varint()?),
+            1 => {
+                self.take(8)?;
+                Value::Fixed64
+            }
+            2 => {
+                let len = usize::try_from(self.varint()?).map_err(|_| invalid("length out of range"))?;
+                Value::Bytes(self.take(len)?)
+            }
+            5 => {
+                self.take(4)?;
+                Value::Fixed32
+            }
+            // Groups (3, 4) are not used by any Sigstore message
+            wire_type => return Err(invalid(&format!("unsupported wire type {}", wire_type))),
+        };
+        Ok(Some((number, value)))
+    }
+}
+
+fn length_delimited<'a>(value: Value<'a>, field: &str) -> Result<&'a [u8], VerificationError> {
+    match value {
+        Value::Bytes(bytes) => Ok(bytes),
+        _ => Err(invalid(&format!("{} is not length-delimited", field))),
+    }
+}
+
+fn base64_field(value: Value<'_>, field: &str) -> Result<String, VerificationError> {
+    Ok(BASE64_STANDARD.encode(length_delimited(value, field)?))
+}
+
+fn string_field(value: Value<'_>, field: &str) -> Result<String, VerificationError> {
+    String::from_utf8(length_delimited(value, field)?.to_vec())
+        .map_err(|_| invalid(&format!("{} is not UTF-8", field)))
+}
+
+/// `int64` field as protobuf JSON renders it
+fn int64_field(value: Value<'_>, field: &str) -> Result<String, VerificationError> {
+    match value {
+        // Negative values are sign-extended to 64 bits on the wire
+        Value::Varint(n) => Ok((n as i64).to_string()),
+        _ => Err(invalid(&format!("{} is not a varint", field))),
+    }
+}
+
+/// Fields of a decoded `Bundle`
+///
+/// The certificate chain of v0.1 and v0.2 bundles is kept separately, so the
+/// caller can map it onto the v0.3 layout as for JSON bundles.
+pub struct ProtobufBundle {
+    pub media_type: String,
+    pub timestamp_verification_data: Option<TimestampVerificationData>,
+    pub certificate: Option<Certificate>,
+    pub x509_certificate_chain: Vec<Certificate>,
+    pub tlog_entries: Option<Vec<TransparencyLogEntry>>,
+    pub content: Option<BundleContent>,
+}
+
+impl ProtobufBundle {
+    /// Map onto the v0.3 layout
+    ///
+    /// Only the leaf of a v0.1/v0.2 chain is kept: intermediates and root come
+    /// from the trust bundle.
+    ///
+    /// # Errors
+    ///
+    /// Returns `VerificationError::InvalidBundleFormat` if the bundle carries no
+    /// signing certificate or no content
+    pub fn into_bundle(self) -> Result<SigstoreBundle, VerificationError> {
+        let certificate = match self.certificate {
+            Some(certificate) => certificate,
+            None => self
+                .x509_certificate_chain
+                .into_iter()
+                .next()
+                .ok_or_else(|| invalid("no signing certificate (key-based signatures are not supported)"))?,
+        };
+        let content = self
+            .content
+            .ok_or_else(|| invalid("neither dsseEnvelope nor messageSignature is set"))?;
+
+        Ok(SigstoreBundle {
+            media_type: self.media_type,
+            verification_material: VerificationMaterial {
+                timestamp_verification_data: self.timestamp_verification_data,
+                certificate,
+                tlog_entries: self.tlog_entries,
+            },
+            content,
+        })
+    }
+}
+
+/// Decode a binary `Bundle` message
+///
+/// # Errors
+///
+/// Returns `VerificationError::InvalidBundleFormat` if the bytes are not a
+/// well-formed protobuf message or a field has the wrong wire type
+pub fn decode_bundle(bytes: &[u8]) -> Result<ProtobufBundle, VerificationError> {
+    let mut bundle = ProtobufBundle {
+        media_type: String::new(),
+        timestamp_verification_data: None,
+        certificate: None,
+        x509_certificate_chain: Vec::new(),
+        tlog_entries: None,
+        content: None,
+    };
+
+    let mut reader = Reader::new(bytes);
+    while let Some((number, value)) = reader.next_field()? {
+        match number {
+            1 => bundle.media_type = string_field(value, "mediaType")?,
+            2 => decode_verification_material(length_delimited(value, "verificationMaterial")?, &mut bundle)?,
+            3 => {
+                bundle.content = Some(BundleContent::MessageSignature(decode_message_signature(
+                    length_delimited(value, "messageSignature")?,
+                )?))
+            }
+            4 => {
+                bundle.content = Some(BundleContent::DsseEnvelope(decode_envelope(length_delimited(
+                    value,
+                    "dsseEnvelope",
+                )?)?))
+            }
+            _ => {}
+        }
+    }
+    Ok(bundle)
+}
+
+fn decode_verification_material(
+    bytes: &[u8],
+    bundle: &mut ProtobufBundle,
+) -> Result<(), VerificationError> {
+    let mut reader = Reader::new(bytes);
+    while let Some((number, value)) = reader.next_field()? {
+        match number {
+            1 => return Err(invalid("no signing certificate (key-based signatures are not supported)")),
+            2 => {
+                bundle.x509_certificate_chain =
+                    decode_repeated(length_delimited(value, "x509CertificateChain")?, 1, decode_certificate)?
+            }
+            3 => bundle
+                .tlog_entries
+                .get_or_insert_with(Vec::new)
+                .push(decode_tlog_entry(length_delimited(value, "tlogEntries")?)?),
+            4 => {
+                let timestamps = decode_repeated(
+                    length_delimited(value, "timestampVerificationData")?,
+                    1,
+                    decode_rfc3161_timestamp,
+                )?;
+                // An empty repeated field is omitted from protobuf JSON
+                bundle.timestamp_verification_data = Some(TimestampVerificationData {
+                    rfc3161_timestamps: (!timestamps.is_empty()).then_some(timestamps),
+                })
+            }
+            5 => bundle.certificate = Some(decode_certificate(length_delimited(value, "certificate")?)?),
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Decode every occurrence of the message field `field` in `bytes`
+fn decode_repeated<T>(
+    bytes: &[u8],
+    field: u32,
+    decode: fn(&[u8]) -> Result<T, VerificationError>,
+) -> Result<Vec<T>, VerificationError> {
+    let mut items = Vec::new();
+    let mut reader = Reader::new(bytes);
+    while let Some((number, value)) = reader.next_field()? {
+        if number == field {
+            items.push(decode(length_delimited(value, "repeated field")?)?);
+        }
+    }
+    Ok(items)
+}
+
+fn decode_certificate(bytes: &[u8]) -> Result<Certificate, VerificationError> {
+    let mut raw_bytes = String::new();
+    let mut reader = Reader::new(bytes);
+    while let Some((number, value)) = reader.next_field()? {
+        if number == 1 {
+            raw_bytes = base64_field(value, "rawBytes")?;
+        }
+    }
+    Ok(Certificate { raw_bytes })
+}
+
+fn decode_rfc3161_timestamp(bytes: &[u8]) -> Result<Rfc3161Timestamp, VerificationError> {
+    let mut signed_timestamp = String::new();
+    let mut reader = Reader::new(bytes);
+    while let Some((number, value)) = reader.next_field()? {
+        if number == 1 {
+            signed_timestamp = base64_field(value, "signedTimestamp")?;
+        }
+    }
+    Ok(Rfc3161Timestamp { signed_timestamp })
+}
+
+fn decode_tlog_entry(bytes: &[u8]) -> Result<TransparencyLogEntry, VerificationError> {
+    let mut entry = TransparencyLogEntry {
+        log_index: Some("0".to_string()),
+        log_id: None,
+        kind_version: None,
+        integrated_time: "0".to_string(),
+        inclusion_promise: None,
+        inclusion_proof: None,
+        canonicalized_body: String::new(),
+    };
+
+    let mut reader = Reader::new(bytes);
+    while let Some((number, value)) = reader.next_field()? {
+        match number {
+            1 => entry.log_index = Some(int64_field(value, "logIndex")?),
+            2 => {
+                let mut key_id = String::new();
+                let mut fields = Reader::new(length_delimited(value, "logId")?);
+                while let Some((number, value)) = fields.next_field()? {
+                    if number == 1 {
+                        key_id = base64_field(value, "keyId")?;
+                    }
+                }
+                entry.log_id = Some(LogId { key_id });
+            }
+            3 => {
+                let mut kind_version = KindVersion {
+                    kind: String::new(),
+                    version: String::new(),
+                };
+                let mut fields = Reader::new(length_delimited(value, "kindVersion")?);
+                while let Some((number, value)) = fields.next_field()? {
+                    match number {
+                        1 => kind_version.kind = string_field(value, "kind")?,
+                        2 => kind_version.version = string_field(value, "version")?,
+                        _ => {}
+                    }
+                }
+                entry.kind_version = Some(kind_version);
+            }
+            4 => entry.integrated_time = int64_field(value, "integratedTime")?,
+            5 => {
+                let mut signed_entry_timestamp = String::new();
+                let mut fields = Reader::new(length_delimited(value, "inclusionPromise")?);
+                while let Some((number, value)) = fields.next_field()? {
+                    if number == 1 {
+                        signed_entry_timestamp = base64_field(value, "signedEntryTimestamp")?;
+                    }
+                }
+                entry.inclusion_promise = Some(InclusionPromise { signed_entry_timestamp });
+            }
+            6 => entry.inclusion_proof = Some(decode_inclusion_proof(length_delimited(value, "inclusionProof")?)?),
+            7 => entry.canonicalized_body = base64_field(value, "canonicalizedBody")?,
+            _ => {}
+        }
+    }
+    Ok(entry)
+}
+
+fn decode_inclusion_proof(bytes: &[u8]) -> Result<InclusionProof, VerificationError> {
+    let mut proof = InclusionProof {
+        log_index: "0".to_string(),
+        root_hash: String::new(),
+        tree_size: "0".to_string(),
+        hashes: Vec::new(),
+        checkpoint: None,
+    };
+
+    let mut reader = Reader::new(bytes);
+    while let Some((number, value)) = reader.next_field()? {
+        match number {
+            1 => proof.log_index = int64_field(value, "logIndex")?,
+            2 => proof.root_hash = base64_field(value, "rootHash")?,
+            3 => proof.tree_size = int64_field(value, "treeSize")?,
+            4 => proof.hashes.push(base64_field(value, "hashes")?),
+            5 => {
+                let mut envelope = String::new();
+                let mut fields = Reader::new(length_delimited(value, "checkpoint")?);
+                while let Some((number, value)) = fields.next_field()? {
+                    if number == 1 {
+                        envelope = string_field(value, "envelope")?;
+                    }
+                }
+                proof.checkpoint = Some(Checkpoint { envelope });
+            }
+            _ => {}
+        }
+    }
+    Ok(proof)
+}
+
+fn decode_envelope(bytes: &[u8]) -> Result<DsseEnvelope, VerificationError> {
+    let mut envelope = DsseEnvelope {
+        payload: String::new(),
+        payload_type: String::new(),
+        signatures: Vec::new(),
+    };
+
+    let mut reader = Reader::new(bytes);
+    while let Some((number, value)) = reader.next_field()? {
+        match number {
+            1 => envelope.payload = base64_field(value, "payload")?,
+            2 => envelope.payload_type = string_field(value, "payloadType")?,
+            3 => {
+                let mut signature = Signature {
+                    sig: String::new(),
+                    keyid: String::new(),
+                };
+                let mut fields = Reader::new(length_delimited(value, "signatures")?);
+                while let Some((number, value)) = fields.next_field()? {
+                    match number {
+                        1 => signature.sig = base64_field(value, "sig")?,
+                        2 => signature.keyid = string_field(value, "keyid")?,
+                        _ => {}
+                    }
+                }
+                envelope.signatures.push(signature);
+            }
+            _ => {}
+        }
+    }
+    Ok(envelope)
+}
+
+/// Name of a `HashAlgorithm` enum value, as protobuf JSON renders it
+fn hash_algorithm_name(value: u64) -> String {
+    match value {
+        0 => "HASH_ALGORITHM_UNSPECIFIED".to_string(),
+        1 => "SHA2_256".to_string(),
+        2 => "SHA2_384".to_string(),
+        3 => "SHA2_512".to_string(),
+        4 => "SHA3_256".to_string(),
+        5 => "SHA3_384".to_string(),
+        // Unknown enum values are rendered as numbers
+        other => other.to_string(),
+    }
+}
+
+fn decode_message_signature(bytes: &[u8]) -> Result<MessageSignature, VerificationError> {
+    let mut message_signature = MessageSignature {
+        message_digest: MessageDigest {
+            algorithm: hash_algorithm_name(0),
+            digest: String::new(),
+        },
+        signature: String::new(),
+    };
+
+    let mut reader = Reader::new(bytes);
+    while let Some((number, value)) = reader.next_field()? {
+        match number {
+            1 => {
+                let mut fields = Reader::new(length_delimited(value, "messageDigest")?);
+                while let Some((number, value)) = fields.next_field()? {
+                    match (number, value) {
+                        (1, Value::Varint(algorithm)) => {
+                            message_signature.message_digest.algorithm = hash_algorithm_name(algorithm)
+                        }
+                        (1, _) => return Err(invalid("algorithm is not a varint")),
+                        (2, value) => message_signature.message_digest.digest = base64_field(value, "digest")?,
+                        _ => {}
+                    }
+                }
+            }
+            2 => message_signature.signature = base64_field(value, "signature")?,
+            _ => {}
+        }
+    }
+    Ok(message_signature)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::bundle::{parse_bundle_from_path, parse_bundle_from_protobuf};
+
+    fn put_key(out: &mut Vec<u8>, number: u32, wire_type: u8) {
+        put_varint(out, (u64::from(number) << 3) | u64::from(wire_type));
+    }
+
+    fn put_varint(out: &mut Vec<u8>, mut value: u64) {
+        while value >= 0x80 {
+            out.push((value as u8) | 0x80);
+            value >>= 7;
+        }
+        out.push(value as u8);
+    }
+
+    fn put_bytes(out: &mut Vec<u8>, number: u32, bytes: &[u8]) {
+        put_key(out, number, 2);
+        put_varint(out, bytes.len() as u64);
+        out.extend_from_slice(bytes);
+    }
+
+    fn put_base64(out: &mut Vec<u8>, number: u32, value: &str) {
+        put_bytes(out, number, &BASE64_STANDARD.decode(value).unwrap());
+    }
+
+    fn put_int64(out: &mut Vec<u8>, number: u32, value: &str) {
+        put_key(out, number, 0);
+        put_varint(out, value.parse::<i64>().unwrap() as u64);
+    }
+
+    /// Binary encoding of a bundle, for tests
+    fn encode_bundle(bundle: &SigstoreBundle) -> Vec<u8> {
+        let material = &bundle.verification_material;
+        let mut verification_material = Vec::new();
+        for entry in material.tlog_entries.iter().flatten() {
+            let mut tlog = Vec::new();
+            put_int64(&mut tlog, 1, entry.log_index.as_deref().unwrap_or("0"));
+            if let Some(log_id) = &entry.log_id {
+                let mut id = Vec::new();
+                put_base64(&mut id, 1, &log_id.key_id);
+                put_bytes(&mut tlog, 2, &id);
+            }
+            if let Some(kind_version) = &entry.kind_version {
+                let mut kv = Vec::new();
+                put_bytes(&mut kv, 1, kind_version.kind.as_bytes());
+                put_bytes(&mut kv, 2, kind_version.version.as_bytes());
+                put_bytes(&mut tlog, 3, &kv);
+            }
+            put_int64(&mut tlog, 4, &entry.integrated_time);
+            if let Some(promise) = &entry.inclusion_promise {
+                let mut p = Vec::new();
+                put_base64(&mut p, 1, &promise.signed_entry_timestamp);
+                put_bytes(&mut tlog, 5, &p);
+            }
+            if let Some(proof) = &entry.inclusion_proof {
+                let mut p = Vec::new();
+                put_int64(&mut p, 1, &proof.log_index);
+                put_base64(&mut p, 2, &proof.root_hash);
+                put_int64(&mut p, 3, &proof.tree_size);
+                for hash in &proof.hashes {
+                    put_base64(&mut p, 4, hash);
+                }
+                if let Some(checkpoint) = &proof.checkpoint {
+                    let mut c = Vec::new();
+                    put_bytes(&mut c, 1, checkpoint.envelope.as_bytes());
+                    put_bytes(&mut p, 5, &c);
+                }
+                put_bytes(&mut tlog, 6, &p);
+            }
+            put_base64(&mut tlog, 7, &entry.canonicalized_body);
+            put_bytes(&mut verification_material, 3, &tlog);
+        }
+        if let Some(data) = &material.timestamp_verification_data {
+            let mut tvd = Vec::new();
+            for timestamp in data.rfc3161_timestamps.iter().flatten() {
+                let mut t = Vec::new();
+                put_base64(&mut t, 1, &timestamp.signed_timestamp);
+                put_bytes(&mut tvd, 1, &t);
+            }
+            put_bytes(&mut verification_material, 4, &tvd);
+        }
+        let mut certificate = Vec::new();
+        put_base64(&mut certificate, 1, &material.certificate.raw_bytes);
+        put_bytes(&mut verification_material, 5, &certificate);
+
+        let mut out = Vec::new();
+        put_bytes(&mut out, 1, bundle.media_type.as_bytes());
+        put_bytes(&mut out, 2, &verification_material);
+        match &bundle.content {
+            BundleContent::DsseEnvelope(envelope) => {
+                let mut e = Vec::new();
+                put_base64(&mut e, 1, &envelope.payload);
+                put_bytes(&mut e, 2, envelope.payload_type.as_bytes());
+                for signature in &envelope.signatures {
+                    let mut s = Vec::new();
+                    put_base64(&mut s, 1, &signature.sig);
+                    if !signature.keyid.is_empty() {
+                        put_bytes(&mut s, 2, signature.keyid.as_bytes());
+                    }
+                    put_bytes(&mut e, 3, &s);
+                }
+                put_bytes(&mut out, 4, &e);
+            }
+            BundleContent::MessageSignature(signature) => {
+                let mut digest = Vec::new();
+                put_key(&mut digest, 1, 0);
+                put_varint(&mut digest, 1);
+                put_base64(&mut digest, 2, &signature.message_digest.digest);
+                let mut m = Vec::new();
+                put_bytes(&mut m, 1, &digest);
+                put_base64(&mut m, 2, &signature.signature);
+                put_bytes(&mut out, 3, &m);
+            }
+        }
+        out
+    }
+
+    fn sample(name: &str) -> SigstoreBundle {
+        let mut path = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        path.push("../../samples");
+        path.push(name);
+        parse_bundle_from_path(&path).unwrap()
+    }
+
+    #[test]
+    fn test_protobuf_bundle_matches_json() {
+        for name in [
+            "actions-attest-build-provenance-attestation-13532655.sigstore.json",
+            "actions-attest-build-provenance-attestation-13581567.sigstore.json",
+        ] {
+            let bundle = sample(name);
+            let decoded = parse_bundle_from_protobuf(&encode_bundle(&bundle)).unwrap();
+            assert_eq!(
+                serde_json::to_value(&decoded).unwrap(),
+                serde_json::to_value(&bundle).unwrap(),
+                "{}",
+                name
+            );
+        }
+    }
+
+    #[test]
+    fn test_protobuf_unknown_fields_are_skipped() {
+        let bundle = sample("actions-attest-build-provenance-attestation-13532655.sigstore.json");
+        let mut encoded = encode_bundle(&bundle);
+        // Fields a newer writer might add: a varint, a fixed64 and a string
+        put_key(&mut encoded, 15, 0);
+        put_varint(&mut encoded, 300);
+        put_key(&mut encoded, 16, 1);
+        encoded.extend_from_slice(&[0; 8]);
+        put_bytes(&mut encoded, 17, b"future");
+
+        let decoded = parse_bundle_from_protobuf(&encoded).unwrap();
+        assert_eq!(decoded.media_type, bundle.media_type);
+    }
+
+    #[test]
+    fn test_protobuf_malformed_is_rejected() {
+        let bundle = sample("actions-attest-build-provenance-attestation-13532655.sigstore.json");
+        let encoded = encode_bundle(&bundle);
+
+        // Truncated in the middle of the content
+        assert!(matches!(
+            parse_bundle_from_protobuf(&encoded[..encoded.len() - 10]),
+            Err(VerificationError::InvalidBundleFormat(_))
+        ));
+        // JSON is not a protobuf bundle
+        assert!(parse_bundle_from_protobuf(br#"{"mediaType":"x"}"#).is_err());
+        // Key-based bundles carry a public key instead of a certificate
+        let mut material = Vec::new();
+        put_bytes(&mut material, 1, b"");
+        let mut key_based = Vec::new();
+        put_bytes(&mut key_based, 2, &material);
+        assert!(matches!(
+            decode_bundle(&key_based),
+            Err(VerificationError::InvalidBundleFormat(_))
+        ));
+    }
+
+    #[test]
+    fn test_protobuf_negative_int64() {
+        let mut out = Vec::new();
+        put_int64(&mut out, 1, "-1");
+        let (_, value) = Reader::new(&out).next_field().unwrap().unwrap();
+        assert_eq!(int64_field(value, "logIndex").unwrap(), "-1");
+    }
+}
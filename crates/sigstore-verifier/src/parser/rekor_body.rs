@@ -0,0 +1,257 @@
+//! Parser for the canonicalized bodies of Rekor entries
+//!
+//! The body is the JSON document Rekor logged for the entry; its leaf hash is
+//! what the inclusion proof covers. For DSSE envelopes it is of one of two kinds:
+//!
+//! - `dsse` (0.0.1): `spec.payloadHash` and `spec.signatures[]` with the
+//!   `signature` (base64) and the signer's certificate as `verifier` (base64 PEM)
+//! - `intoto` (0.0.2): `spec.content.payloadHash` and
+//!   `spec.content.envelope.signatures[]` with `sig` (the base64 signature,
+//!   base64-encoded again) and the certificate as `publicKey` (base64 PEM)
+//!
+//! Blob signatures are logged as `hashedrekord` (0.0.1): `spec.data.hash` with
+//! the artifact's digest, and `spec.signature` with the base64 `content` and the
+//! certificate as `publicKey.content` (base64 PEM).
+//!
+//! Other kinds are reported as [`EntryKind::Other`] without parsing their spec.
+
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use crate::crypto::backend::DigestAlgorithm;
+use crate::error::TransparencyError;
+use crate::parser::bundle::decode_base64;
+use crate::parser::certificate::parse_pem_certificate;
+use serde_json::Value;
+
+/// Kind of a Rekor entry
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EntryKind {
+    Dsse,
+    Intoto,
+    HashedRekord,
+    /// Any other kind, e.g. `rekord`
+    Other(String),
+}
+
+impl EntryKind {
+    /// Name of the kind, as in the body and the entry's `kindVersion`
+    pub fn name(&self) -> &str {
+        match self {
+            EntryKind::Dsse => "dsse",
+            EntryKind::Intoto => "intoto",
+            EntryKind::HashedRekord => "hashedrekord",
+            EntryKind::Other(kind) => kind,
+        }
+    }
+}
+
+/// A parsed canonicalized body
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EntryBody {
+    pub kind: EntryKind,
+    pub api_version: String,
+    /// SHA256 of the envelope payload; always present for `dsse` bodies
+    pub payload_hash: Option<Vec<u8>>,
+    /// Digest of the signed artifact, for `hashedrekord` entries
+    pub artifact_hash: Option<(DigestAlgorithm, Vec<u8>)>,
+    pub signatures: Vec<EntrySignature>,
+}
+
+/// A signature recorded in the body, with the certificate that verifies it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EntrySignature {
+    pub signature: Vec<u8>,
+    /// DER of the signer's certificate
+    pub certificate: Vec<u8>,
+}
+
+/// Parse a decoded canonicalized body
+///
+/// # Errors
+///
+/// Returns `TransparencyError::InvalidEntryBody` if the body is not JSON, a
+/// `dsse`, `intoto` or `hashedrekord` body lacks its signatures or has malformed
+/// fields, or a `dsse` body lacks its `payloadHash`
+pub fn parse_entry_body(body: &[u8]) -> Result<EntryBody, TransparencyError> {
+    let invalid = |reason: &str| TransparencyError::InvalidEntryBody(reason.to_string());
+
+    let body: Value = serde_json::from_slice(body).map_err(|_| invalid("not JSON"))?;
+    let api_version = body["apiVersion"].as_str().unwrap_or_default().to_string();
+
+    let (kind, content, signatures, signature_field, certificate_field) =
+        match body["kind"].as_str().ok_or_else(|| invalid("missing kind"))? {
+            "hashedrekord" => return parse_hashedrekord(&body["spec"], api_version),
+            "dsse" => {
+                let spec = &body["spec"];
+                (EntryKind::Dsse, spec, &spec["signatures"], "signature", "verifier")
+            }
+            "intoto" => {
+                let content = &body["spec"]["content"];
+                (EntryKind::Intoto, content, &content["envelope"]["signatures"], "sig", "publicKey")
+            }
+            other => {
+                return Ok(EntryBody {
+                    kind: EntryKind::Other(other.to_string()),
+                    api_version,
+                    payload_hash: None,
+                    artifact_hash: None,
+                    signatures: Vec::new(),
+                })
+            }
+        };
+
+    let payload_hash = match &content["payloadHash"] {
+        Value::Null if kind == EntryKind::Dsse => return Err(invalid("missing payloadHash")),
+        Value::Null => None,
+        hash if hash["algorithm"] == "sha256" => Some(
+            hash["value"]
+                .as_str()
+                .and_then(|value| hex::decode(value).ok())
+                .ok_or_else(|| invalid("invalid payloadHash"))?,
+        ),
+        _ => return Err(invalid("payloadHash is not sha256")),
+    };
+
+    let mut parsed = Vec::new();
+    for signature in signatures.as_array().ok_or_else(|| invalid("missing signatures"))? {
+        let mut signature_bytes = signature[signature_field]
+            .as_str()
+            .and_then(|sig| decode_base64(sig).ok())
+            .ok_or_else(|| invalid("invalid signature"))?;
+        if kind == EntryKind::Intoto {
+            signature_bytes = core::str::from_utf8(&signature_bytes)
+                .ok()
+                .and_then(|sig| decode_base64(sig).ok())
+                .ok_or_else(|| invalid("invalid signature"))?;
+        }
+        let certificate = decode_certificate(&signature[certificate_field])?;
+        parsed.push(EntrySignature { signature: signature_bytes, certificate });
+    }
+
+    Ok(EntryBody {
+        kind,
+        api_version,
+        payload_hash,
+        artifact_hash: None,
+        signatures: parsed,
+    })
+}
+
+/// Parse the spec of a `hashedrekord` body
+fn parse_hashedrekord(spec: &Value, api_version: String) -> Result<EntryBody, TransparencyError> {
+    let invalid = |reason: &str| TransparencyError::InvalidEntryBody(reason.to_string());
+
+    let hash = &spec["data"]["hash"];
+    let algorithm = match hash["algorithm"].as_str() {
+        Some("sha256") => DigestAlgorithm::Sha256,
+        Some("sha384") => DigestAlgorithm::Sha384,
+        Some("sha512") => DigestAlgorithm::Sha512,
+        _ => return Err(invalid("unsupported artifact hash algorithm")),
+    };
+    let digest = hash["value"]
+        .as_str()
+        .and_then(|value| hex::decode(value).ok())
+        .ok_or_else(|| invalid("invalid artifact hash"))?;
+
+    let signature = spec["signature"]["content"]
+        .as_str()
+        .and_then(|sig| decode_base64(sig).ok())
+        .ok_or_else(|| invalid("invalid signature"))?;
+    let certificate = decode_certificate(&spec["signature"]["publicKey"]["content"])?;
+
+    Ok(EntryBody {
+        kind: EntryKind::HashedRekord,
+        api_version,
+        payload_hash: None,
+        artifact_hash: Some((algorithm, digest)),
+        signatures: vec![EntrySignature { signature, certificate }],
+    })
+}
+
+/// DER of a base64 PEM certificate field
+fn decode_certificate(field: &Value) -> Result<Vec<u8>, TransparencyError> {
+    field
+        .as_str()
+        .and_then(|pem| decode_base64(pem).ok())
+        .and_then(|pem| String::from_utf8(pem).ok())
+        .and_then(|pem| parse_pem_certificate(&pem).ok())
+        .ok_or_else(|| TransparencyError::InvalidEntryBody("invalid certificate".to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+    use alloc::format;
+
+    const CERT_PEM: &str = "-----BEGIN CERTIFICATE-----\nMAA=\n-----END CERTIFICATE-----\n";
+
+    #[test]
+    fn test_parse_dsse_and_intoto_bodies() {
+        let cert = BASE64.encode(CERT_PEM);
+        let dsse = format!(
+            r#"{{"apiVersion":"0.0.1","kind":"dsse","spec":{{"payloadHash":{{"algorithm":"sha256","value":"{}"}},"signatures":[{{"signature":"AQID","verifier":"{}"}}]}}}}"#,
+            "ab".repeat(32),
+            cert
+        );
+        let body = parse_entry_body(dsse.as_bytes()).unwrap();
+        assert_eq!(body.kind, EntryKind::Dsse);
+        assert_eq!(body.payload_hash, Some(vec![0xab; 32]));
+        assert_eq!(body.signatures, [EntrySignature { signature: vec![1, 2, 3], certificate: vec![0x30, 0] }]);
+
+        let intoto = format!(
+            r#"{{"apiVersion":"0.0.2","kind":"intoto","spec":{{"content":{{"envelope":{{"signatures":[{{"sig":"{}","publicKey":"{}"}}]}}}}}}}}"#,
+            BASE64.encode("AQID"),
+            cert
+        );
+        let body = parse_entry_body(intoto.as_bytes()).unwrap();
+        assert_eq!(body.kind, EntryKind::Intoto);
+        assert_eq!(body.payload_hash, None);
+        assert_eq!(body.signatures[0].signature, [1, 2, 3]);
+    }
+
+    #[test]
+    fn test_parse_hashedrekord_body() {
+        let hashedrekord = format!(
+            r#"{{"apiVersion":"0.0.1","kind":"hashedrekord","spec":{{"data":{{"hash":{{"algorithm":"sha256","value":"{}"}}}},"signature":{{"content":"AQID","publicKey":{{"content":"{}"}}}}}}}}"#,
+            "cd".repeat(32),
+            BASE64.encode(CERT_PEM)
+        );
+        let body = parse_entry_body(hashedrekord.as_bytes()).unwrap();
+        assert_eq!(body.kind, EntryKind::HashedRekord);
+        assert_eq!(body.artifact_hash, Some((DigestAlgorithm::Sha256, vec![0xcd; 32])));
+        assert_eq!(body.signatures, [EntrySignature { signature: vec![1, 2, 3], certificate: vec![0x30, 0] }]);
+
+        // A key rather than a certificate, and an unknown hash algorithm
+        let key_pem = BASE64.encode(CERT_PEM.replace("CERTIFICATE", "PUBLIC KEY"));
+        let key = hashedrekord.replace(&BASE64.encode(CERT_PEM), &key_pem);
+        assert!(parse_entry_body(key.as_bytes()).is_err());
+        assert!(parse_entry_body(hashedrekord.replace("sha256", "md5").as_bytes()).is_err());
+
+        // Rekor logs SHA-512 digests as well
+        let sha512 = hashedrekord.replace("sha256", "sha512").replace(&"cd".repeat(32), &"cd".repeat(64));
+        let body = parse_entry_body(sha512.as_bytes()).unwrap();
+        assert_eq!(body.artifact_hash, Some((DigestAlgorithm::Sha512, vec![0xcd; 64])));
+    }
+
+    #[test]
+    fn test_parse_other_kinds_and_malformed_bodies() {
+        let body = parse_entry_body(br#"{"apiVersion":"0.0.1","kind":"rekord","spec":{}}"#).unwrap();
+        assert_eq!(body.kind, EntryKind::Other("rekord".to_string()));
+
+        assert!(parse_entry_body(b"not json").is_err());
+        assert!(parse_entry_body(br#"{"kind":"dsse","spec":{}}"#).is_err());
+
+        // A dsse body must record the payload it was logged for
+        let cert = BASE64.encode(CERT_PEM);
+        let no_payload_hash = format!(
+            r#"{{"apiVersion":"0.0.1","kind":"dsse","spec":{{"signatures":[{{"signature":"AQID","verifier":"{}"}}]}}}}"#,
+            cert
+        );
+        assert!(matches!(
+            parse_entry_body(no_payload_hash.as_bytes()),
+            Err(TransparencyError::InvalidEntryBody(_))
+        ));
+    }
+}
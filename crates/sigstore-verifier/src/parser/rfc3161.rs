@@ -1,8 +1,11 @@
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
 use chrono::{DateTime, TimeZone, Utc};
 use cms::content_info::ContentInfo;
 use cms::signed_data::SignedData;
 use der::{Decode, Encode};
-use sha2::{Digest, Sha256, Sha384};
+use sha2::{Digest, Sha256, Sha384, Sha512};
 
 use crate::error::TimestampError;
 
@@ -11,6 +14,7 @@ use crate::error::TimestampError;
 pub enum HashAlgorithm {
     Sha256,
     Sha384,
+    Sha512,
 }
 
 impl HashAlgorithm {
@@ -19,6 +23,7 @@ impl HashAlgorithm {
         match self {
             HashAlgorithm::Sha256 => Sha256::digest(data).to_vec(),
             HashAlgorithm::Sha384 => Sha384::digest(data).to_vec(),
+            HashAlgorithm::Sha512 => Sha512::digest(data).to_vec(),
         }
     }
 }
@@ -30,11 +35,26 @@ pub struct MessageImprint {
     pub hashed_message: Vec<u8>,
 }
 
+/// Accuracy of the time in TSTInfo; absent components are zero
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Accuracy {
+    pub seconds: u64,
+    pub millis: u16,
+    pub micros: u16,
+}
+
 /// Parsed RFC 3161 timestamp information
 #[derive(Debug, Clone)]
 pub struct TSTInfo {
-    pub gen_time: DateTime<Utc>,
+    /// TSA policy the token was issued under, as a dotted OID
+    pub policy: String,
     pub message_imprint: MessageImprint,
+    /// Serial number of the token, as the big-endian bytes of the INTEGER
+    pub serial_number: Vec<u8>,
+    pub gen_time: DateTime<Utc>,
+    pub accuracy: Option<Accuracy>,
+    /// Nonce of the request, as the big-endian bytes of the INTEGER
+    pub nonce: Option<Vec<u8>>,
 }
 
 /// Parsed RFC 3161 timestamp token with optional embedded certificates
@@ -168,10 +188,14 @@ fn parse_tstinfo(signed_data: &SignedData) -> Result<TSTInfo, TimestampError> {
 ///   messageImprint MessageImprint,
 ///   serialNumber INTEGER,
 ///   genTime GeneralizedTime,
-///   ...
+///   accuracy Accuracy OPTIONAL,
+///   ordering BOOLEAN DEFAULT FALSE,
+///   nonce INTEGER OPTIONAL,
+///   tsa [0] GeneralName OPTIONAL,
+///   extensions [1] IMPLICIT Extensions OPTIONAL
 /// }
 fn parse_tstinfo_asn1(der: &[u8]) -> Result<TSTInfo, TimestampError> {
-    use asn1_rs::{FromDer, Integer, Sequence, Any};
+    use asn1_rs::{Any, Boolean, FromDer, Integer, Oid, Sequence, Tag};
 
     let (_rem, tstinfo_seq) = Sequence::from_der(der)
         .map_err(|e| TimestampError::Rfc3161Parse(format!("Failed to parse TSTInfo sequence: {}", e)))?;
@@ -183,8 +207,8 @@ fn parse_tstinfo_asn1(der: &[u8]) -> Result<TSTInfo, TimestampError> {
     let (rem, _version) = Integer::from_der(content)
         .map_err(|e| TimestampError::Rfc3161Parse(format!("Failed to parse version: {}", e)))?;
 
-    // Skip policy (OID)
-    let (rem, _policy) = Any::from_der(rem)
+    // Parse policy (OID)
+    let (rem, policy) = Oid::from_der(rem)
         .map_err(|e| TimestampError::Rfc3161Parse(format!("Failed to parse policy: {}", e)))?;
 
     // Parse messageImprint (SEQUENCE)
@@ -194,24 +218,77 @@ fn parse_tstinfo_asn1(der: &[u8]) -> Result<TSTInfo, TimestampError> {
     let message_imprint = parse_message_imprint_from_sequence(&message_imprint_obj)
         .map_err(|e| TimestampError::Rfc3161Parse(format!("Failed to parse MessageImprint: {}", e)))?;
 
-    // Skip serialNumber (INTEGER)
-    let (rem, _serial) = Integer::from_der(rem)
+    // Parse serialNumber (INTEGER)
+    let (rem, serial) = Integer::from_der(rem)
         .map_err(|e| TimestampError::Rfc3161Parse(format!("Failed to parse serialNumber: {}", e)))?;
 
     // Parse genTime (GeneralizedTime)
-    let (_, gen_time_obj) = Any::from_der(rem)
+    let (mut rem, gen_time_obj) = Any::from_der(rem)
         .map_err(|e| TimestampError::Rfc3161Parse(format!("Failed to parse genTime: {}", e)))?;
 
     // Use data() to get the actual content bytes without tag/length
     let gen_time = parse_generalized_time_value(gen_time_obj.data)
         .map_err(|e| TimestampError::Rfc3161Parse(format!("Failed to parse GeneralizedTime: {}", e)))?;
 
+    // Optional fields, in order: accuracy, ordering, nonce (tsa and extensions are not needed)
+    let mut accuracy = None;
+    let mut nonce = None;
+    while !rem.is_empty() {
+        let (next, field) = Any::from_der(rem)
+            .map_err(|e| TimestampError::Rfc3161Parse(format!("Failed to parse TSTInfo field: {}", e)))?;
+        match field.tag() {
+            Tag::Sequence if accuracy.is_none() && nonce.is_none() => {
+                let accuracy_seq = Sequence::try_from(field)
+                    .map_err(|e| TimestampError::Rfc3161Parse(format!("Failed to parse accuracy: {}", e)))?;
+                accuracy = Some(parse_accuracy(&accuracy_seq).map_err(TimestampError::Rfc3161Parse)?);
+            }
+            Tag::Boolean if nonce.is_none() => {
+                Boolean::try_from(field)
+                    .map_err(|e| TimestampError::Rfc3161Parse(format!("Failed to parse ordering: {}", e)))?;
+            }
+            Tag::Integer if nonce.is_none() => {
+                let value = Integer::try_from(field)
+                    .map_err(|e| TimestampError::Rfc3161Parse(format!("Failed to parse nonce: {}", e)))?;
+                nonce = Some(value.as_ref().to_vec());
+            }
+            _ => break,
+        }
+        rem = next;
+    }
+
     Ok(TSTInfo {
-        gen_time,
+        policy: policy.to_id_string(),
         message_imprint,
+        serial_number: serial.as_ref().to_vec(),
+        gen_time,
+        accuracy,
+        nonce,
     })
 }
 
+/// Parse Accuracy ::= SEQUENCE { seconds INTEGER OPTIONAL, millis [0] INTEGER OPTIONAL, micros [1] INTEGER OPTIONAL }
+fn parse_accuracy(seq: &asn1_rs::Sequence) -> Result<Accuracy, String> {
+    use asn1_rs::{Any, Class, FromDer, Integer, Tag};
+
+    let mut accuracy = Accuracy::default();
+    let mut rem = seq.content.as_ref();
+    while !rem.is_empty() {
+        let (next, field) = Any::from_der(rem).map_err(|e| format!("Failed to parse accuracy field: {}", e))?;
+        // millis and micros are implicitly tagged INTEGERs
+        let value = Integer::new(field.data)
+            .as_u64()
+            .map_err(|e| format!("Failed to parse accuracy value: {}", e))?;
+        match (field.class(), field.tag()) {
+            (Class::Universal, Tag::Integer) => accuracy.seconds = value,
+            (Class::ContextSpecific, Tag(0)) if value < 1000 => accuracy.millis = value as u16,
+            (Class::ContextSpecific, Tag(1)) if value < 1000 => accuracy.micros = value as u16,
+            _ => return Err(format!("Unexpected accuracy field {}", field.tag())),
+        }
+        rem = next;
+    }
+    Ok(accuracy)
+}
+
 /// Parse MessageImprint from Sequence object
 fn parse_message_imprint_from_sequence(seq: &asn1_rs::Sequence) -> Result<MessageImprint, String> {
     use asn1_rs::{FromDer, Sequence, OctetString};
@@ -248,9 +325,11 @@ fn parse_hash_algorithm_from_sequence(seq: &asn1_rs::Sequence) -> Result<HashAlg
 
     // OID for SHA-256: 2.16.840.1.101.3.4.2.1
     // OID for SHA-384: 2.16.840.1.101.3.4.2.2
+    // OID for SHA-512: 2.16.840.1.101.3.4.2.3
     match oid.to_string().as_str() {
         "2.16.840.1.101.3.4.2.1" => Ok(HashAlgorithm::Sha256),
         "2.16.840.1.101.3.4.2.2" => Ok(HashAlgorithm::Sha384),
+        "2.16.840.1.101.3.4.2.3" => Ok(HashAlgorithm::Sha512),
         other => Err(format!("Unsupported hash algorithm OID: {}", other)),
     }
 }
@@ -261,7 +340,7 @@ fn parse_hash_algorithm_from_sequence(seq: &asn1_rs::Sequence) -> Result<HashAlg
 /// Parse GeneralizedTime from value bytes (without tag/length)
 fn parse_generalized_time_value(value_bytes: &[u8]) -> Result<DateTime<Utc>, String> {
     // GeneralizedTime format: YYYYMMDDHHMMSS[.fff]Z (as ASCII/UTF8 string)
-    let time_str = std::str::from_utf8(value_bytes)
+    let time_str = core::str::from_utf8(value_bytes)
         .map_err(|e| format!("Invalid UTF-8 in GeneralizedTime: {}", e))?;
 
     // Parse format: YYYYMMDDHHMMSSZ or YYYYMMDDHHMMSS.fffZ
@@ -322,6 +401,7 @@ pub fn extract_tsa_certificates(signed_data: &SignedData) -> Option<Vec<Vec<u8>>
 #[cfg(test)]
 mod tests {
     use super::*;
+    use alloc::vec;
 
     #[test]
     fn test_hash_algorithm_sha256() {
@@ -342,4 +422,54 @@ mod tests {
         // Verify it's 48 bytes (384 bits)
         assert_eq!(hash.len(), 48);
     }
+
+    #[test]
+    fn test_parse_tstinfo_sample() {
+        use base64::prelude::*;
+
+        let path = concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/../../samples/actions-attest-build-provenance-attestation-13581567.sigstore.json"
+        );
+        let bundle = crate::parser::bundle::parse_bundle_from_bytes(&std::fs::read(path).unwrap()).unwrap();
+        let timestamps = bundle.verification_material.timestamp_verification_data.unwrap();
+        let der = BASE64_STANDARD
+            .decode(&timestamps.rfc3161_timestamps.unwrap()[0].signed_timestamp)
+            .unwrap();
+        let tst_info = parse_rfc3161_timestamp(&der).unwrap().tst_info;
+
+        assert_eq!(tst_info.policy, "1.3.6.1.4.1.57264.2");
+        assert_eq!(hex::encode(&tst_info.serial_number), "00dc05025c5b5af8444be82f15649ea8b329fbecfd");
+        assert_eq!(tst_info.gen_time.to_rfc3339(), "2025-11-19T02:26:13+00:00");
+        assert_eq!(tst_info.accuracy, Some(Accuracy { seconds: 1, millis: 0, micros: 0 }));
+        assert_eq!(tst_info.nonce, None);
+    }
+
+    #[test]
+    fn test_parse_tstinfo_optional_fields() {
+        let mut content = vec![0x02, 0x01, 0x01]; // version
+        content.extend([0x06, 0x03, 0x2a, 0x03, 0x04]); // policy 1.2.3.4
+        content.extend([0x30, 0x2f, 0x30, 0x0b, 0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x01]);
+        content.extend([0x04, 0x20]);
+        content.extend([0xab; 32]); // messageImprint
+        content.extend([0x02, 0x01, 0x2a]); // serialNumber 42
+        content.extend([0x18, 0x0f]);
+        content.extend(b"20240101000000Z"); // genTime
+        content.extend([0x30, 0x07, 0x02, 0x01, 0x02, 0x80, 0x02, 0x01, 0xf4]); // accuracy 2s 500ms
+        content.extend([0x01, 0x01, 0xff]); // ordering
+        content.extend([0x02, 0x08, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08]); // nonce
+        let mut der = vec![0x30, content.len() as u8];
+        der.extend(content);
+
+        let tst_info = parse_tstinfo_asn1(&der).unwrap();
+        assert_eq!(tst_info.policy, "1.2.3.4");
+        assert_eq!(tst_info.serial_number, [42]);
+        assert_eq!(tst_info.accuracy, Some(Accuracy { seconds: 2, millis: 500, micros: 0 }));
+        assert_eq!(tst_info.nonce, Some(vec![1, 2, 3, 4, 5, 6, 7, 8]));
+
+        // Out-of-range millis
+        let index = der.windows(2).position(|window| window == [0x80, 0x02]).unwrap();
+        der[index + 2] = 0x03;
+        assert!(parse_tstinfo_asn1(&der).is_err());
+    }
 }
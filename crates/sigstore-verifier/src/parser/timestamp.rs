@@ -1,3 +1,5 @@
+use alloc::string::ToString;
+use alloc::vec::Vec;
 use asn1_rs::{FromDer, Sequence};
 use chrono::{DateTime, Utc};
 
@@ -0,0 +1,363 @@
+//! Allocation and cycle profiling of verification steps
+//!
+//! Guest memory is paid for in cycles (paging in RISC0, memory checking in SP1
+//! and Pico), so a step that builds large intermediate values costs more than
+//! its instruction count suggests. With the `alloc-profile` feature, a zkVM
+//! guest installs [`CountingAllocator`] as its global allocator; every
+//! verification step then records how many allocations it made, how many bytes
+//! it allocated and how far it grew the heap. The guest prints [`take_report`]
+//! to stderr after committing the journal, which the executor forwards to the
+//! host without touching the journal.
+//!
+//! With the `cycle-profile` feature, the guest tells the verifier how to
+//! measure cycles on its backend with [`set_cycle_source`]: a cycle counter it
+//! can read (RISC0), or the markers its executor's cycle tracker recognizes
+//! (SP1, Pico). Counted cycles are printed with [`take_cycle_report`] the same
+//! way as the allocation profile; tracked cycles are reported by the executor.
+//!
+//! Without either feature, steps are plain function calls.
+
+#[cfg(feature = "alloc-profile")]
+pub use counting::{take_report, AllocStats, AllocationProfile, CountingAllocator, StepProfile};
+#[cfg(feature = "cycle-profile")]
+pub use cycles::{set_cycle_source, take_cycle_report, CycleProfile, CycleSource, StepCycles};
+
+/// Run one verification step, profiling it with the enabled features
+#[inline(always)]
+pub(crate) fn step<T>(name: &'static str, f: impl FnOnce() -> T) -> T {
+    #[cfg(feature = "alloc-profile")]
+    let f = || counting::record_step(name, f);
+    // Outermost, so that the cycles spent on allocation counting are attributed to the step
+    #[cfg(feature = "cycle-profile")]
+    let f = || cycles::record_step(name, f);
+    let _ = name;
+    f()
+}
+
+#[cfg(feature = "cycle-profile")]
+mod cycles {
+    use std::fmt;
+    use std::println;
+    use std::sync::{Mutex, OnceLock};
+    use std::vec::Vec;
+
+    /// Prefix of every report line, so the host can pick them out of guest output
+    const REPORT_PREFIX: &str = "cycle-profile:";
+
+    static SOURCE: OnceLock<CycleSource> = OnceLock::new();
+    static STEPS: Mutex<Vec<StepCycles>> = Mutex::new(Vec::new());
+
+    /// How a backend measures the cycles of a step
+    #[derive(Debug, Clone, Copy)]
+    pub enum CycleSource {
+        /// A cycle counter readable from the guest, e.g. RISC0's `env::cycle_count`
+        Counter(fn() -> u64),
+        /// Lines printed to stdout before and after the step, followed by the step
+        /// name, e.g. SP1's `cycle-tracker-report-start: ` and `cycle-tracker-report-end: `
+        Markers { start: &'static str, end: &'static str },
+    }
+
+    /// Set how steps are measured; only the first call has an effect
+    ///
+    /// Steps run before a source is set are not measured.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// use sigstore_verifier::profile::{set_cycle_source, CycleSource};
+    ///
+    /// set_cycle_source(CycleSource::Counter(risc0_zkvm::guest::env::cycle_count));
+    /// ```
+    pub fn set_cycle_source(source: CycleSource) {
+        let _ = SOURCE.set(source);
+    }
+
+    /// Cycles counted for one verification step
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct StepCycles {
+        pub step: &'static str,
+        pub cycles: u64,
+    }
+
+    /// Per-step cycle counts of a verification run
+    ///
+    /// Displays as one `cycle-profile:` line per step. Empty with a
+    /// [`CycleSource::Markers`] source, whose counts the executor reports.
+    #[derive(Debug, Clone, PartialEq, Eq, Default)]
+    pub struct CycleProfile {
+        pub steps: Vec<StepCycles>,
+    }
+
+    impl fmt::Display for CycleProfile {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            for (index, step) in self.steps.iter().enumerate() {
+                if index > 0 {
+                    writeln!(f)?;
+                }
+                write!(f, "{} {} cycles={}", REPORT_PREFIX, step.step, step.cycles)?;
+            }
+            Ok(())
+        }
+    }
+
+    /// Take the step cycle counts recorded so far
+    pub fn take_cycle_report() -> CycleProfile {
+        CycleProfile {
+            steps: std::mem::take(&mut *STEPS.lock().unwrap_or_else(|e| e.into_inner())),
+        }
+    }
+
+    pub(super) fn record_step<T>(name: &'static str, f: impl FnOnce() -> T) -> T {
+        match SOURCE.get() {
+            None => f(),
+            Some(CycleSource::Counter(count)) => {
+                let start = count();
+                let result = f();
+                let cycles = count().saturating_sub(start);
+                STEPS
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .push(StepCycles { step: name, cycles });
+                result
+            }
+            Some(CycleSource::Markers { start, end }) => {
+                println!("{}{}", start, name);
+                let result = f();
+                println!("{}{}", end, name);
+                result
+            }
+        }
+    }
+}
+
+#[cfg(feature = "alloc-profile")]
+mod counting {
+    use std::alloc::{GlobalAlloc, Layout, System};
+    use std::fmt;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Mutex;
+    use std::vec::Vec;
+
+    /// Prefix of every report line, so the host can pick them out of guest output
+    const REPORT_PREFIX: &str = "alloc-profile:";
+
+    static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+    static ALLOCATED_BYTES: AtomicUsize = AtomicUsize::new(0);
+    static CURRENT_BYTES: AtomicUsize = AtomicUsize::new(0);
+    static PEAK_BYTES: AtomicUsize = AtomicUsize::new(0);
+    static STEPS: Mutex<Vec<StepProfile>> = Mutex::new(Vec::new());
+
+    /// Global allocator that counts allocations on top of [`System`]
+    ///
+    /// Counters are process-wide, so steps are attributed correctly only in a
+    /// single-threaded program such as a zkVM guest.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// use sigstore_verifier::profile::CountingAllocator;
+    ///
+    /// #[global_allocator]
+    /// static ALLOCATOR: CountingAllocator = CountingAllocator::new();
+    /// ```
+    #[derive(Debug, Default)]
+    pub struct CountingAllocator;
+
+    impl CountingAllocator {
+        pub const fn new() -> Self {
+            Self
+        }
+    }
+
+    unsafe impl GlobalAlloc for CountingAllocator {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            let ptr = unsafe { System.alloc(layout) };
+            if !ptr.is_null() {
+                record_alloc(layout.size());
+            }
+            ptr
+        }
+
+        unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+            let ptr = unsafe { System.alloc_zeroed(layout) };
+            if !ptr.is_null() {
+                record_alloc(layout.size());
+            }
+            ptr
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            unsafe { System.dealloc(ptr, layout) };
+            CURRENT_BYTES.fetch_sub(layout.size(), Ordering::Relaxed);
+        }
+
+        unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+            let new_ptr = unsafe { System.realloc(ptr, layout, new_size) };
+            if !new_ptr.is_null() {
+                CURRENT_BYTES.fetch_sub(layout.size(), Ordering::Relaxed);
+                record_alloc(new_size);
+            }
+            new_ptr
+        }
+    }
+
+    fn record_alloc(size: usize) {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        ALLOCATED_BYTES.fetch_add(size, Ordering::Relaxed);
+        let current = CURRENT_BYTES.fetch_add(size, Ordering::Relaxed) + size;
+        PEAK_BYTES.fetch_max(current, Ordering::Relaxed);
+    }
+
+    /// Allocation counters of the whole run
+    ///
+    /// - allocations: Number of allocations (a reallocation counts as one)
+    /// - allocated_bytes: Total bytes requested, wrapping on 32-bit guests
+    /// - peak_bytes: Largest number of bytes live at once
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+    pub struct AllocStats {
+        pub allocations: usize,
+        pub allocated_bytes: usize,
+        pub peak_bytes: usize,
+    }
+
+    /// Allocations made by one verification step
+    ///
+    /// - step: Name of the step
+    /// - allocations: Number of allocations made during the step
+    /// - allocated_bytes: Bytes requested during the step
+    /// - peak_bytes: Largest heap growth over the heap size at the start of the step
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct StepProfile {
+        pub step: &'static str,
+        pub allocations: usize,
+        pub allocated_bytes: usize,
+        pub peak_bytes: usize,
+    }
+
+    /// Per-step profile of a verification run
+    ///
+    /// Displays as one `alloc-profile:` line per step followed by the totals.
+    #[derive(Debug, Clone, PartialEq, Eq, Default)]
+    pub struct AllocationProfile {
+        pub steps: Vec<StepProfile>,
+        pub total: AllocStats,
+    }
+
+    impl fmt::Display for AllocationProfile {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            for step in &self.steps {
+                writeln!(
+                    f,
+                    "{} {} allocations={} bytes={} peak={}",
+                    REPORT_PREFIX, step.step, step.allocations, step.allocated_bytes, step.peak_bytes
+                )?;
+            }
+            write!(
+                f,
+                "{} total allocations={} bytes={} peak={}",
+                REPORT_PREFIX, self.total.allocations, self.total.allocated_bytes, self.total.peak_bytes
+            )
+        }
+    }
+
+    /// Take the steps recorded so far, together with the run's totals
+    pub fn take_report() -> AllocationProfile {
+        let steps = std::mem::take(&mut *STEPS.lock().unwrap_or_else(|e| e.into_inner()));
+        AllocationProfile {
+            steps,
+            total: AllocStats {
+                allocations: ALLOCATIONS.load(Ordering::Relaxed),
+                allocated_bytes: ALLOCATED_BYTES.load(Ordering::Relaxed),
+                peak_bytes: PEAK_BYTES.load(Ordering::Relaxed),
+            },
+        }
+    }
+
+    pub(super) fn record_step<T>(name: &'static str, f: impl FnOnce() -> T) -> T {
+        let allocations = ALLOCATIONS.load(Ordering::Relaxed);
+        let allocated_bytes = ALLOCATED_BYTES.load(Ordering::Relaxed);
+        let current_bytes = CURRENT_BYTES.load(Ordering::Relaxed);
+        // Track the step's own peak, then fold it back into the run's peak
+        let run_peak = PEAK_BYTES.swap(current_bytes, Ordering::Relaxed);
+
+        let result = f();
+
+        let step_peak = PEAK_BYTES.fetch_max(run_peak, Ordering::Relaxed);
+        let profile = StepProfile {
+            step: name,
+            allocations: ALLOCATIONS.load(Ordering::Relaxed).wrapping_sub(allocations),
+            allocated_bytes: ALLOCATED_BYTES.load(Ordering::Relaxed).wrapping_sub(allocated_bytes),
+            peak_bytes: step_peak.saturating_sub(current_bytes),
+        };
+        // Recorded after measuring, so the report's own allocation is not attributed to the step
+        STEPS.lock().unwrap_or_else(|e| e.into_inner()).push(profile);
+
+        result
+    }
+}
+
+#[cfg(all(test, any(feature = "alloc-profile", feature = "cycle-profile")))]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+    #[cfg(feature = "alloc-profile")]
+    use std::alloc::{GlobalAlloc, Layout};
+
+    #[cfg(feature = "cycle-profile")]
+    #[test]
+    fn test_step_records_counted_cycles() {
+        use std::sync::atomic::{AtomicU64, Ordering};
+
+        static CYCLES: AtomicU64 = AtomicU64::new(0);
+        set_cycle_source(CycleSource::Counter(|| CYCLES.load(Ordering::SeqCst)));
+
+        let value = step("test_cycles", || {
+            CYCLES.fetch_add(1500, Ordering::SeqCst);
+            7
+        });
+        assert_eq!(value, 7);
+
+        let report = take_cycle_report();
+        let cycles = report
+            .steps
+            .iter()
+            .find(|step| step.step == "test_cycles")
+            .expect("step should be recorded");
+        assert_eq!(cycles.cycles, 1500);
+        assert!(report.to_string().contains("cycle-profile: test_cycles cycles=1500"));
+    }
+
+    #[cfg(feature = "alloc-profile")]
+    #[test]
+    fn test_step_records_allocations_and_peak() {
+        let allocator = CountingAllocator::new();
+        let layout = Layout::from_size_align(1024, 8).unwrap();
+
+        let value = step("test_alloc", || unsafe {
+            let first = allocator.alloc(layout);
+            let second = allocator.alloc(layout);
+            allocator.dealloc(first, layout);
+            let third = allocator.alloc(layout);
+            allocator.dealloc(second, layout);
+            allocator.dealloc(third, layout);
+            7
+        });
+        assert_eq!(value, 7);
+
+        let report = take_report();
+        let profile = report
+            .steps
+            .iter()
+            .find(|profile| profile.step == "test_alloc")
+            .expect("step should be recorded");
+        assert_eq!(profile.allocations, 3);
+        assert_eq!(profile.allocated_bytes, 3072);
+        assert_eq!(profile.peak_bytes, 2048);
+        assert!(report.total.peak_bytes >= 2048);
+        assert!(report
+            .to_string()
+            .contains("alloc-profile: test_alloc allocations=3 bytes=3072 peak=2048"));
+
+        assert!(take_report().steps.iter().all(|profile| profile.step != "test_alloc"));
+    }
+}
@@ -0,0 +1,352 @@
+//! Per-step verification reports
+//!
+//! [`AttestationVerifier::verify_bundle_report`](crate::AttestationVerifier::verify_bundle_report)
+//! runs the same verification as `verify_bundle_bytes`, but records every step
+//! it ran (parsing, subject digest, certificate chain, signature, timestamp,
+//! transparency log, identity, ...) with its duration and whether it passed.
+//! The report is returned for rejected bundles as well, with the error and the
+//! step that raised it, so a rejection can be narrowed down without a debugger.
+//!
+//! In audit mode ([`verify_bundle_audit`](crate::AttestationVerifier::verify_bundle_audit))
+//! the failure of selected [`AuditCheck`]s is collected as an [`AuditWarning`]
+//! and verification goes on, so a corpus of existing bundles can be scanned
+//! for those that would fail under the strict checks, and why. A report with
+//! warnings is not a successful verification.
+//!
+//! Steps are timed with `std::time::Instant`, which zkVM guests don't provide;
+//! without the `std` feature their duration is zero. The other verification
+//! methods don't record steps and never read the clock.
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::cell::{Cell, RefCell};
+use core::fmt;
+use core::str::FromStr;
+use core::time::Duration;
+
+use crate::error::VerificationError;
+use crate::profile;
+use crate::types::result::VerificationResult;
+
+/// Checks that audit mode can report as warnings instead of failing on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AuditCheck {
+    /// The TSA leaf certificate's extended key usage is critical and only
+    /// id-kp-timeStamping (RFC 3161 §2.3)
+    TsaEku,
+    /// The signed entry timestamp verifies against the Rekor keys
+    SignedEntryTimestamp,
+    /// The inclusion proof's checkpoint is for the proven tree and verifies
+    /// against the Rekor keys
+    Checkpoint,
+    /// The logged entry body describes the bundle's content
+    EntryBody,
+    /// The leaf certificate's SCTs verify against the CT log keys
+    CertificateTransparency,
+    /// The signature is no older than `max_signing_age`
+    SigningAge,
+}
+
+impl AuditCheck {
+    /// Every check audit mode can relax
+    pub const ALL: [AuditCheck; 6] = [
+        AuditCheck::TsaEku,
+        AuditCheck::SignedEntryTimestamp,
+        AuditCheck::Checkpoint,
+        AuditCheck::EntryBody,
+        AuditCheck::CertificateTransparency,
+        AuditCheck::SigningAge,
+    ];
+
+    /// Name of the check, as accepted by [`AuditCheck::from_str`]
+    pub fn name(&self) -> &'static str {
+        match self {
+            AuditCheck::TsaEku => "tsa-eku",
+            AuditCheck::SignedEntryTimestamp => "set",
+            AuditCheck::Checkpoint => "checkpoint",
+            AuditCheck::EntryBody => "entry-body",
+            AuditCheck::CertificateTransparency => "sct",
+            AuditCheck::SigningAge => "signing-age",
+        }
+    }
+}
+
+impl fmt::Display for AuditCheck {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for AuditCheck {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        AuditCheck::ALL
+            .into_iter()
+            .find(|check| check.name() == s)
+            .ok_or_else(|| {
+                let names: Vec<_> = AuditCheck::ALL.iter().map(|check| check.name()).collect();
+                format!("Unknown audit check '{}' (expected one of {})", s, names.join(", "))
+            })
+    }
+}
+
+/// Failure of a relaxed check, which verification went on after
+///
+/// - check: The check that failed
+/// - step: Name of the step it failed in
+/// - error: The error the check would have failed verification with
+#[derive(Debug)]
+pub struct AuditWarning {
+    pub check: AuditCheck,
+    pub step: &'static str,
+    pub error: VerificationError,
+}
+
+/// Outcome of one verification step
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StepStatus {
+    Passed,
+    /// The step raised the error of the report's result
+    Failed,
+}
+
+/// One verification step that was run
+///
+/// - step: Name of the step, as in the allocation and cycle profiles
+/// - status: Whether the step passed
+/// - duration: Wall-clock time the step took, zero without the `std` feature
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StepReport {
+    pub step: &'static str,
+    pub status: StepStatus,
+    pub duration: Duration,
+}
+
+/// Steps of a verification run and its outcome
+///
+/// Steps that are not needed (e.g. the transparency log of an RFC 3161
+/// timestamped bundle) or come after a failed one are not listed. In audit
+/// mode, `result` is that of verification without the relaxed checks that
+/// failed, which are listed in `warnings`. Displays as one line per step and
+/// warning followed by the outcome.
+#[derive(Debug)]
+pub struct VerificationReport {
+    pub steps: Vec<StepReport>,
+    pub warnings: Vec<AuditWarning>,
+    pub result: Result<VerificationResult, VerificationError>,
+}
+
+impl VerificationReport {
+    /// Whether the bundle verified, relaxed checks included
+    pub fn is_ok(&self) -> bool {
+        self.result.is_ok() && self.warnings.is_empty()
+    }
+
+    /// Name of the step that rejected the bundle, if any
+    pub fn failed_step(&self) -> Option<&'static str> {
+        self.steps
+            .iter()
+            .find(|step| step.status == StepStatus::Failed)
+            .map(|step| step.step)
+    }
+
+    /// Total time of the recorded steps
+    pub fn duration(&self) -> Duration {
+        self.steps.iter().map(|step| step.duration).sum()
+    }
+}
+
+impl fmt::Display for VerificationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for step in &self.steps {
+            let status = match step.status {
+                StepStatus::Passed => "ok",
+                StepStatus::Failed => "FAILED",
+            };
+            writeln!(
+                f,
+                "{:<6} {:<26} {:>9.3} ms",
+                status,
+                step.step,
+                step.duration.as_secs_f64() * 1000.0
+            )?;
+        }
+        for warning in &self.warnings {
+            writeln!(f, "{:<6} {} in {}: {}", "WARN", warning.check, warning.step, warning.error)?;
+        }
+        match &self.result {
+            Ok(_) if !self.warnings.is_empty() => write!(
+                f,
+                "verified with {} relaxed check(s) failing in {:.3} ms",
+                self.warnings.len(),
+                self.duration().as_secs_f64() * 1000.0
+            ),
+            Ok(_) => write!(f, "verified in {:.3} ms", self.duration().as_secs_f64() * 1000.0),
+            Err(e) => write!(f, "rejected (error {}): {}", e.code(), e),
+        }
+    }
+}
+
+/// Runs verification steps, recording them if a report was asked for
+///
+/// Steps take `&self`, so that checks nested in a step can be relaxed with
+/// [`Self::relax`] while it runs.
+#[derive(Debug, Default)]
+pub(crate) struct StepRecorder {
+    steps: Option<RefCell<Vec<StepReport>>>,
+    relaxed: Vec<AuditCheck>,
+    warnings: RefCell<Vec<AuditWarning>>,
+    current_step: Cell<&'static str>,
+}
+
+impl StepRecorder {
+    /// Recorder for a report, relaxing the given checks
+    pub(crate) fn recording(relaxed: &[AuditCheck]) -> Self {
+        Self {
+            steps: Some(RefCell::new(Vec::new())),
+            relaxed: relaxed.to_vec(),
+            ..Self::default()
+        }
+    }
+
+    /// Run one verification step, profiling it and recording it if enabled
+    pub(crate) fn run<T>(
+        &self,
+        name: &'static str,
+        f: impl FnOnce() -> Result<T, VerificationError>,
+    ) -> Result<T, VerificationError> {
+        let Some(ref steps) = self.steps else {
+            return profile::step(name, f);
+        };
+        self.current_step.set(name);
+        let start = Stopwatch::start();
+        let result = profile::step(name, f);
+        steps.borrow_mut().push(StepReport {
+            step: name,
+            status: if result.is_ok() { StepStatus::Passed } else { StepStatus::Failed },
+            duration: start.elapsed(),
+        });
+        result
+    }
+
+    /// Fail with `error` of `check`, or record it as a warning if the check is relaxed
+    pub(crate) fn relax(&self, check: AuditCheck, error: VerificationError) -> Result<(), VerificationError> {
+        if !self.relaxed.contains(&check) {
+            return Err(error);
+        }
+        self.warnings.borrow_mut().push(AuditWarning {
+            check,
+            step: self.current_step.get(),
+            error,
+        });
+        Ok(())
+    }
+
+    /// Drop the warnings raised so far
+    pub(crate) fn clear_warnings(&self) {
+        self.warnings.borrow_mut().clear();
+    }
+
+    /// Report of the recorded steps with the run's result
+    pub(crate) fn finish(self, result: Result<VerificationResult, VerificationError>) -> VerificationReport {
+        VerificationReport {
+            steps: self.steps.map(RefCell::into_inner).unwrap_or_default(),
+            warnings: self.warnings.into_inner(),
+            result,
+        }
+    }
+}
+
+/// Time since the start of a step
+#[cfg(feature = "std")]
+struct Stopwatch(std::time::Instant);
+
+#[cfg(feature = "std")]
+impl Stopwatch {
+    fn start() -> Self {
+        Self(std::time::Instant::now())
+    }
+
+    fn elapsed(&self) -> Duration {
+        self.0.elapsed()
+    }
+}
+
+/// Without a clock every step takes zero time
+#[cfg(not(feature = "std"))]
+struct Stopwatch;
+
+#[cfg(not(feature = "std"))]
+impl Stopwatch {
+    fn start() -> Self {
+        Self
+    }
+
+    fn elapsed(&self) -> Duration {
+        Duration::ZERO
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+
+    #[test]
+    fn test_recorder_records_passed_and_failed_steps() {
+        let recorder = StepRecorder::recording(&[]);
+        assert_eq!(recorder.run("first", || Ok(7)).unwrap(), 7);
+        let failed: Result<(), _> = recorder.run("second", || Err(VerificationError::ZeroSubjectDigest));
+        let report = recorder.finish(failed.map(|_| unreachable!()));
+
+        let steps: Vec<_> = report.steps.iter().map(|step| (step.step, step.status)).collect();
+        assert_eq!(steps, [("first", StepStatus::Passed), ("second", StepStatus::Failed)]);
+        assert_eq!(report.failed_step(), Some("second"));
+        assert!(!report.is_ok());
+        assert!(report.to_string().lines().last().unwrap().starts_with("rejected (error "));
+    }
+
+    #[test]
+    fn test_disabled_recorder_records_nothing() {
+        let recorder = StepRecorder::default();
+        assert_eq!(recorder.run("first", || Ok(7)).unwrap(), 7);
+        assert!(recorder.finish(Err(VerificationError::ZeroSubjectDigest)).steps.is_empty());
+    }
+
+    #[test]
+    fn test_relaxed_check_becomes_warning() {
+        let recorder = StepRecorder::recording(&[AuditCheck::TsaEku]);
+        recorder
+            .run("tsa_certificate_chain", || {
+                recorder.relax(AuditCheck::TsaEku, VerificationError::ZeroSubjectDigest)
+            })
+            .unwrap();
+        assert!(recorder
+            .run("transparency_log", || {
+                recorder.relax(AuditCheck::SignedEntryTimestamp, VerificationError::ZeroSubjectDigest)
+            })
+            .is_err());
+
+        let report = recorder.finish(Err(VerificationError::ZeroSubjectDigest));
+        assert_eq!(report.warnings.len(), 1);
+        assert_eq!(report.warnings[0].check, AuditCheck::TsaEku);
+        assert_eq!(report.warnings[0].step, "tsa_certificate_chain");
+        assert!(report.to_string().contains("WARN   tsa-eku in tsa_certificate_chain: "));
+        assert_eq!(report.failed_step(), Some("transparency_log"));
+
+        // Without relaxed checks, nothing is downgraded
+        assert!(StepRecorder::default()
+            .relax(AuditCheck::TsaEku, VerificationError::ZeroSubjectDigest)
+            .is_err());
+    }
+
+    #[test]
+    fn test_audit_check_names_roundtrip() {
+        for check in AuditCheck::ALL {
+            assert_eq!(check.name().parse::<AuditCheck>().unwrap(), check);
+        }
+        assert!("eku".parse::<AuditCheck>().unwrap_err().contains("tsa-eku"));
+    }
+}
@@ -0,0 +1,374 @@
+//! Async verification service for servers
+//!
+//! [`AttestationVerifier`] is blocking and CPU-heavy: a bundle takes
+//! milliseconds of certificate, signature and inclusion proof checks. Called
+//! from an async handler it holds up a runtime worker, and under load a server
+//! stops answering anything else, health checks included. [`VerifierService`]
+//! verifies on a dedicated pool of threads instead, and bounds the work it
+//! takes on:
+//!
+//! - At most `workers` bundles are verified at once, and at most `queue_depth`
+//!   more wait for a worker. A request beyond that fails at once with
+//!   [`ServiceError::Overloaded`], for the server to answer 503 (or gRPC
+//!   `RESOURCE_EXHAUSTED`) instead of queueing without bound.
+//! - A request not answered within its deadline, from the time it was
+//!   accepted, fails with [`ServiceError::DeadlineExceeded`].
+//! - A request whose future is dropped, e.g. because the client went away, or
+//!   whose deadline passed is cancelled: a worker that has not started it yet
+//!   skips it. A verification already running is not interrupted; its worker
+//!   finishes the bundle and drops the outcome.
+//!
+//! The service is cheap to clone and shared by the handlers; the workers exit
+//! once the last clone is dropped. Deadlines need a Tokio runtime with the
+//! time driver enabled.
+
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::vec::Vec;
+use std::num::NonZeroUsize;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use thiserror::Error;
+use tokio::sync::{oneshot, Semaphore};
+
+use crate::outcome::VerificationOutcome;
+use crate::types::certificate::CertificateChain;
+use crate::types::result::VerificationOptions;
+use crate::AttestationVerifier;
+
+/// Deadline of requests that don't set one, by default
+pub const DEFAULT_DEADLINE: Duration = Duration::from_secs(30);
+
+/// Requests waiting per worker, by default
+const QUEUE_PER_WORKER: usize = 4;
+
+/// Errors of the service itself; verification errors are part of the outcome
+#[derive(Debug, Error)]
+pub enum ServiceError {
+    #[error("Verifier service overloaded: {0} requests already in flight")]
+    Overloaded(usize),
+
+    #[error("Verification did not complete within {0:?}")]
+    DeadlineExceeded(Duration),
+
+    #[error("Verification worker failed without an outcome")]
+    WorkerFailed,
+}
+
+/// Sizing of a [`VerifierService`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ServiceConfig {
+    /// Verification threads
+    pub workers: usize,
+    /// Accepted requests waiting for a worker at most
+    pub queue_depth: usize,
+    /// Deadline of requests that don't set one; `None` waits indefinitely
+    pub default_deadline: Option<Duration>,
+}
+
+impl Default for ServiceConfig {
+    /// One worker per available CPU, four waiting requests per worker and a
+    /// deadline of [`DEFAULT_DEADLINE`]
+    fn default() -> Self {
+        let workers = thread::available_parallelism()
+            .map(NonZeroUsize::get)
+            .unwrap_or(1);
+        Self {
+            workers,
+            queue_depth: workers * QUEUE_PER_WORKER,
+            default_deadline: Some(DEFAULT_DEADLINE),
+        }
+    }
+}
+
+/// A bundle to verify and the trust material to verify it against
+///
+/// The certificate chains are shared, so requests verified against the same
+/// trusted root don't copy it.
+#[derive(Debug, Clone)]
+pub struct VerifyRequest {
+    /// Raw JSON bytes of the sigstore bundle
+    pub bundle_json: Vec<u8>,
+    /// Verification options
+    pub options: VerificationOptions,
+    /// Certificate chain (intermediates and root) for verification
+    pub trust_bundle: Arc<CertificateChain>,
+    /// Optional TSA certificate chain for RFC 3161 timestamp verification
+    pub tsa_cert_chain: Option<Arc<CertificateChain>>,
+    /// Deadline of this request, instead of the service's default
+    pub deadline: Option<Duration>,
+}
+
+impl VerifyRequest {
+    /// Request verifying `bundle_json` with default options and no TSA chain
+    pub fn new(bundle_json: Vec<u8>, trust_bundle: Arc<CertificateChain>) -> Self {
+        Self {
+            bundle_json,
+            options: VerificationOptions::default(),
+            trust_bundle,
+            tsa_cert_chain: None,
+            deadline: None,
+        }
+    }
+
+    fn verify(self) -> VerificationOutcome {
+        AttestationVerifier::new().verify_bundle_outcome(
+            &self.bundle_json,
+            self.options,
+            &self.trust_bundle,
+            self.tsa_cert_chain.as_deref(),
+        )
+    }
+}
+
+type Job = Box<dyn FnOnce() + Send>;
+
+/// Async facade over [`AttestationVerifier`] with bounded concurrency
+///
+/// See the [module documentation](self) for how requests are queued,
+/// rejected, timed out and cancelled.
+#[derive(Clone)]
+pub struct VerifierService {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    jobs: mpsc::Sender<Job>,
+    /// One permit per request the service may hold, running or waiting
+    permits: Arc<Semaphore>,
+    capacity: usize,
+    default_deadline: Option<Duration>,
+}
+
+impl VerifierService {
+    /// Start a service with `config.workers` verification threads
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a worker thread cannot be spawned.
+    pub fn new(config: ServiceConfig) -> std::io::Result<Self> {
+        let workers = config.workers.max(1);
+        let (sender, receiver) = mpsc::channel::<Job>();
+        let receiver = Arc::new(Mutex::new(receiver));
+        for index in 0..workers {
+            let receiver = Arc::clone(&receiver);
+            thread::Builder::new()
+                .name(format!("sigstore-verify-{}", index))
+                .spawn(move || loop {
+                    // The lock is released before running the job
+                    let job = receiver.lock().unwrap_or_else(|e| e.into_inner()).recv();
+                    match job {
+                        Ok(job) => job(),
+                        // The service was dropped
+                        Err(_) => break,
+                    }
+                })?;
+        }
+
+        let capacity = workers + config.queue_depth;
+        Ok(Self {
+            inner: Arc::new(Inner {
+                jobs: sender,
+                permits: Arc::new(Semaphore::new(capacity)),
+                capacity,
+                default_deadline: config.default_deadline,
+            }),
+        })
+    }
+
+    /// Verify a bundle on the service's threads
+    ///
+    /// The outcome is that of
+    /// [`AttestationVerifier::verify_bundle_outcome`]: an invalid bundle is an
+    /// `Ok` outcome, errors are the service's own.
+    ///
+    /// # Errors
+    ///
+    /// - `ServiceError::Overloaded` if the workers are busy and the queue is full
+    /// - `ServiceError::DeadlineExceeded` if no outcome arrived within the deadline
+    /// - `ServiceError::WorkerFailed` if verification panicked
+    pub async fn verify(&self, request: VerifyRequest) -> Result<VerificationOutcome, ServiceError> {
+        let permit = Arc::clone(&self.inner.permits)
+            .try_acquire_owned()
+            .map_err(|_| ServiceError::Overloaded(self.inner.capacity))?;
+        let deadline = request.deadline.or(self.inner.default_deadline);
+
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let (sender, receiver) = oneshot::channel();
+        let job_cancelled = Arc::clone(&cancelled);
+        let job: Job = Box::new(move || {
+            // Held until the worker is done with the request, cancelled or not
+            let permit = permit;
+            if job_cancelled.load(Ordering::Acquire) {
+                return;
+            }
+            // A panic fails this request, not the worker
+            let outcome = panic::catch_unwind(AssertUnwindSafe(|| request.verify()));
+            // Released before the caller can see the outcome, so `in_flight` no longer counts it
+            drop(permit);
+            if let Ok(outcome) = outcome {
+                let _ = sender.send(outcome);
+            }
+        });
+        self.inner
+            .jobs
+            .send(job)
+            .map_err(|_| ServiceError::WorkerFailed)?;
+
+        // Cancels the job when this future is dropped or gives up waiting
+        let _cancel = CancelOnDrop(cancelled);
+        let outcome = match deadline {
+            Some(deadline) => tokio::time::timeout(deadline, receiver)
+                .await
+                .map_err(|_| ServiceError::DeadlineExceeded(deadline))?,
+            None => receiver.await,
+        };
+        outcome.map_err(|_| ServiceError::WorkerFailed)
+    }
+
+    /// Requests the service holds at most, running or waiting
+    pub fn capacity(&self) -> usize {
+        self.inner.capacity
+    }
+
+    /// Requests the service holds now, running or waiting
+    pub fn in_flight(&self) -> usize {
+        self.inner.capacity - self.inner.permits.available_permits()
+    }
+}
+
+impl std::fmt::Debug for VerifierService {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("VerifierService")
+            .field("capacity", &self.inner.capacity)
+            .field("in_flight", &self.in_flight())
+            .field("default_deadline", &self.inner.default_deadline)
+            .finish()
+    }
+}
+
+struct CancelOnDrop(Arc<AtomicBool>);
+
+impl Drop for CancelOnDrop {
+    fn drop(&mut self) {
+        self.0.store(true, Ordering::Release);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fetcher::jsonl::parser::{
+        load_trusted_root_from_jsonl, select_certificate_authority, select_timestamp_authority,
+    };
+    use crate::parser::bundle::{extract_bundle_timestamp, parse_bundle_from_bytes};
+    use crate::types::certificate::FulcioInstance;
+    use std::path::PathBuf;
+    use std::time::Instant;
+
+    fn sample_request() -> VerifyRequest {
+        let samples = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../../samples");
+        let bundle_json = std::fs::read(
+            samples.join("actions-attest-build-provenance-attestation-13581567.sigstore.json"),
+        )
+        .unwrap();
+        let trust_roots =
+            load_trusted_root_from_jsonl(&std::fs::read_to_string(samples.join("trusted_root.jsonl")).unwrap())
+                .unwrap();
+        let bundle = parse_bundle_from_bytes(&bundle_json).unwrap();
+        let timestamp = extract_bundle_timestamp(&bundle).unwrap();
+        let instance = FulcioInstance::from_bundle_json(std::str::from_utf8(&bundle_json).unwrap()).unwrap();
+
+        let trust_bundle = select_certificate_authority(&trust_roots, &instance, timestamp).unwrap();
+        let mut request = VerifyRequest::new(bundle_json, Arc::new(trust_bundle));
+        request.tsa_cert_chain = Some(Arc::new(
+            select_timestamp_authority(&trust_roots, &instance, timestamp).unwrap(),
+        ));
+        request
+    }
+
+    fn service(workers: usize, queue_depth: usize) -> VerifierService {
+        VerifierService::new(ServiceConfig {
+            workers,
+            queue_depth,
+            default_deadline: Some(DEFAULT_DEADLINE),
+        })
+        .unwrap()
+    }
+
+    /// Keep a worker busy, holding a permit, until the returned sender is dropped
+    fn occupy_worker(service: &VerifierService) -> mpsc::Sender<()> {
+        let permit = Arc::clone(&service.inner.permits).try_acquire_owned().unwrap();
+        let (release, released) = mpsc::channel::<()>();
+        let (started, has_started) = mpsc::channel();
+        let job: Job = Box::new(move || {
+            let _permit = permit;
+            started.send(()).unwrap();
+            let _ = released.recv();
+        });
+        service.inner.jobs.send(job).unwrap();
+        has_started.recv().unwrap();
+        release
+    }
+
+    async fn wait_until_idle(service: &VerifierService) {
+        let start = Instant::now();
+        while service.in_flight() > 0 {
+            assert!(start.elapsed() < Duration::from_secs(10), "service did not drain");
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+    }
+
+    #[tokio::test]
+    async fn test_service_matches_direct_verification() {
+        let request = sample_request();
+        let expected = request.clone().verify().into_result().unwrap().as_slice();
+
+        let service = service(2, 2);
+        let outcome = service.verify(request.clone()).await.unwrap();
+        assert!(outcome.is_verified());
+        assert_eq!(outcome.into_result().unwrap().as_slice(), expected);
+
+        // An invalid bundle is an outcome, not a service error
+        let mut invalid = request;
+        invalid.bundle_json = b"{}".to_vec();
+        let outcome = service.verify(invalid).await.unwrap();
+        assert_eq!(outcome.name(), "invalid");
+        assert_eq!(service.in_flight(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_full_service_rejects_requests() {
+        let service = service(1, 0);
+        assert_eq!(service.capacity(), 1);
+        let release = occupy_worker(&service);
+
+        let result = service.verify(sample_request()).await;
+        assert!(matches!(result, Err(ServiceError::Overloaded(1))));
+
+        drop(release);
+        wait_until_idle(&service).await;
+        assert!(service.verify(sample_request()).await.unwrap().is_verified());
+    }
+
+    #[tokio::test]
+    async fn test_deadline_cancels_queued_request() {
+        let service = service(1, 1);
+        let release = occupy_worker(&service);
+
+        let mut request = sample_request();
+        request.deadline = Some(Duration::from_millis(50));
+        let result = service.verify(request).await;
+        assert!(matches!(result, Err(ServiceError::DeadlineExceeded(_))));
+        // The timed out request still holds its queue slot until a worker skips it
+        assert_eq!(service.in_flight(), 2);
+
+        drop(release);
+        wait_until_idle(&service).await;
+        assert!(service.verify(sample_request()).await.unwrap().is_verified());
+    }
+}
@@ -1,11 +1,91 @@
+use alloc::string::String;
+use alloc::vec::Vec;
 use serde::{Deserialize, Serialize};
 
+/// Bundle format versions this verifier understands, oldest first
+///
+/// Any other version is rejected when the bundle is parsed, including newer
+/// versions that merely share the media type prefix.
+pub const SUPPORTED_BUNDLE_VERSIONS: &[&str] = &["0.1", "0.2", "0.3"];
+
+/// Extract the format version from a bundle media type
+///
+/// Accepts both spellings used by Sigstore clients,
+/// `application/vnd.dev.sigstore.bundle.v0.3+json` and
+/// `application/vnd.dev.sigstore.bundle+json;version=0.3`.
+///
+/// # Returns
+///
+/// The version (e.g. `"0.3"`), or `None` if this is not a bundle media type
+pub fn bundle_media_type_version(media_type: &str) -> Option<&str> {
+    const PREFIX: &str = "application/vnd.dev.sigstore.bundle";
+
+    let rest = media_type.strip_prefix(PREFIX)?;
+    let version = if let Some(rest) = rest.strip_prefix(".v") {
+        rest.strip_suffix("+json")?
+    } else {
+        rest.strip_prefix("+json;version=")?
+    };
+
+    let well_formed = !version.is_empty()
+        && version.split('.').all(|part| !part.is_empty() && part.bytes().all(|b| b.is_ascii_digit()));
+    well_formed.then_some(version)
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct SigstoreBundle {
     pub media_type: String,
     pub verification_material: VerificationMaterial,
-    pub dsse_envelope: DsseEnvelope,
+    #[serde(flatten)]
+    pub content: BundleContent,
+}
+
+impl SigstoreBundle {
+    /// The DSSE envelope, if this bundle signs an attestation
+    pub fn dsse_envelope(&self) -> Option<&DsseEnvelope> {
+        match &self.content {
+            BundleContent::DsseEnvelope(envelope) => Some(envelope),
+            BundleContent::MessageSignature(_) => None,
+        }
+    }
+
+    /// The message signature, if this bundle signs a blob
+    pub fn message_signature(&self) -> Option<&MessageSignature> {
+        match &self.content {
+            BundleContent::DsseEnvelope(_) => None,
+            BundleContent::MessageSignature(signature) => Some(signature),
+        }
+    }
+
+    /// Base64 signature that timestamps and transparency log entries are made over
+    ///
+    /// The first DSSE signature, or the message signature; `None` for an envelope
+    /// without signatures.
+    pub fn signature(&self) -> Option<&str> {
+        match &self.content {
+            BundleContent::DsseEnvelope(envelope) => envelope.signatures.first().map(|s| s.sig.as_str()),
+            BundleContent::MessageSignature(signature) => Some(&signature.signature),
+        }
+    }
+
+    /// Number of signatures carried by the content
+    pub fn signature_count(&self) -> usize {
+        match &self.content {
+            BundleContent::DsseEnvelope(envelope) => envelope.signatures.len(),
+            BundleContent::MessageSignature(_) => 1,
+        }
+    }
+}
+
+/// What a bundle signs, serialized as its `dsseEnvelope` or `messageSignature` field
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BundleContent {
+    /// In-toto attestation in a DSSE envelope (`cosign attest`, GitHub attestations)
+    DsseEnvelope(DsseEnvelope),
+    /// Signature over an artifact (`cosign sign-blob`)
+    MessageSignature(MessageSignature),
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -37,21 +117,31 @@ pub struct Certificate {
     pub raw_bytes: String, // Base64-encoded DER certificate
 }
 
+// Protobuf JSON parsers also accept the proto field names, which some writers
+// of v0.1 and v0.2 bundles emitted for tlog entries, so both spellings are read
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct TransparencyLogEntry {
+    #[serde(alias = "log_index")]
     pub log_index: Option<String>,
+    #[serde(alias = "log_id")]
     pub log_id: Option<LogId>,
+    #[serde(alias = "kind_version")]
     pub kind_version: Option<KindVersion>,
+    #[serde(alias = "integrated_time")]
     pub integrated_time: String,
+    #[serde(alias = "inclusion_promise")]
     pub inclusion_promise: Option<InclusionPromise>,
+    #[serde(alias = "inclusion_proof")]
     pub inclusion_proof: Option<InclusionProof>,
+    #[serde(alias = "canonicalized_body")]
     pub canonicalized_body: String, // Base64-encoded
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct LogId {
+    #[serde(alias = "key_id")]
     pub key_id: String, // Base64-encoded
 }
 
@@ -65,14 +155,18 @@ pub struct KindVersion {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct InclusionPromise {
+    #[serde(alias = "signed_entry_timestamp")]
     pub signed_entry_timestamp: String, // Base64-encoded
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct InclusionProof {
+    #[serde(alias = "log_index")]
     pub log_index: String,
+    #[serde(alias = "root_hash")]
     pub root_hash: String,   // Base64-encoded
+    #[serde(alias = "tree_size")]
     pub tree_size: String,
     pub hashes: Vec<String>, // Base64-encoded
     pub checkpoint: Option<Checkpoint>,
@@ -87,7 +181,9 @@ pub struct Checkpoint {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct DsseEnvelope {
-    pub payload: String,      // Base64-encoded
+    // Base64-encoded; empty (or absent) when the statement is shipped detached
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub payload: String,
     pub payload_type: String,
     pub signatures: Vec<Signature>,
 }
@@ -95,4 +191,21 @@ pub struct DsseEnvelope {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Signature {
     pub sig: String, // Base64-encoded
+    // Optional hint naming the signing key (see `verifier::signature::key_fingerprint`)
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub keyid: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MessageSignature {
+    pub message_digest: MessageDigest,
+    pub signature: String, // Base64-encoded
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MessageDigest {
+    pub algorithm: String, // e.g. "SHA2_256"
+    pub digest: String,    // Base64-encoded
 }
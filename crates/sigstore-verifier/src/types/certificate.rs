@@ -1,7 +1,12 @@
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
 use crate::parser::bundle::{decode_base64, parse_bundle_from_str};
 use crate::parser::certificate::{determine_fulcio_instance, parse_der_certificate};
 use serde::{Deserialize, Serialize};
 
+pub use sigstore_journal::{IdentityKind, OidcIdentity};
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CertificateChain {
     pub leaf: Vec<u8>,               // DER-encoded
@@ -19,6 +24,20 @@ pub struct CertChain {
     pub certificates: Vec<String>, // PEM-encoded certificates
 }
 
+/// A signed certificate timestamp embedded in a certificate (RFC 6962, section 3.3)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EmbeddedSct {
+    pub version: u8,
+    /// SHA256 of the log's public key
+    pub log_id: [u8; 32],
+    /// Milliseconds since the Unix epoch
+    pub timestamp: u64,
+    pub extensions: Vec<u8>,
+    pub hash_algorithm: u8,
+    pub signature_algorithm: u8,
+    pub signature: Vec<u8>,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum FulcioInstance {
     GitHub,
@@ -60,11 +79,12 @@ impl FulcioInstance {
     /// # #[cfg(feature = "fetcher")]
     /// # fn example() -> Result<(), Box<dyn std::error::Error>> {
     /// use sigstore_verifier::types::certificate::FulcioInstance;
+    /// use sigstore_verifier::fetcher::http::default_client;
     /// use sigstore_verifier::fetcher::trust_bundle::fetch_fulcio_trust_bundle;
     ///
     /// let bundle_json = std::fs::read_to_string("bundle.sigstore.json")?;
     /// let instance = FulcioInstance::from_bundle_json(&bundle_json)?;
-    /// let trust_bundle = fetch_fulcio_trust_bundle(&instance)?;
+    /// let trust_bundle = fetch_fulcio_trust_bundle(&*default_client()?, &instance)?;
     /// # Ok(())
     /// # }
     /// ```
@@ -83,11 +103,3 @@ impl FulcioInstance {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-pub struct OidcIdentity {
-    pub issuer: Option<String>,
-    pub subject: Option<String>,
-    pub workflow_ref: Option<String>,
-    pub repository: Option<String>,
-    pub event_name: Option<String>,
-}
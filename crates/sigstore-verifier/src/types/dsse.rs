@@ -1,5 +1,8 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use crate::types::purl::PackageUrl;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use alloc::collections::BTreeMap;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Statement {
@@ -14,7 +17,8 @@ pub struct Statement {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Subject {
     pub name: String,
-    pub digest: HashMap<String, String>,
+    /// Ordered map so re-serialization and iteration never depend on a random hasher seed
+    pub digest: BTreeMap<String, String>,
 }
 
 impl Statement {
@@ -23,4 +27,47 @@ impl Statement {
             .first()
             .and_then(|s| s.digest.get(algorithm).cloned())
     }
+
+    /// Iterate over the subjects whose name matches `pattern`
+    ///
+    /// The pattern is either an exact name or a glob where `*` matches any
+    /// sequence of characters and `?` matches a single character. A cargo or
+    /// Maven package URL also matches the subject named by the package's file
+    /// (see [`PackageUrl::matches_subject`]).
+    pub fn find_subjects<'a>(&'a self, pattern: &'a str) -> impl Iterator<Item = &'a Subject> {
+        let purl = PackageUrl::parse(pattern);
+        self.subject.iter().filter(move |s| match purl {
+            Some(ref purl) => purl.matches_subject(&s.name),
+            None => subject_name_matches(pattern, &s.name),
+        })
+    }
+}
+
+/// Match a subject name against an exact name or a `*`/`?` glob pattern
+pub fn subject_name_matches(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+
+    let (mut p, mut n) = (0, 0);
+    // Position of the last `*` in the pattern and the name index it was tried at
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while n < name.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == name[n]) {
+            p += 1;
+            n += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            backtrack = Some((p, n));
+            p += 1;
+        } else if let Some((star, matched)) = backtrack {
+            // Let the last `*` absorb one more character and retry
+            p = star + 1;
+            n = matched + 1;
+            backtrack = Some((star, n));
+        } else {
+            return false;
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == '*')
 }
@@ -0,0 +1,32 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use serde::{Deserialize, Serialize};
+
+/// Kind of a signed git object
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GitObjectKind {
+    Commit,
+    Tag,
+}
+
+impl GitObjectKind {
+    /// Type name in the object header hashed into the object ID
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GitObjectKind::Commit => "commit",
+            GitObjectKind::Tag => "tag",
+        }
+    }
+}
+
+/// A signed commit or annotated tag, split into what was signed and what names it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GitObject {
+    pub kind: GitObjectKind,
+    /// Object ID: SHA-1 of the object header and the raw object, signature included
+    pub id: [u8; 20],
+    /// Tag name, empty for commits
+    pub name: String,
+    /// The object without its signature, i.e. the bytes `gitsign` signed
+    pub signed_payload: Vec<u8>,
+}
@@ -1,4 +1,7 @@
 pub mod bundle;
 pub mod certificate;
 pub mod dsse;
+pub mod git;
+pub mod purl;
 pub mod result;
+pub mod subject;
@@ -0,0 +1,336 @@
+//! Package URLs for Rust crates and Maven artifacts
+//!
+//! Provenance for a published package names its subject either by package URL
+//! or by the file name of the published artifact: `<name>-<version>.crate` for
+//! crates (`pkg:cargo/<name>@<version>`), and
+//! `<artifactId>-<version>[-<classifier>].<type>` for Maven artifacts
+//! (`pkg:maven/<groupId>/<artifactId>@<version>`). A purl used as the expected
+//! subject name matches both forms.
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+/// Package URL of a supported ecosystem
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PackageUrl {
+    Cargo(CargoPurl),
+    Maven(MavenPurl),
+}
+
+impl PackageUrl {
+    /// Parse a `pkg:cargo/` or `pkg:maven/` package URL
+    pub fn parse(purl: &str) -> Option<Self> {
+        CargoPurl::parse(purl)
+            .map(PackageUrl::Cargo)
+            .or_else(|| MavenPurl::parse(purl).map(PackageUrl::Maven))
+    }
+
+    /// Whether an attestation subject name refers to this package version
+    pub fn matches_subject(&self, subject_name: &str) -> bool {
+        match self {
+            PackageUrl::Cargo(purl) => purl.matches_subject(subject_name),
+            PackageUrl::Maven(purl) => purl.matches_subject(subject_name),
+        }
+    }
+}
+
+/// Crate name and version parsed from a `pkg:cargo/<name>@<version>` package URL
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CargoPurl {
+    pub name: String,
+    pub version: String,
+}
+
+impl CargoPurl {
+    /// Parse a cargo package URL
+    ///
+    /// Qualifiers (`?...`) and subpaths (`#...`) are ignored.
+    ///
+    /// # Returns
+    ///
+    /// `None` if `purl` is not a cargo purl with both a name and a version
+    pub fn parse(purl: &str) -> Option<Self> {
+        let rest = purl.strip_prefix("pkg:cargo/")?;
+        let rest = rest.split(['?', '#']).next().unwrap_or_default();
+        let (name, version) = rest.split_once('@')?;
+        let valid_name = !name.is_empty()
+            && name
+                .bytes()
+                .all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_');
+        if !valid_name || version.is_empty() || version.contains('/') {
+            return None;
+        }
+
+        Some(Self {
+            name: name.to_string(),
+            version: version.to_string(),
+        })
+    }
+
+    /// File name of the crate archive, as served by crates.io
+    pub fn crate_file_name(&self) -> String {
+        format!("{}-{}.crate", self.name, self.version)
+    }
+
+    /// Whether an attestation subject name refers to this crate version
+    ///
+    /// Accepts the purl itself or the `.crate` file name. Crate names are compared
+    /// the way crates.io does: case-insensitively, with `-` and `_` equivalent.
+    pub fn matches_subject(&self, subject_name: &str) -> bool {
+        if let Some(other) = CargoPurl::parse(subject_name) {
+            return normalize_crate_name(&other.name) == normalize_crate_name(&self.name)
+                && other.version == self.version;
+        }
+
+        match subject_name.strip_suffix(".crate") {
+            Some(stem) => {
+                let expected = format!("{}-{}", self.name, self.version);
+                normalize_crate_name(stem) == normalize_crate_name(&expected)
+            }
+            None => false,
+        }
+    }
+}
+
+impl core::fmt::Display for CargoPurl {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "pkg:cargo/{}@{}", self.name, self.version)
+    }
+}
+
+/// Maven artifact coordinates parsed from a `pkg:maven/` purl or a GAV string
+///
+/// - group_id: Group ID (e.g. `org.apache.commons`)
+/// - artifact_id: Artifact ID (e.g. `commons-lang3`)
+/// - version: Version
+/// - classifier: Optional classifier (e.g. `sources`)
+/// - extension: File extension / packaging type, `jar` unless specified
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MavenPurl {
+    pub group_id: String,
+    pub artifact_id: String,
+    pub version: String,
+    pub classifier: Option<String>,
+    pub extension: String,
+}
+
+impl MavenPurl {
+    /// Parse `pkg:maven/<groupId>/<artifactId>@<version>[?classifier=...&type=...]`
+    ///
+    /// # Returns
+    ///
+    /// `None` if `purl` is not a Maven purl with group, artifact and version
+    pub fn parse(purl: &str) -> Option<Self> {
+        let rest = purl.strip_prefix("pkg:maven/")?;
+        let rest = rest.split('#').next().unwrap_or_default();
+        let (coordinates, qualifiers) = rest.split_once('?').unwrap_or((rest, ""));
+        let (path, version) = coordinates.split_once('@')?;
+        let (group_id, artifact_id) = path.split_once('/')?;
+
+        let mut classifier = None;
+        let mut extension = None;
+        for qualifier in qualifiers.split('&').filter(|q| !q.is_empty()) {
+            match qualifier.split_once('=') {
+                Some(("classifier", value)) => classifier = Some(value.to_string()),
+                Some(("type", value)) => extension = Some(value.to_string()),
+                _ => {}
+            }
+        }
+
+        Self::new(group_id, artifact_id, version, classifier, extension)
+    }
+
+    /// Parse Gradle-style coordinates: `group:artifact:version[:classifier][@extension]`
+    ///
+    /// # Returns
+    ///
+    /// `None` unless group, artifact and version are all present
+    pub fn from_gav(gav: &str) -> Option<Self> {
+        let (coordinates, extension) = match gav.split_once('@') {
+            Some((coordinates, extension)) => (coordinates, Some(extension.to_string())),
+            None => (gav, None),
+        };
+        let parts: Vec<&str> = coordinates.split(':').collect();
+        let (group_id, artifact_id, version, classifier) = match parts.as_slice() {
+            [group_id, artifact_id, version] => (*group_id, *artifact_id, *version, None),
+            [group_id, artifact_id, version, classifier] => {
+                (*group_id, *artifact_id, *version, Some(classifier.to_string()))
+            }
+            _ => return None,
+        };
+
+        Self::new(group_id, artifact_id, version, classifier, extension)
+    }
+
+    fn new(
+        group_id: &str,
+        artifact_id: &str,
+        version: &str,
+        classifier: Option<String>,
+        extension: Option<String>,
+    ) -> Option<Self> {
+        let valid = |part: &str| {
+            !part.is_empty() && !part.contains(['/', ':', '@', '?', '#']) && part != ".."
+        };
+        if !valid(group_id) || !valid(artifact_id) || !valid(version) {
+            return None;
+        }
+        let classifier = classifier.filter(|c| !c.is_empty());
+        let extension = extension.filter(|e| !e.is_empty()).unwrap_or_else(|| "jar".to_string());
+        if !classifier.as_deref().is_none_or(valid) || !valid(&extension) {
+            return None;
+        }
+
+        Some(Self {
+            group_id: group_id.to_string(),
+            artifact_id: artifact_id.to_string(),
+            version: version.to_string(),
+            classifier,
+            extension,
+        })
+    }
+
+    /// File name of the artifact in a Maven repository
+    pub fn file_name(&self) -> String {
+        match self.classifier {
+            Some(ref classifier) => format!(
+                "{}-{}-{}.{}",
+                self.artifact_id, self.version, classifier, self.extension
+            ),
+            None => format!("{}-{}.{}", self.artifact_id, self.version, self.extension),
+        }
+    }
+
+    /// Path of the artifact relative to the repository root
+    /// (`org/apache/commons/commons-lang3/3.14.0/commons-lang3-3.14.0.jar`)
+    pub fn repository_path(&self) -> String {
+        format!(
+            "{}/{}/{}/{}",
+            self.group_id.replace('.', "/"),
+            self.artifact_id,
+            self.version,
+            self.file_name()
+        )
+    }
+
+    /// Coordinates in the `group:artifact:version[:classifier][@extension]` form
+    pub fn gav(&self) -> String {
+        let mut gav = format!("{}:{}:{}", self.group_id, self.artifact_id, self.version);
+        if let Some(ref classifier) = self.classifier {
+            gav.push(':');
+            gav.push_str(classifier);
+        }
+        if self.extension != "jar" {
+            gav.push('@');
+            gav.push_str(&self.extension);
+        }
+        gav
+    }
+
+    /// Whether an attestation subject name refers to this artifact
+    ///
+    /// Accepts the purl (with the same classifier and type) or the artifact's file
+    /// name. Maven coordinates are case-sensitive.
+    pub fn matches_subject(&self, subject_name: &str) -> bool {
+        match MavenPurl::parse(subject_name) {
+            Some(other) => &other == self,
+            None => subject_name == self.file_name(),
+        }
+    }
+}
+
+impl core::fmt::Display for MavenPurl {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "pkg:maven/{}/{}@{}", self.group_id, self.artifact_id, self.version)?;
+        // Qualifiers in lexicographic order, as the purl spec requires
+        let mut separator = '?';
+        if let Some(ref classifier) = self.classifier {
+            write!(f, "{}classifier={}", separator, classifier)?;
+            separator = '&';
+        }
+        if self.extension != "jar" {
+            write!(f, "{}type={}", separator, self.extension)?;
+        }
+        Ok(())
+    }
+}
+
+fn normalize_crate_name(name: &str) -> String {
+    name.to_ascii_lowercase().replace('_', "-")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_cargo_purl() {
+        let purl = CargoPurl::parse("pkg:cargo/serde_json@1.0.128").unwrap();
+        assert_eq!(purl.name, "serde_json");
+        assert_eq!(purl.version, "1.0.128");
+        assert_eq!(purl.to_string(), "pkg:cargo/serde_json@1.0.128");
+        assert_eq!(purl.crate_file_name(), "serde_json-1.0.128.crate");
+
+        assert_eq!(
+            CargoPurl::parse("pkg:cargo/rand@0.8.5?repository_url=https://crates.io"),
+            Some(CargoPurl { name: "rand".to_string(), version: "0.8.5".to_string() })
+        );
+        assert!(CargoPurl::parse("pkg:npm/left-pad@1.3.0").is_none());
+        assert!(CargoPurl::parse("pkg:cargo/serde").is_none());
+        assert!(CargoPurl::parse("pkg:cargo/@1.0.0").is_none());
+    }
+
+    #[test]
+    fn test_cargo_purl_matches_subject() {
+        let purl = CargoPurl::parse("pkg:cargo/serde_json@1.0.128").unwrap();
+
+        assert!(purl.matches_subject("pkg:cargo/serde_json@1.0.128"));
+        assert!(purl.matches_subject("pkg:cargo/serde-json@1.0.128"));
+        assert!(purl.matches_subject("serde_json-1.0.128.crate"));
+        assert!(purl.matches_subject("Serde-JSON-1.0.128.crate"));
+
+        assert!(!purl.matches_subject("pkg:cargo/serde_json@1.0.127"));
+        assert!(!purl.matches_subject("serde_json-1.0.1280.crate"));
+        assert!(!purl.matches_subject("serde_json-1.0.128.tar.gz"));
+    }
+
+    #[test]
+    fn test_parse_maven_purl_and_gav() {
+        let purl = MavenPurl::parse("pkg:maven/org.apache.commons/commons-lang3@3.14.0").unwrap();
+        assert_eq!(purl.group_id, "org.apache.commons");
+        assert_eq!(purl.artifact_id, "commons-lang3");
+        assert_eq!(purl.extension, "jar");
+        assert_eq!(
+            purl.repository_path(),
+            "org/apache/commons/commons-lang3/3.14.0/commons-lang3-3.14.0.jar"
+        );
+        assert_eq!(MavenPurl::from_gav("org.apache.commons:commons-lang3:3.14.0"), Some(purl));
+
+        let sources = MavenPurl::from_gav("com.example:app:1.2.0:sources@zip").unwrap();
+        assert_eq!(sources.file_name(), "app-1.2.0-sources.zip");
+        assert_eq!(sources.gav(), "com.example:app:1.2.0:sources@zip");
+        assert_eq!(sources.to_string(), "pkg:maven/com.example/app@1.2.0?classifier=sources&type=zip");
+        assert_eq!(MavenPurl::parse(&sources.to_string()), Some(sources));
+
+        assert!(MavenPurl::from_gav("com.example:app").is_none());
+        assert!(MavenPurl::from_gav("com.example:app:1.0:a:b").is_none());
+        assert!(MavenPurl::parse("pkg:maven/app@1.0").is_none());
+    }
+
+    #[test]
+    fn test_maven_purl_matches_subject() {
+        let purl = MavenPurl::from_gav("com.example:app:1.2.0").unwrap();
+
+        assert!(purl.matches_subject("pkg:maven/com.example/app@1.2.0"));
+        assert!(purl.matches_subject("pkg:maven/com.example/app@1.2.0?type=jar"));
+        assert!(purl.matches_subject("app-1.2.0.jar"));
+
+        assert!(!purl.matches_subject("pkg:maven/com.example/app@1.2.0?classifier=sources"));
+        assert!(!purl.matches_subject("app-1.2.0-sources.jar"));
+        assert!(!purl.matches_subject("App-1.2.0.jar"));
+
+        let package = PackageUrl::parse("pkg:maven/com.example/app@1.2.0").unwrap();
+        assert!(package.matches_subject("app-1.2.0.jar"));
+        assert!(matches!(PackageUrl::parse("pkg:cargo/app@1.2.0"), Some(PackageUrl::Cargo(_))));
+    }
+}
@@ -1,662 +1,789 @@
-use chrono::{DateTime, Utc};
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
 use serde::{Deserialize, Serialize};
-use super::certificate::OidcIdentity;
-use alloy_sol_types::{sol, SolValue};
-
-// =============================================================================
-// Solidity ABI Encoding Format
-// =============================================================================
-//
-// The serialized VerificationResult has the following binary format:
-//
-// ┌─────────────────────────────────────────────────────────────────────────────┐
-// │ [8 bytes]  signing_time          - uint64 big-endian Unix timestamp         │
-// │ [1 byte]   timestamp_proof_type  - 0=None, 1=RFC3161, 2=Rekor               │
-// │ [N bytes]  ABI-encoded VerificationResultEncoded struct                     │
-// └─────────────────────────────────────────────────────────────────────────────┘
-//
-// Field descriptions:
-//
-// - certificateHashes: SHA256 hashes of the signing certificate chain
-//   Format: [leaf_hash, ...intermediate_hashes, root_hash]
-//
-// - subjectDigest: The artifact digest from the attestation (typically SHA256)
-//
-// - subjectDigestAlgorithm: Hash algorithm for subjectDigest
-//   0 = Unknown, 1 = SHA256, 2 = SHA384
-//
-// - oidcIssuer: OIDC token issuer (e.g., "https://token.actions.githubusercontent.com")
-//
-// - oidcSubject: OIDC subject claim
-//
-// - oidcWorkflowRef: GitHub workflow reference (GitHub Actions specific)
-//
-// - oidcRepository: Source repository (GitHub Actions specific)
-//
-// - oidcEventName: Trigger event name (GitHub Actions specific)
-//
-// - tsaChainHashes: For RFC 3161 timestamps, SHA256 hashes of TSA certificate chain
-//   Format: [leaf_hash, ...intermediate_hashes, root_hash]. Empty for Rekor.
-//
-// - messageImprintAlgorithm: For RFC 3161, the hash algorithm used in the timestamp
-//   0 = Unknown, 1 = SHA256, 2 = SHA384. Set to 0 for Rekor.
-//
-// - messageImprint: For RFC 3161, the hash of the DSSE signature that was timestamped.
-//   This proves the timestamp was generated for this specific signature. Empty for Rekor.
-//
-// - rekorLogId: For Rekor, the SHA256 hash of Rekor's public key (identifies the log instance).
-//   Zero bytes for RFC 3161.
-//
-// - rekorLogIndex: For Rekor, the tree leaf index (for Merkle proof verification).
-//   Set to 0 for RFC 3161.
-//
-// - rekorEntryIndex: For Rekor, the entry index (for API queries to fetch the full entry).
-//   Set to 0 for RFC 3161.
-//
-// =============================================================================
-
-sol! {
-    #[derive(Debug, PartialEq)]
-    struct VerificationResultEncoded {
-        bytes32[] certificateHashes;
-        bytes subjectDigest;
-        uint8 subjectDigestAlgorithm;
-        string oidcIssuer;
-        string oidcSubject;
-        string oidcWorkflowRef;
-        string oidcRepository;
-        string oidcEventName;
-        bytes32[] tsaChainHashes;
-        uint8 messageImprintAlgorithm;
-        bytes messageImprint;
-        bytes32 rekorLogId;
-        uint64 rekorLogIndex;
-        uint64 rekorEntryIndex;
-    }
+use alloy_sol_types::SolValue;
+use crate::crypto::hash::sha256;
+use crate::crypto::signature::SignatureEncoding;
+use crate::verifier::identity::normalize_issuer;
+use crate::verifier::policy::IdentityPolicy;
+use crate::verifier::repository_policy::RepositoryPolicy;
+
+// The journal types live in the dependency-light `sigstore-journal` crate so
+// consumers that only decode journals do not need the verifier.
+pub use sigstore_journal::{
+    annotations_hash, custody, flags, result_hash, BlockAnchor, CertificateChainHashes, CertificateTimestamp,
+    subject_name_hash, DigestAlgorithm, SubjectKind, TimestampProof, TimestampProofType, VerificationOptionsEncoded,
+    VerificationResult, VerificationResultEncoded,
+};
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct VerificationOptions {
+    /// Optional expected digest to verify against the subject digest in the attestation
+    pub expected_digest: Option<Vec<u8>>,
+
+    /// Optional expected OIDC issuer (e.g., "https://token.actions.githubusercontent.com")
+    pub expected_issuer: Option<String>,
+
+    /// Optional expected OIDC subject (e.g., "repo:owner/repo:ref:refs/heads/main")
+    pub expected_subject: Option<String>,
+
+    /// Optional subject name (exact, `*`/`?` glob, or `pkg:cargo/` / `pkg:maven/` purl) selecting which attested artifact to verify
+    pub expected_subject_name: Option<String>,
+
+    /// Optional expected signer email (e.g., "alice@example.com"), matched case-insensitively
+    ///
+    /// Requires an email SAN, i.e. a bundle signed by a human through an OAuth provider.
+    pub expected_email: Option<String>,
+
+    /// Issuers accepted for email identities (e.g., "https://accounts.google.com")
+    ///
+    /// Empty accepts any issuer. Identities of other kinds are not affected.
+    #[serde(default)]
+    pub allowed_email_issuers: Vec<String>,
+
+    /// Optional SPIFFE trust domain (e.g., "prod.example.org") the workload identity must belong to
+    pub expected_spiffe_trust_domain: Option<String>,
+
+    /// Optional SPIFFE ID path prefix (e.g., "/ns/release"), matched on whole path segments
+    pub expected_spiffe_path_prefix: Option<String>,
+
+    /// Optional policy the signer identity must satisfy (see `verifier::policy`)
+    ///
+    /// Checked in addition to the `expected_*` options, after them. Committed in the
+    /// options hash as the SHA256 of its JSON encoding.
+    #[serde(default)]
+    pub identity_policy: Option<IdentityPolicy>,
+
+    /// Optional expected source repository, as recorded in the certificate (e.g., "https://github.com/owner/repo")
+    #[serde(default)]
+    pub expected_repository: Option<String>,
+
+    /// Optional expected source ref, as recorded in the certificate (e.g., "refs/tags/v1.0.0")
+    #[serde(default)]
+    pub expected_workflow_ref: Option<String>,
+
+    /// Optional expected event that triggered the workflow (e.g., "push")
+    #[serde(default)]
+    pub expected_event_name: Option<String>,
+
+    /// Optional expected in-toto predicate type (e.g., "https://slsa.dev/provenance/v1")
+    ///
+    /// Requires a DSSE envelope: message signatures have no statement.
+    #[serde(default)]
+    pub expected_predicate_type: Option<String>,
+
+    /// Maximum age of the signature, in seconds, at `verification_time`
+    ///
+    /// Requires `verification_time`: the verifier never reads the clock, so the
+    /// caller fixes the time the age is measured at, and both are committed in the
+    /// options hash.
+    #[serde(default)]
+    pub max_signing_age: Option<u64>,
+
+    /// Unix time (seconds) `max_signing_age` is measured at; signatures made after it are rejected
+    #[serde(default)]
+    pub verification_time: Option<u64>,
+
+    /// Verification profile to apply, as `name` or `name@version` (see `verifier::profiles`)
+    ///
+    /// The profile's constraints are added to these options before verification,
+    /// and the options hash commits the result with the profile's `name@version`.
+    #[serde(default)]
+    pub profile: Option<String>,
+
+    /// Policy of the bundle's repository, picked by the caller from its repository
+    /// policies (see `verifier::repository_policy`)
+    ///
+    /// Its constraints are added to these options before verification, and the
+    /// options hash commits the entry as the SHA256 of its JSON encoding.
+    #[serde(default)]
+    pub repository_policy: Option<RepositoryPolicy>,
+
+    /// Timestamp mechanism the bundle must use; either is accepted if unset
+    #[serde(default)]
+    pub required_timestamp: Option<TimestampKind>,
+
+    /// TSA policy OID (e.g. "1.3.6.1.4.1.57264.2") the RFC 3161 timestamp must be issued under
+    ///
+    /// Requires an RFC 3161 timestamp: bundles timestamped by Rekor are rejected.
+    #[serde(default)]
+    pub expected_tsa_policy: Option<String>,
+
+    /// Seconds a signature may postdate `verification_time`, to tolerate clock drift
+    /// between the signer and the caller
+    #[serde(default)]
+    pub clock_skew: u64,
+
+    /// Compare issuers as normalized URLs (see `verifier::identity::normalize_issuer`)
+    ///
+    /// Applies to the expected issuer and the email issuer allow-list, which are
+    /// committed in the options hash in normalized form. The mode is committed in
+    /// the journal flags.
+    #[serde(default)]
+    pub normalize_issuer: bool,
+
+    /// Compare the expected subject ignoring ASCII case
+    ///
+    /// The expected subject is then committed in the options hash in lowercase.
+    /// The mode is committed in the journal flags.
+    #[serde(default)]
+    pub case_insensitive_subject: bool,
+
+    /// Archival verification: evaluate the validity of every certificate strictly at the signing time
+    ///
+    /// Years after signing, the Fulcio and TSA chains have expired although the
+    /// signature was valid when made. By default only the leaf certificate is checked
+    /// against the signing time; in archival mode the intermediates, the root and the
+    /// TSA chain are too, so the result holds independently of when it is produced.
+    /// The wall-clock time is never consulted. Not part of the options hash: the mode
+    /// is committed in the journal flags instead.
+    #[serde(default)]
+    pub archival: bool,
+
+    /// Verify every signature of a DSSE envelope instead of only the first
+    ///
+    /// Each signature must verify with the leaf certificate key, and a signature
+    /// with a keyid must name that key (see `verifier::signature::key_fingerprint`).
+    /// Not part of the options hash: the mode is committed in the journal flags instead.
+    #[serde(default)]
+    pub verify_all_signatures: bool,
+
+    /// Quarantine mode: accept a bundle that verifies but violates the policy
+    ///
+    /// The bundle is then verified without the policy (see [`Self::without_policy`])
+    /// and the result carries the `POLICY_VIOLATION` journal flag, with the options
+    /// hash still committing the policy that was violated. Bundles that fail
+    /// verification for any other reason are rejected as usual. Not part of the
+    /// options hash: the outcome is committed in the journal flags instead.
+    #[serde(default)]
+    pub quarantine: bool,
+
+    /// Trusted Rekor public keys (DER SubjectPublicKeyInfo) for signed entry timestamps
+    ///
+    /// The signed entry timestamp (inclusion promise) of a Rekor entry must verify
+    /// with the key whose SHA256 is the entry's log ID, and its checkpoint with one of
+    /// the keys. A Rekor-timestamped bundle with either fails to verify when this is
+    /// empty. Not part of the options hash: the key used is committed as the `log_id`
+    /// of the timestamp proof.
+    #[serde(default)]
+    pub rekor_keys: Vec<Vec<u8>>,
+
+    /// Trusted certificate transparency log public keys (DER SubjectPublicKeyInfo)
+    ///
+    /// When set, the leaf certificate must embed a signed certificate timestamp (SCT)
+    /// that verifies with the key whose SHA256 is its log ID. When empty, SCTs are not
+    /// checked. Not part of the options hash: the verified SCTs are committed in the
+    /// journal as `certificate_timestamps`.
+    #[serde(default)]
+    pub ctlog_keys: Vec<Vec<u8>>,
+
+    /// Trusted TSA root certificates (DER), e.g. of the trusted root's timestamp authorities
+    ///
+    /// A complete certificate chain embedded in an RFC 3161 timestamp is used if it
+    /// roots in one of these, or in the root of the TSA chain given to the verifier;
+    /// no TSA chain needs to be given then. Not part of the options hash: the root
+    /// used is committed in the timestamp proof's `tsa_chain_hashes`.
+    #[serde(default)]
+    pub tsa_roots: Vec<Vec<u8>>,
+
+    /// Bundle format versions accepted (e.g. `["0.3"]`)
+    ///
+    /// Empty accepts every version in `SUPPORTED_BUNDLE_VERSIONS`; bundles of other
+    /// versions are rejected by the parser regardless, so this can only narrow the
+    /// set. Not part of the options hash: it only decides which bundles are rejected.
+    #[serde(default)]
+    pub allowed_bundle_versions: Vec<String>,
+
+    /// Statement bytes for a DSSE envelope shipped without its payload
+    ///
+    /// The PAE is computed over these bytes, so the signature binds them. Rejected
+    /// if the envelope embeds a different payload. Not part of the options hash:
+    /// it is input data, and the subject it attests is committed in the journal.
+    ///
+    /// For a message signature bundle, the signed blob instead: it must have the
+    /// recorded message digest, and is required for Ed25519 signatures.
+    #[serde(default)]
+    pub detached_payload: Option<Vec<u8>>,
+
+    /// Encoding of the DSSE envelope signature (DER, raw `r || s`, or auto-detect)
+    ///
+    /// Not part of the options hash: it only selects how the same signature is decoded.
+    #[serde(default)]
+    pub signature_encoding: SignatureEncoding,
+
+    /// Caps on the cryptographic work the bundle may cause
+    ///
+    /// Not part of the options hash: a bundle within the caps verifies exactly as without them.
+    #[serde(default)]
+    pub limits: VerificationLimits,
 }
 
-/// Hash algorithm identifier for Solidity encoding
+/// Caps on the cryptographic work performed for a single bundle
+///
+/// Bundle contents dictate how many certificates, proof nodes and signatures are
+/// processed. The caps are checked before any of them are verified, so they bound
+/// the cost of a proof independently of the bundle (see the crate README for the
+/// resulting worst case).
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
-#[repr(u8)]
-pub enum DigestAlgorithm {
-    Unknown = 0,
-    Sha256 = 1,
-    Sha384 = 2,
-}
+pub struct VerificationLimits {
+    /// Maximum certificates in a chain, leaf and root included (Fulcio and TSA chains)
+    pub max_chain_length: usize,
 
-impl DigestAlgorithm {
-    pub fn from_u8(value: u8) -> Self {
-        match value {
-            1 => DigestAlgorithm::Sha256,
-            2 => DigestAlgorithm::Sha384,
-            _ => DigestAlgorithm::Unknown,
-        }
-    }
+    /// Maximum sibling hashes in a transparency log inclusion proof
+    pub max_proof_depth: usize,
+
+    /// Maximum DSSE signatures, RFC 3161 timestamps and transparency log entries (each)
+    pub max_signatures: usize,
 }
 
-/// Timestamp proof type identifier
+/// Timestamp mechanism of a bundle, numbered as in [`TimestampProofType`]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[repr(u8)]
-pub enum TimestampProofType {
-    None = 0,
+pub enum TimestampKind {
+    /// RFC 3161 signed timestamp from a timestamp authority
     Rfc3161 = 1,
+    /// Integrated time of a Rekor transparency log entry
     Rekor = 2,
 }
 
-impl TimestampProofType {
-    pub fn from_u8(value: u8) -> Self {
-        match value {
-            1 => TimestampProofType::Rfc3161,
-            2 => TimestampProofType::Rekor,
-            _ => TimestampProofType::None,
+impl core::fmt::Display for TimestampKind {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            TimestampKind::Rfc3161 => write!(f, "RFC 3161"),
+            TimestampKind::Rekor => write!(f, "Rekor"),
         }
     }
 }
 
-/// Timestamp proof data - proves when the signature was created
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub enum TimestampProof {
-    /// No timestamp proof available
-    None,
-
-    /// RFC 3161 Timestamp Authority proof
-    Rfc3161 {
-        /// SHA256 hashes of TSA certificate chain [leaf, ...intermediates, root]
-        tsa_chain_hashes: CertificateChainHashes,
-        /// Hash algorithm used for the message imprint
-        message_imprint_algorithm: DigestAlgorithm,
-        /// The message imprint (hash of the DSSE signature)
-        message_imprint: Vec<u8>,
-    },
-
-    /// Sigstore Rekor transparency log proof
-    Rekor {
-        /// SHA256 of Rekor's public key (identifies the log instance)
-        log_id: [u8; 32],
-        /// Tree leaf index (for Merkle proof verification against checkpoint)
-        log_index: u64,
-        /// Entry index (for API queries to fetch the full entry)
-        entry_index: u64,
-    },
-}
-
-impl Default for TimestampProof {
+impl Default for VerificationLimits {
+    /// Generous for Sigstore public-good and GitHub bundles (3-certificate chains,
+    /// one signature, proofs well under 64 levels for any `u64` tree size)
     fn default() -> Self {
-        TimestampProof::None
+        Self {
+            max_chain_length: 5,
+            max_proof_depth: 64,
+            max_signatures: 4,
+        }
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct VerificationResult {
-    pub certificate_hashes: CertificateChainHashes,
-    pub signing_time: DateTime<Utc>,
-    pub subject_digest: Vec<u8>,
-    pub subject_digest_algorithm: DigestAlgorithm,
-    pub oidc_identity: Option<OidcIdentity>,
-    pub timestamp_proof: TimestampProof,
+impl VerificationOptions {
+    /// Start building options with every constraint unset
+    pub fn builder() -> VerificationOptionsBuilder {
+        VerificationOptionsBuilder::default()
+    }
+
+    /// Compute the commitment to these options that is embedded in the journal
+    ///
+    /// The fields of `VerificationOptionsEncoded` are ABI-encoded as a parameter
+    /// list (unset options become empty values) and hashed with SHA256, so a
+    /// contract can recompute `sha256(abi.encode(expectedDigest, expectedIssuer,
+    /// expectedSubject, expectedSubjectName, expectedEmail, allowedEmailIssuers,
+    /// expectedSpiffeTrustDomain, expectedSpiffePathPrefix, identityPolicyHash,
+    /// expectedRepository, expectedWorkflowRef, expectedEventName, expectedPredicateType,
+    /// maxSigningAge, verificationTime, profile, requiredTimestamp, clockSkew,
+    /// repositoryPolicyHash))` for the constraints it expects and compare it against
+    /// `optionsHash` in the journal, where `identityPolicyHash` is [`IdentityPolicy::hash`]
+    /// or zero without a policy, `repositoryPolicyHash` likewise [`RepositoryPolicy::hash`],
+    /// and `requiredTimestamp` is the [`TimestampKind`] or zero. The expected email is committed
+    /// in lowercase, since it is matched case-insensitively, and so is the expected subject
+    /// with `case_insensitive_subject`. With `normalize_issuer`, issuers are committed
+    /// normalized. With a `profile` or a `repository_policy`, the verifier commits the
+    /// options after applying it (see `VerificationProfile::apply`).
+    pub fn hash(&self) -> [u8; 32] {
+        sha256(&self.encode())
+    }
+
+    /// ABI encoding of these options that [`Self::hash`] hashes
+    ///
+    /// It is committed in the journal next to the hash, so a contract can decode
+    /// the constraints that were enforced (see `VerificationResult::decode_verification_options`).
+    pub fn encode(&self) -> Vec<u8> {
+        VerificationOptionsEncoded {
+            expectedDigest: self.expected_digest.clone().unwrap_or_default().into(),
+            expectedIssuer: self
+                .expected_issuer
+                .as_deref()
+                .map(|issuer| self.committed_issuer(issuer))
+                .unwrap_or_default(),
+            expectedSubject: self
+                .expected_subject
+                .as_deref()
+                .map(|subject| {
+                    if self.case_insensitive_subject {
+                        subject.to_ascii_lowercase()
+                    } else {
+                        subject.to_string()
+                    }
+                })
+                .unwrap_or_default(),
+            expectedSubjectName: self.expected_subject_name.clone().unwrap_or_default(),
+            expectedEmail: self
+                .expected_email
+                .as_deref()
+                .map(str::to_lowercase)
+                .unwrap_or_default(),
+            allowedEmailIssuers: self
+                .allowed_email_issuers
+                .iter()
+                .map(|issuer| self.committed_issuer(issuer))
+                .collect(),
+            expectedSpiffeTrustDomain: self.expected_spiffe_trust_domain.clone().unwrap_or_default(),
+            expectedSpiffePathPrefix: self.expected_spiffe_path_prefix.clone().unwrap_or_default(),
+            identityPolicyHash: self.identity_policy.as_ref().map(IdentityPolicy::hash).unwrap_or_default().into(),
+            expectedRepository: self.expected_repository.clone().unwrap_or_default(),
+            expectedWorkflowRef: self.expected_workflow_ref.clone().unwrap_or_default(),
+            expectedEventName: self.expected_event_name.clone().unwrap_or_default(),
+            expectedPredicateType: self.expected_predicate_type.clone().unwrap_or_default(),
+            maxSigningAge: self.max_signing_age.unwrap_or_default(),
+            verificationTime: self.verification_time.unwrap_or_default(),
+            profile: self.profile.clone().unwrap_or_default(),
+            requiredTimestamp: self.required_timestamp.map_or(0, |kind| kind as u8),
+            clockSkew: self.clock_skew,
+            repositoryPolicyHash: self
+                .repository_policy
+                .as_ref()
+                .map(RepositoryPolicy::hash)
+                .unwrap_or_default()
+                .into(),
+            expectedTsaPolicy: self.expected_tsa_policy.clone().unwrap_or_default(),
+        }
+        .abi_encode_params()
+    }
+
+    /// These options without the policy: every constraint on what was signed, by
+    /// whom and when
+    ///
+    /// Clears the expected digest, identity, repository, predicate type, signing
+    /// age, required timestamp and TSA policy, identity and repository policies and
+    /// profile.
+    /// Everything deciding whether the bundle is valid at all (subject name, modes,
+    /// trusted keys, accepted versions, detached payload, limits) is kept. Meant
+    /// for options a profile and repository policy were already applied to.
+    pub fn without_policy(&self) -> Self {
+        Self {
+            expected_digest: None,
+            expected_issuer: None,
+            expected_subject: None,
+            expected_email: None,
+            allowed_email_issuers: Vec::new(),
+            expected_spiffe_trust_domain: None,
+            expected_spiffe_path_prefix: None,
+            identity_policy: None,
+            expected_repository: None,
+            expected_workflow_ref: None,
+            expected_event_name: None,
+            expected_predicate_type: None,
+            max_signing_age: None,
+            verification_time: None,
+            profile: None,
+            repository_policy: None,
+            required_timestamp: None,
+            expected_tsa_policy: None,
+            ..self.clone()
+        }
+    }
+
+    /// An issuer as committed in the options hash
+    fn committed_issuer(&self, issuer: &str) -> String {
+        if self.normalize_issuer {
+            normalize_issuer(issuer)
+        } else {
+            issuer.to_string()
+        }
+    }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct CertificateChainHashes {
-    pub leaf: [u8; 32],
-    pub intermediates: Vec<[u8; 32]>,
-    pub root: [u8; 32],
+/// Builder for [`VerificationOptions`]
+///
+/// # Example
+///
+/// ```ignore
+/// let options = VerificationOptions::builder()
+///     .expected_issuer("https://token.actions.githubusercontent.com")
+///     .expected_repository("https://github.com/owner/repo")
+///     .expected_predicate_type("https://slsa.dev/provenance/v1")
+///     .max_signing_age(86_400, unix_now)
+///     .build();
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct VerificationOptionsBuilder {
+    options: VerificationOptions,
 }
 
-impl CertificateChainHashes {
-    pub fn as_tuple(&self) -> ([u8; 32], Vec<[u8; 32]>, [u8; 32]) {
-        (self.leaf, self.intermediates.clone(), self.root)
+impl VerificationOptionsBuilder {
+    pub fn expected_digest(mut self, digest: impl Into<Vec<u8>>) -> Self {
+        self.options.expected_digest = Some(digest.into());
+        self
     }
-}
 
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
-pub struct VerificationOptions {
-    /// Optional expected digest to verify against the subject digest in the attestation
-    pub expected_digest: Option<Vec<u8>>,
+    pub fn expected_issuer(mut self, issuer: impl Into<String>) -> Self {
+        self.options.expected_issuer = Some(issuer.into());
+        self
+    }
 
-    /// Optional expected OIDC issuer (e.g., "https://token.actions.githubusercontent.com")
-    pub expected_issuer: Option<String>,
+    pub fn expected_subject(mut self, subject: impl Into<String>) -> Self {
+        self.options.expected_subject = Some(subject.into());
+        self
+    }
 
-    /// Optional expected OIDC subject (e.g., "repo:owner/repo:ref:refs/heads/main")
-    pub expected_subject: Option<String>,
-}
+    pub fn expected_subject_name(mut self, name: impl Into<String>) -> Self {
+        self.options.expected_subject_name = Some(name.into());
+        self
+    }
 
-impl VerificationResult {
-    /// Serialize the VerificationResult into a Solidity-compatible byte array
-    ///
-    /// See the module-level documentation for the complete binary format specification.
-    ///
-    /// # Returns
-    ///
-    /// A byte vector containing the serialized data suitable for Solidity smart contract consumption.
-    pub fn as_slice(&self) -> Vec<u8> {
-        // Convert signing_time to u64 Unix timestamp (big-endian)
-        let timestamp = self.signing_time.timestamp() as u64;
-        let timestamp_bytes = timestamp.to_be_bytes();
-
-        // Determine timestamp proof type
-        let proof_type: u8 = match &self.timestamp_proof {
-            TimestampProof::None => TimestampProofType::None as u8,
-            TimestampProof::Rfc3161 { .. } => TimestampProofType::Rfc3161 as u8,
-            TimestampProof::Rekor { .. } => TimestampProofType::Rekor as u8,
-        };
+    pub fn expected_email(mut self, email: impl Into<String>) -> Self {
+        self.options.expected_email = Some(email.into());
+        self
+    }
 
-        // Build certificate hashes array: [leaf, ...intermediates, root]
-        let mut cert_hashes = Vec::with_capacity(2 + self.certificate_hashes.intermediates.len());
-        cert_hashes.push(self.certificate_hashes.leaf.into());
-        for intermediate in &self.certificate_hashes.intermediates {
-            cert_hashes.push((*intermediate).into());
-        }
-        cert_hashes.push(self.certificate_hashes.root.into());
-
-        // Extract OIDC fields, using empty strings for None
-        let (issuer, subject, workflow_ref, repository, event_name) = if let Some(ref oidc) = self.oidc_identity {
-            (
-                oidc.issuer.clone().unwrap_or_default(),
-                oidc.subject.clone().unwrap_or_default(),
-                oidc.workflow_ref.clone().unwrap_or_default(),
-                oidc.repository.clone().unwrap_or_default(),
-                oidc.event_name.clone().unwrap_or_default(),
-            )
-        } else {
-            (String::new(), String::new(), String::new(), String::new(), String::new())
-        };
+    /// Accept email identities from `issuer` (repeat to allow several)
+    pub fn allowed_email_issuer(mut self, issuer: impl Into<String>) -> Self {
+        self.options.allowed_email_issuers.push(issuer.into());
+        self
+    }
 
-        // Extract timestamp proof fields based on type
-        let (tsa_chain_hashes, message_imprint_algorithm, message_imprint, rekor_log_id, rekor_log_index, rekor_entry_index) =
-            match &self.timestamp_proof {
-                TimestampProof::None => {
-                    (vec![], 0u8, vec![], [0u8; 32], 0u64, 0u64)
-                }
-                TimestampProof::Rfc3161 {
-                    tsa_chain_hashes,
-                    message_imprint_algorithm,
-                    message_imprint,
-                } => {
-                    let mut hashes = Vec::with_capacity(2 + tsa_chain_hashes.intermediates.len());
-                    hashes.push(tsa_chain_hashes.leaf.into());
-                    for intermediate in &tsa_chain_hashes.intermediates {
-                        hashes.push((*intermediate).into());
-                    }
-                    hashes.push(tsa_chain_hashes.root.into());
-                    (
-                        hashes,
-                        *message_imprint_algorithm as u8,
-                        message_imprint.clone(),
-                        [0u8; 32],
-                        0u64,
-                        0u64,
-                    )
-                }
-                TimestampProof::Rekor { log_id, log_index, entry_index } => {
-                    (vec![], 0u8, vec![], *log_id, *log_index, *entry_index)
-                }
-            };
-
-        // Create the Solidity-compatible struct
-        let encoded_struct = VerificationResultEncoded {
-            certificateHashes: cert_hashes,
-            subjectDigest: self.subject_digest.clone().into(),
-            subjectDigestAlgorithm: self.subject_digest_algorithm as u8,
-            oidcIssuer: issuer,
-            oidcSubject: subject,
-            oidcWorkflowRef: workflow_ref,
-            oidcRepository: repository,
-            oidcEventName: event_name,
-            tsaChainHashes: tsa_chain_hashes,
-            messageImprintAlgorithm: message_imprint_algorithm,
-            messageImprint: message_imprint.into(),
-            rekorLogId: rekor_log_id.into(),
-            rekorLogIndex: rekor_log_index,
-            rekorEntryIndex: rekor_entry_index,
-        };
+    pub fn expected_spiffe_trust_domain(mut self, trust_domain: impl Into<String>) -> Self {
+        self.options.expected_spiffe_trust_domain = Some(trust_domain.into());
+        self
+    }
 
-        // Encode using standard ABI encoding
-        let abi_encoded = encoded_struct.abi_encode();
+    pub fn expected_spiffe_path_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.options.expected_spiffe_path_prefix = Some(prefix.into());
+        self
+    }
 
-        // Build result: [timestamp (8 bytes)] || [proof_type (1 byte)] || [ABI-encoded data]
-        let mut result = Vec::with_capacity(9 + abi_encoded.len());
-        result.extend_from_slice(&timestamp_bytes);
-        result.push(proof_type);
-        result.extend_from_slice(&abi_encoded);
+    pub fn identity_policy(mut self, policy: IdentityPolicy) -> Self {
+        self.options.identity_policy = Some(policy);
+        self
+    }
 
-        result
+    pub fn expected_repository(mut self, repository: impl Into<String>) -> Self {
+        self.options.expected_repository = Some(repository.into());
+        self
     }
 
-    /// Deserialize a VerificationResult from a Solidity-compatible byte array
-    ///
-    /// This is the inverse operation of `as_slice()`. It parses the byte array
-    /// and reconstructs the VerificationResult.
-    ///
-    /// # Arguments
-    ///
-    /// * `data` - The byte slice to deserialize
-    ///
-    /// # Returns
-    ///
-    /// Returns `Ok(VerificationResult)` if deserialization succeeds, or an error message
-    /// describing what went wrong.
-    ///
-    /// # Errors
-    ///
-    /// Returns an error if:
-    /// - The data is shorter than 9 bytes (minimum size for timestamp + proof type)
-    /// - ABI decoding fails
-    /// - The certificate hashes array has fewer than 2 elements
-    pub fn from_slice(data: &[u8]) -> Result<Self, String> {
-        // Need at least 9 bytes for timestamp (8) + proof type (1)
-        if data.len() < 9 {
-            return Err(format!("Data too short: expected at least 9 bytes, got {}", data.len()));
-        }
+    pub fn expected_workflow_ref(mut self, workflow_ref: impl Into<String>) -> Self {
+        self.options.expected_workflow_ref = Some(workflow_ref.into());
+        self
+    }
 
-        // Extract timestamp (first 8 bytes, big-endian)
-        let timestamp_bytes: [u8; 8] = data[0..8].try_into().unwrap();
-        let timestamp = u64::from_be_bytes(timestamp_bytes);
+    pub fn expected_event_name(mut self, event_name: impl Into<String>) -> Self {
+        self.options.expected_event_name = Some(event_name.into());
+        self
+    }
 
-        // Extract proof type (byte 9)
-        let proof_type = TimestampProofType::from_u8(data[8]);
+    pub fn expected_predicate_type(mut self, predicate_type: impl Into<String>) -> Self {
+        self.options.expected_predicate_type = Some(predicate_type.into());
+        self
+    }
 
-        // Decode the remaining ABI-encoded data
-        let abi_data = &data[9..];
-        let decoded = VerificationResultEncoded::abi_decode(abi_data)
-            .map_err(|e| format!("Failed to ABI decode: {}", e))?;
+    /// Require the signature to be at most `max_age` seconds old at `verification_time` (Unix seconds)
+    pub fn max_signing_age(mut self, max_age: u64, verification_time: u64) -> Self {
+        self.options.max_signing_age = Some(max_age);
+        self.options.verification_time = Some(verification_time);
+        self
+    }
 
-        // Extract certificate hashes: first is leaf, last is root, middle are intermediates
-        if decoded.certificateHashes.len() < 2 {
-            return Err(format!(
-                "Certificate hashes array must have at least 2 elements (leaf and root), got {}",
-                decoded.certificateHashes.len()
-            ));
-        }
+    /// Apply the verification profile `profile` (`name` or `name@version`)
+    pub fn profile(mut self, profile: impl Into<String>) -> Self {
+        self.options.profile = Some(profile.into());
+        self
+    }
 
-        let leaf = decoded.certificateHashes[0].0;
-        let root = decoded.certificateHashes[decoded.certificateHashes.len() - 1].0;
-        let intermediates: Vec<[u8; 32]> = decoded.certificateHashes[1..decoded.certificateHashes.len() - 1]
-            .iter()
-            .map(|h| h.0)
-            .collect();
-
-        // Reconstruct OIDC identity (only if any field is non-empty)
-        let oidc_identity = if decoded.oidcIssuer.is_empty()
-            && decoded.oidcSubject.is_empty()
-            && decoded.oidcWorkflowRef.is_empty()
-            && decoded.oidcRepository.is_empty()
-            && decoded.oidcEventName.is_empty()
-        {
-            None
-        } else {
-            Some(OidcIdentity {
-                issuer: if decoded.oidcIssuer.is_empty() { None } else { Some(decoded.oidcIssuer) },
-                subject: if decoded.oidcSubject.is_empty() { None } else { Some(decoded.oidcSubject) },
-                workflow_ref: if decoded.oidcWorkflowRef.is_empty() { None } else { Some(decoded.oidcWorkflowRef) },
-                repository: if decoded.oidcRepository.is_empty() { None } else { Some(decoded.oidcRepository) },
-                event_name: if decoded.oidcEventName.is_empty() { None } else { Some(decoded.oidcEventName) },
-            })
-        };
+    /// Enforce the policy picked for the bundle's repository
+    pub fn repository_policy(mut self, policy: RepositoryPolicy) -> Self {
+        self.options.repository_policy = Some(policy);
+        self
+    }
 
-        // Reconstruct timestamp proof based on type
-        let timestamp_proof = match proof_type {
-            TimestampProofType::None => TimestampProof::None,
-            TimestampProofType::Rfc3161 => {
-                // Extract TSA chain hashes
-                if decoded.tsaChainHashes.len() < 2 {
-                    return Err(format!(
-                        "TSA chain hashes must have at least 2 elements for RFC 3161, got {}",
-                        decoded.tsaChainHashes.len()
-                    ));
-                }
-                let tsa_leaf = decoded.tsaChainHashes[0].0;
-                let tsa_root = decoded.tsaChainHashes[decoded.tsaChainHashes.len() - 1].0;
-                let tsa_intermediates: Vec<[u8; 32]> = decoded.tsaChainHashes[1..decoded.tsaChainHashes.len() - 1]
-                    .iter()
-                    .map(|h| h.0)
-                    .collect();
-
-                TimestampProof::Rfc3161 {
-                    tsa_chain_hashes: CertificateChainHashes {
-                        leaf: tsa_leaf,
-                        intermediates: tsa_intermediates,
-                        root: tsa_root,
-                    },
-                    message_imprint_algorithm: DigestAlgorithm::from_u8(decoded.messageImprintAlgorithm),
-                    message_imprint: decoded.messageImprint.to_vec(),
-                }
-            }
-            TimestampProofType::Rekor => {
-                TimestampProof::Rekor {
-                    log_id: decoded.rekorLogId.0,
-                    log_index: decoded.rekorLogIndex,
-                    entry_index: decoded.rekorEntryIndex,
-                }
-            }
-        };
+    pub fn required_timestamp(mut self, kind: TimestampKind) -> Self {
+        self.options.required_timestamp = Some(kind);
+        self
+    }
 
-        // Convert timestamp to DateTime<Utc>
-        let signing_time = DateTime::from_timestamp(timestamp as i64, 0)
-            .ok_or_else(|| format!("Invalid timestamp: {}", timestamp))?;
+    /// Require an RFC 3161 timestamp issued under the TSA policy `oid`
+    pub fn expected_tsa_policy(mut self, oid: impl Into<String>) -> Self {
+        self.options.expected_tsa_policy = Some(oid.into());
+        self
+    }
 
-        Ok(VerificationResult {
-            certificate_hashes: CertificateChainHashes {
-                leaf,
-                intermediates,
-                root,
-            },
-            signing_time,
-            subject_digest: decoded.subjectDigest.to_vec(),
-            subject_digest_algorithm: DigestAlgorithm::from_u8(decoded.subjectDigestAlgorithm),
-            oidc_identity,
-            timestamp_proof,
-        })
+    /// Accept signatures up to `seconds` after the verification time
+    pub fn clock_skew(mut self, seconds: u64) -> Self {
+        self.options.clock_skew = seconds;
+        self
+    }
+
+    pub fn normalize_issuer(mut self, normalize: bool) -> Self {
+        self.options.normalize_issuer = normalize;
+        self
+    }
+
+    pub fn case_insensitive_subject(mut self, case_insensitive: bool) -> Self {
+        self.options.case_insensitive_subject = case_insensitive;
+        self
+    }
+
+    pub fn archival(mut self, archival: bool) -> Self {
+        self.options.archival = archival;
+        self
+    }
+
+    pub fn verify_all_signatures(mut self, verify_all: bool) -> Self {
+        self.options.verify_all_signatures = verify_all;
+        self
+    }
+
+    pub fn quarantine(mut self, quarantine: bool) -> Self {
+        self.options.quarantine = quarantine;
+        self
+    }
+
+    pub fn rekor_keys(mut self, keys: Vec<Vec<u8>>) -> Self {
+        self.options.rekor_keys = keys;
+        self
+    }
+
+    pub fn ctlog_keys(mut self, keys: Vec<Vec<u8>>) -> Self {
+        self.options.ctlog_keys = keys;
+        self
+    }
+
+    pub fn tsa_roots(mut self, roots: Vec<Vec<u8>>) -> Self {
+        self.options.tsa_roots = roots;
+        self
+    }
+
+    /// Accept bundles of format `version` (repeat to accept several)
+    pub fn allowed_bundle_version(mut self, version: impl Into<String>) -> Self {
+        self.options.allowed_bundle_versions.push(version.into());
+        self
+    }
+
+    pub fn detached_payload(mut self, payload: impl Into<Vec<u8>>) -> Self {
+        self.options.detached_payload = Some(payload.into());
+        self
+    }
+
+    pub fn signature_encoding(mut self, encoding: SignatureEncoding) -> Self {
+        self.options.signature_encoding = encoding;
+        self
+    }
+
+    pub fn limits(mut self, limits: VerificationLimits) -> Self {
+        self.options.limits = limits;
+        self
+    }
+
+    pub fn build(self) -> VerificationOptions {
+        self.options
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use alloc::vec;
 
     #[test]
-    fn test_as_slice_from_slice_roundtrip_with_rfc3161() {
-        // Create a test VerificationResult with RFC 3161 timestamp proof
-        let original = VerificationResult {
-            certificate_hashes: CertificateChainHashes {
-                leaf: [1u8; 32],
-                intermediates: vec![[2u8; 32], [3u8; 32]],
-                root: [4u8; 32],
-            },
-            signing_time: DateTime::from_timestamp(1700000000, 0).unwrap(),
-            subject_digest: vec![5u8; 32],
-            subject_digest_algorithm: DigestAlgorithm::Sha256,
-            oidc_identity: Some(OidcIdentity {
-                issuer: Some("https://token.actions.githubusercontent.com".to_string()),
-                subject: Some("repo:owner/repo:ref:refs/heads/main".to_string()),
-                workflow_ref: Some("owner/repo/.github/workflows/ci.yml@refs/heads/main".to_string()),
-                repository: Some("owner/repo".to_string()),
-                event_name: Some("push".to_string()),
-            }),
-            timestamp_proof: TimestampProof::Rfc3161 {
-                tsa_chain_hashes: CertificateChainHashes {
-                    leaf: [10u8; 32],
-                    intermediates: vec![[11u8; 32]],
-                    root: [12u8; 32],
-                },
-                message_imprint_algorithm: DigestAlgorithm::Sha256,
-                message_imprint: vec![13u8; 32],
-            },
+    fn test_options_hash_commits_to_each_constraint() {
+        let unconstrained = VerificationOptions::default().hash();
+        assert_eq!(unconstrained, VerificationOptions::default().hash());
+
+        let with_digest = VerificationOptions {
+            expected_digest: Some(vec![1u8; 32]),
+            ..Default::default()
+        };
+        let with_issuer = VerificationOptions {
+            expected_issuer: Some("https://token.actions.githubusercontent.com".to_string()),
+            ..Default::default()
+        };
+        let with_subject = VerificationOptions {
+            expected_subject: Some("https://token.actions.githubusercontent.com".to_string()),
+            ..Default::default()
         };
 
-        let encoded = original.as_slice();
-        let decoded = VerificationResult::from_slice(&encoded).expect("Failed to decode");
-
-        // Verify all fields match
-        assert_eq!(original.certificate_hashes.leaf, decoded.certificate_hashes.leaf);
-        assert_eq!(original.certificate_hashes.intermediates, decoded.certificate_hashes.intermediates);
-        assert_eq!(original.certificate_hashes.root, decoded.certificate_hashes.root);
-        assert_eq!(original.signing_time.timestamp(), decoded.signing_time.timestamp());
-        assert_eq!(original.subject_digest, decoded.subject_digest);
-        assert_eq!(original.subject_digest_algorithm, decoded.subject_digest_algorithm);
-        assert_eq!(original.oidc_identity, decoded.oidc_identity);
-
-        // Verify RFC 3161 timestamp proof
-        match (&original.timestamp_proof, &decoded.timestamp_proof) {
-            (
-                TimestampProof::Rfc3161 { tsa_chain_hashes: orig_tsa, message_imprint_algorithm: orig_alg, message_imprint: orig_imprint },
-                TimestampProof::Rfc3161 { tsa_chain_hashes: dec_tsa, message_imprint_algorithm: dec_alg, message_imprint: dec_imprint },
-            ) => {
-                assert_eq!(orig_tsa.leaf, dec_tsa.leaf);
-                assert_eq!(orig_tsa.intermediates, dec_tsa.intermediates);
-                assert_eq!(orig_tsa.root, dec_tsa.root);
-                assert_eq!(orig_alg, dec_alg);
-                assert_eq!(orig_imprint, dec_imprint);
-            }
-            _ => panic!("Expected RFC 3161 timestamp proof"),
-        }
+        // The same string as issuer or subject must not produce the same commitment
+        assert_ne!(with_issuer.hash(), with_subject.hash());
+        assert_ne!(unconstrained, with_digest.hash());
+        assert_ne!(unconstrained, with_issuer.hash());
+        assert_ne!(unconstrained, with_subject.hash());
+
+        // The journal carries the encoding next to its hash
+        let decoded = VerificationOptionsEncoded::abi_decode_params(&with_issuer.encode()).unwrap();
+        assert_eq!(decoded.expectedIssuer, "https://token.actions.githubusercontent.com");
+        assert_eq!(with_issuer.hash(), sha256(&with_issuer.encode()));
     }
 
     #[test]
-    fn test_as_slice_from_slice_roundtrip_with_rekor() {
-        // Create a test VerificationResult with Rekor timestamp proof
-        let original = VerificationResult {
-            certificate_hashes: CertificateChainHashes {
-                leaf: [1u8; 32],
-                intermediates: vec![],
-                root: [2u8; 32],
-            },
-            signing_time: DateTime::from_timestamp(1700000000, 0).unwrap(),
-            subject_digest: vec![3u8; 32],
-            subject_digest_algorithm: DigestAlgorithm::Sha256,
-            oidc_identity: None,
-            timestamp_proof: TimestampProof::Rekor {
-                log_id: [20u8; 32],
-                log_index: 12345678,
-                entry_index: 87654321,
-            },
+    fn test_options_hash_email_is_case_insensitive() {
+        let lower = VerificationOptions {
+            expected_email: Some("alice@example.com".to_string()),
+            ..Default::default()
         };
+        let mixed = VerificationOptions {
+            expected_email: Some("Alice@Example.com".to_string()),
+            ..Default::default()
+        };
+        let with_issuers = VerificationOptions {
+            allowed_email_issuers: vec!["https://accounts.google.com".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(lower.hash(), mixed.hash());
+        assert_ne!(lower.hash(), VerificationOptions::default().hash());
+        assert_ne!(with_issuers.hash(), VerificationOptions::default().hash());
+    }
 
-        let encoded = original.as_slice();
-        let decoded = VerificationResult::from_slice(&encoded).expect("Failed to decode");
-
-        // Verify Rekor timestamp proof
-        match (&original.timestamp_proof, &decoded.timestamp_proof) {
-            (
-                TimestampProof::Rekor { log_id: orig_id, log_index: orig_idx, entry_index: orig_entry },
-                TimestampProof::Rekor { log_id: dec_id, log_index: dec_idx, entry_index: dec_entry },
-            ) => {
-                assert_eq!(orig_id, dec_id);
-                assert_eq!(orig_idx, dec_idx);
-                assert_eq!(orig_entry, dec_entry);
+    #[test]
+    fn test_options_hash_follows_equality_options() {
+        let options = |issuer: &str, subject: &str, normalize_issuer, case_insensitive_subject| {
+            VerificationOptions {
+                expected_issuer: Some(issuer.to_string()),
+                expected_subject: Some(subject.to_string()),
+                normalize_issuer,
+                case_insensitive_subject,
+                ..Default::default()
             }
-            _ => panic!("Expected Rekor timestamp proof"),
-        }
+            .hash()
+        };
+        let (issuer, other_issuer) = (
+            "https://token.actions.githubusercontent.com",
+            "HTTPS://Token.Actions.GitHubUserContent.com/",
+        );
+        let (subject, other_subject) = (
+            "repo:owner/repo:ref:refs/heads/main",
+            "repo:Owner/Repo:ref:refs/heads/main",
+        );
+
+        // Equivalent spellings commit alike only when compared alike
+        assert_ne!(options(issuer, subject, false, false), options(other_issuer, subject, false, false));
+        assert_eq!(options(issuer, subject, true, false), options(other_issuer, subject, true, false));
+        assert_ne!(options(issuer, subject, false, false), options(issuer, other_subject, false, false));
+        assert_eq!(options(issuer, subject, false, true), options(issuer, other_subject, false, true));
     }
 
     #[test]
-    fn test_as_slice_from_slice_roundtrip_no_timestamp_proof() {
-        // Test with no timestamp proof
-        let original = VerificationResult {
-            certificate_hashes: CertificateChainHashes {
-                leaf: [10u8; 32],
-                intermediates: vec![],
-                root: [20u8; 32],
-            },
-            signing_time: DateTime::from_timestamp(1600000000, 0).unwrap(),
-            subject_digest: vec![30u8; 32],
-            subject_digest_algorithm: DigestAlgorithm::Sha384,
-            oidc_identity: None,
-            timestamp_proof: TimestampProof::None,
+    fn test_options_hash_commits_to_spiffe_constraints() {
+        let with_trust_domain = VerificationOptions {
+            expected_spiffe_trust_domain: Some("prod.example.org".to_string()),
+            ..Default::default()
         };
-
-        let encoded = original.as_slice();
-        let decoded = VerificationResult::from_slice(&encoded).expect("Failed to decode");
-
-        assert_eq!(original.certificate_hashes.leaf, decoded.certificate_hashes.leaf);
-        assert_eq!(original.certificate_hashes.intermediates.len(), 0);
-        assert_eq!(decoded.certificate_hashes.intermediates.len(), 0);
-        assert_eq!(original.certificate_hashes.root, decoded.certificate_hashes.root);
-        assert_eq!(original.subject_digest_algorithm, decoded.subject_digest_algorithm);
-        assert!(matches!(decoded.timestamp_proof, TimestampProof::None));
+        let with_path_prefix = VerificationOptions {
+            expected_spiffe_path_prefix: Some("prod.example.org".to_string()),
+            ..Default::default()
+        };
+        assert_ne!(with_trust_domain.hash(), with_path_prefix.hash());
+        assert_ne!(with_trust_domain.hash(), VerificationOptions::default().hash());
     }
 
     #[test]
-    fn test_as_slice_from_slice_roundtrip_partial_oidc() {
-        // Test with partial OIDC identity (some fields None)
-        let original = VerificationResult {
-            certificate_hashes: CertificateChainHashes {
-                leaf: [100u8; 32],
-                intermediates: vec![[101u8; 32]],
-                root: [102u8; 32],
-            },
-            signing_time: DateTime::from_timestamp(1650000000, 0).unwrap(),
-            subject_digest: vec![103u8; 32],
-            subject_digest_algorithm: DigestAlgorithm::Sha256,
-            oidc_identity: Some(OidcIdentity {
-                issuer: Some("https://example.com".to_string()),
-                subject: Some("test-subject".to_string()),
-                workflow_ref: None,
-                repository: None,
-                event_name: None,
-            }),
-            timestamp_proof: TimestampProof::None,
+    fn test_options_hash_commits_to_identity_policy() {
+        use crate::verifier::policy::IdentityMatcher;
+
+        let policy = |subject_regex: &str| VerificationOptions {
+            identity_policy: Some(IdentityPolicy::Identity(IdentityMatcher {
+                subject_regex: Some(subject_regex.to_string()),
+                ..Default::default()
+            })),
+            ..Default::default()
         };
-
-        let encoded = original.as_slice();
-        let decoded = VerificationResult::from_slice(&encoded).expect("Failed to decode");
-
-        assert_eq!(original.oidc_identity, decoded.oidc_identity);
+        assert_ne!(policy(".*@acme\\.com").hash(), VerificationOptions::default().hash());
+        assert_ne!(policy(".*@acme\\.com").hash(), policy(".*@acme\\.org").hash());
     }
 
     #[test]
-    fn test_from_slice_error_too_short() {
-        // Test with data that's too short (less than 9 bytes)
-        let short_data = vec![1u8, 2, 3, 4];
-        let result = VerificationResult::from_slice(&short_data);
-        assert!(result.is_err());
-        assert!(result.unwrap_err().contains("Data too short"));
+    fn test_options_hash_commits_to_repository_policy() {
+        let policy = |max_age: u64| {
+            VerificationOptions::builder()
+                .repository_policy(RepositoryPolicy {
+                    repository: "https://github.com/acme/*".to_string(),
+                    max_age: Some(max_age),
+                    ..Default::default()
+                })
+                .build()
+        };
+        assert_ne!(policy(3600).hash(), VerificationOptions::default().hash());
+        assert_ne!(policy(3600).hash(), policy(7200).hash());
+
+        let decoded = VerificationOptionsEncoded::abi_decode_params(&policy(3600).encode()).unwrap();
+        assert_eq!(decoded.repositoryPolicyHash.0, policy(3600).repository_policy.unwrap().hash());
     }
 
     #[test]
-    fn test_from_slice_error_invalid_abi_encoding() {
-        // Test with valid timestamp + proof type but invalid ABI encoding
-        let mut invalid_data = vec![0u8; 9]; // Valid timestamp (8) + proof type (1)
-        invalid_data.extend_from_slice(&[255u8; 32]); // Invalid ABI data
-        let result = VerificationResult::from_slice(&invalid_data);
-        assert!(result.is_err());
-        assert!(result.unwrap_err().contains("Failed to ABI decode"));
+    fn test_builder_sets_constraints() {
+        let options = VerificationOptions::builder()
+            .expected_repository("https://github.com/owner/repo")
+            .expected_predicate_type("https://slsa.dev/provenance/v1")
+            .max_signing_age(3600, 1_700_000_000)
+            .allowed_email_issuer("https://accounts.google.com")
+            .build();
+        assert_eq!(options.expected_repository.as_deref(), Some("https://github.com/owner/repo"));
+        assert_eq!(options.max_signing_age, Some(3600));
+        assert_eq!(options.verification_time, Some(1_700_000_000));
+        assert_eq!(options.allowed_email_issuers, ["https://accounts.google.com"]);
+
+        // Every new constraint is committed
+        let default_hash = VerificationOptions::default().hash();
+        assert_ne!(options.hash(), default_hash);
+        for options in [
+            VerificationOptions::builder().expected_workflow_ref("refs/heads/main").build(),
+            VerificationOptions::builder().expected_event_name("push").build(),
+            VerificationOptions::builder().max_signing_age(60, 0).build(),
+            VerificationOptions::builder().expected_tsa_policy("1.3.6.1.4.1.57264.2").build(),
+        ] {
+            assert_ne!(options.hash(), default_hash);
+        }
     }
 
     #[test]
-    fn test_as_slice_format() {
-        // Verify the format: first 8 bytes should be timestamp, byte 9 is proof type
-        let original = VerificationResult {
-            certificate_hashes: CertificateChainHashes {
-                leaf: [1u8; 32],
-                intermediates: vec![],
-                root: [2u8; 32],
-            },
-            signing_time: DateTime::from_timestamp(1700000000, 0).unwrap(),
-            subject_digest: vec![3u8; 32],
-            subject_digest_algorithm: DigestAlgorithm::Sha256,
-            oidc_identity: None,
-            timestamp_proof: TimestampProof::Rekor {
-                log_id: [4u8; 32],
-                log_index: 999,
-                entry_index: 1000,
-            },
+    fn test_options_hash_ignores_signature_encoding() {
+        let raw = VerificationOptions {
+            signature_encoding: SignatureEncoding::Raw,
+            ..Default::default()
         };
-
-        let encoded = original.as_slice();
-
-        // First 8 bytes should be the timestamp in big-endian
-        let timestamp_bytes: [u8; 8] = encoded[0..8].try_into().unwrap();
-        let timestamp = u64::from_be_bytes(timestamp_bytes);
-        assert_eq!(timestamp, 1700000000);
-
-        // Byte 9 should be proof type (2 = Rekor)
-        assert_eq!(encoded[8], TimestampProofType::Rekor as u8);
-
-        // Remaining bytes should be ABI-encoded
-        assert!(encoded.len() > 9);
+        assert_eq!(raw.hash(), VerificationOptions::default().hash());
     }
 
     #[test]
-    fn test_certificate_hashes_ordering() {
-        // Verify that certificate hashes are encoded in the correct order
-        let original = VerificationResult {
-            certificate_hashes: CertificateChainHashes {
-                leaf: [11u8; 32],
-                intermediates: vec![[22u8; 32], [33u8; 32], [44u8; 32]],
-                root: [55u8; 32],
+    fn test_options_hash_ignores_limits() {
+        let strict = VerificationOptions {
+            limits: VerificationLimits {
+                max_chain_length: 3,
+                max_proof_depth: 32,
+                max_signatures: 1,
             },
-            signing_time: DateTime::from_timestamp(1700000000, 0).unwrap(),
-            subject_digest: vec![66u8; 32],
-            subject_digest_algorithm: DigestAlgorithm::Sha256,
-            oidc_identity: None,
-            timestamp_proof: TimestampProof::None,
+            ..Default::default()
         };
-
-        let encoded = original.as_slice();
-        let decoded = VerificationResult::from_slice(&encoded).expect("Failed to decode");
-
-        // Verify order: leaf, intermediates (in order), root
-        assert_eq!(decoded.certificate_hashes.leaf, [11u8; 32]);
-        assert_eq!(decoded.certificate_hashes.intermediates, vec![[22u8; 32], [33u8; 32], [44u8; 32]]);
-        assert_eq!(decoded.certificate_hashes.root, [55u8; 32]);
-    }
-
-    #[test]
-    fn test_digest_algorithm_roundtrip() {
-        // Test all digest algorithm values
-        assert_eq!(DigestAlgorithm::from_u8(0), DigestAlgorithm::Unknown);
-        assert_eq!(DigestAlgorithm::from_u8(1), DigestAlgorithm::Sha256);
-        assert_eq!(DigestAlgorithm::from_u8(2), DigestAlgorithm::Sha384);
-        assert_eq!(DigestAlgorithm::from_u8(255), DigestAlgorithm::Unknown);
+        assert_eq!(strict.hash(), VerificationOptions::default().hash());
     }
 
     #[test]
-    fn test_timestamp_proof_type_roundtrip() {
-        // Test all timestamp proof type values
-        assert_eq!(TimestampProofType::from_u8(0), TimestampProofType::None);
-        assert_eq!(TimestampProofType::from_u8(1), TimestampProofType::Rfc3161);
-        assert_eq!(TimestampProofType::from_u8(2), TimestampProofType::Rekor);
-        assert_eq!(TimestampProofType::from_u8(255), TimestampProofType::None);
+    fn test_without_policy_keeps_validity_options() {
+        let options = VerificationOptions::builder()
+            .expected_digest(vec![1u8; 32])
+            .expected_issuer("https://token.actions.githubusercontent.com")
+            .expected_subject_name("app.tar.gz")
+            .expected_predicate_type("https://slsa.dev/provenance/v1")
+            .max_signing_age(3600, 1_700_000_000)
+            .required_timestamp(TimestampKind::Rfc3161)
+            .expected_tsa_policy("1.3.6.1.4.1.57264.2")
+            .profile("github-actions")
+            .clock_skew(60)
+            .archival(true)
+            .quarantine(true)
+            .ctlog_keys(vec![vec![2u8; 91]])
+            .tsa_roots(vec![vec![3u8; 64]])
+            .build();
+
+        let relaxed = options.without_policy();
+        let unconstrained = VerificationOptions::builder()
+            .expected_subject_name("app.tar.gz")
+            .clock_skew(60)
+            .build();
+        assert_eq!(relaxed.hash(), unconstrained.hash());
+        assert_eq!(relaxed.expected_subject_name.as_deref(), Some("app.tar.gz"));
+        assert!(relaxed.archival && relaxed.quarantine);
+        assert_eq!(relaxed.ctlog_keys, options.ctlog_keys);
+        assert_eq!(relaxed.tsa_roots, options.tsa_roots);
     }
 }
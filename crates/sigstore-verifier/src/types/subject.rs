@@ -0,0 +1,180 @@
+//! What an attested subject refers to
+//!
+//! A result carries the subject as a name and digest bytes, but subjects follow
+//! different naming conventions: a file or image name, a package URL
+//! (`pkg:cargo/app@1.0.0`), or a git reference (`git+https://...@refs/tags/v1`,
+//! or a tag name when a signed git object was verified). [`subject_kind`]
+//! classifies a subject, and the verifier commits the kind in the journal;
+//! [`SubjectRef`] carries the parsed form so consumers don't have to re-guess.
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use crate::types::purl::PackageUrl;
+use crate::types::result::{DigestAlgorithm, SubjectKind, VerificationResult};
+
+/// Classify a subject by its name and digest algorithm
+///
+/// A SHA-1 digest is the object ID of a signed git commit or tag, and `git+`
+/// names are git URLs. Other names starting with `pkg:` are package URLs of
+/// any ecosystem, the rest plain artifacts.
+pub fn subject_kind(name: &str, algorithm: DigestAlgorithm) -> SubjectKind {
+    if algorithm == DigestAlgorithm::Sha1 || name.starts_with("git+") {
+        SubjectKind::Git
+    } else if name.starts_with("pkg:") {
+        SubjectKind::Purl
+    } else {
+        SubjectKind::Artifact
+    }
+}
+
+/// A git repository and reference, from a `git+<url>@<ref>` subject name or the
+/// tag name of a signed git object
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GitRef {
+    /// Repository URL without the `git+` prefix, unknown for signed git objects
+    pub repository: Option<String>,
+    /// Reference (e.g. `refs/tags/v1.0.0`, a commit hash or a tag name), if any
+    pub reference: Option<String>,
+}
+
+impl GitRef {
+    /// Parse a `git+<url>[@<ref>]` subject name
+    pub fn parse(name: &str) -> Option<Self> {
+        let url = name.strip_prefix("git+")?;
+        // The reference follows the last `@` that comes after the URL's path
+        let path_start = url.find("://").map_or(0, |scheme| scheme + 3);
+        let (repository, reference) = match url[path_start..].rfind('@') {
+            Some(at) if url[path_start..path_start + at].contains('/') => {
+                let at = path_start + at;
+                (&url[..at], Some(url[at + 1..].to_string()))
+            }
+            _ => (url, None),
+        };
+        Some(Self {
+            repository: Some(repository.to_string()),
+            reference: reference.filter(|reference| !reference.is_empty()),
+        })
+    }
+}
+
+/// Typed view of the subject of a [`VerificationResult`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SubjectRef {
+    pub name: String,
+    pub digest_algorithm: DigestAlgorithm,
+    pub digest: Vec<u8>,
+    pub kind: SubjectKind,
+    /// The parsed name, for [`SubjectKind::Purl`] subjects of a supported ecosystem
+    pub purl: Option<PackageUrl>,
+    /// The parsed name, for [`SubjectKind::Git`] subjects
+    pub git: Option<GitRef>,
+}
+
+impl SubjectRef {
+    /// Typed subject of a result
+    ///
+    /// Version 1 journals record no subject kind; it is then classified
+    /// from the name and digest algorithm, as the verifier does.
+    pub fn of(result: &VerificationResult) -> Self {
+        let name = &result.subject_name;
+        let kind = match result.subject_kind {
+            SubjectKind::Unknown => subject_kind(name, result.subject_digest_algorithm),
+            kind => kind,
+        };
+        let git = match kind {
+            SubjectKind::Git => GitRef::parse(name).or_else(|| {
+                Some(GitRef {
+                    repository: None,
+                    reference: Some(name.clone()).filter(|name| !name.is_empty()),
+                })
+            }),
+            _ => None,
+        };
+
+        Self {
+            name: name.clone(),
+            digest_algorithm: result.subject_digest_algorithm,
+            digest: result.subject_digest.clone(),
+            kind,
+            purl: match kind {
+                SubjectKind::Purl => PackageUrl::parse(name),
+                _ => None,
+            },
+            git,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::result::{CertificateChainHashes, TimestampProof};
+    use chrono::DateTime;
+    use alloc::vec;
+
+    #[test]
+    fn test_subject_kind() {
+        assert_eq!(subject_kind("app.tar.gz", DigestAlgorithm::Sha256), SubjectKind::Artifact);
+        assert_eq!(subject_kind("pkg:npm/left-pad@1.3.0", DigestAlgorithm::Sha256), SubjectKind::Purl);
+        assert_eq!(
+            subject_kind("git+https://github.com/acme/app@refs/tags/v1", DigestAlgorithm::Sha256),
+            SubjectKind::Git
+        );
+        assert_eq!(subject_kind("", DigestAlgorithm::Sha1), SubjectKind::Git);
+    }
+
+    #[test]
+    fn test_git_ref_parse() {
+        let git = GitRef::parse("git+https://github.com/acme/app@refs/tags/v1.0.0").unwrap();
+        assert_eq!(git.repository.as_deref(), Some("https://github.com/acme/app"));
+        assert_eq!(git.reference.as_deref(), Some("refs/tags/v1.0.0"));
+
+        // The user part of an ssh URL is not a reference
+        let git = GitRef::parse("git+ssh://git@github.com/acme/app").unwrap();
+        assert_eq!(git.repository.as_deref(), Some("ssh://git@github.com/acme/app"));
+        assert_eq!(git.reference, None);
+
+        assert!(GitRef::parse("https://github.com/acme/app").is_none());
+    }
+
+    #[test]
+    fn test_subject_ref_of_result() {
+        let result = |name: &str, algorithm: DigestAlgorithm, kind: SubjectKind| VerificationResult {
+            certificate_hashes: CertificateChainHashes {
+                leaf: [0u8; 32],
+                intermediates: vec![],
+                root: [0u8; 32],
+            },
+            signing_time: DateTime::from_timestamp(1_700_000_000, 0).unwrap(),
+            subject_digest: vec![1, 2],
+            subject_digest_algorithm: algorithm,
+            oidc_identity: None,
+            timestamp_proof: TimestampProof::None,
+            options_hash: [0u8; 32],
+            subject_name: name.to_string(),
+            verifier_version: "0.1.0".to_string(),
+            guest_version: String::new(),
+            annotations_hash: [0u8; 32],
+            flags: 0,
+            block_anchor: None,
+            previous_result_hash: None,
+            verification_options: vec![],
+            certificate_timestamps: vec![],
+            input_digest: [0u8; 32],
+            predicate_type: String::new(),
+            predicate_sha256: [0u8; 32],
+            subject_kind: kind,
+            policy_owner_hash: None,
+        };
+
+        let purl = SubjectRef::of(&result("pkg:cargo/app@1.0.0", DigestAlgorithm::Sha256, SubjectKind::Purl));
+        assert!(matches!(purl.purl, Some(PackageUrl::Cargo(_))));
+        assert_eq!(purl.git, None);
+
+        // A tag of a signed git object, decoded from a journal without subject kind
+        let tag = SubjectRef::of(&result("v1.0.0", DigestAlgorithm::Sha1, SubjectKind::Unknown));
+        assert_eq!(tag.kind, SubjectKind::Git);
+        assert_eq!(tag.git.unwrap().reference.as_deref(), Some("v1.0.0"));
+        assert_eq!(tag.digest, vec![1, 2]);
+    }
+}
@@ -1,3 +1,6 @@
+use alloc::format;
+use alloc::string::ToString;
+use alloc::vec::Vec;
 use x509_parser::prelude::*;
 
 use crate::crypto::hash::sha256;
@@ -81,6 +84,14 @@ fn verify_cert_signature(
     cert: &X509Certificate,
     issuer: &X509Certificate,
 ) -> Result<(), CertificateError> {
+    // The outer algorithm is not signed; RFC 5280 requires it to repeat the signed one
+    if cert.signature_algorithm != cert.tbs_certificate.signature {
+        return Err(CertificateError::ChainVerificationFailed(format!(
+            "signature algorithm {} does not match the signed algorithm {}",
+            cert.signature_algorithm.algorithm, cert.tbs_certificate.signature.algorithm
+        )));
+    }
+
     let public_key = PublicKey::from_certificate(issuer)
         .map_err(|e| CertificateError::ChainVerificationFailed(e.to_string()))?;
 
@@ -88,7 +99,7 @@ fn verify_cert_signature(
     let tbs_certificate = cert.tbs_certificate.as_ref();
 
     public_key
-        .verify_signature(tbs_certificate, signature)
+        .verify_x509_signature(tbs_certificate, signature, &cert.signature_algorithm)
         .map_err(|e| CertificateError::ChainVerificationFailed(e.to_string()))?;
 
     Ok(())
@@ -109,6 +120,17 @@ fn verify_cert_signature(
 pub fn verify_tsa_certificate_chain(
     tsa_chain: &CertificateChain,
 ) -> Result<(), CertificateError> {
+    verify_tsa_certificate_chain_with(tsa_chain, Err)
+}
+
+/// [`verify_tsa_certificate_chain`], handing an EKU failure to `on_eku_error`
+///
+/// Verification goes on if `on_eku_error` returns `Ok`, as audit mode's does
+/// for a relaxed [`AuditCheck::TsaEku`](crate::report::AuditCheck::TsaEku).
+pub(crate) fn verify_tsa_certificate_chain_with<E: From<CertificateError>>(
+    tsa_chain: &CertificateChain,
+    on_eku_error: impl FnOnce(CertificateError) -> Result<(), E>,
+) -> Result<(), E> {
     // Parse all certificates
     let leaf_x509 = parse_der_certificate(&tsa_chain.leaf)?;
     let mut intermediate_x509 = Vec::new();
@@ -118,7 +140,7 @@ pub fn verify_tsa_certificate_chain(
     let root_x509 = parse_der_certificate(&tsa_chain.root)?;
 
     // Verify TSA leaf certificate EKU
-    verify_tsa_certificate_eku(&leaf_x509)?;
+    verify_tsa_certificate_eku(&leaf_x509).or_else(on_eku_error)?;
 
     // Verify certificate signatures
     // 1. Verify leaf signed by first intermediate
@@ -234,6 +256,71 @@ pub fn verify_tsa_certificate_eku(cert: &X509Certificate) -> Result<(), Certific
 
 #[cfg(test)]
 mod tests {
+    use super::*;
+
+    fn parse_pem(pem: &str) -> Vec<u8> {
+        ::pem::parse(pem).unwrap().into_contents()
+    }
+
+    #[test]
+    fn test_ed25519_chain_signatures() {
+        let root_der = parse_pem(include_str!("../../testdata/ed25519/root.pem"));
+        let intermediate_der = parse_pem(include_str!("../../testdata/ed25519/intermediate.pem"));
+        let leaf_der = parse_pem(include_str!("../../testdata/ed25519/leaf.pem"));
+        let root = parse_der_certificate(&root_der).unwrap();
+        let intermediate = parse_der_certificate(&intermediate_der).unwrap();
+        let leaf = parse_der_certificate(&leaf_der).unwrap();
+
+        assert!(verify_cert_signature(&root, &root).is_ok());
+        assert!(verify_cert_signature(&intermediate, &root).is_ok());
+        assert!(verify_cert_signature(&leaf, &intermediate).is_ok());
+
+        // Signed by the intermediate, not the root
+        assert!(matches!(
+            verify_cert_signature(&leaf, &root),
+            Err(CertificateError::ChainVerificationFailed(_))
+        ));
+    }
+
+    #[test]
+    fn test_rsa_chain_signatures() {
+        // Root: sha256WithRSAEncryption, intermediate: RSASSA-PSS/SHA-256 with a
+        // 32-byte salt, leaf: sha384WithRSAEncryption
+        let root_der = parse_pem(include_str!("../../testdata/rsa/root.pem"));
+        let intermediate_der = parse_pem(include_str!("../../testdata/rsa/intermediate.pem"));
+        let leaf_der = parse_pem(include_str!("../../testdata/rsa/leaf.pem"));
+        let root = parse_der_certificate(&root_der).unwrap();
+        let intermediate = parse_der_certificate(&intermediate_der).unwrap();
+        let leaf = parse_der_certificate(&leaf_der).unwrap();
+
+        assert!(verify_cert_signature(&root, &root).is_ok());
+        assert!(verify_cert_signature(&intermediate, &root).is_ok());
+        assert!(verify_cert_signature(&leaf, &intermediate).is_ok());
+
+        assert!(matches!(
+            verify_cert_signature(&leaf, &root),
+            Err(CertificateError::ChainVerificationFailed(_))
+        ));
+    }
+
+    #[test]
+    fn test_signature_algorithm_must_match_signed_algorithm() {
+        let intermediate_der = parse_pem(include_str!("../../testdata/ed25519/intermediate.pem"));
+        let mut leaf_der = parse_pem(include_str!("../../testdata/ed25519/leaf.pem"));
+
+        // Turn the outer Ed25519 OID (1.3.101.112) into Ed448 (1.3.101.113)
+        let ed25519 = [0x06, 0x03, 0x2b, 0x65, 0x70];
+        let outer = leaf_der.windows(ed25519.len()).rposition(|window| window == ed25519).unwrap();
+        leaf_der[outer + 4] = 0x71;
+
+        let intermediate = parse_der_certificate(&intermediate_der).unwrap();
+        let leaf = parse_der_certificate(&leaf_der).unwrap();
+        assert!(matches!(
+            verify_cert_signature(&leaf, &intermediate),
+            Err(CertificateError::ChainVerificationFailed(_))
+        ));
+    }
+
     #[test]
     fn test_time_stamping_oid() {
         // Verify the OID constant is correct
@@ -0,0 +1,481 @@
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use crate::error::{IdentityField, VerificationError};
+use crate::parser::identity::parse_spiffe_id;
+use crate::types::certificate::{IdentityKind, OidcIdentity};
+use crate::types::result::VerificationOptions;
+
+/// Verify the signer identity against the expected values in `options`
+///
+/// Issuer and subject are compared exactly, unless `normalize_issuer` compares
+/// issuers as [normalized URLs](normalize_issuer) and `case_insensitive_subject`
+/// the subject ignoring ASCII case. The expected email is compared
+/// case-insensitively and requires an email SAN, and `allowed_email_issuers`
+/// restricts which OAuth providers may vouch for an email identity. The SPIFFE
+/// trust domain and path prefix require a SPIFFE ID SAN. The repository,
+/// workflow ref and event name are compared exactly with the certificate's
+/// extensions.
+///
+/// # Errors
+///
+/// Returns the error of the first constraint that is not met:
+/// `VerificationError::IdentityMismatch` for a field with another value,
+/// `IdentityFieldMissing` for a field the certificate lacks,
+/// `EmailIssuerNotAllowed`, or `OidcIdentityMissing` when a constraint is set
+/// but the certificate carries no identity.
+pub fn verify_oidc_identity(
+    identity: Option<&OidcIdentity>,
+    options: &VerificationOptions,
+) -> Result<(), VerificationError> {
+    let Some(identity) = identity else {
+        if options.expected_issuer.is_some()
+            || options.expected_subject.is_some()
+            || options.expected_email.is_some()
+            || options.expected_spiffe_trust_domain.is_some()
+            || options.expected_spiffe_path_prefix.is_some()
+            || options.expected_repository.is_some()
+            || options.expected_workflow_ref.is_some()
+            || options.expected_event_name.is_some()
+        {
+            return Err(VerificationError::OidcIdentityMissing);
+        }
+        return Ok(());
+    };
+
+    let issuer_matches = |expected: &str, actual: &str| {
+        if options.normalize_issuer {
+            normalize_issuer(expected) == normalize_issuer(actual)
+        } else {
+            expected == actual
+        }
+    };
+
+    if let Some(ref expected_issuer) = options.expected_issuer {
+        if let Some(ref actual_issuer) = identity.issuer {
+            if !issuer_matches(expected_issuer, actual_issuer) {
+                return Err(VerificationError::IdentityMismatch {
+                    field: IdentityField::Issuer,
+                    expected: expected_issuer.clone(),
+                    actual: actual_issuer.clone(),
+                });
+            }
+        } else {
+            return Err(VerificationError::IdentityFieldMissing(IdentityField::Issuer));
+        }
+    }
+
+    if let Some(ref expected_subject) = options.expected_subject {
+        if let Some(ref actual_subject) = identity.subject {
+            let matches = if options.case_insensitive_subject {
+                actual_subject.eq_ignore_ascii_case(expected_subject)
+            } else {
+                actual_subject == expected_subject
+            };
+            if !matches {
+                return Err(VerificationError::IdentityMismatch {
+                    field: IdentityField::Subject,
+                    expected: expected_subject.clone(),
+                    actual: actual_subject.clone(),
+                });
+            }
+        } else {
+            return Err(VerificationError::IdentityFieldMissing(IdentityField::Subject));
+        }
+    }
+
+    for (field, expected, actual) in [
+        (IdentityField::Repository, &options.expected_repository, &identity.repository),
+        (IdentityField::WorkflowRef, &options.expected_workflow_ref, &identity.workflow_ref),
+        (IdentityField::EventName, &options.expected_event_name, &identity.event_name),
+    ] {
+        let Some(expected) = expected else { continue };
+        match actual {
+            Some(actual) if actual == expected => {}
+            Some(actual) => {
+                return Err(VerificationError::IdentityMismatch {
+                    field,
+                    expected: expected.clone(),
+                    actual: actual.clone(),
+                });
+            }
+            None => return Err(VerificationError::IdentityFieldMissing(field)),
+        }
+    }
+
+    let email = match (identity.subject_kind, identity.subject.as_deref()) {
+        (IdentityKind::Email, Some(email)) => Some(email),
+        _ => None,
+    };
+
+    if let Some(ref expected_email) = options.expected_email {
+        match email {
+            Some(email) if email.eq_ignore_ascii_case(expected_email) => {}
+            Some(email) => {
+                return Err(VerificationError::IdentityMismatch {
+                    field: IdentityField::Email,
+                    expected: expected_email.clone(),
+                    actual: email.to_string(),
+                });
+            }
+            None => return Err(VerificationError::IdentityFieldMissing(IdentityField::Email)),
+        }
+    }
+
+    if email.is_some() && !options.allowed_email_issuers.is_empty() {
+        let issuer = identity.issuer.as_deref().unwrap_or_default();
+        if !options.allowed_email_issuers.iter().any(|allowed| issuer_matches(allowed, issuer)) {
+            return Err(VerificationError::EmailIssuerNotAllowed(issuer.to_string()));
+        }
+    }
+
+    if options.expected_spiffe_trust_domain.is_some() || options.expected_spiffe_path_prefix.is_some() {
+        let spiffe_id = match (identity.subject_kind, identity.subject.as_deref()) {
+            (IdentityKind::Spiffe, Some(subject)) => parse_spiffe_id(subject),
+            _ => None,
+        };
+        let Some((trust_domain, path)) = spiffe_id else {
+            return Err(VerificationError::IdentityFieldMissing(IdentityField::SpiffeId));
+        };
+
+        if let Some(ref expected_trust_domain) = options.expected_spiffe_trust_domain {
+            if trust_domain != expected_trust_domain {
+                return Err(VerificationError::IdentityMismatch {
+                    field: IdentityField::SpiffeTrustDomain,
+                    expected: expected_trust_domain.clone(),
+                    actual: trust_domain.to_string(),
+                });
+            }
+        }
+
+        if let Some(ref prefix) = options.expected_spiffe_path_prefix {
+            if !spiffe_path_has_prefix(path, prefix) {
+                return Err(VerificationError::IdentityMismatch {
+                    field: IdentityField::SpiffePath,
+                    expected: prefix.clone(),
+                    actual: path.to_string(),
+                });
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Normalize an issuer URL for comparison (RFC 3986, sections 6.2.2 and 6.2.3)
+///
+/// The scheme and host are lowercased, the default port of `http` and `https`
+/// is dropped, percent-encodings are uppercased and those of unreserved
+/// characters decoded, and dot segments are removed. A trailing slash is
+/// dropped as well, as issuers are published with and without one. Query and
+/// fragment are kept as they are, and issuers that are not URLs are returned
+/// unchanged.
+pub fn normalize_issuer(issuer: &str) -> String {
+    let Some((scheme, rest)) = issuer.split_once("://") else {
+        return issuer.to_string();
+    };
+    let scheme = scheme.to_ascii_lowercase();
+
+    let (authority, rest) = rest.split_at(rest.find(['/', '?', '#']).unwrap_or(rest.len()));
+    let (path, suffix) = rest.split_at(rest.find(['?', '#']).unwrap_or(rest.len()));
+
+    // Only the host is case-insensitive, not the userinfo
+    let (userinfo, host) = match authority.rsplit_once('@') {
+        Some((userinfo, host)) => (format!("{}@", userinfo), host),
+        None => (String::new(), authority),
+    };
+    let host = host.to_ascii_lowercase();
+    let default_port = match scheme.as_str() {
+        "https" => ":443",
+        "http" => ":80",
+        _ => "",
+    };
+    let host = host
+        .strip_suffix(default_port)
+        .filter(|_| !default_port.is_empty())
+        .unwrap_or(&host);
+    // An empty port is the default port as well
+    let host = host.strip_suffix(':').unwrap_or(host);
+
+    let path = remove_dot_segments(&normalize_percent_encoding(path));
+    format!("{}://{}{}{}{}", scheme, userinfo, host, path.trim_end_matches('/'), suffix)
+}
+
+/// Uppercase percent-encodings, and decode those of unreserved characters
+fn normalize_percent_encoding(input: &str) -> String {
+    let bytes = input.as_bytes();
+    let mut output = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let decoded = (bytes[i] == b'%')
+            .then(|| bytes.get(i + 1..i + 3))
+            .flatten()
+            .and_then(|hex| core::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match decoded {
+            Some(byte) if byte.is_ascii_alphanumeric() || b"-._~".contains(&byte) => output.push(byte),
+            Some(byte) => output.extend_from_slice(format!("%{:02X}", byte).as_bytes()),
+            None => {
+                output.push(bytes[i]);
+                i += 1;
+                continue;
+            }
+        }
+        i += 3;
+    }
+    // Only ASCII sequences were replaced, by ASCII
+    String::from_utf8(output).unwrap_or_else(|_| input.to_string())
+}
+
+/// Resolve `.` and `..` segments of an absolute path (RFC 3986, section 5.2.4)
+fn remove_dot_segments(path: &str) -> String {
+    let mut segments: Vec<&str> = Vec::new();
+    for segment in path.split('/').skip(1) {
+        match segment {
+            "." => {}
+            ".." => {
+                segments.pop();
+            }
+            segment => segments.push(segment),
+        }
+    }
+    if path.is_empty() {
+        String::new()
+    } else {
+        format!("/{}", segments.join("/"))
+    }
+}
+
+/// Whether `path` equals `prefix` or lies below it, on whole segments
+/// (`/ns/prod` covers `/ns/prod/sa/x` but not `/ns/production`)
+fn spiffe_path_has_prefix(path: &str, prefix: &str) -> bool {
+    let prefix = prefix.trim_end_matches('/');
+    match path.strip_prefix(prefix) {
+        Some(rest) => rest.is_empty() || rest.starts_with('/'),
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    fn email_identity(email: &str, issuer: &str) -> OidcIdentity {
+        OidcIdentity {
+            issuer: Some(issuer.to_string()),
+            subject: Some(email.to_string()),
+            workflow_ref: None,
+            repository: None,
+            event_name: None,
+            subject_kind: IdentityKind::Email,
+        }
+    }
+
+    #[test]
+    fn test_expected_email_is_case_insensitive() {
+        let identity = email_identity("Alice@Example.com", "https://accounts.google.com");
+        let options = VerificationOptions {
+            expected_email: Some("alice@example.com".to_string()),
+            ..Default::default()
+        };
+        assert!(verify_oidc_identity(Some(&identity), &options).is_ok());
+
+        let other = VerificationOptions {
+            expected_email: Some("bob@example.com".to_string()),
+            ..Default::default()
+        };
+        assert!(matches!(
+            verify_oidc_identity(Some(&identity), &other),
+            Err(VerificationError::IdentityMismatch { field: IdentityField::Email, expected, actual })
+                if expected == "bob@example.com" && actual == "Alice@Example.com"
+        ));
+    }
+
+    #[test]
+    fn test_expected_email_requires_email_identity() {
+        let mut identity = email_identity("alice@example.com", "https://accounts.google.com");
+        identity.subject_kind = IdentityKind::Uri;
+        let options = VerificationOptions {
+            expected_email: Some("alice@example.com".to_string()),
+            ..Default::default()
+        };
+        assert!(matches!(
+            verify_oidc_identity(Some(&identity), &options),
+            Err(VerificationError::IdentityFieldMissing(IdentityField::Email))
+        ));
+        assert!(matches!(
+            verify_oidc_identity(None, &options),
+            Err(VerificationError::OidcIdentityMissing)
+        ));
+    }
+
+    #[test]
+    fn test_email_issuer_allow_list() {
+        let options = VerificationOptions {
+            allowed_email_issuers: vec!["https://accounts.google.com".to_string()],
+            ..Default::default()
+        };
+
+        let google = email_identity("alice@example.com", "https://accounts.google.com");
+        assert!(verify_oidc_identity(Some(&google), &options).is_ok());
+
+        let other = email_identity("alice@example.com", "https://issuer.example.com");
+        assert!(matches!(
+            verify_oidc_identity(Some(&other), &options),
+            Err(VerificationError::EmailIssuerNotAllowed(issuer)) if issuer == "https://issuer.example.com"
+        ));
+
+        // Workflow identities are not restricted by the email allow-list
+        let mut workflow = other.clone();
+        workflow.subject_kind = IdentityKind::Uri;
+        assert!(verify_oidc_identity(Some(&workflow), &options).is_ok());
+    }
+
+    #[test]
+    fn test_normalize_issuer() {
+        let canonical = "https://token.actions.githubusercontent.com";
+        for issuer in [
+            "https://token.actions.githubusercontent.com/",
+            "HTTPS://Token.Actions.GitHubUserContent.com",
+            "https://token.actions.githubusercontent.com:443",
+            "https://token.actions.githubusercontent.com:/",
+        ] {
+            assert_eq!(normalize_issuer(issuer), canonical, "{}", issuer);
+        }
+
+        assert_eq!(
+            normalize_issuer("https://issuer.example.com/a/./b/../%7euser/%2f?Q=%2f"),
+            "https://issuer.example.com/a/~user/%2F?Q=%2f"
+        );
+        // Other ports, paths and non-URL issuers stay distinct
+        assert_eq!(normalize_issuer("https://issuer.example.com:8443"), "https://issuer.example.com:8443");
+        assert_eq!(normalize_issuer("http://issuer.example.com:443"), "http://issuer.example.com:443");
+        assert_eq!(normalize_issuer("https://issuer.example.com/Tenant"), "https://issuer.example.com/Tenant");
+        assert_eq!(normalize_issuer("accounts.google.com"), "accounts.google.com");
+    }
+
+    #[test]
+    fn test_issuer_and_subject_equality_options() {
+        let identity = OidcIdentity {
+            issuer: Some("https://token.actions.githubusercontent.com".to_string()),
+            subject: Some("repo:Owner/Repo:ref:refs/heads/main".to_string()),
+            workflow_ref: None,
+            repository: None,
+            event_name: None,
+            subject_kind: IdentityKind::Uri,
+        };
+        let mut options = VerificationOptions {
+            expected_issuer: Some("https://token.actions.githubusercontent.com/".to_string()),
+            expected_subject: Some("repo:owner/repo:ref:refs/heads/main".to_string()),
+            ..Default::default()
+        };
+        assert!(verify_oidc_identity(Some(&identity), &options).is_err());
+
+        options.normalize_issuer = true;
+        assert!(verify_oidc_identity(Some(&identity), &options).is_err());
+
+        options.case_insensitive_subject = true;
+        assert!(verify_oidc_identity(Some(&identity), &options).is_ok());
+
+        // Normalization applies to the email issuer allow-list as well
+        let google = email_identity("alice@example.com", "https://accounts.google.com");
+        let allow_list = VerificationOptions {
+            allowed_email_issuers: vec!["https://Accounts.Google.com/".to_string()],
+            normalize_issuer: true,
+            ..Default::default()
+        };
+        assert!(verify_oidc_identity(Some(&google), &allow_list).is_ok());
+    }
+
+    #[test]
+    fn test_workflow_extension_options() {
+        let identity = OidcIdentity {
+            issuer: Some("https://token.actions.githubusercontent.com".to_string()),
+            subject: None,
+            workflow_ref: Some("refs/tags/v1.0.0".to_string()),
+            repository: Some("https://github.com/owner/repo".to_string()),
+            event_name: Some("push".to_string()),
+            subject_kind: IdentityKind::Uri,
+        };
+        let options = VerificationOptions {
+            expected_repository: Some("https://github.com/owner/repo".to_string()),
+            expected_workflow_ref: Some("refs/tags/v1.0.0".to_string()),
+            expected_event_name: Some("push".to_string()),
+            ..Default::default()
+        };
+        assert!(verify_oidc_identity(Some(&identity), &options).is_ok());
+
+        let pull_request = VerificationOptions {
+            expected_event_name: Some("pull_request".to_string()),
+            ..options.clone()
+        };
+        let error = verify_oidc_identity(Some(&identity), &pull_request).unwrap_err();
+        assert!(matches!(
+            error,
+            VerificationError::IdentityMismatch { field: IdentityField::EventName, .. }
+        ));
+        assert_eq!(error.to_string(), "Identity event_name mismatch: expected 'pull_request', got 'push'");
+        let without_ref = OidcIdentity { workflow_ref: None, ..identity };
+        assert!(matches!(
+            verify_oidc_identity(Some(&without_ref), &options),
+            Err(VerificationError::IdentityFieldMissing(IdentityField::WorkflowRef))
+        ));
+        assert!(verify_oidc_identity(None, &options).is_err());
+    }
+
+    fn spiffe_identity(spiffe_id: &str) -> OidcIdentity {
+        OidcIdentity {
+            issuer: Some("https://oidc.prod.example.org".to_string()),
+            subject: Some(spiffe_id.to_string()),
+            workflow_ref: None,
+            repository: None,
+            event_name: None,
+            subject_kind: IdentityKind::Spiffe,
+        }
+    }
+
+    #[test]
+    fn test_spiffe_trust_domain_and_path_prefix() {
+        let identity = spiffe_identity("spiffe://prod.example.org/ns/release/sa/builder");
+        let options = VerificationOptions {
+            expected_spiffe_trust_domain: Some("prod.example.org".to_string()),
+            expected_spiffe_path_prefix: Some("/ns/release".to_string()),
+            ..Default::default()
+        };
+        assert!(verify_oidc_identity(Some(&identity), &options).is_ok());
+
+        let other_domain = VerificationOptions {
+            expected_spiffe_trust_domain: Some("staging.example.org".to_string()),
+            ..Default::default()
+        };
+        assert!(verify_oidc_identity(Some(&identity), &other_domain).is_err());
+
+        // Prefixes match whole segments only
+        let partial_segment = VerificationOptions {
+            expected_spiffe_path_prefix: Some("/ns/rel".to_string()),
+            ..Default::default()
+        };
+        assert!(verify_oidc_identity(Some(&identity), &partial_segment).is_err());
+    }
+
+    #[test]
+    fn test_spiffe_options_require_spiffe_identity() {
+        let options = VerificationOptions {
+            expected_spiffe_trust_domain: Some("prod.example.org".to_string()),
+            ..Default::default()
+        };
+        let email = email_identity("alice@example.com", "https://accounts.google.com");
+        assert!(verify_oidc_identity(Some(&email), &options).is_err());
+        assert!(verify_oidc_identity(None, &options).is_err());
+    }
+
+    #[test]
+    fn test_spiffe_path_has_prefix() {
+        assert!(spiffe_path_has_prefix("/ns/prod/sa/x", "/ns/prod"));
+        assert!(spiffe_path_has_prefix("/ns/prod", "/ns/prod/"));
+        assert!(spiffe_path_has_prefix("/ns/prod", "/"));
+        assert!(!spiffe_path_has_prefix("/ns/production", "/ns/prod"));
+        assert!(!spiffe_path_has_prefix("", "/ns"));
+    }
+}
@@ -0,0 +1,134 @@
+use crate::error::VerificationError;
+use crate::types::bundle::SigstoreBundle;
+use crate::types::certificate::CertificateChain;
+use crate::types::result::VerificationLimits;
+
+/// Check the bundle and Fulcio chain against the verification limits
+///
+/// Runs before any signature or proof is verified, so a bundle over the caps is
+/// rejected without doing the expensive work.
+///
+/// # Arguments
+///
+/// * `bundle` - The parsed sigstore bundle
+/// * `trust_bundle` - Fulcio intermediates and root the leaf is verified against
+/// * `limits` - The caps to enforce
+///
+/// # Errors
+///
+/// Returns `VerificationError::LimitExceeded` naming the first cap that is exceeded
+pub fn check_bundle_limits(
+    bundle: &SigstoreBundle,
+    trust_bundle: &CertificateChain,
+    limits: &VerificationLimits,
+) -> Result<(), VerificationError> {
+    check_chain_length(trust_bundle, limits)?;
+
+    check(
+        "DSSE signature count",
+        bundle.signature_count(),
+        limits.max_signatures,
+    )?;
+
+    let material = &bundle.verification_material;
+    if let Some(timestamps) = material
+        .timestamp_verification_data
+        .as_ref()
+        .and_then(|td| td.rfc3161_timestamps.as_ref())
+    {
+        check("RFC 3161 timestamp count", timestamps.len(), limits.max_signatures)?;
+    }
+
+    if let Some(entries) = material.tlog_entries.as_ref() {
+        check("Transparency log entry count", entries.len(), limits.max_signatures)?;
+        for proof in entries.iter().filter_map(|entry| entry.inclusion_proof.as_ref()) {
+            check("Inclusion proof depth", proof.hashes.len(), limits.max_proof_depth)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Check a certificate chain (leaf, intermediates and root) against `max_chain_length`
+///
+/// Used for the Fulcio chain and for TSA chains, which may be embedded in the timestamp.
+pub fn check_chain_length(
+    chain: &CertificateChain,
+    limits: &VerificationLimits,
+) -> Result<(), VerificationError> {
+    check(
+        "Certificate chain length",
+        chain.intermediates.len() + 2,
+        limits.max_chain_length,
+    )
+}
+
+fn check(limit: &'static str, actual: usize, max: usize) -> Result<(), VerificationError> {
+    if actual > max {
+        return Err(VerificationError::LimitExceeded { limit, actual, max });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::bundle::parse_bundle_from_bytes;
+    use crate::types::bundle::BundleContent;
+    use alloc::vec;
+
+    fn sample_bundle() -> SigstoreBundle {
+        // Rekor-timestamped sample, so the inclusion proof is checked too
+        let mut path = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        path.push("../../samples/actions-attest-build-provenance-attestation-13532655.sigstore.json");
+        parse_bundle_from_bytes(&std::fs::read(path).unwrap()).unwrap()
+    }
+
+    fn chain(intermediates: usize) -> CertificateChain {
+        CertificateChain {
+            leaf: vec![],
+            intermediates: vec![vec![]; intermediates],
+            root: vec![],
+        }
+    }
+
+    #[test]
+    fn test_default_limits_accept_sample() {
+        assert!(check_bundle_limits(&sample_bundle(), &chain(1), &VerificationLimits::default()).is_ok());
+    }
+
+    #[test]
+    fn test_proof_depth_limit() {
+        let limits = VerificationLimits {
+            max_proof_depth: 1,
+            ..Default::default()
+        };
+        assert!(matches!(
+            check_bundle_limits(&sample_bundle(), &chain(1), &limits),
+            Err(VerificationError::LimitExceeded { limit: "Inclusion proof depth", .. })
+        ));
+    }
+
+    #[test]
+    fn test_limits_exceeded() {
+        let limits = VerificationLimits {
+            max_chain_length: 3,
+            max_proof_depth: 64,
+            max_signatures: 1,
+        };
+        assert!(matches!(
+            check_bundle_limits(&sample_bundle(), &chain(2), &limits),
+            Err(VerificationError::LimitExceeded { actual: 4, max: 3, .. })
+        ));
+
+        let mut bundle = sample_bundle();
+        if let BundleContent::DsseEnvelope(ref mut envelope) = bundle.content {
+            let signature = envelope.signatures[0].clone();
+            envelope.signatures.push(signature);
+        }
+        assert!(matches!(
+            check_bundle_limits(&bundle, &chain(1), &limits),
+            Err(VerificationError::LimitExceeded { limit: "DSSE signature count", .. })
+        ));
+    }
+}
@@ -1,5 +1,11 @@
 pub mod certificate;
+pub mod identity;
+pub mod limits;
+pub mod policy;
+pub mod profiles;
+pub mod repository_policy;
 pub mod rfc3161;
+pub mod sct;
 pub mod signature;
 pub mod subject;
 pub mod timestamp;
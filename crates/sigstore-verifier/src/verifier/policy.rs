@@ -0,0 +1,325 @@
+//! Identity policies: composable constraints on the signer identity
+//!
+//! The `expected_*` options compare the issuer and subject with a single value
+//! each. An [`IdentityPolicy`] instead describes every identity that is
+//! accepted: an [`IdentityMatcher`] can match the subject with a regular
+//! expression and require a kind of SAN, and matchers are combined with
+//! [`IdentityPolicy::All`] and [`IdentityPolicy::Any`], e.g. to accept the
+//! release workflows of several repositories as well as a few maintainers.
+//!
+//! Policies are plain data, so they travel inside `VerificationOptions` to the
+//! zkVM guests, and are committed in the options hash (see [`IdentityPolicy::hash`]).
+//!
+//! ```json
+//! {"any": [
+//!   {"identity": {
+//!     "issuer": "https://token.actions.githubusercontent.com",
+//!     "subject_regex": "https://github\\.com/acme/[^/]+/\\.github/workflows/release\\.yml@refs/tags/v.*"
+//!   }},
+//!   {"identity": {"san_kind": "Email", "subject_regex": "[^@]+@acme\\.com"}}
+//! ]}
+//! ```
+
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use crate::crypto::hash::sha256;
+use crate::error::VerificationError;
+use crate::types::certificate::{IdentityKind, OidcIdentity};
+use crate::verifier::identity::normalize_issuer;
+#[cfg(feature = "std")]
+use regex_lite::Regex;
+use serde::{Deserialize, Serialize};
+
+/// Constraint on the identity of the signer
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IdentityPolicy {
+    /// Accepts identities accepted by every policy
+    All(Vec<IdentityPolicy>),
+    /// Accepts identities accepted by at least one policy
+    Any(Vec<IdentityPolicy>),
+    /// Accepts identities matched by the matcher
+    Identity(IdentityMatcher),
+}
+
+/// A single allowed identity; every field that is set must match
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct IdentityMatcher {
+    /// OIDC issuer, compared as a normalized URL with `normalize_issuer`
+    #[serde(default)]
+    pub issuer: Option<String>,
+
+    /// Subject (SAN), compared exactly
+    #[serde(default)]
+    pub subject: Option<String>,
+
+    /// Regular expression the whole subject must match (`regex-lite` syntax)
+    ///
+    /// Requires the `std` feature: without it, a policy with a pattern is rejected.
+    #[serde(default)]
+    pub subject_regex: Option<String>,
+
+    /// Kind of SAN the subject must be, e.g. `Email` to accept only humans
+    #[serde(default)]
+    pub san_kind: Option<IdentityKind>,
+}
+
+impl IdentityPolicy {
+    /// Check that the policy is well-formed
+    ///
+    /// # Errors
+    ///
+    /// Returns `VerificationError::InvalidIdentityPolicy` if a regular expression
+    /// doesn't compile, an `all` or `any` is empty, or a matcher sets no field
+    /// (and would thus accept anyone)
+    pub fn validate(&self) -> Result<(), VerificationError> {
+        match self {
+            IdentityPolicy::All(policies) | IdentityPolicy::Any(policies) => {
+                if policies.is_empty() {
+                    return Err(VerificationError::InvalidIdentityPolicy(
+                        "empty 'all' or 'any'".to_string(),
+                    ));
+                }
+                policies.iter().try_for_each(IdentityPolicy::validate)
+            }
+            IdentityPolicy::Identity(matcher) => {
+                if *matcher == IdentityMatcher::default() {
+                    return Err(VerificationError::InvalidIdentityPolicy(
+                        "identity matcher without any constraint".to_string(),
+                    ));
+                }
+                matcher.subject_regex.as_deref().map(compile).transpose().map(|_| ())
+            }
+        }
+    }
+
+    /// Check the signer identity against the policy
+    ///
+    /// # Arguments
+    ///
+    /// * `identity` - Identity extracted from the leaf certificate, if any
+    /// * `normalize` - Compare issuers as [normalized URLs](normalize_issuer)
+    ///
+    /// # Errors
+    ///
+    /// Returns `VerificationError::InvalidIdentityPolicy` if the policy is not
+    /// well-formed (see [`Self::validate`]), and
+    /// `VerificationError::IdentityPolicyRejected` if the certificate has no
+    /// identity or the policy doesn't accept it
+    pub fn evaluate(&self, identity: Option<&OidcIdentity>, normalize: bool) -> Result<(), VerificationError> {
+        self.validate()?;
+        let Some(identity) = identity else {
+            return Err(VerificationError::IdentityPolicyRejected(
+                "the certificate carries no identity".to_string(),
+            ));
+        };
+
+        if self.accepts(identity, normalize)? {
+            Ok(())
+        } else {
+            Err(VerificationError::IdentityPolicyRejected(format!(
+                "issuer '{}', subject '{}' ({:?})",
+                identity.issuer.as_deref().unwrap_or_default(),
+                identity.subject.as_deref().unwrap_or_default(),
+                identity.subject_kind
+            )))
+        }
+    }
+
+    /// SHA256 of the policy's JSON encoding, committed in the options hash
+    pub fn hash(&self) -> [u8; 32] {
+        // Serializing plain data with derived impls cannot fail
+        sha256(&serde_json::to_vec(self).expect("identity policy is serializable"))
+    }
+
+    fn accepts(&self, identity: &OidcIdentity, normalize: bool) -> Result<bool, VerificationError> {
+        match self {
+            IdentityPolicy::All(policies) => {
+                for policy in policies {
+                    if !policy.accepts(identity, normalize)? {
+                        return Ok(false);
+                    }
+                }
+                Ok(true)
+            }
+            IdentityPolicy::Any(policies) => {
+                for policy in policies {
+                    if policy.accepts(identity, normalize)? {
+                        return Ok(true);
+                    }
+                }
+                Ok(false)
+            }
+            IdentityPolicy::Identity(matcher) => matcher.matches(identity, normalize),
+        }
+    }
+}
+
+impl IdentityMatcher {
+    fn matches(&self, identity: &OidcIdentity, normalize: bool) -> Result<bool, VerificationError> {
+        if let Some(ref expected) = self.issuer {
+            let matches = match identity.issuer.as_deref() {
+                Some(issuer) if normalize => normalize_issuer(expected) == normalize_issuer(issuer),
+                Some(issuer) => expected == issuer,
+                None => false,
+            };
+            if !matches {
+                return Ok(false);
+            }
+        }
+
+        if let Some(kind) = self.san_kind {
+            if identity.subject_kind != kind {
+                return Ok(false);
+            }
+        }
+
+        let subject = identity.subject.as_deref();
+        if let Some(ref expected) = self.subject {
+            if subject != Some(expected.as_str()) {
+                return Ok(false);
+            }
+        }
+        if let Some(ref pattern) = self.subject_regex {
+            let Some(subject) = subject else {
+                return Ok(false);
+            };
+            if !compile(pattern)?.is_match(subject) {
+                return Ok(false);
+            }
+        }
+
+        Ok(true)
+    }
+}
+
+/// Compile `pattern` so that it must match the whole input
+#[cfg(feature = "std")]
+fn compile(pattern: &str) -> Result<Regex, VerificationError> {
+    Regex::new(&format!("^(?:{})$", pattern)).map_err(|e| {
+        VerificationError::InvalidIdentityPolicy(format!("invalid regular expression '{}': {}", pattern, e))
+    })
+}
+
+/// `regex-lite` needs `std`; without it, policies with a pattern are rejected
+#[cfg(not(feature = "std"))]
+fn compile(pattern: &str) -> Result<NoRegex, VerificationError> {
+    Err(VerificationError::InvalidIdentityPolicy(format!(
+        "regular expression '{}' requires the std feature",
+        pattern
+    )))
+}
+
+#[cfg(not(feature = "std"))]
+enum NoRegex {}
+
+#[cfg(not(feature = "std"))]
+impl NoRegex {
+    fn is_match(&self, _subject: &str) -> bool {
+        match *self {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    const GITHUB: &str = "https://token.actions.githubusercontent.com";
+
+    fn identity(issuer: &str, subject: &str, subject_kind: IdentityKind) -> OidcIdentity {
+        OidcIdentity {
+            issuer: Some(issuer.to_string()),
+            subject: Some(subject.to_string()),
+            workflow_ref: None,
+            repository: None,
+            event_name: None,
+            subject_kind,
+        }
+    }
+
+    fn release_workflows() -> IdentityPolicy {
+        IdentityPolicy::Identity(IdentityMatcher {
+            issuer: Some(GITHUB.to_string()),
+            subject_regex: Some(r"https://github\.com/acme/[^/]+/\.github/workflows/release\.yml@refs/tags/v.*".to_string()),
+            san_kind: Some(IdentityKind::Uri),
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn test_regex_matches_the_whole_subject() {
+        let policy = release_workflows();
+        let release = "https://github.com/acme/app/.github/workflows/release.yml@refs/tags/v1.2.0";
+        assert!(policy.evaluate(Some(&identity(GITHUB, release, IdentityKind::Uri)), false).is_ok());
+
+        // Another repository owner, a branch, or the subject embedded in a longer one
+        for subject in [
+            "https://github.com/evil/app/.github/workflows/release.yml@refs/tags/v1.2.0",
+            "https://github.com/acme/app/.github/workflows/release.yml@refs/heads/main",
+            "https://evil.example/https://github.com/acme/app/.github/workflows/release.yml@refs/tags/v1",
+        ] {
+            let result = policy.evaluate(Some(&identity(GITHUB, subject, IdentityKind::Uri)), false);
+            assert!(matches!(result, Err(VerificationError::IdentityPolicyRejected(_))), "{}", subject);
+        }
+    }
+
+    #[test]
+    fn test_any_and_all_composition() {
+        let maintainer = IdentityPolicy::Identity(IdentityMatcher {
+            subject_regex: Some(r"[^@]+@acme\.com".to_string()),
+            san_kind: Some(IdentityKind::Email),
+            ..Default::default()
+        });
+        let policy = IdentityPolicy::Any(vec![release_workflows(), maintainer.clone()]);
+
+        let alice = identity("https://accounts.google.com", "alice@acme.com", IdentityKind::Email);
+        assert!(policy.evaluate(Some(&alice), false).is_ok());
+        // An email-looking URI SAN is not an email identity
+        let uri = identity("https://accounts.google.com", "alice@acme.com", IdentityKind::Uri);
+        assert!(policy.evaluate(Some(&uri), false).is_err());
+
+        let google_only = IdentityPolicy::Identity(IdentityMatcher {
+            issuer: Some("https://accounts.google.com/".to_string()),
+            ..Default::default()
+        });
+        let policy = IdentityPolicy::All(vec![maintainer, google_only]);
+        assert!(policy.evaluate(Some(&alice), false).is_err());
+        assert!(policy.evaluate(Some(&alice), true).is_ok());
+        let github_login = identity("https://github.com/login/oauth", "alice@acme.com", IdentityKind::Email);
+        assert!(policy.evaluate(Some(&github_login), true).is_err());
+        assert!(policy.evaluate(None, true).is_err());
+    }
+
+    #[test]
+    fn test_malformed_policies_are_rejected() {
+        let invalid_regex = IdentityPolicy::Identity(IdentityMatcher {
+            subject_regex: Some("(unclosed".to_string()),
+            ..Default::default()
+        });
+        let unconstrained = IdentityPolicy::Any(vec![IdentityPolicy::Identity(IdentityMatcher::default())]);
+        for policy in [invalid_regex, unconstrained, IdentityPolicy::All(vec![])] {
+            assert!(matches!(policy.validate(), Err(VerificationError::InvalidIdentityPolicy(_))));
+        }
+    }
+
+    #[test]
+    fn test_policy_json() {
+        let json = r#"{"any": [{"identity": {"issuer": "https://accounts.google.com", "san_kind": "Email"}}]}"#;
+        let policy: IdentityPolicy = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            policy,
+            IdentityPolicy::Any(vec![IdentityPolicy::Identity(IdentityMatcher {
+                issuer: Some("https://accounts.google.com".to_string()),
+                san_kind: Some(IdentityKind::Email),
+                ..Default::default()
+            })])
+        );
+        assert_ne!(policy.hash(), release_workflows().hash());
+
+        // Misspelled fields are errors rather than silently unconstrained
+        assert!(serde_json::from_str::<IdentityPolicy>(r#"{"identity": {"subject_regexp": ".*"}}"#).is_err());
+    }
+}
@@ -0,0 +1,284 @@
+//! Verification profiles: named, versioned presets of verification options
+//!
+//! Relying parties usually want one of a few well-known option sets, e.g. "a
+//! release workflow on GitHub Actions with SLSA provenance". A profile bundles
+//! such a set under a name and a version, and is selected by setting
+//! `VerificationOptions::profile` to `name` (the latest version) or
+//! `name@version`. The verifier then adds the profile's constraints to the
+//! options (see [`VerificationProfile::apply`]) and commits the result together
+//! with the profile's `name@version`, so a journal shows which preset governed
+//! verification.
+//!
+//! A version of a profile never changes once released; changed constraints
+//! ship as a new version, so journals committing `github-strict@1` keep their
+//! meaning.
+
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use crate::error::VerificationError;
+use crate::types::certificate::IdentityKind;
+use crate::types::result::{TimestampKind, VerificationOptions};
+use crate::verifier::policy::{IdentityMatcher, IdentityPolicy};
+
+const GITHUB_ACTIONS_ISSUER: &str = "https://token.actions.githubusercontent.com";
+
+/// OIDC providers federated by the Sigstore public-good Fulcio instance
+const PUBLIC_GOOD_ISSUERS: [&str; 6] = [
+    GITHUB_ACTIONS_ISSUER,
+    "https://accounts.google.com",
+    "https://github.com/login/oauth",
+    "https://login.microsoftonline.com",
+    "https://gitlab.com",
+    "https://oauth2.sigstore.dev/auth",
+];
+
+/// A named, versioned set of constraints
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VerificationProfile {
+    pub name: &'static str,
+    pub version: u32,
+    pub description: &'static str,
+    pub expected_issuer: Option<&'static str>,
+    pub identity_policy: Option<IdentityPolicy>,
+    pub expected_predicate_type: Option<&'static str>,
+    pub required_timestamp: Option<TimestampKind>,
+    /// Largest clock skew, in seconds, the profile tolerates
+    pub clock_skew: u64,
+    pub normalize_issuer: bool,
+    pub verify_all_signatures: bool,
+}
+
+/// Every version of every profile shipped with the crate
+pub fn profiles() -> Vec<VerificationProfile> {
+    vec![github_strict_v1(), public_good_default_v1()]
+}
+
+/// `github-strict@1`: GitHub Actions workflows of github.com repositories
+fn github_strict_v1() -> VerificationProfile {
+    VerificationProfile {
+        name: "github-strict",
+        version: 1,
+        description: "Workflows of github.com repositories signing SLSA v1 provenance with \
+                      GitHub Actions identities; every DSSE signature must verify",
+        expected_issuer: Some(GITHUB_ACTIONS_ISSUER),
+        identity_policy: Some(IdentityPolicy::Identity(IdentityMatcher {
+            subject_regex: Some(r"https://github\.com/[^/]+/[^/]+/\.github/workflows/[^@]+@refs/.+".to_string()),
+            san_kind: Some(IdentityKind::Uri),
+            ..Default::default()
+        })),
+        expected_predicate_type: Some("https://slsa.dev/provenance/v1"),
+        required_timestamp: None,
+        clock_skew: 0,
+        normalize_issuer: false,
+        verify_all_signatures: true,
+    }
+}
+
+/// `public-good-default@1`: keyless signing on the Sigstore public-good instance
+fn public_good_default_v1() -> VerificationProfile {
+    let issuers = PUBLIC_GOOD_ISSUERS
+        .iter()
+        .map(|issuer| {
+            IdentityPolicy::Identity(IdentityMatcher {
+                issuer: Some(issuer.to_string()),
+                ..Default::default()
+            })
+        })
+        .collect();
+
+    VerificationProfile {
+        name: "public-good-default",
+        version: 1,
+        description: "Keyless signatures from the OIDC providers of the Sigstore public-good \
+                      instance, timestamped by the Rekor transparency log",
+        expected_issuer: None,
+        identity_policy: Some(IdentityPolicy::Any(issuers)),
+        expected_predicate_type: None,
+        required_timestamp: Some(TimestampKind::Rekor),
+        clock_skew: 60,
+        normalize_issuer: true,
+        verify_all_signatures: false,
+    }
+}
+
+impl VerificationProfile {
+    /// Look up a profile by `name` (its latest version) or `name@version`
+    ///
+    /// # Errors
+    ///
+    /// Returns `VerificationError::UnknownProfile` if no shipped profile has that
+    /// name and version
+    pub fn resolve(spec: &str) -> Result<Self, VerificationError> {
+        let unknown = || VerificationError::UnknownProfile(spec.to_string());
+        let (name, version) = match spec.split_once('@') {
+            Some((name, version)) => (name, Some(version.parse::<u32>().map_err(|_| unknown())?)),
+            None => (spec, None),
+        };
+
+        profiles()
+            .into_iter()
+            .filter(|profile| profile.name == name && version.is_none_or(|version| profile.version == version))
+            .max_by_key(|profile| profile.version)
+            .ok_or_else(unknown)
+    }
+
+    /// The `name@version` committed in the options hash
+    pub fn id(&self) -> String {
+        format!("{}@{}", self.name, self.version)
+    }
+
+    /// Add the profile's constraints to `options`
+    ///
+    /// Constraints the caller set as well must agree with the profile's: a
+    /// profile can be narrowed (a more specific identity policy, a smaller clock
+    /// skew) but not loosened. The identity policies are combined with
+    /// [`IdentityPolicy::All`], and `options.profile` is set to [`Self::id`].
+    /// Applying a profile to options it was already applied to changes nothing.
+    ///
+    /// # Errors
+    ///
+    /// Returns `VerificationError::ProfileConflict` if an option contradicts the
+    /// profile, e.g. another expected issuer or a larger clock skew
+    pub fn apply(&self, mut options: VerificationOptions) -> Result<VerificationOptions, VerificationError> {
+        let conflict = |reason: String| VerificationError::ProfileConflict {
+            profile: self.id(),
+            reason,
+        };
+
+        merge("expected issuer", &mut options.expected_issuer, self.expected_issuer).map_err(conflict)?;
+        merge(
+            "expected predicate type",
+            &mut options.expected_predicate_type,
+            self.expected_predicate_type,
+        )
+        .map_err(conflict)?;
+        if let Some(required) = self.required_timestamp {
+            match options.required_timestamp {
+                Some(kind) if kind != required => {
+                    return Err(conflict(format!("requires a {} timestamp, not {}", required, kind)))
+                }
+                _ => options.required_timestamp = Some(required),
+            }
+        }
+        if options.clock_skew > self.clock_skew {
+            return Err(conflict(format!(
+                "tolerates a clock skew of {} seconds, not {}",
+                self.clock_skew, options.clock_skew
+            )));
+        }
+        if options.clock_skew == 0 {
+            options.clock_skew = self.clock_skew;
+        }
+
+        if let Some(ref policy) = self.identity_policy {
+            options.identity_policy = Some(match options.identity_policy.take() {
+                None => policy.clone(),
+                Some(existing) if is_applied(&existing, policy) => existing,
+                Some(existing) => IdentityPolicy::All(vec![policy.clone(), existing]),
+            });
+        }
+        options.normalize_issuer |= self.normalize_issuer;
+        options.verify_all_signatures |= self.verify_all_signatures;
+
+        match options.profile {
+            Some(ref spec) if VerificationProfile::resolve(spec)? != *self => {
+                return Err(conflict(format!("the options select profile '{}'", spec)))
+            }
+            _ => options.profile = Some(self.id()),
+        }
+        Ok(options)
+    }
+}
+
+/// Set `option` to the profile's `value`, unless the caller already chose the same
+fn merge(name: &str, option: &mut Option<String>, value: Option<&str>) -> Result<(), String> {
+    match (option.as_deref(), value) {
+        (Some(current), Some(value)) if current != value => {
+            Err(format!("requires the {} '{}', not '{}'", name, value, current))
+        }
+        (None, Some(value)) => {
+            *option = Some(value.to_string());
+            Ok(())
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Whether `policy` already enforces the profile policy `profile`
+fn is_applied(policy: &IdentityPolicy, profile: &IdentityPolicy) -> bool {
+    match policy {
+        IdentityPolicy::All(policies) => policy == profile || policies.first() == Some(profile),
+        _ => policy == profile,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_by_name_and_version() {
+        assert_eq!(VerificationProfile::resolve("github-strict").unwrap().id(), "github-strict@1");
+        assert_eq!(
+            VerificationProfile::resolve("public-good-default@1").unwrap().id(),
+            "public-good-default@1"
+        );
+        for spec in ["github-strict@2", "github-strict@latest", "github", ""] {
+            assert!(matches!(
+                VerificationProfile::resolve(spec),
+                Err(VerificationError::UnknownProfile(_))
+            ));
+        }
+        for profile in profiles() {
+            assert!(profile.identity_policy.iter().all(|policy| policy.validate().is_ok()));
+        }
+    }
+
+    #[test]
+    fn test_apply_adds_constraints_once() {
+        let profile = VerificationProfile::resolve("github-strict").unwrap();
+        let caller_policy = IdentityPolicy::Identity(IdentityMatcher {
+            subject_regex: Some(r"https://github\.com/acme/.*".to_string()),
+            ..Default::default()
+        });
+        let options = VerificationOptions::builder()
+            .profile("github-strict")
+            .identity_policy(caller_policy.clone())
+            .build();
+
+        let applied = profile.apply(options).unwrap();
+        assert_eq!(applied.profile.as_deref(), Some("github-strict@1"));
+        assert_eq!(applied.expected_issuer.as_deref(), Some(GITHUB_ACTIONS_ISSUER));
+        assert!(applied.verify_all_signatures);
+        assert_eq!(
+            applied.identity_policy,
+            Some(IdentityPolicy::All(vec![profile.identity_policy.clone().unwrap(), caller_policy]))
+        );
+        assert_eq!(profile.apply(applied.clone()).unwrap().hash(), applied.hash());
+    }
+
+    #[test]
+    fn test_apply_rejects_loosened_constraints() {
+        let profile = VerificationProfile::resolve("public-good-default").unwrap();
+        let conflicting = [
+            VerificationOptions::builder().clock_skew(61).build(),
+            VerificationOptions::builder().required_timestamp(TimestampKind::Rfc3161).build(),
+            VerificationOptions::builder().profile("github-strict").build(),
+        ];
+        for options in conflicting {
+            assert!(matches!(profile.apply(options), Err(VerificationError::ProfileConflict { .. })));
+        }
+
+        let narrowed = profile.apply(VerificationOptions::builder().clock_skew(5).build()).unwrap();
+        assert_eq!(narrowed.clock_skew, 5);
+        assert_eq!(narrowed.required_timestamp, Some(TimestampKind::Rekor));
+
+        let github = VerificationProfile::resolve("github-strict").unwrap();
+        let other_issuer = VerificationOptions::builder()
+            .expected_issuer("https://accounts.google.com")
+            .build();
+        assert!(github.apply(other_issuer).is_err());
+    }
+}
@@ -0,0 +1,223 @@
+//! Repository policies: per-repository rules of a proving service
+//!
+//! A service proving bundles for several organisations enforces different
+//! rules for each, e.g. `acme` releases must come from one workflow and carry an
+//! RFC 3161 timestamp, while `contoso` bundles must be less than a day old. Each
+//! rule is a [`RepositoryPolicy`] whose `repository` pattern selects the bundles
+//! it governs. The caller picks the entry matching the bundle's repository and
+//! sets it as `VerificationOptions::repository_policy`; the verifier adds its
+//! constraints to the options (see [`RepositoryPolicy::apply`]), checks the
+//! signer's repository and workflow against it (see [`RepositoryPolicy::check`])
+//! and commits the options with the entry's [hash](RepositoryPolicy::hash), so a
+//! journal shows which rule governed verification.
+
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use serde::{Deserialize, Serialize};
+
+use crate::crypto::hash::sha256;
+use crate::error::VerificationError;
+use crate::types::certificate::OidcIdentity;
+use crate::types::dsse::subject_name_matches;
+use crate::types::result::{TimestampKind, VerificationOptions};
+
+/// Constraints for the bundles of the repositories matching `repository`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RepositoryPolicy {
+    /// Exact name or `*`/`?` glob the source repository recorded in the
+    /// certificate must match (e.g. "https://github.com/acme/*")
+    pub repository: String,
+
+    /// OIDC issuer the signer must be vouched for by
+    #[serde(default)]
+    pub issuer: Option<String>,
+
+    /// Exact names or `*`/`?` globs of which the signer subject must match one;
+    /// for CI identities the subject is the workflow URI (e.g.
+    /// "https://github.com/acme/*/.github/workflows/release.yml@*"). Any
+    /// workflow is accepted when empty.
+    #[serde(default)]
+    pub workflows: Vec<String>,
+
+    /// Timestamp mechanism the bundle must use (`Rfc3161` or `Rekor`)
+    #[serde(default)]
+    pub timestamp: Option<TimestampKind>,
+
+    /// Maximum age of the signature, in seconds, at the verification time
+    #[serde(default)]
+    pub max_age: Option<u64>,
+}
+
+impl RepositoryPolicy {
+    /// Whether the policy governs bundles signed for `repository`
+    pub fn governs(&self, repository: &str) -> bool {
+        subject_name_matches(&self.repository, repository)
+    }
+
+    /// SHA256 of the JSON encoding of the policy, committed in the options hash
+    pub fn hash(&self) -> [u8; 32] {
+        // Serializing plain data with derived impls cannot fail
+        sha256(&serde_json::to_vec(self).expect("repository policy is serializable"))
+    }
+
+    /// Add the policy's issuer, timestamp and age constraints to `options`
+    ///
+    /// Like a verification profile, the policy can be narrowed (a smaller
+    /// maximum age) but not loosened. Applying it to options it was already
+    /// applied to changes nothing.
+    ///
+    /// # Errors
+    ///
+    /// Returns `VerificationError::RepositoryPolicyConflict` if an option
+    /// contradicts the policy, e.g. another expected issuer, or if the policy
+    /// bounds the age but the options fix no verification time
+    pub fn apply(&self, mut options: VerificationOptions) -> Result<VerificationOptions, VerificationError> {
+        let conflict = |reason: String| VerificationError::RepositoryPolicyConflict {
+            repository: self.repository.clone(),
+            reason,
+        };
+
+        if let Some(ref issuer) = self.issuer {
+            match options.expected_issuer {
+                Some(ref expected) if expected != issuer => {
+                    return Err(conflict(format!("requires the issuer '{}', not '{}'", issuer, expected)))
+                }
+                _ => options.expected_issuer = Some(issuer.clone()),
+            }
+        }
+        if let Some(required) = self.timestamp {
+            match options.required_timestamp {
+                Some(kind) if kind != required => {
+                    return Err(conflict(format!("requires a {} timestamp, not {}", required, kind)))
+                }
+                _ => options.required_timestamp = Some(required),
+            }
+        }
+        if let Some(max_age) = self.max_age {
+            if options.verification_time.is_none() {
+                return Err(conflict("bounds the signature age but no verification time is set".to_string()));
+            }
+            options.max_signing_age = Some(options.max_signing_age.map_or(max_age, |age| age.min(max_age)));
+        }
+        Ok(options)
+    }
+
+    /// Check the signer's repository and workflow against the policy
+    ///
+    /// # Errors
+    ///
+    /// Returns `VerificationError::RepositoryPolicyRejected` if the certificate
+    /// records no repository or one the policy doesn't govern, or a subject
+    /// matching none of the workflows
+    pub fn check(&self, identity: Option<&OidcIdentity>) -> Result<(), VerificationError> {
+        let rejected = VerificationError::RepositoryPolicyRejected;
+
+        let repository = identity
+            .and_then(|identity| identity.repository.as_deref())
+            .ok_or_else(|| rejected("the certificate records no source repository".to_string()))?;
+        if !self.governs(repository) {
+            return Err(rejected(format!(
+                "repository '{}' doesn't match '{}'",
+                repository, self.repository
+            )));
+        }
+
+        if !self.workflows.is_empty() {
+            let subject = identity.and_then(|identity| identity.subject.as_deref()).unwrap_or_default();
+            if !self.workflows.iter().any(|workflow| subject_name_matches(workflow, subject)) {
+                return Err(rejected(format!(
+                    "workflow '{}' is not one of the {} allowed for '{}'",
+                    subject,
+                    self.workflows.len(),
+                    self.repository
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::certificate::IdentityKind;
+    use alloc::vec;
+
+    fn acme_policy() -> RepositoryPolicy {
+        RepositoryPolicy {
+            repository: "https://github.com/acme/*".to_string(),
+            issuer: Some("https://token.actions.githubusercontent.com".to_string()),
+            workflows: vec!["https://github.com/acme/*/.github/workflows/release.yml@*".to_string()],
+            timestamp: Some(TimestampKind::Rfc3161),
+            max_age: Some(86_400),
+        }
+    }
+
+    fn identity(repository: &str, workflow: &str) -> OidcIdentity {
+        OidcIdentity {
+            issuer: Some("https://token.actions.githubusercontent.com".to_string()),
+            subject: Some(format!("{}/.github/workflows/{}@refs/tags/v1", repository, workflow)),
+            workflow_ref: Some("refs/tags/v1".to_string()),
+            repository: Some(repository.to_string()),
+            event_name: Some("push".to_string()),
+            subject_kind: IdentityKind::Uri,
+        }
+    }
+
+    #[test]
+    fn test_apply_adds_constraints_once() {
+        let policy = acme_policy();
+        let options = VerificationOptions::builder()
+            .max_signing_age(3_600, 1_700_000_000)
+            .build();
+
+        let applied = policy.apply(options).unwrap();
+        assert_eq!(applied.expected_issuer, policy.issuer);
+        assert_eq!(applied.required_timestamp, Some(TimestampKind::Rfc3161));
+        assert_eq!(applied.max_signing_age, Some(3_600));
+        assert_eq!(policy.apply(applied.clone()).unwrap().hash(), applied.hash());
+
+        // Other issuers and timestamps contradict the policy, a missing time can't bound the age
+        let conflicting = [
+            VerificationOptions::builder().expected_issuer("https://gitlab.com").build(),
+            VerificationOptions::builder().required_timestamp(TimestampKind::Rekor).build(),
+            VerificationOptions::default(),
+        ];
+        for options in conflicting {
+            assert!(matches!(
+                policy.apply(options),
+                Err(VerificationError::RepositoryPolicyConflict { .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn test_check_repository_and_workflow() {
+        let policy = acme_policy();
+        assert!(policy.check(Some(&identity("https://github.com/acme/app", "release.yml"))).is_ok());
+
+        let rejected = [
+            Some(identity("https://github.com/contoso/app", "release.yml")),
+            Some(identity("https://github.com/acme/app", "nightly.yml")),
+            Some(OidcIdentity {
+                repository: None,
+                ..identity("https://github.com/acme/app", "release.yml")
+            }),
+            None,
+        ];
+        for identity in rejected {
+            assert!(matches!(
+                policy.check(identity.as_ref()),
+                Err(VerificationError::RepositoryPolicyRejected(_))
+            ));
+        }
+
+        let any_workflow = RepositoryPolicy {
+            workflows: vec![],
+            ..acme_policy()
+        };
+        assert!(any_workflow.check(Some(&identity("https://github.com/acme/app", "nightly.yml"))).is_ok());
+    }
+}
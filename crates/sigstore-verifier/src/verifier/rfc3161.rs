@@ -1,24 +1,83 @@
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
 use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
 use chrono::{DateTime, Utc};
 
-use crate::error::TimestampError;
+use crate::crypto::backend::{self, DigestAlgorithm};
+use crate::crypto::signature::{rsa_pss_parameters, PublicKey};
+use crate::error::{SignatureError, TimestampError};
 use crate::parser::certificate::parse_der_certificate;
-use crate::parser::rfc3161::{parse_rfc3161_timestamp, MessageImprint, Rfc3161Timestamp};
+use crate::parser::rfc3161::{parse_rfc3161_timestamp, HashAlgorithm, MessageImprint, Rfc3161Timestamp, TSTInfo};
 use crate::types::bundle::SigstoreBundle;
 use crate::types::certificate::CertificateChain;
+use cms::signed_data::{SignedAttributes, SignedData, SignerInfo};
+use der::asn1::{Any, ObjectIdentifier, OctetString, OctetStringRef};
+use der::Sequence;
+use x509_cert::ext::pkix::name::{GeneralName, GeneralNames};
+use x509_cert::serial_number::SerialNumber;
+use x509_cert::spki::AlgorithmIdentifierOwned;
+use x509_parser::certificate::X509Certificate;
+
+/// Attributes RFC 5652 (section 5.3) requires among the signed attributes
+const CONTENT_TYPE_OID: &str = "1.2.840.113549.1.9.3";
+const MESSAGE_DIGEST_OID: &str = "1.2.840.113549.1.9.4";
+
+/// Signed attributes naming the signing certificate (RFC 2634 section 5.4, RFC 5035 section 3)
+const SIGNING_CERTIFICATE_OID: &str = "1.2.840.113549.1.9.16.2.12";
+const SIGNING_CERTIFICATE_V2_OID: &str = "1.2.840.113549.1.9.16.2.47";
+
+/// `SigningCertificate ::= SEQUENCE { certs SEQUENCE OF ESSCertID, policies SEQUENCE OF PolicyInformation OPTIONAL }`
+#[derive(Debug, Sequence)]
+struct SigningCertificate {
+    certs: Vec<EssCertId>,
+    policies: Option<Vec<Any>>,
+}
+
+/// `ESSCertID ::= SEQUENCE { certHash OCTET STRING, issuerSerial IssuerSerial OPTIONAL }`, a SHA-1 certificate hash
+#[derive(Debug, Sequence)]
+struct EssCertId {
+    cert_hash: OctetString,
+    issuer_serial: Option<IssuerSerial>,
+}
+
+/// `SigningCertificateV2 ::= SEQUENCE { certs SEQUENCE OF ESSCertIDv2, policies SEQUENCE OF PolicyInformation OPTIONAL }`
+#[derive(Debug, Sequence)]
+struct SigningCertificateV2 {
+    certs: Vec<EssCertIdV2>,
+    policies: Option<Vec<Any>>,
+}
+
+/// `ESSCertIDv2 ::= SEQUENCE { hashAlgorithm AlgorithmIdentifier DEFAULT sha256, certHash OCTET STRING,
+/// issuerSerial IssuerSerial OPTIONAL }`
+#[derive(Debug, Sequence)]
+struct EssCertIdV2 {
+    hash_algorithm: Option<AlgorithmIdentifierOwned>,
+    cert_hash: OctetString,
+    issuer_serial: Option<IssuerSerial>,
+}
+
+/// `IssuerSerial ::= SEQUENCE { issuer GeneralNames, serialNumber CertificateSerialNumber }`
+#[derive(Debug, Sequence)]
+struct IssuerSerial {
+    issuer: GeneralNames,
+    serial_number: SerialNumber,
+}
 
 /// Verify RFC 3161 timestamp token
 ///
 /// This function:
 /// 1. Parses the RFC 3161 timestamp from the bundle
 /// 2. Verifies the message imprint matches the DSSE signature bytes
-/// 3. Verifies the PKCS#7 signature on the timestamp token
+/// 3. Verifies the PKCS#7 signature on the timestamp token, and its signed attributes,
+///    which must name the TSA leaf certificate as the signing certificate
 /// 4. Returns the signing time from the timestamp
 ///
 /// # Arguments
 ///
 /// * `bundle` - The sigstore bundle containing the RFC 3161 timestamp
-/// * `signature_b64` - Base64-encoded DSSE signature bytes
+/// * `signature` - Decoded signature bytes (DSSE or message signature)
 /// * `tsa_chain` - TSA certificate chain for verification
 ///
 /// # Returns
@@ -26,7 +85,7 @@ use crate::types::certificate::CertificateChain;
 /// The signing time from the timestamp token on success
 pub fn verify_rfc3161_timestamp(
     bundle: &SigstoreBundle,
-    signature_b64: &str,
+    signature: &[u8],
     tsa_chain: &CertificateChain,
 ) -> Result<DateTime<Utc>, TimestampError> {
     // Extract RFC 3161 timestamp from bundle
@@ -52,13 +111,8 @@ pub fn verify_rfc3161_timestamp(
     // Parse the RFC 3161 timestamp token
     let parsed_timestamp = parse_rfc3161_timestamp(&timestamp_der)?;
 
-    // Decode the DSSE signature bytes
-    let signature_bytes = BASE64
-        .decode(signature_b64)
-        .map_err(|e| TimestampError::Rfc3161Parse(format!("Failed to decode signature base64: {}", e)))?;
-
     // Verify message imprint matches the signature
-    verify_message_imprint(&signature_bytes, &parsed_timestamp.tst_info.message_imprint)?;
+    verify_message_imprint(signature, &parsed_timestamp.tst_info.message_imprint)?;
 
     // Verify PKCS#7 signature on the timestamp token
     // Use the signed_data we already extracted during parsing
@@ -67,6 +121,17 @@ pub fn verify_rfc3161_timestamp(
     Ok(parsed_timestamp.tst_info.gen_time)
 }
 
+/// Verify that the timestamp was issued under the TSA policy `expected`, a dotted OID
+pub fn verify_tsa_policy(tst_info: &TSTInfo, expected: &str) -> Result<(), TimestampError> {
+    if tst_info.policy != expected {
+        return Err(TimestampError::Rfc3161PolicyMismatch {
+            expected: expected.to_string(),
+            actual: tst_info.policy.clone(),
+        });
+    }
+    Ok(())
+}
+
 /// Verify that the message imprint in the timestamp matches the hash of the signature bytes
 ///
 /// This is the core verification that proves the timestamp is for this specific signature.
@@ -102,7 +167,6 @@ fn verify_pkcs7_signature(
     signed_data_bytes: &[u8],
     tsa_chain: &CertificateChain,
 ) -> Result<(), TimestampError> {
-    use cms::signed_data::SignedData;
     use der::Decode;
 
     // Parse SignedData
@@ -118,40 +182,17 @@ fn verify_pkcs7_signature(
     let signer_info = signed_data.signer_infos.0.iter().next()
         .ok_or_else(|| TimestampError::Rfc3161SignatureInvalid)?;
 
-    // Determine what content was actually signed
-    // If signed attributes are present, the signature is over the DER encoding of the attributes
-    // Otherwise, it's over the encapsulated content
-    let signed_content_bytes = if let Some(ref signed_attrs) = signer_info.signed_attrs {
-        // When signed attributes are present, the signature is over the DER encoding
-        // of the SET OF Attribute structure
-        // The signed_attrs field has tag [0] IMPLICIT, but for signing we need SET (tag 0x31)
-        use der::Encode;
-        let mut attrs_der = signed_attrs.to_der()
-            .map_err(|e| TimestampError::Rfc3161Parse(format!("Failed to encode signed attributes: {}", e)))?;
-
-        // Replace the [0] IMPLICIT tag (0xA0) with SET tag (0x31)
-        // Per RFC 5652, the signature is computed over the DER encoding of the
-        // signedAttrs field with the tag changed from [0] IMPLICIT to SET
-        if !attrs_der.is_empty() && attrs_der[0] == 0xA0 {
-            attrs_der[0] = 0x31; // SET tag
-        }
-
-        attrs_der
-    } else {
-        // No signed attributes - signature is directly over the encapsulated content
-        signed_data
-            .encap_content_info
-            .econtent
-            .as_ref()
-            .ok_or_else(|| TimestampError::Rfc3161Parse("No encapsulated content".to_string()))?
-            .value()
-            .to_vec()
-    };
+    let digest = cms_digest_algorithm(&signer_info.digest_alg)?;
+    let signed_content_bytes = signed_content(&signed_data, signer_info, &digest)?;
 
     // Parse the TSA leaf certificate from the chain
     let tsa_leaf_cert = parse_der_certificate(&tsa_chain.leaf)
         .map_err(|e| TimestampError::InvalidTSACertificate(format!("Failed to parse TSA leaf certificate: {}", e)))?;
 
+    // The token must name the leaf as its signer, so it can't be verified under another
+    // certificate for the same key
+    verify_signing_certificate(signer_info.signed_attrs.as_ref(), &tsa_chain.leaf, &tsa_leaf_cert)?;
+
     // Extract public key from certificate
     let public_key_info = tsa_leaf_cert.public_key();
     let public_key_der = public_key_info.raw;
@@ -159,15 +200,244 @@ fn verify_pkcs7_signature(
     // Verify the signature using the digest algorithm and signature algorithm from signer info
     verify_cms_signature(
         &signed_content_bytes,
-        &signer_info.signature.as_bytes(),
+        signer_info.signature.as_bytes(),
         public_key_der,
-        &signer_info.digest_alg,
+        &digest,
         &signer_info.signature_algorithm,
     )?;
 
     Ok(())
 }
 
+/// Digest algorithm of a CMS signer
+fn cms_digest_algorithm(
+    digest_alg: &x509_cert::spki::AlgorithmIdentifierOwned,
+) -> Result<HashAlgorithm, TimestampError> {
+    match digest_alg.oid.to_string().as_str() {
+        "2.16.840.1.101.3.4.2.1" => Ok(HashAlgorithm::Sha256), // SHA-256
+        "2.16.840.1.101.3.4.2.2" => Ok(HashAlgorithm::Sha384), // SHA-384
+        "2.16.840.1.101.3.4.2.3" => Ok(HashAlgorithm::Sha512), // SHA-512, for Ed25519
+        other => Err(TimestampError::UnsupportedHashAlgorithm(format!(
+            "Unsupported digest algorithm: {}",
+            other
+        ))),
+    }
+}
+
+/// The content the TSA signed
+///
+/// Without signed attributes, the signature is over the encapsulated TSTInfo.
+/// With them, it is over the DER encoding of the attributes as a SET (RFC 5652,
+/// section 5.4), and the TSTInfo is only bound to the signature through their
+/// `messageDigest` attribute, so the attributes are checked against it first.
+fn signed_content(
+    signed_data: &SignedData,
+    signer_info: &SignerInfo,
+    digest: &HashAlgorithm,
+) -> Result<Vec<u8>, TimestampError> {
+    use der::Encode;
+
+    let content = signed_data
+        .encap_content_info
+        .econtent
+        .as_ref()
+        .ok_or_else(|| TimestampError::Rfc3161Parse("No encapsulated content".to_string()))?
+        .value();
+
+    let Some(ref signed_attrs) = signer_info.signed_attrs else {
+        return Ok(content.to_vec());
+    };
+    verify_signed_attributes(signed_attrs, &signed_data.encap_content_info.econtent_type, content, digest)?;
+
+    // SignedAttributes encodes with the SET tag, not the [0] IMPLICIT tag of its field
+    signed_attrs
+        .to_der()
+        .map_err(|e| TimestampError::Rfc3161Parse(format!("Failed to encode signed attributes: {}", e)))
+}
+
+/// Check the `contentType` and `messageDigest` signed attributes against the encapsulated content
+///
+/// RFC 5652 (section 5.3) requires both when signed attributes are present, each
+/// once and with a single value: the content type must be that of the
+/// encapsulated content, and the message digest its digest with the signer's
+/// digest algorithm.
+fn verify_signed_attributes(
+    signed_attrs: &SignedAttributes,
+    content_type: &ObjectIdentifier,
+    content: &[u8],
+    digest: &HashAlgorithm,
+) -> Result<(), TimestampError> {
+    let signed_type = single_attribute_value(signed_attrs, CONTENT_TYPE_OID, "contentType")?
+        .decode_as::<ObjectIdentifier>()
+        .map_err(|e| TimestampError::Rfc3161SignedAttributes(format!("Invalid contentType attribute: {}", e)))?;
+    if signed_type != *content_type {
+        return Err(TimestampError::Rfc3161SignedAttributes(format!(
+            "contentType attribute {} does not match the encapsulated content type {}",
+            signed_type, content_type
+        )));
+    }
+
+    let message_digest = single_attribute_value(signed_attrs, MESSAGE_DIGEST_OID, "messageDigest")?
+        .decode_as::<OctetStringRef>()
+        .map_err(|e| TimestampError::Rfc3161SignedAttributes(format!("Invalid messageDigest attribute: {}", e)))?;
+    let computed = digest.hash(content);
+    if message_digest.as_bytes() != computed.as_slice() {
+        return Err(TimestampError::Rfc3161SignedAttributes(format!(
+            "messageDigest attribute {} does not match the digest of the TSTInfo {}",
+            hex::encode(message_digest.as_bytes()),
+            hex::encode(&computed)
+        )));
+    }
+
+    Ok(())
+}
+
+/// The value of the signed attribute `oid`, which must appear once with a single value
+fn single_attribute_value<'a>(
+    signed_attrs: &'a SignedAttributes,
+    oid: &str,
+    name: &str,
+) -> Result<&'a Any, TimestampError> {
+    optional_attribute_value(signed_attrs, oid, name)?
+        .ok_or_else(|| TimestampError::Rfc3161SignedAttributes(format!("Missing {} attribute", name)))
+}
+
+/// The value of the signed attribute `oid` if present, which must then appear once with a single value
+fn optional_attribute_value<'a>(
+    signed_attrs: &'a SignedAttributes,
+    oid: &str,
+    name: &str,
+) -> Result<Option<&'a Any>, TimestampError> {
+    let mut attributes = signed_attrs.iter().filter(|attr| attr.oid.to_string() == oid);
+    let Some(attribute) = attributes.next() else {
+        return Ok(None);
+    };
+    if attributes.next().is_some() {
+        return Err(TimestampError::Rfc3161SignedAttributes(format!(
+            "{} attribute appears more than once",
+            name
+        )));
+    }
+
+    let mut values = attribute.values.iter();
+    match (values.next(), values.next()) {
+        (Some(value), None) => Ok(Some(value)),
+        _ => Err(TimestampError::Rfc3161SignedAttributes(format!(
+            "{} attribute must have exactly one value",
+            name
+        ))),
+    }
+}
+
+/// Check that the signing certificate attributes name the TSA leaf certificate
+///
+/// RFC 3161 (section 2.4.1) requires the `signingCertificate` attribute, and RFC
+/// 5816 allows `signingCertificateV2`, with a hash algorithm other than SHA-1,
+/// instead. The first ESSCertID of each one present identifies the signer: its
+/// hash must be that of the leaf's DER encoding, and its issuer and serial
+/// number, if given, the leaf's. Without the attribute a token could be verified
+/// under any certificate for the TSA's key, e.g. one that is not valid for time
+/// stamping.
+fn verify_signing_certificate(
+    signed_attrs: Option<&SignedAttributes>,
+    leaf_der: &[u8],
+    leaf: &X509Certificate,
+) -> Result<(), TimestampError> {
+    let invalid = |reason: String| TimestampError::Rfc3161SigningCertificate(reason);
+    let signed_attrs =
+        signed_attrs.ok_or_else(|| invalid("the token has no signed attributes to name it".to_string()))?;
+    let v1 = optional_attribute_value(signed_attrs, SIGNING_CERTIFICATE_OID, "signingCertificate")?;
+    let v2 = optional_attribute_value(signed_attrs, SIGNING_CERTIFICATE_V2_OID, "signingCertificateV2")?;
+    if v1.is_none() && v2.is_none() {
+        return Err(invalid("Missing signingCertificate or signingCertificateV2 attribute".to_string()));
+    }
+
+    if let Some(value) = v1 {
+        let attribute = value
+            .decode_as::<SigningCertificate>()
+            .map_err(|e| invalid(format!("Invalid signingCertificate attribute: {}", e)))?;
+        let cert_id = attribute
+            .certs
+            .first()
+            .ok_or_else(|| invalid("signingCertificate attribute names no certificate".to_string()))?;
+        let hash = <sha1::Sha1 as sha1::Digest>::digest(leaf_der);
+        verify_ess_cert_id("signingCertificate", &cert_id.cert_hash, &hash, cert_id.issuer_serial.as_ref(), leaf)?;
+    }
+
+    if let Some(value) = v2 {
+        let attribute = value
+            .decode_as::<SigningCertificateV2>()
+            .map_err(|e| invalid(format!("Invalid signingCertificateV2 attribute: {}", e)))?;
+        let cert_id = attribute
+            .certs
+            .first()
+            .ok_or_else(|| invalid("signingCertificateV2 attribute names no certificate".to_string()))?;
+        let algorithm = match cert_id.hash_algorithm.as_ref().map(|alg| alg.oid.to_string()) {
+            None => HashAlgorithm::Sha256,
+            Some(oid) => match oid.as_str() {
+                "2.16.840.1.101.3.4.2.1" => HashAlgorithm::Sha256,
+                "2.16.840.1.101.3.4.2.2" => HashAlgorithm::Sha384,
+                "2.16.840.1.101.3.4.2.3" => HashAlgorithm::Sha512,
+                other => {
+                    return Err(TimestampError::UnsupportedHashAlgorithm(format!(
+                        "Unsupported signingCertificateV2 hash algorithm: {}",
+                        other
+                    )))
+                }
+            },
+        };
+        let hash = algorithm.hash(leaf_der);
+        verify_ess_cert_id("signingCertificateV2", &cert_id.cert_hash, &hash, cert_id.issuer_serial.as_ref(), leaf)?;
+    }
+
+    Ok(())
+}
+
+/// Check one ESSCertID(v2) against the TSA leaf, whose hash with the ID's algorithm is `leaf_hash`
+fn verify_ess_cert_id(
+    name: &str,
+    cert_hash: &OctetString,
+    leaf_hash: &[u8],
+    issuer_serial: Option<&IssuerSerial>,
+    leaf: &X509Certificate,
+) -> Result<(), TimestampError> {
+    use der::Encode;
+
+    if cert_hash.as_bytes() != leaf_hash {
+        return Err(TimestampError::Rfc3161SigningCertificate(format!(
+            "{} attribute names the certificate {}, not the TSA certificate {}",
+            name,
+            hex::encode(cert_hash.as_bytes()),
+            hex::encode(leaf_hash)
+        )));
+    }
+
+    let Some(issuer_serial) = issuer_serial else {
+        return Ok(());
+    };
+    if issuer_serial.serial_number.as_bytes() != leaf.raw_serial() {
+        return Err(TimestampError::Rfc3161SigningCertificate(format!(
+            "{} attribute names the serial number {}, not the TSA certificate's {}",
+            name,
+            hex::encode(issuer_serial.serial_number.as_bytes()),
+            hex::encode(leaf.raw_serial())
+        )));
+    }
+    let issuer_matches = issuer_serial.issuer.iter().any(|general_name| match general_name {
+        GeneralName::DirectoryName(issuer) => issuer.to_der().is_ok_and(|der| der == leaf.issuer().as_raw()),
+        _ => false,
+    });
+    if !issuer_matches {
+        return Err(TimestampError::Rfc3161SigningCertificate(format!(
+            "{} attribute names another issuer than the TSA certificate's ({})",
+            name,
+            leaf.issuer()
+        )));
+    }
+
+    Ok(())
+}
+
 /// Verify CMS signature using the public key
 ///
 /// This is a simplified verification that:
@@ -177,35 +447,27 @@ fn verify_cms_signature(
     signed_content: &[u8],
     signature: &[u8],
     public_key_der: &[u8],
-    digest_alg: &x509_cert::spki::AlgorithmIdentifierOwned,
+    digest: &HashAlgorithm,
     sig_alg: &x509_cert::spki::AlgorithmIdentifierOwned,
 ) -> Result<(), TimestampError> {
-    use sha2::{Digest, Sha256, Sha384};
-
-    // Compute digest of signed content
-    let digest = match digest_alg.oid.to_string().as_str() {
-        "2.16.840.1.101.3.4.2.1" => Sha256::digest(signed_content).to_vec(), // SHA-256
-        "2.16.840.1.101.3.4.2.2" => Sha384::digest(signed_content).to_vec(), // SHA-384
-        other => {
-            return Err(TimestampError::UnsupportedHashAlgorithm(format!(
-                "Unsupported digest algorithm: {}",
-                other
-            )))
-        }
-    };
-
     // Verify signature based on algorithm
     // RSA with SHA-256: 1.2.840.113549.1.1.11
     // RSA with SHA-384: 1.2.840.113549.1.1.12
+    // RSASSA-PSS: 1.2.840.113549.1.1.10
     // ECDSA with SHA-256: 1.2.840.10045.4.3.2
     // ECDSA with SHA-384: 1.2.840.10045.4.3.3
+    // Ed25519: 1.3.101.112
     match sig_alg.oid.to_string().as_str() {
         "1.2.840.113549.1.1.11" | "1.2.840.113549.1.1.12" => {
-            verify_rsa_signature(&digest, signature, public_key_der)?
+            verify_rsa_signature(signed_content, backend_digest(digest)?, signature, public_key_der)?
+        }
+        "1.2.840.113549.1.1.10" => {
+            verify_rsa_pss_signature(signed_content, backend_digest(digest)?, signature, public_key_der, sig_alg)?
         }
         "1.2.840.10045.4.3.2" | "1.2.840.10045.4.3.3" => {
-            verify_ecdsa_signature(&digest, signature, public_key_der)?
+            verify_ecdsa_signature(signed_content, backend_digest(digest)?, signature, public_key_der)?
         }
+        "1.3.101.112" => verify_ed25519_signature(signed_content, digest, signature, public_key_der)?,
         other => {
             return Err(TimestampError::Rfc3161Parse(format!(
                 "Unsupported signature algorithm: {}",
@@ -219,124 +481,439 @@ fn verify_cms_signature(
 
 /// Verify RSA signature
 fn verify_rsa_signature(
-    digest: &[u8],
+    signed_content: &[u8],
+    digest: DigestAlgorithm,
     signature: &[u8],
     public_key_der: &[u8],
 ) -> Result<(), TimestampError> {
-    use rsa::pkcs1v15::VerifyingKey;
-    use rsa::signature::hazmat::PrehashVerifier;
-    use rsa::RsaPublicKey;
     use rsa::pkcs8::DecodePublicKey;
-    use sha2::{Sha256, Sha384};
+    use rsa::RsaPublicKey;
 
     // Parse RSA public key
     let public_key = RsaPublicKey::from_public_key_der(public_key_der)
         .map_err(|e| TimestampError::Rfc3161Parse(format!("Failed to parse RSA public key: {}", e)))?;
 
-    // Create verifying key based on digest length
-    match digest.len() {
-        32 => {
-            // SHA-256
-            let verifying_key: VerifyingKey<Sha256> = VerifyingKey::new(public_key);
-            let sig = rsa::pkcs1v15::Signature::try_from(signature)
-                .map_err(|e| TimestampError::Rfc3161Parse(format!("Invalid RSA signature: {}", e)))?;
-            verifying_key
-                .verify_prehash(digest, &sig)
-                .map_err(|_| TimestampError::Rfc3161SignatureInvalid)?;
-        }
-        48 => {
-            // SHA-384
-            let verifying_key: VerifyingKey<Sha384> = VerifyingKey::new(public_key);
-            let sig = rsa::pkcs1v15::Signature::try_from(signature)
-                .map_err(|e| TimestampError::Rfc3161Parse(format!("Invalid RSA signature: {}", e)))?;
-            verifying_key
-                .verify_prehash(digest, &sig)
-                .map_err(|_| TimestampError::Rfc3161SignatureInvalid)?;
-        }
-        _ => {
-            return Err(TimestampError::UnsupportedHashAlgorithm(format!(
-                "Unexpected digest length: {}",
-                digest.len()
-            )))
-        }
+    backend::verify_rsa_pkcs1v15(&public_key, digest, signed_content, signature)
+        .map_err(map_backend_error("Invalid RSA signature"))
+}
+
+/// Verify RSASSA-PSS signature
+///
+/// The parameters (RFC 4055) must hash with the signer's digest algorithm,
+/// using MGF1 over the same digest; the salt length is taken from them.
+fn verify_rsa_pss_signature(
+    signed_content: &[u8],
+    digest: DigestAlgorithm,
+    signature: &[u8],
+    public_key_der: &[u8],
+    sig_alg: &x509_cert::spki::AlgorithmIdentifierOwned,
+) -> Result<(), TimestampError> {
+    use der::Encode;
+    use x509_parser::prelude::FromDer;
+    use x509_parser::signature_algorithm::SignatureAlgorithm;
+    use x509_parser::x509::AlgorithmIdentifier;
+
+    let invalid = |e: String| TimestampError::Rfc3161Parse(format!("Invalid RSASSA-PSS parameters: {}", e));
+    let sig_alg_der = sig_alg.to_der().map_err(|e| invalid(e.to_string()))?;
+    let (_, algorithm) = AlgorithmIdentifier::from_der(&sig_alg_der).map_err(|e| invalid(e.to_string()))?;
+    let SignatureAlgorithm::RSASSA_PSS(params) =
+        SignatureAlgorithm::try_from(&algorithm).map_err(|e| invalid(e.to_string()))?
+    else {
+        return Err(invalid("not an RSASSA-PSS algorithm".to_string()));
+    };
+    let (hash, salt_len) = rsa_pss_parameters(&params).ok_or_else(|| {
+        TimestampError::UnsupportedHashAlgorithm(
+            "RSASSA-PSS requires SHA-256 or SHA-384 with MGF1 over the same digest".to_string(),
+        )
+    })?;
+    if hash != digest {
+        return Err(TimestampError::UnsupportedHashAlgorithm(format!(
+            "RSASSA-PSS digest {:?} does not match the signer's digest {:?}",
+            hash, digest
+        )));
     }
 
-    Ok(())
+    // PSS keys may be rsaEncryption or id-RSASSA-PSS keys
+    let PublicKey::Rsa(public_key) = PublicKey::from_spki_der(public_key_der)
+        .map_err(|e| TimestampError::Rfc3161Parse(format!("Failed to parse RSA public key: {}", e)))?
+    else {
+        return Err(TimestampError::Rfc3161Parse("RSASSA-PSS signature without an RSA key".to_string()));
+    };
+
+    backend::verify_rsa_pss(&public_key, hash, salt_len, signed_content, signature)
+        .map_err(map_backend_error("Invalid RSASSA-PSS signature"))
+}
+
+/// Verify Ed25519 signature
+///
+/// Ed25519 signs the content itself; RFC 8419 (section 3.1) requires SHA-512 as
+/// the signer's digest algorithm, which hashes the TSTInfo into the signed attributes.
+fn verify_ed25519_signature(
+    signed_content: &[u8],
+    digest: &HashAlgorithm,
+    signature: &[u8],
+    public_key_der: &[u8],
+) -> Result<(), TimestampError> {
+    use ed25519_dalek::Signature as Ed25519Signature;
+
+    if *digest != HashAlgorithm::Sha512 {
+        return Err(TimestampError::UnsupportedHashAlgorithm(format!(
+            "Ed25519 requires the SHA-512 digest algorithm, got {:?}",
+            digest
+        )));
+    }
+
+    let PublicKey::Ed25519(public_key) = PublicKey::from_spki_der(public_key_der)
+        .map_err(|e| TimestampError::Rfc3161Parse(format!("Failed to parse Ed25519 public key: {}", e)))?
+    else {
+        return Err(TimestampError::Rfc3161Parse("Ed25519 signature without an Ed25519 key".to_string()));
+    };
+    let sig = Ed25519Signature::from_slice(signature)
+        .map_err(|e| TimestampError::Rfc3161Parse(format!("Invalid Ed25519 signature: {}", e)))?;
+
+    backend::verify_ed25519(&public_key, signed_content, &sig).map_err(map_backend_error("Invalid Ed25519 signature"))
 }
 
 /// Verify ECDSA signature
 fn verify_ecdsa_signature(
-    digest: &[u8],
+    signed_content: &[u8],
+    digest: DigestAlgorithm,
     signature: &[u8],
     public_key_der: &[u8],
 ) -> Result<(), TimestampError> {
-    use ecdsa::signature::hazmat::PrehashVerifier;
     use p256::ecdsa::{Signature as P256Signature, VerifyingKey as P256VerifyingKey};
     use p256::pkcs8::DecodePublicKey as P256DecodePublicKey;
     use p384::ecdsa::{Signature as P384Signature, VerifyingKey as P384VerifyingKey};
 
-    // Try P-256 first, then P-384 based on digest length
-    match digest.len() {
-        32 => {
-            // P-256 / SHA-256
+    // The curve follows the digest: P-256 with SHA-256, P-384 with SHA-384
+    match digest {
+        DigestAlgorithm::Sha256 => {
             let verifying_key = P256VerifyingKey::from_public_key_der(public_key_der)
                 .map_err(|e| TimestampError::Rfc3161Parse(format!("Failed to parse P-256 public key: {}", e)))?;
 
             let sig = P256Signature::from_der(signature)
                 .map_err(|e| TimestampError::Rfc3161Parse(format!("Invalid ECDSA signature: {}", e)))?;
 
-            verifying_key
-                .verify_prehash(digest, &sig)
-                .map_err(|_| TimestampError::Rfc3161SignatureInvalid)?;
+            backend::verify_p256(&verifying_key, signed_content, &sig)
+                .map_err(map_backend_error("Invalid ECDSA signature"))
         }
-        48 => {
-            // P-384 / SHA-384
+        DigestAlgorithm::Sha384 => {
             let verifying_key = P384VerifyingKey::from_public_key_der(public_key_der)
                 .map_err(|e| TimestampError::Rfc3161Parse(format!("Failed to parse P-384 public key: {}", e)))?;
 
             let sig = P384Signature::from_der(signature)
                 .map_err(|e| TimestampError::Rfc3161Parse(format!("Invalid ECDSA signature: {}", e)))?;
 
-            verifying_key
-                .verify_prehash(digest, &sig)
-                .map_err(|_| TimestampError::Rfc3161SignatureInvalid)?;
-        }
-        _ => {
-            return Err(TimestampError::UnsupportedHashAlgorithm(format!(
-                "Unexpected digest length: {}",
-                digest.len()
-            )))
+            backend::verify_p384(&verifying_key, signed_content, &sig)
+                .map_err(map_backend_error("Invalid ECDSA signature"))
         }
+        DigestAlgorithm::Sha512 => Err(TimestampError::UnsupportedHashAlgorithm(
+            "SHA-512 is only supported with Ed25519".to_string(),
+        )),
     }
+}
 
-    Ok(())
+/// The backend digest of a signer's digest algorithm; SHA-512 is only used with Ed25519
+fn backend_digest(digest: &HashAlgorithm) -> Result<DigestAlgorithm, TimestampError> {
+    match digest {
+        HashAlgorithm::Sha256 => Ok(DigestAlgorithm::Sha256),
+        HashAlgorithm::Sha384 => Ok(DigestAlgorithm::Sha384),
+        HashAlgorithm::Sha512 => Err(TimestampError::UnsupportedHashAlgorithm(
+            "SHA-512 is only supported with Ed25519".to_string(),
+        )),
+    }
+}
+
+/// Map a backend failure onto the timestamp error kinds used above
+fn map_backend_error(context: &'static str) -> impl Fn(SignatureError) -> TimestampError {
+    move |e| match e {
+        SignatureError::InvalidSignature => TimestampError::Rfc3161SignatureInvalid,
+        SignatureError::InvalidFormat(msg) => TimestampError::Rfc3161Parse(format!("{}: {}", context, msg)),
+        other => TimestampError::Rfc3161Parse(format!("{}: {}", context, other)),
+    }
 }
 
 /// Detect or validate TSA certificate chain
 ///
 /// Returns the TSA chain to use for verification:
-/// - If embedded certs exist in the timestamp, extract and use them
-/// - Otherwise, use the provided tsa_cert_chain parameter
+/// - If the timestamp embeds a complete chain (signing certificate to a
+///   self-issued root), use it, provided its root is the root of
+///   `tsa_cert_chain` or one of `tsa_roots`; no chain needs to be provided then
+/// - Otherwise (no embedded certificates, not up to a root, or up to an
+///   untrusted root), use the provided tsa_cert_chain parameter
 /// - If neither exists, return error
-pub fn detect_or_validate_tsa_chain<'a>(
+///
+/// The embedded chain's signatures and EKU are checked afterwards like those
+/// of a provided chain, by `verify_tsa_certificate_chain`.
+///
+/// # Arguments
+///
+/// * `timestamp` - The parsed timestamp token
+/// * `tsa_cert_chain` - TSA chain given to the verifier, if any
+/// * `tsa_roots` - Trusted TSA root certificates (DER, see `VerificationOptions::tsa_roots`)
+///
+/// # Errors
+///
+/// - `TimestampError::InvalidTSACertificate` if the embedded certificates can't be
+///   parsed or don't form a single chain
+/// - `TimestampError::UntrustedTSARoot` if the embedded chain roots in none of the trusted roots
+///   and no chain is provided
+/// - `TimestampError::MissingTSAChain` if there is neither a trusted embedded chain nor a provided one
+pub fn detect_or_validate_tsa_chain(
     timestamp: &Rfc3161Timestamp,
-    tsa_cert_chain: Option<&'a CertificateChain>,
-) -> Result<&'a CertificateChain, TimestampError> {
-    // Check if certificates are embedded in the timestamp
-    if timestamp.certificates.is_some() {
-        // TODO: Convert embedded DER certificates to CertificateChain
-        // For now, we'll require the user to provide the chain
-        // This is a simplification that can be improved later
+    tsa_cert_chain: Option<&CertificateChain>,
+    tsa_roots: &[Vec<u8>],
+) -> Result<CertificateChain, TimestampError> {
+    let embedded = timestamp.certificates.as_deref().unwrap_or_default();
+    let Some(chain) = embedded_chain(embedded)? else {
+        return tsa_cert_chain.cloned().ok_or(TimestampError::MissingTSAChain);
+    };
+
+    // The embedded chain comes from the bundle, so it must root in a trusted root
+    let mut trusted = tsa_cert_chain
+        .map(|provided| &provided.root)
+        .into_iter()
+        .chain(tsa_roots)
+        .peekable();
+    if trusted.peek().is_none() {
+        return Err(TimestampError::MissingTSAChain);
+    }
+    if trusted.any(|root| *root == chain.root) {
+        return Ok(chain);
+    }
+    // Bundles verified against the provided chain before embedded chains were used still do
+    tsa_cert_chain.cloned().ok_or(TimestampError::UntrustedTSARoot)
+}
+
+/// Order the certificates embedded in a timestamp into a chain, if they form a complete one
+///
+/// CMS carries them as a SET, whose DER encoding sorts them by their bytes, so
+/// the chain is followed from issuer to subject: from the one certificate that
+/// issued none of the others up to a self-issued root. `None` if there are
+/// fewer than two certificates or the chain stops short of a root.
+fn embedded_chain(certs: &[Vec<u8>]) -> Result<Option<CertificateChain>, TimestampError> {
+    if certs.len() < 2 {
+        return Ok(None);
+    }
+
+    let parsed = certs
+        .iter()
+        .map(|der| {
+            parse_der_certificate(der).map_err(|e| {
+                TimestampError::InvalidTSACertificate(format!("Failed to parse embedded TSA certs: {}", e))
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    // (subject, issuer) of each certificate
+    let names: Vec<(&[u8], &[u8])> = parsed
+        .iter()
+        .map(|cert| (cert.tbs_certificate.subject.as_raw(), cert.tbs_certificate.issuer.as_raw()))
+        .collect();
+    let self_issued = |i: usize| names[i].0 == names[i].1;
+
+    let mut leaves = (0..names.len()).filter(|&i| {
+        !self_issued(i) && !(0..names.len()).any(|j| j != i && names[j].1 == names[i].0)
+    });
+    let (Some(leaf), None) = (leaves.next(), leaves.next()) else {
+        return Err(TimestampError::InvalidTSACertificate(
+            "Embedded TSA certificates do not form a single chain".to_string(),
+        ));
+    };
+
+    let mut order = vec![leaf];
+    let mut current = leaf;
+    while !self_issued(current) {
+        let next = (0..names.len()).find(|&i| !order.contains(&i) && names[i].0 == names[current].1);
+        match next {
+            Some(next) => {
+                order.push(next);
+                current = next;
+            }
+            // No issuer embedded: the chain stops short of a root
+            None => return Ok(None),
+        }
     }
 
-    // Use provided chain if available
-    tsa_cert_chain.ok_or(TimestampError::MissingTSAChain)
+    let root = order.pop().expect("the chain has a leaf");
+    Ok(Some(CertificateChain {
+        leaf: certs[order[0]].clone(),
+        intermediates: order[1..].iter().map(|&i| certs[i].clone()).collect(),
+        root: certs[root].clone(),
+    }))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::parser::rfc3161::{HashAlgorithm, MessageImprint};
+    use crate::parser::bundle::parse_bundle_from_bytes;
+    use crate::parser::rfc3161::MessageImprint;
+    use der::asn1::SetOfVec;
+    use der::{Decode, Tag};
+
+    /// RFC 3161 timestamp of a GitHub attestation, which embeds no certificates
+    fn sample_timestamp() -> Rfc3161Timestamp {
+        let path = concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/../../samples/actions-attest-build-provenance-attestation-13581567.sigstore.json"
+        );
+        let bundle = parse_bundle_from_bytes(&std::fs::read(path).unwrap()).unwrap();
+        let timestamps = bundle.verification_material.timestamp_verification_data.unwrap();
+        let der = BASE64
+            .decode(&timestamps.rfc3161_timestamps.unwrap()[0].signed_timestamp)
+            .unwrap();
+        parse_rfc3161_timestamp(&der).unwrap()
+    }
+
+    /// SignedData of the sample timestamp, which has signed attributes
+    fn sample_signed_data() -> SignedData {
+        SignedData::from_der(&sample_timestamp().signed_data).unwrap()
+    }
+
+    fn signer(signed_data: &SignedData) -> &SignerInfo {
+        signed_data.signer_infos.0.iter().next().unwrap()
+    }
+
+    /// TSA chain the sample's timestamp was issued under
+    fn sample_tsa_chain() -> CertificateChain {
+        use crate::fetcher::jsonl::parser::{load_trusted_root_from_jsonl, select_timestamp_authority};
+        use crate::parser::bundle::extract_bundle_timestamp;
+        use crate::types::certificate::FulcioInstance;
+
+        let samples = concat!(env!("CARGO_MANIFEST_DIR"), "/../../samples");
+        let roots =
+            load_trusted_root_from_jsonl(&std::fs::read_to_string(format!("{}/trusted_root.jsonl", samples)).unwrap())
+                .unwrap();
+        let bundle = std::fs::read(format!(
+            "{}/actions-attest-build-provenance-attestation-13581567.sigstore.json",
+            samples
+        ))
+        .unwrap();
+        let timestamp = extract_bundle_timestamp(&parse_bundle_from_bytes(&bundle).unwrap()).unwrap();
+        select_timestamp_authority(&roots, &FulcioInstance::GitHub, timestamp).unwrap()
+    }
+
+    /// Signed attributes of the sample with the signingCertificateV2 value replaced
+    fn with_signing_certificate_v2(
+        signed_attrs: &SignedAttributes,
+        update: impl Fn(&mut SigningCertificateV2),
+    ) -> SignedAttributes {
+        let attrs = signed_attrs
+            .iter()
+            .map(|attr| {
+                if attr.oid.to_string() != SIGNING_CERTIFICATE_V2_OID {
+                    return attr.clone();
+                }
+                let mut value = attr.values.iter().next().unwrap().decode_as::<SigningCertificateV2>().unwrap();
+                update(&mut value);
+                let mut attr = attr.clone();
+                attr.values = SetOfVec::try_from(vec![Any::encode_from(&value).unwrap()]).unwrap();
+                attr
+            })
+            .collect::<Vec<_>>();
+        SetOfVec::try_from(attrs).unwrap()
+    }
+
+    #[test]
+    fn test_signing_certificate_names_tsa_leaf() {
+        let signed_data = sample_signed_data();
+        let attrs = signer(&signed_data).signed_attrs.clone().unwrap();
+        let chain = sample_tsa_chain();
+        let leaf = parse_der_certificate(&chain.leaf).unwrap();
+        verify_signing_certificate(Some(&attrs), &chain.leaf, &leaf).unwrap();
+
+        // The token can't be verified under another certificate
+        let other = parse_der_certificate(&chain.intermediates[0]).unwrap();
+        let err = verify_signing_certificate(Some(&attrs), &chain.intermediates[0], &other).unwrap_err();
+        assert!(matches!(err, TimestampError::Rfc3161SigningCertificate(_)), "{}", err);
+        assert_eq!(err.code(), 412);
+
+        // Nor if the issuer serial names another certificate of the same hash
+        let other_serial = with_signing_certificate_v2(&attrs, |value| {
+            value.certs[0].issuer_serial.as_mut().unwrap().serial_number = SerialNumber::new(&[1]).unwrap();
+        });
+        let err = verify_signing_certificate(Some(&other_serial), &chain.leaf, &leaf).unwrap_err();
+        assert!(err.to_string().contains("serial number 01"), "{}", err);
+
+        // An explicit SHA-256 is the default
+        let explicit = with_signing_certificate_v2(&attrs, |value| {
+            value.certs[0].hash_algorithm = Some(AlgorithmIdentifierOwned {
+                oid: ObjectIdentifier::new_unwrap("2.16.840.1.101.3.4.2.1"),
+                parameters: None,
+            });
+        });
+        verify_signing_certificate(Some(&explicit), &chain.leaf, &leaf).unwrap();
+    }
+
+    #[test]
+    fn test_signing_certificate_is_required() {
+        let signed_data = sample_signed_data();
+        let attrs = signer(&signed_data).signed_attrs.clone().unwrap();
+        let chain = sample_tsa_chain();
+        let leaf = parse_der_certificate(&chain.leaf).unwrap();
+
+        let without: Vec<_> = attrs
+            .iter()
+            .filter(|attr| attr.oid.to_string() != SIGNING_CERTIFICATE_V2_OID)
+            .cloned()
+            .collect();
+        let err = verify_signing_certificate(Some(&SetOfVec::try_from(without).unwrap()), &chain.leaf, &leaf)
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "RFC3161 timestamp is not bound to the TSA certificate: Missing signingCertificate or signingCertificateV2 attribute"
+        );
+        assert!(verify_signing_certificate(None, &chain.leaf, &leaf).is_err());
+    }
+
+    #[test]
+    fn test_signed_content_is_signed_attributes() {
+        let signed_data = sample_signed_data();
+        let signer_info = signer(&signed_data);
+        assert!(signer_info.signed_attrs.is_some());
+
+        let digest = cms_digest_algorithm(&signer_info.digest_alg).unwrap();
+        let content = signed_content(&signed_data, signer_info, &digest).unwrap();
+        assert_eq!(content[0], 0x31, "signed over the attributes as a SET");
+    }
+
+    #[test]
+    fn test_signed_attributes_bind_tstinfo() {
+        // Another TSTInfo under the same signed attributes, e.g. with a forged time
+        let mut signed_data = sample_signed_data();
+        let mut tst_info = signed_data.encap_content_info.econtent.as_ref().unwrap().value().to_vec();
+        let last = tst_info.len() - 1;
+        tst_info[last] ^= 1;
+        signed_data.encap_content_info.econtent = Some(Any::new(Tag::OctetString, tst_info).unwrap());
+
+        let signer_info = signer(&signed_data);
+        let digest = cms_digest_algorithm(&signer_info.digest_alg).unwrap();
+        let err = signed_content(&signed_data, signer_info, &digest).unwrap_err();
+        assert!(matches!(err, TimestampError::Rfc3161SignedAttributes(_)), "{}", err);
+        assert!(err.to_string().contains("messageDigest attribute"), "{}", err);
+    }
+
+    #[test]
+    fn test_signed_attributes_require_message_digest() {
+        let mut signed_data = sample_signed_data();
+        let signer_info = signer(&signed_data).clone();
+        let digest = cms_digest_algorithm(&signer_info.digest_alg).unwrap();
+        let attrs = signer_info.signed_attrs.as_ref().unwrap();
+        let without_digest: Vec<_> = attrs
+            .iter()
+            .filter(|attr| attr.oid.to_string() != MESSAGE_DIGEST_OID)
+            .cloned()
+            .collect();
+
+        let mut stripped = signer_info.clone();
+        stripped.signed_attrs = Some(SetOfVec::try_from(without_digest).unwrap());
+        let err = signed_content(&signed_data, &stripped, &digest).unwrap_err();
+        assert_eq!(err.to_string(), "Invalid signed attributes in RFC3161 timestamp: Missing messageDigest attribute");
+
+        // The content type must be the TSTInfo's
+        signed_data.encap_content_info.econtent_type = ObjectIdentifier::new_unwrap("1.2.840.113549.1.7.1");
+        let err = signed_content(&signed_data, &signer_info, &digest).unwrap_err();
+        assert!(err.to_string().contains("contentType attribute"), "{}", err);
+    }
 
     #[test]
     fn test_verify_message_imprint_success() {
@@ -366,4 +943,174 @@ mod tests {
         assert!(result.is_err());
         assert!(matches!(result, Err(TimestampError::MessageImprintMismatch { .. })));
     }
+
+    /// TBS bytes, signature and signature algorithm of a test certificate
+    fn signed_certificate(pem: &str) -> (Vec<u8>, Vec<u8>, AlgorithmIdentifierOwned) {
+        use der::Encode;
+        let cert = x509_cert::Certificate::from_der(&::pem::parse(pem).unwrap().into_contents()).unwrap();
+        let tbs = cert.tbs_certificate.to_der().unwrap();
+        (tbs, cert.signature.raw_bytes().to_vec(), cert.signature_algorithm)
+    }
+
+    fn spki_der(pem: &str) -> Vec<u8> {
+        use der::Encode;
+        let cert = x509_cert::Certificate::from_der(&::pem::parse(pem).unwrap().into_contents()).unwrap();
+        cert.tbs_certificate.subject_public_key_info.to_der().unwrap()
+    }
+
+    #[test]
+    fn test_verify_cms_signature_rsa_pss() {
+        // The intermediate is signed by the root with RSASSA-PSS over SHA-256
+        let (tbs, mut signature, sig_alg) = signed_certificate(include_str!("../../testdata/rsa/intermediate.pem"));
+        let root_key = spki_der(include_str!("../../testdata/rsa/root.pem"));
+        assert_eq!(sig_alg.oid.to_string(), "1.2.840.113549.1.1.10");
+
+        verify_cms_signature(&tbs, &signature, &root_key, &HashAlgorithm::Sha256, &sig_alg).unwrap();
+
+        let result = verify_cms_signature(&tbs, &signature, &root_key, &HashAlgorithm::Sha384, &sig_alg);
+        assert!(matches!(result, Err(TimestampError::UnsupportedHashAlgorithm(_))));
+
+        signature[10] ^= 0x01;
+        let result = verify_cms_signature(&tbs, &signature, &root_key, &HashAlgorithm::Sha256, &sig_alg);
+        assert!(matches!(result, Err(TimestampError::Rfc3161SignatureInvalid)));
+    }
+
+    #[test]
+    fn test_verify_cms_signature_ed25519() {
+        let (tbs, mut signature, sig_alg) = signed_certificate(include_str!("../../testdata/ed25519/leaf.pem"));
+        let intermediate_key = spki_der(include_str!("../../testdata/ed25519/intermediate.pem"));
+        assert_eq!(sig_alg.oid.to_string(), "1.3.101.112");
+
+        verify_cms_signature(&tbs, &signature, &intermediate_key, &HashAlgorithm::Sha512, &sig_alg).unwrap();
+
+        // RFC 8419 requires SHA-512 as the signer's digest algorithm
+        let result = verify_cms_signature(&tbs, &signature, &intermediate_key, &HashAlgorithm::Sha256, &sig_alg);
+        assert!(matches!(result, Err(TimestampError::UnsupportedHashAlgorithm(_))));
+
+        signature[0] ^= 0x01;
+        let result = verify_cms_signature(&tbs, &signature, &intermediate_key, &HashAlgorithm::Sha512, &sig_alg);
+        assert!(matches!(result, Err(TimestampError::Rfc3161SignatureInvalid)));
+    }
+
+    #[test]
+    fn test_sha512_digest_requires_ed25519() {
+        let (tbs, signature, _) = signed_certificate(include_str!("../../testdata/rsa/intermediate.pem"));
+        let root_key = spki_der(include_str!("../../testdata/rsa/root.pem"));
+        let sig_alg = AlgorithmIdentifierOwned {
+            oid: ObjectIdentifier::new_unwrap("1.2.840.113549.1.1.11"),
+            parameters: None,
+        };
+
+        let result = verify_cms_signature(&tbs, &signature, &root_key, &HashAlgorithm::Sha512, &sig_alg);
+        assert!(matches!(result, Err(TimestampError::UnsupportedHashAlgorithm(_))));
+    }
+
+    fn pem_der(pem: &str) -> Vec<u8> {
+        ::pem::parse(pem).unwrap().into_contents()
+    }
+
+    /// The sample timestamp, embedding `certificates`
+    fn timestamp_embedding(certificates: &[&Vec<u8>]) -> Rfc3161Timestamp {
+        let mut timestamp = sample_timestamp();
+        timestamp.certificates = Some(certificates.iter().map(|&der| der.clone()).collect());
+        timestamp
+    }
+
+    #[test]
+    fn test_embedded_chain_anchored_in_trusted_root() {
+        let leaf = pem_der(include_str!("../../testdata/ed25519/leaf.pem"));
+        let intermediate = pem_der(include_str!("../../testdata/ed25519/intermediate.pem"));
+        let root = pem_der(include_str!("../../testdata/ed25519/root.pem"));
+        let other_root = pem_der(include_str!("../../testdata/rsa/root.pem"));
+
+        // Embedded in any order, as a SET
+        let timestamp = timestamp_embedding(&[&root, &leaf, &intermediate]);
+        let chain = detect_or_validate_tsa_chain(&timestamp, None, std::slice::from_ref(&root)).unwrap();
+        assert_eq!(chain.leaf, leaf);
+        assert_eq!(chain.intermediates, vec![intermediate.clone()]);
+        assert_eq!(chain.root, root);
+
+        // Anchored in the provided chain's root instead
+        let provided = CertificateChain {
+            leaf: leaf.clone(),
+            intermediates: Vec::new(),
+            root: root.clone(),
+        };
+        let chain = detect_or_validate_tsa_chain(&timestamp, Some(&provided), &[]).unwrap();
+        assert_eq!(chain.intermediates, vec![intermediate.clone()]);
+
+        // Nothing to anchor it in
+        assert!(matches!(
+            detect_or_validate_tsa_chain(&timestamp, None, &[]),
+            Err(TimestampError::MissingTSAChain)
+        ));
+        assert!(matches!(
+            detect_or_validate_tsa_chain(&timestamp, None, std::slice::from_ref(&other_root)),
+            Err(TimestampError::UntrustedTSARoot)
+        ));
+    }
+
+    #[test]
+    fn test_untrusted_embedded_chain_falls_back_to_provided() {
+        let leaf = pem_der(include_str!("../../testdata/ed25519/leaf.pem"));
+        let intermediate = pem_der(include_str!("../../testdata/ed25519/intermediate.pem"));
+        let root = pem_der(include_str!("../../testdata/ed25519/root.pem"));
+        let other_root = pem_der(include_str!("../../testdata/rsa/root.pem"));
+        let timestamp = timestamp_embedding(&[&root, &leaf, &intermediate]);
+        let provided = sample_tsa_chain();
+
+        // Rooted in neither the provided chain's root nor a trusted root: the
+        // provided chain is used, as it was before embedded chains were
+        for tsa_roots in [&[][..], std::slice::from_ref(&other_root)] {
+            let chain = detect_or_validate_tsa_chain(&timestamp, Some(&provided), tsa_roots).unwrap();
+            assert_eq!(chain.leaf, provided.leaf);
+            assert_eq!(chain.root, provided.root);
+        }
+        assert!(matches!(
+            detect_or_validate_tsa_chain(&timestamp, None, std::slice::from_ref(&other_root)),
+            Err(TimestampError::UntrustedTSARoot)
+        ));
+    }
+
+    #[test]
+    fn test_incomplete_embedded_chain_falls_back_to_provided() {
+        let leaf = pem_der(include_str!("../../testdata/ed25519/leaf.pem"));
+        let intermediate = pem_der(include_str!("../../testdata/ed25519/intermediate.pem"));
+        let root = pem_der(include_str!("../../testdata/ed25519/root.pem"));
+        let provided = sample_tsa_chain();
+
+        for timestamp in [
+            sample_timestamp(),
+            timestamp_embedding(&[&leaf]),
+            timestamp_embedding(&[&intermediate, &leaf]),
+        ] {
+            let chain = detect_or_validate_tsa_chain(&timestamp, Some(&provided), std::slice::from_ref(&root)).unwrap();
+            assert_eq!(chain.leaf, provided.leaf);
+            assert!(matches!(
+                detect_or_validate_tsa_chain(&timestamp, None, std::slice::from_ref(&root)),
+                Err(TimestampError::MissingTSAChain)
+            ));
+        }
+    }
+
+    #[test]
+    fn test_ambiguous_embedded_certificates_rejected() {
+        let ed25519_leaf = pem_der(include_str!("../../testdata/ed25519/leaf.pem"));
+        let rsa_leaf = pem_der(include_str!("../../testdata/rsa/leaf.pem"));
+        let root = pem_der(include_str!("../../testdata/ed25519/root.pem"));
+
+        // Two leaves, neither issued by the other
+        let timestamp = timestamp_embedding(&[&ed25519_leaf, &rsa_leaf]);
+        assert!(matches!(
+            detect_or_validate_tsa_chain(&timestamp, None, std::slice::from_ref(&root)),
+            Err(TimestampError::InvalidTSACertificate(_))
+        ));
+
+        let garbage = vec![0x30, 0x00];
+        let timestamp = timestamp_embedding(&[&ed25519_leaf, &garbage]);
+        assert!(matches!(
+            detect_or_validate_tsa_chain(&timestamp, None, &[root]),
+            Err(TimestampError::InvalidTSACertificate(_))
+        ));
+    }
 }
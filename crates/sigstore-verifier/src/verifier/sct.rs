@@ -0,0 +1,127 @@
+use alloc::format;
+use alloc::string::ToString;
+use alloc::vec::Vec;
+use der::{Decode, Encode};
+use x509_cert::Certificate;
+
+use crate::crypto::hash::sha256;
+use crate::crypto::signature::{PublicKey, SignatureEncoding};
+use crate::error::{CertificateError, VerificationError};
+use crate::parser::certificate::{extract_embedded_scts, parse_der_certificate};
+use crate::types::certificate::{CertificateChain, EmbeddedSct};
+use crate::types::result::CertificateTimestamp;
+
+/// OID of the embedded SCT list extension (1.3.6.1.4.1.11129.2.4.2)
+const SCT_LIST_OID: der::asn1::ObjectIdentifier =
+    der::asn1::ObjectIdentifier::new_unwrap("1.3.6.1.4.1.11129.2.4.2");
+
+/// Verify the signed certificate timestamps embedded in the leaf certificate
+///
+/// Each SCT is a CT log's signature over the precertificate Fulcio submitted:
+/// the leaf's TBSCertificate without the SCT extension, bound to the issuer by
+/// the SHA256 of its public key (RFC 6962, section 3.2). This verification
+/// ensures that:
+/// 1. At least one SCT names a log in `ctlog_keys` (by its log ID, the SHA256
+///    of the log's key)
+/// 2. Every SCT from such a log verifies with the log's key
+///
+/// SCTs from other logs are ignored, so a certificate can be logged to logs
+/// the verifier doesn't trust as well.
+///
+/// # Arguments
+///
+/// * `chain` - Verified certificate chain; the leaf is issued by the first
+///   intermediate, or the root if there is none
+/// * `ctlog_keys` - Trusted CT log keys (DER SubjectPublicKeyInfo)
+///
+/// # Returns
+/// The verified SCTs, in the order the certificate lists them
+pub fn verify_embedded_scts(
+    chain: &CertificateChain,
+    ctlog_keys: &[Vec<u8>],
+) -> Result<Vec<CertificateTimestamp>, VerificationError> {
+    let leaf = parse_der_certificate(&chain.leaf)?;
+    let issuer_der = chain.intermediates.first().unwrap_or(&chain.root);
+    let issuer = parse_der_certificate(issuer_der)?;
+    let issuer_key_hash = sha256(issuer.public_key().raw);
+
+    let scts = extract_embedded_scts(&leaf)?;
+    let tbs = precertificate_tbs(&chain.leaf)?;
+
+    let mut verified = Vec::new();
+    for sct in &scts {
+        let Some(key) = ctlog_keys.iter().find(|key| sha256(key) == sct.log_id) else {
+            continue;
+        };
+        let log_id = hex::encode(sct.log_id);
+        if sct.version != 0 {
+            return Err(CertificateError::InvalidSct(format!(
+                "unsupported version {} (log {})",
+                sct.version, log_id
+            ))
+            .into());
+        }
+
+        let public_key = PublicKey::from_spki_der(key)?;
+        public_key
+            .verify_signature_with_encoding(
+                &signed_data(sct, &issuer_key_hash, &tbs)?,
+                &sct.signature,
+                SignatureEncoding::Der,
+            )
+            .map_err(|_| {
+                CertificateError::InvalidSct(format!("signature of log {} does not verify", log_id))
+            })?;
+
+        verified.push(CertificateTimestamp {
+            log_id: sct.log_id,
+            timestamp: sct.timestamp,
+        });
+    }
+
+    if verified.is_empty() {
+        return Err(CertificateError::MissingSct.into());
+    }
+    Ok(verified)
+}
+
+/// DER TBSCertificate of the leaf with the SCT list extension removed, as it
+/// was in the precertificate the logs signed
+fn precertificate_tbs(leaf_der: &[u8]) -> Result<Vec<u8>, CertificateError> {
+    let mut certificate =
+        Certificate::from_der(leaf_der).map_err(|e| CertificateError::ParseError(e.to_string()))?;
+    if let Some(extensions) = certificate.tbs_certificate.extensions.as_mut() {
+        extensions.retain(|extension| extension.extn_id != SCT_LIST_OID);
+    }
+    certificate
+        .tbs_certificate
+        .to_der()
+        .map_err(|e| CertificateError::ParseError(e.to_string()))
+}
+
+/// Data a log signs for a precertificate entry (RFC 6962, section 3.2)
+fn signed_data(
+    sct: &EmbeddedSct,
+    issuer_key_hash: &[u8; 32],
+    tbs: &[u8],
+) -> Result<Vec<u8>, CertificateError> {
+    let too_long =
+        || CertificateError::InvalidSct("TBSCertificate or extensions too long".to_string());
+    let tbs_len = u32::try_from(tbs.len())
+        .ok()
+        .filter(|len| *len < 1 << 24)
+        .ok_or_else(too_long)?;
+    let extensions_len = u16::try_from(sct.extensions.len()).map_err(|_| too_long())?;
+
+    let mut data = Vec::with_capacity(50 + tbs.len() + sct.extensions.len());
+    data.push(sct.version);
+    data.push(0); // signature_type: certificate_timestamp
+    data.extend_from_slice(&sct.timestamp.to_be_bytes());
+    data.extend_from_slice(&1u16.to_be_bytes()); // entry_type: precert_entry
+    data.extend_from_slice(issuer_key_hash);
+    data.extend_from_slice(&tbs_len.to_be_bytes()[1..]);
+    data.extend_from_slice(tbs);
+    data.extend_from_slice(&extensions_len.to_be_bytes());
+    data.extend_from_slice(&sct.extensions);
+    Ok(data)
+}
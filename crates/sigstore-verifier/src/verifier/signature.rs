@@ -1,16 +1,132 @@
-use crate::crypto::signature::PublicKey;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use crate::crypto::backend::DigestAlgorithm;
+use crate::crypto::hash::sha256;
+use crate::crypto::signature::{PublicKey, SignatureEncoding};
 use crate::error::VerificationError;
-use crate::parser::bundle::decode_base64;
+use crate::parser::bundle::{DecodedEnvelope, DecodedMessageSignature};
 use crate::parser::certificate::parse_der_certificate;
-use crate::types::bundle::DsseEnvelope;
 use crate::types::certificate::CertificateChain;
+use x509_parser::certificate::X509Certificate;
 
 const DSSE_PREFIX: &[u8] = b"DSSEv1";
 
 pub fn verify_dsse_signature(
-    envelope: &DsseEnvelope,
+    envelope: &DecodedEnvelope,
     chain: &CertificateChain,
+    encoding: SignatureEncoding,
 ) -> Result<(), VerificationError> {
+    let (public_key, _, pae) = signing_context(envelope, chain)?;
+
+    // Verify the first signature (bundles typically have one signature)
+    let signature_bytes = envelope.first_signature()?;
+
+    public_key
+        .verify_signature_with_encoding(&pae, signature_bytes, encoding)
+        .map_err(|e| e.into())
+}
+
+/// Outcome of checking one signature of a DSSE envelope
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignatureReport {
+    /// Position of the signature in the envelope
+    pub index: usize,
+    /// The signature's keyid, empty if it has none
+    pub keyid: String,
+    /// Whether the keyid is the leaf key's fingerprint; `None` without a keyid
+    pub keyid_matches: Option<bool>,
+    /// Whether the signature verifies with the leaf key
+    pub verified: bool,
+}
+
+impl SignatureReport {
+    /// Whether the signature verifies and does not name another key
+    pub fn passed(&self) -> bool {
+        self.verified && self.keyid_matches != Some(false)
+    }
+}
+
+/// Fingerprint of a certificate's public key: lowercase hex SHA-256 of its
+/// DER SubjectPublicKeyInfo
+///
+/// A DSSE signature's keyid is matched against this, case-insensitively.
+pub fn key_fingerprint(cert: &X509Certificate) -> String {
+    hex::encode(sha256(cert.public_key().raw))
+}
+
+/// Check every signature of a DSSE envelope against the leaf certificate
+///
+/// Unlike [`verify_dsse_signature`], which only verifies the first signature,
+/// each signature is verified and its keyid, if any, is compared with the leaf
+/// key's [`key_fingerprint`]. Signatures that fail are reported, not returned
+/// as errors.
+///
+/// # Errors
+///
+/// Returns `VerificationError::InvalidBundleFormat` for an envelope without
+/// signatures or an unparseable leaf certificate
+pub fn check_dsse_signatures(
+    envelope: &DecodedEnvelope,
+    chain: &CertificateChain,
+    encoding: SignatureEncoding,
+) -> Result<Vec<SignatureReport>, VerificationError> {
+    let (public_key, fingerprint, pae) = signing_context(envelope, chain)?;
+
+    Ok(envelope
+        .envelope
+        .signatures
+        .iter()
+        .zip(&envelope.signatures)
+        .enumerate()
+        .map(|(index, (signature, decoded))| {
+            let verified = decoded.as_ref().is_ok_and(|signature_bytes| {
+                public_key
+                    .verify_signature_with_encoding(&pae, signature_bytes, encoding)
+                    .is_ok()
+            });
+            let keyid_matches = (!signature.keyid.is_empty())
+                .then(|| signature.keyid.eq_ignore_ascii_case(&fingerprint));
+            SignatureReport {
+                index,
+                keyid: signature.keyid.clone(),
+                keyid_matches,
+                verified,
+            }
+        })
+        .collect())
+}
+
+/// Verify every signature of a DSSE envelope (see [`check_dsse_signatures`])
+///
+/// # Errors
+///
+/// Returns `VerificationError::DsseSignaturesFailed` listing the signatures that
+/// do not verify or whose keyid names another key
+pub fn verify_all_dsse_signatures(
+    envelope: &DecodedEnvelope,
+    chain: &CertificateChain,
+    encoding: SignatureEncoding,
+) -> Result<Vec<SignatureReport>, VerificationError> {
+    let reports = check_dsse_signatures(envelope, chain, encoding)?;
+    let failed: Vec<usize> = reports
+        .iter()
+        .filter(|report| !report.passed())
+        .map(|report| report.index)
+        .collect();
+    if !failed.is_empty() {
+        return Err(VerificationError::DsseSignaturesFailed {
+            failed,
+            total: reports.len(),
+        });
+    }
+    Ok(reports)
+}
+
+/// Leaf public key, its fingerprint and the PAE the envelope's signatures are over
+fn signing_context(
+    envelope: &DecodedEnvelope,
+    chain: &CertificateChain,
+) -> Result<(PublicKey, String, Vec<u8>), VerificationError> {
     if envelope.signatures.is_empty() {
         return Err(VerificationError::InvalidBundleFormat(
             "No signatures in envelope".to_string(),
@@ -23,20 +139,75 @@ pub fn verify_dsse_signature(
     let public_key = PublicKey::from_certificate(&leaf_cert)?;
 
     // DSSE signature is over: "DSSEv1" || len(payloadType) || payloadType || len(payload) || payload
-    let pae = create_pae(&envelope.payload_type, &envelope.payload)?;
+    let pae = pae(&envelope.envelope.payload_type, &envelope.payload);
 
-    // Verify the first signature (bundles typically have one signature)
-    let signature_bytes = decode_base64(&envelope.signatures[0].sig)?;
-
-    public_key
-        .verify_signature(&pae, &signature_bytes)
-        .map_err(|e| e.into())
+    Ok((public_key, key_fingerprint(&leaf_cert), pae))
 }
 
-fn create_pae(payload_type: &str, payload_b64: &str) -> Result<Vec<u8>, VerificationError> {
-    // Decode base64 payload
-    let payload = decode_base64(payload_b64)?;
+/// Verify the signature of a blob-signing (`cosign sign-blob`) bundle
+///
+/// ECDSA and RSA signatures are verified against the message digest recorded in
+/// the bundle, so the blob is not needed. An Ed25519 signature covers the blob
+/// itself, which must then be supplied.
+///
+/// # Arguments
+///
+/// * `message_signature` - The bundle's decoded message signature
+/// * `chain` - Verified certificate chain; the signature is checked with the leaf key
+/// * `artifact` - The signed blob, if supplied; it must have the recorded digest
+/// * `encoding` - Encoding of ECDSA signatures
+///
+/// # Errors
+///
+/// Returns `VerificationError::SubjectDigestMismatch` if the artifact does not
+/// have the recorded digest, `VerificationError::MissingArtifact` for an Ed25519
+/// key without the artifact, and signature errors if the signature does not verify
+pub fn verify_message_signature(
+    message_signature: &DecodedMessageSignature,
+    chain: &CertificateChain,
+    artifact: Option<&[u8]>,
+    encoding: SignatureEncoding,
+) -> Result<(), VerificationError> {
+    let leaf_cert = parse_der_certificate(&chain.leaf)
+        .map_err(|e| VerificationError::InvalidBundleFormat(e.to_string()))?;
+    let public_key = PublicKey::from_certificate(&leaf_cert)?;
+
+    let DecodedMessageSignature { algorithm: hash, digest, signature: signature_bytes } =
+        message_signature;
+    let hash = *hash;
+
+    if let Some(artifact) = artifact {
+        let artifact_digest = match hash {
+            DigestAlgorithm::Sha256 => sha256(artifact).to_vec(),
+            DigestAlgorithm::Sha384 => {
+                use sha2::{Digest, Sha384};
+                Sha384::digest(artifact).to_vec()
+            }
+            DigestAlgorithm::Sha512 => {
+                use sha2::{Digest, Sha512};
+                Sha512::digest(artifact).to_vec()
+            }
+        };
+        if artifact_digest != *digest {
+            return Err(VerificationError::SubjectDigestMismatch {
+                expected: hex::encode(artifact_digest),
+                actual: hex::encode(digest),
+            });
+        }
+    }
+
+    match (&public_key, artifact) {
+        (PublicKey::Ed25519(_), Some(artifact)) => {
+            public_key.verify_signature_with_encoding(artifact, signature_bytes, encoding)?
+        }
+        (PublicKey::Ed25519(_), None) => return Err(VerificationError::MissingArtifact),
+        _ => public_key.verify_prehash(hash, digest, signature_bytes, encoding)?,
+    }
+    Ok(())
+}
 
+/// DSSE pre-authentication encoding of a decoded payload
+pub fn pae(payload_type: &str, payload: &[u8]) -> Vec<u8> {
     // PAE = "DSSEv1" || len(payloadType) || payloadType || len(payload) || payload
     let mut pae = Vec::new();
 
@@ -59,34 +230,241 @@ fn create_pae(payload_type: &str, payload_b64: &str) -> Result<Vec<u8>, Verifica
     pae.push(b' ');
 
     // Add payload
-    pae.extend_from_slice(&payload);
+    pae.extend_from_slice(payload);
 
-    Ok(pae)
+    pae
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::types::bundle::{DsseEnvelope, MessageSignature};
     use base64::prelude::*;
+    use alloc::vec;
+
+    fn decoded(envelope: &DsseEnvelope) -> DecodedEnvelope<'_> {
+        DecodedEnvelope::decode(envelope).unwrap()
+    }
 
     #[test]
-    fn test_create_pae() {
+    fn test_pae() {
+        let pae = pae("application/vnd.in-toto+json", b"test payload");
+        assert!(pae.starts_with(DSSE_PREFIX));
+        assert!(pae.ends_with(b" 12 test payload"));
+    }
+
+    #[test]
+    fn test_pae_empty() {
+        assert_eq!(pae("test", b""), b"DSSEv1 4 test 0 ");
+    }
+
+    #[test]
+    fn test_verify_dsse_signature_ed25519() {
+        use crate::types::bundle::Signature;
+        use ed25519_dalek::{Signer, SigningKey};
+
+        let der = |pem: &str| pem::parse(pem).unwrap().into_contents();
+        let chain = CertificateChain {
+            leaf: der(include_str!("../../testdata/ed25519/leaf.pem")),
+            intermediates: vec![der(include_str!("../../testdata/ed25519/intermediate.pem"))],
+            root: der(include_str!("../../testdata/ed25519/root.pem")),
+        };
+
         let payload_type = "application/vnd.in-toto+json";
-        let payload_b64 = BASE64_STANDARD.encode(b"test payload");
+        let statement = b"{\"_type\":\"https://in-toto.io/Statement/v1\"}";
+        let payload = BASE64_STANDARD.encode(statement);
+        let pae = pae(payload_type, statement);
+        // The test leaf key is derived from the seed [3; 32]
+        let sig = SigningKey::from_bytes(&[3u8; 32]).sign(&pae).to_bytes();
 
-        let result = create_pae(payload_type, &payload_b64);
-        assert!(result.is_ok());
+        let mut envelope = DsseEnvelope {
+            payload,
+            payload_type: payload_type.to_string(),
+            signatures: vec![Signature {
+                sig: BASE64_STANDARD.encode(sig),
+                keyid: String::new(),
+            }],
+        };
+        assert!(verify_dsse_signature(&decoded(&envelope), &chain, SignatureEncoding::Auto).is_ok());
 
-        let pae = result.unwrap();
-        assert!(pae.starts_with(DSSE_PREFIX));
+        envelope.payload_type = "application/json".to_string();
+        assert!(verify_dsse_signature(&decoded(&envelope), &chain, SignatureEncoding::Auto).is_err());
+    }
+
+    #[test]
+    fn test_verify_all_dsse_signatures() {
+        use crate::types::bundle::Signature;
+        use ed25519_dalek::{Signer, SigningKey};
+
+        let der = |pem: &str| pem::parse(pem).unwrap().into_contents();
+        let chain = CertificateChain {
+            leaf: der(include_str!("../../testdata/ed25519/leaf.pem")),
+            intermediates: vec![der(include_str!("../../testdata/ed25519/intermediate.pem"))],
+            root: der(include_str!("../../testdata/ed25519/root.pem")),
+        };
+        let fingerprint = key_fingerprint(&parse_der_certificate(&chain.leaf).unwrap());
+
+        let payload_type = "application/vnd.in-toto+json";
+        let statement = b"{\"_type\":\"https://in-toto.io/Statement/v1\"}";
+        let payload = BASE64_STANDARD.encode(statement);
+        let pae = pae(payload_type, statement);
+        let sig = BASE64_STANDARD.encode(SigningKey::from_bytes(&[3u8; 32]).sign(&pae).to_bytes());
+        let other = BASE64_STANDARD.encode(SigningKey::from_bytes(&[4u8; 32]).sign(&pae).to_bytes());
+
+        let signature = |sig: &str, keyid: &str| Signature {
+            sig: sig.to_string(),
+            keyid: keyid.to_string(),
+        };
+        let mut envelope = DsseEnvelope {
+            payload,
+            payload_type: payload_type.to_string(),
+            signatures: vec![
+                signature(&sig, ""),
+                signature(&sig, &fingerprint.to_uppercase()),
+            ],
+        };
+        let reports = verify_all_dsse_signatures(&decoded(&envelope), &chain, SignatureEncoding::Auto).unwrap();
+        assert_eq!(reports[0].keyid_matches, None);
+        assert_eq!(reports[1].keyid_matches, Some(true));
+
+        // A valid signature under another keyid, and a signature by another key
+        envelope.signatures.push(signature(&sig, "ab12"));
+        envelope.signatures.push(signature(&other, ""));
+        let reports = check_dsse_signatures(&decoded(&envelope), &chain, SignatureEncoding::Auto).unwrap();
+        let passed: Vec<bool> = reports.iter().map(SignatureReport::passed).collect();
+        assert_eq!(passed, [true, true, false, false]);
+        assert!(reports[2].verified);
+        assert!(!reports[3].verified);
+
+        // The first signature still verifies on its own
+        assert!(verify_dsse_signature(&decoded(&envelope), &chain, SignatureEncoding::Auto).is_ok());
+        match verify_all_dsse_signatures(&decoded(&envelope), &chain, SignatureEncoding::Auto) {
+            Err(VerificationError::DsseSignaturesFailed { failed, total }) => {
+                assert_eq!(failed, [2, 3]);
+                assert_eq!(total, 4);
+            }
+            other => panic!("expected DsseSignaturesFailed, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_verify_dsse_signature_rsa() {
+        use crate::types::bundle::Signature;
+        use rsa::pkcs1v15::SigningKey;
+        use rsa::pkcs8::DecodePrivateKey;
+        use rsa::signature::{SignatureEncoding as _, Signer};
+        use rsa::RsaPrivateKey;
+
+        let der = |pem: &str| pem::parse(pem).unwrap().into_contents();
+        let chain = CertificateChain {
+            leaf: der(include_str!("../../testdata/rsa/leaf.pem")),
+            intermediates: vec![der(include_str!("../../testdata/rsa/intermediate.pem"))],
+            root: der(include_str!("../../testdata/rsa/root.pem")),
+        };
+
+        let payload_type = "application/vnd.in-toto+json";
+        let statement = b"{\"_type\":\"https://in-toto.io/Statement/v1\"}";
+        let payload = BASE64_STANDARD.encode(statement);
+        let pae = pae(payload_type, statement);
+        let private_key = RsaPrivateKey::from_pkcs8_pem(include_str!("../../testdata/rsa/leaf.key")).unwrap();
+        let pkcs1 = SigningKey::<sha2::Sha256>::new(private_key).sign(&pae).to_vec();
+        // PSS signatures are randomized; this one was made with openssl over the same PAE
+        let pss = include_str!("../../testdata/rsa/dsse-pss.sig").trim().to_string();
+
+        for sig in [BASE64_STANDARD.encode(pkcs1), pss] {
+            let mut envelope = DsseEnvelope {
+                payload: payload.clone(),
+                payload_type: payload_type.to_string(),
+                signatures: vec![Signature {
+                    sig,
+                    keyid: String::new(),
+                }],
+            };
+            assert!(verify_dsse_signature(&decoded(&envelope), &chain, SignatureEncoding::Auto).is_ok());
+
+            envelope.payload_type = "application/json".to_string();
+            assert!(verify_dsse_signature(&decoded(&envelope), &chain, SignatureEncoding::Auto).is_err());
+        }
+    }
+
+    fn message_signature(artifact: &[u8], signature: &[u8]) -> DecodedMessageSignature {
+        use crate::types::bundle::MessageDigest;
+
+        DecodedMessageSignature::decode(&MessageSignature {
+            message_digest: MessageDigest {
+                algorithm: "SHA2_256".to_string(),
+                digest: BASE64_STANDARD.encode(sha256(artifact)),
+            },
+            signature: BASE64_STANDARD.encode(signature),
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn test_verify_message_signature_rsa() {
+        use rsa::pkcs1v15::SigningKey;
+        use rsa::pkcs8::DecodePrivateKey;
+        use rsa::signature::{SignatureEncoding as _, Signer};
+        use rsa::RsaPrivateKey;
+
+        let der = |pem: &str| pem::parse(pem).unwrap().into_contents();
+        let chain = CertificateChain {
+            leaf: der(include_str!("../../testdata/rsa/leaf.pem")),
+            intermediates: vec![der(include_str!("../../testdata/rsa/intermediate.pem"))],
+            root: der(include_str!("../../testdata/rsa/root.pem")),
+        };
+        let private_key = RsaPrivateKey::from_pkcs8_pem(include_str!("../../testdata/rsa/leaf.key")).unwrap();
+        let signature = SigningKey::<sha2::Sha256>::new(private_key.clone()).sign(b"blob").to_vec();
+
+        // The digest in the bundle suffices; a supplied artifact must match it
+        let signed = message_signature(b"blob", &signature);
+        assert!(verify_message_signature(&signed, &chain, None, SignatureEncoding::Auto).is_ok());
+        assert!(verify_message_signature(&signed, &chain, Some(b"blob"), SignatureEncoding::Auto).is_ok());
+        assert!(matches!(
+            verify_message_signature(&signed, &chain, Some(b"other"), SignatureEncoding::Auto),
+            Err(VerificationError::SubjectDigestMismatch { .. })
+        ));
+
+        // A digest the signature was not made over
+        let forged = message_signature(b"other", &signature);
+        assert!(verify_message_signature(&forged, &chain, None, SignatureEncoding::Auto).is_err());
+
+        // SHA-512 digests, which Rekor logs as well
+        use crate::types::bundle::MessageDigest;
+        use sha2::Digest;
+        let signature = SigningKey::<sha2::Sha512>::new(private_key).sign(b"blob").to_vec();
+        let signed = DecodedMessageSignature::decode(&MessageSignature {
+            message_digest: MessageDigest {
+                algorithm: "SHA2_512".to_string(),
+                digest: BASE64_STANDARD.encode(sha2::Sha512::digest(b"blob")),
+            },
+            signature: BASE64_STANDARD.encode(&signature),
+        })
+        .unwrap();
+        assert!(verify_message_signature(&signed, &chain, Some(b"blob"), SignatureEncoding::Auto).is_ok());
+        assert!(matches!(
+            verify_message_signature(&signed, &chain, Some(b"other"), SignatureEncoding::Auto),
+            Err(VerificationError::SubjectDigestMismatch { .. })
+        ));
     }
 
     #[test]
-    fn test_create_pae_empty() {
-        let payload_type = "test";
-        let payload_b64 = BASE64_STANDARD.encode(b"");
+    fn test_verify_message_signature_ed25519_needs_artifact() {
+        use ed25519_dalek::{Signer, SigningKey};
 
-        let result = create_pae(payload_type, &payload_b64);
-        assert!(result.is_ok());
+        let der = |pem: &str| pem::parse(pem).unwrap().into_contents();
+        let chain = CertificateChain {
+            leaf: der(include_str!("../../testdata/ed25519/leaf.pem")),
+            intermediates: vec![der(include_str!("../../testdata/ed25519/intermediate.pem"))],
+            root: der(include_str!("../../testdata/ed25519/root.pem")),
+        };
+        let signature = SigningKey::from_bytes(&[3u8; 32]).sign(b"blob").to_bytes();
+        let signed = message_signature(b"blob", &signature);
+
+        assert!(matches!(
+            verify_message_signature(&signed, &chain, None, SignatureEncoding::Auto),
+            Err(VerificationError::MissingArtifact)
+        ));
+        assert!(verify_message_signature(&signed, &chain, Some(b"blob"), SignatureEncoding::Auto).is_ok());
     }
 }
@@ -1,21 +1,94 @@
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use crate::crypto::backend;
 use crate::crypto::hash::hex_decode;
 use crate::error::VerificationError;
-use crate::types::dsse::Statement;
+use crate::parser::bundle::DecodedMessageSignature;
+use crate::types::dsse::{subject_name_matches, Statement, Subject};
+use crate::types::git::GitObject;
+use crate::types::result::DigestAlgorithm;
 
+/// Subject digest algorithms the verifier reports, in order of preference
+const SUBJECT_DIGEST_ALGORITHMS: [(&str, DigestAlgorithm); 3] = [
+    ("sha256", DigestAlgorithm::Sha256),
+    ("sha512", DigestAlgorithm::Sha512),
+    ("sha384", DigestAlgorithm::Sha384),
+];
+
+/// Algorithm the subject selected by `expected_name` is verified with, for
+/// hashing a local copy of the artifact
+///
+/// The preferred algorithm carried by any subject matching `expected_name`
+/// (any subject without a name), SHA256 if none does.
+pub fn subject_digest_algorithm(statement: &Statement, expected_name: Option<&str>) -> DigestAlgorithm {
+    let carries = |key: &str| match expected_name {
+        Some(pattern) => statement.find_subjects(pattern).any(|subject| subject.digest.contains_key(key)),
+        None => statement.subject.iter().any(|subject| subject.digest.contains_key(key)),
+    };
+    SUBJECT_DIGEST_ALGORITHMS
+        .iter()
+        .find(|(key, _)| carries(key))
+        .map_or(DigestAlgorithm::Sha256, |(_, algorithm)| *algorithm)
+}
+
+/// Select the attested subject and verify its digest
+///
+/// With `expected_name`, exactly one subject must match the name (exact or
+/// `*`/`?` glob), so a statement covering several artifacts cannot silently
+/// resolve to the wrong one. Without it, the first subject whose digest is
+/// `expected_digest` is used, or the first subject with a supported digest when
+/// no digest is expected either.
+///
+/// Of the digests of a subject, SHA256 is preferred, then SHA512 and SHA384. An
+/// `expected_digest` picks the algorithm of its length instead, so a 64-byte
+/// expected digest is compared with the SHA512 digest when there is one.
+///
+/// # Returns
+///
+/// The name, decoded digest and digest algorithm of the selected subject.
 pub fn verify_subject_digest(
     statement: &Statement,
+    expected_name: Option<&str>,
     expected_digest: Option<&[u8]>,
-) -> Result<Vec<u8>, VerificationError> {
-    // Get SHA256 digest from subject
-    let digest_hex = statement
-        .get_subject_digest("sha256")
-        .ok_or_else(|| {
-            VerificationError::InvalidBundleFormat("No sha256 digest in subject".to_string())
-        })?;
+) -> Result<(String, Vec<u8>, DigestAlgorithm), VerificationError> {
+    if statement.subject.is_empty() {
+        return Err(VerificationError::NoSubjects);
+    }
 
-    // Decode hex digest
-    let digest = hex_decode(&digest_hex)
-        .map_err(|e| VerificationError::InvalidBundleFormat(format!("Invalid digest hex: {}", e)))?;
+    let subject = match expected_name {
+        Some(pattern) => {
+            let mut matches = statement.find_subjects(pattern);
+            let subject = matches
+                .next()
+                .ok_or_else(|| VerificationError::SubjectNotFound(pattern.to_string()))?;
+            if matches.next().is_some() {
+                return Err(VerificationError::AmbiguousSubject(pattern.to_string()));
+            }
+            subject
+        }
+        None => {
+            let mut supported = statement.subject.iter().filter(|subject| {
+                SUBJECT_DIGEST_ALGORITHMS.iter().any(|(key, _)| subject.digest.contains_key(*key))
+            });
+            let first = supported.clone().next().ok_or_else(|| {
+                VerificationError::InvalidBundleFormat(
+                    "No subject has a sha256, sha512 or sha384 digest".to_string(),
+                )
+            })?;
+            // Subjects with malformed digests can't match; the first one still reports its error
+            match expected_digest {
+                Some(expected) => supported
+                    .find(|subject| {
+                        subject_digest(subject, Some(expected)).is_ok_and(|(digest, _)| digest == expected)
+                    })
+                    .unwrap_or(first),
+                None => first,
+            }
+        }
+    };
+
+    let (digest, algorithm) = subject_digest(subject, expected_digest)?;
 
     // Check digest is not all zeros
     if digest.iter().all(|&b| b == 0) {
@@ -27,23 +100,131 @@ pub fn verify_subject_digest(
         if digest != expected {
             return Err(VerificationError::SubjectDigestMismatch {
                 expected: hex::encode(expected),
-                actual: digest_hex,
+                actual: hex::encode(&digest),
+            });
+        }
+    }
+
+    Ok((subject.name.clone(), digest, algorithm))
+}
+
+/// Decoded digest of a subject and its algorithm
+///
+/// Picks the algorithm matching the length of `expected`, if the subject has it,
+/// and otherwise the preferred one of [`SUBJECT_DIGEST_ALGORITHMS`].
+fn subject_digest(
+    subject: &Subject,
+    expected: Option<&[u8]>,
+) -> Result<(Vec<u8>, DigestAlgorithm), VerificationError> {
+    let mut available = SUBJECT_DIGEST_ALGORITHMS
+        .iter()
+        .filter_map(|&(key, algorithm)| Some((key, subject.digest.get(key)?, algorithm)));
+    let (key, digest_hex, algorithm) = available
+        .clone()
+        .find(|(_, _, algorithm)| expected.is_some_and(|expected| digest_len(*algorithm) == expected.len()))
+        .or_else(|| available.next())
+        .ok_or_else(|| {
+            VerificationError::InvalidBundleFormat(format!(
+                "No sha256, sha512 or sha384 digest in subject '{}'",
+                subject.name
+            ))
+        })?;
+    let digest = hex_decode(digest_hex)
+        .map_err(|e| VerificationError::InvalidBundleFormat(format!("Invalid digest hex: {}", e)))?;
+    if digest.len() != digest_len(algorithm) {
+        return Err(VerificationError::InvalidBundleFormat(format!(
+            "Invalid {} digest length in subject '{}': {} bytes",
+            key,
+            subject.name,
+            digest.len()
+        )));
+    }
+    Ok((digest, algorithm))
+}
+
+/// Length in bytes of a subject digest
+fn digest_len(algorithm: DigestAlgorithm) -> usize {
+    match algorithm {
+        DigestAlgorithm::Sha1 => 20,
+        DigestAlgorithm::Sha384 => 48,
+        DigestAlgorithm::Sha512 => 64,
+        DigestAlgorithm::Sha256 | DigestAlgorithm::Unknown => 32,
+    }
+}
+
+/// Verify the digest of a blob signature as the subject
+///
+/// A signed blob has no name, so `expected_name` can never match it.
+///
+/// # Returns
+///
+/// An empty name, the decoded digest and its algorithm
+pub fn verify_message_digest(
+    message_signature: &DecodedMessageSignature,
+    expected_name: Option<&str>,
+    expected_digest: Option<&[u8]>,
+) -> Result<(String, Vec<u8>, DigestAlgorithm), VerificationError> {
+    if let Some(pattern) = expected_name {
+        return Err(VerificationError::SubjectNotFound(pattern.to_string()));
+    }
+
+    let digest = &message_signature.digest;
+    if digest.iter().all(|&b| b == 0) {
+        return Err(VerificationError::ZeroSubjectDigest);
+    }
+    if let Some(expected) = expected_digest {
+        if digest != expected {
+            return Err(VerificationError::SubjectDigestMismatch {
+                expected: hex::encode(expected),
+                actual: hex::encode(digest),
             });
         }
     }
 
-    Ok(digest)
+    let algorithm = match message_signature.algorithm {
+        backend::DigestAlgorithm::Sha256 => DigestAlgorithm::Sha256,
+        backend::DigestAlgorithm::Sha384 => DigestAlgorithm::Sha384,
+        backend::DigestAlgorithm::Sha512 => DigestAlgorithm::Sha512,
+    };
+    Ok((String::new(), digest.clone(), algorithm))
+}
+
+/// Verify the object ID and name of a signed git object as the subject
+///
+/// # Returns
+///
+/// The tag name (empty for a commit), the object ID and `DigestAlgorithm::Sha1`
+pub fn verify_git_object_digest(
+    object: &GitObject,
+    expected_name: Option<&str>,
+    expected_digest: Option<&[u8]>,
+) -> Result<(String, Vec<u8>, DigestAlgorithm), VerificationError> {
+    if let Some(pattern) = expected_name {
+        if !subject_name_matches(pattern, &object.name) {
+            return Err(VerificationError::SubjectNotFound(pattern.to_string()));
+        }
+    }
+    if let Some(expected) = expected_digest {
+        if object.id[..] != *expected {
+            return Err(VerificationError::SubjectDigestMismatch {
+                expected: hex::encode(expected),
+                actual: hex::encode(object.id),
+            });
+        }
+    }
+    Ok((object.name.clone(), object.id.to_vec(), DigestAlgorithm::Sha1))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::types::dsse::Subject;
-    use std::collections::HashMap;
+    use alloc::collections::BTreeMap;
+    use alloc::vec;
 
     #[test]
     fn test_verify_subject_digest_success() {
-        let mut digest_map = HashMap::new();
+        let mut digest_map = BTreeMap::new();
         digest_map.insert(
             "sha256".to_string(),
             "658913cfebe8a49165264e2b5e54ad99b3bdbfbc8cd281b3cfaa949a21588f18".to_string(),
@@ -59,14 +240,14 @@ mod tests {
             predicate: serde_json::Value::Null,
         };
 
-        let result = verify_subject_digest(&statement, None);
-        assert!(result.is_ok());
-        assert_eq!(result.unwrap().len(), 32);
+        let (_, digest, algorithm) = verify_subject_digest(&statement, None, None).unwrap();
+        assert_eq!(digest.len(), 32);
+        assert_eq!(algorithm, DigestAlgorithm::Sha256);
     }
 
     #[test]
     fn test_verify_subject_digest_zero() {
-        let mut digest_map = HashMap::new();
+        let mut digest_map = BTreeMap::new();
         digest_map.insert(
             "sha256".to_string(),
             "0000000000000000000000000000000000000000000000000000000000000000".to_string(),
@@ -82,13 +263,13 @@ mod tests {
             predicate: serde_json::Value::Null,
         };
 
-        let result = verify_subject_digest(&statement, None);
+        let result = verify_subject_digest(&statement, None, None);
         assert!(matches!(result, Err(VerificationError::ZeroSubjectDigest)));
     }
 
     #[test]
     fn test_verify_subject_digest_mismatch() {
-        let mut digest_map = HashMap::new();
+        let mut digest_map = BTreeMap::new();
         digest_map.insert(
             "sha256".to_string(),
             "658913cfebe8a49165264e2b5e54ad99b3bdbfbc8cd281b3cfaa949a21588f18".to_string(),
@@ -105,10 +286,191 @@ mod tests {
         };
 
         let expected = vec![0u8; 32];
-        let result = verify_subject_digest(&statement, Some(&expected));
+        let result = verify_subject_digest(&statement, None, Some(&expected));
         assert!(matches!(
             result,
             Err(VerificationError::SubjectDigestMismatch { .. })
         ));
     }
+
+    fn multi_subject_statement() -> Statement {
+        let subject = |name: &str, digest: &str| {
+            let mut digest_map = BTreeMap::new();
+            digest_map.insert("sha256".to_string(), digest.to_string());
+            Subject {
+                name: name.to_string(),
+                digest: digest_map,
+            }
+        };
+
+        Statement {
+            statement_type: "test".to_string(),
+            subject: vec![
+                subject(
+                    "app-linux-amd64.tar.gz",
+                    "658913cfebe8a49165264e2b5e54ad99b3bdbfbc8cd281b3cfaa949a21588f18",
+                ),
+                subject(
+                    "app-darwin-arm64.tar.gz",
+                    "11111111111111111111111111111111111111111111111111111111111111aa",
+                ),
+            ],
+            predicate_type: "test".to_string(),
+            predicate: serde_json::Value::Null,
+        }
+    }
+
+    #[test]
+    fn test_verify_subject_digest_selects_by_name() {
+        let statement = multi_subject_statement();
+
+        let (name, digest, _) = verify_subject_digest(&statement, None, None).unwrap();
+        assert_eq!(name, "app-linux-amd64.tar.gz");
+        assert_eq!(digest[0], 0x65);
+
+        let (name, digest, _) =
+            verify_subject_digest(&statement, Some("app-darwin-arm64.tar.gz"), None).unwrap();
+        assert_eq!(name, "app-darwin-arm64.tar.gz");
+        assert_eq!(digest[31], 0xaa);
+
+        let (name, _, _) = verify_subject_digest(&statement, Some("*-darwin-*"), None).unwrap();
+        assert_eq!(name, "app-darwin-arm64.tar.gz");
+    }
+
+    #[test]
+    fn test_verify_subject_digest_selects_by_digest() {
+        let mut statement = multi_subject_statement();
+        let darwin = hex_decode("11111111111111111111111111111111111111111111111111111111111111aa").unwrap();
+
+        let (name, digest, _) = verify_subject_digest(&statement, None, Some(&darwin)).unwrap();
+        assert_eq!(name, "app-darwin-arm64.tar.gz");
+        assert_eq!(digest, darwin);
+        assert!(matches!(
+            verify_subject_digest(&statement, None, Some(&[0x22; 32])),
+            Err(VerificationError::SubjectDigestMismatch { .. })
+        ));
+
+        // Subjects without a supported digest are skipped
+        statement.subject[0].digest = BTreeMap::from([("md5".to_string(), "00".to_string())]);
+        let (name, _, _) = verify_subject_digest(&statement, None, None).unwrap();
+        assert_eq!(name, "app-darwin-arm64.tar.gz");
+        statement.subject.truncate(1);
+        assert!(matches!(
+            verify_subject_digest(&statement, None, None),
+            Err(VerificationError::InvalidBundleFormat(_))
+        ));
+    }
+
+    #[test]
+    fn test_verify_subject_digest_sha512_and_sha384() {
+        let sha512 = "ab".repeat(64);
+        let sha384 = "cd".repeat(48);
+        let mut statement = multi_subject_statement();
+        statement.subject[0].digest = BTreeMap::from([
+            ("sha384".to_string(), sha384.clone()),
+            ("sha512".to_string(), sha512.clone()),
+        ]);
+
+        // SHA512 is preferred over SHA384
+        let (name, digest, algorithm) = verify_subject_digest(&statement, None, None).unwrap();
+        assert_eq!(name, "app-linux-amd64.tar.gz");
+        assert_eq!(digest, vec![0xab; 64]);
+        assert_eq!(algorithm, DigestAlgorithm::Sha512);
+
+        // An expected digest selects the algorithm of its length
+        let (_, digest, algorithm) = verify_subject_digest(&statement, None, Some(&[0xcd; 48])).unwrap();
+        assert_eq!(digest, vec![0xcd; 48]);
+        assert_eq!(algorithm, DigestAlgorithm::Sha384);
+        let (name, _, algorithm) = verify_subject_digest(&statement, None, Some(&[0xab; 64])).unwrap();
+        assert_eq!(name, "app-linux-amd64.tar.gz");
+        assert_eq!(algorithm, DigestAlgorithm::Sha512);
+
+        // A digest of the wrong length for its algorithm is rejected
+        statement.subject[0].digest = BTreeMap::from([("sha512".to_string(), "ab".repeat(32))]);
+        assert!(matches!(
+            verify_subject_digest(&statement, None, None),
+            Err(VerificationError::InvalidBundleFormat(_))
+        ));
+    }
+
+    #[test]
+    fn test_subject_digest_algorithm() {
+        let mut statement = multi_subject_statement();
+        assert_eq!(subject_digest_algorithm(&statement, None), DigestAlgorithm::Sha256);
+
+        statement.subject[1].digest = BTreeMap::from([("sha512".to_string(), "ab".repeat(64))]);
+        assert_eq!(
+            subject_digest_algorithm(&statement, Some("*-darwin-*")),
+            DigestAlgorithm::Sha512
+        );
+        assert_eq!(subject_digest_algorithm(&statement, None), DigestAlgorithm::Sha256);
+    }
+
+    #[test]
+    fn test_verify_subject_digest_name_not_found_or_ambiguous() {
+        let statement = multi_subject_statement();
+
+        assert!(matches!(
+            verify_subject_digest(&statement, Some("app-windows-*"), None),
+            Err(VerificationError::SubjectNotFound(_))
+        ));
+        assert!(matches!(
+            verify_subject_digest(&statement, Some("app-*.tar.gz"), None),
+            Err(VerificationError::AmbiguousSubject(_))
+        ));
+    }
+
+    #[test]
+    fn test_verify_subject_digest_selects_crate_by_purl() {
+        let mut statement = multi_subject_statement();
+        statement.subject[1].name = "my_crate-0.3.1.crate".to_string();
+
+        let (name, digest, _) =
+            verify_subject_digest(&statement, Some("pkg:cargo/my-crate@0.3.1"), None).unwrap();
+        assert_eq!(name, "my_crate-0.3.1.crate");
+        assert_eq!(digest[31], 0xaa);
+
+        assert!(matches!(
+            verify_subject_digest(&statement, Some("pkg:cargo/my-crate@0.3.0"), None),
+            Err(VerificationError::SubjectNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_verify_git_object_digest() {
+        use crate::types::git::GitObjectKind;
+
+        let tag = GitObject {
+            kind: GitObjectKind::Tag,
+            id: [0xab; 20],
+            name: "v1.2.0".to_string(),
+            signed_payload: vec![],
+        };
+        let (name, digest, algorithm) = verify_git_object_digest(&tag, Some("v1.*"), Some(&[0xab; 20])).unwrap();
+        assert_eq!(name, "v1.2.0");
+        assert_eq!(digest, vec![0xab; 20]);
+        assert_eq!(algorithm, DigestAlgorithm::Sha1);
+
+        assert!(matches!(
+            verify_git_object_digest(&tag, Some("v2.*"), None),
+            Err(VerificationError::SubjectNotFound(_))
+        ));
+        assert!(matches!(
+            verify_git_object_digest(&tag, None, Some(&[0xcd; 20])),
+            Err(VerificationError::SubjectDigestMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn test_subject_name_glob() {
+        use crate::types::dsse::subject_name_matches;
+
+        assert!(subject_name_matches("artifact", "artifact"));
+        assert!(!subject_name_matches("artifact", "artifact.zip"));
+        assert!(subject_name_matches("*", ""));
+        assert!(subject_name_matches("a*b*c", "aXXbYYbc"));
+        assert!(subject_name_matches("file-?.txt", "file-1.txt"));
+        assert!(!subject_name_matches("file-?.txt", "file-10.txt"));
+        assert!(!subject_name_matches("*.zip", "archive.tar.gz"));
+    }
 }
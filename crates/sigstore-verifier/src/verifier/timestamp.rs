@@ -1,11 +1,15 @@
+use alloc::format;
+use alloc::string::ToString;
 use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
 use chrono::{DateTime, Utc};
 use x509_parser::prelude::*;
 
-use crate::error::{CertificateError, TimestampError};
+use crate::error::{CertificateError, TimestampError, VerificationError};
 use crate::parser::rfc3161::parse_rfc3161_timestamp;
+use crate::parser::certificate::parse_der_certificate;
 use crate::parser::timestamp::parse_integrated_time;
 use crate::types::bundle::{SigstoreBundle, TransparencyLogEntry};
+use crate::types::certificate::CertificateChain;
 
 /// Extract signing time from RFC 3161 timestamp
 pub fn get_rfc3161_time(bundle: &SigstoreBundle) -> Result<DateTime<Utc>, TimestampError> {
@@ -58,9 +62,63 @@ pub fn verify_signing_time_in_validity(
     Ok(())
 }
 
+/// Verify that every certificate of a chain was valid at the signing time
+///
+/// Used in archival verification, where the chain may long have expired: only the
+/// signing time proven by the timestamp is evaluated, never the current time.
+pub fn verify_chain_valid_at(
+    signing_time: &DateTime<Utc>,
+    chain: &CertificateChain,
+) -> Result<(), CertificateError> {
+    let certificates = core::iter::once(&chain.leaf)
+        .chain(&chain.intermediates)
+        .chain(core::iter::once(&chain.root));
+    for der in certificates {
+        let cert = parse_der_certificate(der)?;
+        verify_signing_time_in_validity(signing_time, &cert)?;
+    }
+    Ok(())
+}
+
+/// Verify that the signature was made at most `max_age` seconds before `verification_time`
+///
+/// `verification_time` is supplied by the caller (Unix seconds), as the
+/// verifier never reads the clock. A signing time more than `clock_skew`
+/// seconds after it is rejected as well.
+pub fn verify_signing_age(
+    signing_time: &DateTime<Utc>,
+    verification_time: u64,
+    max_age: u64,
+    clock_skew: u64,
+) -> Result<(), VerificationError> {
+    let signing_timestamp = signing_time.timestamp();
+    let verification_timestamp = i64::try_from(verification_time).unwrap_or(i64::MAX);
+    let describe = |timestamp: i64| {
+        DateTime::from_timestamp(timestamp, 0)
+            .map(|time| time.to_rfc3339())
+            .unwrap_or_else(|| timestamp.to_string())
+    };
+
+    if signing_timestamp > verification_timestamp.saturating_add(i64::try_from(clock_skew).unwrap_or(i64::MAX)) {
+        return Err(VerificationError::SignedAfterVerificationTime {
+            signing_time: signing_time.to_rfc3339(),
+            verification_time: describe(verification_timestamp),
+        });
+    }
+    if verification_timestamp.saturating_sub(signing_timestamp).max(0) as u64 > max_age {
+        return Err(VerificationError::SignatureTooOld {
+            signing_time: signing_time.to_rfc3339(),
+            verification_time: describe(verification_timestamp),
+            max_age,
+        });
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use alloc::string::String;
 
     #[test]
     fn test_get_integrated_time() {
@@ -78,4 +136,22 @@ mod tests {
         assert!(result.is_ok());
         assert_eq!(result.unwrap().timestamp(), 1732068373);
     }
+
+    #[test]
+    fn test_verify_signing_age() {
+        let signing_time = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        assert!(verify_signing_age(&signing_time, 1_700_003_600, 3600, 0).is_ok());
+        assert!(matches!(
+            verify_signing_age(&signing_time, 1_700_003_601, 3600, 0),
+            Err(VerificationError::SignatureTooOld { max_age: 3600, .. })
+        ));
+        assert!(matches!(
+            verify_signing_age(&signing_time, 1_699_999_999, 3600, 0),
+            Err(VerificationError::SignedAfterVerificationTime { .. })
+        ));
+
+        // A signer whose clock runs ahead is tolerated up to the skew
+        assert!(verify_signing_age(&signing_time, 1_699_999_940, 3600, 60).is_ok());
+        assert!(verify_signing_age(&signing_time, 1_699_999_939, 3600, 60).is_err());
+    }
 }
@@ -1,7 +1,15 @@
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use crate::crypto::hash::sha256;
 use crate::crypto::merkle::{compute_leaf_hash, verify_inclusion_proof};
+use crate::crypto::signature::{PublicKey, SignatureEncoding};
 use crate::error::{TransparencyError, VerificationError};
-use crate::parser::bundle::decode_base64;
-use crate::types::bundle::SigstoreBundle;
+use crate::parser::bundle::{decode_base64, decode_message_digest};
+use crate::parser::checkpoint::{parse_checkpoint, SignedCheckpoint};
+use crate::parser::rekor_body::{parse_entry_body, EntryKind};
+use crate::report::AuditCheck;
+use crate::types::bundle::{BundleContent, SigstoreBundle, TransparencyLogEntry};
 
 /// Verify the Rekor transparency log inclusion proof
 ///
@@ -9,10 +17,43 @@ use crate::types::bundle::SigstoreBundle;
 /// 1. The bundle contains transparency log entries
 /// 2. The inclusion proof is valid (Merkle tree verification)
 /// 3. The entry was properly logged in Rekor
+/// 4. The checkpoint, if present, is for the proven tree size and root hash, and
+///    was signed by the entry's log
+/// 5. The entry has a signed entry timestamp, signed by the entry's log; an
+///    inclusion proof is therefore never accepted on its own
+/// 6. The logged body of a `dsse` or `intoto` entry records the bundle's payload,
+///    certificate and signature, and that of a `hashedrekord` entry the message
+///    digest, certificate and signature, so an entry logged for other content
+///    cannot be substituted; entries of other kinds are rejected
 ///
 /// This provides protection against backdating attacks and ensures the signature
-/// was publicly logged in an immutable transparency log.
-pub fn verify_transparency_log(bundle: &SigstoreBundle) -> Result<(), VerificationError> {
+/// was publicly logged in an immutable transparency log. The signed entry timestamp
+/// is what vouches for the integrated time, the signing time of a Rekor-timestamped
+/// bundle, so an entry without one fails with `MissingSignedEntryTimestamp`. A
+/// checkpoint or signed entry timestamp can only vouch for the entry with a trusted
+/// key, so verification fails with `MissingRekorKeys` if `rekor_keys` is empty.
+///
+/// # Arguments
+///
+/// * `bundle` - The bundle whose first transparency log entry is verified
+/// * `rekor_keys` - Trusted Rekor keys (DER SubjectPublicKeyInfo); the entry's log ID selects one
+pub fn verify_transparency_log(
+    bundle: &SigstoreBundle,
+    rekor_keys: &[Vec<u8>],
+) -> Result<(), VerificationError> {
+    verify_transparency_log_with(bundle, rekor_keys, &|_, error| Err(error))
+}
+
+/// [`verify_transparency_log`], handing the failure of the entry body,
+/// checkpoint or SET check to `relax`
+///
+/// Verification goes on if `relax` returns `Ok`, as audit mode's does for
+/// relaxed [`AuditCheck`]s.
+pub(crate) fn verify_transparency_log_with(
+    bundle: &SigstoreBundle,
+    rekor_keys: &[Vec<u8>],
+    relax: &dyn Fn(AuditCheck, VerificationError) -> Result<(), VerificationError>,
+) -> Result<(), VerificationError> {
     let tlog_entries = bundle
         .verification_material
         .tlog_entries
@@ -25,6 +66,11 @@ pub fn verify_transparency_log(bundle: &SigstoreBundle) -> Result<(), Verificati
 
     let entry = &tlog_entries[0];
 
+    let canonicalized_body = decode_base64(&entry.canonicalized_body)
+        .map_err(|_| TransparencyError::InvalidEntryHash)?;
+    verify_entry_body(bundle, entry, &canonicalized_body)
+        .or_else(|error| relax(AuditCheck::EntryBody, error))?;
+
     // Verify inclusion proof if present
     if let Some(ref inclusion_proof) = entry.inclusion_proof {
         let log_index = inclusion_proof
@@ -48,30 +94,243 @@ pub fn verify_transparency_log(bundle: &SigstoreBundle) -> Result<(), Verificati
         }
 
         // Compute leaf hash from canonicalized body
-        let canonicalized_body = decode_base64(&entry.canonicalized_body)
-            .map_err(|_| TransparencyError::InvalidEntryHash)?;
         let leaf_hash = compute_leaf_hash(&canonicalized_body);
 
         // Verify inclusion proof
         verify_inclusion_proof(&leaf_hash, log_index, tree_size, &proof_hashes, &root_hash)?;
+
+        // The checkpoint vouches for the root the proof was verified against
+        if let Some(ref checkpoint) = inclusion_proof.checkpoint {
+            verify_checkpoint(&checkpoint.envelope, tree_size, &root_hash, entry, rekor_keys)
+                .or_else(|error| relax(AuditCheck::Checkpoint, error))?;
+        }
+    }
+
+    // Verify the signed entry timestamp, without which the integrated time is unauthenticated
+    match entry.inclusion_promise {
+        Some(ref inclusion_promise) => decode_base64(&inclusion_promise.signed_entry_timestamp)
+            .map_err(|_| TransparencyError::SignedEntryTimestampInvalid.into())
+            .and_then(|set_bytes| verify_signed_entry_timestamp(entry, &set_bytes, rekor_keys)),
+        None => Err(TransparencyError::MissingSignedEntryTimestamp.into()),
+    }
+    .or_else(|error| relax(AuditCheck::SignedEntryTimestamp, error))?;
+
+    Ok(())
+}
+
+/// Check that a checkpoint is for the proven tree and signed by the entry's log
+fn verify_checkpoint(
+    envelope: &str,
+    tree_size: u64,
+    root_hash: &[u8],
+    entry: &TransparencyLogEntry,
+    rekor_keys: &[Vec<u8>],
+) -> Result<(), VerificationError> {
+    let checkpoint = parse_checkpoint(envelope)?;
+    if checkpoint.tree_size != tree_size {
+        return Err(TransparencyError::CheckpointMismatch(format!(
+            "tree size {} differs from {}",
+            checkpoint.tree_size, tree_size
+        ))
+        .into());
+    }
+    if checkpoint.root_hash != root_hash {
+        return Err(TransparencyError::CheckpointMismatch("root hash differs".to_string()).into());
+    }
+    let (key, _) = log_key(
+        entry,
+        rekor_keys,
+        TransparencyError::InvalidCheckpoint("entry has no log ID".to_string()),
+    )?;
+    verify_checkpoint_signature(&checkpoint, key)
+}
+
+/// Check that the logged body describes the bundle's content
+///
+/// The inclusion proof and the SET only cover the body, so without this check a
+/// bundle could carry the entry of different content. Bodies of other kinds than
+/// `dsse`, `intoto` and `hashedrekord` (e.g. `rekord`) cannot be cross-checked and
+/// are rejected, and the body must be of the kind the entry's `kindVersion` names.
+fn verify_entry_body(
+    bundle: &SigstoreBundle,
+    entry: &TransparencyLogEntry,
+    canonicalized_body: &[u8],
+) -> Result<(), VerificationError> {
+    let mismatch = |reason: &str| TransparencyError::EntryBodyMismatch(reason.to_string());
+
+    let body = parse_entry_body(canonicalized_body)?;
+    if let Some(ref kind_version) = entry.kind_version {
+        if kind_version.kind != body.kind.name() {
+            return Err(mismatch(&format!(
+                "{} body for a {} entry",
+                body.kind.name(),
+                kind_version.kind
+            ))
+            .into());
+        }
     }
 
-    // Verify signed entry timestamp if present
-    if let Some(ref inclusion_promise) = entry.inclusion_promise {
-        // TODO: Verify the signed entry timestamp signature
-        // This requires fetching the Rekor public key and verifying the signature
-        // For now, we just check it exists
-        let _set_bytes = decode_base64(&inclusion_promise.signed_entry_timestamp)
-            .map_err(|_| TransparencyError::SignedEntryTimestampInvalid)?;
+    let signature = match (&body.kind, &bundle.content) {
+        (EntryKind::Other(kind), _) => {
+            return Err(mismatch(&format!("{} entries cannot be checked against the bundle", kind)).into())
+        }
+        (EntryKind::Dsse | EntryKind::Intoto, BundleContent::DsseEnvelope(envelope)) => {
+            // Always present in `dsse` bodies (see `parse_entry_body`)
+            if let Some(ref payload_hash) = body.payload_hash {
+                let payload = decode_base64(&envelope.payload)
+                    .map_err(|_| mismatch("payload is not base64"))?;
+                if sha256(&payload).as_slice() != payload_hash {
+                    return Err(mismatch("payload hash differs").into());
+                }
+            }
+            envelope
+                .signatures
+                .first()
+                .and_then(|signature| decode_base64(&signature.sig).ok())
+                .ok_or_else(|| mismatch("envelope has no signature"))?
+        }
+        (EntryKind::HashedRekord, BundleContent::MessageSignature(message_signature)) => {
+            let digest = decode_message_digest(&message_signature.message_digest)
+                .map_err(|_| mismatch("invalid message digest"))?;
+            if body.artifact_hash.as_ref() != Some(&digest) {
+                return Err(mismatch("artifact hash differs").into());
+            }
+            decode_base64(&message_signature.signature)
+                .map_err(|_| mismatch("signature is not base64"))?
+        }
+        (EntryKind::HashedRekord, _) => {
+            return Err(mismatch("hashedrekord entry for a DSSE bundle").into())
+        }
+        _ => return Err(mismatch("DSSE entry for a message signature bundle").into()),
+    };
+
+    let certificate = decode_base64(&bundle.verification_material.certificate.raw_bytes)
+        .map_err(|_| mismatch("certificate is not base64"))?;
+    let logged = body
+        .signatures
+        .iter()
+        .any(|entry| entry.certificate == certificate && entry.signature == signature);
+    if !logged {
+        return Err(mismatch("signature and certificate were not logged").into());
     }
 
     Ok(())
 }
 
+/// The trusted Rekor key of the entry's log, with the log ID in hex
+///
+/// The key is the one in `rekor_keys` whose SHA256 is the entry's log ID, so the
+/// SET and checkpoint are only accepted from the log the journal commits to.
+/// `missing` is returned if the entry has no (base64) log ID.
+fn log_key<'a>(
+    entry: &TransparencyLogEntry,
+    rekor_keys: &'a [Vec<u8>],
+    missing: TransparencyError,
+) -> Result<(&'a [u8], String), VerificationError> {
+    if rekor_keys.is_empty() {
+        return Err(TransparencyError::MissingRekorKeys.into());
+    }
+    let log_id = match entry.log_id.as_ref().and_then(|log_id| decode_base64(&log_id.key_id).ok()) {
+        Some(log_id) => log_id,
+        None => return Err(missing.into()),
+    };
+    let log_id_hex = hex::encode(&log_id);
+
+    let key = rekor_keys
+        .iter()
+        .find(|key| sha256(key).as_slice() == log_id.as_slice())
+        .ok_or_else(|| TransparencyError::UnknownLogKey(log_id_hex.clone()))?;
+    Ok((key, log_id_hex))
+}
+
+/// Verify the signature of a signed entry timestamp (SET)
+///
+/// Rekor signs the canonical JSON (keys sorted, no whitespace) of the entry's
+/// body, integrated time, log ID (hex) and log index, with the key of the
+/// entry's log (see [`log_key`]).
+fn verify_signed_entry_timestamp(
+    entry: &TransparencyLogEntry,
+    set_bytes: &[u8],
+    rekor_keys: &[Vec<u8>],
+) -> Result<(), VerificationError> {
+    let (key, log_id_hex) =
+        log_key(entry, rekor_keys, TransparencyError::SignedEntryTimestampInvalid)?;
+    let public_key = PublicKey::from_spki_der(key)?;
+
+    let payload = signed_entry_timestamp_payload(entry, &log_id_hex)?;
+    public_key
+        .verify_signature_with_encoding(&payload, set_bytes, SignatureEncoding::Der)
+        .map_err(|_| TransparencyError::SignedEntryTimestampSignature(log_id_hex).into())
+}
+
+/// Verify that the log's key (DER SubjectPublicKeyInfo) signed the checkpoint
+///
+/// Only signatures whose key hint names the key are checked: the first 4 bytes
+/// of its log ID, or for Ed25519 keys the signed note key ID
+/// `SHA256(name || "\n" || 0x01 || key)`.
+fn verify_checkpoint_signature(
+    checkpoint: &SignedCheckpoint,
+    key_der: &[u8],
+) -> Result<(), VerificationError> {
+    let log_id_hint = &sha256(key_der)[..4];
+    let key = PublicKey::from_spki_der(key_der)?;
+
+    let verified = checkpoint.signatures.iter().any(|signature| {
+        let hint_matches = match key {
+            PublicKey::Ed25519(ref verifying_key) => {
+                let mut key_id = signature.name.as_bytes().to_vec();
+                key_id.extend_from_slice(b"\n\x01");
+                key_id.extend_from_slice(verifying_key.as_bytes());
+                sha256(&key_id)[..4] == signature.key_hint
+            }
+            _ => log_id_hint == signature.key_hint,
+        };
+        hint_matches
+            && key
+                .verify_signature_with_encoding(
+                    checkpoint.body.as_bytes(),
+                    &signature.signature,
+                    SignatureEncoding::Der,
+                )
+                .is_ok()
+    });
+
+    if verified {
+        Ok(())
+    } else {
+        Err(TransparencyError::CheckpointSignature.into())
+    }
+}
+
+/// Canonical JSON payload covered by a signed entry timestamp
+fn signed_entry_timestamp_payload(
+    entry: &TransparencyLogEntry,
+    log_id_hex: &str,
+) -> Result<Vec<u8>, VerificationError> {
+    // The numbers are re-rendered from their parsed values, as canonical JSON has no
+    // leading zeros or signs; the body is base64 and needs no escaping
+    let integrated_time: i64 = entry
+        .integrated_time
+        .parse()
+        .map_err(|_| TransparencyError::SignedEntryTimestampInvalid)?;
+    let log_index: u64 = entry
+        .log_index
+        .as_deref()
+        .and_then(|log_index| log_index.parse().ok())
+        .ok_or(TransparencyError::SignedEntryTimestampInvalid)?;
+
+    Ok(format!(
+        r#"{{"body":"{}","integratedTime":{},"logID":"{}","logIndex":{}}}"#,
+        entry.canonicalized_body, integrated_time, log_id_hex, log_index
+    )
+    .into_bytes())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::types::bundle::{Certificate, DsseEnvelope, VerificationMaterial};
+    use crate::types::bundle::{BundleContent, Certificate, DsseEnvelope, VerificationMaterial};
+    use alloc::vec;
 
     #[test]
     fn test_missing_tlog_entries() {
@@ -84,14 +343,14 @@ mod tests {
                 },
                 tlog_entries: None,
             },
-            dsse_envelope: DsseEnvelope {
+            content: BundleContent::DsseEnvelope(DsseEnvelope {
                 payload: String::new(),
                 payload_type: String::new(),
                 signatures: vec![],
-            },
+            }),
         };
 
-        let result = verify_transparency_log(&bundle);
+        let result = verify_transparency_log(&bundle, &[]);
         assert!(matches!(
             result,
             Err(VerificationError::Transparency(TransparencyError::NoRekorEntry))
@@ -0,0 +1,297 @@
+//! Seeded corpus of mutated bundles for the negative tests
+//!
+//! Every entry is one of the sample bundles with a single mutation that must make
+//! verification fail: a flipped bit in the DSSE payload or signature, the signing
+//! certificate, the TSA signature or the inclusion proof, or a dropped required
+//! field. The corpus is a pure function of the seed: entry `i` draws from its own
+//! generator, seeded from the corpus seed and `i`, so any entry can be regenerated
+//! on its own.
+//!
+//! Shared by `tests/corpus_test.rs` and the `replay_corpus` example, which
+//! regenerates the corpus of a failing run and writes the offending bundles to
+//! disk.
+
+#![allow(dead_code)]
+
+use base64::prelude::*;
+use serde_json::Value;
+use sigstore_verifier::error::VerificationError;
+use sigstore_verifier::fetcher::jsonl::parser::{
+    load_trusted_root_from_jsonl, select_certificate_authority, select_rekor_keys, select_timestamp_authority,
+};
+use sigstore_verifier::parser::bundle::{extract_bundle_timestamp, parse_bundle_from_bytes};
+use sigstore_verifier::types::certificate::{CertificateChain, FulcioInstance};
+use sigstore_verifier::types::result::{VerificationOptions, VerificationResult};
+use sigstore_verifier::AttestationVerifier;
+use std::path::PathBuf;
+
+/// Environment variable pinning the corpus seed
+pub const SEED_VAR: &str = "SIGSTORE_CORPUS_SEED";
+
+/// Environment variable setting the number of mutated bundles
+pub const SIZE_VAR: &str = "SIGSTORE_CORPUS_SIZE";
+
+/// Number of mutated bundles unless `SIGSTORE_CORPUS_SIZE` is set
+pub const DEFAULT_SIZE: usize = 64;
+
+/// Sample bundles the corpus mutates: two RFC 3161 bundles and a Rekor bundle
+const BASE_SAMPLES: &[&str] = &[
+    "actions-attest-build-provenance-attestation-13581567.sigstore.json",
+    "actions-attest-build-provenance-attestation-13739985.sigstore.json",
+    "actions-attest-build-provenance-attestation-13532655.sigstore.json",
+];
+
+/// Fields whose absence must fail parsing or verification
+const REQUIRED_FIELDS: &[&[&str]] = &[
+    &["mediaType"],
+    &["dsseEnvelope"],
+    &["dsseEnvelope", "payload"],
+    &["dsseEnvelope", "payloadType"],
+    &["dsseEnvelope", "signatures"],
+    &["verificationMaterial"],
+    &["verificationMaterial", "certificate"],
+];
+
+/// SplitMix64: small, fast and identical on every platform
+pub struct CorpusRng(u64);
+
+impl CorpusRng {
+    pub fn new(seed: u64) -> Self {
+        CorpusRng(seed)
+    }
+
+    /// Generator of entry `index` of the corpus of `seed`
+    pub fn for_entry(seed: u64, index: usize) -> Self {
+        let mut rng = CorpusRng(seed ^ (index as u64).wrapping_mul(0xd6e8_feb8_6659_fd93));
+        rng.next_u64();
+        rng
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform value in `0..bound`
+    pub fn below(&mut self, bound: usize) -> usize {
+        (self.next_u64() % bound as u64) as usize
+    }
+}
+
+/// Mutation applied to a base bundle
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mutation {
+    FlipPayloadBit,
+    FlipSignatureBit,
+    FlipCertificateBit,
+    FlipTimestampSignatureBit,
+    FlipInclusionProofBit,
+    DropField,
+}
+
+impl Mutation {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Mutation::FlipPayloadBit => "payload-bit",
+            Mutation::FlipSignatureBit => "signature-bit",
+            Mutation::FlipCertificateBit => "certificate-bit",
+            Mutation::FlipTimestampSignatureBit => "timestamp-signature-bit",
+            Mutation::FlipInclusionProofBit => "inclusion-proof-bit",
+            Mutation::DropField => "drop-field",
+        }
+    }
+}
+
+/// Sample bundle with the trust material it verifies against
+pub struct BaseBundle {
+    pub name: &'static str,
+    pub bundle_json: Vec<u8>,
+    pub fulcio_chain: CertificateChain,
+    pub tsa_chain: Option<CertificateChain>,
+    pub rekor_keys: Vec<Vec<u8>>,
+}
+
+impl BaseBundle {
+    /// Verify `bundle_json` against the trust material of this base bundle
+    pub fn verify(&self, bundle_json: &[u8]) -> Result<VerificationResult, VerificationError> {
+        let options = VerificationOptions::builder().rekor_keys(self.rekor_keys.clone()).build();
+        AttestationVerifier::new().verify_bundle_bytes(bundle_json, options, &self.fulcio_chain, self.tsa_chain.as_ref())
+    }
+
+    fn has_timestamps(&self) -> bool {
+        self.tsa_chain.is_some()
+    }
+
+    fn has_tlog_entries(&self) -> bool {
+        !self.rekor_keys.is_empty()
+    }
+}
+
+/// Entry of the corpus
+pub struct MutatedBundle {
+    pub index: usize,
+    /// Index of the base bundle in [`load_base_bundles`]
+    pub base: usize,
+    pub mutation: Mutation,
+    /// What exactly was mutated, e.g. the flipped bit
+    pub detail: String,
+    pub bundle_json: Vec<u8>,
+}
+
+impl MutatedBundle {
+    /// File name of the entry, unique within the corpus of `seed`
+    pub fn file_name(&self, seed: u64) -> String {
+        format!("corpus-{}-{:04}-{}.sigstore.json", seed, self.index, self.mutation.name())
+    }
+}
+
+pub fn samples_dir() -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.pop();
+    path.pop();
+    path.push("samples");
+    path
+}
+
+/// Load the sample bundles the corpus mutates, with their trust material
+pub fn load_base_bundles() -> Vec<BaseBundle> {
+    let samples = samples_dir();
+    let trusted_root_content = std::fs::read_to_string(samples.join("trusted_root.jsonl"))
+        .expect("Failed to read trusted root file");
+    let trust_roots = load_trusted_root_from_jsonl(&trusted_root_content)
+        .expect("Failed to parse trusted root JSONL");
+
+    BASE_SAMPLES
+        .iter()
+        .map(|name| {
+            let bundle_json = std::fs::read(samples.join(name)).expect("Failed to read bundle");
+            let bundle = parse_bundle_from_bytes(&bundle_json).expect("Failed to parse bundle");
+            let timestamp = extract_bundle_timestamp(&bundle).expect("Failed to extract timestamp");
+            let instance = FulcioInstance::from_bundle_json(std::str::from_utf8(&bundle_json).unwrap())
+                .expect("Failed to detect Fulcio instance");
+            let material = &bundle.verification_material;
+            let has_timestamps = material
+                .timestamp_verification_data
+                .as_ref()
+                .and_then(|data| data.rfc3161_timestamps.as_ref())
+                .is_some_and(|timestamps| !timestamps.is_empty());
+            let has_tlog_entries = material.tlog_entries.as_ref().is_some_and(|entries| !entries.is_empty());
+
+            BaseBundle {
+                name,
+                fulcio_chain: select_certificate_authority(&trust_roots, &instance, timestamp)
+                    .expect("Failed to select certificate authority"),
+                tsa_chain: has_timestamps.then(|| {
+                    select_timestamp_authority(&trust_roots, &instance, timestamp)
+                        .expect("Failed to select timestamp authority")
+                }),
+                rekor_keys: if has_tlog_entries {
+                    select_rekor_keys(&trust_roots, timestamp).expect("Failed to select Rekor keys")
+                } else {
+                    Vec::new()
+                },
+                bundle_json,
+            }
+        })
+        .collect()
+}
+
+/// Seed of the corpus: `SIGSTORE_CORPUS_SEED` if set, otherwise a fresh one
+pub fn corpus_seed() -> u64 {
+    match std::env::var(SEED_VAR) {
+        Ok(seed) => seed.parse().unwrap_or_else(|_| panic!("{} must be an unsigned integer", SEED_VAR)),
+        Err(_) => {
+            let now = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .expect("System clock before the Unix epoch");
+            CorpusRng::new(now.as_nanos() as u64).next_u64()
+        }
+    }
+}
+
+/// Size of the corpus: `SIGSTORE_CORPUS_SIZE` if set, otherwise [`DEFAULT_SIZE`]
+pub fn corpus_size() -> usize {
+    match std::env::var(SIZE_VAR) {
+        Ok(size) => size.parse().unwrap_or_else(|_| panic!("{} must be an unsigned integer", SIZE_VAR)),
+        Err(_) => DEFAULT_SIZE,
+    }
+}
+
+/// Entry `index` of the corpus of `seed`
+pub fn mutated_bundle(bases: &[BaseBundle], seed: u64, index: usize) -> MutatedBundle {
+    let mut rng = CorpusRng::for_entry(seed, index);
+    let base = rng.below(bases.len());
+
+    let mut mutations = vec![
+        Mutation::FlipPayloadBit,
+        Mutation::FlipSignatureBit,
+        Mutation::FlipCertificateBit,
+        Mutation::DropField,
+    ];
+    if bases[base].has_timestamps() {
+        mutations.push(Mutation::FlipTimestampSignatureBit);
+    }
+    if bases[base].has_tlog_entries() {
+        mutations.push(Mutation::FlipInclusionProofBit);
+    }
+    let mutation = mutations[rng.below(mutations.len())];
+
+    let mut bundle: Value = serde_json::from_slice(&bases[base].bundle_json).expect("Failed to parse bundle JSON");
+    let detail = match mutation {
+        Mutation::FlipPayloadBit => flip_base64_bit(&mut bundle["dsseEnvelope"]["payload"], 0, &mut rng),
+        Mutation::FlipSignatureBit => {
+            flip_base64_bit(&mut bundle["dsseEnvelope"]["signatures"][0]["sig"], 0, &mut rng)
+        }
+        Mutation::FlipCertificateBit => {
+            flip_base64_bit(&mut bundle["verificationMaterial"]["certificate"]["rawBytes"], 0, &mut rng)
+        }
+        // The SignerInfo signature ends the token; its embedded certificates are not signed
+        Mutation::FlipTimestampSignatureBit => flip_base64_bit(
+            &mut bundle["verificationMaterial"]["timestampVerificationData"]["rfc3161Timestamps"][0]
+                ["signedTimestamp"],
+            64,
+            &mut rng,
+        ),
+        Mutation::FlipInclusionProofBit => {
+            let hashes = &mut bundle["verificationMaterial"]["tlogEntries"][0]["inclusionProof"]["hashes"];
+            let hash = rng.below(hashes.as_array().expect("Inclusion proof without hashes").len());
+            format!("hash {}: {}", hash, flip_base64_bit(&mut hashes[hash], 0, &mut rng))
+        }
+        Mutation::DropField => {
+            let path = REQUIRED_FIELDS[rng.below(REQUIRED_FIELDS.len())];
+            let (field, parents) = path.split_last().unwrap();
+            let parent = parents.iter().fold(&mut bundle, |value, key| &mut value[*key]);
+            parent.as_object_mut().expect("Not an object").remove(*field);
+            path.join(".")
+        }
+    };
+
+    MutatedBundle {
+        index,
+        base,
+        mutation,
+        detail,
+        bundle_json: serde_json::to_vec(&bundle).expect("Failed to serialize bundle"),
+    }
+}
+
+/// The corpus of `seed`
+pub fn mutated_corpus(bases: &[BaseBundle], seed: u64, size: usize) -> Vec<MutatedBundle> {
+    (0..size).map(|index| mutated_bundle(bases, seed, index)).collect()
+}
+
+/// Flip a bit of a base64 string field, within its last `tail` bytes unless `tail` is 0
+fn flip_base64_bit(field: &mut Value, tail: usize, rng: &mut CorpusRng) -> String {
+    let mut bytes = BASE64_STANDARD
+        .decode(field.as_str().expect("Not a string field"))
+        .expect("Not a base64 field");
+    let start = if tail == 0 { 0 } else { bytes.len().saturating_sub(tail) };
+    let byte = start + rng.below(bytes.len() - start);
+    let bit = rng.below(8);
+    bytes[byte] ^= 1 << bit;
+    *field = Value::String(BASE64_STANDARD.encode(&bytes));
+    format!("byte {} bit {}", byte, bit)
+}
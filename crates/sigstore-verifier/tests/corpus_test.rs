@@ -0,0 +1,71 @@
+//! Negative tests over a seeded corpus of mutated bundles.
+//!
+//! Each run draws a fresh seed unless `SIGSTORE_CORPUS_SEED` pins one, so the corpus
+//! grows its coverage over CI runs. A failure names the seed and the entry; replay it
+//! with the `replay_corpus` example, which writes the offending bundles to disk:
+//!
+//! ```bash
+//! cargo run -p sigstore-verifier --example replay_corpus -- --seed <seed> --out corpus
+//! ```
+
+mod corpus;
+
+use corpus::{corpus_seed, corpus_size, load_base_bundles, mutated_bundle, mutated_corpus, CorpusRng};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+#[test]
+fn test_base_bundles_verify() {
+    for base in load_base_bundles() {
+        base.verify(&base.bundle_json)
+            .unwrap_or_else(|e| panic!("Base bundle {} failed verification: {}", base.name, e));
+    }
+}
+
+#[test]
+fn test_corpus_is_deterministic() {
+    let bases = load_base_bundles();
+    let seed = CorpusRng::new(7).next_u64();
+
+    let corpus = mutated_corpus(&bases, seed, 16);
+    for entry in &corpus {
+        let replayed = mutated_bundle(&bases, seed, entry.index);
+        assert_eq!(replayed.bundle_json, entry.bundle_json);
+        assert_eq!(replayed.detail, entry.detail);
+    }
+    assert_ne!(mutated_corpus(&bases, seed + 1, 16)[0].bundle_json, corpus[0].bundle_json);
+}
+
+#[test]
+fn test_mutated_bundles_are_rejected() {
+    let bases = load_base_bundles();
+    let seed = corpus_seed();
+    let size = corpus_size();
+
+    let mut failures = Vec::new();
+    for entry in mutated_corpus(&bases, seed, size) {
+        let base = &bases[entry.base];
+        let outcome = match catch_unwind(AssertUnwindSafe(|| base.verify(&entry.bundle_json))) {
+            Ok(Ok(_)) => "was accepted",
+            Ok(Err(_)) => continue,
+            Err(_) => "panicked",
+        };
+        failures.push(format!(
+            "  entry {} ({} of {}, {}) {}",
+            entry.index,
+            entry.mutation.name(),
+            base.name,
+            entry.detail,
+            outcome
+        ));
+    }
+
+    assert!(
+        failures.is_empty(),
+        "Corpus seed {} (size {}):\n{}\nReplay with: cargo run -p sigstore-verifier --example replay_corpus -- --seed {} --size {}",
+        seed,
+        size,
+        failures.join("\n"),
+        seed,
+        size
+    );
+}
@@ -0,0 +1,113 @@
+//! Determinism checks for the serialized verification result.
+//!
+//! The journal committed by the zkVM guests is `VerificationResult::as_slice()`, so the
+//! same bundle and trust roots must always produce bit-identical bytes: across repeated
+//! runs, across threads, and across targets (host vs. guest). The pinned digest below
+//! catches any change to the encoding or any input-independent source of variation; update
+//! it only together with an intentional journal format change.
+
+use sigstore_verifier::fetcher::jsonl::parser::{
+    load_trusted_root_from_jsonl, select_certificate_authority, select_timestamp_authority,
+};
+use sigstore_verifier::parser::bundle::{extract_bundle_timestamp, parse_bundle_from_bytes};
+use sigstore_verifier::types::certificate::{CertificateChain, FulcioInstance};
+use sigstore_verifier::types::result::VerificationOptions;
+use sigstore_verifier::AttestationVerifier;
+use sha2::{Digest, Sha256};
+use std::path::PathBuf;
+
+/// SHA256 of the journal produced for the RFC 3161 sample bundle
+///
+/// The journal commits to the crate version, so this changes on every version bump.
+const RFC3161_SAMPLE_JOURNAL_SHA256: &str =
+    "2a9c89a9e20c9112e746613e63488b5f5ced8e89aa442cd9e6bd9433f840c62f";
+
+const ITERATIONS: usize = 16;
+const THREADS: usize = 8;
+
+struct Fixture {
+    bundle_json: Vec<u8>,
+    fulcio_chain: CertificateChain,
+    tsa_chain: CertificateChain,
+}
+
+fn samples_dir() -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.pop();
+    path.pop();
+    path.push("samples");
+    path
+}
+
+fn load_rfc3161_fixture() -> Fixture {
+    let samples = samples_dir();
+    let bundle_json = std::fs::read(
+        samples.join("actions-attest-build-provenance-attestation-13581567.sigstore.json"),
+    )
+    .expect("Failed to read bundle");
+
+    let trusted_root_content = std::fs::read_to_string(samples.join("trusted_root.jsonl"))
+        .expect("Failed to read trusted root file");
+    let trust_roots = load_trusted_root_from_jsonl(&trusted_root_content)
+        .expect("Failed to parse trusted root JSONL");
+
+    let bundle = parse_bundle_from_bytes(&bundle_json).expect("Failed to parse bundle");
+    let timestamp = extract_bundle_timestamp(&bundle).expect("Failed to extract timestamp");
+    let instance = FulcioInstance::from_bundle_json(std::str::from_utf8(&bundle_json).unwrap())
+        .expect("Failed to detect Fulcio instance");
+
+    Fixture {
+        fulcio_chain: select_certificate_authority(&trust_roots, &instance, timestamp)
+            .expect("Failed to select certificate authority"),
+        tsa_chain: select_timestamp_authority(&trust_roots, &instance, timestamp)
+            .expect("Failed to select timestamp authority"),
+        bundle_json,
+    }
+}
+
+fn journal(fixture: &Fixture) -> Vec<u8> {
+    AttestationVerifier::new()
+        .verify_bundle_bytes(
+            &fixture.bundle_json,
+            VerificationOptions::default(),
+            &fixture.fulcio_chain,
+            Some(&fixture.tsa_chain),
+        )
+        .expect("Verification failed")
+        .as_slice()
+}
+
+#[test]
+fn test_repeated_verification_is_bit_identical() {
+    let fixture = load_rfc3161_fixture();
+    let expected = journal(&fixture);
+
+    for _ in 0..ITERATIONS {
+        assert_eq!(journal(&fixture), expected);
+    }
+}
+
+#[test]
+fn test_concurrent_verification_is_bit_identical() {
+    let fixture = load_rfc3161_fixture();
+    let expected = journal(&fixture);
+
+    std::thread::scope(|scope| {
+        let handles: Vec<_> = (0..THREADS)
+            .map(|_| scope.spawn(|| (0..ITERATIONS / 4).map(|_| journal(&fixture)).collect::<Vec<_>>()))
+            .collect();
+
+        for handle in handles {
+            for output in handle.join().expect("Verification thread panicked") {
+                assert_eq!(output, expected);
+            }
+        }
+    });
+}
+
+#[test]
+fn test_journal_matches_pinned_digest() {
+    let fixture = load_rfc3161_fixture();
+    let digest = Sha256::digest(journal(&fixture));
+    assert_eq!(hex::encode(digest), RFC3161_SAMPLE_JOURNAL_SHA256);
+}
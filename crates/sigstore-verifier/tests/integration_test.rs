@@ -6,6 +6,7 @@ use std::path::PathBuf;
 #[test]
 #[cfg(feature = "fetcher")]
 fn test_verify_rekor_bundle() {
+    use sigstore_verifier::fetcher::http::default_client;
     use sigstore_verifier::fetcher::trust_bundle::fetch_fulcio_trust_bundle;
 
     let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
@@ -20,13 +21,13 @@ fn test_verify_rekor_bundle() {
 
     // Fetch trust bundle for detected instance
     // In production, the client should fetch and cache this
-    let trust_bundle = fetch_fulcio_trust_bundle(&instance).expect("Failed to fetch trust bundle");
+    let client = default_client().expect("Failed to create HTTP client");
+    let trust_bundle = fetch_fulcio_trust_bundle(&*client, &instance).expect("Failed to fetch trust bundle");
 
     let verifier = AttestationVerifier::new();
     let options = VerificationOptions {
-        expected_digest: None,
-        expected_issuer: None,
-        expected_subject: None,
+        rekor_keys: rekor_sample().1,
+        ..Default::default()
     };
 
     let result = verifier.verify_bundle(&path, options, &trust_bundle, None);
@@ -80,11 +81,7 @@ fn test_verify_rfc3161_bundle() {
     let timestamp = extract_bundle_timestamp(&bundle).expect("Failed to extract timestamp");
 
     let verifier = AttestationVerifier::new();
-    let options = VerificationOptions {
-        expected_digest: None,
-        expected_issuer: None,
-        expected_subject: None,
-    };
+    let options = VerificationOptions::default();
 
     let fulcio_chain = select_certificate_authority(&trust_roots, &fulcio_instance, timestamp)
         .expect("Failed to select certificate authority");
@@ -95,6 +92,8 @@ fn test_verify_rfc3161_bundle() {
     assert!(result.is_ok(), "Verification failed: {:?}", result.err());
 
     if let Ok(verification_result) = result {
+        assert_eq!(verification_result.predicate_type, "https://slsa.dev/provenance/v1");
+        assert_ne!(verification_result.predicate_sha256, [0u8; 32]);
         println!("Verification succeeded!");
         println!(
             "Leaf hash: {}",
@@ -106,4 +105,1060 @@ fn test_verify_rfc3161_bundle() {
         );
         println!("Signing time: {}", verification_result.signing_time);
     }
-}
\ No newline at end of file
+}
+#[test]
+fn test_verify_rfc3161_bundle_archival() {
+    use chrono::DateTime;
+    use sigstore_verifier::fetcher::jsonl::parser::{
+        load_trusted_root_from_jsonl, select_certificate_authority, select_timestamp_authority,
+    };
+    use sigstore_verifier::parser::bundle::{extract_bundle_timestamp, parse_bundle_from_path};
+    use sigstore_verifier::verifier::timestamp::verify_chain_valid_at;
+
+    let mut samples = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    samples.pop();
+    samples.pop();
+    samples.push("samples");
+    let path = samples.join("actions-attest-build-provenance-attestation-13581567.sigstore.json");
+
+    let bundle_json = std::fs::read_to_string(&path).expect("Failed to read bundle");
+    let fulcio_instance =
+        FulcioInstance::from_bundle_json(&bundle_json).expect("Failed to detect Fulcio instance");
+    let trusted_root_content = std::fs::read_to_string(samples.join("trusted_root.jsonl"))
+        .expect("Failed to read trusted root file");
+    let trust_roots = load_trusted_root_from_jsonl(&trusted_root_content)
+        .expect("Failed to parse trusted root JSONL");
+    let bundle = parse_bundle_from_path(&path).expect("Failed to parse bundle");
+    let timestamp = extract_bundle_timestamp(&bundle).expect("Failed to extract timestamp");
+    let fulcio_chain = select_certificate_authority(&trust_roots, &fulcio_instance, timestamp)
+        .expect("Failed to select certificate authority");
+    let tsa_chain = select_timestamp_authority(&trust_roots, &fulcio_instance, timestamp)
+        .expect("Failed to select timestamp authority");
+
+    let verifier = AttestationVerifier::new();
+    let standard = verifier
+        .verify_bundle(&path, VerificationOptions::default(), &fulcio_chain, Some(&tsa_chain))
+        .expect("Standard verification failed");
+    let archival = verifier
+        .verify_bundle(
+            &path,
+            VerificationOptions {
+                archival: true,
+                ..Default::default()
+            },
+            &fulcio_chain,
+            Some(&tsa_chain),
+        )
+        .expect("Archival verification failed");
+
+    // Same attestation, only the mode flag differs
+    assert!(!standard.is_archival());
+    assert!(archival.is_archival());
+    assert_eq!(archival.options_hash, standard.options_hash);
+    assert_eq!(archival.certificate_hashes, standard.certificate_hashes);
+    assert_eq!(archival.signing_time, standard.signing_time);
+    assert_ne!(archival.as_slice(), standard.as_slice());
+
+    // The chain is held to the signing time, not to any other time
+    assert!(verify_chain_valid_at(&archival.signing_time, &tsa_chain).is_ok());
+    let before_issuance = DateTime::from_timestamp(0, 0).unwrap();
+    assert!(verify_chain_valid_at(&before_issuance, &fulcio_chain).is_err());
+}
+
+#[test]
+fn test_verify_bundle_rejects_version_outside_allow_list() {
+    use sigstore_verifier::error::VerificationError;
+    use sigstore_verifier::fetcher::jsonl::parser::{
+        load_trusted_root_from_jsonl, select_certificate_authority, select_timestamp_authority,
+    };
+    use sigstore_verifier::parser::bundle::{extract_bundle_timestamp, parse_bundle_from_path};
+
+    let mut samples = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    samples.pop();
+    samples.pop();
+    samples.push("samples");
+    let path = samples.join("actions-attest-build-provenance-attestation-13581567.sigstore.json");
+
+    let bundle_json = std::fs::read_to_string(&path).expect("Failed to read bundle");
+    let fulcio_instance =
+        FulcioInstance::from_bundle_json(&bundle_json).expect("Failed to detect Fulcio instance");
+    let trusted_root_content = std::fs::read_to_string(samples.join("trusted_root.jsonl"))
+        .expect("Failed to read trusted root file");
+    let trust_roots = load_trusted_root_from_jsonl(&trusted_root_content)
+        .expect("Failed to parse trusted root JSONL");
+    let bundle = parse_bundle_from_path(&path).expect("Failed to parse bundle");
+    let timestamp = extract_bundle_timestamp(&bundle).expect("Failed to extract timestamp");
+    let fulcio_chain = select_certificate_authority(&trust_roots, &fulcio_instance, timestamp)
+        .expect("Failed to select certificate authority");
+    let tsa_chain = select_timestamp_authority(&trust_roots, &fulcio_instance, timestamp)
+        .expect("Failed to select timestamp authority");
+
+    let verify = |allowed: &[&str]| {
+        AttestationVerifier::new().verify_bundle(
+            &path,
+            VerificationOptions {
+                allowed_bundle_versions: allowed.iter().map(|v| v.to_string()).collect(),
+                ..Default::default()
+            },
+            &fulcio_chain,
+            Some(&tsa_chain),
+        )
+    };
+
+    assert!(verify(&["0.3"]).is_ok());
+    match verify(&["0.1", "0.2"]) {
+        Err(VerificationError::UnsupportedMediaType { version, nearest, .. }) => {
+            assert_eq!(version, "0.3");
+            assert_eq!(nearest, "0.2");
+        }
+        other => panic!("expected UnsupportedMediaType, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_verify_bundle_report() {
+    use sigstore_verifier::error::VerificationError;
+    use sigstore_verifier::fetcher::jsonl::parser::{
+        load_trusted_root_from_jsonl, select_certificate_authority, select_timestamp_authority,
+    };
+    use sigstore_verifier::parser::bundle::{extract_bundle_timestamp, parse_bundle_from_path};
+    use sigstore_verifier::report::StepStatus;
+
+    let mut samples = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    samples.pop();
+    samples.pop();
+    samples.push("samples");
+    let path = samples.join("actions-attest-build-provenance-attestation-13581567.sigstore.json");
+
+    let bundle_json = std::fs::read(&path).expect("Failed to read bundle");
+    let trusted_root_content = std::fs::read_to_string(samples.join("trusted_root.jsonl"))
+        .expect("Failed to read trusted root file");
+    let trust_roots = load_trusted_root_from_jsonl(&trusted_root_content)
+        .expect("Failed to parse trusted root JSONL");
+    let bundle = parse_bundle_from_path(&path).expect("Failed to parse bundle");
+    let timestamp = extract_bundle_timestamp(&bundle).expect("Failed to extract timestamp");
+    let fulcio_chain = select_certificate_authority(&trust_roots, &FulcioInstance::GitHub, timestamp)
+        .expect("Failed to select certificate authority");
+    let tsa_chain = select_timestamp_authority(&trust_roots, &FulcioInstance::GitHub, timestamp)
+        .expect("Failed to select timestamp authority");
+    let verifier = AttestationVerifier::new();
+
+    let report = verifier.verify_bundle_report(&bundle_json, VerificationOptions::default(), &fulcio_chain, Some(&tsa_chain));
+    let expected = verifier
+        .verify_bundle_bytes(&bundle_json, VerificationOptions::default(), &fulcio_chain, Some(&tsa_chain))
+        .unwrap();
+    assert_eq!(report.result.as_ref().unwrap().as_slice(), expected.as_slice());
+    assert_eq!(report.failed_step(), None);
+    let steps: Vec<_> = report.steps.iter().map(|step| step.step).collect();
+    for step in ["parse_bundle", "subject", "certificate_chain", "dsse_signature", "rfc3161_timestamp", "identity"] {
+        assert!(steps.contains(&step), "{} not reported in {:?}", step, steps);
+    }
+    assert!(!steps.contains(&"transparency_log"));
+    assert!(report.steps.iter().all(|step| step.status == StepStatus::Passed));
+
+    // A rejected bundle reports the steps up to the failing one
+    let options = VerificationOptions {
+        expected_digest: Some(vec![0xab; 32]),
+        ..Default::default()
+    };
+    let report = verifier.verify_bundle_report(&bundle_json, options, &fulcio_chain, Some(&tsa_chain));
+    assert!(matches!(report.result, Err(VerificationError::SubjectDigestMismatch { .. })));
+    assert_eq!(report.failed_step(), Some("subject"));
+    assert_eq!(report.steps.last().unwrap().step, "subject");
+    assert!(report.to_string().contains("FAILED subject"));
+
+    let report = verifier.verify_bundle_report(b"{", VerificationOptions::default(), &fulcio_chain, None);
+    assert_eq!(report.failed_step(), Some("parse_bundle"));
+}
+
+#[cfg(feature = "fetcher")]
+#[test]
+fn test_verify_bundle_with_repository_policy() {
+    use sigstore_verifier::error::VerificationError;
+    use sigstore_verifier::fetcher::jsonl::parser::{
+        load_trusted_root_from_jsonl, select_certificate_authority, select_timestamp_authority,
+    };
+    use sigstore_verifier::parser::bundle::{extract_bundle_timestamp, parse_bundle_from_path};
+    use sigstore_verifier::types::result::TimestampKind;
+    use sigstore_verifier::verifier::repository_policy::RepositoryPolicy;
+
+    let mut samples = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    samples.pop();
+    samples.pop();
+    samples.push("samples");
+    let path = samples.join("actions-attest-build-provenance-attestation-13581567.sigstore.json");
+
+    let bundle_json = std::fs::read_to_string(&path).expect("Failed to read bundle");
+    let fulcio_instance =
+        FulcioInstance::from_bundle_json(&bundle_json).expect("Failed to detect Fulcio instance");
+    let trusted_root_content = std::fs::read_to_string(samples.join("trusted_root.jsonl"))
+        .expect("Failed to read trusted root file");
+    let trust_roots = load_trusted_root_from_jsonl(&trusted_root_content)
+        .expect("Failed to parse trusted root JSONL");
+    let bundle = parse_bundle_from_path(&path).expect("Failed to parse bundle");
+    let timestamp = extract_bundle_timestamp(&bundle).expect("Failed to extract timestamp");
+    let fulcio_chain = select_certificate_authority(&trust_roots, &fulcio_instance, timestamp)
+        .expect("Failed to select certificate authority");
+    let tsa_chain = select_timestamp_authority(&trust_roots, &fulcio_instance, timestamp)
+        .expect("Failed to select timestamp authority");
+
+    let verify = |policy: &RepositoryPolicy| {
+        AttestationVerifier::new().verify_bundle(
+            &path,
+            VerificationOptions::builder().repository_policy(policy.clone()).build(),
+            &fulcio_chain,
+            Some(&tsa_chain),
+        )
+    };
+
+    let policy = RepositoryPolicy {
+        repository: "https://github.com/actions/*".to_string(),
+        issuer: Some("https://token.actions.githubusercontent.com".to_string()),
+        workflows: vec!["https://github.com/actions/*/.github/workflows/prober*.yml@refs/heads/*".to_string()],
+        timestamp: Some(TimestampKind::Rfc3161),
+        max_age: None,
+    };
+    let result = verify(&policy).expect("Verification failed");
+    let options = result.decode_verification_options().unwrap().unwrap();
+    assert_eq!(options.repositoryPolicyHash.0, policy.hash());
+    assert_eq!(options.expectedIssuer, "https://token.actions.githubusercontent.com");
+    assert_eq!(options.requiredTimestamp, TimestampKind::Rfc3161 as u8);
+
+    // Another organisation or workflow is rejected, and so is another timestamp mechanism
+    for rejected in [
+        RepositoryPolicy {
+            repository: "https://github.com/acme/*".to_string(),
+            ..policy.clone()
+        },
+        RepositoryPolicy {
+            workflows: vec!["https://github.com/actions/*/.github/workflows/release.yml@*".to_string()],
+            ..policy.clone()
+        },
+    ] {
+        assert!(matches!(verify(&rejected), Err(VerificationError::RepositoryPolicyRejected(_))));
+    }
+    let rekor = RepositoryPolicy {
+        timestamp: Some(TimestampKind::Rekor),
+        ..policy.clone()
+    };
+    assert!(verify(&rekor).is_err());
+}
+
+#[cfg(feature = "fetcher")]
+#[test]
+fn test_verify_bundle_outcome() {
+    use sigstore_verifier::error::VerificationError;
+    use sigstore_verifier::fetcher::jsonl::parser::{
+        load_trusted_root_from_jsonl, select_certificate_authority, select_timestamp_authority,
+    };
+    use sigstore_verifier::outcome::VerificationOutcome;
+    use sigstore_verifier::parser::bundle::{extract_bundle_timestamp, parse_bundle_from_path};
+    use sigstore_verifier::verifier::repository_policy::RepositoryPolicy;
+
+    let mut samples = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    samples.pop();
+    samples.pop();
+    samples.push("samples");
+    let path = samples.join("actions-attest-build-provenance-attestation-13581567.sigstore.json");
+
+    let bundle_json = std::fs::read(&path).expect("Failed to read bundle");
+    let trusted_root_content = std::fs::read_to_string(samples.join("trusted_root.jsonl"))
+        .expect("Failed to read trusted root file");
+    let trust_roots = load_trusted_root_from_jsonl(&trusted_root_content)
+        .expect("Failed to parse trusted root JSONL");
+    let bundle = parse_bundle_from_path(&path).expect("Failed to parse bundle");
+    let timestamp = extract_bundle_timestamp(&bundle).expect("Failed to extract timestamp");
+    let fulcio_chain = select_certificate_authority(&trust_roots, &FulcioInstance::GitHub, timestamp)
+        .expect("Failed to select certificate authority");
+    let tsa_chain = select_timestamp_authority(&trust_roots, &FulcioInstance::GitHub, timestamp)
+        .expect("Failed to select timestamp authority");
+    let verifier = AttestationVerifier::new();
+
+    let verified = verifier
+        .verify_bundle_bytes(&bundle_json, VerificationOptions::default(), &fulcio_chain, Some(&tsa_chain))
+        .unwrap();
+    let outcome =
+        verifier.verify_bundle_outcome(&bundle_json, VerificationOptions::default(), &fulcio_chain, Some(&tsa_chain));
+    assert!(outcome.is_verified());
+    assert_eq!(outcome.result().unwrap().as_slice(), verified.as_slice());
+
+    // A bundle for another artifact verifies, but violates the policy
+    let options = VerificationOptions::builder().expected_digest(vec![0xab; 32]).build();
+    let outcome = verifier.verify_bundle_outcome(&bundle_json, options.clone(), &fulcio_chain, Some(&tsa_chain));
+    assert_eq!(outcome.name(), "policy_violation");
+    let VerificationOutcome::PolicyViolation { result, violation } = outcome else {
+        panic!("expected a policy violation");
+    };
+    assert!(matches!(violation, VerificationError::SubjectDigestMismatch { .. }));
+    assert!(result.is_policy_violation());
+    assert_eq!(result.flags & !sigstore_verifier::types::result::flags::POLICY_VIOLATION, verified.flags);
+    assert_eq!(result.subject_digest, verified.subject_digest);
+    // The violated options are committed, not the ones the result was produced with
+    assert_eq!(result.options_hash, options.hash());
+    assert_ne!(result.options_hash, verified.options_hash);
+
+    // Quarantine mode returns the same result from the other methods, and so from the guests
+    let quarantine = VerificationOptions {
+        quarantine: true,
+        ..options.clone()
+    };
+    let quarantined = verifier
+        .verify_bundle_bytes(&bundle_json, quarantine, &fulcio_chain, Some(&tsa_chain))
+        .unwrap();
+    assert_eq!(quarantined.as_slice(), result.as_slice());
+    assert!(verifier.verify_bundle_bytes(&bundle_json, options.clone(), &fulcio_chain, Some(&tsa_chain)).is_err());
+
+    // Policies added by a repository policy are committed after they are applied
+    let policy = RepositoryPolicy {
+        repository: "https://github.com/acme/*".to_string(),
+        issuer: Some("https://token.actions.githubusercontent.com".to_string()),
+        workflows: Vec::new(),
+        timestamp: None,
+        max_age: None,
+    };
+    let options = VerificationOptions::builder().repository_policy(policy.clone()).build();
+    let outcome = verifier.verify_bundle_outcome(&bundle_json, options, &fulcio_chain, Some(&tsa_chain));
+    assert!(matches!(outcome.error(), Some(VerificationError::RepositoryPolicyRejected(_))));
+    let committed = outcome.result().unwrap().decode_verification_options().unwrap().unwrap();
+    assert_eq!(committed.repositoryPolicyHash.0, policy.hash());
+    assert_eq!(committed.expectedIssuer, "https://token.actions.githubusercontent.com");
+
+    // A bundle that fails without the policy too is invalid, whatever the policy
+    let options = VerificationOptions::builder().expected_digest(vec![0xab; 32]).quarantine(true).build();
+    let outcome = verifier.verify_bundle_outcome(&bundle_json, options.clone(), &fulcio_chain, None);
+    assert_eq!(outcome.name(), "invalid");
+    assert_eq!(outcome.error().unwrap().code(), 407);
+    assert!(outcome.result().is_none());
+    assert!(verifier.verify_bundle_bytes(&bundle_json, options, &fulcio_chain, None).is_err());
+    let outcome = verifier.verify_bundle_outcome(b"{", VerificationOptions::default(), &fulcio_chain, None);
+    assert!(matches!(outcome, VerificationOutcome::Invalid { error: VerificationError::BundleParse(_) }));
+}
+
+#[test]
+fn test_verify_tsa_policy() {
+    use sigstore_verifier::error::{TimestampError, VerificationError};
+    use sigstore_verifier::fetcher::jsonl::parser::{
+        load_trusted_root_from_jsonl, select_certificate_authority, select_ctlog_keys, select_timestamp_authority,
+    };
+    use sigstore_verifier::parser::bundle::{extract_bundle_timestamp, parse_bundle_from_path};
+    use sigstore_verifier::types::result::TimestampKind;
+
+    let mut samples = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    samples.pop();
+    samples.pop();
+    samples.push("samples");
+    let path = samples.join("actions-attest-build-provenance-attestation-13581567.sigstore.json");
+
+    let bundle_json = std::fs::read(&path).expect("Failed to read bundle");
+    let trust_roots = load_trusted_root_from_jsonl(
+        &std::fs::read_to_string(samples.join("trusted_root.jsonl")).expect("Failed to read trusted root"),
+    )
+    .expect("Failed to parse trusted root JSONL");
+    let bundle = parse_bundle_from_path(&path).expect("Failed to parse bundle");
+    let timestamp = extract_bundle_timestamp(&bundle).expect("Failed to extract timestamp");
+    let fulcio_chain = select_certificate_authority(&trust_roots, &FulcioInstance::GitHub, timestamp)
+        .expect("Failed to select certificate authority");
+    let tsa_chain = select_timestamp_authority(&trust_roots, &FulcioInstance::GitHub, timestamp)
+        .expect("Failed to select timestamp authority");
+    let verifier = AttestationVerifier::new();
+
+    // GitHub's TSA issues its tokens under 1.3.6.1.4.1.57264.2
+    let options = VerificationOptions::builder().expected_tsa_policy("1.3.6.1.4.1.57264.2").build();
+    let result = verifier
+        .verify_bundle_bytes(&bundle_json, options.clone(), &fulcio_chain, Some(&tsa_chain))
+        .expect("Verification failed");
+    let committed = result.decode_verification_options().unwrap().unwrap();
+    assert_eq!(committed.expectedTsaPolicy, "1.3.6.1.4.1.57264.2");
+    assert_eq!(result.options_hash, options.hash());
+
+    let options = VerificationOptions::builder().expected_tsa_policy("1.3.6.1.4.1.57264.3").build();
+    let err = verifier
+        .verify_bundle_bytes(&bundle_json, options, &fulcio_chain, Some(&tsa_chain))
+        .unwrap_err();
+    assert!(matches!(err, VerificationError::Timestamp(TimestampError::Rfc3161PolicyMismatch { .. })));
+    assert_eq!(err.code(), 413);
+    assert!(err.is_policy_violation());
+
+    // A TSA policy requires an RFC 3161 timestamp
+    let rekor_path = samples.join("actions-attest-build-provenance-attestation-13532655.sigstore.json");
+    let rekor_bundle = parse_bundle_from_path(&rekor_path).expect("Failed to parse bundle");
+    let rekor_timestamp = extract_bundle_timestamp(&rekor_bundle).expect("Failed to extract timestamp");
+    let public_chain = select_certificate_authority(&trust_roots, &FulcioInstance::PublicGood, rekor_timestamp)
+        .expect("Failed to select certificate authority");
+    let ctlog_keys = select_ctlog_keys(&trust_roots, rekor_timestamp).expect("Failed to select CT log keys");
+    let options = VerificationOptions::builder()
+        .ctlog_keys(ctlog_keys)
+        .expected_tsa_policy("1.3.6.1.4.1.57264.2")
+        .build();
+    let err = verifier.verify_bundle(&rekor_path, options.clone(), &public_chain, None).unwrap_err();
+    assert!(matches!(err, VerificationError::Timestamp(TimestampError::UnexpectedMechanism { .. })));
+
+    let options = VerificationOptions {
+        required_timestamp: Some(TimestampKind::Rekor),
+        ..options
+    };
+    let err = verifier.verify_bundle(&rekor_path, options, &public_chain, None).unwrap_err();
+    assert!(matches!(err, VerificationError::InvalidOptions(_)));
+}
+
+#[test]
+fn test_verify_pre_v03_bundle_layouts() {
+    use sigstore_verifier::fetcher::jsonl::parser::{
+        load_trusted_root_from_jsonl, select_certificate_authority, select_timestamp_authority,
+    };
+    use sigstore_verifier::parser::bundle::{extract_bundle_timestamp, parse_bundle_from_path};
+
+    let mut samples = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    samples.pop();
+    samples.pop();
+    samples.push("samples");
+    let path = samples.join("actions-attest-build-provenance-attestation-13581567.sigstore.json");
+
+    let bundle_json = std::fs::read_to_string(&path).expect("Failed to read bundle");
+    let fulcio_instance =
+        FulcioInstance::from_bundle_json(&bundle_json).expect("Failed to detect Fulcio instance");
+    let trusted_root_content = std::fs::read_to_string(samples.join("trusted_root.jsonl"))
+        .expect("Failed to read trusted root file");
+    let trust_roots = load_trusted_root_from_jsonl(&trusted_root_content)
+        .expect("Failed to parse trusted root JSONL");
+    let bundle = parse_bundle_from_path(&path).expect("Failed to parse bundle");
+    let timestamp = extract_bundle_timestamp(&bundle).expect("Failed to extract timestamp");
+    let fulcio_chain = select_certificate_authority(&trust_roots, &fulcio_instance, timestamp)
+        .expect("Failed to select certificate authority");
+    let tsa_chain = select_timestamp_authority(&trust_roots, &fulcio_instance, timestamp)
+        .expect("Failed to select timestamp authority");
+
+    let verify = |json: &[u8]| {
+        AttestationVerifier::new()
+            .verify_bundle_bytes(json, VerificationOptions::default(), &fulcio_chain, Some(&tsa_chain))
+            .expect("Failed to verify bundle")
+    };
+    let expected = verify(bundle_json.as_bytes());
+
+    for media_type in [
+        "application/vnd.dev.sigstore.bundle+json;version=0.1",
+        "application/vnd.dev.sigstore.bundle+json;version=0.2",
+    ] {
+        let mut json: serde_json::Value = serde_json::from_str(&bundle_json).unwrap();
+        json["mediaType"] = media_type.into();
+        let material = json["verificationMaterial"].as_object_mut().unwrap();
+        let certificate = material.remove("certificate").unwrap();
+        material.insert(
+            "x509CertificateChain".to_string(),
+            serde_json::json!({ "certificates": [certificate] }),
+        );
+
+        let result = verify(json.to_string().as_bytes());
+        assert_eq!(result.as_slice(), expected.as_slice(), "{}", media_type);
+    }
+}
+
+#[test]
+fn test_verify_bundle_with_detached_payload() {
+    use base64::prelude::*;
+    use sigstore_verifier::error::VerificationError;
+    use sigstore_verifier::fetcher::jsonl::parser::{
+        load_trusted_root_from_jsonl, select_certificate_authority, select_timestamp_authority,
+    };
+    use sigstore_verifier::parser::bundle::{extract_bundle_timestamp, parse_bundle_from_path};
+
+    let mut samples = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    samples.pop();
+    samples.pop();
+    samples.push("samples");
+    let path = samples.join("actions-attest-build-provenance-attestation-13581567.sigstore.json");
+
+    let bundle_json = std::fs::read_to_string(&path).expect("Failed to read bundle");
+    let fulcio_instance =
+        FulcioInstance::from_bundle_json(&bundle_json).expect("Failed to detect Fulcio instance");
+    let trusted_root_content = std::fs::read_to_string(samples.join("trusted_root.jsonl"))
+        .expect("Failed to read trusted root file");
+    let trust_roots = load_trusted_root_from_jsonl(&trusted_root_content)
+        .expect("Failed to parse trusted root JSONL");
+    let bundle = parse_bundle_from_path(&path).expect("Failed to parse bundle");
+    let timestamp = extract_bundle_timestamp(&bundle).expect("Failed to extract timestamp");
+    let fulcio_chain = select_certificate_authority(&trust_roots, &fulcio_instance, timestamp)
+        .expect("Failed to select certificate authority");
+    let tsa_chain = select_timestamp_authority(&trust_roots, &fulcio_instance, timestamp)
+        .expect("Failed to select timestamp authority");
+
+    // Ship the envelope without its payload
+    let statement = BASE64_STANDARD
+        .decode(&bundle.dsse_envelope().unwrap().payload)
+        .expect("Failed to decode payload");
+    let mut detached: serde_json::Value =
+        serde_json::from_str(&bundle_json).expect("Failed to parse bundle JSON");
+    detached["dsseEnvelope"]
+        .as_object_mut()
+        .unwrap()
+        .remove("payload");
+    let detached_json = detached.to_string();
+
+    let verifier = AttestationVerifier::new();
+    let verify = |detached_payload: Option<Vec<u8>>| {
+        verifier.verify_bundle_bytes(
+            detached_json.as_bytes(),
+            VerificationOptions {
+                detached_payload,
+                ..Default::default()
+            },
+            &fulcio_chain,
+            Some(&tsa_chain),
+        )
+    };
+
+    let embedded = verifier
+        .verify_bundle(&path, VerificationOptions::default(), &fulcio_chain, Some(&tsa_chain))
+        .expect("Embedded payload verification failed");
+    let reattached = verify(Some(statement.clone())).expect("Detached payload verification failed");
+    assert_eq!(reattached.as_slice(), embedded.as_slice());
+
+    assert!(matches!(verify(None), Err(VerificationError::MissingPayload)));
+
+    // The signature binds the statement, even to changes that keep it equivalent
+    let mut tampered = statement;
+    tampered.push(b'\n');
+    assert!(matches!(verify(Some(tampered)), Err(VerificationError::Signature(_))));
+}
+
+#[test]
+fn test_verify_message_signature_bundle() {
+    use base64::prelude::*;
+    use p256::pkcs8::EncodePublicKey;
+    use rsa::pkcs1v15::SigningKey;
+    use rsa::pkcs8::DecodePrivateKey;
+    use rsa::signature::{SignatureEncoding as _, Signer};
+    use sha2::{Digest, Sha256};
+    use sigstore_verifier::error::{TransparencyError, VerificationError};
+    use sigstore_verifier::types::certificate::CertificateChain;
+    use sigstore_verifier::types::result::DigestAlgorithm;
+
+    // `cosign sign-blob` layout, signed by the RSA test leaf and dated by an
+    // integrated time, whose SET a test Rekor key signs (the entry carries no
+    // inclusion proof to check)
+    let der = |pem: &str| pem::parse(pem).unwrap().into_contents();
+    let trust_bundle = CertificateChain {
+        leaf: Vec::new(),
+        intermediates: vec![der(include_str!("../testdata/rsa/intermediate.pem"))],
+        root: der(include_str!("../testdata/rsa/root.pem")),
+    };
+    let private_key =
+        rsa::RsaPrivateKey::from_pkcs8_pem(include_str!("../testdata/rsa/leaf.key")).unwrap();
+    let signature = SigningKey::<Sha256>::new(private_key).sign(b"blob").to_vec();
+    let digest = Sha256::digest(b"blob");
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+
+    let body = serde_json::json!({
+        "apiVersion": "0.0.1",
+        "kind": "hashedrekord",
+        "spec": {
+            "data": { "hash": { "algorithm": "sha256", "value": hex::encode(digest) } },
+            "signature": {
+                "content": BASE64_STANDARD.encode(&signature),
+                "publicKey": { "content": BASE64_STANDARD.encode(include_str!("../testdata/rsa/leaf.pem")) }
+            }
+        }
+    });
+
+    let rekor_key = p256::ecdsa::SigningKey::from_slice(&[7u8; 32]).unwrap();
+    let rekor_keys = vec![rekor_key.verifying_key().to_public_key_der().unwrap().into_vec()];
+    let log_id = Sha256::digest(&rekor_keys[0]);
+    let canonicalized_body = BASE64_STANDARD.encode(body.to_string());
+    let set: p256::ecdsa::Signature = rekor_key.sign(
+        format!(
+            r#"{{"body":"{}","integratedTime":{},"logID":"{}","logIndex":1}}"#,
+            canonicalized_body,
+            now,
+            hex::encode(log_id)
+        )
+        .as_bytes(),
+    );
+
+    let bundle_json = serde_json::json!({
+        "mediaType": "application/vnd.dev.sigstore.bundle.v0.3+json",
+        "verificationMaterial": {
+            "certificate": {
+                "rawBytes": BASE64_STANDARD.encode(der(include_str!("../testdata/rsa/leaf.pem")))
+            },
+            "tlogEntries": [{
+                "logIndex": "1",
+                "logId": { "keyId": BASE64_STANDARD.encode(log_id) },
+                "integratedTime": now.to_string(),
+                "inclusionPromise": { "signedEntryTimestamp": BASE64_STANDARD.encode(set.to_der()) },
+                "canonicalizedBody": canonicalized_body
+            }]
+        },
+        "messageSignature": {
+            "messageDigest": { "algorithm": "SHA2_256", "digest": BASE64_STANDARD.encode(digest) },
+            "signature": BASE64_STANDARD.encode(&signature)
+        }
+    })
+    .to_string();
+
+    let verify = |options: VerificationOptions| {
+        let options = VerificationOptions { rekor_keys: rekor_keys.clone(), ..options };
+        AttestationVerifier::new().verify_bundle_bytes(bundle_json.as_bytes(), options, &trust_bundle, None)
+    };
+
+    let result = verify(VerificationOptions {
+        expected_digest: Some(digest.to_vec()),
+        ..Default::default()
+    })
+    .expect("Message signature verification failed");
+    assert_eq!(result.subject_digest, digest.to_vec());
+    assert_eq!(result.subject_digest_algorithm, DigestAlgorithm::Sha256);
+    assert!(result.subject_name.is_empty());
+    assert!(result.predicate_type.is_empty());
+    assert_eq!(result.predicate_sha256, [0u8; 32]);
+
+    // The blob, when supplied, must be the one that was signed
+    assert!(verify(VerificationOptions {
+        detached_payload: Some(b"blob".to_vec()),
+        ..Default::default()
+    })
+    .is_ok());
+    assert!(matches!(
+        verify(VerificationOptions {
+            detached_payload: Some(b"other".to_vec()),
+            ..Default::default()
+        }),
+        Err(VerificationError::SubjectDigestMismatch { .. })
+    ));
+    // A blob has no name to select
+    assert!(matches!(
+        verify(VerificationOptions {
+            expected_subject_name: Some("blob".to_string()),
+            ..Default::default()
+        }),
+        Err(VerificationError::SubjectNotFound(_))
+    ));
+
+    // A local copy of the artifact pins the expected digest
+    let artifact = std::env::temp_dir().join(format!("sigstore-artifact-{}", std::process::id()));
+    let verify_artifact = |content: &[u8]| {
+        std::fs::write(&artifact, content).unwrap();
+        AttestationVerifier::new().verify_artifact(
+            &artifact,
+            bundle_json.as_bytes(),
+            VerificationOptions::builder().rekor_keys(rekor_keys.clone()).build(),
+            &trust_bundle,
+            None,
+        )
+    };
+    assert_eq!(verify_artifact(b"blob").unwrap().subject_digest, digest.to_vec());
+    assert!(matches!(
+        verify_artifact(b"other"),
+        Err(VerificationError::SubjectDigestMismatch { .. })
+    ));
+    std::fs::remove_file(&artifact).unwrap();
+    assert!(matches!(
+        AttestationVerifier::new().verify_artifact(
+            &artifact,
+            bundle_json.as_bytes(),
+            VerificationOptions::builder().rekor_keys(rekor_keys.clone()).build(),
+            &trust_bundle,
+            None,
+        ),
+        Err(VerificationError::ArtifactRead(_))
+    ));
+
+    // The logged body must record the bundle's digest
+    let mut forged_body = body.clone();
+    forged_body["spec"]["data"]["hash"]["value"] = hex::encode(Sha256::digest(b"other")).into();
+    let forged = bundle_json.replace(
+        &BASE64_STANDARD.encode(body.to_string()),
+        &BASE64_STANDARD.encode(forged_body.to_string()),
+    );
+    assert!(matches!(
+        AttestationVerifier::new().verify_bundle_bytes(
+            forged.as_bytes(),
+            VerificationOptions::builder().rekor_keys(rekor_keys.clone()).build(),
+            &trust_bundle,
+            None
+        ),
+        Err(VerificationError::Transparency(TransparencyError::EntryBodyMismatch(_)))
+    ));
+}
+
+/// The public-good Rekor sample bundle and the Rekor keys of the sample trusted root
+fn rekor_sample() -> (sigstore_verifier::types::bundle::SigstoreBundle, Vec<Vec<u8>>) {
+    use sigstore_verifier::fetcher::jsonl::parser::{load_trusted_root_from_jsonl, select_rekor_keys};
+    use sigstore_verifier::parser::bundle::{extract_bundle_timestamp, parse_bundle_from_path};
+
+    let mut samples = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    samples.pop();
+    samples.pop();
+    samples.push("samples");
+
+    // Its entry carries an inclusion promise and a checkpoint
+    let bundle =
+        parse_bundle_from_path(&samples.join("actions-attest-build-provenance-attestation-13532655.sigstore.json"))
+            .expect("Failed to parse bundle");
+    let roots = load_trusted_root_from_jsonl(
+        &std::fs::read_to_string(samples.join("trusted_root.jsonl")).expect("Failed to read trusted root"),
+    )
+    .expect("Failed to load trusted root");
+    let timestamp = extract_bundle_timestamp(&bundle).expect("Failed to extract timestamp");
+    let rekor_keys = select_rekor_keys(&roots, timestamp).expect("Failed to select Rekor keys");
+    assert!(!rekor_keys.is_empty());
+    (bundle, rekor_keys)
+}
+
+#[test]
+fn test_verify_signed_entry_timestamp() {
+    use base64::prelude::*;
+    use sigstore_verifier::crypto::hash::sha256;
+    use sigstore_verifier::error::{TransparencyError, VerificationError};
+    use sigstore_verifier::verifier::transparency::verify_transparency_log;
+
+    let (bundle, rekor_keys) = rekor_sample();
+    verify_transparency_log(&bundle, &rekor_keys).expect("SET verification failed");
+
+    // Without the log's key the promise cannot be checked (the checkpoint, signed
+    // with the same key, is dropped so that the promise is what fails)
+    let mut promise_only = bundle.clone();
+    let entry = &mut promise_only.verification_material.tlog_entries.as_mut().unwrap()[0];
+    entry.inclusion_proof.as_mut().unwrap().checkpoint = None;
+    let log_id = BASE64_STANDARD.decode(&entry.log_id.as_ref().unwrap().key_id).unwrap();
+    let other_keys: Vec<Vec<u8>> =
+        rekor_keys.iter().filter(|key| sha256(key).as_slice() != log_id).cloned().collect();
+    assert!(matches!(
+        verify_transparency_log(&promise_only, &other_keys),
+        Err(VerificationError::Transparency(TransparencyError::UnknownLogKey(_)))
+    ));
+
+    // Without any key, neither the promise nor the checkpoint vouches for the entry
+    for bundle in [&bundle, &promise_only] {
+        let error = verify_transparency_log(bundle, &[]).unwrap_err();
+        assert!(matches!(error, VerificationError::Transparency(TransparencyError::MissingRekorKeys)));
+        assert_eq!(error.code(), 511);
+    }
+
+    // The SET covers the integrated time, so backdating the entry breaks it
+    let mut backdated = bundle.clone();
+    let entry = &mut backdated.verification_material.tlog_entries.as_mut().unwrap()[0];
+    entry.integrated_time = (entry.integrated_time.parse::<i64>().unwrap() - 3600).to_string();
+    assert!(matches!(
+        verify_transparency_log(&backdated, &rekor_keys),
+        Err(VerificationError::Transparency(TransparencyError::SignedEntryTimestampSignature(_)))
+    ));
+}
+
+#[test]
+fn test_verify_bundle_requires_signed_entry_timestamp() {
+    use sigstore_verifier::error::{TransparencyError, VerificationError};
+    use sigstore_verifier::fetcher::jsonl::parser::{load_trusted_root_from_jsonl, select_certificate_authority};
+    use sigstore_verifier::parser::bundle::extract_bundle_timestamp;
+
+    let (bundle, rekor_keys) = rekor_sample();
+    let mut samples = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    samples.pop();
+    samples.pop();
+    samples.push("samples");
+    let bundle_json =
+        std::fs::read(samples.join("actions-attest-build-provenance-attestation-13532655.sigstore.json"))
+            .expect("Failed to read bundle");
+    let roots = load_trusted_root_from_jsonl(
+        &std::fs::read_to_string(samples.join("trusted_root.jsonl")).expect("Failed to read trusted root"),
+    )
+    .expect("Failed to load trusted root");
+    let timestamp = extract_bundle_timestamp(&bundle).expect("Failed to extract timestamp");
+    let fulcio_chain = select_certificate_authority(&roots, &FulcioInstance::PublicGood, timestamp)
+        .expect("Failed to select certificate authority");
+
+    let with_entry = |edit: &dyn Fn(&mut serde_json::Value)| {
+        let mut json: serde_json::Value = serde_json::from_slice(&bundle_json).unwrap();
+        edit(&mut json["verificationMaterial"]["tlogEntries"][0]);
+        AttestationVerifier::new().verify_bundle_bytes(
+            &serde_json::to_vec(&json).unwrap(),
+            VerificationOptions::builder().rekor_keys(rekor_keys.clone()).build(),
+            &fulcio_chain,
+            None,
+        )
+    };
+    assert!(with_entry(&|_| {}).is_ok());
+
+    // The integrated time is the signing time; without the SET nothing vouches for it
+    let result = with_entry(&|entry| {
+        entry.as_object_mut().unwrap().remove("inclusionPromise");
+    });
+    assert!(matches!(
+        result,
+        Err(VerificationError::Transparency(TransparencyError::MissingSignedEntryTimestamp))
+    ));
+
+    // A changed integrated time, still within the certificate's validity, breaks the SET
+    let result = with_entry(&|entry| {
+        let integrated_time: i64 = entry["integratedTime"].as_str().unwrap().parse().unwrap();
+        entry["integratedTime"] = (integrated_time + 60).to_string().into();
+    });
+    assert!(matches!(
+        result,
+        Err(VerificationError::Transparency(TransparencyError::SignedEntryTimestampSignature(_)))
+    ));
+}
+
+#[test]
+fn test_verify_checkpoint() {
+    use base64::prelude::*;
+    use p256::pkcs8::EncodePublicKey;
+    use sigstore_verifier::crypto::hash::sha256;
+    use sigstore_verifier::error::{TransparencyError, VerificationError};
+    use sigstore_verifier::verifier::transparency::verify_transparency_log;
+
+    let (bundle, rekor_keys) = rekor_sample();
+    let with_checkpoint = |edit: &dyn Fn(&str) -> String| {
+        let mut bundle = bundle.clone();
+        let entry = &mut bundle.verification_material.tlog_entries.as_mut().unwrap()[0];
+        let checkpoint = entry.inclusion_proof.as_mut().unwrap().checkpoint.as_mut().unwrap();
+        checkpoint.envelope = edit(&checkpoint.envelope);
+        verify_transparency_log(&bundle, &rekor_keys)
+    };
+
+    assert!(with_checkpoint(&|envelope| envelope.to_string()).is_ok());
+
+    // A checkpoint for another tree
+    let result = with_checkpoint(&|envelope| envelope.replace("\n585383803\n", "\n585383804\n"));
+    assert!(matches!(
+        result,
+        Err(VerificationError::Transparency(TransparencyError::CheckpointMismatch(_)))
+    ));
+
+    // Extra content under the same signature
+    let result = with_checkpoint(&|envelope| envelope.replacen("=\n\n", "=\nextension\n\n", 1));
+    assert!(matches!(
+        result,
+        Err(VerificationError::Transparency(TransparencyError::CheckpointSignature))
+    ));
+
+    // The checkpoint must be signed by the entry's log, not any trusted log: pointed
+    // at another trusted key, the entry's checkpoint no longer verifies
+    let other_log = p256::ecdsa::SigningKey::from_slice(&[7u8; 32]).unwrap();
+    let other_key = other_log.verifying_key().to_public_key_der().unwrap().into_vec();
+    let mut repointed = bundle.clone();
+    let entry = &mut repointed.verification_material.tlog_entries.as_mut().unwrap()[0];
+    entry.log_id.as_mut().unwrap().key_id = BASE64_STANDARD.encode(sha256(&other_key));
+    let keys: Vec<Vec<u8>> = rekor_keys.iter().cloned().chain([other_key]).collect();
+    assert!(matches!(
+        verify_transparency_log(&repointed, &keys),
+        Err(VerificationError::Transparency(TransparencyError::CheckpointSignature))
+    ));
+
+    // An inclusion proof is not accepted without the SET, even with no checkpoint to check
+    let mut proof_only = bundle.clone();
+    let entry = &mut proof_only.verification_material.tlog_entries.as_mut().unwrap()[0];
+    entry.inclusion_promise = None;
+    entry.inclusion_proof.as_mut().unwrap().checkpoint = None;
+    assert!(matches!(
+        verify_transparency_log(&proof_only, &rekor_keys),
+        Err(VerificationError::Transparency(TransparencyError::MissingSignedEntryTimestamp))
+    ));
+}
+
+#[test]
+fn test_verify_entry_body_matches_bundle() {
+    use base64::prelude::*;
+    use sigstore_verifier::error::{TransparencyError, VerificationError};
+    use sigstore_verifier::parser::bundle::parse_bundle_from_path;
+    use sigstore_verifier::verifier::transparency::verify_transparency_log;
+
+    let (bundle, rekor_keys) = rekor_sample();
+    let mut samples = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    samples.pop();
+    samples.pop();
+    let mut other = parse_bundle_from_path(
+        &samples.join("samples/actions-attest-build-provenance-attestation-13531551.sigstore.json"),
+    )
+    .expect("Failed to parse bundle");
+
+    // Another envelope presenting this entry: its proofs hold, but not for that envelope
+    other.verification_material.timestamp_verification_data = None;
+    other.verification_material.tlog_entries = bundle.verification_material.tlog_entries.clone();
+    let result = verify_transparency_log(&other, &rekor_keys);
+    assert!(matches!(
+        result,
+        Err(VerificationError::Transparency(TransparencyError::EntryBodyMismatch(_)))
+    ));
+
+    // A logged body without this envelope's signature
+    let mut edited = bundle.clone();
+    let entry = &mut edited.verification_material.tlog_entries.as_mut().unwrap()[0];
+    let body = String::from_utf8(BASE64_STANDARD.decode(&entry.canonicalized_body).unwrap()).unwrap();
+    let signature = match &bundle.content {
+        sigstore_verifier::types::bundle::BundleContent::DsseEnvelope(envelope) => envelope.signatures[0].sig.clone(),
+        _ => unreachable!(),
+    };
+    let other_signature = BASE64_STANDARD.encode([0x30, 0x03, 0x02, 0x01, 0x01]);
+    entry.canonicalized_body = BASE64_STANDARD.encode(body.replace(&signature, &other_signature));
+    let result = verify_transparency_log(&edited, &rekor_keys);
+    assert!(matches!(
+        result,
+        Err(VerificationError::Transparency(TransparencyError::EntryBodyMismatch(_)))
+    ));
+
+    // An entry of a kind that cannot be checked against the bundle, with a matching kindVersion
+    for kind in ["rekord", "made-up"] {
+        let mut other_kind = bundle.clone();
+        let entry = &mut other_kind.verification_material.tlog_entries.as_mut().unwrap()[0];
+        entry.canonicalized_body =
+            BASE64_STANDARD.encode(format!(r#"{{"apiVersion":"0.0.1","kind":"{}","spec":{{}}}}"#, kind));
+        entry.kind_version.as_mut().unwrap().kind = kind.to_string();
+        let result = verify_transparency_log(&other_kind, &rekor_keys);
+        assert!(matches!(
+            result,
+            Err(VerificationError::Transparency(TransparencyError::EntryBodyMismatch(_)))
+        ));
+    }
+}
+
+#[test]
+fn test_verify_embedded_scts() {
+    use sigstore_verifier::crypto::hash::sha256;
+    use sigstore_verifier::error::{CertificateError, VerificationError};
+    use sigstore_verifier::fetcher::jsonl::parser::{
+        load_trusted_root_from_jsonl, select_certificate_authority, select_ctlog_keys,
+    };
+    use sigstore_verifier::parser::bundle::{decode_base64, extract_bundle_timestamp};
+    use sigstore_verifier::types::certificate::CertificateChain;
+    use sigstore_verifier::verifier::sct::verify_embedded_scts;
+
+    // The public-good sample's leaf embeds an SCT from a log of the trusted root
+    let (bundle, rekor_keys) = rekor_sample();
+    let mut samples = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    samples.pop();
+    samples.pop();
+    samples.push("samples");
+    let roots = load_trusted_root_from_jsonl(
+        &std::fs::read_to_string(samples.join("trusted_root.jsonl")).expect("Failed to read trusted root"),
+    )
+    .expect("Failed to load trusted root");
+    let timestamp = extract_bundle_timestamp(&bundle).expect("Failed to extract timestamp");
+    let ctlog_keys = select_ctlog_keys(&roots, timestamp).expect("Failed to select CT log keys");
+    let fulcio_chain = select_certificate_authority(&roots, &FulcioInstance::PublicGood, timestamp)
+        .expect("Failed to select certificate authority");
+
+    let options = VerificationOptions::builder()
+        .rekor_keys(rekor_keys)
+        .ctlog_keys(ctlog_keys.clone())
+        .build();
+    let result = AttestationVerifier::new()
+        .verify_bundle(
+            &samples.join("actions-attest-build-provenance-attestation-13532655.sigstore.json"),
+            options,
+            &fulcio_chain,
+            None,
+        )
+        .expect("Verification failed");
+    assert_eq!(result.certificate_timestamps.len(), 1);
+    let sct = result.certificate_timestamps[0];
+    assert!(ctlog_keys.iter().any(|key| sha256(key) == sct.log_id));
+    assert!(sct.timestamp / 1000 >= timestamp as u64 - 60 && sct.timestamp / 1000 <= timestamp as u64);
+
+    let leaf = decode_base64(&bundle.verification_material.certificate.raw_bytes).unwrap();
+    let chain = CertificateChain {
+        leaf,
+        intermediates: fulcio_chain.intermediates.clone(),
+        root: fulcio_chain.root.clone(),
+    };
+    assert_eq!(verify_embedded_scts(&chain, &ctlog_keys).unwrap(), vec![sct]);
+
+    // No trusted log, or an SCT bound to another issuer key
+    let other_logs: Vec<Vec<u8>> = ctlog_keys.iter().filter(|key| sha256(key) != sct.log_id).cloned().collect();
+    assert!(matches!(
+        verify_embedded_scts(&chain, &other_logs),
+        Err(VerificationError::Certificate(CertificateError::MissingSct))
+    ));
+    let reissued = CertificateChain {
+        intermediates: Vec::new(),
+        ..chain
+    };
+    assert!(matches!(
+        verify_embedded_scts(&reissued, &ctlog_keys),
+        Err(VerificationError::Certificate(CertificateError::InvalidSct(_)))
+    ));
+}
+
+#[test]
+fn test_verify_bundle_audit() {
+    use base64::prelude::*;
+    use sigstore_verifier::crypto::hash::sha256;
+    use sigstore_verifier::error::{TransparencyError, VerificationError};
+    use sigstore_verifier::fetcher::jsonl::parser::{
+        load_trusted_root_from_jsonl, select_certificate_authority, select_ctlog_keys,
+    };
+    use sigstore_verifier::parser::bundle::extract_bundle_timestamp;
+    use sigstore_verifier::report::AuditCheck;
+
+    let (bundle, rekor_keys) = rekor_sample();
+    let mut samples = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    samples.pop();
+    samples.pop();
+    samples.push("samples");
+    let bundle_json =
+        std::fs::read(samples.join("actions-attest-build-provenance-attestation-13532655.sigstore.json"))
+            .expect("Failed to read bundle");
+    let roots = load_trusted_root_from_jsonl(
+        &std::fs::read_to_string(samples.join("trusted_root.jsonl")).expect("Failed to read trusted root"),
+    )
+    .expect("Failed to load trusted root");
+    let timestamp = extract_bundle_timestamp(&bundle).expect("Failed to extract timestamp");
+    let fulcio_chain = select_certificate_authority(&roots, &FulcioInstance::PublicGood, timestamp)
+        .expect("Failed to select certificate authority");
+    let ctlog_keys = select_ctlog_keys(&roots, timestamp).expect("Failed to select CT log keys");
+
+    // Without the key of the Rekor log, the checkpoint and SET cannot be checked
+    let log_id = BASE64_STANDARD
+        .decode(&bundle.verification_material.tlog_entries.as_ref().unwrap()[0].log_id.as_ref().unwrap().key_id)
+        .unwrap();
+    let other_keys: Vec<Vec<u8>> =
+        rekor_keys.iter().filter(|key| sha256(key).as_slice() != log_id).cloned().collect();
+    let options = || {
+        VerificationOptions::builder()
+            .rekor_keys(other_keys.clone())
+            .ctlog_keys(ctlog_keys.clone())
+            .build()
+    };
+    let verifier = AttestationVerifier::new();
+
+    let strict = verifier.verify_bundle_report(&bundle_json, options(), &fulcio_chain, None);
+    assert!(matches!(
+        strict.result,
+        Err(VerificationError::Transparency(TransparencyError::UnknownLogKey(_)))
+    ));
+    assert_eq!(strict.failed_step(), Some("transparency_log"));
+
+    let relaxed = [AuditCheck::Checkpoint, AuditCheck::SignedEntryTimestamp, AuditCheck::TsaEku];
+    let report = verifier.verify_bundle_audit(&bundle_json, options(), &fulcio_chain, None, &relaxed);
+    let expected = verifier
+        .verify_bundle_bytes(
+            &bundle_json,
+            VerificationOptions::builder().rekor_keys(rekor_keys.clone()).build(),
+            &fulcio_chain,
+            None,
+        )
+        .unwrap();
+    assert_eq!(report.result.as_ref().unwrap().certificate_hashes.leaf, expected.certificate_hashes.leaf);
+    let warnings: Vec<_> = report.warnings.iter().map(|warning| (warning.check, warning.step)).collect();
+    assert_eq!(
+        warnings,
+        [
+            (AuditCheck::Checkpoint, "transparency_log"),
+            (AuditCheck::SignedEntryTimestamp, "transparency_log"),
+        ]
+    );
+    assert!(!report.is_ok());
+    assert!(report.to_string().contains("verified with 2 relaxed check(s) failing"));
+
+    // Checks that pass leave no warning behind
+    let report = verifier.verify_bundle_audit(
+        &bundle_json,
+        VerificationOptions::builder().rekor_keys(rekor_keys.clone()).build(),
+        &fulcio_chain,
+        None,
+        &AuditCheck::ALL,
+    );
+    assert!(report.is_ok(), "{}", report);
+}
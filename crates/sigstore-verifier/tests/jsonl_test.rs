@@ -1,9 +1,11 @@
 #![cfg(feature = "fetcher")]
 
+use sigstore_verifier::fetcher::jsonl::convert::cert_chain_to_pem;
 use sigstore_verifier::fetcher::jsonl::parser::{
-    load_trusted_root_from_jsonl, select_certificate_authority, select_timestamp_authority,
+    certificate_authority_from_pem, load_trusted_root_from_jsonl, select_certificate_authority,
+    select_timestamp_authority, timestamp_authority_from_pem,
 };
-use sigstore_verifier::types::certificate::FulcioInstance;
+use sigstore_verifier::types::certificate::{CertificateChain, FulcioInstance};
 use std::fs;
 use std::path::PathBuf;
 
@@ -211,3 +213,34 @@ fn test_certificate_chain_structure() {
     assert!(chain.root.len() > 100, "Root certificate too small");
     assert_eq!(chain.root[0], 0x30, "Root not a valid DER certificate");
 }
+
+#[test]
+fn test_chains_from_pem_match_selection() {
+    let content = get_sample_trusted_root();
+    let roots = load_trusted_root_from_jsonl(&content).expect("Failed to parse JSONL");
+
+    // The chains of every authority, as they would be served in PEM by the endpoints
+    let mut ca_chains = Vec::new();
+    let mut tsa_chains = Vec::new();
+    for root in &roots {
+        for ca in &root.certificate_authorities {
+            let pem = cert_chain_to_pem(&ca.cert_chain).unwrap();
+            ca_chains.push(certificate_authority_from_pem(&pem).unwrap());
+        }
+        for tsa in &root.timestamp_authorities {
+            let pem = cert_chain_to_pem(&tsa.cert_chain).unwrap();
+            tsa_chains.push(timestamp_authority_from_pem(&pem).unwrap());
+        }
+    }
+
+    let same = |a: &CertificateChain, b: &CertificateChain| {
+        a.leaf == b.leaf && a.intermediates == b.intermediates && a.root == b.root
+    };
+    let ca = select_certificate_authority(&roots, &FulcioInstance::GitHub, 1720000000).unwrap();
+    assert!(ca_chains.iter().any(|chain| same(chain, &ca)), "Selected CA not built from its PEM chain");
+    let tsa = select_timestamp_authority(&roots, &FulcioInstance::GitHub, 1720000000).unwrap();
+    assert!(tsa_chains.iter().any(|chain| same(chain, &tsa)), "Selected TSA not built from its PEM chain");
+    assert!(!tsa.leaf.is_empty(), "TSA leaf should be the first certificate");
+
+    assert!(certificate_authority_from_pem("no certificates").is_err());
+}
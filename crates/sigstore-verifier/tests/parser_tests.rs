@@ -36,7 +36,7 @@ fn test_rfc3161_bundle_parser() {
 
     // Extract DSSE Payload from bundle
     let statement =
-        parse_dsse_payload(&bundle.dsse_envelope).expect("Failed to parse DSSE payload");
+        parse_dsse_payload(bundle.dsse_envelope().unwrap()).expect("Failed to parse DSSE payload");
     println!("Extracted DSSE statement: {:?}", statement);
 }
 
@@ -53,6 +53,6 @@ fn test_rekor_bundle_parser() {
 
     // Extract DSSE Payload from bundle
     let statement =
-        parse_dsse_payload(&bundle.dsse_envelope).expect("Failed to parse DSSE payload");
+        parse_dsse_payload(bundle.dsse_envelope().unwrap()).expect("Failed to parse DSSE payload");
     println!("Extracted DSSE statement: {:?}", statement);
 }
\ No newline at end of file
@@ -0,0 +1,407 @@
+//! Human-readable rendering of verification results
+//!
+//! Shared by the hosts and the CLIs that print journals, so that times,
+//! digests and timestamp proofs read the same everywhere:
+//!
+//! - Times as RFC 3339 in UTC, followed by the local time of the host
+//! - Digests as `<algorithm>:<hex>`, as in OCI and in-toto
+//! - Timestamp proofs as one-line summaries
+//!
+//! Terminal output is colorized when stdout is a terminal and `NO_COLOR` is
+//! not set. The hosts' `--no-color` flag turns it off with [`set_color`].
+
+use chrono::{DateTime, Local, SecondsFormat, TimeZone, Utc};
+use sigstore_verifier::types::certificate::IdentityKind;
+use sigstore_verifier::types::result::{DigestAlgorithm, SubjectKind, TimestampProof, VerificationResult};
+use std::io::IsTerminal;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+// 0 = not decided yet, 1 = off, 2 = on
+static COLOR: AtomicU8 = AtomicU8::new(0);
+
+/// Enable or disable colorized output for the rest of the process
+pub fn set_color(enabled: bool) {
+    COLOR.store(if enabled { 2 } else { 1 }, Ordering::Relaxed);
+}
+
+/// Whether output is colorized
+///
+/// Unless [`set_color`] was called, this is decided once from whether stdout is
+/// a terminal and `NO_COLOR` is unset.
+pub fn color_enabled() -> bool {
+    match COLOR.load(Ordering::Relaxed) {
+        0 => {
+            let enabled = std::io::stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none();
+            set_color(enabled);
+            enabled
+        }
+        value => value == 2,
+    }
+}
+
+/// Terminal styles used by the display helpers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Style {
+    Bold,
+    Dim,
+    Green,
+    Yellow,
+    Red,
+    Cyan,
+}
+
+impl Style {
+    fn ansi_code(self) -> &'static str {
+        match self {
+            Style::Bold => "1",
+            Style::Dim => "2",
+            Style::Green => "32",
+            Style::Yellow => "33",
+            Style::Red => "31",
+            Style::Cyan => "36",
+        }
+    }
+}
+
+/// Wrap `text` in `style` if output is colorized
+pub fn paint(text: impl std::fmt::Display, style: Style) -> String {
+    if color_enabled() {
+        format!("\x1b[{}m{}\x1b[0m", style.ansi_code(), text)
+    } else {
+        text.to_string()
+    }
+}
+
+/// Render a time as RFC 3339 in UTC, e.g. `2025-01-31T12:00:00Z`
+pub fn format_rfc3339(time: &DateTime<Utc>) -> String {
+    time.to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+/// Render a time in the host's time zone, e.g. `2025-01-31 13:00:00 +01:00`
+pub fn format_local(time: &DateTime<Utc>) -> String {
+    time.with_timezone(&Local).format("%Y-%m-%d %H:%M:%S %:z").to_string()
+}
+
+/// Render a time as RFC 3339 followed by the local time
+pub fn format_time(time: &DateTime<Utc>) -> String {
+    format!("{} ({})", format_rfc3339(time), paint(format!("local {}", format_local(time)), Style::Dim))
+}
+
+/// Render a Unix timestamp as RFC 3339, or the raw number if it is out of range
+pub fn format_unix_time(secs: u64) -> String {
+    i64::try_from(secs)
+        .ok()
+        .and_then(|secs| Utc.timestamp_opt(secs, 0).single())
+        .map(|time| format_rfc3339(&time))
+        .unwrap_or_else(|| secs.to_string())
+}
+
+/// Human-readable name of a digest algorithm, e.g. `SHA-256`
+pub fn digest_algorithm_label(algorithm: &DigestAlgorithm) -> &'static str {
+    match algorithm {
+        DigestAlgorithm::Unknown => "Unknown",
+        DigestAlgorithm::Sha256 => "SHA-256",
+        DigestAlgorithm::Sha384 => "SHA-384",
+        DigestAlgorithm::Sha1 => "SHA-1 (git object ID)",
+        DigestAlgorithm::Sha512 => "SHA-512",
+    }
+}
+
+/// Render a digest as `<algorithm>:<hex>`, e.g. `sha256:9f86...`
+pub fn format_digest(algorithm: &DigestAlgorithm, digest: &[u8]) -> String {
+    let prefix = match algorithm {
+        DigestAlgorithm::Unknown => "unknown",
+        DigestAlgorithm::Sha256 => "sha256",
+        DigestAlgorithm::Sha384 => "sha384",
+        DigestAlgorithm::Sha1 => "sha1",
+        DigestAlgorithm::Sha512 => "sha512",
+    };
+    format!("{}:{}", prefix, hex::encode(digest))
+}
+
+/// Human-readable name of what a SAN identifies
+pub fn identity_kind_label(kind: &IdentityKind) -> &'static str {
+    match kind {
+        IdentityKind::Unknown => "Unknown",
+        IdentityKind::Uri => "URI",
+        IdentityKind::Email => "Email",
+        IdentityKind::Spiffe => "SPIFFE",
+    }
+}
+
+/// Human-readable name of what a subject is
+pub fn subject_kind_label(kind: &SubjectKind) -> &'static str {
+    match kind {
+        SubjectKind::Unknown => "Unknown",
+        SubjectKind::Artifact => "Artifact",
+        SubjectKind::Purl => "Package URL",
+        SubjectKind::Git => "Git",
+    }
+}
+
+/// One-line summary of a timestamp proof
+///
+/// # Example
+///
+/// ```ignore
+/// // "RFC 3161 (TSA leaf 1a2b3c4d..., imprint SHA-256)"
+/// println!("{}", timestamp_proof_summary(&result.timestamp_proof));
+/// ```
+pub fn timestamp_proof_summary(proof: &TimestampProof) -> String {
+    match proof {
+        TimestampProof::None => "none".to_string(),
+        TimestampProof::Rfc3161 {
+            tsa_chain_hashes,
+            message_imprint_algorithm,
+            ..
+        } => format!(
+            "RFC 3161 (TSA leaf {}..., imprint {})",
+            &hex::encode(tsa_chain_hashes.leaf)[..8],
+            digest_algorithm_label(message_imprint_algorithm)
+        ),
+        TimestampProof::Rekor { log_id, entry_index, .. } => format!(
+            "Rekor (entry {} in log {}...)",
+            entry_index,
+            &hex::encode(log_id)[..8]
+        ),
+    }
+}
+
+/// Display verification result in a readable format
+///
+/// Prints the verification result with formatted output including:
+/// - Subject digest and algorithm
+/// - Signing time in UTC and local time
+/// - Verifier and guest program versions
+/// - Certificate hashes (leaf, intermediates, root)
+/// - Signed certificate timestamps (if verified)
+/// - OIDC identity information (if present)
+/// - The constraints that were enforced (if committed)
+/// - Timestamp proof details (RFC 3161 or Rekor)
+///
+/// # Arguments
+///
+/// * `result` - The verification result to display
+///
+/// # Example
+///
+/// ```ignore
+/// let result = VerificationResult::from_slice(&journal)?;
+/// display_verification_result(&result);
+/// ```
+pub fn display_verification_result(result: &VerificationResult) {
+    println!("\n{}", paint("=== Verification Result ===", Style::Bold));
+    if result.subject_kind == SubjectKind::Unknown {
+        println!("Subject:        {}", paint(&result.subject_name, Style::Cyan));
+    } else {
+        println!(
+            "Subject:        {} ({})",
+            paint(&result.subject_name, Style::Cyan),
+            subject_kind_label(&result.subject_kind)
+        );
+    }
+    println!(
+        "Subject digest: {}",
+        format_digest(&result.subject_digest_algorithm, &result.subject_digest)
+    );
+    println!("Signing time:   {}", format_time(&result.signing_time));
+    if result.is_archival() {
+        println!(
+            "Mode:           {}",
+            paint("archival (all certificates checked at signing time)", Style::Yellow)
+        );
+    }
+    if result.is_policy_violation() {
+        println!(
+            "Outcome:        {}",
+            paint("POLICY VIOLATION (verified without the committed policy)", Style::Red)
+        );
+    }
+    if !result.predicate_type.is_empty() {
+        println!("Predicate:      {}", result.predicate_type);
+        println!("Predicate hash: {}", hex::encode(result.predicate_sha256));
+    }
+    println!("Options hash:   {}", hex::encode(result.options_hash));
+    if result.annotations_hash != [0u8; 32] {
+        println!("Annotations:    {}", hex::encode(result.annotations_hash));
+    }
+    if let Some(anchor) = &result.block_anchor {
+        println!("Anchor block:   {} (0x{})", anchor.number, hex::encode(anchor.hash));
+    }
+    if let Some(previous) = &result.previous_result_hash {
+        println!("Previous:       {}", hex::encode(previous));
+    }
+    if result.input_digest != [0u8; 32] {
+        println!("Input digest:   {}", hex::encode(result.input_digest));
+    }
+    if let Some(owner) = &result.policy_owner_hash {
+        println!("Policy owner:   {}", hex::encode(owner));
+    }
+    println!("Verifier:       {}", result.verifier_version);
+    if !result.guest_version.is_empty() {
+        println!("Guest program:  {}", result.guest_version);
+    }
+
+    println!("\n{}", paint("Certificate Hashes:", Style::Bold));
+    println!("  Leaf:   {}", hex::encode(result.certificate_hashes.leaf));
+    if !result.certificate_hashes.intermediates.is_empty() {
+        println!("  Intermediates:");
+        for (i, intermediate) in result.certificate_hashes.intermediates.iter().enumerate() {
+            println!("    [{}] {}", i, hex::encode(intermediate));
+        }
+    }
+    println!("  Root:   {}", hex::encode(result.certificate_hashes.root));
+
+    if !result.certificate_timestamps.is_empty() {
+        println!("\n{}", paint("Certificate Transparency:", Style::Bold));
+        for sct in &result.certificate_timestamps {
+            let time = DateTime::from_timestamp_millis(sct.timestamp as i64)
+                .map(|time| format_time(&time))
+                .unwrap_or_else(|| sct.timestamp.to_string());
+            println!("  Log {}: {}", &hex::encode(sct.log_id)[..8], time);
+        }
+    }
+
+    if let Some(ref oidc) = result.oidc_identity {
+        println!("\n{}", paint("OIDC Identity:", Style::Bold));
+        if let Some(ref issuer) = oidc.issuer {
+            println!("  Issuer:       {}", issuer);
+        }
+        if let Some(ref subject) = oidc.subject {
+            println!(
+                "  Subject:      {} ({})",
+                paint(subject, Style::Cyan),
+                identity_kind_label(&oidc.subject_kind)
+            );
+        }
+        if let Some(ref workflow_ref) = oidc.workflow_ref {
+            println!("  Workflow:     {}", workflow_ref);
+        }
+        if let Some(ref repository) = oidc.repository {
+            println!("  Repository:   {}", repository);
+        }
+        if let Some(ref event_name) = oidc.event_name {
+            println!("  Event:        {}", event_name);
+        }
+    }
+
+    display_constraints(result);
+
+    // Display timestamp proof information
+    let heading = format!("Timestamp Proof: {}", timestamp_proof_summary(&result.timestamp_proof));
+    match &result.timestamp_proof {
+        TimestampProof::None => {
+            println!("\n{}", paint(heading, Style::Yellow));
+        }
+        TimestampProof::Rfc3161 {
+            tsa_chain_hashes,
+            message_imprint_algorithm,
+            message_imprint,
+        } => {
+            println!("\n{}", paint(heading, Style::Bold));
+            println!(
+                "  Message Imprint: {}",
+                format_digest(message_imprint_algorithm, message_imprint)
+            );
+            println!("  TSA Certificate Chain:");
+            println!("    Leaf: {}", hex::encode(tsa_chain_hashes.leaf));
+            if !tsa_chain_hashes.intermediates.is_empty() {
+                println!("    Intermediates:");
+                for (i, intermediate) in tsa_chain_hashes.intermediates.iter().enumerate() {
+                    println!("      [{}] {}", i, hex::encode(intermediate));
+                }
+            }
+            println!("    Root: {}", hex::encode(tsa_chain_hashes.root));
+        }
+        TimestampProof::Rekor { log_id, log_index, entry_index } => {
+            println!("\n{}", paint(heading, Style::Bold));
+            println!("  Log ID:      {}", hex::encode(log_id));
+            println!("  Entry Index: {} (for API queries)", entry_index);
+            println!("  Log Index:   {} (tree leaf index for Merkle proof)", log_index);
+            println!("  Fetch URL:   https://rekor.sigstore.dev/api/v1/log/entries?logIndex={}", entry_index);
+        }
+    }
+}
+
+/// Print the constraints committed in the journal, if any were set
+fn display_constraints(result: &VerificationResult) {
+    let options = match result.decode_verification_options() {
+        Ok(Some(options)) => options,
+        Ok(None) => return,
+        Err(e) => {
+            println!("\n{}", paint(format!("Constraints: {}", e), Style::Yellow));
+            return;
+        }
+    };
+
+    let mut constraints: Vec<(&str, String)> = vec![
+        ("Profile", options.profile),
+        ("Digest", hex::encode(&options.expectedDigest)),
+        ("Issuer", options.expectedIssuer),
+        ("Subject", options.expectedSubject),
+        ("Subject name", options.expectedSubjectName),
+        ("Email", options.expectedEmail),
+        ("Email issuers", options.allowedEmailIssuers.join(", ")),
+        ("SPIFFE domain", options.expectedSpiffeTrustDomain),
+        ("SPIFFE path", options.expectedSpiffePathPrefix),
+        ("Repository", options.expectedRepository),
+        ("Workflow", options.expectedWorkflowRef),
+        ("Event", options.expectedEventName),
+        ("Predicate", options.expectedPredicateType),
+        ("TSA policy", options.expectedTsaPolicy),
+    ];
+    if options.identityPolicyHash.0 != [0u8; 32] {
+        constraints.push(("Policy", hex::encode(options.identityPolicyHash)));
+    }
+    if options.repositoryPolicyHash.0 != [0u8; 32] {
+        constraints.push(("Repo policy", hex::encode(options.repositoryPolicyHash)));
+    }
+    if options.maxSigningAge != 0 {
+        constraints.push((
+            "Max age",
+            format!("{}s at {}", options.maxSigningAge, options.verificationTime),
+        ));
+    }
+    match options.requiredTimestamp {
+        1 => constraints.push(("Timestamp", "RFC 3161".to_string())),
+        2 => constraints.push(("Timestamp", "Rekor".to_string())),
+        _ => {}
+    }
+    if options.clockSkew != 0 {
+        constraints.push(("Clock skew", format!("{}s", options.clockSkew)));
+    }
+    constraints.retain(|(_, value)| !value.is_empty());
+
+    if constraints.is_empty() {
+        println!("\n{}", paint("Constraints: none", Style::Yellow));
+        return;
+    }
+    println!("\n{}", paint("Constraints:", Style::Bold));
+    for (name, value) in constraints {
+        println!("  {:<14}{}", format!("{}:", name), value);
+    }
+}
+
+/// Display proof generation result summary
+///
+/// Prints the journal and the proof bytes, or a DEV_MODE marker if the proof is empty.
+///
+/// # Arguments
+///
+/// * `journal` - The public output/journal bytes
+/// * `seal` - The proof bytes
+///
+/// # Example
+///
+/// ```ignore
+/// display_proof_result(&journal, &seal);
+/// ```
+pub fn display_proof_result(journal: &[u8], seal: &[u8]) {
+    println!("\n{}", paint("=== Proof Generation Result ===", Style::Bold));
+    println!("Journal: {}", hex::encode(journal));
+    if seal.is_empty() {
+        println!("{}", paint("<empty-proof> (DEV_MODE)", Style::Yellow));
+    } else {
+        println!("Proof: {}", hex::encode(seal));
+    }
+}
@@ -0,0 +1,323 @@
+//! Environment variables recognized by the hosts
+//!
+//! Every variable a host reads is declared as an [`EnvVar`]: the ones shared
+//! by all hosts in [`COMMON_VARS`], and each host's own (e.g. `RISC0_DEV_MODE`,
+//! `BOUNDLESS_*`, `SP1_*`) in its CLI module. The hosts parse them once at
+//! startup with [`init`], after loading `.env`, so that an invalid value (e.g.
+//! `DEV_MODE=maybe`) is reported up front with every other invalid one, rather
+//! than being read differently by each part of the host. `--help-env` lists
+//! them with [`describe`], which shows the resolved values with secrets
+//! masked.
+//!
+//! Variables that are also bound to a command-line flag (e.g.
+//! `SIGSTORE_CACHE_DIR` for `--cache-dir`) are read by clap; they are declared
+//! here to be validated and listed.
+
+use crate::failpoint::{parse_failpoints, FAILPOINTS_ENV};
+use anyhow::{bail, Result};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// Type of the value of an environment variable
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VarKind {
+    /// `1`, `true`, `yes`, `on` or `0`, `false`, `no`, `off` (or empty), in any case
+    Flag,
+    /// Non-empty filesystem path
+    Path,
+    /// `http://` or `https://` URL
+    Url,
+    /// Non-empty value that is never displayed
+    Secret,
+    /// Any value
+    Text,
+    /// [`SIGSTORE_FAILPOINTS`](crate::failpoint) specification
+    Failpoints,
+}
+
+/// An environment variable a host reads
+///
+/// - name: Name of the variable
+/// - kind: Type its value is parsed as
+/// - description: What it controls, for `--help-env`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EnvVar {
+    pub name: &'static str,
+    pub kind: VarKind,
+    pub description: &'static str,
+}
+
+impl EnvVar {
+    pub const fn new(name: &'static str, kind: VarKind, description: &'static str) -> Self {
+        Self {
+            name,
+            kind,
+            description,
+        }
+    }
+}
+
+/// Variables read by every host
+pub const COMMON_VARS: &[EnvVar] = &[
+    EnvVar::new(
+        "DEV_MODE",
+        VarKind::Flag,
+        "Execute the guest without generating a proof, with any zkVM",
+    ),
+    EnvVar::new(
+        "SIGSTORE_CACHE_DIR",
+        VarKind::Path,
+        "Cache of downloaded bundles, trusted roots and artifacts (--cache-dir)",
+    ),
+    EnvVar::new(
+        "SIGSTORE_STORE_DIR",
+        VarKind::Path,
+        "Proof store of server mode and --bundle-list (--store)",
+    ),
+    EnvVar::new(
+        "GITHUB_TOKEN",
+        VarKind::Secret,
+        "Token for GitHub API requests of github:// sources",
+    ),
+    EnvVar::new(
+        "NO_COLOR",
+        VarKind::Text,
+        "Disable colored output when set to any non-empty value (--no-color)",
+    ),
+    EnvVar::new(
+        FAILPOINTS_ENV,
+        VarKind::Failpoints,
+        "Failures to inject at pipeline stages (builds with the failpoints feature)",
+    ),
+];
+
+/// Parsed value of an environment variable
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EnvValue {
+    Flag(bool),
+    Path(PathBuf),
+    Text(String),
+}
+
+/// Environment variables of a host, parsed and validated
+///
+/// The common variables have typed fields; a host's own are looked up by name
+/// with [`Self::flag`] and [`Self::text`]. Unset variables and variables the
+/// host did not declare read as unset.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EnvConfig {
+    /// `DEV_MODE`; the zkVM's own dev mode variable is checked by its prover
+    pub dev_mode: bool,
+    pub cache_dir: Option<PathBuf>,
+    pub store_dir: Option<PathBuf>,
+    pub github_token: Option<String>,
+    pub no_color: bool,
+    pub failpoints: Option<String>,
+    values: BTreeMap<&'static str, EnvValue>,
+}
+
+impl EnvConfig {
+    /// Parse the common variables and `host_vars` from the process environment
+    ///
+    /// Every invalid variable is reported in the error, one per line.
+    pub fn from_env(host_vars: &[EnvVar]) -> Result<Self> {
+        Self::from_lookup(host_vars, |name| std::env::var(name).ok())
+    }
+
+    /// Parse the common variables and `host_vars` with the values of `lookup`
+    pub fn from_lookup(host_vars: &[EnvVar], lookup: impl Fn(&str) -> Option<String>) -> Result<Self> {
+        let mut values = BTreeMap::new();
+        let mut errors = Vec::new();
+        for var in COMMON_VARS.iter().chain(host_vars) {
+            let Some(raw) = lookup(var.name) else {
+                continue;
+            };
+            match parse_value(var.kind, &raw) {
+                Ok(Some(value)) => {
+                    values.insert(var.name, value);
+                }
+                Ok(None) => {}
+                Err(e) => errors.push(format!("{}: {}", var.name, e)),
+            }
+        }
+        if !errors.is_empty() {
+            bail!("Invalid environment variables:\n  {}", errors.join("\n  "));
+        }
+
+        let mut config = Self {
+            values,
+            ..Self::default()
+        };
+        config.dev_mode = config.flag("DEV_MODE");
+        config.cache_dir = config.path("SIGSTORE_CACHE_DIR").map(Path::to_path_buf);
+        config.store_dir = config.path("SIGSTORE_STORE_DIR").map(Path::to_path_buf);
+        config.github_token = config.text("GITHUB_TOKEN").map(str::to_string);
+        config.no_color = config.text("NO_COLOR").is_some_and(|value| !value.is_empty());
+        config.failpoints = config.text(FAILPOINTS_ENV).map(str::to_string);
+        Ok(config)
+    }
+
+    /// Whether the flag variable `name` is set to a true value
+    pub fn flag(&self, name: &str) -> bool {
+        matches!(self.values.get(name), Some(EnvValue::Flag(true)))
+    }
+
+    /// Value of the path variable `name`
+    pub fn path(&self, name: &str) -> Option<&Path> {
+        match self.values.get(name) {
+            Some(EnvValue::Path(path)) => Some(path),
+            _ => None,
+        }
+    }
+
+    /// Value of the URL, secret or text variable `name`
+    pub fn text(&self, name: &str) -> Option<&str> {
+        match self.values.get(name) {
+            Some(EnvValue::Text(text)) => Some(text),
+            _ => None,
+        }
+    }
+}
+
+/// Parse `raw` as a value of `kind`; `None` for an unset flag
+fn parse_value(kind: VarKind, raw: &str) -> Result<Option<EnvValue>> {
+    match kind {
+        VarKind::Flag => match raw.trim().to_ascii_lowercase().as_str() {
+            "1" | "true" | "yes" | "on" => Ok(Some(EnvValue::Flag(true))),
+            "" | "0" | "false" | "no" | "off" => Ok(Some(EnvValue::Flag(false))),
+            _ => bail!("expected 1/true/yes/on or 0/false/no/off, got '{}'", raw),
+        },
+        VarKind::Path if raw.is_empty() => bail!("empty path"),
+        VarKind::Path => Ok(Some(EnvValue::Path(PathBuf::from(raw)))),
+        VarKind::Url if !(raw.starts_with("http://") || raw.starts_with("https://")) => {
+            bail!("expected an http:// or https:// URL, got '{}'", raw)
+        }
+        VarKind::Secret if raw.is_empty() => bail!("empty value"),
+        VarKind::Failpoints => {
+            parse_failpoints(raw)?;
+            Ok(Some(EnvValue::Text(raw.to_string())))
+        }
+        VarKind::Url | VarKind::Secret | VarKind::Text => Ok(Some(EnvValue::Text(raw.to_string()))),
+    }
+}
+
+static CONFIG: OnceLock<EnvConfig> = OnceLock::new();
+
+/// Parse the host's environment once, at startup
+///
+/// Later calls and [`config`] return the configuration of the first call.
+pub fn init(host_vars: &[EnvVar]) -> Result<&'static EnvConfig> {
+    if let Some(config) = CONFIG.get() {
+        return Ok(config);
+    }
+    let config = EnvConfig::from_env(host_vars)?;
+    Ok(CONFIG.get_or_init(|| config))
+}
+
+/// The configuration parsed by [`init`]
+///
+/// Without `init`, only the common variables are parsed, on first use.
+///
+/// # Panics
+///
+/// If `init` was not called and a common variable is invalid.
+pub fn config() -> &'static EnvConfig {
+    CONFIG.get_or_init(|| EnvConfig::from_env(&[]).unwrap_or_else(|e| panic!("{:#}", e)))
+}
+
+/// `--help-env` listing of the common variables and `host_vars`, with the
+/// value each one resolves to now
+///
+/// Secrets are masked; invalid values are shown with the reason.
+pub fn describe(host_vars: &[EnvVar]) -> String {
+    describe_with(host_vars, |name| std::env::var(name).ok())
+}
+
+fn describe_with(host_vars: &[EnvVar], lookup: impl Fn(&str) -> Option<String>) -> String {
+    let mut out = String::from("Environment variables (also read from .env):\n");
+    for var in COMMON_VARS.iter().chain(host_vars) {
+        let value = match lookup(var.name) {
+            None => "(unset)".to_string(),
+            Some(raw) => {
+                let shown = match var.kind {
+                    VarKind::Secret => "********".to_string(),
+                    _ => format!("'{}'", raw),
+                };
+                match parse_value(var.kind, &raw) {
+                    Ok(_) => shown,
+                    Err(e) => format!("{} (invalid: {:#})", shown, e),
+                }
+            }
+        };
+        out.push_str(&format!("\n  {:<24} {}\n      {}\n", var.name, value, var.description));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HOST_VARS: &[EnvVar] = &[
+        EnvVar::new("TEST_DEV_MODE", VarKind::Flag, "Dev mode"),
+        EnvVar::new("TEST_RPC_URL", VarKind::Url, "RPC"),
+        EnvVar::new("TEST_PRIVATE_KEY", VarKind::Secret, "Key"),
+    ];
+
+    fn lookup(vars: &'static [(&'static str, &'static str)]) -> impl Fn(&str) -> Option<String> {
+        move |name| vars.iter().find(|(key, _)| *key == name).map(|(_, value)| value.to_string())
+    }
+
+    #[test]
+    fn test_parses_typed_values() {
+        let config = EnvConfig::from_lookup(
+            HOST_VARS,
+            lookup(&[
+                ("DEV_MODE", "0"),
+                ("TEST_DEV_MODE", "Yes"),
+                ("SIGSTORE_CACHE_DIR", "/tmp/cache"),
+                ("NO_COLOR", ""),
+                ("TEST_RPC_URL", "https://rpc.example"),
+                ("UNDECLARED", "1"),
+            ]),
+        )
+        .unwrap();
+        assert!(!config.dev_mode);
+        assert!(config.flag("TEST_DEV_MODE"));
+        assert_eq!(config.cache_dir.as_deref(), Some(Path::new("/tmp/cache")));
+        assert_eq!(config.store_dir, None);
+        assert!(!config.no_color);
+        assert_eq!(config.text("TEST_RPC_URL"), Some("https://rpc.example"));
+        assert!(!config.flag("UNDECLARED"));
+    }
+
+    #[test]
+    fn test_reports_every_invalid_variable() {
+        let err = EnvConfig::from_lookup(
+            HOST_VARS,
+            lookup(&[
+                ("DEV_MODE", "maybe"),
+                ("TEST_RPC_URL", "rpc.example"),
+                ("SIGSTORE_FAILPOINTS", "nowhere"),
+            ]),
+        )
+        .unwrap_err()
+        .to_string();
+        assert!(err.contains("DEV_MODE: expected 1/true/yes/on"), "{}", err);
+        assert!(err.contains("TEST_RPC_URL: expected an http:// or https:// URL"), "{}", err);
+        assert!(err.contains("SIGSTORE_FAILPOINTS: Unknown failpoint stage"), "{}", err);
+    }
+
+    #[test]
+    fn test_describe_masks_secrets() {
+        let help = describe_with(
+            HOST_VARS,
+            lookup(&[("TEST_PRIVATE_KEY", "0xdeadbeef"), ("DEV_MODE", "maybe")]),
+        );
+        assert!(help.contains("TEST_PRIVATE_KEY         ********\n"), "{}", help);
+        assert!(!help.contains("deadbeef"));
+        assert!(help.contains("DEV_MODE                 'maybe' (invalid: "), "{}", help);
+        assert!(help.contains("SIGSTORE_STORE_DIR       (unset)\n"), "{}", help);
+    }
+}
@@ -0,0 +1,176 @@
+//! Failure injection for resilience testing of the proving pipeline
+//!
+//! The hosts and the backfill tool call [`inject`] at the start of each stage.
+//! Without the `failpoints` feature it compiles to `Ok(())`. With it, failures
+//! are injected according to the `SIGSTORE_FAILPOINTS` environment variable or
+//! the programmatic [`configure`] / [`clear`] functions, so retry and resume
+//! logic can be exercised deterministically in CI.
+//!
+//! `SIGSTORE_FAILPOINTS` is a comma-separated list of `stage[=action]`:
+//! - `submission`: fail every time the stage is reached
+//! - `submission=2`: fail the first 2 times, then succeed
+//! - `submission=panic`: panic, simulating a crash mid-pipeline
+//!
+//! Stages: `input-prep`, `emulation`, `submission`, `fulfillment-wait`,
+//! `artifact-write`.
+
+use anyhow::{bail, Result};
+use std::fmt;
+use std::str::FromStr;
+
+/// Environment variable holding the failpoint configuration
+pub const FAILPOINTS_ENV: &str = "SIGSTORE_FAILPOINTS";
+
+/// Pipeline stage at which a failure can be injected
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Stage {
+    /// Resolving sources and preparing the guest input
+    InputPrep,
+    /// Executing the guest program locally to obtain the journal
+    Emulation,
+    /// Submitting the proof request to a proving network
+    Submission,
+    /// Waiting for a submitted request to be fulfilled
+    FulfillmentWait,
+    /// Writing the proof artifact or store record
+    ArtifactWrite,
+}
+
+impl FromStr for Stage {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "input-prep" => Ok(Stage::InputPrep),
+            "emulation" => Ok(Stage::Emulation),
+            "submission" => Ok(Stage::Submission),
+            "fulfillment-wait" => Ok(Stage::FulfillmentWait),
+            "artifact-write" => Ok(Stage::ArtifactWrite),
+            _ => bail!("Unknown failpoint stage '{}'", s),
+        }
+    }
+}
+
+impl fmt::Display for Stage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Stage::InputPrep => write!(f, "input-prep"),
+            Stage::Emulation => write!(f, "emulation"),
+            Stage::Submission => write!(f, "submission"),
+            Stage::FulfillmentWait => write!(f, "fulfillment-wait"),
+            Stage::ArtifactWrite => write!(f, "artifact-write"),
+        }
+    }
+}
+
+/// What happens when a configured stage is reached
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FailAction {
+    /// Return an error every time
+    Always,
+    /// Return an error for the next `n` hits, then succeed
+    Times(u32),
+    /// Panic
+    Panic,
+}
+
+impl FromStr for FailAction {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "" | "always" => Ok(FailAction::Always),
+            "panic" => Ok(FailAction::Panic),
+            n => match n.parse() {
+                Ok(times) => Ok(FailAction::Times(times)),
+                Err(_) => bail!("Invalid failpoint action '{}'", s),
+            },
+        }
+    }
+}
+
+/// Inject a failure at `stage` if one is configured
+///
+/// # Errors
+///
+/// Returns an error naming the stage when a failure is injected
+#[cfg(not(feature = "failpoints"))]
+#[inline(always)]
+pub fn inject(_stage: Stage) -> Result<()> {
+    Ok(())
+}
+
+/// Inject a failure at `stage` if one is configured
+///
+/// # Errors
+///
+/// Returns an error naming the stage when a failure is injected
+///
+/// # Panics
+///
+/// Panics when the stage is configured with `FailAction::Panic`, or when
+/// `SIGSTORE_FAILPOINTS` cannot be parsed
+#[cfg(feature = "failpoints")]
+pub fn inject(stage: Stage) -> Result<()> {
+    let mut registry = imp::registry();
+    let Some(action) = registry.get_mut(&stage) else {
+        return Ok(());
+    };
+
+    match action {
+        FailAction::Always => {}
+        FailAction::Times(0) => return Ok(()),
+        FailAction::Times(remaining) => *remaining -= 1,
+        FailAction::Panic => panic!("Injected panic at {}", stage),
+    }
+    bail!("Injected failure at {}", stage)
+}
+
+/// Configure a failure for `stage`, replacing any previous configuration
+#[cfg(feature = "failpoints")]
+pub fn configure(stage: Stage, action: FailAction) {
+    imp::registry().insert(stage, action);
+}
+
+/// Remove all configured failures, including those from the environment
+#[cfg(feature = "failpoints")]
+pub fn clear() {
+    imp::registry().clear();
+}
+
+/// Parse a `SIGSTORE_FAILPOINTS` value
+///
+/// # Errors
+///
+/// Returns an error for unknown stages or invalid actions
+pub fn parse_failpoints(spec: &str) -> Result<Vec<(Stage, FailAction)>> {
+    spec.split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| {
+            let (stage, action) = entry.split_once('=').unwrap_or((entry, ""));
+            Ok((stage.trim().parse()?, action.trim().parse()?))
+        })
+        .collect()
+}
+
+#[cfg(feature = "failpoints")]
+mod imp {
+    use super::*;
+    use std::collections::HashMap;
+    use std::sync::{Mutex, MutexGuard, OnceLock};
+
+    static REGISTRY: OnceLock<Mutex<HashMap<Stage, FailAction>>> = OnceLock::new();
+
+    pub fn registry() -> MutexGuard<'static, HashMap<Stage, FailAction>> {
+        REGISTRY
+            .get_or_init(|| {
+                let spec = std::env::var(FAILPOINTS_ENV).unwrap_or_default();
+                let failpoints = parse_failpoints(&spec)
+                    .unwrap_or_else(|e| panic!("Invalid {}: {}", FAILPOINTS_ENV, e));
+                Mutex::new(failpoints.into_iter().collect())
+            })
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
@@ -0,0 +1,307 @@
+//! On-disk encodings of proof artifacts and proof bundles
+//!
+//! Artifacts are JSON by default, with the journal, the proof and the guest
+//! input as hex strings. Across thousands of artifacts (Groth16-wrapped seals
+//! plus journals) the hex doubles the size on disk, so they can also be written
+//! as CBOR with those fields as raw byte strings, optionally zstd-compressed:
+//!
+//! | Format      | Layout                                           |
+//! |-------------|--------------------------------------------------|
+//! | `json`      | Pretty-printed JSON                              |
+//! | `cbor`      | CBOR self-describe tag (`d9 d9 f7`), CBOR record |
+//! | `cbor-zstd` | zstd frame of the `cbor` encoding                |
+//!
+//! Readers don't need to be told the format: [`decode_proof_artifact`] and
+//! [`decode_proof_bundle`] recognize the zstd frame magic and the CBOR tag, and
+//! fall back to JSON.
+//!
+//! The in-memory types stay hex-based, since that is what the TEE binding
+//! digest covers (see [`crate::tee::artifact_digest`]). Hex fields must hence
+//! be in the canonical `0x`-prefixed lowercase form the hosts write, so that an
+//! artifact decodes to exactly what was encoded.
+
+use crate::tee::TeeAttestation;
+use crate::utils::{ProofArtifact, ProofBundle};
+use anyhow::{bail, Context, Result};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+/// CBOR self-describe tag 55799, marking the content as CBOR
+const CBOR_MAGIC: [u8; 3] = [0xd9, 0xd9, 0xf7];
+/// Magic number starting every zstd frame
+const ZSTD_MAGIC: [u8; 4] = [0x28, 0xb5, 0x2f, 0xfd];
+/// Proofs are written once and read often, so compress hard
+const ZSTD_LEVEL: i32 = 19;
+
+/// Encoding of a proof artifact or proof bundle file
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ArtifactFormat {
+    /// Pretty-printed JSON with hex-encoded binary fields
+    #[default]
+    Json,
+    /// CBOR with binary fields as byte strings
+    Cbor,
+    /// zstd-compressed CBOR
+    CborZstd,
+}
+
+impl ArtifactFormat {
+    /// Detect the format of encoded content
+    pub fn detect(content: &[u8]) -> Self {
+        if content.starts_with(&ZSTD_MAGIC) {
+            ArtifactFormat::CborZstd
+        } else if content.starts_with(&CBOR_MAGIC) {
+            ArtifactFormat::Cbor
+        } else {
+            ArtifactFormat::Json
+        }
+    }
+}
+
+impl FromStr for ArtifactFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "json" => Ok(ArtifactFormat::Json),
+            "cbor" => Ok(ArtifactFormat::Cbor),
+            "cbor-zstd" => Ok(ArtifactFormat::CborZstd),
+            _ => bail!("Unsupported artifact format '{}', expected 'json', 'cbor' or 'cbor-zstd'", s),
+        }
+    }
+}
+
+impl fmt::Display for ArtifactFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ArtifactFormat::Json => write!(f, "json"),
+            ArtifactFormat::Cbor => write!(f, "cbor"),
+            ArtifactFormat::CborZstd => write!(f, "cbor-zstd"),
+        }
+    }
+}
+
+/// CBOR record of a proof artifact
+///
+/// Mirrors [`ProofArtifact`] with the journal and the proof as byte strings.
+#[derive(Debug, Serialize, Deserialize)]
+struct BinaryProofArtifact {
+    zkvm: String,
+    program_id: String,
+    circuit_version: String,
+    #[serde(with = "serde_bytes")]
+    journal: Vec<u8>,
+    #[serde(with = "serde_bytes")]
+    proof: Vec<u8>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    tee_attestation: Option<TeeAttestation>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    annotations: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    metadata: BTreeMap<String, String>,
+}
+
+/// CBOR record of a proof bundle
+///
+/// Unlike the JSON encoding the artifact is nested, as a binary bundle does not
+/// need to double as an artifact file.
+#[derive(Debug, Serialize, Deserialize)]
+struct BinaryProofBundle {
+    artifact: BinaryProofArtifact,
+    source: String,
+    #[serde(with = "serde_bytes")]
+    input: Vec<u8>,
+}
+
+/// Bytes of a canonical `0x`-prefixed lowercase hex field
+fn canonical_hex(value: &str, field: &str) -> Result<Vec<u8>> {
+    let bytes = value
+        .strip_prefix("0x")
+        .and_then(|hex| hex::decode(hex).ok())
+        .with_context(|| format!("{} is not 0x-prefixed hex", field))?;
+    if value[2..] != hex::encode(&bytes) {
+        bail!("{} is not lowercase hex, which binary formats cannot reproduce", field);
+    }
+    Ok(bytes)
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    format!("0x{}", hex::encode(bytes))
+}
+
+impl BinaryProofArtifact {
+    fn from_artifact(artifact: &ProofArtifact) -> Result<Self> {
+        Ok(Self {
+            zkvm: artifact.zkvm.clone(),
+            program_id: artifact.program_id.clone(),
+            circuit_version: artifact.circuit_version.clone(),
+            journal: canonical_hex(&artifact.journal, "journal")?,
+            proof: canonical_hex(&artifact.proof, "proof")?,
+            tee_attestation: artifact.tee_attestation.clone(),
+            annotations: artifact.annotations.clone(),
+            metadata: artifact.metadata.clone(),
+        })
+    }
+
+    fn into_artifact(self) -> ProofArtifact {
+        ProofArtifact {
+            zkvm: self.zkvm,
+            program_id: self.program_id,
+            circuit_version: self.circuit_version,
+            journal: to_hex(&self.journal),
+            proof: to_hex(&self.proof),
+            tee_attestation: self.tee_attestation,
+            annotations: self.annotations,
+            metadata: self.metadata,
+        }
+    }
+}
+
+/// Encode `value` (JSON) or `binary` (CBOR) in `format`
+fn encode<J: Serialize, B: Serialize>(
+    value: &J,
+    binary: impl FnOnce() -> Result<B>,
+    format: ArtifactFormat,
+) -> Result<Vec<u8>> {
+    if format == ArtifactFormat::Json {
+        return serde_json::to_vec_pretty(value).context("Failed to serialize JSON");
+    }
+
+    let mut cbor = CBOR_MAGIC.to_vec();
+    ciborium::into_writer(&binary()?, &mut cbor).context("Failed to serialize CBOR")?;
+    match format {
+        ArtifactFormat::CborZstd => {
+            zstd::encode_all(cbor.as_slice(), ZSTD_LEVEL).context("Failed to compress")
+        }
+        _ => Ok(cbor),
+    }
+}
+
+/// Decode content in any format into `J` (JSON) or `B` (CBOR)
+fn decode<J: DeserializeOwned, B: DeserializeOwned>(
+    content: &[u8],
+    from_binary: impl FnOnce(B) -> J,
+) -> Result<J> {
+    match ArtifactFormat::detect(content) {
+        ArtifactFormat::Json => serde_json::from_slice(content).context("Invalid JSON"),
+        ArtifactFormat::Cbor => {
+            let binary = ciborium::from_reader(&content[CBOR_MAGIC.len()..]).context("Invalid CBOR")?;
+            Ok(from_binary(binary))
+        }
+        ArtifactFormat::CborZstd => {
+            let cbor = zstd::decode_all(content).context("Invalid zstd frame")?;
+            if !cbor.starts_with(&CBOR_MAGIC) {
+                bail!("Compressed content is not CBOR");
+            }
+            decode(&cbor, from_binary)
+        }
+    }
+}
+
+/// Encode a proof artifact
+///
+/// # Errors
+///
+/// Returns an error for binary formats if the journal or proof is not canonical hex
+pub fn encode_proof_artifact(artifact: &ProofArtifact, format: ArtifactFormat) -> Result<Vec<u8>> {
+    encode(artifact, || BinaryProofArtifact::from_artifact(artifact), format)
+        .context("Failed to encode proof artifact")
+}
+
+/// Decode a proof artifact in any of the [`ArtifactFormat`]s
+pub fn decode_proof_artifact(content: &[u8]) -> Result<ProofArtifact> {
+    decode(content, BinaryProofArtifact::into_artifact).context("Invalid proof artifact")
+}
+
+/// Encode a proof bundle
+///
+/// # Errors
+///
+/// Returns an error for binary formats if the journal, proof or input is not
+/// canonical hex
+pub fn encode_proof_bundle(bundle: &ProofBundle, format: ArtifactFormat) -> Result<Vec<u8>> {
+    let binary = || {
+        Ok(BinaryProofBundle {
+            artifact: BinaryProofArtifact::from_artifact(&bundle.artifact)?,
+            source: bundle.source.clone(),
+            input: canonical_hex(&bundle.input, "input")?,
+        })
+    };
+    encode(bundle, binary, format).context("Failed to encode proof bundle")
+}
+
+/// Decode a proof bundle in any of the [`ArtifactFormat`]s
+pub fn decode_proof_bundle(content: &[u8]) -> Result<ProofBundle> {
+    let from_binary = |binary: BinaryProofBundle| ProofBundle {
+        artifact: binary.artifact.into_artifact(),
+        source: binary.source,
+        input: to_hex(&binary.input),
+    };
+    decode(content, from_binary).context("Invalid proof bundle")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn artifact() -> ProofArtifact {
+        ProofArtifact {
+            zkvm: "risc0".to_string(),
+            program_id: "0x1234".to_string(),
+            circuit_version: "3.0.0".to_string(),
+            journal: to_hex(&[0xab; 512]),
+            proof: to_hex(&[0xcd; 260]),
+            tee_attestation: None,
+            annotations: BTreeMap::from([("build".to_string(), "1234".to_string())]),
+            metadata: BTreeMap::from([("field_type".to_string(), "kb".to_string())]),
+        }
+    }
+
+    #[test]
+    fn test_artifact_roundtrip_in_every_format() {
+        let artifact = artifact();
+        let json = encode_proof_artifact(&artifact, ArtifactFormat::Json).unwrap();
+        for format in [ArtifactFormat::Json, ArtifactFormat::Cbor, ArtifactFormat::CborZstd] {
+            let encoded = encode_proof_artifact(&artifact, format).unwrap();
+            assert_eq!(ArtifactFormat::detect(&encoded), format);
+            let decoded = decode_proof_artifact(&encoded).unwrap();
+            assert_eq!(encode_proof_artifact(&decoded, ArtifactFormat::Json).unwrap(), json, "{}", format);
+        }
+
+        let cbor = encode_proof_artifact(&artifact, ArtifactFormat::Cbor).unwrap();
+        assert!(cbor.len() * 3 < json.len() * 2);
+    }
+
+    #[test]
+    fn test_bundle_roundtrip() {
+        let bundle = ProofBundle {
+            artifact: artifact(),
+            source: "samples/bundle.json".to_string(),
+            input: to_hex(b"guest input"),
+        };
+        let encoded = encode_proof_bundle(&bundle, ArtifactFormat::CborZstd).unwrap();
+        let decoded = decode_proof_bundle(&encoded).unwrap();
+        assert_eq!(decoded.input, bundle.input);
+        assert_eq!(decoded.source, bundle.source);
+        assert_eq!(decoded.artifact.journal, bundle.artifact.journal);
+    }
+
+    #[test]
+    fn test_non_canonical_hex_is_not_encoded_binary() {
+        let mut artifact = artifact();
+        artifact.proof = artifact.proof.to_uppercase().replacen("0X", "0x", 1);
+        assert!(encode_proof_artifact(&artifact, ArtifactFormat::Cbor).is_err());
+        // JSON keeps the strings as they are
+        assert!(encode_proof_artifact(&artifact, ArtifactFormat::Json).is_ok());
+    }
+
+    #[test]
+    fn test_format_names() {
+        for format in [ArtifactFormat::Json, ArtifactFormat::Cbor, ArtifactFormat::CborZstd] {
+            assert_eq!(format.to_string().parse::<ArtifactFormat>().unwrap(), format);
+        }
+        assert!("protobuf".parse::<ArtifactFormat>().is_err());
+    }
+}
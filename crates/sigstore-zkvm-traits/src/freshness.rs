@@ -0,0 +1,131 @@
+//! Freshness of stored trusted roots
+//!
+//! Sigstore rotates and revokes CA and TSA certificates by publishing a new
+//! trusted root. A prover that keeps using an old copy keeps trusting what was
+//! since removed, so the hosts refuse to prove against a trusted root older
+//! than a configurable age.
+//!
+//! The age comes from a metadata file stored next to the trusted root:
+//!
+//! ```text
+//! <trusted root>.meta.json   # TrustRootMetadata
+//! ```
+//!
+//! It is written when a remote trusted root is downloaded into the cache, and
+//! by `trust-root convert`/`sign` with `--expires-in`. Files without metadata
+//! are dated by their modification time.
+//!
+//! This is a host-side policy on when to prove. It uses the wall clock and is
+//! deliberately kept out of verification, which only depends on the signing
+//! time of the bundle.
+
+use crate::utils::write_atomic;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, UNIX_EPOCH};
+
+/// Metadata of a stored trusted root
+///
+/// - source: Where the content was fetched or converted from
+/// - fetched_at: Unix timestamp the content was fetched or generated
+/// - expires: Unix timestamp after which the content must not be used, if set
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrustRootMetadata {
+    pub source: String,
+    pub fetched_at: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires: Option<u64>,
+}
+
+impl TrustRootMetadata {
+    /// Path of the metadata file of `trust_root`
+    pub fn path_for(trust_root: &Path) -> PathBuf {
+        let mut path = trust_root.as_os_str().to_owned();
+        path.push(".meta.json");
+        PathBuf::from(path)
+    }
+
+    /// Load the metadata of `trust_root`, if any
+    pub fn load(trust_root: &Path) -> Result<Option<Self>> {
+        let path = Self::path_for(trust_root);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let content =
+            fs::read(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+        let metadata = serde_json::from_slice(&content)
+            .with_context(|| format!("Failed to parse {}", path.display()))?;
+        Ok(Some(metadata))
+    }
+
+    /// Write the metadata of `trust_root` atomically
+    pub fn save(&self, trust_root: &Path) -> Result<()> {
+        let json = serde_json::to_vec_pretty(self).context("Failed to serialize metadata")?;
+        write_atomic(&Self::path_for(trust_root), &json)
+    }
+
+    /// Metadata of `trust_root`, dated by its modification time if none was stored
+    pub fn for_file(trust_root: &Path) -> Result<Self> {
+        if let Some(metadata) = Self::load(trust_root)? {
+            return Ok(metadata);
+        }
+        let modified = fs::metadata(trust_root)
+            .and_then(|metadata| metadata.modified())
+            .with_context(|| format!("Failed to read modification time of {}", trust_root.display()))?;
+        Ok(Self {
+            source: trust_root.display().to_string(),
+            fetched_at: modified
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or_default(),
+            expires: None,
+        })
+    }
+
+    /// Why the trusted root is stale at `now`, if it is
+    ///
+    /// A trusted root is stale once it is past its expiry or older than `max_age`.
+    pub fn staleness(&self, max_age: Duration, now: u64) -> Option<String> {
+        if let Some(expires) = self.expires.filter(|&expires| now >= expires) {
+            return Some(format!(
+                "trusted root from {} expired {} ago",
+                self.source,
+                format_age(now - expires)
+            ));
+        }
+        let age = now.saturating_sub(self.fetched_at);
+        (age > max_age.as_secs()).then(|| {
+            format!(
+                "trusted root from {} is {} old, older than the maximum of {}",
+                self.source,
+                format_age(age),
+                format_age(max_age.as_secs())
+            )
+        })
+    }
+}
+
+/// Check that the trusted root at `path` is fresh at `now`
+///
+/// # Returns
+///
+/// `None` if the trusted root is fresh, else the reason it is stale
+///
+/// # Errors
+///
+/// Returns an error if neither its metadata nor its modification time can be read
+pub fn check_trust_root_freshness(path: &Path, max_age: Duration, now: u64) -> Result<Option<String>> {
+    Ok(TrustRootMetadata::for_file(path)?.staleness(max_age, now))
+}
+
+/// Coarse human-readable duration, e.g. `3d 4h`
+fn format_age(secs: u64) -> String {
+    let (days, hours, minutes) = (secs / 86_400, secs % 86_400 / 3_600, secs % 3_600 / 60);
+    match (days, hours) {
+        (0, 0) => format!("{}m", minutes),
+        (0, _) => format!("{}h {}m", hours, minutes),
+        _ => format!("{}d {}h", days, hours),
+    }
+}
@@ -0,0 +1,219 @@
+//! File-backed store for proving network jobs
+//!
+//! A job is keyed by the SHA256 of the program, the guest input and the proof
+//! kind, so running the same proof again resumes the request that is already in
+//! flight (or reuses its seal) instead of paying for a second one:
+//!
+//! ```text
+//! <root>/<job key>.json   # ProvingJob
+//! ```
+//!
+//! Jobs are written with [`write_atomic`], so a crash while updating a job
+//! leaves its previous state in place.
+//!
+//! A job that is no longer needed is cancelled with [`JobStore::cancel`]: a
+//! prover waiting on it stops, and later runs submit a new request rather than
+//! resuming it. Whether the network can still charge for the request is up to
+//! the network; the host's `jobs cancel` command reports it.
+
+use crate::store::unix_now;
+use crate::utils::{remove_partial_files, write_atomic};
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use sigstore_verifier::crypto::hash::sha256;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Lifecycle of a proving network job
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JobStatus {
+    /// Not submitted yet, or the last submission failed before a request was created
+    Pending,
+    /// A request is on-chain and waiting for fulfillment
+    Submitted,
+    /// The request was fulfilled; the job carries the seal
+    Fulfilled,
+    /// The request expired unfulfilled
+    Expired,
+    /// The job failed with an error that retrying cannot fix
+    Failed,
+    /// The job was cancelled; its request is neither resumed nor waited on
+    Cancelled,
+}
+
+/// One proof request on a proving network
+///
+/// - key: Hex SHA256 identifying program, input and proof kind (see [`job_key`])
+/// - network: Network the request lives on (e.g. `boundless:8453`)
+/// - status: Current state of the job
+/// - request_id: Hex request ID once submitted
+/// - expires_at: Unix timestamp after which the request can no longer be fulfilled
+/// - seal: Hex-encoded seal once fulfilled
+/// - max_price: Offer's maximum price in wei of the last submission, if capped
+/// - attempts: Number of submissions made for this input
+/// - last_error: Most recent error, if any
+/// - updated_at: Unix timestamp of the last update
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProvingJob {
+    pub key: String,
+    pub network: String,
+    pub status: JobStatus,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seal: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_price: Option<String>,
+    #[serde(default)]
+    pub attempts: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+    pub updated_at: u64,
+}
+
+impl ProvingJob {
+    /// Create a pending job
+    pub fn new(key: String, network: String) -> Self {
+        Self {
+            key,
+            network,
+            status: JobStatus::Pending,
+            request_id: None,
+            expires_at: None,
+            seal: None,
+            max_price: None,
+            attempts: 0,
+            last_error: None,
+            updated_at: 0,
+        }
+    }
+
+    /// Request ID and expiry of a submitted request that can still be fulfilled
+    pub fn live_request(&self, now: u64) -> Option<(&str, u64)> {
+        match (self.status, self.request_id.as_deref(), self.expires_at) {
+            (JobStatus::Submitted, Some(request_id), Some(expires_at)) if expires_at > now => {
+                Some((request_id, expires_at))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Idempotency key for a proof of `input` by `program`
+///
+/// # Arguments
+///
+/// * `program` - Program identity (ELF bytes or program URL)
+/// * `input` - Encoded guest input
+/// * `proof_kind` - Kind of proof requested (e.g. `groth16`), since seals differ per kind
+///
+/// # Returns
+///
+/// Hex SHA256 over the SHA256 of the program, the SHA256 of the input and the proof kind
+pub fn job_key(program: &[u8], input: &[u8], proof_kind: &str) -> String {
+    let mut preimage = Vec::with_capacity(64 + proof_kind.len());
+    preimage.extend_from_slice(&sha256(program));
+    preimage.extend_from_slice(&sha256(input));
+    preimage.extend_from_slice(proof_kind.as_bytes());
+    hex::encode(sha256(&preimage))
+}
+
+/// Directory-backed job store
+#[derive(Debug, Clone)]
+pub struct JobStore {
+    root: PathBuf,
+}
+
+impl JobStore {
+    /// Open the store at `root`, creating the directory if needed
+    ///
+    /// Leftovers of writes interrupted by a crash are removed.
+    pub fn open(root: &Path) -> Result<Self> {
+        fs::create_dir_all(root)
+            .with_context(|| format!("Failed to create job store directory: {}", root.display()))?;
+        remove_partial_files(root)?;
+        Ok(Self {
+            root: root.to_path_buf(),
+        })
+    }
+
+    /// Load a job, if any
+    pub fn get(&self, key: &str) -> Result<Option<ProvingJob>> {
+        let path = self.path(key);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let content =
+            fs::read(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+        let job = serde_json::from_slice(&content)
+            .with_context(|| format!("Failed to parse {}", path.display()))?;
+        Ok(Some(job))
+    }
+
+    /// Insert or replace a job, stamping its update time
+    pub fn put(&self, job: &mut ProvingJob) -> Result<()> {
+        job.updated_at = unix_now();
+        let json = serde_json::to_vec_pretty(job).context("Failed to serialize job")?;
+        write_atomic(&self.path(&job.key), &json)
+    }
+
+    /// Load all jobs, ordered by key
+    pub fn list(&self) -> Result<Vec<ProvingJob>> {
+        let entries = fs::read_dir(&self.root)
+            .with_context(|| format!("Failed to read {}", self.root.display()))?;
+        let mut jobs = Vec::new();
+        for entry in entries {
+            let path = entry?.path();
+            let key = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .and_then(|name| name.strip_suffix(".json"));
+            if let Some(key) = key {
+                jobs.extend(self.get(key)?);
+            }
+        }
+        jobs.sort_by(|a, b| a.key.cmp(&b.key));
+        Ok(jobs)
+    }
+
+    /// Load the job whose key starts with `prefix`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no job or more than one job matches
+    pub fn find(&self, prefix: &str) -> Result<ProvingJob> {
+        let prefix = prefix.to_ascii_lowercase();
+        let mut matches = self.list()?.into_iter().filter(|job| job.key.starts_with(&prefix));
+        match (matches.next(), matches.next()) {
+            (Some(job), None) => Ok(job),
+            (None, _) => bail!("No job matches '{}'", prefix),
+            (Some(_), Some(_)) => bail!("Several jobs match '{}'; give more of the key", prefix),
+        }
+    }
+
+    /// Mark a job cancelled
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the job is already fulfilled, as its proof has been
+    /// paid for, or if the store cannot be written
+    pub fn cancel(&self, job: &mut ProvingJob) -> Result<()> {
+        if job.status == JobStatus::Fulfilled {
+            bail!("Job {} is already fulfilled; nothing to cancel", job.key);
+        }
+        job.status = JobStatus::Cancelled;
+        self.put(job)
+    }
+
+    /// Whether the job has been cancelled since it was loaded
+    pub fn is_cancelled(&self, key: &str) -> Result<bool> {
+        Ok(self.get(key)?.is_some_and(|job| job.status == JobStatus::Cancelled))
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        self.root.join(format!("{}.json", key))
+    }
+}
@@ -41,7 +41,23 @@
 //! let (public_output, proof_bytes) = prover.prove(&config, &input).await?;
 //! ```
 
+pub mod display;
+pub mod env;
 pub mod error;
+pub mod failpoint;
+pub mod format;
+pub mod freshness;
+pub mod jobs;
+pub mod paths;
+pub mod pipeline;
+pub mod policies;
+pub mod prepared;
+pub mod resolver;
+pub mod setup;
+pub mod sizing;
+pub mod source;
+pub mod store;
+pub mod tee;
 pub mod traits;
 pub mod types;
 pub mod utils;
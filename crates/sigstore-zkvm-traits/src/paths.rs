@@ -0,0 +1,267 @@
+//! Platform-aware path handling
+//!
+//! Paths reach the hosts from CLI flags, environment variables and config
+//! files that may have been written on another platform, and artifacts are
+//! written below directories that can be deeply nested on CI runners. On
+//! Windows this means:
+//!
+//! - Both `/` and `\` separate components, and `C:`, `\\server\share` and
+//!   `\\?\` prefixes must be kept intact
+//! - Paths of [`MAX_PATH`] characters or more need the `\\?\` prefix, which in
+//!   turn disables all lexical processing by Windows itself
+//!
+//! The string functions take a [`PathStyle`] so that both behaviours are
+//! exercised on any platform; the [`Path`] functions use the native style.
+
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+
+/// Longest path most Windows APIs accept without the `\\?\` prefix
+pub const MAX_PATH: usize = 260;
+
+/// Path syntax of a platform
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PathStyle {
+    Unix,
+    Windows,
+}
+
+impl PathStyle {
+    /// Style of the platform this binary was built for
+    pub const fn native() -> Self {
+        if cfg!(windows) {
+            PathStyle::Windows
+        } else {
+            PathStyle::Unix
+        }
+    }
+
+    /// Preferred separator
+    pub const fn separator(self) -> char {
+        match self {
+            PathStyle::Unix => '/',
+            PathStyle::Windows => '\\',
+        }
+    }
+
+    /// Whether `c` separates path components
+    ///
+    /// On Unix a backslash is an ordinary file name character.
+    pub fn is_separator(self, c: char) -> bool {
+        c == '/' || (self == PathStyle::Windows && c == '\\')
+    }
+}
+
+/// Split a Windows path into its prefix (`C:`, `\\server\share` or a verbatim
+/// `\\?\...` path) and the rest
+fn split_windows_prefix(path: &str) -> (&str, &str) {
+    let style = PathStyle::Windows;
+    let bytes = path.as_bytes();
+    if path.starts_with(r"\\?\") {
+        return (path, "");
+    }
+    if bytes.len() >= 2 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':' {
+        return path.split_at(2);
+    }
+    let mut chars = path.chars();
+    let unc = chars.next().is_some_and(|c| style.is_separator(c))
+        && chars.next().is_some_and(|c| style.is_separator(c));
+    if unc {
+        // \\server\share: the prefix ends before the separator after the share
+        let rest = &path[2..];
+        let mut separators = rest.match_indices(|c| style.is_separator(c)).map(|(i, _)| i);
+        let end = match (separators.next(), separators.next()) {
+            (Some(_), Some(share_end)) => 2 + share_end,
+            _ => path.len(),
+        };
+        return path.split_at(end);
+    }
+    ("", path)
+}
+
+/// Normalize `path` lexically for `style`
+///
+/// Separators are unified to the preferred one, repeated separators and `.`
+/// components are dropped, and `..` removes the preceding component where
+/// there is one. The file system is not consulted, so `..` after a symlink is
+/// resolved textually. Windows verbatim (`\\?\`) paths are returned unchanged.
+///
+/// # Example
+///
+/// ```ignore
+/// assert_eq!(normalize_str("C:/artifacts/./pico/../vm_pk", PathStyle::Windows), r"C:\artifacts\vm_pk");
+/// ```
+pub fn normalize_str(path: &str, style: PathStyle) -> String {
+    let (prefix, rest) = match style {
+        PathStyle::Windows => split_windows_prefix(path),
+        PathStyle::Unix => ("", path),
+    };
+    if prefix.starts_with(r"\\?\") {
+        return path.to_string();
+    }
+
+    let separator = style.separator();
+    let rooted = rest.starts_with(|c| style.is_separator(c));
+    let mut components: Vec<&str> = Vec::new();
+    for component in rest.split(|c| style.is_separator(c)) {
+        match component {
+            "" | "." => {}
+            ".." => match components.last() {
+                Some(&last) if last != ".." => {
+                    components.pop();
+                }
+                // Nothing above the root
+                _ if rooted => {}
+                _ => components.push(".."),
+            },
+            _ => components.push(component),
+        }
+    }
+
+    let mut normalized: String = prefix
+        .chars()
+        .map(|c| if style.is_separator(c) { separator } else { c })
+        .collect();
+    if rooted {
+        normalized.push(separator);
+    }
+    normalized.push_str(&components.join(&separator.to_string()));
+    if normalized.is_empty() {
+        normalized.push('.');
+    }
+    normalized
+}
+
+/// Add the `\\?\` prefix to an absolute Windows path of [`MAX_PATH`] characters or more
+///
+/// The path is normalized first, since Windows does not process verbatim paths.
+/// Relative paths, short paths and Unix paths are only normalized.
+pub fn long_path_str(path: &str, style: PathStyle) -> String {
+    let normalized = normalize_str(path, style);
+    if style != PathStyle::Windows || normalized.len() < MAX_PATH {
+        return normalized;
+    }
+    match split_windows_prefix(&normalized) {
+        (prefix, rest) if prefix.len() == 2 && rest.starts_with('\\') => format!(r"\\?\{}", normalized),
+        (prefix, _) if prefix.starts_with(r"\\") && !prefix.starts_with(r"\\?\") => {
+            format!(r"\\?\UNC\{}", &normalized[2..])
+        }
+        _ => normalized,
+    }
+}
+
+/// Normalize `path` lexically for the native platform
+///
+/// Paths that are not valid Unicode are returned unchanged.
+pub fn normalize(path: &Path) -> PathBuf {
+    match path.to_str() {
+        Some(path) => PathBuf::from(normalize_str(path, PathStyle::native())),
+        None => path.to_path_buf(),
+    }
+}
+
+/// Form of `path` that file system calls accept regardless of its length
+///
+/// A no-op except on Windows, see [`long_path_str`].
+pub fn long_path(path: &Path) -> PathBuf {
+    match path.to_str() {
+        Some(path) if PathStyle::native() == PathStyle::Windows => {
+            PathBuf::from(long_path_str(path, PathStyle::Windows))
+        }
+        _ => path.to_path_buf(),
+    }
+}
+
+/// Absolute, normalized form of `path`
+///
+/// Relative paths are resolved against the current directory without touching
+/// the file system, so `path` does not need to exist yet. No `\\?\` prefix is
+/// added, as the result may be handed to other programs; apply [`long_path`]
+/// where the path is opened.
+///
+/// # Errors
+///
+/// Returns an error if the current directory cannot be determined
+pub fn resolve(path: &Path) -> Result<PathBuf> {
+    let absolute = std::path::absolute(path)
+        .with_context(|| format!("Failed to resolve path: {}", path.display()))?;
+    Ok(normalize(&absolute))
+}
+
+/// `path` with `suffix` appended to its file name, e.g. `proof.json.partial`
+///
+/// Unlike [`Path::with_extension`], files that only differ in their extension
+/// get distinct sibling paths.
+pub fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(suffix);
+    PathBuf::from(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_windows_separators() {
+        let style = PathStyle::Windows;
+        assert_eq!(normalize_str("C:/artifacts/pico/vm_pk", style), r"C:\artifacts\pico\vm_pk");
+        assert_eq!(normalize_str(r"artifacts\\pico/./vm_pk", style), r"artifacts\pico\vm_pk");
+        assert_eq!(normalize_str(r"C:\artifacts\pico\..\vm_pk", style), r"C:\artifacts\vm_pk");
+        assert_eq!(normalize_str(r"C:\..\vm_pk", style), r"C:\vm_pk");
+        assert_eq!(normalize_str(r"..\..\vm_pk", style), r"..\..\vm_pk");
+        assert_eq!(normalize_str("C:artifacts", style), r"C:artifacts");
+        assert_eq!(normalize_str(r"\\server\share\..\proof.json", style), r"\\server\share\proof.json");
+        assert_eq!(normalize_str("//server/share/a/./b", style), r"\\server\share\a\b");
+        // Verbatim paths are taken literally
+        assert_eq!(normalize_str(r"\\?\C:\a\..\b", style), r"\\?\C:\a\..\b");
+    }
+
+    #[test]
+    fn test_normalize_unix() {
+        let style = PathStyle::Unix;
+        assert_eq!(normalize_str("/artifacts//pico/./../vm_pk", style), "/artifacts/vm_pk");
+        assert_eq!(normalize_str("./", style), ".");
+        assert_eq!(normalize_str("../a/../../b", style), "../../b");
+        // A backslash is part of the file name
+        assert_eq!(normalize_str(r"a\b/c", style), r"a\b/c");
+    }
+
+    #[test]
+    fn test_long_path() {
+        let long_name = "a".repeat(MAX_PATH);
+        let style = PathStyle::Windows;
+
+        let drive = format!("C:/out/{}/proof.json", long_name);
+        assert_eq!(
+            long_path_str(&drive, style),
+            format!(r"\\?\C:\out\{}\proof.json", long_name)
+        );
+        let unc = format!(r"\\server\share\{}", long_name);
+        assert_eq!(long_path_str(&unc, style), format!(r"\\?\UNC\server\share\{}", long_name));
+        // Already verbatim, relative or short: unchanged apart from normalization
+        let verbatim = format!(r"\\?\C:\{}", long_name);
+        assert_eq!(long_path_str(&verbatim, style), verbatim);
+        assert_eq!(long_path_str(&long_name, style), long_name);
+        assert_eq!(long_path_str("C:/out/proof.json", style), r"C:\out\proof.json");
+
+        let unix = format!("/out/{}", long_name);
+        assert_eq!(long_path_str(&unix, PathStyle::Unix), unix);
+    }
+
+    #[test]
+    fn test_with_suffix() {
+        assert_eq!(with_suffix(Path::new("out/proof.json"), ".partial"), Path::new("out/proof.json.partial"));
+        assert_ne!(
+            with_suffix(Path::new("roots.json"), ".partial"),
+            with_suffix(Path::new("roots.jsonl"), ".partial")
+        );
+    }
+
+    #[test]
+    fn test_resolve_is_absolute() {
+        let resolved = resolve(Path::new("artifacts/./pico")).unwrap();
+        assert!(resolved.is_absolute());
+        assert!(resolved.ends_with(Path::new("artifacts").join("pico")));
+    }
+}
@@ -0,0 +1,323 @@
+//! Staged pipeline for proving many bundles in one run
+//!
+//! Proving a bundle goes through four stages with very different costs:
+//!
+//! 1. **Prepare**: resolve the source and build the guest input (network and CPU)
+//! 2. **Emulate**: execute the guest locally as a preflight (CPU)
+//! 3. **Prove**: generate the proof, locally or on a proving network
+//! 4. **Write**: write the proof artifact or store record (disk)
+//!
+//! Running them back to back for each bundle leaves the prover idle while the
+//! next input is prepared. [`run_pipeline`] instead runs every stage on its own
+//! threads, connected by bounded queues: while one bundle is being proven, the
+//! next ones are prepared and emulated, and the previous one is written. The
+//! queue depth bounds how far the CPU-bound stages run ahead of the prover, and
+//! thereby how many prepared inputs are held in memory.
+//!
+//! Prove runs on the calling thread, one item at a time, so that hosts can drive
+//! their async provers from it. A failing item is reported with its stage and
+//! doesn't stop the others.
+
+use anyhow::Result;
+use std::fmt;
+use std::sync::mpsc::{self, Receiver, Sender, SyncSender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Stage of the proving pipeline
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PipelineStage {
+    Prepare,
+    Emulate,
+    Prove,
+    Write,
+}
+
+impl fmt::Display for PipelineStage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PipelineStage::Prepare => write!(f, "prepare"),
+            PipelineStage::Emulate => write!(f, "emulate"),
+            PipelineStage::Prove => write!(f, "prove"),
+            PipelineStage::Write => write!(f, "write"),
+        }
+    }
+}
+
+/// Sizing of the pipeline
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PipelineOptions {
+    /// Threads each for the prepare and emulate stages
+    pub workers: usize,
+    /// Capacity of the queue in front of each stage
+    pub queue_depth: usize,
+}
+
+impl Default for PipelineOptions {
+    fn default() -> Self {
+        Self { workers: 4, queue_depth: 2 }
+    }
+}
+
+/// Failure of one item, with the stage it failed in
+#[derive(Debug)]
+pub struct StageError {
+    pub stage: PipelineStage,
+    pub error: anyhow::Error,
+}
+
+impl fmt::Display for StageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} failed: {:#}", self.stage, self.error)
+    }
+}
+
+/// Outcome of a pipeline run
+#[derive(Debug)]
+pub struct PipelineReport<T> {
+    /// Result of each item, in input order
+    pub outcomes: Vec<Result<T, StageError>>,
+    /// Wall-clock time of the run
+    pub elapsed: Duration,
+}
+
+impl<T> PipelineReport<T> {
+    /// Number of items that made it through every stage
+    pub fn succeeded(&self) -> usize {
+        self.outcomes.iter().filter(|outcome| outcome.is_ok()).count()
+    }
+
+    /// Items that failed, with their index in the input
+    pub fn failures(&self) -> impl Iterator<Item = (usize, &StageError)> {
+        self.outcomes
+            .iter()
+            .enumerate()
+            .filter_map(|(index, outcome)| outcome.as_ref().err().map(|e| (index, e)))
+    }
+}
+
+type Outcomes<T> = Sender<(usize, Result<T, StageError>)>;
+
+/// Run `workers` threads taking items from `input`, applying `stage` and
+/// passing successes on to `output` and failures to `outcomes`
+fn spawn_stage<'scope, A, B, T, F>(
+    scope: &'scope thread::Scope<'scope, '_>,
+    workers: usize,
+    name: PipelineStage,
+    input: Receiver<(usize, A)>,
+    output: SyncSender<(usize, B)>,
+    outcomes: &Outcomes<T>,
+    stage: &'scope F,
+) where
+    A: Send + 'scope,
+    B: Send + 'scope,
+    T: Send + 'scope,
+    F: Fn(A) -> Result<B> + Sync,
+{
+    let input = Arc::new(Mutex::new(input));
+    for _ in 0..workers.max(1) {
+        let input = Arc::clone(&input);
+        let output = output.clone();
+        let outcomes = outcomes.clone();
+        scope.spawn(move || loop {
+            // Hold the lock only while taking the next item
+            let next = input.lock().unwrap().recv();
+            let Ok((index, item)) = next else { break };
+            match stage(item) {
+                Ok(result) => {
+                    if output.send((index, result)).is_err() {
+                        break;
+                    }
+                }
+                Err(error) => {
+                    let _ = outcomes.send((index, Err(StageError { stage: name, error })));
+                }
+            }
+        });
+    }
+}
+
+/// Run `items` through the prepare, emulate, prove and write stages
+///
+/// `prepare` and `emulate` run on [`PipelineOptions::workers`] threads each,
+/// `write` on a thread of its own, and `prove` on the calling thread. Items
+/// overtake each other in the parallel stages, so they reach `prove` and
+/// `write` in no particular order; the report is in input order.
+///
+/// # Example
+///
+/// ```ignore
+/// let report = run_pipeline(
+///     &sources,
+///     &PipelineOptions::default(),
+///     |source| prepare_input(source),
+///     |input| preflight_guest_input(&prover, &input).map(|_| input),
+///     |input| runtime.block_on(prover.prove(&config, &input)).map_err(Into::into),
+///     |(journal, seal)| write_artifact(journal, seal),
+/// );
+/// ```
+pub fn run_pipeline<'a, I, P, E, R, T>(
+    items: &'a [I],
+    options: &PipelineOptions,
+    prepare: impl Fn(&'a I) -> Result<P> + Sync,
+    emulate: impl Fn(P) -> Result<E> + Sync,
+    mut prove: impl FnMut(E) -> Result<R>,
+    write: impl Fn(R) -> Result<T> + Sync,
+) -> PipelineReport<T>
+where
+    I: Sync,
+    P: Send,
+    E: Send,
+    R: Send,
+    T: Send,
+{
+    let start = Instant::now();
+    let depth = options.queue_depth.max(1);
+    let (outcomes_tx, outcomes_rx) = mpsc::channel();
+
+    thread::scope(|scope| {
+        let (items_tx, items_rx) = mpsc::sync_channel(depth);
+        let (prepared_tx, prepared_rx) = mpsc::sync_channel(depth);
+        let (emulated_tx, emulated_rx) = mpsc::sync_channel(depth);
+        let (proven_tx, proven_rx) = mpsc::sync_channel::<(usize, R)>(depth);
+
+        scope.spawn(move || {
+            for item in items.iter().enumerate() {
+                if items_tx.send(item).is_err() {
+                    break;
+                }
+            }
+        });
+        spawn_stage(
+            scope,
+            options.workers,
+            PipelineStage::Prepare,
+            items_rx,
+            prepared_tx,
+            &outcomes_tx,
+            &prepare,
+        );
+        spawn_stage(
+            scope,
+            options.workers,
+            PipelineStage::Emulate,
+            prepared_rx,
+            emulated_tx,
+            &outcomes_tx,
+            &emulate,
+        );
+
+        let writer_outcomes = outcomes_tx.clone();
+        let write = &write;
+        scope.spawn(move || {
+            for (index, proven) in proven_rx {
+                let outcome = write(proven).map_err(|error| StageError {
+                    stage: PipelineStage::Write,
+                    error,
+                });
+                let _ = writer_outcomes.send((index, outcome));
+            }
+        });
+
+        for (index, emulated) in emulated_rx {
+            match prove(emulated) {
+                Ok(proven) => {
+                    let _ = proven_tx.send((index, proven));
+                }
+                Err(error) => {
+                    let failure = StageError { stage: PipelineStage::Prove, error };
+                    let _ = outcomes_tx.send((index, Err(failure)));
+                }
+            }
+        }
+        // Let the writer finish once the last proof is handed over
+        drop(proven_tx);
+    });
+    drop(outcomes_tx);
+
+    let mut outcomes: Vec<_> = outcomes_rx.into_iter().collect();
+    outcomes.sort_by_key(|(index, _)| *index);
+    PipelineReport {
+        outcomes: outcomes.into_iter().map(|(_, outcome)| outcome).collect(),
+        elapsed: start.elapsed(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::bail;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[test]
+    fn test_pipeline_reports_in_input_order() {
+        let items: Vec<u32> = (0..50).collect();
+        let report = run_pipeline(
+            &items,
+            &PipelineOptions::default(),
+            |item| Ok(*item * 2),
+            |item| Ok(item + 1),
+            |item| Ok(item * 10),
+            |item| Ok(item.to_string()),
+        );
+
+        assert_eq!(report.succeeded(), 50);
+        for (item, outcome) in items.iter().zip(&report.outcomes) {
+            assert_eq!(outcome.as_ref().unwrap(), &((item * 2 + 1) * 10).to_string());
+        }
+    }
+
+    #[test]
+    fn test_pipeline_failures_name_their_stage() {
+        let items: Vec<u32> = (0..8).collect();
+        let report = run_pipeline(
+            &items,
+            &PipelineOptions { workers: 2, queue_depth: 1 },
+            |item| if *item == 1 { bail!("no bundle") } else { Ok(*item) },
+            |item| if item == 3 { bail!("guest panicked") } else { Ok(item) },
+            |item| if item == 5 { bail!("request expired") } else { Ok(item) },
+            |item| if item == 7 { bail!("disk full") } else { Ok(item) },
+        );
+
+        let failures: Vec<_> = report.failures().map(|(index, e)| (index, e.stage)).collect();
+        assert_eq!(
+            failures,
+            [
+                (1, PipelineStage::Prepare),
+                (3, PipelineStage::Emulate),
+                (5, PipelineStage::Prove),
+                (7, PipelineStage::Write),
+            ]
+        );
+        assert_eq!(report.succeeded(), 4);
+        assert_eq!(report.outcomes[3].as_ref().unwrap_err().to_string(), "emulate failed: guest panicked");
+    }
+
+    #[test]
+    fn test_queues_bound_how_far_prep_runs_ahead() {
+        let items: Vec<u32> = (0..20).collect();
+        let ahead = AtomicUsize::new(0);
+        let max_ahead = AtomicUsize::new(0);
+        let report = run_pipeline(
+            &items,
+            &PipelineOptions { workers: 1, queue_depth: 1 },
+            |item| {
+                let now = ahead.fetch_add(1, Ordering::SeqCst) + 1;
+                max_ahead.fetch_max(now, Ordering::SeqCst);
+                Ok(*item)
+            },
+            Ok,
+            |item| {
+                ahead.fetch_sub(1, Ordering::SeqCst);
+                thread::sleep(Duration::from_millis(2));
+                Ok(item)
+            },
+            Ok,
+        );
+
+        assert_eq!(report.succeeded(), 20);
+        // One item in each worker and queue up to prove, and the one entering it
+        assert!(max_ahead.load(Ordering::SeqCst) <= 5);
+    }
+}
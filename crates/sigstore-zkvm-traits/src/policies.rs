@@ -0,0 +1,157 @@
+//! Repository policies file of a proving service
+//!
+//! A single proving service can enforce different rules per organisation: a
+//! YAML file maps repository patterns to a [`RepositoryPolicy`] each, and the
+//! entry governing a bundle is picked by the repository its certificate records:
+//!
+//! ```yaml
+//! policies:
+//!   - repository: https://github.com/acme/*
+//!     issuer: https://token.actions.githubusercontent.com
+//!     workflows:
+//!       - https://github.com/acme/*/.github/workflows/release.yml@refs/tags/*
+//!     timestamp: Rfc3161
+//!     max_age: 86400
+//!   - repository: https://github.com/*
+//!     max_age: 3600
+//! ```
+//!
+//! Entries are tried in order and the first whose pattern matches wins, so
+//! specific patterns go before catch-alls. The picked entry becomes the input's
+//! `repository_policy`: the guest checks it against the verified certificate and
+//! commits its hash in the options hash, so the journal names the entry that
+//! governed verification.
+
+use crate::types::ProverInput;
+use anyhow::{bail, Context, Result};
+use serde::Deserialize;
+use sigstore_verifier::parser::bundle::{decode_base64, parse_bundle_from_bytes};
+use sigstore_verifier::parser::certificate::parse_der_certificate;
+use sigstore_verifier::parser::identity::extract_oidc_identity;
+use sigstore_verifier::verifier::repository_policy::RepositoryPolicy;
+use std::fs;
+use std::path::Path;
+
+/// Repository policies, in the order they are tried
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RepositoryPolicies {
+    pub policies: Vec<RepositoryPolicy>,
+}
+
+impl RepositoryPolicies {
+    /// Load a policies file
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or is not a valid policies file
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read repository policies from: {}", path.display()))?;
+        Self::from_yaml(&content).with_context(|| format!("Invalid repository policies: {}", path.display()))
+    }
+
+    /// Parse policies from YAML
+    ///
+    /// # Errors
+    ///
+    /// Returns an error for malformed YAML, unknown fields, or a file without policies
+    pub fn from_yaml(content: &str) -> Result<Self> {
+        let policies: Self = serde_yaml::from_str(content)?;
+        if policies.policies.is_empty() {
+            bail!("No policies defined");
+        }
+        Ok(policies)
+    }
+
+    /// The first policy governing `repository`
+    pub fn select(&self, repository: &str) -> Option<&RepositoryPolicy> {
+        self.policies.iter().find(|policy| policy.governs(repository))
+    }
+
+    /// Pick the policy of the input's bundle and set it as the input's `repository_policy`
+    ///
+    /// A policy bounding the signature age is measured at the options'
+    /// verification time, which is set to `now` if the options fix none.
+    ///
+    /// # Returns
+    ///
+    /// The policy picked
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the bundle's certificate records no repository, or no
+    /// policy governs it
+    pub fn apply(&self, input: &mut ProverInput, now: u64) -> Result<&RepositoryPolicy> {
+        let repository = bundle_repository(&input.bundle_json)?;
+        let policy = self
+            .select(&repository)
+            .with_context(|| format!("No repository policy governs '{}'", repository))?;
+
+        let options = &mut input.verification_options;
+        options.repository_policy = Some(policy.clone());
+        if policy.max_age.is_some() && options.verification_time.is_none() {
+            options.verification_time = Some(now);
+        }
+        Ok(policy)
+    }
+}
+
+/// Source repository recorded in the leaf certificate of a bundle
+///
+/// The certificate is not verified here; the guest checks the policy against the
+/// verified one.
+fn bundle_repository(bundle_json: &[u8]) -> Result<String> {
+    let bundle = parse_bundle_from_bytes(bundle_json).context("Failed to parse Sigstore bundle")?;
+    let leaf = decode_base64(&bundle.verification_material.certificate.raw_bytes)
+        .context("Failed to decode the bundle certificate")?;
+    let certificate = parse_der_certificate(&leaf).context("Failed to parse the bundle certificate")?;
+    extract_oidc_identity(&certificate)
+        .ok()
+        .and_then(|identity| identity.repository)
+        .context("Bundle certificate records no source repository to pick a policy by")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sigstore_verifier::types::result::TimestampKind;
+
+    const POLICIES: &str = r#"
+policies:
+  - repository: https://github.com/acme/*
+    issuer: https://token.actions.githubusercontent.com
+    workflows:
+      - https://github.com/acme/*/.github/workflows/release.yml@refs/tags/*
+    timestamp: Rfc3161
+    max_age: 86400
+  - repository: https://github.com/*
+    max_age: 3600
+"#;
+
+    #[test]
+    fn test_select_first_matching_policy() {
+        let policies = RepositoryPolicies::from_yaml(POLICIES).unwrap();
+        assert_eq!(policies.policies.len(), 2);
+
+        let acme = policies.select("https://github.com/acme/app").unwrap();
+        assert_eq!(acme.timestamp, Some(TimestampKind::Rfc3161));
+        assert_eq!(acme.workflows.len(), 1);
+        let other = policies.select("https://github.com/contoso/app").unwrap();
+        assert_eq!(other.max_age, Some(3600));
+        assert!(policies.select("https://gitlab.com/acme/app").is_none());
+        assert_ne!(acme.hash(), other.hash());
+    }
+
+    #[test]
+    fn test_invalid_policies_rejected() {
+        for content in [
+            "policies: []",
+            "policies:\n  - repository: https://github.com/acme/*\n    max_ages: 60",
+            "policies:\n  - issuer: https://gitlab.com",
+            "repository: https://github.com/acme/*",
+        ] {
+            assert!(RepositoryPolicies::from_yaml(content).is_err(), "{}", content);
+        }
+    }
+}
@@ -0,0 +1,243 @@
+//! Prover inputs prepared on one machine and proven on another
+//!
+//! The machine that owns the verification policy resolves the bundle, options
+//! and trusted root into a [`ProverInput`] (`prepare`), and a proving farm that
+//! is not trusted with the policy proves it (`prove-from-input`). The input
+//! travels in a DSSE envelope together with its digest, the SHA256 of the
+//! encoded input, which the guest commits as the journal's input digest:
+//!
+//! ```text
+//! {"payloadType":"application/vnd.sigstore-verifier.prover-input+bincode",
+//!  "payload":"<base64 encoded ProverInput>",
+//!  "digest":"<hex SHA256 of the payload>",
+//!  "signatures":[{"keyid":"<optional hint>","sig":"<base64>"}]}
+//! ```
+//!
+//! Signed by the policy owner, the envelope can't be altered by the farm: with
+//! input keys configured, an input whose signature doesn't verify is refused.
+//! Either way, a proof whose journal commits another input digest than the one
+//! prepared was generated for other options or trust roots.
+
+use crate::types::ProverInput;
+use crate::utils::{create_parent_dir, write_atomic};
+use crate::workflow::load_public_keys;
+use anyhow::{bail, Context, Result};
+use base64::prelude::*;
+use serde::{Deserialize, Serialize};
+use sigstore_verifier::crypto::hash::sha256;
+use sigstore_verifier::fetcher::jsonl::governance::{TrustRootKeys, TrustRootSignature};
+use sigstore_verifier::verifier::signature::pae;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// DSSE payload type of a prepared prover input
+pub const PROVER_INPUT_PAYLOAD_TYPE: &str = "application/vnd.sigstore-verifier.prover-input+bincode";
+
+/// DSSE envelope around an encoded [`ProverInput`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreparedInput {
+    pub payload_type: String,
+    pub payload: String, // Base64-encoded ProverInput
+    pub digest: String,  // Hex SHA256 of the payload
+    #[serde(default)]
+    pub signatures: Vec<TrustRootSignature>,
+}
+
+impl PreparedInput {
+    /// Wrap `input`, unsigned
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the input cannot be encoded
+    pub fn new(input: &ProverInput) -> Result<Self> {
+        let payload = input.encode_input().map_err(anyhow::Error::msg)?;
+        Ok(Self {
+            payload_type: PROVER_INPUT_PAYLOAD_TYPE.to_string(),
+            digest: hex::encode(sha256(&payload)),
+            payload: BASE64_STANDARD.encode(payload),
+            signatures: Vec::new(),
+        })
+    }
+
+    /// Add a signature over the DSSE pre-authentication encoding of the payload
+    ///
+    /// # Arguments
+    ///
+    /// * `key_pem` - PKCS#8 P-256 or Ed25519 private key
+    /// * `keyid` - Optional hint naming the key
+    pub fn sign(&mut self, key_pem: &str, keyid: String) -> Result<()> {
+        let payload = BASE64_STANDARD.decode(&self.payload).context("Invalid prepared input payload")?;
+        let signature = sign_message(key_pem, &pae(&self.payload_type, &payload))?;
+        self.signatures.push(TrustRootSignature {
+            keyid,
+            sig: BASE64_STANDARD.encode(signature),
+        });
+        Ok(())
+    }
+
+    /// Verify the envelope and return the input it carries
+    ///
+    /// The payload must hash to the envelope's digest and be the canonical
+    /// encoding of the input, so that the guest commits that digest.
+    ///
+    /// # Arguments
+    ///
+    /// * `keys` - Policy owner keys, one valid signature by any of them suffices;
+    ///   when empty, signatures are not checked
+    ///
+    /// # Errors
+    ///
+    /// Returns an error for a malformed envelope, a payload that doesn't match
+    /// the digest, or, with keys, no signature that verifies
+    pub fn open(&self, keys: &TrustRootKeys) -> Result<ProverInput> {
+        if self.payload_type != PROVER_INPUT_PAYLOAD_TYPE {
+            bail!(
+                "Prepared input has payload type '{}', expected '{}'",
+                self.payload_type,
+                PROVER_INPUT_PAYLOAD_TYPE
+            );
+        }
+
+        let payload = BASE64_STANDARD.decode(&self.payload).context("Invalid prepared input payload")?;
+        let digest = self.digest()?;
+        if sha256(&payload) != digest {
+            bail!(
+                "Prepared input payload has digest {}, expected {}",
+                hex::encode(sha256(&payload)),
+                self.digest
+            );
+        }
+        if !keys.is_empty() && !keys.verify_any(&pae(&self.payload_type, &payload), &self.signatures) {
+            bail!("Prepared input is not signed by any of the {} input key(s)", keys.len());
+        }
+
+        let input = ProverInput::parse_input(&payload).map_err(anyhow::Error::msg)?;
+        if input.digest().map_err(anyhow::Error::msg)? != digest {
+            bail!("Prepared input payload is not a canonical ProverInput encoding");
+        }
+        Ok(input)
+    }
+
+    /// The digest the guest commits for this input
+    pub fn digest(&self) -> Result<[u8; 32]> {
+        hex::decode(&self.digest)
+            .ok()
+            .and_then(|digest| digest.try_into().ok())
+            .with_context(|| format!("Invalid prepared input digest '{}'", self.digest))
+    }
+}
+
+/// Write a prepared input as JSON
+pub fn write_prepared_input(path: &Path, prepared: &PreparedInput) -> Result<()> {
+    create_parent_dir(path)?;
+    let content = serde_json::to_vec_pretty(prepared).context("Failed to serialize prepared input")?;
+    write_atomic(path, &content)
+        .with_context(|| format!("Failed to write prepared input to: {}", path.display()))
+}
+
+/// Read a prepared input written by [`write_prepared_input`]
+pub fn read_prepared_input(path: &Path) -> Result<PreparedInput> {
+    let content = fs::read(path)
+        .with_context(|| format!("Failed to read prepared input from: {}", path.display()))?;
+    serde_json::from_slice(&content).with_context(|| format!("Invalid prepared input: {}", path.display()))
+}
+
+/// Load the public keys prepared inputs must be signed with
+///
+/// # Arguments
+///
+/// * `paths` - PEM files with one or more `PUBLIC KEY` blocks each
+///
+/// # Returns
+///
+/// The keys of all files; empty (unsigned inputs accepted) when `paths` is empty
+pub fn load_input_keys(paths: &[PathBuf]) -> Result<TrustRootKeys> {
+    load_public_keys(paths, "input key")
+}
+
+/// Sign `message` with a PKCS#8 P-256 (DER signature) or Ed25519 private key
+fn sign_message(key_pem: &str, message: &[u8]) -> Result<Vec<u8>> {
+    use p256::ecdsa::signature::Signer;
+    use p256::pkcs8::DecodePrivateKey;
+
+    if let Ok(key) = p256::ecdsa::SigningKey::from_pkcs8_pem(key_pem) {
+        let signature: p256::ecdsa::Signature = key.sign(message);
+        return Ok(signature.to_der().as_bytes().to_vec());
+    }
+    if let Ok(key) = ed25519_dalek::SigningKey::from_pkcs8_pem(key_pem) {
+        return Ok(key.sign(message).to_bytes().to_vec());
+    }
+    bail!("Unsupported private key: expected a PKCS#8 P-256 or Ed25519 key")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use p256::pkcs8::{EncodePrivateKey, LineEnding};
+    use sigstore_verifier::crypto::signature::PublicKey;
+    use sigstore_verifier::types::certificate::CertificateChain;
+    use sigstore_verifier::types::result::VerificationOptions;
+
+    fn sample_input() -> ProverInput {
+        ProverInput::new(
+            b"{}".to_vec(),
+            VerificationOptions::default(),
+            CertificateChain {
+                leaf: vec![1],
+                intermediates: vec![],
+                root: vec![2],
+            },
+            None,
+        )
+    }
+
+    fn signing_key(seed: u8) -> (String, TrustRootKeys) {
+        let key = p256::ecdsa::SigningKey::from_slice(&[seed; 32]).unwrap();
+        let pem = key.to_pkcs8_pem(LineEnding::LF).unwrap().to_string();
+        let mut keys = TrustRootKeys::default();
+        keys.push(PublicKey::P256(*key.verifying_key()));
+        (pem, keys)
+    }
+
+    #[test]
+    fn test_signed_input_roundtrip() {
+        let input = sample_input();
+        let (pem, keys) = signing_key(3);
+        let mut prepared = PreparedInput::new(&input).unwrap();
+        prepared.sign(&pem, "policy-owner".to_string()).unwrap();
+        assert_eq!(prepared.digest().unwrap(), input.digest().unwrap());
+
+        let opened = prepared.open(&keys).unwrap();
+        assert_eq!(opened.encode_input(), input.encode_input());
+        assert!(prepared.open(&TrustRootKeys::default()).is_ok());
+    }
+
+    #[test]
+    fn test_altered_input_rejected() {
+        let (pem, keys) = signing_key(3);
+        let mut prepared = PreparedInput::new(&sample_input()).unwrap();
+        prepared.sign(&pem, String::new()).unwrap();
+
+        // Other options, with or without a matching digest
+        let mut altered = sample_input();
+        altered.verification_options.archival = true;
+        let other = PreparedInput::new(&altered).unwrap();
+        let swapped = PreparedInput {
+            payload: other.payload.clone(),
+            ..prepared.clone()
+        };
+        assert!(swapped.open(&TrustRootKeys::default()).is_err());
+        let resigned = PreparedInput {
+            signatures: prepared.signatures.clone(),
+            ..other
+        };
+        assert!(resigned.open(&keys).is_err());
+        assert!(resigned.open(&TrustRootKeys::default()).is_ok());
+
+        // Unsigned, or signed with another key
+        assert!(PreparedInput::new(&sample_input()).unwrap().open(&keys).is_err());
+        let (_, other_keys) = signing_key(4);
+        assert!(prepared.open(&other_keys).is_err());
+    }
+}
@@ -0,0 +1,411 @@
+//! Artifact resolvers for locating attestation bundles
+//!
+//! A bundle can be given directly as an [`InputSource`](crate::source::InputSource),
+//! or discovered from the artifact it attests: a package URL, the digest of a
+//! local file, or both. Where the bundle is published depends on the ecosystem,
+//! so discovery goes through the [`ArtifactResolver`] trait, and a
+//! [`ResolverRegistry`] holds the resolvers consulted, in order.
+//!
+//! Built-in resolvers (require the `fetcher` feature):
+//!
+//! - [`CratesIoResolver`] - `pkg:cargo/<name>@<version>`; attestations stored on
+//!   GitHub for the repository the crate declares
+//! - [`MavenCentralResolver`] - `pkg:maven/<group>/<artifact>@<version>`; the
+//!   bundle published next to the artifact on Maven Central
+//! - [`GitHubResolver`] - `pkg:github/<owner>/<repo>` with an artifact digest;
+//!   the GitHub attestations API
+//! - [`OciResolver`] - `pkg:oci/<name>@sha256:<hex>?repository_url=<registry>/<repository>`;
+//!   the registry's referrers API
+//!
+//! Other ecosystems are supported by implementing [`ArtifactResolver`] outside
+//! this crate and registering the resolver with [`ResolverRegistry::register`].
+
+use anyhow::{bail, Context, Result};
+use sigstore_verifier::crypto::hash::digest_reader;
+use sigstore_verifier::types::purl::MavenPurl;
+use sigstore_verifier::types::result::DigestAlgorithm;
+use std::fmt;
+use std::path::Path;
+
+/// Artifact whose attestation bundle is to be located
+///
+/// - purl: Package URL of the artifact (e.g. `pkg:cargo/serde@1.0.0`)
+/// - digest: SHA256 of the artifact, if known (e.g. hashed from a local copy)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ArtifactRef {
+    pub purl: Option<String>,
+    pub digest: Option<[u8; 32]>,
+}
+
+impl ArtifactRef {
+    /// Build the artifact reference given by host CLI flags
+    ///
+    /// # Arguments
+    ///
+    /// * `purl` - Package URL (`--purl`)
+    /// * `gav` - Maven coordinates (`--gav`), shorthand for a `pkg:maven/` purl
+    /// * `artifact_path` - Local copy of the artifact (`--artifact`), hashed for its digest
+    ///
+    /// # Returns
+    ///
+    /// `None` if none of the flags is given
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the coordinates are malformed or the artifact cannot be read
+    pub fn from_args(
+        purl: Option<&str>,
+        gav: Option<&str>,
+        artifact_path: Option<&Path>,
+    ) -> Result<Option<Self>> {
+        let purl = match (purl, gav) {
+            (Some(purl), _) => Some(purl.to_string()),
+            (None, Some(gav)) => {
+                let purl = MavenPurl::from_gav(gav).with_context(|| {
+                    format!("Invalid Maven coordinates '{}' (group:artifact:version)", gav)
+                })?;
+                Some(purl.to_string())
+            }
+            (None, None) => None,
+        };
+        let digest = artifact_path
+            .map(|path| -> Result<[u8; 32]> {
+                let file = std::fs::File::open(path)
+                    .with_context(|| format!("Failed to open artifact {}", path.display()))?;
+                let digest = digest_reader(file, DigestAlgorithm::Sha256)
+                    .with_context(|| format!("Failed to read artifact {}", path.display()))?;
+                Ok(digest.try_into().expect("SHA256 digests are 32 bytes"))
+            })
+            .transpose()?;
+
+        if purl.is_none() && digest.is_none() {
+            return Ok(None);
+        }
+        Ok(Some(Self { purl, digest }))
+    }
+
+    /// Package type of the purl (`cargo` for `pkg:cargo/...`)
+    pub fn purl_type(&self) -> Option<&str> {
+        self.purl.as_deref().and_then(PurlParts::parse).map(|parts| parts.ty)
+    }
+}
+
+impl fmt::Display for ArtifactRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (&self.purl, &self.digest) {
+            (Some(purl), Some(digest)) => write!(f, "{} (sha256:{})", purl, hex::encode(digest)),
+            (Some(purl), None) => write!(f, "{}", purl),
+            (None, Some(digest)) => write!(f, "sha256:{}", hex::encode(digest)),
+            (None, None) => write!(f, "<unspecified artifact>"),
+        }
+    }
+}
+
+/// Bundles located for an artifact and the subject they must attest
+///
+/// - bundles: Candidate bundles (JSON), in the order the resolver found them
+/// - digest: SHA256 the attested subject must have
+/// - subject_name: Name the attested subject must match, if the ecosystem names subjects
+#[derive(Debug, Clone, Default)]
+pub struct ResolvedArtifact {
+    pub bundles: Vec<Vec<u8>>,
+    pub digest: Option<[u8; 32]>,
+    pub subject_name: Option<String>,
+}
+
+/// Ecosystem-specific discovery of attestation bundles
+///
+/// Resolvers are blocking, like the rest of the input preparation.
+pub trait ArtifactResolver: Send + Sync {
+    /// Short name used in messages (e.g. `crates.io`)
+    fn name(&self) -> &str;
+
+    /// Whether this resolver handles the artifact, typically by purl type
+    fn supports(&self, artifact: &ArtifactRef) -> bool;
+
+    /// Locate the bundles attesting the artifact
+    ///
+    /// When the artifact digest is unknown the resolver determines it (e.g. by
+    /// downloading the published artifact), so that bundles are verified against
+    /// the artifact itself rather than whatever subject they name.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the artifact reference is incomplete for this
+    /// resolver or the lookup fails
+    fn resolve(&self, artifact: &ArtifactRef) -> Result<ResolvedArtifact>;
+}
+
+/// Ordered set of resolvers; the first one supporting an artifact is used
+#[derive(Default)]
+pub struct ResolverRegistry {
+    resolvers: Vec<Box<dyn ArtifactResolver>>,
+}
+
+impl ResolverRegistry {
+    /// Create an empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a registry with the built-in resolvers
+    #[cfg(feature = "fetcher")]
+    pub fn with_defaults() -> Self {
+        let mut registry = Self::new();
+        registry.register(Box::new(CratesIoResolver));
+        registry.register(Box::new(MavenCentralResolver));
+        registry.register(Box::new(GitHubResolver));
+        registry.register(Box::new(OciResolver));
+        registry
+    }
+
+    /// Add a resolver, consulted after those already registered
+    pub fn register(&mut self, resolver: Box<dyn ArtifactResolver>) {
+        self.resolvers.push(resolver);
+    }
+
+    /// Locate the bundles for an artifact with the first supporting resolver
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no resolver supports the artifact, the resolver
+    /// fails, or it finds no bundles
+    pub fn resolve(&self, artifact: &ArtifactRef) -> Result<ResolvedArtifact> {
+        let Some(resolver) = self.resolvers.iter().find(|r| r.supports(artifact)) else {
+            bail!(
+                "No resolver for {} (package type: {})",
+                artifact,
+                artifact.purl_type().unwrap_or("none")
+            );
+        };
+
+        let resolved = resolver
+            .resolve(artifact)
+            .with_context(|| format!("{} lookup failed for {}", resolver.name(), artifact))?;
+        if resolved.bundles.is_empty() {
+            bail!("{} has no attestation bundles for {}", resolver.name(), artifact);
+        }
+        Ok(resolved)
+    }
+}
+
+/// Components of `pkg:<type>/<path>[@<version>][?<qualifiers>][#<subpath>]`
+struct PurlParts<'a> {
+    ty: &'a str,
+    path: &'a str,
+    version: Option<&'a str>,
+    qualifiers: Option<&'a str>,
+}
+
+impl<'a> PurlParts<'a> {
+    fn parse(purl: &'a str) -> Option<Self> {
+        let rest = purl.strip_prefix("pkg:")?;
+        let rest = rest.split('#').next().unwrap_or_default();
+        let (rest, qualifiers) = match rest.split_once('?') {
+            Some((rest, qualifiers)) => (rest, Some(qualifiers)),
+            None => (rest, None),
+        };
+        let (ty, path) = rest.split_once('/')?;
+        let (path, version) = match path.rsplit_once('@') {
+            Some((path, version)) => (path, Some(version)),
+            None => (path, None),
+        };
+        if ty.is_empty() || path.is_empty() {
+            return None;
+        }
+        Some(Self { ty, path, version, qualifiers })
+    }
+
+    fn qualifier(&self, key: &str) -> Option<&'a str> {
+        self.qualifiers?
+            .split('&')
+            .filter_map(|pair| pair.split_once('='))
+            .find(|(k, _)| *k == key)
+            .map(|(_, value)| value)
+    }
+}
+
+/// Artifact digest, or the SHA256 of the published artifact downloaded by `fetch`
+#[cfg(feature = "fetcher")]
+fn digest_or_fetch(
+    artifact: &ArtifactRef,
+    fetch: impl FnOnce() -> Result<Vec<u8>, sigstore_verifier::error::VerificationError>,
+) -> Result<[u8; 32]> {
+    match artifact.digest {
+        Some(digest) => Ok(digest),
+        None => {
+            let content = fetch().context("Failed to download the published artifact")?;
+            Ok(sigstore_verifier::crypto::hash::sha256(&content))
+        }
+    }
+}
+
+/// Crates on crates.io, attested through the GitHub repository they declare
+#[cfg(feature = "fetcher")]
+pub struct CratesIoResolver;
+
+#[cfg(feature = "fetcher")]
+impl ArtifactResolver for CratesIoResolver {
+    fn name(&self) -> &str {
+        "crates.io"
+    }
+
+    fn supports(&self, artifact: &ArtifactRef) -> bool {
+        artifact.purl_type() == Some("cargo")
+    }
+
+    fn resolve(&self, artifact: &ArtifactRef) -> Result<ResolvedArtifact> {
+        use sigstore_verifier::fetcher::http::default_client;
+        use sigstore_verifier::fetcher::remote::{fetch_crate, fetch_crate_attestation_bundles};
+        use sigstore_verifier::types::purl::CargoPurl;
+
+        let purl = artifact
+            .purl
+            .as_deref()
+            .and_then(CargoPurl::parse)
+            .context("Expected pkg:cargo/<name>@<version>")?;
+        let client = default_client()?;
+        let digest = digest_or_fetch(artifact, || fetch_crate(&*client, &purl))?;
+
+        Ok(ResolvedArtifact {
+            bundles: fetch_crate_attestation_bundles(&*client, &purl, &digest)?,
+            digest: Some(digest),
+            subject_name: Some(purl.to_string()),
+        })
+    }
+}
+
+/// Maven Central artifacts, attested by the bundle published next to them
+#[cfg(feature = "fetcher")]
+pub struct MavenCentralResolver;
+
+#[cfg(feature = "fetcher")]
+impl ArtifactResolver for MavenCentralResolver {
+    fn name(&self) -> &str {
+        "Maven Central"
+    }
+
+    fn supports(&self, artifact: &ArtifactRef) -> bool {
+        artifact.purl_type() == Some("maven")
+    }
+
+    fn resolve(&self, artifact: &ArtifactRef) -> Result<ResolvedArtifact> {
+        use sigstore_verifier::fetcher::http::default_client;
+        use sigstore_verifier::fetcher::remote::{
+            fetch_maven_artifact, fetch_maven_attestation_bundle,
+        };
+
+        let purl = artifact
+            .purl
+            .as_deref()
+            .and_then(MavenPurl::parse)
+            .context("Expected pkg:maven/<group>/<artifact>@<version>")?;
+        let client = default_client()?;
+        let digest = digest_or_fetch(artifact, || fetch_maven_artifact(&*client, &purl))?;
+
+        Ok(ResolvedArtifact {
+            bundles: vec![fetch_maven_attestation_bundle(&*client, &purl)?],
+            digest: Some(digest),
+            subject_name: Some(purl.to_string()),
+        })
+    }
+}
+
+/// Artifacts attested in a GitHub repository's attestations API
+///
+/// GitHub indexes attestations by subject digest only, so the artifact digest
+/// must be known (e.g. from a local copy).
+#[cfg(feature = "fetcher")]
+pub struct GitHubResolver;
+
+#[cfg(feature = "fetcher")]
+impl ArtifactResolver for GitHubResolver {
+    fn name(&self) -> &str {
+        "GitHub"
+    }
+
+    fn supports(&self, artifact: &ArtifactRef) -> bool {
+        artifact.purl_type() == Some("github")
+    }
+
+    fn resolve(&self, artifact: &ArtifactRef) -> Result<ResolvedArtifact> {
+        use sigstore_verifier::fetcher::http::default_client;
+        use sigstore_verifier::fetcher::remote::fetch_github_attestation_bundles;
+
+        let parts = artifact
+            .purl
+            .as_deref()
+            .and_then(PurlParts::parse)
+            .context("Expected pkg:github/<owner>/<repo>")?;
+        let Some((owner, repo)) = parts.path.split_once('/') else {
+            bail!("Expected pkg:github/<owner>/<repo>");
+        };
+        let Some(digest) = artifact.digest else {
+            bail!("GitHub attestations are looked up by digest; give the artifact file as well");
+        };
+
+        Ok(ResolvedArtifact {
+            bundles: fetch_github_attestation_bundles(
+                &*default_client()?,
+                owner,
+                repo,
+                &format!("sha256:{}", hex::encode(digest)),
+            )?,
+            digest: Some(digest),
+            subject_name: None,
+        })
+    }
+}
+
+/// OCI artifacts, attested by a bundle attached through the referrers API
+///
+/// The purl version is the manifest digest and the `repository_url` qualifier
+/// names the registry and repository.
+#[cfg(feature = "fetcher")]
+pub struct OciResolver;
+
+#[cfg(feature = "fetcher")]
+impl ArtifactResolver for OciResolver {
+    fn name(&self) -> &str {
+        "OCI registry"
+    }
+
+    fn supports(&self, artifact: &ArtifactRef) -> bool {
+        artifact.purl_type() == Some("oci")
+    }
+
+    fn resolve(&self, artifact: &ArtifactRef) -> Result<ResolvedArtifact> {
+        use sigstore_verifier::fetcher::http::default_client;
+        use sigstore_verifier::fetcher::remote::fetch_oci_referrer_bundle;
+
+        let parts = artifact
+            .purl
+            .as_deref()
+            .and_then(PurlParts::parse)
+            .context("Expected pkg:oci/<name>@sha256:<hex>?repository_url=<registry>/<repository>")?;
+        let version = parts.version.context("pkg:oci purl must be pinned with @sha256:<hex>")?;
+        let digest = crate::source::parse_sha256_pin(&version.replace("%3A", ":").replace("%3a", ":"))?;
+        if artifact.digest.is_some_and(|expected| expected != digest) {
+            bail!("Artifact digest does not match the digest in {}", parts.path);
+        }
+        let repository_url = parts
+            .qualifier("repository_url")
+            .context("pkg:oci purl needs a repository_url qualifier")?
+            .replace("%2F", "/")
+            .replace("%2f", "/");
+        let Some((registry, repository)) = repository_url.split_once('/') else {
+            bail!("repository_url must be <registry>/<repository>: {}", repository_url);
+        };
+
+        Ok(ResolvedArtifact {
+            bundles: vec![fetch_oci_referrer_bundle(
+                &*default_client()?,
+                registry,
+                repository,
+                &format!("sha256:{}", hex::encode(digest)),
+            )?],
+            digest: Some(digest),
+            subject_name: None,
+        })
+    }
+}
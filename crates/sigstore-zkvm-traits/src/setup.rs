@@ -0,0 +1,165 @@
+//! Exclusive access to setup artifacts shared between prove jobs
+//!
+//! Pico writes the program's proving and verifying keys (`vm_pk`, `vm_vk`)
+//! into its artifacts directory on the first proof, and its EVM proof
+//! (`proof.data`) there on every proof; the SP1 SDK shares its circuit
+//! artifacts between processes under `~/.sp1`. Jobs running at once, in
+//! parallel or server mode or as separate processes, take the directory's
+//! [`SetupLock`] around that work, so one performs the setup while the others
+//! wait for it and then find its result in place.
+//!
+//! The lock is an OS file lock on `<dir>/.setup.lock`, released when its
+//! holder drops it or exits, even by crashing. A setup that was interrupted is
+//! recognized by its `<dir>/.setup-incomplete` marker and performed again.
+
+use crate::paths::long_path;
+use anyhow::{Context, Result};
+use std::fs::{self, File, OpenOptions, TryLockError};
+use std::path::{Path, PathBuf};
+
+/// Name of the lock file in a setup directory
+pub const LOCK_FILE: &str = ".setup.lock";
+
+/// Name of the marker of a setup in progress
+pub const INCOMPLETE_MARKER: &str = ".setup-incomplete";
+
+/// Exclusive lock on a setup directory, released on drop
+#[derive(Debug)]
+pub struct SetupLock {
+    file: File,
+    dir: PathBuf,
+}
+
+impl SetupLock {
+    /// Lock `dir`, creating it if needed, and wait while another process holds it
+    ///
+    /// Waiting is announced on stdout, so a job doesn't look hung while another
+    /// performs a setup that may take minutes.
+    pub fn acquire(dir: &Path) -> Result<Self> {
+        let file = Self::open(dir)?;
+        match file.try_lock() {
+            Ok(()) => {}
+            Err(TryLockError::WouldBlock) => {
+                println!(
+                    "Another process is performing setup in {}; waiting for it to finish...",
+                    dir.display()
+                );
+                file.lock()
+                    .with_context(|| format!("Failed to lock setup directory {}", dir.display()))?;
+            }
+            Err(TryLockError::Error(e)) => {
+                return Err(e).with_context(|| format!("Failed to lock setup directory {}", dir.display()))
+            }
+        }
+        Ok(Self {
+            file,
+            dir: dir.to_path_buf(),
+        })
+    }
+
+    /// Lock `dir` if no other process holds it
+    pub fn try_acquire(dir: &Path) -> Result<Option<Self>> {
+        let file = Self::open(dir)?;
+        match file.try_lock() {
+            Ok(()) => Ok(Some(Self {
+                file,
+                dir: dir.to_path_buf(),
+            })),
+            Err(TryLockError::WouldBlock) => Ok(None),
+            Err(TryLockError::Error(e)) => {
+                Err(e).with_context(|| format!("Failed to lock setup directory {}", dir.display()))
+            }
+        }
+    }
+
+    fn open(dir: &Path) -> Result<File> {
+        fs::create_dir_all(long_path(dir))
+            .with_context(|| format!("Failed to create setup directory {}", dir.display()))?;
+        let path = dir.join(LOCK_FILE);
+        OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(long_path(&path))
+            .with_context(|| format!("Failed to open setup lock {}", path.display()))
+    }
+
+    /// Whether a setup begun in the directory was never finished
+    pub fn interrupted(&self) -> bool {
+        long_path(&self.dir.join(INCOMPLETE_MARKER)).exists()
+    }
+
+    /// Mark a setup as in progress until [`Self::finish_setup`]
+    pub fn begin_setup(&self) -> Result<()> {
+        let marker = self.dir.join(INCOMPLETE_MARKER);
+        fs::write(long_path(&marker), b"")
+            .with_context(|| format!("Failed to write {}", marker.display()))
+    }
+
+    /// Mark the setup begun with [`Self::begin_setup`] as complete
+    pub fn finish_setup(&self) -> Result<()> {
+        let marker = self.dir.join(INCOMPLETE_MARKER);
+        match fs::remove_file(long_path(&marker)) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                Err(e).with_context(|| format!("Failed to remove {}", marker.display()))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+impl Drop for SetupLock {
+    fn drop(&mut self) {
+        let _ = self.file.unlock();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("sigstore-setup-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn test_lock_is_exclusive_until_dropped() {
+        let dir = temp_dir("exclusive");
+        let lock = SetupLock::acquire(&dir).unwrap();
+        assert!(dir.join(LOCK_FILE).exists());
+        assert!(SetupLock::try_acquire(&dir).unwrap().is_none());
+
+        // A waiting job proceeds once the holder is done
+        let waiter = {
+            let dir = dir.clone();
+            std::thread::spawn(move || SetupLock::acquire(&dir).map(|_| ()))
+        };
+        std::thread::sleep(std::time::Duration::from_millis(50));
+        assert!(!waiter.is_finished());
+        drop(lock);
+        waiter.join().unwrap().unwrap();
+
+        assert!(SetupLock::try_acquire(&dir).unwrap().is_some());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_interrupted_setup_is_detected() {
+        let dir = temp_dir("interrupted");
+        {
+            let lock = SetupLock::acquire(&dir).unwrap();
+            assert!(!lock.interrupted());
+            lock.begin_setup().unwrap();
+        }
+
+        let lock = SetupLock::acquire(&dir).unwrap();
+        assert!(lock.interrupted());
+        lock.finish_setup().unwrap();
+        assert!(!lock.interrupted());
+        lock.finish_setup().unwrap();
+        drop(lock);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,249 @@
+//! Segment and shard sizing for local proving
+//!
+//! Local provers split the guest's execution into segments (RISC0) or shards
+//! (SP1) of a power-of-two number of cycles and prove them separately. Larger
+//! segments mean fewer of them to prove and recurse over, but the memory a
+//! segment takes to prove grows with its size, so the defaults either run out
+//! of memory on a small machine or pad a small bundle's few thousand cycles to
+//! a segment sized for millions.
+//!
+//! [`ProvingPlan`] sizes them from the cycle count of the preflight execution
+//! and the memory available: segments are no larger than the execution needs,
+//! and as large as three quarters of the available memory allows. The memory
+//! figures per cycle are rough estimates of the CPU provers' peak usage, not
+//! measurements of a particular machine. The chosen plan is recorded in the
+//! proof artifact's metadata (see [`ProvingPlan::metadata`]).
+
+use std::collections::BTreeMap;
+use std::fs;
+
+/// Artifact metadata key of the cycle count of the proven execution
+pub const CYCLES_KEY: &str = "cycles";
+
+/// Artifact metadata key of the segment (shard) size, as a power of two of cycles
+pub const SEGMENT_PO2_KEY: &str = "segment_po2";
+
+/// Artifact metadata key of the number of segments (shards) proven at once
+pub const SEGMENT_BATCH_KEY: &str = "segment_batch";
+
+/// Artifact metadata key of the memory the plan was sized for, in bytes
+pub const AVAILABLE_MEMORY_KEY: &str = "available_memory";
+
+/// Share of the available memory a plan may use, in quarters
+const MEMORY_QUARTERS: u64 = 3;
+
+/// Sizing constraints of one zkVM's local prover
+struct Limits {
+    min_po2: u32,
+    max_po2: u32,
+    /// Segment size used when the available memory is unknown
+    default_po2: u32,
+    /// Estimated peak proving memory per segment cycle
+    bytes_per_cycle: u64,
+    /// Segments proven at once at most (SP1's shard batch size)
+    max_batch: u64,
+}
+
+/// RISC0: `segment_limit_po2` of the executor, one segment proven at a time
+const RISC0: Limits = Limits {
+    min_po2: 16,
+    max_po2: 21,
+    default_po2: 20,
+    bytes_per_cycle: 10 * 1024,
+    max_batch: 1,
+};
+
+/// SP1: `SHARD_SIZE` cycles per shard and `SHARD_BATCH_SIZE` shards in memory
+const SP1: Limits = Limits {
+    min_po2: 19,
+    max_po2: 22,
+    default_po2: 22,
+    bytes_per_cycle: 512,
+    max_batch: 16,
+};
+
+/// Segment sizing chosen for a local proving run
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProvingPlan {
+    /// Cycles of the guest's execution on the input
+    pub cycles: u64,
+    /// Memory available to the prover in bytes, if it could be determined
+    pub available_memory: Option<u64>,
+    /// Cycles per segment (shard), as a power of two
+    pub segment_po2: u32,
+    /// Segments (shards) proven at once
+    pub batch_size: u64,
+}
+
+impl ProvingPlan {
+    /// Plan a RISC0 proof: the executor's `segment_limit_po2`
+    pub fn risc0(cycles: u64, available_memory: Option<u64>) -> Self {
+        Self::plan(&RISC0, cycles, available_memory)
+    }
+
+    /// Plan an SP1 proof: its `SHARD_SIZE` (`1 << segment_po2`) and `SHARD_BATCH_SIZE`
+    pub fn sp1(cycles: u64, available_memory: Option<u64>) -> Self {
+        Self::plan(&SP1, cycles, available_memory)
+    }
+
+    fn plan(limits: &Limits, cycles: u64, available_memory: Option<u64>) -> Self {
+        // A segment larger than the whole execution only adds padding
+        let needed_po2 = ceil_log2(cycles).clamp(limits.min_po2, limits.max_po2);
+        let segment_po2 = match available_memory {
+            Some(memory) => {
+                let budget = memory / 4 * MEMORY_QUARTERS;
+                (limits.min_po2..=needed_po2)
+                    .rev()
+                    .find(|po2| (1u64 << po2) * limits.bytes_per_cycle <= budget)
+                    .unwrap_or(limits.min_po2)
+            }
+            None => needed_po2.min(limits.default_po2),
+        };
+
+        let segments = cycles.div_ceil(1 << segment_po2).max(1);
+        let batch_size = match available_memory {
+            Some(memory) => {
+                let per_segment = (1u64 << segment_po2) * limits.bytes_per_cycle;
+                (memory / 4 * MEMORY_QUARTERS / per_segment).clamp(1, limits.max_batch)
+            }
+            None => limits.max_batch,
+        };
+
+        Self {
+            cycles,
+            available_memory,
+            segment_po2,
+            batch_size: batch_size.min(segments),
+        }
+    }
+
+    /// Cycles per segment (shard)
+    pub fn segment_size(&self) -> u64 {
+        1 << self.segment_po2
+    }
+
+    /// Number of segments (shards) the execution is split into
+    pub fn segments(&self) -> u64 {
+        self.cycles.div_ceil(self.segment_size()).max(1)
+    }
+
+    /// Proof artifact metadata recording the plan
+    pub fn metadata(&self) -> BTreeMap<String, String> {
+        let mut metadata = BTreeMap::from([
+            (CYCLES_KEY.to_string(), self.cycles.to_string()),
+            (SEGMENT_PO2_KEY.to_string(), self.segment_po2.to_string()),
+            (SEGMENT_BATCH_KEY.to_string(), self.batch_size.to_string()),
+        ]);
+        if let Some(memory) = self.available_memory {
+            metadata.insert(AVAILABLE_MEMORY_KEY.to_string(), memory.to_string());
+        }
+        metadata
+    }
+}
+
+/// Memory available to a prover started now, in bytes
+///
+/// `MemAvailable` of `/proc/meminfo`, lowered to the cgroup's `memory.max`
+/// when running in a container with a memory limit. `None` where neither can
+/// be read, e.g. outside Linux.
+pub fn available_memory() -> Option<u64> {
+    let meminfo = fs::read_to_string("/proc/meminfo")
+        .ok()
+        .and_then(|meminfo| parse_meminfo(&meminfo));
+    let limit = fs::read_to_string("/sys/fs/cgroup/memory.max")
+        .ok()
+        .and_then(|limit| limit.trim().parse::<u64>().ok());
+    match (meminfo, limit) {
+        (Some(available), Some(limit)) => Some(available.min(limit)),
+        (available, limit) => available.or(limit),
+    }
+}
+
+/// `MemAvailable` of a `/proc/meminfo`, in bytes
+fn parse_meminfo(meminfo: &str) -> Option<u64> {
+    let line = meminfo.lines().find_map(|line| line.strip_prefix("MemAvailable:"))?;
+    let kib = line.trim().strip_suffix("kB")?.trim().parse::<u64>().ok()?;
+    Some(kib * 1024)
+}
+
+/// Smallest `po2` with `1 << po2 >= value`
+fn ceil_log2(value: u64) -> u32 {
+    value.max(1).next_power_of_two().trailing_zeros()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GIB: u64 = 1 << 30;
+
+    #[test]
+    fn test_small_executions_get_small_segments() {
+        // A few hundred thousand cycles fit in one minimal segment
+        let plan = ProvingPlan::risc0(300_000, Some(64 * GIB));
+        assert_eq!(plan.segment_po2, 19);
+        assert_eq!(plan.segments(), 1);
+        assert_eq!(plan.batch_size, 1);
+
+        let plan = ProvingPlan::risc0(1_000, None);
+        assert_eq!(plan.segment_po2, 16);
+
+        let plan = ProvingPlan::sp1(300_000, Some(64 * GIB));
+        assert_eq!(plan.segment_po2, 19);
+        assert_eq!(plan.batch_size, 1);
+    }
+
+    #[test]
+    fn test_segments_shrink_with_memory() {
+        let cycles = 50_000_000;
+        let large = ProvingPlan::risc0(cycles, Some(64 * GIB));
+        assert_eq!(large.segment_po2, 21);
+        let small = ProvingPlan::risc0(cycles, Some(8 * GIB));
+        assert_eq!(small.segment_po2, 19);
+        assert!(small.segments() > large.segments());
+
+        // Too little memory for any segment size still yields the smallest
+        assert_eq!(ProvingPlan::risc0(cycles, Some(GIB)).segment_po2, 16);
+
+        // Unknown memory keeps the default
+        assert_eq!(ProvingPlan::risc0(cycles, None).segment_po2, 20);
+    }
+
+    #[test]
+    fn test_sp1_batches_fit_memory() {
+        let cycles = 200_000_000;
+        let plan = ProvingPlan::sp1(cycles, Some(64 * GIB));
+        assert_eq!(plan.segment_po2, 22);
+        assert_eq!(plan.batch_size, 16);
+
+        let plan = ProvingPlan::sp1(cycles, Some(16 * GIB));
+        assert_eq!(plan.segment_po2, 22);
+        assert_eq!(plan.batch_size, 6);
+        assert!(plan.batch_size * plan.segment_size() * SP1.bytes_per_cycle <= 12 * GIB);
+
+        let plan = ProvingPlan::sp1(cycles, Some(GIB));
+        assert_eq!((plan.segment_po2, plan.batch_size), (20, 1));
+
+        // Never more shards at once than there are
+        let plan = ProvingPlan::sp1(10_000_000, None);
+        assert_eq!((plan.segment_po2, plan.segments(), plan.batch_size), (22, 3, 3));
+    }
+
+    #[test]
+    fn test_plan_metadata() {
+        let metadata = ProvingPlan::risc0(300_000, Some(64 * GIB)).metadata();
+        assert_eq!(metadata[CYCLES_KEY], "300000");
+        assert_eq!(metadata[SEGMENT_PO2_KEY], "19");
+        assert_eq!(metadata[SEGMENT_BATCH_KEY], "1");
+        assert_eq!(metadata[AVAILABLE_MEMORY_KEY], (64 * GIB).to_string());
+        assert!(!ProvingPlan::risc0(300_000, None).metadata().contains_key(AVAILABLE_MEMORY_KEY));
+    }
+
+    #[test]
+    fn test_parse_meminfo() {
+        let meminfo =
+            "MemTotal:       65856396 kB\nMemFree:         1234567 kB\nMemAvailable:   40000000 kB\n";
+        assert_eq!(parse_meminfo(meminfo), Some(40_000_000 * 1024));
+        assert_eq!(parse_meminfo("MemTotal: 1 kB\n"), None);
+    }
+}
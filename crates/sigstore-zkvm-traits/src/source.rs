@@ -0,0 +1,297 @@
+//! Input source module for bundles and trusted roots
+//!
+//! Host CLIs accept either a local path or a remote location for their inputs:
+//!
+//! - `path/to/file` - local file (default when no scheme is given)
+//! - `https://…` / `http://…` - downloaded as-is
+//! - `oci://registry/repository@sha256:…` - Sigstore bundle attached to an OCI
+//!   artifact, discovered through the registry's referrers API
+//! - `github://owner/repo@sha256:…` - attestation bundle for a subject digest,
+//!   fetched from the GitHub attestations API
+//! - `maven://group:artifact:version[:classifier][@extension]` - Sigstore bundle
+//!   published next to the artifact on Maven Central
+//!
+//! Remote sources are downloaded into a cache directory so that repeated runs
+//! (and the rest of the workflow, which operates on paths) reuse the same bytes.
+//! Resolving remote sources requires the `fetcher` feature.
+
+use anyhow::{bail, Context, Result};
+use sigstore_verifier::types::purl::MavenPurl;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/// Location of a bundle or trusted root file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InputSource {
+    /// File on the local filesystem
+    Local(PathBuf),
+    /// HTTP(S) URL
+    Url(String),
+    /// Sigstore bundle referring to an OCI artifact
+    Oci {
+        registry: String,
+        repository: String,
+        digest: String,
+    },
+    /// Attestation bundle stored in the GitHub attestations API
+    GitHub {
+        owner: String,
+        repo: String,
+        digest: String,
+    },
+    /// Sigstore bundle published for an artifact on Maven Central
+    Maven(Box<MavenPurl>),
+}
+
+impl InputSource {
+    /// Whether this source has to be downloaded before use
+    pub fn is_remote(&self) -> bool {
+        !matches!(self, InputSource::Local(_))
+    }
+
+    /// Resolve the source to a local file path
+    ///
+    /// Local sources are returned unchanged. Remote sources are downloaded into
+    /// `cache_dir` under a name derived from the source string; an existing cached
+    /// copy is reused unless `refresh` is set.
+    ///
+    /// When `expected_sha256` is given (hex, optionally prefixed with `sha256:`),
+    /// the content must hash to it: downloads that don't match are rejected before
+    /// they reach the cache, and a cached copy that doesn't match is downloaded again.
+    /// Cached copies are stored with a checksum sidecar, and one that no longer
+    /// matches it is downloaded again as well.
+    ///
+    /// Downloads are recorded with their fetch time (see [`crate::freshness`]).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the download fails, the content doesn't match the pin,
+    /// or the cache cannot be written.
+    #[cfg(feature = "fetcher")]
+    pub fn resolve(
+        &self,
+        cache_dir: &Path,
+        refresh: bool,
+        expected_sha256: Option<&str>,
+    ) -> Result<PathBuf> {
+        use sigstore_verifier::fetcher::http::default_client;
+        use sigstore_verifier::fetcher::remote::{
+            fetch_github_attestation_bundle, fetch_maven_attestation_bundle,
+            fetch_oci_referrer_bundle, fetch_url,
+        };
+
+        let pin = expected_sha256.map(parse_sha256_pin).transpose()?;
+
+        if let InputSource::Local(path) = self {
+            if let Some(pin) = pin {
+                let content = std::fs::read(path)
+                    .with_context(|| format!("Failed to read {}", path.display()))?;
+                check_sha256_pin(self, &content, &pin)?;
+            }
+            return Ok(path.clone());
+        }
+
+        let cached_path = self.cache_path(cache_dir);
+        if cached_path.exists() && !refresh {
+            // A cached copy that was damaged on disk is downloaded again
+            let content = std::fs::read(&cached_path)
+                .with_context(|| format!("Failed to read {}", cached_path.display()))?;
+            let intact = crate::utils::check_checksum(&cached_path, &content).is_ok();
+            let pinned = pin.is_none_or(|pin| check_sha256_pin(self, &content, &pin).is_ok());
+            if intact && pinned {
+                return Ok(cached_path);
+            }
+        }
+
+        let client = default_client()?;
+        let content = match self {
+            InputSource::Local(_) => unreachable!(),
+            InputSource::Url(url) => fetch_url(&*client, url),
+            InputSource::Oci { registry, repository, digest } => {
+                fetch_oci_referrer_bundle(&*client, registry, repository, digest)
+            }
+            InputSource::GitHub { owner, repo, digest } => {
+                fetch_github_attestation_bundle(&*client, owner, repo, digest)
+            }
+            InputSource::Maven(purl) => fetch_maven_attestation_bundle(&*client, purl),
+        }
+        .with_context(|| format!("Failed to download {}", self))?;
+
+        if let Some(pin) = pin {
+            check_sha256_pin(self, &content, &pin)?;
+        }
+
+        std::fs::create_dir_all(cache_dir)
+            .with_context(|| format!("Failed to create cache directory: {}", cache_dir.display()))?;
+
+        // An interrupted download must never leave a truncated file that a later
+        // run would pick up from the cache
+        crate::utils::write_atomic(&cached_path, &content)?;
+        crate::utils::write_checksum(&cached_path, &content)?;
+
+        // Date the download, so that a stale cached trusted root is noticed
+        crate::freshness::TrustRootMetadata {
+            source: self.to_string(),
+            fetched_at: crate::store::unix_now(),
+            expires: None,
+        }
+        .save(&cached_path)?;
+
+        Ok(cached_path)
+    }
+
+    /// Cache location for a remote source, keyed by the SHA256 of its string form
+    pub fn cache_path(&self, cache_dir: &Path) -> PathBuf {
+        let key = sigstore_verifier::crypto::hash::sha256(self.to_string().as_bytes());
+        cache_dir.join(format!("{}.download", hex::encode(key)))
+    }
+}
+
+impl FromStr for InputSource {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        if s.starts_with("https://") || s.starts_with("http://") {
+            return Ok(InputSource::Url(s.to_string()));
+        }
+
+        if let Some(reference) = s.strip_prefix("oci://") {
+            let (name, digest) = split_digest(reference)?;
+            let (registry, repository) = name
+                .split_once('/')
+                .context("OCI reference must be oci://registry/repository@sha256:<digest>")?;
+            return Ok(InputSource::Oci {
+                registry: registry.to_string(),
+                repository: repository.to_string(),
+                digest,
+            });
+        }
+
+        if let Some(reference) = s.strip_prefix("github://") {
+            let (name, digest) = split_digest(reference)?;
+            let (owner, repo) = name
+                .split_once('/')
+                .filter(|(owner, repo)| !owner.is_empty() && !repo.is_empty() && !repo.contains('/'))
+                .context("GitHub reference must be github://owner/repo@sha256:<digest>")?;
+            return Ok(InputSource::GitHub {
+                owner: owner.to_string(),
+                repo: repo.to_string(),
+                digest,
+            });
+        }
+
+        if let Some(gav) = s.strip_prefix("maven://") {
+            let purl = MavenPurl::from_gav(gav).context(
+                "Maven reference must be maven://group:artifact:version[:classifier][@extension]",
+            )?;
+            return Ok(InputSource::Maven(Box::new(purl)));
+        }
+
+        if let Some((scheme, _)) = s.split_once("://") {
+            bail!("Unsupported input source scheme: {}://", scheme);
+        }
+
+        Ok(InputSource::Local(PathBuf::from(s)))
+    }
+}
+
+impl fmt::Display for InputSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InputSource::Local(path) => write!(f, "{}", path.display()),
+            InputSource::Url(url) => write!(f, "{}", url),
+            InputSource::Oci { registry, repository, digest } => {
+                write!(f, "oci://{}/{}@{}", registry, repository, digest)
+            }
+            InputSource::GitHub { owner, repo, digest } => {
+                write!(f, "github://{}/{}@{}", owner, repo, digest)
+            }
+            InputSource::Maven(purl) => write!(f, "maven://{}", purl.gav()),
+        }
+    }
+}
+
+/// Split `name@sha256:<hex>` and validate the digest part
+fn split_digest(reference: &str) -> Result<(&str, String)> {
+    let (name, digest) = reference
+        .rsplit_once('@')
+        .context("Remote reference must be pinned with @sha256:<digest>")?;
+    let hex_digest = digest
+        .strip_prefix("sha256:")
+        .context("Only sha256 digests are supported")?;
+    if hex_digest.len() != 64 || hex::decode(hex_digest).is_err() {
+        bail!("Invalid sha256 digest: {}", digest);
+    }
+    Ok((name, digest.to_ascii_lowercase()))
+}
+
+/// Parse a SHA256 pin given as hex, optionally prefixed with `sha256:`
+pub fn parse_sha256_pin(pin: &str) -> Result<[u8; 32]> {
+    let hex_digest = pin.strip_prefix("sha256:").unwrap_or(pin);
+    let bytes = hex::decode(hex_digest).with_context(|| format!("Invalid SHA256 pin: {}", pin))?;
+    bytes
+        .try_into()
+        .map_err(|_| anyhow::anyhow!("SHA256 pin must be 32 bytes: {}", pin))
+}
+
+/// Fail unless `content` hashes to the pinned SHA256
+fn check_sha256_pin(source: &InputSource, content: &[u8], pin: &[u8; 32]) -> Result<()> {
+    let actual = sigstore_verifier::crypto::hash::sha256(content);
+    if &actual != pin {
+        bail!(
+            "SHA256 mismatch for {}: expected {}, got {}",
+            source,
+            hex::encode(pin),
+            hex::encode(actual)
+        );
+    }
+    Ok(())
+}
+
+/// Read sources from a file, one per line; `#` starts a comment
+///
+/// `owner/repo@sha256:...` is shorthand for `github://owner/repo@sha256:...`.
+pub fn read_source_list(path: &Path) -> Result<Vec<InputSource>> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+
+    let mut sources = Vec::new();
+    for (line_num, line) in content.lines().enumerate() {
+        let line = line.split('#').next().unwrap_or_default().trim();
+        if line.is_empty() {
+            continue;
+        }
+        let spec = if !line.contains("://") && line.contains("@sha256:") {
+            format!("github://{}", line)
+        } else {
+            line.to_string()
+        };
+        let source = spec
+            .parse()
+            .with_context(|| format!("{}:{}: invalid source", path.display(), line_num + 1))?;
+        sources.push(source);
+    }
+
+    Ok(sources)
+}
+
+/// Default directory for downloaded inputs
+///
+/// Uses `$XDG_CACHE_HOME/sigstore-zkvm`, then `$HOME/.cache/sigstore-zkvm`
+/// (`%LOCALAPPDATA%\sigstore-zkvm` on Windows, where `HOME` is usually unset),
+/// and falls back to the system temporary directory.
+pub fn default_cache_dir() -> PathBuf {
+    let home_cache = || {
+        if cfg!(windows) {
+            std::env::var_os("LOCALAPPDATA").map(PathBuf::from)
+        } else {
+            std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".cache"))
+        }
+    };
+    std::env::var_os("XDG_CACHE_HOME")
+        .map(PathBuf::from)
+        .or_else(home_cache)
+        .unwrap_or_else(std::env::temp_dir)
+        .join("sigstore-zkvm")
+}
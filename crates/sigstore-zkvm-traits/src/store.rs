@@ -0,0 +1,202 @@
+//! File-backed store for attestation bundles, verification results and proofs
+//!
+//! The store is a plain directory so it can be inspected, synced and archived
+//! with ordinary tools:
+//!
+//! ```text
+//! <root>/
+//! ├── bundles/<sha256>.json   # attestation bundles, content addressed
+//! ├── records/<sha256>.json   # AttestationRecord for the bundle with that hash
+//! └── proofs/<sha256>.json    # ProofArtifact for the bundle with that hash
+//! ```
+//!
+//! Records are keyed by the SHA256 of the bundle bytes, since one subject
+//! digest can have several attestations (provenance, SBOM, ...).
+
+use crate::utils::{write_atomic, ProofArtifact};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use sigstore_verifier::crypto::hash::sha256;
+use sigstore_verifier::types::result::VerificationResult;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Outcome of verifying a stored bundle
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RecordStatus {
+    /// The bundle verified; the record carries the journal
+    Verified,
+    /// The bundle verified but violates the policy (quarantine mode); the record
+    /// carries the journal, flagged `POLICY_VIOLATION`
+    #[serde(rename = "policy_violation")]
+    PolicyViolation,
+    /// The bundle failed verification; the record carries the error
+    Failed,
+}
+
+/// Verification record for one attestation bundle
+///
+/// - bundle_sha256: Hex SHA256 of the bundle bytes (the record key)
+/// - source: Where the bundle came from (path, URL, oci:// or github:// reference)
+/// - subject_digest: Attested subject digest as `algorithm:hex`, if known
+/// - status: Whether verification succeeded
+/// - journal: Hex-encoded journal (`VerificationResult::as_slice()`) on success or policy violation
+/// - error: Verification error on failure
+/// - proof: Path of the proof artifact relative to the store root, once proven
+/// - recorded_at: Unix timestamp of the last update
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AttestationRecord {
+    pub bundle_sha256: String,
+    pub source: String,
+    pub subject_digest: Option<String>,
+    pub status: RecordStatus,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub journal: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proof: Option<String>,
+    pub recorded_at: u64,
+}
+
+/// Directory-backed attestation store
+#[derive(Debug, Clone)]
+pub struct AttestationStore {
+    root: PathBuf,
+}
+
+impl AttestationStore {
+    /// Open the store at `root`, creating its directories if needed
+    pub fn open(root: &Path) -> Result<Self> {
+        for dir in ["bundles", "records", "proofs"] {
+            let path = root.join(dir);
+            fs::create_dir_all(&path)
+                .with_context(|| format!("Failed to create store directory: {}", path.display()))?;
+        }
+        Ok(Self {
+            root: root.to_path_buf(),
+        })
+    }
+
+    /// Root directory of the store
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Store a bundle and return its SHA256 (hex), which keys its record
+    pub fn put_bundle(&self, bundle_json: &[u8]) -> Result<String> {
+        let key = hex::encode(sha256(bundle_json));
+        let path = self.bundle_path(&key);
+        if !path.exists() {
+            write_atomic(&path, bundle_json)?;
+        }
+        Ok(key)
+    }
+
+    /// Path of a stored bundle
+    pub fn bundle_path(&self, bundle_sha256: &str) -> PathBuf {
+        self.root.join("bundles").join(format!("{}.json", bundle_sha256))
+    }
+
+    /// Insert or replace the record for a bundle
+    pub fn put_record(&self, record: &AttestationRecord) -> Result<()> {
+        let json = serde_json::to_vec_pretty(record).context("Failed to serialize record")?;
+        write_atomic(&self.record_path(&record.bundle_sha256), &json)
+    }
+
+    /// Load the record for a bundle, if any
+    pub fn get_record(&self, bundle_sha256: &str) -> Result<Option<AttestationRecord>> {
+        let path = self.record_path(bundle_sha256);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let content =
+            fs::read(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+        let record = serde_json::from_slice(&content)
+            .with_context(|| format!("Failed to parse {}", path.display()))?;
+        Ok(Some(record))
+    }
+
+    /// Load all records, ordered by bundle hash
+    pub fn records(&self) -> Result<Vec<AttestationRecord>> {
+        let dir = self.root.join("records");
+        let mut keys: Vec<String> = fs::read_dir(&dir)
+            .with_context(|| format!("Failed to list {}", dir.display()))?
+            .filter_map(|entry| entry.ok())
+            .filter_map(|entry| {
+                let name = entry.file_name().into_string().ok()?;
+                name.strip_suffix(".json").map(str::to_string)
+            })
+            .collect();
+        keys.sort();
+
+        let mut records = Vec::with_capacity(keys.len());
+        for key in keys {
+            if let Some(record) = self.get_record(&key)? {
+                records.push(record);
+            }
+        }
+        Ok(records)
+    }
+
+    /// Store the proof artifact for a bundle and link it from the bundle's record
+    ///
+    /// Creates a verified record if the bundle has none yet (e.g. when proving a
+    /// bundle that was never backfilled).
+    ///
+    /// # Arguments
+    ///
+    /// * `bundle_json` - The proven bundle
+    /// * `source` - Where the bundle came from
+    /// * `artifact` - The proof artifact; its journal becomes the record journal
+    pub fn put_proof(
+        &self,
+        bundle_json: &[u8],
+        source: &str,
+        artifact: &ProofArtifact,
+    ) -> Result<AttestationRecord> {
+        let key = self.put_bundle(bundle_json)?;
+        let relative = format!("proofs/{}.json", key);
+        let json = serde_json::to_vec_pretty(artifact).context("Failed to serialize proof artifact")?;
+        write_atomic(&self.root.join(&relative), &json)?;
+
+        let mut record = self.get_record(&key)?.unwrap_or_else(|| AttestationRecord {
+            bundle_sha256: key.clone(),
+            source: source.to_string(),
+            subject_digest: None,
+            status: RecordStatus::Verified,
+            journal: None,
+            error: None,
+            proof: None,
+            recorded_at: 0,
+        });
+        // The proven journal tells whether the bundle was quarantined
+        let quarantined = VerificationResult::from_slice(&artifact.journal()?)
+            .is_ok_and(|result| result.is_policy_violation());
+        record.status = if quarantined {
+            RecordStatus::PolicyViolation
+        } else {
+            RecordStatus::Verified
+        };
+        record.journal = Some(artifact.journal.trim_start_matches("0x").to_string());
+        record.error = None;
+        record.proof = Some(relative);
+        record.recorded_at = unix_now();
+        self.put_record(&record)?;
+
+        Ok(record)
+    }
+
+    fn record_path(&self, bundle_sha256: &str) -> PathBuf {
+        self.root.join("records").join(format!("{}.json", bundle_sha256))
+    }
+}
+
+/// Current Unix time in seconds
+pub fn unix_now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
@@ -0,0 +1,258 @@
+//! TEE quotes binding proof artifacts to the machine that produced them
+//!
+//! When the prover host runs inside an Intel TDX trust domain or an SGX enclave,
+//! it can attach a DCAP quote to the `ProofArtifact`. The quote's 64-byte report
+//! data carries the SHA256 of the artifact contents (zero padded), so a relying
+//! party that verifies the quote (e.g. with Automata's DCAP attestation contracts)
+//! additionally learns which measured machine generated the proof.
+//!
+//! Quotes are obtained through the interfaces exposed to guests:
+//! - TDX: the Linux configfs-tsm report interface (`/sys/kernel/config/tsm/report`)
+//! - SGX: the Gramine attestation pseudo-files (`/dev/attestation`)
+//!
+//! The zk proof remains the source of truth for the verification result; the
+//! quote is an optional, additional signal.
+
+use crate::utils::ProofArtifact;
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use sigstore_verifier::crypto::hash::sha256;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/// Domain separator mixed into the artifact digest
+const ARTIFACT_DIGEST_DOMAIN: &[u8] = b"sigstore-zkvm/proof-artifact/v1";
+
+const TSM_REPORT_DIR: &str = "/sys/kernel/config/tsm/report";
+const GRAMINE_ATTESTATION_DIR: &str = "/dev/attestation";
+
+/// DCAP quote header length, shared by SGX (v3) and TDX (v4/v5) quotes
+const QUOTE_HEADER_LEN: usize = 48;
+/// Offset of report data in the SGX enclave report body
+const SGX_REPORT_DATA_OFFSET: usize = 320;
+/// Offset of report data in the TDX TD report body (TDX 1.0 and 1.5)
+const TDX_REPORT_DATA_OFFSET: usize = 520;
+/// Quote v5 inserts a body type (u16) and body size (u32) after the header
+const QUOTE_V5_BODY_DESCRIPTOR_LEN: usize = 6;
+
+const TEE_TYPE_SGX: u32 = 0x00;
+const TEE_TYPE_TDX: u32 = 0x81;
+
+/// Trusted execution environment that produced a quote
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TeeKind {
+    /// Intel TDX trust domain
+    Tdx,
+    /// Intel SGX enclave (Gramine)
+    Sgx,
+}
+
+impl FromStr for TeeKind {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "tdx" => Ok(TeeKind::Tdx),
+            "sgx" => Ok(TeeKind::Sgx),
+            _ => bail!("Unsupported TEE '{}', expected 'tdx' or 'sgx'", s),
+        }
+    }
+}
+
+impl fmt::Display for TeeKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TeeKind::Tdx => write!(f, "tdx"),
+            TeeKind::Sgx => write!(f, "sgx"),
+        }
+    }
+}
+
+/// TEE quote attached to a proof artifact
+///
+/// - tee: The TEE that generated the quote
+/// - report_data: Hex-encoded 64-byte report data (artifact digest, zero padded)
+/// - quote: Hex-encoded raw DCAP quote
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TeeAttestation {
+    pub tee: TeeKind,
+    pub report_data: String,
+    pub quote: String,
+}
+
+/// Compute the digest of a proof artifact that TEE quotes commit to
+///
+/// Covers every field except the attestation itself. Fields are hashed in
+/// declaration order, each prefixed with its length, so that no two artifacts
+/// share a digest by shifting bytes between fields.
+pub fn artifact_digest(artifact: &ProofArtifact) -> [u8; 32] {
+    let mut preimage = ARTIFACT_DIGEST_DOMAIN.to_vec();
+    for field in [
+        &artifact.zkvm,
+        &artifact.program_id,
+        &artifact.circuit_version,
+        &artifact.journal,
+        &artifact.proof,
+    ] {
+        preimage.extend_from_slice(&(field.len() as u64).to_be_bytes());
+        preimage.extend_from_slice(field.as_bytes());
+    }
+    sha256(&preimage)
+}
+
+/// Report data expected in the quote for an artifact
+pub fn artifact_report_data(artifact: &ProofArtifact) -> [u8; 64] {
+    let mut report_data = [0u8; 64];
+    report_data[..32].copy_from_slice(&artifact_digest(artifact));
+    report_data
+}
+
+/// Generate a quote for the artifact and attach it
+///
+/// # Arguments
+///
+/// * `artifact` - The proof artifact; any existing attestation is replaced
+/// * `tee` - The TEE the host is running in
+///
+/// # Errors
+///
+/// Returns an error if the host doesn't expose the quote interface for `tee`
+/// or quote generation fails.
+pub fn attach_tee_attestation(artifact: &mut ProofArtifact, tee: TeeKind) -> Result<()> {
+    let report_data = artifact_report_data(artifact);
+    let quote = generate_quote(tee, &report_data)?;
+
+    artifact.tee_attestation = Some(TeeAttestation {
+        tee,
+        report_data: format!("0x{}", hex::encode(report_data)),
+        quote: format!("0x{}", hex::encode(quote)),
+    });
+    Ok(())
+}
+
+/// Check that an artifact's attached quote commits to the artifact contents
+///
+/// This only checks the binding between quote and artifact. The quote itself
+/// (signature, certification chain and TCB status) must be verified separately
+/// with a DCAP quote verifier.
+///
+/// # Errors
+///
+/// Returns an error if there is no attestation, the quote cannot be parsed, or
+/// its report data doesn't match the artifact digest.
+pub fn verify_tee_binding(artifact: &ProofArtifact) -> Result<TeeKind> {
+    let attestation = artifact
+        .tee_attestation
+        .as_ref()
+        .context("Proof artifact has no TEE attestation")?;
+    let quote = hex::decode(attestation.quote.trim_start_matches("0x"))
+        .context("Invalid hex in TEE quote")?;
+
+    let (tee, report_data) = parse_quote_report_data(&quote)?;
+    if tee != attestation.tee {
+        bail!("Quote was produced by {}, but the artifact claims {}", tee, attestation.tee);
+    }
+    if report_data != artifact_report_data(artifact) {
+        bail!("TEE quote report data does not match the proof artifact digest");
+    }
+
+    Ok(tee)
+}
+
+/// Extract the TEE type and report data from a raw DCAP quote
+pub fn parse_quote_report_data(quote: &[u8]) -> Result<(TeeKind, [u8; 64])> {
+    if quote.len() < QUOTE_HEADER_LEN {
+        bail!("TEE quote is too short: {} bytes", quote.len());
+    }
+    let version = u16::from_le_bytes([quote[0], quote[1]]);
+    let tee_type = u32::from_le_bytes([quote[4], quote[5], quote[6], quote[7]]);
+
+    let body_offset = match version {
+        3 | 4 => QUOTE_HEADER_LEN,
+        5 => QUOTE_HEADER_LEN + QUOTE_V5_BODY_DESCRIPTOR_LEN,
+        _ => bail!("Unsupported quote version: {}", version),
+    };
+    let (tee, offset) = match tee_type {
+        TEE_TYPE_SGX => (TeeKind::Sgx, body_offset + SGX_REPORT_DATA_OFFSET),
+        TEE_TYPE_TDX => (TeeKind::Tdx, body_offset + TDX_REPORT_DATA_OFFSET),
+        _ => bail!("Unsupported quote TEE type: {:#x}", tee_type),
+    };
+
+    let report_data = quote
+        .get(offset..offset + 64)
+        .context("TEE quote is truncated before the report data")?;
+    Ok((tee, report_data.try_into().unwrap()))
+}
+
+/// Request a quote over `report_data` from the TEE the host is running in
+pub fn generate_quote(tee: TeeKind, report_data: &[u8; 64]) -> Result<Vec<u8>> {
+    match tee {
+        TeeKind::Tdx => generate_tdx_quote(report_data),
+        TeeKind::Sgx => generate_sgx_quote(report_data),
+    }
+}
+
+/// TDX quote through configfs-tsm: create a report entry, write the report
+/// data to `inblob` and read the quote from `outblob`
+fn generate_tdx_quote(report_data: &[u8; 64]) -> Result<Vec<u8>> {
+    let base = Path::new(TSM_REPORT_DIR);
+    if !base.is_dir() {
+        bail!(
+            "{} not found; TDX quotes require a TD guest with configfs-tsm mounted",
+            TSM_REPORT_DIR
+        );
+    }
+
+    let entry = TsmReportEntry::create(base)?;
+    let provider = fs::read_to_string(entry.0.join("provider")).unwrap_or_default();
+    if !provider.trim().starts_with("tdx") {
+        bail!("configfs-tsm provider is '{}', not TDX", provider.trim());
+    }
+
+    fs::write(entry.0.join("inblob"), report_data).context("Failed to write TDX report data")?;
+    let quote = fs::read(entry.0.join("outblob")).context("Failed to read TDX quote")?;
+    if quote.is_empty() {
+        bail!("configfs-tsm returned an empty TDX quote");
+    }
+    Ok(quote)
+}
+
+/// SGX quote through Gramine: write the report data to `user_report_data`
+/// and read the quote from `quote`
+fn generate_sgx_quote(report_data: &[u8; 64]) -> Result<Vec<u8>> {
+    let base = Path::new(GRAMINE_ATTESTATION_DIR);
+    let attestation_type = fs::read_to_string(base.join("attestation_type")).with_context(|| {
+        format!(
+            "{} not found; SGX quotes require running under Gramine with remote attestation enabled",
+            GRAMINE_ATTESTATION_DIR
+        )
+    })?;
+    if attestation_type.trim() != "dcap" {
+        bail!("Gramine attestation type is '{}', expected 'dcap'", attestation_type.trim());
+    }
+
+    fs::write(base.join("user_report_data"), report_data)
+        .context("Failed to write SGX user report data")?;
+    fs::read(base.join("quote")).context("Failed to read SGX quote")
+}
+
+/// configfs-tsm report entry, removed again when dropped
+struct TsmReportEntry(PathBuf);
+
+impl TsmReportEntry {
+    fn create(base: &Path) -> Result<Self> {
+        let path = base.join(format!("sigstore-zkvm-{}", std::process::id()));
+        fs::create_dir(&path)
+            .with_context(|| format!("Failed to create report entry {}", path.display()))?;
+        Ok(Self(path))
+    }
+}
+
+impl Drop for TsmReportEntry {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir(&self.0);
+    }
+}
@@ -39,6 +39,19 @@ pub trait ZkVmProver: Sized {
         input: &ProverInput,
     ) -> Result<(Vec<u8>, Vec<u8>), ZkVmError>;
 
+    /// Execute the guest program on the input without generating a proof
+    ///
+    /// Runs the same guest ELF as [`Self::prove`] in the zkVM's executor (or
+    /// emulator), which is deterministic and much cheaper than proving, e.g. to
+    /// re-derive the journal of an existing proof.
+    ///
+    /// # Arguments
+    /// * `input` - The input data containing the bundle and verification parameters
+    ///
+    /// # Returns
+    /// The journal (public output) the guest commits
+    fn execute(&self, input: &ProverInput) -> Result<Vec<u8>, ZkVmError>;
+
     /// Get the program identifier required for on-chain proof verification
     ///
     /// Different zkVMs use different identifiers:
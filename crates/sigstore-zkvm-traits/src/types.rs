@@ -1,5 +1,8 @@
 use serde::{Deserialize, Serialize};
-use sigstore_verifier::types::result::VerificationOptions;
+use std::collections::BTreeMap;
+use sigstore_verifier::crypto::backend;
+use sigstore_verifier::crypto::hash::sha256;
+use sigstore_verifier::types::result::{BlockAnchor, VerificationOptions};
 use sigstore_verifier::types::certificate::CertificateChain;
 
 /// Input data for the zkVM prover
@@ -19,6 +22,28 @@ pub struct ProverInput {
 
     /// Optional TSA certificate chain in PEM format for RFC3161 timestamp verification
     pub tsa_cert_chain: Option<CertificateChain>,
+
+    /// Key-value annotations whose hash the guest commits in the journal
+    pub annotations: BTreeMap<String, String>,
+
+    /// Recent block the guest commits in the journal, bounding when the proof was generated
+    pub block_anchor: Option<BlockAnchor>,
+
+    /// Hash of the previous journal in a chain of custody, committed by the guest
+    pub previous_result_hash: Option<[u8; 32]>,
+
+    /// Policy owner's signature over the input's canonical digest, verified by the
+    /// guest, which commits the hash of the owner's key
+    pub policy_owner: Option<PolicyOwnerSignature>,
+}
+
+/// Ed25519 signature of the policy owner authorizing a [`ProverInput`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PolicyOwnerSignature {
+    /// The owner's raw 32-byte Ed25519 public key
+    pub public_key: [u8; 32],
+    /// 64-byte Ed25519 signature over [`ProverInput::canonical_digest`]
+    pub signature: Vec<u8>,
 }
 
 impl ProverInput {
@@ -34,6 +59,10 @@ impl ProverInput {
             verification_options,
             trust_bundle,
             tsa_cert_chain,
+            annotations: BTreeMap::new(),
+            block_anchor: None,
+            previous_result_hash: None,
+            policy_owner: None,
         }
     }
 
@@ -54,4 +83,203 @@ impl ProverInput {
         bincode::deserialize(bytes)
             .map_err(|e| format!("Failed to deserialize ProverInput: {}", e))
     }
+
+    /// SHA256 of the encoded input, which the guest commits as the journal's
+    /// input digest
+    pub fn digest(&self) -> Result<[u8; 32], String> {
+        Ok(sha256(&self.encode_input()?))
+    }
+
+    /// SHA256 of the input encoded without its policy owner signature, which is
+    /// what the policy owner signs
+    pub fn canonical_digest(&self) -> Result<[u8; 32], String> {
+        Self {
+            policy_owner: None,
+            ..self.clone()
+        }
+        .digest()
+    }
+
+    /// Sign the input as the policy owner
+    ///
+    /// Replaces any previous signature; the input must not be changed afterwards.
+    ///
+    /// # Arguments
+    ///
+    /// * `key_pem` - The owner's PKCS#8 Ed25519 private key
+    pub fn sign_policy_owner(&mut self, key_pem: &str) -> Result<(), String> {
+        use ed25519_dalek::pkcs8::DecodePrivateKey;
+        use ed25519_dalek::Signer;
+
+        let key = ed25519_dalek::SigningKey::from_pkcs8_pem(key_pem)
+            .map_err(|e| format!("Invalid policy owner key, expected a PKCS#8 Ed25519 private key: {}", e))?;
+        let digest = self.canonical_digest()?;
+        self.policy_owner = Some(PolicyOwnerSignature {
+            public_key: key.verifying_key().to_bytes(),
+            signature: key.sign(&digest).to_bytes().to_vec(),
+        });
+        Ok(())
+    }
+
+    /// Verify the policy owner's signature, as the guest does
+    ///
+    /// # Arguments
+    ///
+    /// * `pinned_key` - Hex Ed25519 public key the guest was built with
+    ///   (`SIGSTORE_POLICY_OWNER_KEY`); when given, the input must be signed by it
+    ///
+    /// # Returns
+    ///
+    /// SHA256 of the owner's public key, committed as the journal's policy owner
+    /// hash, or `None` for an unsigned input
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the signature doesn't verify, or with a pinned key if
+    /// the input is unsigned or signed by another key
+    pub fn verify_policy_owner(&self, pinned_key: Option<&str>) -> Result<Option<[u8; 32]>, String> {
+        let pinned_key = pinned_key
+            .filter(|key| !key.is_empty())
+            .map(|key| {
+                hex::decode(key.trim_start_matches("0x"))
+                    .ok()
+                    .and_then(|key| <[u8; 32]>::try_from(key).ok())
+                    .ok_or_else(|| format!("Invalid pinned policy owner key '{}': expected 32 hex bytes", key))
+            })
+            .transpose()?;
+
+        let Some(ref owner) = self.policy_owner else {
+            return match pinned_key {
+                Some(_) => Err("Input is not signed by the policy owner".to_string()),
+                None => Ok(None),
+            };
+        };
+        if pinned_key.is_some_and(|key| key != owner.public_key) {
+            return Err(format!(
+                "Input is signed by {}, not by the policy owner",
+                hex::encode(owner.public_key)
+            ));
+        }
+
+        let key = ed25519_dalek::VerifyingKey::from_bytes(&owner.public_key)
+            .map_err(|e| format!("Invalid policy owner key: {}", e))?;
+        let signature = ed25519_dalek::Signature::from_slice(&owner.signature)
+            .map_err(|e| format!("Invalid policy owner signature: {}", e))?;
+        backend::verify_ed25519(&key, &self.canonical_digest()?, &signature)
+            .map_err(|e| format!("Policy owner signature doesn't verify: {}", e))?;
+        Ok(Some(sha256(&owner.public_key)))
+    }
+}
+
+/// Parse a `key=value` annotation, e.g. from a repeated `--annotation` flag
+///
+/// The value may contain `=`; the key must not be empty.
+pub fn parse_annotation(s: &str) -> Result<(String, String), String> {
+    match s.split_once('=') {
+        Some((key, value)) if !key.is_empty() => Ok((key.to_string(), value.to_string())),
+        _ => Err(format!("Invalid annotation '{}': expected KEY=VALUE", s)),
+    }
+}
+
+/// Collect parsed annotations into the map committed by the guest
+///
+/// # Errors
+///
+/// Returns an error if a key is given more than once
+pub fn collect_annotations(pairs: &[(String, String)]) -> Result<BTreeMap<String, String>, String> {
+    let mut annotations = BTreeMap::new();
+    for (key, value) in pairs {
+        if annotations.insert(key.clone(), value.clone()).is_some() {
+            return Err(format!("Duplicate annotation key '{}'", key));
+        }
+    }
+    Ok(annotations)
+}
+
+/// Parse a `NUMBER:HASH` block anchor, e.g. from `--anchor-block`
+///
+/// The hash is the block's 32-byte hash in hex, with or without `0x`.
+pub fn parse_block_anchor(s: &str) -> Result<BlockAnchor, String> {
+    let invalid = || format!("Invalid block anchor '{}': expected NUMBER:HASH", s);
+    let (number, hash) = s.split_once(':').ok_or_else(invalid)?;
+    let number = number.parse::<u64>().map_err(|_| invalid())?;
+    let hash: [u8; 32] = hex::decode(hash.trim_start_matches("0x"))
+        .ok()
+        .and_then(|hash| hash.try_into().ok())
+        .ok_or_else(invalid)?;
+    if hash == [0u8; 32] {
+        return Err(format!("Invalid block anchor '{}': the hash must not be zero", s));
+    }
+    Ok(BlockAnchor { number, hash })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ed25519_dalek::pkcs8::spki::der::pem::LineEnding;
+    use ed25519_dalek::pkcs8::EncodePrivateKey;
+
+    fn sample_input() -> ProverInput {
+        ProverInput::new(
+            b"{}".to_vec(),
+            VerificationOptions::default(),
+            CertificateChain {
+                leaf: vec![1],
+                intermediates: vec![],
+                root: vec![2],
+            },
+            None,
+        )
+    }
+
+    fn owner_key(seed: u8) -> (String, String) {
+        let key = ed25519_dalek::SigningKey::from_bytes(&[seed; 32]);
+        let pem = key.to_pkcs8_pem(LineEnding::LF).unwrap().to_string();
+        (pem, hex::encode(key.verifying_key().to_bytes()))
+    }
+
+    #[test]
+    fn test_policy_owner_signature() {
+        let (pem, public_key) = owner_key(3);
+        let mut input = sample_input();
+        let unsigned_digest = input.digest().unwrap();
+        input.sign_policy_owner(&pem).unwrap();
+
+        // The signature covers the input without itself
+        assert_eq!(input.canonical_digest().unwrap(), unsigned_digest);
+        assert_ne!(input.digest().unwrap(), unsigned_digest);
+
+        let owner_hash = sha256(&hex::decode(&public_key).unwrap());
+        assert_eq!(input.verify_policy_owner(None), Ok(Some(owner_hash)));
+        assert_eq!(input.verify_policy_owner(Some(&public_key)), Ok(Some(owner_hash)));
+        let roundtrip = ProverInput::parse_input(&input.encode_input().unwrap()).unwrap();
+        assert_eq!(roundtrip.verify_policy_owner(Some(&public_key)), Ok(Some(owner_hash)));
+    }
+
+    #[test]
+    fn test_policy_owner_rejections() {
+        let (pem, public_key) = owner_key(3);
+        let (_, other_key) = owner_key(4);
+
+        // Unsigned inputs only pass without a pinned key
+        let unsigned = sample_input();
+        assert_eq!(unsigned.verify_policy_owner(None), Ok(None));
+        assert_eq!(unsigned.verify_policy_owner(Some("")), Ok(None));
+        assert!(unsigned.verify_policy_owner(Some(&public_key)).is_err());
+
+        let mut signed = sample_input();
+        signed.sign_policy_owner(&pem).unwrap();
+        assert!(signed.verify_policy_owner(Some(&other_key)).is_err());
+        assert!(signed.verify_policy_owner(Some("00")).is_err());
+
+        // Inputs altered after signing
+        let mut altered = signed.clone();
+        altered.verification_options.archival = true;
+        assert!(altered.verify_policy_owner(None).is_err());
+        let mut altered = signed.clone();
+        altered.annotations.insert("env".to_string(), "prod".to_string());
+        assert!(altered.verify_policy_owner(Some(&public_key)).is_err());
+
+        assert!(sample_input().sign_policy_owner("not a key").is_err());
+    }
 }
@@ -1,15 +1,29 @@
 //! Utility functions for zkVM proof generation and artifact management
 //!
 //! This module provides shared utilities for all zkVM implementations including:
-//! - Proof artifact serialization
-//! - Result display functions
-//! - Common output formatting
+//! - Proof artifact and proof bundle serialization (encodings in [`crate::format`])
+//! - Crash-safe file writes, also for paths beyond the Windows path length limit
+//! - Checksum sidecar files (`<file>.sha256`, in `sha256sum` format)
+//!
+//! Result display lives in [`crate::display`].
 
+use crate::format::{
+    decode_proof_artifact, decode_proof_bundle, encode_proof_artifact, encode_proof_bundle,
+    ArtifactFormat,
+};
+use crate::paths::{long_path, with_suffix};
+use crate::tee::TeeAttestation;
+use crate::types::ProverInput;
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
-use sigstore_verifier::types::result::{DigestAlgorithm, TimestampProof, VerificationResult};
-use std::fs;
-use std::path::Path;
+use sigstore_verifier::crypto::hash::sha256;
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Suffix of checksum sidecar files
+pub const CHECKSUM_SUFFIX: &str = ".sha256";
 
 /// Proof artifact structure for serialization
 ///
@@ -19,6 +33,10 @@ use std::path::Path;
 /// - circuit_version: The version of the zkVM circuit used
 /// - journal: Hex-encoded public output/journal from the guest program
 /// - proof: Hex-encoded proof bytes (e.g., Groth16 proof, Merkle proof)
+/// - tee_attestation: Optional TEE quote binding the artifact to the prover host
+/// - annotations: Plaintext of the annotations whose hash is committed in the journal
+/// - metadata: Prover settings the proof depends on but doesn't commit, such as Pico's
+///   field type, so that a proof isn't checked against the wrong verifier
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProofArtifact {
     pub zkvm: String,
@@ -26,17 +44,33 @@ pub struct ProofArtifact {
     pub circuit_version: String,
     pub journal: String,
     pub proof: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tee_attestation: Option<TeeAttestation>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub annotations: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub metadata: BTreeMap<String, String>,
+}
+
+impl ProofArtifact {
+    /// Decode the proven journal
+    pub fn journal(&self) -> Result<Vec<u8>> {
+        decode_hex(&self.journal).context("Invalid proof artifact journal")
+    }
 }
 
-/// Write a proof artifact to a JSON file
+/// Write a proof artifact to a file
 ///
 /// Creates the parent directory if it doesn't exist and writes the artifact
-/// as pretty-printed JSON.
+/// in `format` (pretty-printed JSON by default) with [`write_atomic`], so an
+/// interrupted run never leaves a truncated artifact behind.
 ///
 /// # Arguments
 ///
-/// * `output_path` - Path where the artifact JSON file will be written
+/// * `output_path` - Path where the artifact file will be written
 /// * `artifact` - The proof artifact to serialize
+/// * `format` - Encoding of the file
+/// * `checksum` - Also write a `<output_path>.sha256` sidecar (see [`write_checksum`])
 ///
 /// # Returns
 ///
@@ -51,151 +85,285 @@ pub struct ProofArtifact {
 ///     circuit_version: "1.0.0".to_string(),
 ///     journal: hex::encode(&journal_bytes),
 ///     proof: hex::encode(&proof_bytes),
+///     tee_attestation: None,
 /// };
-/// write_proof_artifact(Path::new("output/proof.json"), &artifact)?;
+/// write_proof_artifact(Path::new("output/proof.json"), &artifact, ArtifactFormat::Json, true)?;
 /// ```
-pub fn write_proof_artifact(output_path: &Path, artifact: &ProofArtifact) -> Result<()> {
+pub fn write_proof_artifact(
+    output_path: &Path,
+    artifact: &ProofArtifact,
+    format: ArtifactFormat,
+    checksum: bool,
+) -> Result<()> {
     // Create parent directories if they don't exist
-    if let Some(parent) = output_path.parent() {
-        fs::create_dir_all(parent)
-            .context(format!("Failed to create directory: {}", parent.display()))?;
-    }
+    create_parent_dir(output_path)?;
 
-    // Serialize to pretty JSON
-    let json = serde_json::to_string_pretty(artifact)
-        .context("Failed to serialize proof artifact")?;
+    let content = encode_proof_artifact(artifact, format)?;
 
     // Write to file
-    fs::write(output_path, json)
+    write_atomic(output_path, &content)
         .context(format!("Failed to write proof artifact to: {}", output_path.display()))?;
+    if checksum {
+        write_checksum(output_path, &content)?;
+    }
 
     println!("✓ Proof artifact written to: {}", output_path.display());
     Ok(())
 }
 
-/// Display verification result in a readable format
+/// Proof artifact bundled with the guest input it was generated for
 ///
-/// Prints the verification result with formatted output including:
-/// - Subject digest and algorithm
-/// - Signing time
-/// - Certificate hashes (leaf, intermediates, root)
-/// - OIDC identity information (if present)
-/// - Timestamp proof details (RFC 3161 or Rekor)
+/// The artifact fields are kept at the top level, so a proof bundle is also a
+/// valid proof artifact file. The input makes the proof reproducible: anyone can
+/// re-execute the guest on it and compare the journal with the proven one
+/// (`reproduce`), which needs no prover network and no trust in the prover host.
+///
+/// - artifact: The proof artifact
+/// - source: Where the Sigstore bundle came from (informational)
+/// - input: Hex-encoded guest input, exactly as passed to the guest
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProofBundle {
+    #[serde(flatten)]
+    pub artifact: ProofArtifact,
+    pub source: String,
+    pub input: String,
+}
+
+impl ProofBundle {
+    /// Bundle an artifact with its guest input
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the input cannot be encoded
+    pub fn new(artifact: ProofArtifact, source: String, input: &ProverInput) -> Result<Self> {
+        let input = input.encode_input().map_err(anyhow::Error::msg)?;
+        Ok(Self {
+            artifact,
+            source,
+            input: format!("0x{}", hex::encode(input)),
+        })
+    }
+
+    /// Decode the guest input
+    pub fn prover_input(&self) -> Result<ProverInput> {
+        let bytes = decode_hex(&self.input).context("Invalid proof bundle input")?;
+        ProverInput::parse_input(&bytes).map_err(anyhow::Error::msg)
+    }
+
+    /// Decode the proven journal
+    pub fn journal(&self) -> Result<Vec<u8>> {
+        decode_hex(&self.artifact.journal).context("Invalid proof bundle journal")
+    }
+}
+
+/// Write a proof bundle to a file
 ///
 /// # Arguments
 ///
-/// * `result` - The verification result to display
+/// * `output_path` - Path where the bundle file will be written
+/// * `bundle` - The proof bundle to serialize
+/// * `format` - Encoding of the file
+/// * `checksum` - Also write a `<output_path>.sha256` sidecar (see [`write_checksum`])
+pub fn write_proof_bundle(
+    output_path: &Path,
+    bundle: &ProofBundle,
+    format: ArtifactFormat,
+    checksum: bool,
+) -> Result<()> {
+    create_parent_dir(output_path)?;
+
+    let content = encode_proof_bundle(bundle, format)?;
+    write_atomic(output_path, &content)
+        .context(format!("Failed to write proof bundle to: {}", output_path.display()))?;
+    if checksum {
+        write_checksum(output_path, &content)?;
+    }
+
+    println!("✓ Proof bundle written to: {}", output_path.display());
+    Ok(())
+}
+
+/// Read a proof bundle written by [`write_proof_bundle`], in any format
 ///
-/// # Example
+/// If the bundle has a checksum sidecar, the content must match it.
+pub fn read_proof_bundle(path: &Path) -> Result<ProofBundle> {
+    let content = fs::read(long_path(path))
+        .context(format!("Failed to read proof bundle from: {}", path.display()))?;
+    check_checksum(path, &content)?;
+    decode_proof_bundle(&content)
+}
+
+/// Read a proof artifact written by [`write_proof_artifact`], in any format
 ///
-/// ```ignore
-/// let result = VerificationResult::from_slice(&journal)?;
-/// display_verification_result(&result);
-/// ```
-pub fn display_verification_result(result: &VerificationResult) {
-    println!("\n=== Verification Result ===");
-    println!(
-        "Subject digest: {} ({})",
-        hex::encode(&result.subject_digest),
-        format_digest_algorithm(&result.subject_digest_algorithm)
-    );
-    println!("Signing time:   {}", result.signing_time);
-
-    println!("\nCertificate Hashes:");
-    println!("  Leaf:   {}", hex::encode(result.certificate_hashes.leaf));
-    if !result.certificate_hashes.intermediates.is_empty() {
-        println!("  Intermediates:");
-        for (i, intermediate) in result.certificate_hashes.intermediates.iter().enumerate() {
-            println!("    [{}] {}", i, hex::encode(intermediate));
-        }
-    }
-    println!("  Root:   {}", hex::encode(result.certificate_hashes.root));
+/// Proof bundles are proof artifacts as well. If the file has a checksum
+/// sidecar, the content must match it.
+pub fn read_proof_artifact(path: &Path) -> Result<ProofArtifact> {
+    let content = fs::read(long_path(path))
+        .context(format!("Failed to read proof artifact from: {}", path.display()))?;
+    check_checksum(path, &content)?;
+    decode_proof_artifact(&content)
+        .or_else(|_| decode_proof_bundle(&content).map(|bundle| bundle.artifact))
+        .with_context(|| format!("Invalid proof artifact: {}", path.display()))
+}
 
-    if let Some(ref oidc) = result.oidc_identity {
-        println!("\nOIDC Identity:");
-        if let Some(ref issuer) = oidc.issuer {
-            println!("  Issuer:       {}", issuer);
-        }
-        if let Some(ref subject) = oidc.subject {
-            println!("  Subject:      {}", subject);
-        }
-        if let Some(ref workflow_ref) = oidc.workflow_ref {
-            println!("  Workflow:     {}", workflow_ref);
-        }
-        if let Some(ref repository) = oidc.repository {
-            println!("  Repository:   {}", repository);
-        }
-        if let Some(ref event_name) = oidc.event_name {
-            println!("  Event:        {}", event_name);
-        }
-    }
+/// Decode `0x`-prefixed or bare hex
+fn decode_hex(value: &str) -> Result<Vec<u8>> {
+    Ok(hex::decode(value.strip_prefix("0x").unwrap_or(value))?)
+}
 
-    // Display timestamp proof information
-    match &result.timestamp_proof {
-        TimestampProof::None => {
-            println!("\nTimestamp Proof: None");
-        }
-        TimestampProof::Rfc3161 {
-            tsa_chain_hashes,
-            message_imprint_algorithm,
-            message_imprint,
-        } => {
-            println!("\nTimestamp Proof: RFC 3161 (TSA)");
-            println!(
-                "  Message Imprint: {} ({})",
-                hex::encode(message_imprint),
-                format_digest_algorithm(message_imprint_algorithm)
-            );
-            println!("  TSA Certificate Chain:");
-            println!("    Leaf: {}", hex::encode(tsa_chain_hashes.leaf));
-            if !tsa_chain_hashes.intermediates.is_empty() {
-                println!("    Intermediates:");
-                for (i, intermediate) in tsa_chain_hashes.intermediates.iter().enumerate() {
-                    println!("      [{}] {}", i, hex::encode(intermediate));
-                }
-            }
-            println!("    Root: {}", hex::encode(tsa_chain_hashes.root));
-        }
-        TimestampProof::Rekor { log_id, log_index, entry_index } => {
-            println!("\nTimestamp Proof: Rekor (Transparency Log)");
-            println!("  Log ID:      {}", hex::encode(log_id));
-            println!("  Entry Index: {} (for API queries)", entry_index);
-            println!("  Log Index:   {} (tree leaf index for Merkle proof)", log_index);
-            println!("  Fetch URL:   https://rekor.sigstore.dev/api/v1/log/entries?logIndex={}", entry_index);
-        }
+/// Create the parent directory of `path` if it doesn't exist
+pub(crate) fn create_parent_dir(path: &Path) -> Result<()> {
+    match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => fs::create_dir_all(long_path(parent))
+            .context(format!("Failed to create directory: {}", parent.display())),
+        _ => Ok(()),
     }
 }
 
-/// Format a DigestAlgorithm as a human-readable string
-fn format_digest_algorithm(alg: &DigestAlgorithm) -> &'static str {
-    match alg {
-        DigestAlgorithm::Unknown => "Unknown",
-        DigestAlgorithm::Sha256 => "SHA-256",
-        DigestAlgorithm::Sha384 => "SHA-384",
+/// Write a file atomically
+///
+/// Writes next to the final path, flushes the content to disk and renames, so
+/// that readers (and later runs after a crash or power loss) see either the old
+/// or the new content, never a truncated file. The temporary file is
+/// `<file name>.partial`, so files that only differ in their extension do not
+/// share one; it is removed again if the write fails. On Windows the rename
+/// replaces an existing file as on Unix.
+pub fn write_atomic(path: &Path, content: &[u8]) -> Result<()> {
+    let target = long_path(path);
+    let partial_path = with_suffix(&target, ".partial");
+    let written = write_synced(&partial_path, content)
+        .with_context(|| format!("Failed to write {}", partial_path.display()))
+        .and_then(|()| {
+            fs::rename(&partial_path, &target)
+                .with_context(|| format!("Failed to write {}", path.display()))
+        });
+    if written.is_err() {
+        let _ = fs::remove_file(&partial_path);
     }
+    written?;
+    sync_parent_dir(&target)
+}
+
+/// Create or truncate `path`, write `content` and wait until it reached the disk
+fn write_synced(path: &Path, content: &[u8]) -> std::io::Result<()> {
+    let mut file = File::create(path)?;
+    file.write_all(content)?;
+    file.sync_all()
 }
 
-/// Display proof generation result summary
+/// Make a rename in the parent directory of `path` durable
 ///
-/// Prints a summary of the proof generation including journal and proof sizes.
+/// Only needed (and possible) on Unix; Windows commits the directory entry
+/// together with the file.
+fn sync_parent_dir(path: &Path) -> Result<()> {
+    if cfg!(unix) {
+        let parent = match path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent,
+            _ => Path::new("."),
+        };
+        File::open(parent)
+            .and_then(|dir| dir.sync_all())
+            .with_context(|| format!("Failed to sync directory: {}", parent.display()))?;
+    }
+    Ok(())
+}
+
+/// Remove `.partial` files left behind in `dir` by writes that were interrupted
 ///
-/// # Arguments
+/// Returns the number of files removed. A missing directory has none.
+pub fn remove_partial_files(dir: &Path) -> Result<usize> {
+    let entries = match fs::read_dir(long_path(dir)) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+        Err(e) => return Err(e).with_context(|| format!("Failed to list {}", dir.display())),
+    };
+    let mut removed = 0;
+    for entry in entries {
+        let path = entry.with_context(|| format!("Failed to list {}", dir.display()))?.path();
+        if path.extension().is_some_and(|extension| extension == "partial") {
+            fs::remove_file(&path)
+                .with_context(|| format!("Failed to remove {}", path.display()))?;
+            removed += 1;
+        }
+    }
+    Ok(removed)
+}
+
+/// Path of the checksum sidecar of `path`, e.g. `proof.json.sha256`
+pub fn checksum_path(path: &Path) -> PathBuf {
+    with_suffix(path, CHECKSUM_SUFFIX)
+}
+
+/// `sha256sum` line for `content` stored as `file_name`
+fn checksum_line(file_name: &str, content: &[u8]) -> String {
+    format!("{}  {}\n", hex::encode(sha256(content)), file_name)
+}
+
+/// Hex digest from the first line of a `sha256sum` file
+fn parse_checksum_line(line: &str) -> Option<&str> {
+    let digest = line.split_whitespace().next()?;
+    (digest.len() == 64 && digest.bytes().all(|b| b.is_ascii_hexdigit())).then_some(digest)
+}
+
+/// Write the checksum sidecar of `path` for `content` atomically
 ///
-/// * `journal` - The public output/journal bytes
-/// * `seal` - The proof bytes
-/// * `proof_type` - Description of the proof type (e.g., "Groth16", "Merkle", "Seal")
+/// The sidecar uses the `sha256sum` format, so the file can also be checked
+/// with `sha256sum -c` from its directory. Write the file itself first: a crash
+/// in between then leaves a stale sidecar, which [`check_checksum`] reports,
+/// rather than a file whose corruption would go unnoticed.
+pub fn write_checksum(path: &Path, content: &[u8]) -> Result<()> {
+    let file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy())
+        .unwrap_or_default();
+    write_atomic(&checksum_path(path), checksum_line(&file_name, content).as_bytes())
+}
+
+/// Check `content` read from `path` against its checksum sidecar, if there is one
 ///
-/// # Example
+/// # Errors
 ///
-/// ```ignore
-/// display_proof_result(&journal, &seal);
-/// ```
-pub fn display_proof_result(journal: &[u8], seal: &[u8]) {
-    println!("\n=== Proof Generation Result ===");
-    println!("Journal: {}", hex::encode(&journal));
-    if seal.is_empty() {
-        println!("<empty-proof> (DEV_MODE)");
-    } else {
-        println!("Proof: {}", hex::encode(&seal));
+/// Returns an error if the sidecar cannot be read or parsed, or if the digest
+/// differs
+pub fn check_checksum(path: &Path, content: &[u8]) -> Result<()> {
+    let sidecar = checksum_path(path);
+    let expected = match fs::read_to_string(long_path(&sidecar)) {
+        Ok(expected) => expected,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+        Err(e) => return Err(e).with_context(|| format!("Failed to read {}", sidecar.display())),
+    };
+    let expected = parse_checksum_line(&expected)
+        .with_context(|| format!("Invalid checksum file: {}", sidecar.display()))?;
+    let actual = hex::encode(sha256(content));
+    if !expected.eq_ignore_ascii_case(&actual) {
+        anyhow::bail!(
+            "{} does not match its checksum file (expected sha256 {}, got {})",
+            path.display(),
+            expected,
+            actual
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_checksum_line_roundtrip() {
+        let line = checksum_line("proof.json", b"{}");
+        assert_eq!(
+            line,
+            "44136fa355b3678a1146ad16f7e8649e94fb4fc21fe77e8310c060f61caaff8a  proof.json\n"
+        );
+        assert_eq!(parse_checksum_line(&line), Some(&line[..64]));
+    }
+
+    #[test]
+    fn test_parse_checksum_line_rejects_garbage() {
+        assert_eq!(parse_checksum_line(""), None);
+        assert_eq!(parse_checksum_line("abcd  proof.json"), None);
+        assert_eq!(parse_checksum_line(&format!("{}  proof.json", "z".repeat(64))), None);
     }
 }
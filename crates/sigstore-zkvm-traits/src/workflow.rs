@@ -1,29 +1,183 @@
 //! Workflow module for preparing zkVM guest inputs
 //!
 //! This module provides utilities to prepare input data for zkVM guest programs
-//! that verify Sigstore attestation bundles.
+//! that verify Sigstore attestation bundles, and to check the journal a prover
+//! returns against the same verification run natively on the host.
 
+use crate::resolver::{ArtifactRef, ResolverRegistry};
+use crate::traits::ZkVmProver;
 use crate::types::ProverInput;
-use anyhow::{Context, Result};
+use crate::utils::ProofBundle;
+use anyhow::{bail, Context, Result};
+use sigstore_verifier::outcome::VerificationOutcome;
+use sigstore_verifier::AttestationVerifier;
+use sigstore_verifier::fetcher::jsonl::governance::{load_signed_trusted_root, TrustRootKeys};
+use sigstore_verifier::fetcher::jsonl::types::TrustedRoot;
 use sigstore_verifier::fetcher::jsonl::parser::{
-    load_trusted_root_from_jsonl, select_certificate_authority, select_timestamp_authority,
+    certificate_authority_from_pem, select_certificate_authority, select_ctlog_keys, select_rekor_keys,
+    rekor_key_from_pem, select_timestamp_authority, select_tsa_roots, timestamp_authority_from_pem,
 };
-use sigstore_verifier::parser::bundle::{extract_bundle_timestamp, parse_bundle_from_path};
-use sigstore_verifier::types::certificate::FulcioInstance;
-use sigstore_verifier::types::result::VerificationOptions;
+use sigstore_verifier::parser::bundle::{extract_bundle_timestamp, parse_bundle_from_bytes};
+use sigstore_verifier::types::certificate::{CertificateChain, FulcioInstance};
+use sigstore_verifier::types::result::{annotations_hash, VerificationOptions, VerificationResult};
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+
+/// Load the governance keys trusted roots must be signed with
+///
+/// # Arguments
+///
+/// * `paths` - PEM files with one or more `PUBLIC KEY` blocks each
+///
+/// # Returns
+///
+/// The keys of all files; empty (plain JSONL accepted) when `paths` is empty
+///
+/// # Errors
+///
+/// Returns an error if a file cannot be read or holds no supported public key
+pub fn load_trust_root_keys(paths: &[PathBuf]) -> Result<TrustRootKeys> {
+    load_public_keys(paths, "trust root key")
+}
+
+/// Load the PEM public keys of `paths`, naming them `kind` in errors
+pub(crate) fn load_public_keys(paths: &[PathBuf], kind: &str) -> Result<TrustRootKeys> {
+    let mut keys = TrustRootKeys::default();
+    for path in paths {
+        let pem = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}: {}", kind, path.display()))?;
+        let mut file_keys = TrustRootKeys::from_pem(&pem)
+            .with_context(|| format!("Invalid {}: {}", kind, path.display()))?;
+        keys.append(&mut file_keys);
+    }
+    Ok(keys)
+}
+
+/// Read the policy owner's private key prover inputs are signed with
+///
+/// # Arguments
+///
+/// * `path` - PKCS#8 Ed25519 private key (PEM)
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be read or holds no Ed25519 private key
+pub fn load_policy_owner_key(path: &Path) -> Result<String> {
+    use ed25519_dalek::pkcs8::DecodePrivateKey;
+
+    let key_pem = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read policy owner key: {}", path.display()))?;
+    // Check the key before any input is prepared
+    ed25519_dalek::SigningKey::from_pkcs8_pem(&key_pem)
+        .map_err(|e| anyhow::anyhow!("Invalid policy owner key {}: {}", path.display(), e))?;
+    Ok(key_pem)
+}
+
+/// Where the certificate chains bundles are verified with come from
+pub enum TrustSource {
+    /// Trusted root JSONL file; the chains and log keys valid at signing time
+    /// are selected from it for each bundle
+    TrustedRoot {
+        path: PathBuf,
+        /// Governance keys the trusted root must be signed with (none for a plain JSONL file)
+        keys: TrustRootKeys,
+    },
+    /// Trusted roots already loaded, e.g. with [`Self::from_trusted_root_bytes`];
+    /// selected from like a trusted root file, without reading one
+    TrustedRoots(Vec<TrustedRoot>),
+    /// Fulcio and TSA chains used as given, e.g. loaded with [`Self::from_pem_files`]
+    ///
+    /// Rekor-timestamped bundles need `rekor_keys` (or the options' own): their
+    /// signed entry timestamps and checkpoints fail to verify without a log key.
+    /// No CT log keys come with the chains, so unless the options set
+    /// `ctlog_keys`, SCTs are not checked.
+    Chains {
+        fulcio_chain: CertificateChain,
+        tsa_chain: Option<CertificateChain>,
+        /// Trusted Rekor keys (DER SubjectPublicKeyInfo)
+        rekor_keys: Vec<Vec<u8>>,
+    },
+}
+
+impl TrustSource {
+    /// Load trusted roots held in memory, e.g. received by a service
+    ///
+    /// # Arguments
+    ///
+    /// * `content` - Trusted root JSONL, `trusted_root.json` or signed envelope, as in a trusted root file
+    /// * `keys` - Governance keys the trusted root must be signed with (none for plain JSONL)
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the content cannot be parsed or its signature does not verify
+    pub fn from_trusted_root_bytes(content: &[u8], keys: &TrustRootKeys) -> Result<Self> {
+        let content = std::str::from_utf8(content).context("Failed to parse trusted root as UTF-8")?;
+        let roots = load_signed_trusted_root(content, keys).context("Failed to load trusted root")?;
+        Ok(Self::TrustedRoots(roots))
+    }
+
+    /// Load the chains and Rekor key of PEM files, as served by the Fulcio, TSA and Rekor endpoints
+    ///
+    /// # Arguments
+    ///
+    /// * `fulcio_chain_path` - Fulcio chain, `[intermediates..., root]`
+    /// * `tsa_chain_path` - TSA chain, `[leaf, intermediates..., root]`, for bundles with RFC3161 timestamps
+    /// * `rekor_key_path` - Rekor public key, for bundles with Rekor timestamps
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a file cannot be read, or holds no certificate or no valid public key
+    pub fn from_pem_files(
+        fulcio_chain_path: &Path,
+        tsa_chain_path: Option<&Path>,
+        rekor_key_path: Option<&Path>,
+    ) -> Result<Self> {
+        let fulcio_chain = fs::read_to_string(fulcio_chain_path)
+            .with_context(|| format!("Failed to read Fulcio chain: {}", fulcio_chain_path.display()))
+            .and_then(|pem| {
+                certificate_authority_from_pem(&pem)
+                    .with_context(|| format!("Invalid Fulcio chain: {}", fulcio_chain_path.display()))
+            })?;
+        let tsa_chain = tsa_chain_path
+            .map(|path| {
+                fs::read_to_string(path)
+                    .with_context(|| format!("Failed to read TSA chain: {}", path.display()))
+                    .and_then(|pem| {
+                        timestamp_authority_from_pem(&pem)
+                            .with_context(|| format!("Invalid TSA chain: {}", path.display()))
+                    })
+            })
+            .transpose()?;
+        let rekor_keys = rekor_key_path
+            .map(|path| {
+                fs::read_to_string(path)
+                    .with_context(|| format!("Failed to read Rekor key: {}", path.display()))
+                    .and_then(|pem| {
+                        rekor_key_from_pem(&pem).with_context(|| format!("Invalid Rekor key: {}", path.display()))
+                    })
+            })
+            .transpose()?
+            .into_iter()
+            .collect();
+        Ok(Self::Chains {
+            fulcio_chain,
+            tsa_chain,
+            rekor_keys,
+        })
+    }
+}
 
 /// Prepare zkVM guest input from local files
 ///
 /// This function reads the Sigstore bundle, trusted root, and prepares all necessary
 /// data for the zkVM guest program to perform verification. It operates entirely on
-/// local files without requiring the fetcher feature.
+/// local files without requiring the fetcher feature; the bundle file is read and
+/// passed to [`prepare_guest_input`].
 ///
 /// # Arguments
 ///
 /// * `bundle_path` - Path to the Sigstore attestation bundle JSON file
-/// * `trusted_root_path` - Path to the trusted root JSONL file containing CA and TSA certificate chains
+/// * `trust` - Trusted roots or certificate chains to verify the bundle with
 /// * `options` - Verification options (expected digest, issuer, subject, etc.)
 ///
 /// # Returns
@@ -38,54 +192,110 @@ use std::path::Path;
 ///
 /// This function will return an error if:
 /// - The bundle file cannot be read or parsed
-/// - The trusted root file cannot be read or parsed
+/// - The trusted root file cannot be read or parsed, or its signature does not verify
 /// - The Fulcio instance cannot be auto-detected from the bundle
 /// - The appropriate certificate chains cannot be selected based on the bundle timestamp
 ///
 /// # Example
 ///
-/// ```ignore
-/// use sigstore_zkvm_traits::workflow::prepare_guest_input_local;
+/// ```no_run
+/// # fn main() -> anyhow::Result<()> {
+/// use sigstore_zkvm_traits::workflow::{prepare_guest_input_local, TrustSource};
+/// use sigstore_verifier::fetcher::jsonl::governance::TrustRootKeys;
 /// use sigstore_verifier::types::result::VerificationOptions;
-/// use std::path::Path;
+/// use std::path::{Path, PathBuf};
 ///
 /// let bundle_path = Path::new("samples/attestation.sigstore.json");
-/// let trusted_root_path = Path::new("samples/trusted_root.jsonl");
-/// let options = VerificationOptions {
-///     expected_digest: None,
-///     expected_issuer: None,
-///     expected_subject: None,
+/// let trust = TrustSource::TrustedRoot {
+///     path: PathBuf::from("samples/trusted_root.jsonl"),
+///     keys: TrustRootKeys::default(),
 /// };
+/// let options = VerificationOptions::builder()
+///     .expected_issuer("https://token.actions.githubusercontent.com")
+///     .build();
 ///
-/// let prover_input = prepare_guest_input_local(
-///     bundle_path,
-///     trusted_root_path,
-///     options
-/// )?;
+/// let prover_input = prepare_guest_input_local(bundle_path, &trust, options)?;
+/// # Ok(())
+/// # }
 /// ```
 pub fn prepare_guest_input_local(
     bundle_path: &Path,
-    trusted_root_path: &Path,
+    trust: &TrustSource,
     options: VerificationOptions,
 ) -> Result<ProverInput> {
     // Read the attestation bundle
     let bundle_json = fs::read(bundle_path)
         .context(format!("Failed to read bundle from: {}", bundle_path.display()))?;
 
+    prepare_guest_input(bundle_json, trust, options)
+}
+
+/// Prepare zkVM guest input from a bundle held in memory
+///
+/// Same as [`prepare_guest_input_local`], for bundles that were not read from a
+/// file (e.g. located by an [`ArtifactResolver`](crate::resolver::ArtifactResolver)
+/// or received by a service). With [`TrustSource::TrustedRoots`] or
+/// [`TrustSource::Chains`], no file is read at all.
+///
+/// # Arguments
+///
+/// * `bundle_json` - Sigstore attestation bundle JSON
+/// * `trust` - Trusted roots or certificate chains to verify the bundle with
+/// * `options` - Verification options (expected digest, issuer, subject, etc.)
+///
+/// # Errors
+///
+/// Same as [`prepare_guest_input_local`], except that no bundle file is read
+///
+/// # Example
+///
+/// ```ignore
+/// use sigstore_zkvm_traits::workflow::{prepare_guest_input, TrustSource};
+///
+/// let trust = TrustSource::from_trusted_root_bytes(&trusted_root_bytes, &TrustRootKeys::default())?;
+/// let prover_input = prepare_guest_input(bundle_bytes, &trust, VerificationOptions::default())?;
+/// ```
+pub fn prepare_guest_input(
+    bundle_json: Vec<u8>,
+    trust: &TrustSource,
+    options: VerificationOptions,
+) -> Result<ProverInput> {
+    // Chains given directly bypass the selection from a trusted root
+    if let TrustSource::Chains {
+        fulcio_chain,
+        tsa_chain,
+        rekor_keys,
+    } = trust
+    {
+        let mut options = options;
+        if options.rekor_keys.is_empty() {
+            options.rekor_keys = rekor_keys.clone();
+        }
+        return Ok(ProverInput::new(bundle_json, options, fulcio_chain.clone(), tsa_chain.clone()));
+    }
+
     // Auto-detect Fulcio instance from bundle
     let bundle_json_str = String::from_utf8(bundle_json.clone())
         .context("Failed to parse bundle as UTF-8")?;
     let fulcio_instance = FulcioInstance::from_bundle_json(&bundle_json_str)
         .map_err(|e| anyhow::anyhow!("Failed to detect Fulcio instance from bundle: {}", e))?;
 
-    // Load trusted roots for Fulcio and TSA
-    let trusted_root_content = fs::read_to_string(trusted_root_path)
-        .context(format!("Failed to read trusted root from: {}", trusted_root_path.display()))?;
-    let trust_roots = load_trusted_root_from_jsonl(&trusted_root_content)
-        .context("Failed to parse trusted root JSONL")?;
+    // Load trusted roots for Fulcio and TSA, unless already loaded
+    let loaded;
+    let trust_roots = match trust {
+        TrustSource::TrustedRoot { path, keys } => {
+            let trusted_root_content = fs::read_to_string(path)
+                .context(format!("Failed to read trusted root from: {}", path.display()))?;
+            loaded = load_signed_trusted_root(&trusted_root_content, keys)
+                .context("Failed to load trusted root")?;
+            &loaded
+        }
+        TrustSource::TrustedRoots(roots) => roots,
+        TrustSource::Chains { .. } => unreachable!("handled above"),
+    };
 
     // Parse the Sigstore bundle
-    let bundle = parse_bundle_from_path(bundle_path)
+    let bundle = parse_bundle_from_bytes(&bundle_json)
         .context("Failed to parse Sigstore bundle")?;
 
     // Extract timestamp from the bundle
@@ -93,17 +303,435 @@ pub fn prepare_guest_input_local(
         .context("Failed to extract timestamp from bundle")?;
 
     // Select the appropriate certificate chains based on Fulcio instance and timestamp
-    let fulcio_chain = select_certificate_authority(&trust_roots, &fulcio_instance, timestamp)
+    let fulcio_chain = select_certificate_authority(trust_roots, &fulcio_instance, timestamp)
         .context("Failed to select Fulcio certificate authority")?;
 
-    let tsa_chain = select_timestamp_authority(&trust_roots, &fulcio_instance, timestamp)
-        .context("Failed to select TSA certificate authority")?;
+    // A complete TSA chain embedded in an RFC 3161 timestamp is trusted if it roots in
+    // one of the trusted root's timestamp authorities, unless the caller supplied roots
+    let mut options = options;
+    if options.tsa_roots.is_empty() {
+        options.tsa_roots = select_tsa_roots(trust_roots, timestamp)
+            .context("Failed to select TSA roots")?;
+    }
+
+    // The instance's TSA chain is only needed for timestamps without a trusted embedded chain
+    let tsa_chain = match select_timestamp_authority(trust_roots, &fulcio_instance, timestamp) {
+        Ok(tsa_chain) => Some(tsa_chain),
+        Err(_) if !options.tsa_roots.is_empty() => None,
+        Err(e) => return Err(e).context("Failed to select TSA certificate authority"),
+    };
+
+    // Signed entry timestamps are verified with the Rekor keys valid at signing time,
+    // unless the caller supplied its own
+    if options.rekor_keys.is_empty() {
+        options.rekor_keys = select_rekor_keys(trust_roots, timestamp)
+            .context("Failed to select Rekor keys")?;
+    }
+
+    // Public-good certificates embed SCTs from the CT logs of the trusted root; the
+    // GitHub instance doesn't log to CT, so its certificates are not checked
+    if options.ctlog_keys.is_empty() && fulcio_instance == FulcioInstance::PublicGood {
+        options.ctlog_keys = select_ctlog_keys(trust_roots, timestamp)
+            .context("Failed to select CT log keys")?;
+    }
 
     // Create the ProverInput with properly selected certificate chains
     Ok(ProverInput::new(
         bundle_json,
         options,
         fulcio_chain,
-        Some(tsa_chain),
+        tsa_chain,
     ))
 }
+
+/// Prepare zkVM guest input for an artifact, locating its bundle with `registry`
+///
+/// The resolved subject digest and name are used as the expected digest and
+/// subject name unless `options` already sets them. When the resolver finds
+/// several bundles (e.g. provenance and SBOM attestations), the first one that
+/// verifies natively is used.
+///
+/// # Arguments
+///
+/// * `registry` - Resolvers to locate the bundles with
+/// * `artifact` - The artifact whose attestation is proven
+/// * `trust` - Trusted roots or certificate chains to verify the bundles with
+/// * `options` - Verification options
+///
+/// # Errors
+///
+/// Returns an error if no bundle is found for the artifact or none of them verifies
+///
+/// # Example
+///
+/// ```ignore
+/// use sigstore_zkvm_traits::resolver::{ArtifactRef, ResolverRegistry};
+/// use sigstore_zkvm_traits::workflow::prepare_guest_input_for_artifact;
+///
+/// let artifact = ArtifactRef { purl: Some("pkg:cargo/serde@1.0.0".to_string()), digest: None };
+/// let prover_input = prepare_guest_input_for_artifact(
+///     &ResolverRegistry::with_defaults(),
+///     &artifact,
+///     &trust,
+///     options,
+/// )?;
+/// ```
+pub fn prepare_guest_input_for_artifact(
+    registry: &ResolverRegistry,
+    artifact: &ArtifactRef,
+    trust: &TrustSource,
+    options: VerificationOptions,
+) -> Result<ProverInput> {
+    let resolved = registry.resolve(artifact)?;
+    let options = VerificationOptions {
+        expected_digest: options
+            .expected_digest
+            .or_else(|| resolved.digest.map(|digest| digest.to_vec())),
+        expected_subject_name: options.expected_subject_name.or(resolved.subject_name),
+        ..options
+    };
+
+    let count = resolved.bundles.len();
+    let mut failures = Vec::new();
+    for bundle_json in resolved.bundles {
+        let input = prepare_guest_input(bundle_json, trust, options.clone())
+            .and_then(|input| verify_guest_input(&input).map(|_| input));
+        match input {
+            Ok(input) => return Ok(input),
+            Err(e) => failures.push(format!("{:#}", e)),
+        }
+    }
+
+    bail!(
+        "None of the {} bundles found for {} verifies: {}",
+        count,
+        artifact,
+        failures.join("; ")
+    )
+}
+
+/// Pin the expected digest of a guest input to a local copy of the attested artifact
+///
+/// The file is streamed through the hash the input's bundle attests it with
+/// (SHA256, SHA384 or SHA512, see `AttestationVerifier::artifact_digest`) and
+/// its digest replaces `expected_digest`, so the proof only verifies if the
+/// bundle attests this very file.
+///
+/// # Errors
+///
+/// Returns an error if the artifact can't be read or the bundle can't be decoded
+pub fn pin_artifact_digest(input: &mut ProverInput, artifact_path: &Path) -> Result<()> {
+    let (digest, _) = AttestationVerifier::new()
+        .artifact_digest(artifact_path, &input.bundle_json, &input.verification_options)
+        .with_context(|| format!("Failed to hash artifact {}", artifact_path.display()))?;
+    input.verification_options.expected_digest = Some(digest);
+    Ok(())
+}
+
+/// Verify a guest input natively, exactly as the zkVM guests do
+///
+/// # Arguments
+///
+/// * `input` - The prepared guest input
+///
+/// # Returns
+///
+/// Returns the `VerificationResult` whose `as_slice()` the guest commits as its journal.
+///
+/// # Errors
+///
+/// Returns an error if the bundle fails verification
+pub fn verify_guest_input(input: &ProverInput) -> Result<VerificationResult> {
+    AttestationVerifier::new()
+        .verify_bundle_bytes(
+            &input.bundle_json,
+            input.verification_options.clone(),
+            &input.trust_bundle,
+            input.tsa_cert_chain.as_ref(),
+        )
+        .context("Native verification failed")
+}
+
+/// Verify a guest input natively, telling policy violations apart from invalid bundles
+///
+/// Verification is that of [`verify_guest_input`] (see
+/// `AttestationVerifier::verify_bundle_outcome`). The outcome does not depend on
+/// the input's `quarantine` option.
+pub fn verify_guest_input_outcome(input: &ProverInput) -> VerificationOutcome {
+    AttestationVerifier::new().verify_bundle_outcome(
+        &input.bundle_json,
+        input.verification_options.clone(),
+        &input.trust_bundle,
+        input.tsa_cert_chain.as_ref(),
+    )
+}
+
+/// The journal a guest commits for an input, computed natively
+///
+/// Verifies `input` with [`verify_guest_input`] and fills in what the guest
+/// adds outside verification: its crate version, and its commitments to the
+/// annotations, block anchor, previous result, input and policy owner. `as_slice()` of the
+/// result is byte for byte the journal a guest of `guest_version` commits, so
+/// fixtures for contract tests can be built without running a zkVM.
+///
+/// # Arguments
+///
+/// * `input` - The prepared guest input
+/// * `guest_version` - `CARGO_PKG_VERSION` of the guest program
+///
+/// # Errors
+///
+/// Returns an error if the bundle fails verification
+pub fn expected_journal(input: &ProverInput, guest_version: &str) -> Result<VerificationResult> {
+    let mut expected = verify_guest_input(input)?;
+    expected.guest_version = guest_version.to_string();
+    expected.annotations_hash = annotations_hash(&input.annotations);
+    expected.block_anchor = input.block_anchor;
+    expected.previous_result_hash = input.previous_result_hash;
+    expected.input_digest = input.digest().map_err(anyhow::Error::msg)?;
+    expected.policy_owner_hash = input.verify_policy_owner(None).map_err(anyhow::Error::msg)?;
+    Ok(expected)
+}
+
+/// Check the journal returned by a prover against native verification
+///
+/// The guest ELF is built separately from the host, so a guest compiled from a
+/// different `sigstore-verifier` version can commit a journal the host would not
+/// produce for the same input. Hosts call this after proving and before writing
+/// the artifact, so such a proof is never published.
+///
+/// # Arguments
+///
+/// * `input` - The guest input the proof was generated for
+/// * `journal` - The journal (public values) returned by the prover
+///
+/// # Errors
+///
+/// Returns an error if native verification fails, or if its journal differs from
+/// `journal`; the error names the differing fields when `journal` can be decoded.
+///
+/// # Example
+///
+/// ```ignore
+/// use sigstore_zkvm_traits::workflow::check_journal_consistency;
+///
+/// let (journal, seal) = prover.prove(&config, &prover_input).await?;
+/// check_journal_consistency(&prover_input, &journal)?;
+/// ```
+pub fn check_journal_consistency(input: &ProverInput, journal: &[u8]) -> Result<()> {
+    let actual = VerificationResult::from_slice(journal);
+
+    // Native verification runs outside any guest, so take the guest version from the journal
+    let guest_version = actual.as_ref().map(|actual| actual.guest_version.as_str()).unwrap_or_default();
+    let expected = expected_journal(input, guest_version)?;
+    if expected.as_slice() == journal {
+        return Ok(());
+    }
+
+    bail!(
+        "Journal returned by the prover does not match native verification ({}); \
+         the guest program was likely built from a different sigstore-verifier version than the host",
+        describe_journal_differences(&expected, actual, "host", "guest")
+    )
+}
+
+/// Execute the guest on an input and check its journal before proving
+///
+/// The preflight of bulk proving (see [`crate::pipeline`]): an input the guest
+/// fails on, or whose journal differs from native verification, is rejected
+/// before it is submitted to a prover.
+///
+/// # Returns
+///
+/// The journal the guest commits
+///
+/// # Errors
+///
+/// Returns an error if the guest fails or its journal does not match native verification
+pub fn preflight_guest_input<P: ZkVmProver>(prover: &P, input: &ProverInput) -> Result<Vec<u8>> {
+    crate::failpoint::inject(crate::failpoint::Stage::Emulation)?;
+    let journal = prover
+        .execute(input)
+        .map_err(|e| anyhow::anyhow!("Failed to execute guest program: {}", e))?;
+    check_journal_consistency(input, &journal)?;
+    Ok(journal)
+}
+
+/// Re-execute the guest for a proof bundle and check that it reproduces the proven journal
+///
+/// A deterministic audit path for an existing proof: the guest input recorded in
+/// the bundle is executed (not proven) with `prover`'s guest program, and the
+/// resulting journal must match the proven one byte for byte. The input is also
+/// verified natively, as after proving. This establishes what the proof attests
+/// without regenerating the SNARK; the proof itself is checked by the on-chain verifier.
+///
+/// # Arguments
+///
+/// * `prover` - Prover whose guest program produced the proof
+/// * `bundle` - The proof bundle written by `prove --proof-bundle`
+///
+/// # Returns
+///
+/// The reproduced verification result
+///
+/// # Errors
+///
+/// Returns an error if the bundle was generated by a different guest program, its
+/// annotations differ from those in the input, the guest fails, or the journals differ
+///
+/// # Example
+///
+/// ```ignore
+/// use sigstore_zkvm_traits::utils::read_proof_bundle;
+/// use sigstore_zkvm_traits::workflow::reproduce_proof_bundle;
+///
+/// let bundle = read_proof_bundle(Path::new("proof-bundle.json"))?;
+/// let result = reproduce_proof_bundle(&prover, &bundle)?;
+/// ```
+pub fn reproduce_proof_bundle<P: ZkVmProver>(
+    prover: &P,
+    bundle: &ProofBundle,
+) -> Result<VerificationResult> {
+    let program_id = prover
+        .program_identifier()
+        .map_err(|e| anyhow::anyhow!("Failed to get program identifier: {}", e))?;
+    let normalize = |id: &str| id.trim_start_matches("0x").to_ascii_lowercase();
+    if normalize(&program_id) != normalize(&bundle.artifact.program_id) {
+        bail!(
+            "Proof bundle was generated by program {}, but this host runs {}",
+            bundle.artifact.program_id,
+            program_id
+        );
+    }
+
+    let input = bundle.prover_input()?;
+    if input.annotations != bundle.artifact.annotations {
+        bail!("Annotations recorded in the proof bundle differ from those in its guest input");
+    }
+
+    let proven = bundle.journal()?;
+    let journal = prover
+        .execute(&input)
+        .map_err(|e| anyhow::anyhow!("Failed to execute guest program: {}", e))?;
+    if journal != proven {
+        let reproduced = VerificationResult::from_slice(&journal)
+            .map_err(|e| anyhow::anyhow!("Reproduced journal cannot be decoded: {}", e))?;
+        bail!(
+            "Re-executing the guest does not reproduce the proven journal ({})",
+            describe_journal_differences(
+                &reproduced,
+                VerificationResult::from_slice(&proven),
+                "reproduced",
+                "proven"
+            )
+        );
+    }
+
+    check_journal_consistency(&input, &journal)?;
+
+    VerificationResult::from_slice(&journal).map_err(anyhow::Error::msg)
+}
+
+/// Name the fields in which `actual` differs from `expected`, and the verifier
+/// versions of both, labelled for the message
+fn describe_journal_differences(
+    expected: &VerificationResult,
+    actual: Result<VerificationResult, String>,
+    expected_label: &str,
+    actual_label: &str,
+) -> String {
+    match actual {
+        Ok(actual) => {
+            let fields = [
+                ("certificate_hashes", expected.certificate_hashes == actual.certificate_hashes),
+                ("signing_time", expected.signing_time == actual.signing_time),
+                ("subject_digest", expected.subject_digest == actual.subject_digest),
+                (
+                    "subject_digest_algorithm",
+                    expected.subject_digest_algorithm == actual.subject_digest_algorithm,
+                ),
+                ("oidc_identity", expected.oidc_identity == actual.oidc_identity),
+                ("timestamp_proof", expected.timestamp_proof == actual.timestamp_proof),
+                ("options_hash", expected.options_hash == actual.options_hash),
+                ("subject_name", expected.subject_name == actual.subject_name),
+                ("verifier_version", expected.verifier_version == actual.verifier_version),
+                ("guest_version", expected.guest_version == actual.guest_version),
+                ("annotations_hash", expected.annotations_hash == actual.annotations_hash),
+                ("flags", expected.flags == actual.flags),
+                ("block_anchor", expected.block_anchor == actual.block_anchor),
+                ("previous_result_hash", expected.previous_result_hash == actual.previous_result_hash),
+                ("verification_options", expected.verification_options == actual.verification_options),
+                (
+                    "certificate_timestamps",
+                    expected.certificate_timestamps == actual.certificate_timestamps,
+                ),
+                ("input_digest", expected.input_digest == actual.input_digest),
+                ("predicate_type", expected.predicate_type == actual.predicate_type),
+                ("predicate_sha256", expected.predicate_sha256 == actual.predicate_sha256),
+                ("subject_kind", expected.subject_kind == actual.subject_kind),
+                ("policy_owner_hash", expected.policy_owner_hash == actual.policy_owner_hash),
+            ];
+            let differing: Vec<&str> = fields
+                .iter()
+                .filter(|(_, equal)| !equal)
+                .map(|(name, _)| *name)
+                .collect();
+            if differing.is_empty() {
+                "same fields, different encoding".to_string()
+            } else {
+                format!(
+                    "differs in {}; {} verifier {}, {} verifier {}",
+                    differing.join(", "),
+                    actual_label,
+                    actual.verifier_version,
+                    expected_label,
+                    expected.verifier_version
+                )
+            }
+        }
+        Err(e) => format!("journal cannot be decoded: {}", e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(name: &str) -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("../../samples").join(name)
+    }
+
+    #[test]
+    fn test_in_memory_input_matches_local() {
+        let bundle_path = sample("actions-attest-build-provenance-attestation-13532655.sigstore.json");
+        let trusted_root_path = sample("trusted_root.jsonl");
+        let keys = TrustRootKeys::default();
+
+        let local = prepare_guest_input_local(
+            &bundle_path,
+            &TrustSource::TrustedRoot {
+                path: trusted_root_path.clone(),
+                keys: TrustRootKeys::default(),
+            },
+            VerificationOptions::default(),
+        )
+        .unwrap();
+
+        let trust = TrustSource::from_trusted_root_bytes(&fs::read(&trusted_root_path).unwrap(), &keys).unwrap();
+        let in_memory =
+            prepare_guest_input(fs::read(&bundle_path).unwrap(), &trust, VerificationOptions::default()).unwrap();
+
+        assert_eq!(
+            serde_json::to_vec(&in_memory).unwrap(),
+            serde_json::to_vec(&local).unwrap()
+        );
+        verify_guest_input(&in_memory).unwrap();
+    }
+
+    #[test]
+    fn test_in_memory_trusted_root_must_parse() {
+        let keys = TrustRootKeys::default();
+        assert!(TrustSource::from_trusted_root_bytes(b"not a trusted root", &keys).is_err());
+        assert!(TrustSource::from_trusted_root_bytes(&[0xff, 0xfe], &keys).is_err());
+    }
+}
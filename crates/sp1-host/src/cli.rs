@@ -2,9 +2,29 @@
 //!
 //! Defines all CLI commands, subcommands, and arguments using clap.
 
-use clap::{Args, Parser, Subcommand, ValueEnum};
+use clap::{ArgGroup, Args, Parser, Subcommand, ValueEnum};
+use sigstore_verifier::types::result::BlockAnchor;
+use sigstore_zkvm_traits::env::{EnvVar, VarKind};
+use sigstore_zkvm_traits::format::ArtifactFormat;
+use sigstore_zkvm_traits::source::InputSource;
+use sigstore_zkvm_traits::tee::TeeKind;
+use sigstore_zkvm_traits::types::{parse_annotation, parse_block_anchor};
 use std::path::PathBuf;
 
+/// Environment variables read by sp1-host besides the common ones, listed by `--help-env`
+pub const ENV_VARS: &[EnvVar] = &[
+    EnvVar::new(
+        "SP1_DEV_MODE",
+        VarKind::Flag,
+        "Execute the guest without generating a proof (as DEV_MODE)",
+    ),
+    EnvVar::new(
+        "SP1_NETWORK_PRIVATE_KEY",
+        VarKind::Secret,
+        "SP1 network wallet key, hex-encoded (--network-private-key)",
+    ),
+];
+
 #[derive(Parser, Debug)]
 #[command(
     name = "sp1-host",
@@ -15,7 +35,16 @@ use std::path::PathBuf;
 )]
 pub struct Cli {
     #[command(subcommand)]
-    pub command: Commands,
+    /// Required unless --help-env is given
+    pub command: Option<Commands>,
+
+    /// Disable colored output (also disabled when NO_COLOR is set or stdout is not a terminal)
+    #[arg(long = "no-color", global = true)]
+    pub no_color: bool,
+
+    /// List the environment variables the host reads, with their current values (secrets masked)
+    #[arg(long = "help-env", exclusive = true)]
+    pub help_env: bool,
 }
 
 #[derive(Subcommand, Debug)]
@@ -26,30 +55,274 @@ pub enum Commands {
 
     /// Generate a proof of attestation verification
     Prove(ProveArgs),
+
+    /// Prepare (and sign) a guest input to be proven elsewhere with `prove-from-input`
+    Prepare(Box<PrepareArgs>),
+
+    /// Generate a proof from an input written by `prepare`
+    #[command(name = "prove-from-input")]
+    ProveFromInput(Box<ProveFromInputArgs>),
+
+    /// Re-execute the guest on a proof bundle and check it reproduces the proven journal
+    Reproduce(ReproduceArgs),
 }
 
 #[derive(Args, Debug)]
+#[command(group(ArgGroup::new("source").args(["bundle", "gav", "purl", "bundle_list"]).required(true)))]
 pub struct ProveArgs {
-    /// Path to the Sigstore attestation bundle JSON file
-    #[arg(long = "bundle", value_name = "PATH", required = true)]
-    pub bundle_path: PathBuf,
+    #[command(flatten)]
+    pub input: InputArgs,
 
-    /// Path to the trusted root JSONL file
-    #[arg(long = "trust-roots", value_name = "PATH", required = true)]
-    pub trust_roots_path: PathBuf,
+    /// File with one bundle source per line (as for --bundle; owner/repo@sha256:... is short for
+    /// github://); the bundles are proven as a pipeline and their proofs recorded in --store
+    #[arg(
+        long = "bundle-list",
+        value_name = "PATH",
+        requires = "store",
+        conflicts_with_all = [
+            "bundle", "gav", "purl", "artifact_file", "detached_payload", "output_path",
+            "proof_bundle", "tee_quote", "previous_artifact"
+        ]
+    )]
+    pub bundle_list: Option<PathBuf>,
 
-    /// Path to write the proof artifact JSON file
-    #[arg(long = "output", value_name = "PATH")]
-    pub output_path: Option<PathBuf>,
+    /// Threads preparing and emulating guest inputs with --bundle-list
+    #[arg(long = "workers", default_value_t = 4, value_name = "N")]
+    pub workers: usize,
+
+    /// Inputs queued between the stages of --bundle-list; bounds how far preparation
+    /// runs ahead of the prover
+    #[arg(long = "queue-depth", default_value_t = 2, value_name = "N")]
+    pub queue_depth: usize,
+
+    #[command(flatten)]
+    pub prover: ProverArgs,
+
+    #[command(flatten)]
+    pub output: OutputArgs,
+}
+
+#[derive(Args, Debug)]
+#[command(group(ArgGroup::new("source").args(["bundle", "gav", "purl"]).required(true)))]
+pub struct PrepareArgs {
+    #[command(flatten)]
+    pub input: InputArgs,
+
+    /// Path to write the prepared input to
+    #[arg(long = "output", value_name = "PATH", required = true)]
+    pub output_path: PathBuf,
+
+    /// PKCS#8 private key (P-256 or Ed25519, PEM) of the policy owner to sign the
+    /// prepared input with
+    #[arg(long = "key", value_name = "PEM")]
+    pub signing_key: Option<PathBuf>,
+
+    /// Key ID recorded with the signature, as a hint for verifiers
+    #[arg(long = "keyid", value_name = "ID", requires = "signing_key")]
+    pub keyid: Option<String>,
+}
+
+#[derive(Args, Debug)]
+pub struct ProveFromInputArgs {
+    /// Prepared input written by `prepare`
+    #[arg(long = "input", value_name = "PATH", required = true)]
+    pub input: PathBuf,
+
+    /// Public key (PEM) the prepared input must be signed with; unsigned or otherwise
+    /// signed inputs are refused. Repeat to accept several keys
+    #[arg(long = "input-key", value_name = "PEM")]
+    pub input_keys: Vec<PathBuf>,
+
+    #[command(flatten)]
+    pub prover: ProverArgs,
+
+    #[command(flatten)]
+    pub output: OutputArgs,
+}
+
+/// Bundle, trusted root and verification options a guest input is prepared from
+#[derive(Args, Debug)]
+pub struct InputArgs {
+    /// Sigstore attestation bundle JSON file (path, https:// URL, oci://registry/repo@sha256:...,
+    /// github://owner/repo@sha256:... or maven://group:artifact:version)
+    #[arg(long = "bundle", value_name = "SOURCE")]
+    pub bundle: Option<InputSource>,
+
+    /// Maven Central artifact (group:artifact:version[:classifier][@extension]); proves
+    /// the bundle published next to it, pinned to the artifact's digest
+    #[arg(long = "gav", value_name = "COORDINATES", conflicts_with = "purl")]
+    pub gav: Option<String>,
+
+    /// Package URL of the attested artifact (pkg:cargo/..., pkg:maven/..., pkg:github/owner/repo
+    /// or pkg:oci/...); its bundle is located by the matching artifact resolver
+    #[arg(long = "purl", value_name = "PURL")]
+    pub purl: Option<String>,
+
+    /// Local copy of the attested artifact; its digest is the expected subject digest
+    /// (required for pkg:github purls, which are looked up by digest)
+    #[arg(long = "artifact", value_name = "PATH")]
+    pub artifact_file: Option<PathBuf>,
+
+    /// Statement the DSSE envelope was signed over, for bundles shipped without their payload,
+    /// or the signed blob of a message signature bundle
+    #[arg(long = "detached-payload", value_name = "PATH")]
+    pub detached_payload: Option<PathBuf>,
+
+    /// Trusted root JSONL file (path or https:// URL)
+    #[arg(long = "trust-roots", value_name = "SOURCE", required_unless_present = "fulcio_chain_pem")]
+    pub trust_roots: Option<InputSource>,
+
+    /// Fulcio certificate chain (PEM, intermediates then root) to verify with instead
+    /// of a trusted root. No CT log keys come with it, so SCTs are not checked; bundles
+    /// with Rekor timestamps also need --rekor-key-pem
+    #[arg(
+        long = "fulcio-chain-pem",
+        value_name = "PATH",
+        conflicts_with_all = ["trust_roots", "trust_roots_sha256", "trust_roots_keys"]
+    )]
+    pub fulcio_chain_pem: Option<PathBuf>,
+
+    /// TSA certificate chain (PEM, signing certificate first) for bundles with
+    /// RFC3161 timestamps, with --fulcio-chain-pem
+    #[arg(
+        long = "tsa-chain-pem",
+        value_name = "PATH",
+        requires = "fulcio_chain_pem",
+        conflicts_with_all = ["trust_roots", "trust_roots_sha256", "trust_roots_keys"]
+    )]
+    pub tsa_chain_pem: Option<PathBuf>,
+
+    /// Rekor public key (PEM) for bundles with Rekor timestamps, with --fulcio-chain-pem;
+    /// their signed entry timestamps and checkpoints only verify with the log's key
+    #[arg(
+        long = "rekor-key-pem",
+        value_name = "PATH",
+        requires = "fulcio_chain_pem",
+        conflicts_with_all = ["trust_roots", "trust_roots_sha256", "trust_roots_keys"]
+    )]
+    pub rekor_key_pem: Option<PathBuf>,
+
+    /// Expected SHA256 of the bundle (hex); proving fails if the content differs
+    #[arg(long = "bundle-sha256", value_name = "HEX", requires = "bundle")]
+    pub bundle_sha256: Option<String>,
+
+    /// Expected SHA256 of the trusted root file (hex); proving fails if the content differs
+    #[arg(long = "trust-roots-sha256", value_name = "HEX")]
+    pub trust_roots_sha256: Option<String>,
+
+    /// Public key (PEM) the trusted root must be signed with; the trusted root is
+    /// then expected to be a signed envelope. Repeat to accept several keys
+    #[arg(long = "trust-roots-key", value_name = "PEM")]
+    pub trust_roots_keys: Vec<PathBuf>,
+
+    /// Directory for caching downloaded bundles and trusted roots
+    #[arg(long = "cache-dir", env = "SIGSTORE_CACHE_DIR", value_name = "PATH")]
+    pub cache_dir: Option<PathBuf>,
+
+    /// Re-download remote inputs even if a cached copy exists
+    #[arg(long = "refresh-cache")]
+    pub refresh_cache: bool,
+
+    /// Maximum age of the trusted root in hours; older (or expired) trusted roots are
+    /// refused, since they may still trust rotated or revoked authorities
+    #[arg(long = "max-trust-root-age", value_name = "HOURS", default_value_t = 168)]
+    pub max_trust_root_age: u64,
+
+    /// Prove even if the trusted root is stale
+    #[arg(long = "allow-stale-roots")]
+    pub allow_stale_roots: bool,
+
+    /// Archival verification: check every certificate against the proven signing time
+    /// only, so bundles whose chains have since expired still verify (recorded in the
+    /// journal flags)
+    #[arg(long = "archival")]
+    pub archival: bool,
+
+    /// Verify every DSSE signature in the envelope, not only the first, and require
+    /// each keyid to be the leaf key's fingerprint (recorded in the journal flags)
+    #[arg(long = "all-signatures")]
+    pub all_signatures: bool,
+
+    /// Accept a bundle that verifies but violates the expected digest, identity,
+    /// repository, predicate type or signing age, flagging the journal as a policy
+    /// violation instead of failing (recorded in the journal flags)
+    #[arg(long = "quarantine")]
+    pub quarantine: bool,
+
+    /// Expected source repository recorded in the signing certificate
+    /// (e.g. https://github.com/owner/repo)
+    #[arg(long = "expected-repository", value_name = "REPOSITORY")]
+    pub expected_repository: Option<String>,
+
+    /// Expected source ref recorded in the signing certificate (e.g. refs/tags/v1.0.0)
+    #[arg(long = "expected-workflow-ref", value_name = "REF")]
+    pub expected_workflow_ref: Option<String>,
+
+    /// Expected event that triggered the signing workflow (e.g. push)
+    #[arg(long = "expected-event-name", value_name = "EVENT")]
+    pub expected_event_name: Option<String>,
+
+    /// Expected in-toto predicate type (e.g. https://slsa.dev/provenance/v1)
+    #[arg(long = "expected-predicate-type", value_name = "URI")]
+    pub expected_predicate_type: Option<String>,
+
+    /// Require an RFC 3161 timestamp issued under this TSA policy (e.g. 1.3.6.1.4.1.57264.2)
+    #[arg(long = "tsa-policy", value_name = "OID")]
+    pub expected_tsa_policy: Option<String>,
+
+    /// Reject signatures more than SECS old when the input is prepared; the limit and the
+    /// time it is measured at are committed in the options hash
+    #[arg(long = "max-signing-age", value_name = "SECS")]
+    pub max_signing_age: Option<u64>,
+
+    /// Verification profile to apply (e.g. github-strict, or github-strict@1 to pin the
+    /// version); its constraints and name@version are committed in the options hash
+    #[arg(long = "profile", value_name = "NAME[@VERSION]")]
+    pub profile: Option<String>,
+
+    /// Repository policies file (YAML); the policy of the bundle's repository is enforced
+    /// and its hash committed in the options hash
+    #[arg(long = "policies", value_name = "PATH")]
+    pub policies: Option<PathBuf>,
 
-    /// SP1 network private key (hex-encoded)
+    /// Annotation committed in the journal and recorded in the artifact (repeatable),
+    /// e.g. build=1234 or env=prod
+    #[arg(long = "annotation", value_name = "KEY=VALUE", value_parser = parse_annotation)]
+    pub annotations: Vec<(String, String)>,
+
+    /// Recent block of the chain the proof is submitted to (NUMBER:HASH); the guest commits it
+    /// in the journal, so a contract can check that the proof was generated after that block
+    #[arg(long = "anchor-block", value_name = "NUMBER:HASH", value_parser = parse_block_anchor)]
+    pub anchor_block: Option<BlockAnchor>,
+
+    /// Proof artifact of the previous attestation about the same subject; the guest commits
+    /// the hash of its journal, linking the two proofs into a chain of custody
+    #[arg(long = "previous-artifact", value_name = "PATH")]
+    pub previous_artifact: Option<PathBuf>,
+
+    /// PKCS#8 Ed25519 private key (PEM) of the policy owner to sign the input with; the
+    /// guest verifies the signature and commits the hash of the owner's public key
+    #[arg(long = "policy-owner-key", value_name = "PEM")]
+    pub policy_owner_key: Option<PathBuf>,
+}
+
+/// Prover settings, shared by `prove` and `prove-from-input`
+#[derive(Args, Debug)]
+pub struct ProverArgs {
+    /// SP1 network private key (hex-encoded), required unless --local
     #[arg(
         long = "network-private-key",
         env = "SP1_NETWORK_PRIVATE_KEY",
         value_name = "WALLET_KEY",
-        hide_env_values = true
+        hide_env_values = true,
+        required_unless_present = "local"
     )]
-    pub private_key: String,
+    pub private_key: Option<String>,
+
+    /// Prove on this machine instead of the SP1 network, with shards sized to
+    /// the execution and available memory
+    #[arg(long = "local")]
+    pub local: bool,
 
     /// Proving mode
     #[arg(
@@ -61,6 +334,45 @@ pub struct ProveArgs {
     pub mode: ProvingMode,
 }
 
+/// Where and how proof artifacts are written
+#[derive(Args, Debug)]
+#[command(group(ArgGroup::new("artifact").args(["output_path", "store", "proof_bundle"]).multiple(true)))]
+pub struct OutputArgs {
+    /// Path to write the proof artifact file
+    #[arg(long = "output", value_name = "PATH")]
+    pub output_path: Option<PathBuf>,
+
+    /// Attestation store directory; the proof artifact is recorded for the bundle
+    #[arg(long = "store", env = "SIGSTORE_STORE_DIR", value_name = "PATH")]
+    pub store: Option<PathBuf>,
+
+    /// Path to write a proof bundle: the proof artifact together with the guest input,
+    /// so that the proof can be checked with `reproduce`
+    #[arg(long = "proof-bundle", value_name = "PATH")]
+    pub proof_bundle: Option<PathBuf>,
+
+    /// Write a `.sha256` checksum file (sha256sum format) next to the proof artifact
+    /// and proof bundle; a proof bundle that no longer matches it is refused by `reproduce`
+    #[arg(long = "checksum")]
+    pub checksum: bool,
+
+    /// Encoding of the proof artifact and proof bundle files (json, cbor or cbor-zstd);
+    /// the binary formats store journal, proof and input as raw bytes. Readers detect the format
+    #[arg(long = "artifact-format", value_name = "FORMAT", default_value = "json")]
+    pub artifact_format: ArtifactFormat,
+
+    /// Attach a quote from the TEE the host runs in (tdx or sgx) to the proof artifact
+    #[arg(long = "tee-quote", value_name = "TEE", requires = "artifact")]
+    pub tee_quote: Option<TeeKind>,
+}
+
+#[derive(Args, Debug)]
+pub struct ReproduceArgs {
+    /// Proof bundle file written by `prove --proof-bundle` (JSON, CBOR or zstd-compressed CBOR)
+    #[arg(long = "proof-bundle", value_name = "PATH", required = true)]
+    pub proof_bundle: PathBuf,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
 pub enum ProvingMode {
     /// Compressed SNARK proof
@@ -2,13 +2,14 @@
 //!
 //! Defines configuration structures for different proving strategies and modes.
 
-use crate::cli::{ProveArgs, ProvingMode};
+use crate::cli::{ProverArgs, ProvingMode};
 
 /// SP1 prover configuration
 #[derive(Debug, Clone)]
 pub struct Sp1Config {
     pub proving_mode: ProvingMode,
-    pub private_key: String
+    /// SP1 network wallet key; `None` proves locally
+    pub private_key: Option<String>,
 }
 
 impl Sp1Config {
@@ -16,15 +17,15 @@ impl Sp1Config {
     ///
     /// # Arguments
     ///
-    /// * `args` - The prove command arguments
+    /// * `args` - The prover arguments of the prove or prove-from-input command
     ///
     /// # Returns
     ///
     /// Returns a Sp1Config with the appropriate strategy and parameters.
-    pub fn from_cli_args(args: &ProveArgs) -> Self {
+    pub fn from_cli_args(args: &ProverArgs) -> Self {
         Sp1Config {
             proving_mode: args.mode,
-            private_key: args.private_key.clone(),
+            private_key: if args.local { None } else { args.private_key.clone() },
         }
     }
 }
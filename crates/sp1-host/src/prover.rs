@@ -4,16 +4,75 @@
 //! capabilities for Sigstore attestation verification.
 
 use crate::config::Sp1Config;
+use crate::proving::local::prove_locally;
 use crate::proving::network::prove_with_network;
 use async_trait::async_trait;
+use sigstore_zkvm_traits::env;
 use sigstore_zkvm_traits::error::ZkVmError;
+use sigstore_zkvm_traits::failpoint::{self, Stage};
+use sigstore_zkvm_traits::setup::SetupLock;
+use sigstore_zkvm_traits::sizing::{self, ProvingPlan};
 use sigstore_zkvm_traits::traits::ZkVmProver;
 use sigstore_zkvm_traits::types::ProverInput;
-use sp1_sdk::{EnvProver, HashableKey, Prover, ProverClient, SP1Stdin};
-use sugstore_sp1_methods::{vk, SP1_SIGSTORE_ELF};
+use sp1_sdk::{
+    EnvProver, HashableKey, Prover, ProverClient, SP1ProvingKey, SP1Stdin, SP1VerifyingKey,
+};
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock};
+use sugstore_sp1_methods::SP1_SIGSTORE_ELF;
+
+/// Directory the SP1 SDK shares its artifacts in, whose setup lock key setup takes
+fn sp1_dir() -> PathBuf {
+    let home = if cfg!(windows) { "USERPROFILE" } else { "HOME" };
+    std::env::var_os(home)
+        .map(PathBuf::from)
+        .unwrap_or_else(std::env::temp_dir)
+        .join(".sp1")
+}
 
 pub struct Sp1Prover {
     elf: &'static [u8],
+    /// Proving and verifying keys, set up on first use
+    keys: OnceLock<(SP1ProvingKey, SP1VerifyingKey)>,
+    /// Shard sizing of the last local proof
+    last_plan: Mutex<Option<ProvingPlan>>,
+}
+
+impl Sp1Prover {
+    /// Proving and verifying keys of the guest program
+    ///
+    /// Set up once per prover. Setups in other jobs or processes are
+    /// serialized with this one on the setup lock of `~/.sp1`.
+    fn keys(&self) -> Result<&(SP1ProvingKey, SP1VerifyingKey), ZkVmError> {
+        if let Some(keys) = self.keys.get() {
+            return Ok(keys);
+        }
+        let _setup_lock = SetupLock::acquire(&sp1_dir())
+            .map_err(|e| ZkVmError::ZkVmImplementationError(format!("{:#}", e)))?;
+        Ok(self.keys.get_or_init(|| EnvProver::new().setup(self.elf)))
+    }
+
+    /// Shard sizing of the last proof generated locally, for its artifact metadata
+    pub fn last_plan(&self) -> Option<ProvingPlan> {
+        *self.last_plan.lock().unwrap()
+    }
+
+    /// Execute the guest, returning its public values and cycle count
+    fn run(&self, stdin: &SP1Stdin) -> Result<(Vec<u8>, u64), ZkVmError> {
+        let client = EnvProver::new();
+        let (public_values, report) = client.execute(self.elf, stdin).run().map_err(|e| {
+            ZkVmError::ProofGenerationError(format!("Failed to execute guest program: {}", e))
+        })?;
+
+        // Steps marked by a guest built with cycle profiling, in the guest's report format
+        let mut tracked: Vec<_> = report.cycle_tracker.iter().collect();
+        tracked.sort();
+        for (step, cycles) in tracked {
+            eprintln!("cycle-profile: {} cycles={}", step, cycles);
+        }
+
+        Ok((public_values.to_vec(), report.total_instruction_count()))
+    }
 }
 
 #[async_trait]
@@ -23,6 +82,8 @@ impl ZkVmProver for Sp1Prover {
     fn new() -> Result<Self, ZkVmError> {
         Ok(Sp1Prover {
             elf: SP1_SIGSTORE_ELF,
+            keys: OnceLock::new(),
+            last_plan: Mutex::new(None),
         })
     }
 
@@ -36,8 +97,11 @@ impl ZkVmProver for Sp1Prover {
             .encode_input()
             .map_err(|e| ZkVmError::InvalidInput(format!("Failed to encode ProverInput: {}", e)))?;
 
-        // Log verifying key hash
-        let vk = vk(self.elf);
+        *self.last_plan.lock().unwrap() = None;
+
+        // Log verifying key hash; the keys are set up before SP1_PROVER is switched
+        // to the network below
+        let (pk, vk) = self.keys()?;
         let vk_hash = vk.bytes32();
         println!("Verifying Key Hash: {}", vk_hash);
         println!("SP1 Version: {}", Self::circuit_version());
@@ -47,33 +111,46 @@ impl ZkVmProver for Sp1Prover {
         stdin.write_vec(input_bytes.clone());
 
         // Check for DEV_MODE
-        if std::env::var("DEV_MODE").is_ok() || std::env::var("SP1_DEV_MODE").is_ok() {
+        let env = env::config();
+        if env.dev_mode || env.flag("SP1_DEV_MODE") {
             println!("⚠ Running in DEV_MODE - no proof will be generated");
-            let client = EnvProver::new();
-            let (public_values, _) = client.execute(self.elf, &stdin).run().map_err(|e| {
-                ZkVmError::ProofGenerationError(format!("Failed to execute guest program: {}", e))
-            })?;
-            return Ok((public_values.to_vec(), vec![]));
+            failpoint::inject(Stage::Emulation)?;
+            return Ok((self.execute(input)?, vec![]));
         }
 
+        // Without a network key, prove on this machine with shards sized to the execution
+        let Some(sp1_network_key) = config.private_key.as_deref() else {
+            failpoint::inject(Stage::Emulation)?;
+            let (_, cycles) = self.run(&stdin)?;
+            let plan = ProvingPlan::sp1(cycles, sizing::available_memory());
+            *self.last_plan.lock().unwrap() = Some(plan);
+            return prove_locally(pk, stdin, config.proving_mode, &plan);
+        };
+
         // Set up SP1 environment variables
         std::env::set_var("SP1_PROVER", "network");
-
-        // Get private key from config or environment
-        let sp1_network_key = config.private_key.as_str();
         std::env::set_var("NETWORK_PRIVATE_KEY", sp1_network_key);
 
         let client = ProverClient::builder()
             .network_for(sp1_sdk::network::NetworkMode::Mainnet)
             .build();
 
-        // Get proving key for proof generation
-        let (pk, _) = client.setup(self.elf);
-        prove_with_network(&client, &pk, stdin, config.proving_mode).await
+        prove_with_network(&client, pk, stdin, config.proving_mode).await
+    }
+
+    fn execute(&self, input: &ProverInput) -> Result<Vec<u8>, ZkVmError> {
+        let input_bytes = input
+            .encode_input()
+            .map_err(|e| ZkVmError::InvalidInput(format!("Failed to encode ProverInput: {}", e)))?;
+
+        let mut stdin = SP1Stdin::new();
+        stdin.write_vec(input_bytes);
+
+        Ok(self.run(&stdin)?.0)
     }
 
     fn program_identifier(&self) -> Result<String, ZkVmError> {
-        let vk = vk(self.elf);
+        let (_, vk) = self.keys()?;
         Ok(format!("{}", vk.bytes32()))
     }
 
@@ -0,0 +1,58 @@
+//! Local SP1 proving
+//!
+//! Proves the guest on this machine with the CPU prover, sized by a
+//! [`ProvingPlan`] of the preflight execution.
+
+use crate::cli::ProvingMode;
+use sigstore_zkvm_traits::error::ZkVmError;
+use sigstore_zkvm_traits::sizing::ProvingPlan;
+use sp1_sdk::{Prover, ProverClient, SP1ProvingKey, SP1Stdin};
+
+/// Generate a proof locally
+///
+/// The SP1 SDK reads its shard configuration from the environment, so
+/// `SHARD_SIZE` and `SHARD_BATCH_SIZE` are set from the plan before proving.
+///
+/// # Arguments
+///
+/// * `pk` - SP1 proving key
+/// * `stdin` - Input data for the guest program (consumed)
+/// * `mode` - Proving mode (Compressed, Groth16, Plonk)
+/// * `plan` - Shard sizing for this input
+///
+/// # Returns
+///
+/// Returns (public_values, proof_bytes) on success.
+///
+/// # Errors
+///
+/// Returns an error if proof generation fails, e.g. runs out of memory.
+pub fn prove_locally(
+    pk: &SP1ProvingKey,
+    stdin: SP1Stdin,
+    mode: ProvingMode,
+    plan: &ProvingPlan,
+) -> Result<(Vec<u8>, Vec<u8>), ZkVmError> {
+    println!(
+        "🖥  Proving locally: {} cycles in {} shard(s) of 2^{} cycles, {} at a time",
+        plan.cycles,
+        plan.segments(),
+        plan.segment_po2,
+        plan.batch_size
+    );
+    std::env::set_var("SHARD_SIZE", plan.segment_size().to_string());
+    std::env::set_var("SHARD_BATCH_SIZE", plan.batch_size.to_string());
+
+    let client = ProverClient::builder().cpu().build();
+    let builder = client.prove(pk, &stdin);
+    let builder = match mode {
+        ProvingMode::Compressed => builder.compressed(),
+        ProvingMode::Groth16 => builder.groth16(),
+        ProvingMode::Plonk => builder.plonk(),
+    };
+    let proof = builder.run().map_err(|e| {
+        ZkVmError::ProofGenerationError(format!("Failed to generate {:?} proof: {}", mode, e))
+    })?;
+    println!("✓ Proven locally");
+    Ok((proof.public_values.to_vec(), proof.bytes()))
+}
@@ -1,2 +1,3 @@
 //! Proving implementations for different strategies
+pub mod local;
 pub mod network;
\ No newline at end of file
@@ -4,6 +4,7 @@
 
 use crate::cli::ProvingMode;
 use sigstore_zkvm_traits::error::ZkVmError;
+use sigstore_zkvm_traits::failpoint::{self, Stage};
 use sp1_sdk::{NetworkProver, SP1ProvingKey, SP1Stdin, network::FulfillmentStrategy};
 
 /// Generate a proof using the SP1 proving network
@@ -35,6 +36,7 @@ pub async fn prove_with_network(
 ) -> Result<(Vec<u8>, Vec<u8>), ZkVmError> {
     println!("🔗 Connecting to SP1 network...");
     println!("🚀 Submitting proof request to SP1 network...");
+    failpoint::inject(Stage::Submission)?;
 
     match mode {
         ProvingMode::Compressed => {
@@ -48,6 +50,8 @@ pub async fn prove_with_network(
                 .map_err(|e| {
                     ZkVmError::ProofGenerationError(format!("Failed to generate compressed proof: {}", e))
                 })?;
+            // The SDK submits and waits in one call; a failed wait loses the proof
+            failpoint::inject(Stage::FulfillmentWait)?;
             println!("✓ Compressed proof generated successfully!");
             Ok((proof.public_values.to_vec(), proof.bytes()))
         }
@@ -62,6 +66,8 @@ pub async fn prove_with_network(
                 .map_err(|e| {
                     ZkVmError::ProofGenerationError(format!("Failed to generate Groth16 proof: {}", e))
                 })?;
+            // The SDK submits and waits in one call; a failed wait loses the proof
+            failpoint::inject(Stage::FulfillmentWait)?;
             println!("✓ Groth16 proof generated successfully!");
             Ok((proof.public_values.to_vec(), proof.bytes()))
         }
@@ -76,6 +82,8 @@ pub async fn prove_with_network(
                 .map_err(|e| {
                     ZkVmError::ProofGenerationError(format!("Failed to generate Plonk proof: {}", e))
                 })?;
+            // The SDK submits and waits in one call; a failed wait loses the proof
+            failpoint::inject(Stage::FulfillmentWait)?;
             println!("✓ Plonk proof generated successfully!");
             Ok((proof.public_values.to_vec(), proof.bytes()))
         }
@@ -3,6 +3,17 @@ use sp1_sdk::SP1_CIRCUIT_VERSION;
 
 fn main() {
     let use_docker = std::env::var("USE_DOCKER").is_ok();
+    // Profiling changes the verifying key, so it is opt-in at build time
+    println!("cargo:rerun-if-env-changed=SIGSTORE_ALLOC_PROFILE");
+    println!("cargo:rerun-if-env-changed=SIGSTORE_CYCLE_PROFILE");
+    let features = [
+        ("SIGSTORE_ALLOC_PROFILE", "alloc-profile"),
+        ("SIGSTORE_CYCLE_PROFILE", "cycle-profile"),
+    ]
+    .into_iter()
+    .filter(|(var, _)| std::env::var(var).is_ok())
+    .map(|(_, feature)| feature.to_string())
+    .collect();
     build_program_with_args(
         "./program",
         BuildArgs {
@@ -10,6 +21,7 @@ fn main() {
             elf_name: Some("sigstore-verifier-sp1-elf".to_string()),
             docker: use_docker,
             tag: SP1_CIRCUIT_VERSION.to_string(),
+            features,
             ..Default::default()
         },
     )
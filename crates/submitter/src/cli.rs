@@ -0,0 +1,140 @@
+//! Command-line interface definitions for submitter
+//!
+//! Defines all CLI commands, subcommands, and arguments using clap.
+
+use clap::{Args, Parser, Subcommand};
+use std::path::PathBuf;
+
+#[derive(Parser, Debug)]
+#[command(
+    name = "submitter",
+    author,
+    version,
+    about = "Submit proof artifacts to SigstoreAttestationVerifier contracts",
+    long_about = "Submit one proof artifact to every configured (chain, contract) target concurrently, with per-chain gas settings, and report the receipts of all submissions."
+)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Commands,
+
+    /// Disable colored output (also disabled when NO_COLOR is set or stdout is not a terminal)
+    #[arg(long = "no-color", global = true)]
+    pub no_color: bool,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Commands {
+    /// Submit a proof artifact to all configured targets
+    Submit(SubmitArgs),
+
+    /// Queue proof artifacts for batch submission to the configured targets
+    Enqueue(EnqueueArgs),
+
+    /// Send, fee-bump and confirm the queued submissions
+    Process(ProcessArgs),
+}
+
+#[derive(Args, Debug)]
+pub struct SubmitArgs {
+    /// Proof artifact file written by a host's `prove --output` (or a proof bundle), in any artifact format
+    #[arg(long = "artifact", value_name = "PATH", required = true)]
+    pub artifact: PathBuf,
+
+    /// Targets TOML file listing the chains and verifier contracts to submit to
+    #[arg(long = "targets", value_name = "PATH", required = true)]
+    pub targets: PathBuf,
+
+    /// Submit only to the named targets (repeatable); all targets by default
+    #[arg(long = "only", value_name = "NAME")]
+    pub only: Vec<String>,
+
+    /// Private key of the submitting account (hex-encoded), used on every chain
+    #[arg(
+        long = "private-key",
+        env = "SUBMITTER_PRIVATE_KEY",
+        value_name = "WALLET_KEY",
+        hide_env_values = true
+    )]
+    pub private_key: String,
+
+    /// Seconds to wait for each transaction to be mined
+    #[arg(long = "receipt-timeout", default_value_t = 300, value_name = "SECONDS")]
+    pub receipt_timeout: u64,
+
+    /// Simulate and estimate gas on every target without sending any transaction
+    #[arg(long = "dry-run")]
+    pub dry_run: bool,
+
+    /// Path to write the consolidated receipt report JSON file
+    #[arg(long = "report", value_name = "PATH")]
+    pub report: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+pub struct EnqueueArgs {
+    /// Proof artifact file to queue (repeatable)
+    #[arg(long = "artifact", value_name = "PATH", required = true)]
+    pub artifacts: Vec<PathBuf>,
+
+    /// Targets TOML file listing the chains and verifier contracts to submit to
+    #[arg(long = "targets", value_name = "PATH", required = true)]
+    pub targets: PathBuf,
+
+    /// Queue only for the named targets (repeatable); all targets by default
+    #[arg(long = "only", value_name = "NAME")]
+    pub only: Vec<String>,
+
+    /// Directory holding the submission queue (default: `<cache dir>/submissions`)
+    #[arg(long = "queue", env = "SUBMITTER_QUEUE", value_name = "PATH")]
+    pub queue: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+pub struct ProcessArgs {
+    /// Targets TOML file listing the chains and verifier contracts to submit to
+    #[arg(long = "targets", value_name = "PATH", required = true)]
+    pub targets: PathBuf,
+
+    /// Directory holding the submission queue (default: `<cache dir>/submissions`)
+    #[arg(long = "queue", env = "SUBMITTER_QUEUE", value_name = "PATH")]
+    pub queue: Option<PathBuf>,
+
+    /// Private key of the submitting account (hex-encoded), used on every chain
+    #[arg(
+        long = "private-key",
+        env = "SUBMITTER_PRIVATE_KEY",
+        value_name = "WALLET_KEY",
+        hide_env_values = true
+    )]
+    pub private_key: String,
+
+    /// Maximum number of sent, unconfirmed submissions per chain
+    #[arg(long = "max-in-flight", default_value_t = 16, value_name = "COUNT")]
+    pub max_in_flight: usize,
+
+    /// Seconds after which an unmined transaction is replaced with higher fees
+    #[arg(long = "bump-after", default_value_t = 60, value_name = "SECONDS")]
+    pub bump_after: u64,
+
+    /// Percentage both EIP-1559 fees are raised by per replacement (nodes require at least 10)
+    #[arg(
+        long = "bump-percent",
+        default_value_t = 20,
+        value_name = "PERCENT",
+        value_parser = clap::value_parser!(u64).range(10..)
+    )]
+    pub bump_percent: u64,
+
+    /// Number of blocks (including its own) after which a transaction counts as confirmed
+    #[arg(
+        long = "confirmations",
+        default_value_t = 1,
+        value_name = "BLOCKS",
+        value_parser = clap::value_parser!(u64).range(1..)
+    )]
+    pub confirmations: u64,
+
+    /// Seconds between rounds of tracking, fee bumping and sending
+    #[arg(long = "poll-interval", default_value_t = 5, value_name = "SECONDS")]
+    pub poll_interval: u64,
+}
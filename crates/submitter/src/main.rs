@@ -0,0 +1,226 @@
+//! Multi-chain submission of proof artifacts
+//!
+//! Submits one proof artifact to every SigstoreAttestationVerifier contract in
+//! a targets file, on all chains concurrently and with per-chain gas settings,
+//! and writes a consolidated report of the receipts. Batches of artifacts are
+//! queued and then sent by a submission manager that allocates nonces, bumps
+//! fees and tracks confirmations.
+
+mod cli;
+mod manager;
+mod queue;
+mod simulate;
+mod submit;
+mod targets;
+
+use alloy::signers::local::PrivateKeySigner;
+use anyhow::{bail, Context, Result};
+use clap::Parser;
+use cli::{Commands, EnqueueArgs, ProcessArgs, SubmitArgs};
+use manager::{process_queue, ManagerSettings};
+use queue::{submission_key, QueuedSubmission, SubmissionQueue, SubmissionStatus};
+use serde::Serialize;
+use sigstore_journal::VerificationResult;
+use sigstore_zkvm_traits::display::{
+    self, digest_algorithm_label, format_digest, format_rfc3339, format_time, paint,
+    timestamp_proof_summary, Style,
+};
+use sigstore_zkvm_traits::source::default_cache_dir;
+use sigstore_zkvm_traits::utils::write_atomic;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::time::Duration;
+use submit::{read_artifact, submit_all, ReceiptStatus, Submission, TargetReceipt};
+
+/// Consolidated report of one fan-out
+#[derive(Debug, Serialize)]
+struct SubmissionReport {
+    zkvm: String,
+    program_id: String,
+    subject_digest: String,
+    subject_digest_algorithm: String,
+    signing_time: String,
+    timestamp_proof: String,
+    receipts: Vec<TargetReceipt>,
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let cli = cli::Cli::parse();
+    if cli.no_color {
+        display::set_color(false);
+    }
+
+    match cli.command {
+        Commands::Submit(args) => handle_submit(args).await,
+        Commands::Enqueue(args) => handle_enqueue(args),
+        Commands::Process(args) => handle_process(args).await,
+    }
+}
+
+/// Handle the submit command
+async fn handle_submit(args: SubmitArgs) -> Result<()> {
+    println!("Sigstore Proof Submission");
+    println!("=========================\n");
+
+    // Step 1: Load the artifact and the targets
+    let artifact = read_artifact(&args.artifact)?;
+    let submission = Submission::from_artifact(&artifact)?;
+    let result = VerificationResult::from_slice(&submission.journal)
+        .map_err(|e| anyhow::anyhow!("Failed to decode journal: {}", e))?;
+
+    let targets = targets::load_targets(&args.targets, &args.only)?;
+    let signer: PrivateKeySigner = args
+        .private_key
+        .trim_start_matches("0x")
+        .parse()
+        .context("Invalid private key")?;
+
+    println!("Artifact:   {} ({})", args.artifact.display(), artifact.zkvm);
+    println!(
+        "Subject:    {}",
+        format_digest(&result.subject_digest_algorithm, &result.subject_digest)
+    );
+    println!("Signed at:  {}", format_time(&result.signing_time));
+    println!("Timestamp:  {}", timestamp_proof_summary(&result.timestamp_proof));
+    println!("Submitter:  {}", signer.address());
+    println!("Targets:    {}\n", targets.len());
+
+    // Step 2: Simulate, then submit to all targets
+    println!("{}", if args.dry_run { "Simulating..." } else { "Submitting..." });
+    let receipts = submit_all(
+        targets,
+        signer,
+        submission,
+        Duration::from_secs(args.receipt_timeout),
+        args.dry_run,
+    )
+    .await;
+
+    // Step 3: Report
+    let expected = if args.dry_run {
+        ReceiptStatus::Simulated
+    } else {
+        ReceiptStatus::Success
+    };
+    let succeeded = receipts
+        .iter()
+        .filter(|receipt| receipt.status == expected)
+        .count();
+    let style = if succeeded == receipts.len() { Style::Green } else { Style::Red };
+    println!(
+        "\n{}",
+        paint(format!("{} of {} submissions succeeded", succeeded, receipts.len()), style)
+    );
+
+    let total = receipts.len();
+    let report = SubmissionReport {
+        zkvm: artifact.zkvm,
+        program_id: artifact.program_id,
+        subject_digest: hex::encode(&result.subject_digest),
+        subject_digest_algorithm: digest_algorithm_label(&result.subject_digest_algorithm).to_string(),
+        signing_time: format_rfc3339(&result.signing_time),
+        timestamp_proof: timestamp_proof_summary(&result.timestamp_proof),
+        receipts,
+    };
+    if let Some(ref path) = args.report {
+        let json = serde_json::to_string_pretty(&report).context("Failed to serialize report")?;
+        write_atomic(path, json.as_bytes())
+            .with_context(|| format!("Failed to write report to: {}", path.display()))?;
+        println!("Report written to: {}", path.display());
+    }
+
+    if succeeded < total {
+        bail!("{} of {} submissions did not succeed", total - succeeded, total);
+    }
+
+    Ok(())
+}
+
+/// Handle the enqueue command
+fn handle_enqueue(args: EnqueueArgs) -> Result<()> {
+    let targets = targets::load_targets(&args.targets, &args.only)?;
+    let queue_dir = queue_dir(args.queue);
+    let queue = SubmissionQueue::open(&queue_dir)?;
+
+    let mut queued = 0;
+    for path in &args.artifacts {
+        let submission = Submission::from_artifact(&read_artifact(path)?)?;
+        // The queue outlives the working directory of this invocation
+        let path = std::fs::canonicalize(path)
+            .with_context(|| format!("Failed to resolve {}", path.display()))?;
+
+        for target in &targets {
+            let key = submission_key(&target.name, &submission.journal, &submission.proof);
+            if let Some(existing) = queue.get(&key)? {
+                println!(
+                    "{} -> {}: already queued ({:?})",
+                    path.display(),
+                    target.name,
+                    existing.status
+                );
+                continue;
+            }
+            queue.put(&mut QueuedSubmission::new(key, path.clone(), target.name.clone()))?;
+            queued += 1;
+        }
+    }
+
+    println!("Queued {} submissions in {}", queued, queue_dir.display());
+    Ok(())
+}
+
+/// Handle the process command
+async fn handle_process(args: ProcessArgs) -> Result<()> {
+    println!("Sigstore Batch Submission");
+    println!("=========================\n");
+
+    // Step 1: Open the queue and load the targets
+    let targets = targets::load_targets(&args.targets, &[])?;
+    let queue_dir = queue_dir(args.queue);
+    let queue = SubmissionQueue::open(&queue_dir)?;
+    let signer: PrivateKeySigner = args
+        .private_key
+        .trim_start_matches("0x")
+        .parse()
+        .context("Invalid private key")?;
+
+    println!("Queue:      {}", queue_dir.display());
+    println!("Submitter:  {}\n", signer.address());
+
+    // Step 2: Send, bump and confirm until every open submission is settled
+    let settings = ManagerSettings {
+        max_in_flight: args.max_in_flight,
+        bump_after: Duration::from_secs(args.bump_after),
+        bump_percent: u128::from(args.bump_percent),
+        confirmations: args.confirmations,
+        poll_interval: Duration::from_secs(args.poll_interval),
+    };
+    let processed = process_queue(queue, targets, signer, settings).await?;
+
+    // Step 3: Summarize
+    let mut counts: BTreeMap<String, usize> = BTreeMap::new();
+    for submission in &processed {
+        *counts.entry(format!("{:?}", submission.status).to_lowercase()).or_default() += 1;
+    }
+    let summary: Vec<String> = counts
+        .iter()
+        .map(|(status, count)| format!("{} {}", count, status))
+        .collect();
+    println!("\nProcessed {} submissions: {}", processed.len(), summary.join(", "));
+
+    let unsettled = processed
+        .iter()
+        .filter(|submission| submission.status != SubmissionStatus::Confirmed)
+        .count();
+    if unsettled > 0 {
+        bail!("{} of {} submissions were not confirmed", unsettled, processed.len());
+    }
+
+    Ok(())
+}
+
+/// The queue directory, defaulting to `<cache dir>/submissions`
+fn queue_dir(queue: Option<PathBuf>) -> PathBuf {
+    queue.unwrap_or_else(|| default_cache_dir().join("submissions"))
+}
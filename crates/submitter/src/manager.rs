@@ -0,0 +1,672 @@
+//! Submission manager for queued batch submissions
+//!
+//! Sending dozens of proofs one transaction at a time, each waiting for its
+//! receipt, is slow, and letting the node pick nonces breaks as soon as a
+//! transaction is dropped or replaced. The manager instead drives the queue
+//! in rounds, per chain and concurrently across chains:
+//!
+//! 1. Track sent submissions: a submission is confirmed once any of its
+//!    transactions has the configured number of confirmations. If the account's
+//!    nonce moved past it without one of them being mined, another transaction
+//!    took the nonce and the submission is queued again.
+//! 2. Bump fees: a transaction not mined within `bump_after` is replaced by one
+//!    with the same nonce and higher EIP-1559 fees.
+//! 3. Send queued submissions, up to `max_in_flight` per chain, with nonces
+//!    allocated locally from the account's pending nonce. Each call is simulated
+//!    first; a call that would revert is rejected instead of sent.
+//!
+//! Every state change is written to the queue before the next step, so a
+//! restarted run resumes tracking the transactions already sent.
+
+use crate::queue::{QueuedSubmission, SentTransaction, SubmissionQueue, SubmissionStatus};
+use crate::simulate::{gas_limit, simulate, SimulationError};
+use crate::submit::{connect, read_artifact, Submission, Verifier};
+use crate::targets::Target;
+use alloy::primitives::{Address, TxHash};
+use alloy::providers::Provider;
+use alloy::signers::local::PrivateKeySigner;
+use anyhow::{Context, Result};
+use sigstore_zkvm_traits::store::unix_now;
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+use std::time::Duration;
+use tokio::task::JoinSet;
+
+/// Settings of the submission manager
+///
+/// - max_in_flight: Maximum number of sent, unconfirmed submissions per chain
+/// - bump_after: Time after which an unmined transaction is replaced with higher fees
+/// - bump_percent: Percentage both fees are raised by per replacement
+/// - confirmations: Number of blocks (including its own) after which a transaction is final
+/// - poll_interval: Time between rounds
+#[derive(Debug, Clone, Copy)]
+pub struct ManagerSettings {
+    pub max_in_flight: usize,
+    pub bump_after: Duration,
+    pub bump_percent: u128,
+    pub confirmations: u64,
+    pub poll_interval: Duration,
+}
+
+/// EIP-1559 fees of one transaction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Fees {
+    max_fee_per_gas: u128,
+    max_priority_fee_per_gas: u128,
+}
+
+/// Process all open submissions in the queue until every one is settled
+///
+/// Submissions for targets missing from `targets` stay queued. A chain whose
+/// RPC endpoint fails is abandoned with its submissions left in the queue for
+/// the next run; the other chains carry on.
+///
+/// # Returns
+///
+/// The submissions that were open when processing started, in their final state
+///
+/// # Errors
+///
+/// Returns an error if the queue cannot be read
+pub async fn process_queue(
+    queue: SubmissionQueue,
+    targets: Vec<Target>,
+    signer: PrivateKeySigner,
+    settings: ManagerSettings,
+) -> Result<Vec<QueuedSubmission>> {
+    let targets: HashMap<String, Target> = targets
+        .into_iter()
+        .map(|target| (target.name.clone(), target))
+        .collect();
+
+    let mut by_chain: BTreeMap<u64, Vec<QueuedSubmission>> = BTreeMap::new();
+    let mut unknown = 0;
+    for submission in queue.list()?.into_iter().filter(QueuedSubmission::is_open) {
+        match targets.get(&submission.target) {
+            Some(target) => by_chain.entry(target.chain_id).or_default().push(submission),
+            None => unknown += 1,
+        }
+    }
+    if unknown > 0 {
+        println!("   Skipping {} submissions for targets not in the targets file", unknown);
+    }
+
+    let mut tasks = JoinSet::new();
+    for (chain_id, submissions) in by_chain {
+        let chain_targets: HashMap<String, Target> = targets
+            .values()
+            .filter(|target| target.chain_id == chain_id)
+            .map(|target| (target.name.clone(), target.clone()))
+            .collect();
+        let mut manager = ChainManager {
+            chain_id,
+            targets: chain_targets,
+            queue: queue.clone(),
+            submissions,
+            artifacts: HashMap::new(),
+            settings,
+        };
+        let signer = signer.clone();
+        tasks.spawn(async move {
+            if let Err(e) = manager.run(signer).await {
+                println!("   chain {}: stopped: {:#}", manager.chain_id, e);
+            }
+            manager.submissions
+        });
+    }
+
+    let mut processed = Vec::new();
+    while let Some(joined) = tasks.join_next().await {
+        // Chain tasks don't panic; a panic is a bug worth surfacing
+        processed.extend(joined.expect("submission task panicked"));
+    }
+    processed.sort_by(|a, b| a.key.cmp(&b.key));
+    Ok(processed)
+}
+
+/// Nonce and fee management of one account on one chain
+struct ChainManager {
+    chain_id: u64,
+    targets: HashMap<String, Target>,
+    queue: SubmissionQueue,
+    submissions: Vec<QueuedSubmission>,
+    artifacts: HashMap<PathBuf, Submission>,
+    settings: ManagerSettings,
+}
+
+impl ChainManager {
+    async fn run(&mut self, signer: PrivateKeySigner) -> Result<()> {
+        let from = signer.address();
+        // Every target of the chain shares the account's nonces, so one endpoint serves all
+        let first = self.targets.values().next().context("No targets on chain")?;
+        let provider = connect(first, signer).await?;
+
+        while self.submissions.iter().any(QueuedSubmission::is_open) {
+            // Step 1: Track sent submissions
+            let latest_block = provider.get_block_number().await.context("Failed to query block number")?;
+            let mined_nonce = provider
+                .get_transaction_count(from)
+                .latest()
+                .await
+                .context("Failed to query account nonce")?;
+            for index in 0..self.submissions.len() {
+                if self.submissions[index].status == SubmissionStatus::Sent {
+                    self.track(&provider, index, latest_block, mined_nonce).await?;
+                }
+            }
+
+            // Step 2: Replace stale transactions with higher fees
+            let now = unix_now();
+            for index in 0..self.submissions.len() {
+                if is_stale(&self.submissions[index], now, self.settings.bump_after) {
+                    self.bump(&provider, from, index).await?;
+                }
+            }
+
+            // Step 3: Send queued submissions
+            let in_flight = self
+                .submissions
+                .iter()
+                .filter(|submission| submission.status == SubmissionStatus::Sent)
+                .count();
+            if in_flight < self.settings.max_in_flight {
+                self.send_queued(&provider, from, self.settings.max_in_flight - in_flight)
+                    .await?;
+            }
+
+            if self.submissions.iter().any(QueuedSubmission::is_open) {
+                tokio::time::sleep(self.settings.poll_interval).await;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Settle a sent submission once one of its transactions is mined and confirmed
+    async fn track<P: Provider>(
+        &mut self,
+        provider: &P,
+        index: usize,
+        latest_block: u64,
+        mined_nonce: u64,
+    ) -> Result<()> {
+        let Some(nonce) = self.submissions[index].nonce else {
+            return Ok(());
+        };
+
+        // Newest first: a replacement is the likeliest to be mined
+        let mut mined = None;
+        for tx in self.submissions[index].transactions.iter().rev() {
+            let hash: TxHash = tx.tx_hash.parse().context("Invalid transaction hash in queue")?;
+            if let Some(receipt) = provider
+                .get_transaction_receipt(hash)
+                .await
+                .context("Failed to query transaction receipt")?
+            {
+                mined = Some(Mined {
+                    tx_hash: tx.tx_hash.clone(),
+                    block_number: receipt.block_number.unwrap_or(latest_block),
+                    gas_used: receipt.gas_used,
+                    success: receipt.status(),
+                });
+                break;
+            }
+        }
+
+        let submission = &mut self.submissions[index];
+        let confirmations = self.settings.confirmations;
+        if !settle(submission, mined.as_ref(), latest_block, mined_nonce, confirmations) {
+            return Ok(());
+        }
+        match (submission.status, &mined) {
+            (SubmissionStatus::Confirmed | SubmissionStatus::Reverted, Some(mined)) => println!(
+                "   {:<20} {} in block {} (tx {}, nonce {})",
+                submission.target,
+                if mined.success { "confirmed" } else { "reverted" },
+                mined.block_number,
+                mined.tx_hash,
+                nonce
+            ),
+            (SubmissionStatus::Queued, _) => println!(
+                "   {:<20} nonce {} taken by another transaction, requeued",
+                submission.target, nonce
+            ),
+            _ => {}
+        }
+        self.queue.put(submission)
+    }
+
+    /// Replace the newest transaction of a submission with one paying higher fees
+    async fn bump<P: Provider>(&mut self, provider: &P, from: Address, index: usize) -> Result<()> {
+        let submission = &self.submissions[index];
+        let target = self.targets[&submission.target].clone();
+        let (Some(nonce), Some(gas), Some(last)) = (
+            submission.nonce,
+            submission.gas_limit,
+            submission.transactions.last().cloned(),
+        ) else {
+            return Ok(());
+        };
+
+        let current = current_fees(provider, &target).await?;
+        let fees = bumped_fees(Fees::of(&last), current, self.settings.bump_percent);
+        if let Some(cap) = target.gas.max_fee_cap {
+            // A replacement clamped to the cap might not raise enough to be accepted
+            if fees.max_fee_per_gas > cap {
+                let submission = &mut self.submissions[index];
+                let reason = format!("Fee cap {} reached, waiting at {}", cap, last.max_fee_per_gas);
+                if submission.last_error.as_deref() != Some(reason.as_str()) {
+                    println!("   {:<20} {}", submission.target, reason);
+                    submission.last_error = Some(reason);
+                    self.queue.put(submission)?;
+                }
+                return Ok(());
+            }
+        }
+
+        let artifact = self.submissions[index].artifact.clone();
+        let call_args = self.artifact(&artifact)?.clone();
+        let contract = Verifier::new(target.verifier, provider);
+        let sent = send(&contract, &call_args, from, target.chain_id, nonce, gas, fees).await;
+
+        let submission = &mut self.submissions[index];
+        match sent {
+            Ok(tx) => {
+                println!(
+                    "   {:<20} replaced nonce {} (max fee {}, priority fee {}, tx {})",
+                    submission.target, nonce, tx.max_fee_per_gas, tx.max_priority_fee_per_gas, tx.tx_hash
+                );
+                submission.transactions.push(tx);
+                submission.last_error = None;
+            }
+            // E.g. the original was mined in the meantime; the next round tracks it
+            Err(e) => submission.last_error = Some(format!("{:#}", e)),
+        }
+        self.queue.put(submission)
+    }
+
+    /// Simulate and send up to `limit` queued submissions with consecutive nonces
+    async fn send_queued<P: Provider>(&mut self, provider: &P, from: Address, limit: usize) -> Result<()> {
+        let queued: Vec<usize> = (0..self.submissions.len())
+            .filter(|&index| self.submissions[index].status == SubmissionStatus::Queued)
+            .take(limit)
+            .collect();
+        if queued.is_empty() {
+            return Ok(());
+        }
+
+        let pending_nonce = provider
+            .get_transaction_count(from)
+            .pending()
+            .await
+            .context("Failed to query pending nonce")?;
+        let mut next_nonce = next_nonce(&self.submissions, pending_nonce);
+
+        for index in queued {
+            let target = self.targets[&self.submissions[index].target].clone();
+            let artifact = self.submissions[index].artifact.clone();
+            let call_args = match self.artifact(&artifact) {
+                Ok(call_args) => call_args.clone(),
+                Err(e) => {
+                    self.reject(index, format!("{:#}", e))?;
+                    continue;
+                }
+            };
+
+            let contract = Verifier::new(target.verifier, provider);
+            let estimated_gas = match simulate(&contract, &call_args, from).await {
+                Ok(gas) => gas,
+                Err(SimulationError::Reverted(reason)) => {
+                    self.reject(index, reason)?;
+                    continue;
+                }
+                Err(SimulationError::Rpc(e)) => return Err(e),
+            };
+            let gas = match gas_limit(target.gas.gas_limit, estimated_gas) {
+                Ok(gas) => gas,
+                Err(reason) => {
+                    self.reject(index, reason)?;
+                    continue;
+                }
+            };
+
+            let fees = current_fees(provider, &target).await?;
+            let fees = capped_fees(fees, target.gas.max_fee_cap);
+            let tx = send(&contract, &call_args, from, target.chain_id, next_nonce, gas, fees).await;
+
+            let submission = &mut self.submissions[index];
+            match tx {
+                Ok(tx) => {
+                    println!(
+                        "   {:<20} sent with nonce {} (tx {})",
+                        submission.target, next_nonce, tx.tx_hash
+                    );
+                    submission.status = SubmissionStatus::Sent;
+                    submission.nonce = Some(next_nonce);
+                    submission.gas_limit = Some(gas);
+                    submission.transactions.push(tx);
+                    submission.last_error = None;
+                    self.queue.put(submission)?;
+                    next_nonce += 1;
+                }
+                Err(e) => {
+                    // Later nonces would leave a gap; retry from the node's nonce next round
+                    submission.last_error = Some(format!("{:#}", e));
+                    println!("   {:<20} send failed, retrying: {:#}", submission.target, e);
+                    return self.queue.put(submission);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Mark a queued submission as rejected without sending it
+    fn reject(&mut self, index: usize, reason: String) -> Result<()> {
+        let submission = &mut self.submissions[index];
+        println!("   {:<20} not sent: {}", submission.target, reason);
+        submission.status = SubmissionStatus::Rejected;
+        submission.last_error = Some(reason);
+        self.queue.put(submission)
+    }
+
+    /// Call arguments of an artifact, read once per run
+    fn artifact(&mut self, path: &PathBuf) -> Result<&Submission> {
+        if !self.artifacts.contains_key(path) {
+            let submission = Submission::from_artifact(&read_artifact(path)?)?;
+            self.artifacts.insert(path.clone(), submission);
+        }
+        Ok(&self.artifacts[path])
+    }
+}
+
+impl Fees {
+    /// Fees a transaction was sent with
+    fn of(tx: &SentTransaction) -> Self {
+        Self {
+            max_fee_per_gas: tx.max_fee_per_gas,
+            max_priority_fee_per_gas: tx.max_priority_fee_per_gas,
+        }
+    }
+}
+
+/// A mined transaction of a submission
+struct Mined {
+    tx_hash: String,
+    block_number: u64,
+    gas_used: u64,
+    success: bool,
+}
+
+/// Whether the newest transaction of a sent, unmined submission is due for a replacement
+fn is_stale(submission: &QueuedSubmission, now: u64, bump_after: Duration) -> bool {
+    submission.status == SubmissionStatus::Sent
+        && submission.tx_hash.is_none()
+        && submission
+            .transactions
+            .last()
+            .is_some_and(|tx| now.saturating_sub(tx.sent_at) >= bump_after.as_secs())
+}
+
+/// Apply the tracking result of one round to a sent submission
+///
+/// `mined` is the newest of the submission's transactions with a receipt, and
+/// `mined_nonce` the account's nonce as of the latest block, read before the
+/// receipts. Returns whether the submission changed.
+fn settle(
+    submission: &mut QueuedSubmission,
+    mined: Option<&Mined>,
+    latest_block: u64,
+    mined_nonce: u64,
+    confirmations: u64,
+) -> bool {
+    let Some(nonce) = submission.nonce else {
+        return false;
+    };
+
+    if let Some(mined) = mined {
+        submission.tx_hash = Some(mined.tx_hash.clone());
+        submission.block_number = Some(mined.block_number);
+        submission.gas_used = Some(mined.gas_used);
+        if latest_block + 1 >= mined.block_number + confirmations {
+            submission.status = if mined.success {
+                SubmissionStatus::Confirmed
+            } else {
+                SubmissionStatus::Reverted
+            };
+        }
+        return true;
+    }
+
+    // The nonce was mined but not by us
+    if mined_nonce > nonce {
+        submission.requeue(format!("Nonce {} was used by another transaction", nonce));
+        return true;
+    }
+
+    // A receipt seen earlier may have been reorged out
+    if submission.tx_hash.take().is_some() {
+        submission.block_number = None;
+        submission.gas_used = None;
+        return true;
+    }
+    false
+}
+
+/// Fees of a replacement for a transaction sent with `last`
+///
+/// Nodes only accept a replacement that raises both fees (by 10% in geth), so
+/// each is raised by `bump_percent` and at least 1 wei, or to the current fee
+/// if that is higher.
+fn bumped_fees(last: Fees, current: Fees, bump_percent: u128) -> Fees {
+    let raise = |fee: u128| fee + (fee * bump_percent).div_ceil(100).max(1);
+    let max_fee_per_gas = raise(last.max_fee_per_gas).max(current.max_fee_per_gas);
+    Fees {
+        max_fee_per_gas,
+        max_priority_fee_per_gas: raise(last.max_priority_fee_per_gas)
+            .max(current.max_priority_fee_per_gas)
+            .min(max_fee_per_gas),
+    }
+}
+
+/// Fees clamped to the target's fee cap, if any
+fn capped_fees(fees: Fees, cap: Option<u128>) -> Fees {
+    let max_fee_per_gas = cap.map_or(fees.max_fee_per_gas, |cap| fees.max_fee_per_gas.min(cap));
+    Fees {
+        max_fee_per_gas,
+        max_priority_fee_per_gas: fees.max_priority_fee_per_gas.min(max_fee_per_gas),
+    }
+}
+
+/// Nonce for the next queued submission
+///
+/// The node's pending nonce, or past the highest nonce still in flight if the
+/// node dropped some of our transactions from its pool. Those keep their
+/// nonces and are sent again by fee bumping, which closes the gap.
+fn next_nonce(submissions: &[QueuedSubmission], pending_nonce: u64) -> u64 {
+    submissions
+        .iter()
+        .filter(|submission| submission.status == SubmissionStatus::Sent)
+        .filter_map(|submission| submission.nonce)
+        .map(|nonce| nonce + 1)
+        .fold(pending_nonce, u64::max)
+}
+
+/// Current fees of the chain, overridden by the fees configured for the target
+async fn current_fees<P: Provider>(provider: &P, target: &Target) -> Result<Fees> {
+    let (max_fee_per_gas, max_priority_fee_per_gas) =
+        match (target.gas.max_fee_per_gas, target.gas.max_priority_fee_per_gas) {
+            (Some(max_fee), Some(priority_fee)) => (max_fee, priority_fee),
+            (max_fee, priority_fee) => {
+                let estimate = provider
+                    .estimate_eip1559_fees()
+                    .await
+                    .context("Failed to estimate fees")?;
+                (
+                    max_fee.unwrap_or(estimate.max_fee_per_gas),
+                    priority_fee.unwrap_or(estimate.max_priority_fee_per_gas),
+                )
+            }
+        };
+    Ok(Fees {
+        max_fee_per_gas,
+        max_priority_fee_per_gas: max_priority_fee_per_gas.min(max_fee_per_gas),
+    })
+}
+
+/// Sign and send the verify/attest call with explicit nonce, gas and fees
+async fn send<P: Provider>(
+    contract: &Verifier<P>,
+    submission: &Submission,
+    from: Address,
+    chain_id: u64,
+    nonce: u64,
+    gas: u64,
+    fees: Fees,
+) -> Result<SentTransaction> {
+    let pending = submission
+        .call(contract)
+        .from(from)
+        .chain_id(chain_id)
+        .nonce(nonce)
+        .gas(gas)
+        .max_fee_per_gas(fees.max_fee_per_gas)
+        .max_priority_fee_per_gas(fees.max_priority_fee_per_gas)
+        .send()
+        .await
+        .context("Failed to send transaction")?;
+
+    Ok(SentTransaction {
+        tx_hash: pending.tx_hash().to_string(),
+        max_fee_per_gas: fees.max_fee_per_gas,
+        max_priority_fee_per_gas: fees.max_priority_fee_per_gas,
+        sent_at: unix_now(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fees(max_fee_per_gas: u128, max_priority_fee_per_gas: u128) -> Fees {
+        Fees {
+            max_fee_per_gas,
+            max_priority_fee_per_gas,
+        }
+    }
+
+    fn sent(nonce: u64, tx_hashes: &[&str]) -> QueuedSubmission {
+        let key = format!("key-{}", nonce);
+        let mut submission = QueuedSubmission::new(key, PathBuf::from("proof.json"), "target".into());
+        submission.status = SubmissionStatus::Sent;
+        submission.nonce = Some(nonce);
+        submission.gas_limit = Some(300_000);
+        submission.transactions = tx_hashes
+            .iter()
+            .map(|tx_hash| SentTransaction {
+                tx_hash: tx_hash.to_string(),
+                max_fee_per_gas: 100,
+                max_priority_fee_per_gas: 10,
+                sent_at: 1_000,
+            })
+            .collect();
+        submission
+    }
+
+    fn mined(tx_hash: &str, block_number: u64, success: bool) -> Mined {
+        Mined {
+            tx_hash: tx_hash.into(),
+            block_number,
+            gas_used: 21_000,
+            success,
+        }
+    }
+
+    #[test]
+    fn test_bumped_fees() {
+        // Both fees raised by the percentage, rounded up
+        assert_eq!(bumped_fees(fees(1_000, 101), fees(500, 50), 20), fees(1_200, 122));
+        // At least 1 wei, even for a zero percentage or a zero priority fee
+        assert_eq!(bumped_fees(fees(1_000, 0), fees(500, 0), 0), fees(1_001, 1));
+        // Current network fees win if they rose further
+        assert_eq!(bumped_fees(fees(1_000, 100), fees(5_000, 300), 20), fees(5_000, 300));
+        // The priority fee never exceeds the fee cap of the replacement
+        assert_eq!(bumped_fees(fees(1_000, 100), fees(500, 2_000), 20), fees(1_200, 1_200));
+    }
+
+    #[test]
+    fn test_capped_fees() {
+        assert_eq!(capped_fees(fees(1_000, 100), None), fees(1_000, 100));
+        assert_eq!(capped_fees(fees(1_000, 100), Some(2_000)), fees(1_000, 100));
+        assert_eq!(capped_fees(fees(3_000, 100), Some(2_000)), fees(2_000, 100));
+        assert_eq!(capped_fees(fees(3_000, 2_500), Some(2_000)), fees(2_000, 2_000));
+    }
+
+    #[test]
+    fn test_next_nonce_after_dropped_transaction() {
+        // The node's pending nonce when nothing is in flight
+        assert_eq!(next_nonce(&[], 7), 7);
+
+        // Nonce 5 was dropped from the node's pool, which now reports 5 again;
+        // reusing it would collide with our own transaction
+        let in_flight = vec![sent(5, &["0x05"]), sent(6, &["0x06"])];
+        assert_eq!(next_nonce(&in_flight, 5), 7);
+
+        // A requeued submission released its nonce and leaves no gap to fill
+        let mut requeued = sent(8, &["0x08"]);
+        requeued.requeue("Nonce 8 was used by another transaction".into());
+        assert_eq!(requeued.nonce, None);
+        assert_eq!(next_nonce(&[requeued], 9), 9);
+
+        // Settled submissions no longer hold nonces back
+        let mut confirmed = sent(12, &["0x0c"]);
+        confirmed.status = SubmissionStatus::Confirmed;
+        assert_eq!(next_nonce(&[confirmed], 10), 10);
+    }
+
+    #[test]
+    fn test_settle_replacement_after_receipt() {
+        // The replacement was mined: its hash is recorded, pending confirmations
+        let mut submission = sent(3, &["0xaa", "0xbb"]);
+        let replacement = mined("0xbb", 100, true);
+        assert!(settle(&mut submission, Some(&replacement), 100, 4, 3));
+        assert_eq!(submission.status, SubmissionStatus::Sent);
+        assert_eq!(submission.tx_hash.as_deref(), Some("0xbb"));
+        assert_eq!(submission.block_number, Some(100));
+
+        // A mined submission is not replaced, however old its transactions
+        assert!(!is_stale(&submission, 1_000_000, Duration::from_secs(60)));
+
+        // Confirmed once the block has the configured number of confirmations
+        assert!(settle(&mut submission, Some(&replacement), 102, 4, 3));
+        assert_eq!(submission.status, SubmissionStatus::Confirmed);
+
+        // The original was mined instead and reverted
+        let mut submission = sent(3, &["0xaa", "0xbb"]);
+        assert!(settle(&mut submission, Some(&mined("0xaa", 100, false)), 100, 4, 1));
+        assert_eq!(submission.status, SubmissionStatus::Reverted);
+        assert_eq!(submission.tx_hash.as_deref(), Some("0xaa"));
+    }
+
+    #[test]
+    fn test_settle_without_receipt() {
+        // Still pending: nothing changes and the transaction is due for a bump
+        let mut submission = sent(3, &["0xaa"]);
+        assert!(!settle(&mut submission, None, 100, 3, 1));
+        assert!(is_stale(&submission, 1_060, Duration::from_secs(60)));
+        assert!(!is_stale(&submission, 1_059, Duration::from_secs(60)));
+
+        // The receipt seen last round was reorged out
+        assert!(settle(&mut submission, Some(&mined("0xaa", 100, true)), 100, 3, 3));
+        assert!(settle(&mut submission, None, 101, 3, 3));
+        assert_eq!(submission.status, SubmissionStatus::Sent);
+        assert_eq!(submission.tx_hash, None);
+        assert_eq!(submission.block_number, None);
+
+        // The nonce was taken by another transaction: sent again with a new nonce
+        assert!(settle(&mut submission, None, 102, 4, 3));
+        assert_eq!(submission.status, SubmissionStatus::Queued);
+        assert_eq!(submission.nonce, None);
+        assert!(submission.transactions.is_empty());
+    }
+}
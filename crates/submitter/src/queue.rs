@@ -0,0 +1,195 @@
+//! File-backed queue of batch submissions
+//!
+//! Every (proof artifact, target) pair queued for submission is one job, kept
+//! next to the proving job store so a batch survives restarts:
+//!
+//! ```text
+//! <root>/<submission key>.json   # QueuedSubmission
+//! ```
+//!
+//! A job records the nonce it was sent with and every transaction sent for that
+//! nonce, since any of a transaction and its fee-bumped replacements may be the
+//! one that is mined.
+
+use alloy::primitives::keccak256;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use sigstore_zkvm_traits::store::unix_now;
+use sigstore_zkvm_traits::utils::write_atomic;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Lifecycle of a queued submission
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SubmissionStatus {
+    /// Waiting for a nonce
+    Queued,
+    /// Sent with a nonce and waiting to be mined and confirmed
+    Sent,
+    /// Mined, executed successfully and confirmed
+    Confirmed,
+    /// Mined, but the call reverted
+    Reverted,
+    /// Not sent: the simulated call reverted
+    Rejected,
+}
+
+/// One transaction sent for a submission's nonce
+///
+/// - tx_hash: Hash of the transaction
+/// - max_fee_per_gas: EIP-1559 fee cap in wei
+/// - max_priority_fee_per_gas: EIP-1559 priority fee in wei
+/// - sent_at: Unix timestamp the transaction was sent
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SentTransaction {
+    pub tx_hash: String,
+    pub max_fee_per_gas: u128,
+    pub max_priority_fee_per_gas: u128,
+    pub sent_at: u64,
+}
+
+/// Submission of one proof artifact to one target
+///
+/// - key: Hex keccak256 identifying artifact and target (see [`submission_key`])
+/// - artifact: Path of the proof artifact
+/// - target: Name of the target in the targets file
+/// - status: Current state of the submission
+/// - nonce: Nonce allocated once sent
+/// - gas_limit: Gas limit the transactions were sent with
+/// - transactions: Transactions sent for the nonce, oldest first; later ones replace earlier ones
+/// - tx_hash: Hash of the mined transaction
+/// - block_number: Block the transaction was mined in
+/// - gas_used: Gas used by the mined transaction
+/// - last_error: Most recent error, if any
+/// - updated_at: Unix timestamp of the last update
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueuedSubmission {
+    pub key: String,
+    pub artifact: PathBuf,
+    pub target: String,
+    pub status: SubmissionStatus,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nonce: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gas_limit: Option<u64>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub transactions: Vec<SentTransaction>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tx_hash: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_number: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gas_used: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+    pub updated_at: u64,
+}
+
+impl QueuedSubmission {
+    /// Create a queued submission
+    pub fn new(key: String, artifact: PathBuf, target: String) -> Self {
+        Self {
+            key,
+            artifact,
+            target,
+            status: SubmissionStatus::Queued,
+            nonce: None,
+            gas_limit: None,
+            transactions: Vec::new(),
+            tx_hash: None,
+            block_number: None,
+            gas_used: None,
+            last_error: None,
+            updated_at: 0,
+        }
+    }
+
+    /// Whether the submission still needs work
+    pub fn is_open(&self) -> bool {
+        matches!(self.status, SubmissionStatus::Queued | SubmissionStatus::Sent)
+    }
+
+    /// Release the nonce so the submission is sent again with a fresh one
+    pub fn requeue(&mut self, reason: String) {
+        self.status = SubmissionStatus::Queued;
+        self.nonce = None;
+        self.gas_limit = None;
+        self.transactions.clear();
+        self.last_error = Some(reason);
+    }
+}
+
+/// Idempotency key for submitting `journal` and `proof` to `target`
+///
+/// Queueing the same proof for the same target again finds the existing job.
+pub fn submission_key(target: &str, journal: &[u8], proof: &[u8]) -> String {
+    let mut preimage = Vec::with_capacity(64 + target.len());
+    preimage.extend_from_slice(keccak256(journal).as_slice());
+    preimage.extend_from_slice(keccak256(proof).as_slice());
+    preimage.extend_from_slice(target.as_bytes());
+    hex::encode(keccak256(&preimage))
+}
+
+/// Directory-backed submission queue
+#[derive(Debug, Clone)]
+pub struct SubmissionQueue {
+    root: PathBuf,
+}
+
+impl SubmissionQueue {
+    /// Open the queue at `root`, creating the directory if needed
+    pub fn open(root: &Path) -> Result<Self> {
+        fs::create_dir_all(root)
+            .with_context(|| format!("Failed to create queue directory: {}", root.display()))?;
+        Ok(Self {
+            root: root.to_path_buf(),
+        })
+    }
+
+    /// Load a submission, if any
+    pub fn get(&self, key: &str) -> Result<Option<QueuedSubmission>> {
+        let path = self.path(key);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let content =
+            fs::read(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+        let submission = serde_json::from_slice(&content)
+            .with_context(|| format!("Failed to parse {}", path.display()))?;
+        Ok(Some(submission))
+    }
+
+    /// Insert or replace a submission, stamping its update time
+    pub fn put(&self, submission: &mut QueuedSubmission) -> Result<()> {
+        submission.updated_at = unix_now();
+        let json = serde_json::to_vec_pretty(submission).context("Failed to serialize submission")?;
+        write_atomic(&self.path(&submission.key), &json)
+    }
+
+    /// All submissions, ordered by key
+    pub fn list(&self) -> Result<Vec<QueuedSubmission>> {
+        let mut keys = Vec::new();
+        for entry in fs::read_dir(&self.root)
+            .with_context(|| format!("Failed to read queue directory: {}", self.root.display()))?
+        {
+            let path = entry?.path();
+            if path.extension().is_some_and(|ext| ext == "json") {
+                if let Some(key) = path.file_stem().and_then(|stem| stem.to_str()) {
+                    keys.push(key.to_string());
+                }
+            }
+        }
+        keys.sort();
+
+        let mut submissions = Vec::with_capacity(keys.len());
+        for key in keys {
+            submissions.extend(self.get(&key)?);
+        }
+        Ok(submissions)
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        self.root.join(format!("{}.json", key))
+    }
+}
@@ -0,0 +1,274 @@
+//! Simulation of a submission before it is sent
+//!
+//! The verify/attest call is run with `eth_call` and its gas estimated before
+//! the transaction is signed. A call that would revert is not sent; its revert
+//! data is decoded against the errors of the verifier contract and the zkVM
+//! verifiers behind it, so a doomed submission costs no gas.
+
+use crate::submit::{Submission, Verifier};
+use alloy::primitives::{Address, B256};
+use alloy::providers::Provider;
+use alloy::sol;
+use alloy::sol_types::{decode_revert_reason, SolInterface};
+
+sol! {
+    /// Errors the verify/attest call can revert with
+    interface VerifierErrors {
+        // SigstoreAttestationVerifier
+        error InvalidZkCoProcessorType();
+        error MissingZkVerifier();
+        error MissingZkProgramId();
+        error InvalidMaxAnchorAge();
+        error MissingBlockAnchor();
+        error StaleBlockAnchor();
+        error UnknownBlockAnchor();
+        error PolicyViolationRejected();
+
+        // VerificationResultParser
+        error InvalidDataLength();
+        error InvalidCertificateHashesLength();
+        error InvalidTimestampProofType();
+        error OptionsHashMismatch();
+        error UnsupportedJournalVersion();
+
+        // RISC Zero verifier router and Groth16 verifier
+        error VerificationFailed();
+        error SelectorMismatch(bytes4 received, bytes4 expected);
+        error SelectorUnknown(bytes4 selector);
+        error SelectorRemoved(bytes4 selector);
+
+        // SP1 verifier gateway and verifiers
+        error RouteNotFound(bytes4 selector);
+        error RouteIsFrozen(bytes4 selector);
+        error WrongVerifierSelector(bytes4 received, bytes4 expected);
+        error InvalidProof();
+
+        // Pico and SP1 Groth16 verifiers
+        error ProofInvalid();
+        error PublicInputNotInField();
+    }
+}
+
+use VerifierErrors::VerifierErrorsErrors as RevertError;
+
+/// Why a simulated call cannot be sent
+#[derive(Debug)]
+pub enum SimulationError {
+    /// The call reverts, with the decoded reason
+    Reverted(String),
+    /// The node could not be queried
+    Rpc(anyhow::Error),
+}
+
+/// Simulate the verify/attest call of `submission` sent from `from`
+///
+/// # Returns
+///
+/// The gas estimate of the call
+///
+/// # Errors
+///
+/// Returns [`SimulationError::Reverted`] if the call or its gas estimation
+/// reverts, and [`SimulationError::Rpc`] if the node cannot be queried
+pub async fn simulate<P: Provider>(
+    contract: &Verifier<P>,
+    submission: &Submission,
+    from: Address,
+) -> Result<u64, SimulationError> {
+    let call = submission.call(contract).from(from);
+
+    if let Err(e) = call.call().await {
+        let Some(data) = e.as_revert_data() else {
+            return Err(SimulationError::Rpc(
+                anyhow::Error::new(e).context("Failed to simulate transaction"),
+            ));
+        };
+        let program_ids = if is_proof_rejection(&data) {
+            registered_program_id(contract, submission).await
+        } else {
+            None
+        };
+        return Err(SimulationError::Reverted(format!(
+            "Simulation reverted: {}",
+            explain_revert(&data, program_ids)
+        )));
+    }
+
+    call.estimate_gas().await.map_err(|e| match e.as_revert_data() {
+        Some(data) => SimulationError::Reverted(format!(
+            "Gas estimation reverted: {}",
+            explain_revert(&data, None)
+        )),
+        None => SimulationError::Rpc(anyhow::Error::new(e).context("Failed to estimate gas")),
+    })
+}
+
+/// Gas limit to send with: the configured one if it covers the estimate, else the estimate
+///
+/// # Errors
+///
+/// Returns the reason if the configured limit is below the estimate, since the
+/// transaction would run out of gas
+pub fn gas_limit(configured: Option<u64>, estimated: u64) -> Result<u64, String> {
+    match configured {
+        Some(gas_limit) if gas_limit < estimated => Err(format!(
+            "Configured gas limit {} is below the estimate of {}",
+            gas_limit, estimated
+        )),
+        Some(gas_limit) => Ok(gas_limit),
+        None => Ok(estimated),
+    }
+}
+
+/// The program ID registered on the contract and the one the proof is for
+///
+/// Only used to explain a rejected proof, so lookup failures are ignored.
+async fn registered_program_id<P: Provider>(
+    contract: &Verifier<P>,
+    submission: &Submission,
+) -> Option<(B256, B256)> {
+    let proven = submission.program_id?;
+    let registered = contract
+        .programIdentifier(submission.coprocessor)
+        .call()
+        .await
+        .ok()?;
+    Some((registered, proven))
+}
+
+/// Explain why the verify/attest call reverted
+///
+/// `program_ids` is the program ID registered on the contract and the one the
+/// proof was generated for, when both are known; a proof that fails to verify
+/// is then attributed to the mismatch instead of the journal.
+pub fn explain_revert(data: &[u8], program_ids: Option<(B256, B256)>) -> String {
+    let Ok(error) = RevertError::abi_decode(data) else {
+        return match decode_revert_reason(data) {
+            Some(reason) => reason,
+            None if data.is_empty() => "reverted without a reason".to_string(),
+            None => format!("reverted with unknown error data 0x{}", hex::encode(data)),
+        };
+    };
+
+    match error {
+        RevertError::InvalidZkCoProcessorType(_) => {
+            "InvalidZkCoProcessorType: the contract does not accept proofs of this zkVM".to_string()
+        }
+        RevertError::MissingZkVerifier(_) => {
+            "MissingZkVerifier: no zkVM verifier is configured on the contract for this zkVM".to_string()
+        }
+        RevertError::MissingZkProgramId(_) => {
+            "MissingZkProgramId: no program ID is configured on the contract for this zkVM".to_string()
+        }
+        RevertError::InvalidMaxAnchorAge(_) => {
+            "InvalidMaxAnchorAge: the maximum anchor age must be at most 256 blocks".to_string()
+        }
+        RevertError::MissingBlockAnchor(_) => {
+            "MissingBlockAnchor: the contract requires journals anchored to a recent block (prove with --anchor-block)"
+                .to_string()
+        }
+        RevertError::StaleBlockAnchor(_) => {
+            "StaleBlockAnchor: the journal's anchor block is too old for the contract's maximum anchor age (prove again with a recent --anchor-block)"
+                .to_string()
+        }
+        RevertError::UnknownBlockAnchor(_) => {
+            "UnknownBlockAnchor: the journal's anchor block hash is not that of its block number on this chain"
+                .to_string()
+        }
+        RevertError::PolicyViolationRejected(_) => {
+            "PolicyViolationRejected: the journal is flagged as a policy violation (quarantine mode) and the contract does not accept those"
+                .to_string()
+        }
+        RevertError::OptionsHashMismatch(_) => {
+            "OptionsHashMismatch: the journal's verification options are not the preimage of its options hash"
+                .to_string()
+        }
+        RevertError::UnsupportedJournalVersion(_) => {
+            "UnsupportedJournalVersion: the contract cannot parse this journal version (guest newer than the contract)"
+                .to_string()
+        }
+        RevertError::InvalidDataLength(_)
+        | RevertError::InvalidCertificateHashesLength(_)
+        | RevertError::InvalidTimestampProofType(_) => format!(
+            "{}: the contract cannot parse the journal (guest and contract journal layouts differ)",
+            error_name(&error)
+        ),
+        RevertError::VerificationFailed(_)
+        | RevertError::InvalidProof(_)
+        | RevertError::ProofInvalid(_)
+        | RevertError::PublicInputNotInField(_) => match program_ids {
+            Some((registered, proven)) if registered != proven => format!(
+                "{}: wrong program ID, the contract expects {} but the proof is for {}",
+                error_name(&error),
+                registered,
+                proven
+            ),
+            _ => format!(
+                "{}: the proof does not verify against the registered program ID and this journal (journal mismatch)",
+                error_name(&error)
+            ),
+        },
+        RevertError::SelectorMismatch(e) => format!(
+            "SelectorMismatch: the proof was generated for verifier selector {} but the verifier is {}",
+            e.received, e.expected
+        ),
+        RevertError::WrongVerifierSelector(e) => format!(
+            "WrongVerifierSelector: the proof was generated for verifier selector {} but the verifier is {}",
+            e.received, e.expected
+        ),
+        RevertError::SelectorUnknown(e) => format!(
+            "SelectorUnknown: no verifier is routed for proof selector {} (prover version not deployed on this chain)",
+            e.selector
+        ),
+        RevertError::RouteNotFound(e) => format!(
+            "RouteNotFound: no verifier is routed for proof selector {} (prover version not deployed on this chain)",
+            e.selector
+        ),
+        RevertError::SelectorRemoved(e) => format!(
+            "SelectorRemoved: the verifier for proof selector {} was removed",
+            e.selector
+        ),
+        RevertError::RouteIsFrozen(e) => format!(
+            "RouteIsFrozen: the verifier for proof selector {} is frozen",
+            e.selector
+        ),
+    }
+}
+
+/// Whether the revert means the proof itself was rejected by the zkVM verifier
+pub fn is_proof_rejection(data: &[u8]) -> bool {
+    matches!(
+        RevertError::abi_decode(data),
+        Ok(RevertError::VerificationFailed(_)
+            | RevertError::InvalidProof(_)
+            | RevertError::ProofInvalid(_)
+            | RevertError::PublicInputNotInField(_))
+    )
+}
+
+/// Name of the custom error, e.g. `VerificationFailed`
+fn error_name(error: &RevertError) -> &'static str {
+    RevertError::name_by_selector(error.selector()).unwrap_or("Reverted")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::sol_types::SolError;
+
+    #[test]
+    fn test_explain_block_anchor_reverts() {
+        // Selectors as listed in SigstoreAttestationVerifier.sol
+        let stale = VerifierErrors::StaleBlockAnchor {}.abi_encode();
+        assert_eq!(stale, hex::decode("28b3ddb7").unwrap());
+        assert!(explain_revert(&stale, None).starts_with("StaleBlockAnchor: "));
+        assert!(!is_proof_rejection(&stale));
+
+        let rejected = hex::decode("a56d902f").unwrap();
+        assert!(explain_revert(&rejected, None).starts_with("PolicyViolationRejected: "));
+        assert_eq!(
+            explain_revert(&[0xde, 0xad, 0xbe, 0xef], None),
+            "reverted with unknown error data 0xdeadbeef"
+        );
+    }
+}
@@ -0,0 +1,328 @@
+//! Fan-out of one proof artifact to many verifier contracts
+//!
+//! Targets on different chains are submitted concurrently. Targets sharing a
+//! chain are submitted one after another, so that transactions from the same
+//! account never race for a nonce. Every call is simulated before it is sent
+//! (see [`crate::simulate`]).
+
+use crate::simulate::{gas_limit, simulate, SimulationError};
+use crate::targets::Target;
+use alloy::contract::SolCallBuilder;
+use alloy::network::EthereumWallet;
+use alloy::primitives::{Bytes, TxHash, B256};
+use alloy::providers::{Provider, ProviderBuilder};
+use alloy::signers::local::PrivateKeySigner;
+use alloy::sol;
+use anyhow::{bail, Context, Result};
+use serde::Serialize;
+use sigstore_zkvm_traits::utils::{read_proof_artifact, ProofArtifact};
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::time::Duration;
+use tokio::task::JoinSet;
+
+sol! {
+    /// `ZkCoProcessorType` is ABI-encoded as `uint8`; the returned result is not needed
+    #[sol(rpc)]
+    interface ISigstoreAttestationVerifier {
+        function verifyAndAttestWithZKProof(bytes calldata output, uint8 zkCoProcessor, bytes calldata proofBytes) external;
+        function programIdentifier(uint8 zkCoProcessorType) external view returns (bytes32);
+    }
+}
+
+/// Verifier contract bound to a provider
+pub type Verifier<P> = ISigstoreAttestationVerifier::ISigstoreAttestationVerifierInstance<P>;
+
+/// The calldata shared by every target
+#[derive(Debug, Clone)]
+pub struct Submission {
+    pub journal: Bytes,
+    pub coprocessor: u8,
+    pub proof: Bytes,
+    /// Program ID the proof was generated for, if it is a 32-byte hex value
+    pub program_id: Option<B256>,
+}
+
+impl Submission {
+    /// Extract the call arguments from a proof artifact
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the zkVM is unknown or the journal or proof is not hex
+    pub fn from_artifact(artifact: &ProofArtifact) -> Result<Self> {
+        // Values of the contract's ZkCoProcessorType enum
+        let coprocessor = match artifact.zkvm.as_str() {
+            "risc0" => 1,
+            "sp1" => 2,
+            "pico" => 3,
+            other => bail!("Unknown zkVM in proof artifact: {}", other),
+        };
+
+        Ok(Self {
+            journal: decode_hex(&artifact.journal).context("Invalid journal in proof artifact")?,
+            coprocessor,
+            proof: decode_hex(&artifact.proof).context("Invalid proof in proof artifact")?,
+            program_id: artifact.program_id.parse().ok(),
+        })
+    }
+
+    /// The verify/attest call on `contract`
+    pub fn call<'a, P: Provider>(
+        &self,
+        contract: &'a Verifier<P>,
+    ) -> SolCallBuilder<&'a P, ISigstoreAttestationVerifier::verifyAndAttestWithZKProofCall> {
+        contract.verifyAndAttestWithZKProof(self.journal.clone(), self.coprocessor, self.proof.clone())
+    }
+}
+
+/// Outcome of a submission to one target
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReceiptStatus {
+    /// Mined and executed successfully
+    Success,
+    /// Mined, but the call reverted
+    Reverted,
+    /// Not sent: the simulated call reverted
+    Rejected,
+    /// Not sent: the simulated call succeeded and `--dry-run` was given
+    Simulated,
+    /// Not mined: rejected by the node, or not mined within the timeout
+    Failed,
+}
+
+/// Receipt of the submission to one target
+#[derive(Debug, Clone, Serialize)]
+pub struct TargetReceipt {
+    pub target: String,
+    pub chain_id: u64,
+    pub verifier: String,
+    pub status: ReceiptStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tx_hash: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub block_number: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub estimated_gas: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gas_used: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub effective_gas_price: Option<u128>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Submit to every target and collect one receipt per target, in target order
+///
+/// Failures are recorded in the receipts rather than returned, so one chain
+/// being down does not hide the outcome on the others.
+pub async fn submit_all(
+    targets: Vec<Target>,
+    signer: PrivateKeySigner,
+    submission: Submission,
+    receipt_timeout: Duration,
+    dry_run: bool,
+) -> Vec<TargetReceipt> {
+    let order: Vec<String> = targets.iter().map(|target| target.name.clone()).collect();
+
+    let mut by_chain: BTreeMap<u64, Vec<Target>> = BTreeMap::new();
+    for target in targets {
+        by_chain.entry(target.chain_id).or_default().push(target);
+    }
+
+    let mut tasks = JoinSet::new();
+    for (_, chain_targets) in by_chain {
+        let signer = signer.clone();
+        let submission = submission.clone();
+        tasks.spawn(async move {
+            let mut receipts = Vec::new();
+            for target in chain_targets {
+                let outcome =
+                    submit_to_target(&target, signer.clone(), &submission, receipt_timeout, dry_run).await;
+                let receipt = outcome.unwrap_or_else(|e| TargetReceipt {
+                    target: target.name.clone(),
+                    chain_id: target.chain_id,
+                    verifier: target.verifier.to_string(),
+                    status: e.status,
+                    tx_hash: e.tx_hash.map(|hash| hash.to_string()),
+                    block_number: None,
+                    estimated_gas: e.estimated_gas,
+                    gas_used: None,
+                    effective_gas_price: None,
+                    error: Some(format!("{:#}", e.error)),
+                });
+                println!("   {:<20} {}", receipt.target, describe(&receipt));
+                receipts.push(receipt);
+            }
+            receipts
+        });
+    }
+
+    let mut receipts = Vec::new();
+    while let Some(joined) = tasks.join_next().await {
+        // Submission tasks don't panic; a panic is a bug worth surfacing
+        receipts.extend(joined.expect("submission task panicked"));
+    }
+    receipts.sort_by_key(|receipt| order.iter().position(|name| *name == receipt.target));
+    receipts
+}
+
+/// A submission that did not go through, with the transaction hash if it was sent
+struct SubmitError {
+    status: ReceiptStatus,
+    tx_hash: Option<TxHash>,
+    estimated_gas: Option<u64>,
+    error: anyhow::Error,
+}
+
+impl SubmitError {
+    /// The simulated call reverted, or would run out of gas
+    fn rejected(error: anyhow::Error, estimated_gas: Option<u64>) -> Self {
+        Self {
+            status: ReceiptStatus::Rejected,
+            tx_hash: None,
+            estimated_gas,
+            error,
+        }
+    }
+}
+
+impl<E: Into<anyhow::Error>> From<E> for SubmitError {
+    fn from(error: E) -> Self {
+        Self {
+            status: ReceiptStatus::Failed,
+            tx_hash: None,
+            estimated_gas: None,
+            error: error.into(),
+        }
+    }
+}
+
+/// Read a proof artifact file (JSON, CBOR or zstd-compressed CBOR)
+pub fn read_artifact(path: &Path) -> Result<ProofArtifact> {
+    read_proof_artifact(path)
+}
+
+/// Connect to the RPC endpoint of `target` with `signer` as the wallet
+///
+/// # Errors
+///
+/// Returns an error if the RPC URL is invalid or the endpoint serves another chain
+pub async fn connect(target: &Target, signer: PrivateKeySigner) -> Result<impl Provider> {
+    let url = target.rpc_url.parse().context("Invalid RPC URL")?;
+    let provider = ProviderBuilder::new()
+        .wallet(EthereumWallet::from(signer))
+        .connect_http(url);
+
+    // A misconfigured RPC URL must not send the transaction to another chain
+    let chain_id = provider.get_chain_id().await.context("Failed to query chain ID")?;
+    if chain_id != target.chain_id {
+        bail!("RPC endpoint serves chain {}, expected {}", chain_id, target.chain_id);
+    }
+
+    Ok(provider)
+}
+
+async fn submit_to_target(
+    target: &Target,
+    signer: PrivateKeySigner,
+    submission: &Submission,
+    receipt_timeout: Duration,
+    dry_run: bool,
+) -> Result<TargetReceipt, SubmitError> {
+    let from = signer.address();
+    let provider = connect(target, signer).await?;
+    let contract = ISigstoreAttestationVerifier::new(target.verifier, &provider);
+
+    // Simulate first: a call that reverts would only burn gas
+    let estimated_gas = simulate(&contract, submission, from).await.map_err(|e| match e {
+        SimulationError::Reverted(reason) => SubmitError::rejected(anyhow::anyhow!(reason), None),
+        SimulationError::Rpc(error) => error.into(),
+    })?;
+    let gas = gas_limit(target.gas.gas_limit, estimated_gas)
+        .map_err(|reason| SubmitError::rejected(anyhow::anyhow!(reason), Some(estimated_gas)))?;
+
+    if dry_run {
+        return Ok(TargetReceipt {
+            target: target.name.clone(),
+            chain_id: target.chain_id,
+            verifier: target.verifier.to_string(),
+            status: ReceiptStatus::Simulated,
+            tx_hash: None,
+            block_number: None,
+            estimated_gas: Some(estimated_gas),
+            gas_used: None,
+            effective_gas_price: None,
+            error: None,
+        });
+    }
+
+    let mut call = submission
+        .call(&contract)
+        .from(from)
+        .chain_id(target.chain_id)
+        .gas(gas);
+    if let Some(max_fee_per_gas) = target.gas.max_fee_per_gas {
+        call = call.max_fee_per_gas(max_fee_per_gas);
+    }
+    if let Some(max_priority_fee_per_gas) = target.gas.max_priority_fee_per_gas {
+        call = call.max_priority_fee_per_gas(max_priority_fee_per_gas);
+    }
+
+    let pending = call.send().await.context("Failed to send transaction")?;
+    let tx_hash = *pending.tx_hash();
+    let receipt = pending
+        .with_timeout(Some(receipt_timeout))
+        .get_receipt()
+        .await
+        .map_err(|e| SubmitError {
+            status: ReceiptStatus::Failed,
+            tx_hash: Some(tx_hash),
+            estimated_gas: Some(estimated_gas),
+            error: anyhow::Error::new(e).context("Transaction was not mined"),
+        })?;
+
+    Ok(TargetReceipt {
+        target: target.name.clone(),
+        chain_id: target.chain_id,
+        verifier: target.verifier.to_string(),
+        status: if receipt.status() {
+            ReceiptStatus::Success
+        } else {
+            ReceiptStatus::Reverted
+        },
+        tx_hash: Some(tx_hash.to_string()),
+        block_number: receipt.block_number,
+        estimated_gas: Some(estimated_gas),
+        gas_used: Some(receipt.gas_used),
+        effective_gas_price: Some(receipt.effective_gas_price),
+        error: None,
+    })
+}
+
+/// One-line summary of a receipt
+pub fn describe(receipt: &TargetReceipt) -> String {
+    match receipt.status {
+        ReceiptStatus::Success | ReceiptStatus::Reverted => format!(
+            "{} in block {} (tx {}, gas {})",
+            if receipt.status == ReceiptStatus::Success { "mined" } else { "reverted" },
+            receipt.block_number.map_or("?".to_string(), |block| block.to_string()),
+            receipt.tx_hash.as_deref().unwrap_or("?"),
+            receipt.gas_used.unwrap_or_default(),
+        ),
+        ReceiptStatus::Simulated => format!(
+            "simulated (estimated gas {})",
+            receipt.estimated_gas.unwrap_or_default()
+        ),
+        ReceiptStatus::Rejected | ReceiptStatus::Failed => format!(
+            "{}: {}",
+            if receipt.status == ReceiptStatus::Rejected { "not sent" } else { "failed" },
+            receipt.error.as_deref().unwrap_or("unknown error")
+        ),
+    }
+}
+
+/// Decode `0x`-prefixed or bare hex
+fn decode_hex(value: &str) -> Result<Bytes> {
+    Ok(hex::decode(value.strip_prefix("0x").unwrap_or(value))?.into())
+}
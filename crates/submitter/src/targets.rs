@@ -0,0 +1,130 @@
+//! Submission targets
+//!
+//! A targets file lists the (chain, contract) pairs a proof is submitted to,
+//! in the style of `contracts/script/config/deployment.toml`; `${VAR}` in the
+//! RPC URL and the verifier address is replaced with the environment variable:
+//!
+//! ```toml
+//! [[targets]]
+//! name = "ata-sepolia"
+//! chain_id = 1398243
+//! rpc_url = "${ATA_SEPOLIA_RPC_URL}"
+//! verifier = "0x1B1c7e34aF05bE9D8c93c1A0c1e1056b5272Bb6a"
+//!
+//! [targets.gas]
+//! gas_limit = 3000000
+//! max_fee_per_gas = 2000000000
+//! max_priority_fee_per_gas = 1000000
+//! max_fee_cap = 10000000000
+//! ```
+
+use alloy::primitives::Address;
+use anyhow::{bail, Context, Result};
+use serde::Deserialize;
+use std::collections::HashSet;
+use std::path::Path;
+
+#[derive(Debug, Deserialize)]
+struct TargetsFile {
+    targets: Vec<TargetEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TargetEntry {
+    name: String,
+    chain_id: u64,
+    rpc_url: String,
+    verifier: String,
+    #[serde(default)]
+    gas: GasSettings,
+}
+
+/// Gas settings of one chain; unset values are filled in from the node
+///
+/// - gas_limit: Gas limit of the transaction
+/// - max_fee_per_gas: EIP-1559 fee cap in wei
+/// - max_priority_fee_per_gas: EIP-1559 priority fee in wei
+/// - max_fee_cap: Highest fee cap in wei that queued submissions are sent with or bumped to
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct GasSettings {
+    pub gas_limit: Option<u64>,
+    pub max_fee_per_gas: Option<u128>,
+    pub max_priority_fee_per_gas: Option<u128>,
+    pub max_fee_cap: Option<u128>,
+}
+
+/// A verifier contract on one chain
+#[derive(Debug, Clone)]
+pub struct Target {
+    pub name: String,
+    pub chain_id: u64,
+    pub rpc_url: String,
+    pub verifier: Address,
+    pub gas: GasSettings,
+}
+
+/// Load the targets file, keeping only the targets named in `only` (all if empty)
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be read or parsed, a referenced
+/// environment variable is not set, a verifier address is invalid, target names
+/// are not unique, or `only` names an unknown target
+pub fn load_targets(path: &Path, only: &[String]) -> Result<Vec<Target>> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read targets file: {}", path.display()))?;
+    let file: TargetsFile = toml::from_str(&content)
+        .with_context(|| format!("Invalid targets file: {}", path.display()))?;
+
+    let mut names = HashSet::new();
+    let mut targets = Vec::new();
+    for entry in file.targets {
+        if !names.insert(entry.name.clone()) {
+            bail!("Duplicate target name: {}", entry.name);
+        }
+        if !only.is_empty() && !only.contains(&entry.name) {
+            continue;
+        }
+
+        let verifier = expand_env(&entry.verifier)
+            .with_context(|| format!("Target {}", entry.name))?;
+        targets.push(Target {
+            rpc_url: expand_env(&entry.rpc_url).with_context(|| format!("Target {}", entry.name))?,
+            verifier: verifier
+                .parse()
+                .with_context(|| format!("Target {}: invalid verifier address {}", entry.name, verifier))?,
+            name: entry.name,
+            chain_id: entry.chain_id,
+            gas: entry.gas,
+        });
+    }
+
+    if let Some(unknown) = only.iter().find(|name| !names.contains(*name)) {
+        bail!("Unknown target: {}", unknown);
+    }
+    if targets.is_empty() {
+        bail!("No targets to submit to");
+    }
+
+    Ok(targets)
+}
+
+/// Replace every `${VAR}` with the value of the environment variable `VAR`
+fn expand_env(value: &str) -> Result<String> {
+    let mut expanded = String::with_capacity(value.len());
+    let mut rest = value;
+    while let Some(start) = rest.find("${") {
+        let end = rest[start..]
+            .find('}')
+            .with_context(|| format!("Unterminated ${{...}} in {}", value))?;
+        let name = &rest[start + 2..start + end];
+        let var = std::env::var(name)
+            .with_context(|| format!("Environment variable {} is not set", name))?;
+        expanded.push_str(&rest[..start]);
+        expanded.push_str(&var);
+        rest = &rest[start + end + 1..];
+    }
+    expanded.push_str(rest);
+    Ok(expanded)
+}
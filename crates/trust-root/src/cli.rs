@@ -0,0 +1,214 @@
+//! Command-line interface definitions for trust-root
+//!
+//! Defines all CLI commands, subcommands, and arguments using clap.
+
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use sigstore_verifier::fetcher::tuf::SIGSTORE_TUF_URL;
+use std::path::{Path, PathBuf};
+
+#[derive(Parser, Debug)]
+#[command(
+    name = "trust-root",
+    author,
+    version,
+    about = "Tools for managing Sigstore trusted root files",
+    long_about = "Convert, validate, inspect and compare Sigstore trusted roots in trusted_root.json, JSONL and PEM formats"
+)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Commands,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Commands {
+    /// Convert between trusted_root.json, JSONL and PEM chains
+    Convert(ConvertArgs),
+
+    /// Check a JSONL trusted root for schema errors, reporting line and field
+    Lint(LintArgs),
+
+    /// Wrap a JSONL trusted root in an envelope signed with a governance key
+    Sign(SignArgs),
+
+    /// Verify a signed trusted root envelope against governance public keys
+    Verify(VerifyArgs),
+
+    /// Download the trusted root from a TUF repository, verifying its metadata
+    Fetch(FetchArgs),
+
+    /// List the authorities and logs of a trusted root with their key hashes
+    Inspect(InspectArgs),
+
+    /// Show the authorities and logs added, removed or changed between two trusted roots
+    Diff(DiffArgs),
+}
+
+#[derive(Args, Debug)]
+pub struct InspectArgs {
+    /// Trusted root (trusted_root.json or JSONL)
+    #[arg(value_name = "PATH")]
+    pub input: PathBuf,
+
+    /// Input format (detected from the file extension if omitted)
+    #[arg(long = "from", value_enum, value_name = "FORMAT")]
+    pub from: Option<TrustRootFormat>,
+}
+
+#[derive(Args, Debug)]
+pub struct DiffArgs {
+    /// Trusted root in use (trusted_root.json or JSONL)
+    #[arg(value_name = "OLD")]
+    pub old: PathBuf,
+
+    /// Candidate trusted root (trusted_root.json or JSONL)
+    #[arg(value_name = "NEW")]
+    pub new: PathBuf,
+
+    /// Input format of both files (detected from the file extensions if omitted)
+    #[arg(long = "from", value_enum, value_name = "FORMAT")]
+    pub from: Option<TrustRootFormat>,
+
+    /// Exit with an error if the trusted roots differ
+    #[arg(long = "exit-code")]
+    pub exit_code: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct FetchArgs {
+    /// Trusted TUF root metadata (root.json); replaced with the newest root the update verified
+    #[arg(long = "tuf-root", value_name = "PATH", required = true)]
+    pub tuf_root: PathBuf,
+
+    /// Base URL of the TUF repository
+    #[arg(long = "repository", value_name = "URL", default_value = SIGSTORE_TUF_URL)]
+    pub repository: String,
+
+    /// Trusted root to write (trusted_root.json, or JSONL for a .jsonl path)
+    #[arg(long = "output", value_name = "PATH", required = true)]
+    pub output: PathBuf,
+
+    /// Record in the output's metadata that it expires after this many days; the
+    /// hosts refuse to prove against an expired trusted root
+    #[arg(long = "expires-in", value_name = "DAYS")]
+    pub expires_in: Option<u64>,
+}
+
+#[derive(Args, Debug)]
+pub struct SignArgs {
+    /// JSONL trusted root to sign
+    #[arg(long = "input", value_name = "PATH", required = true)]
+    pub input: PathBuf,
+
+    /// Governance private key (PKCS#8 PEM, P-256 or Ed25519)
+    #[arg(long = "key", value_name = "PEM", required = true)]
+    pub key: PathBuf,
+
+    /// Key identifier recorded in the envelope as a hint
+    #[arg(long = "keyid", value_name = "ID", default_value = "")]
+    pub keyid: String,
+
+    /// Signed envelope to write
+    #[arg(long = "output", value_name = "PATH", required = true)]
+    pub output: PathBuf,
+
+    /// Record in the output's metadata that it expires after this many days; the
+    /// hosts refuse to prove against an expired trusted root
+    #[arg(long = "expires-in", value_name = "DAYS")]
+    pub expires_in: Option<u64>,
+}
+
+#[derive(Args, Debug)]
+pub struct VerifyArgs {
+    /// Signed trusted root envelope
+    #[arg(long = "input", value_name = "PATH", required = true)]
+    pub input: PathBuf,
+
+    /// Governance public key (PEM); repeat to accept several keys
+    #[arg(long = "key", value_name = "PEM", required = true)]
+    pub keys: Vec<PathBuf>,
+
+    /// Write the verified JSONL trusted root here
+    #[arg(long = "output", value_name = "PATH")]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+pub struct LintArgs {
+    /// JSONL trusted root files to check
+    #[arg(value_name = "PATH", required = true)]
+    pub inputs: Vec<PathBuf>,
+
+    /// Treat warnings as errors
+    #[arg(long = "deny-warnings")]
+    pub deny_warnings: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct ConvertArgs {
+    /// Input file
+    #[arg(long = "input", value_name = "PATH", required = true)]
+    pub input: PathBuf,
+
+    /// Output file (directory for PEM output); only validates and summarizes if omitted
+    #[arg(long = "output", value_name = "PATH")]
+    pub output: Option<PathBuf>,
+
+    /// Input format (detected from the file extension if omitted)
+    #[arg(long = "from", value_enum, value_name = "FORMAT")]
+    pub from: Option<TrustRootFormat>,
+
+    /// Output format (detected from the file extension if omitted)
+    #[arg(long = "to", value_enum, value_name = "FORMAT")]
+    pub to: Option<TrustRootFormat>,
+
+    /// Authority kind of a PEM input chain
+    #[arg(long = "kind", value_enum, default_value = "ca", value_name = "KIND")]
+    pub kind: AuthorityKindArg,
+
+    /// Authority URI of a PEM input chain (e.g. https://fulcio.sigstore.dev)
+    #[arg(long = "uri", value_name = "URI")]
+    pub uri: Option<String>,
+
+    /// Record in the output's metadata that it expires after this many days; the
+    /// hosts refuse to prove against an expired trusted root
+    #[arg(long = "expires-in", value_name = "DAYS")]
+    pub expires_in: Option<u64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum TrustRootFormat {
+    /// Official trusted_root.json (single document)
+    #[value(name = "json")]
+    Json,
+
+    /// One trusted root document per line
+    #[value(name = "jsonl")]
+    Jsonl,
+
+    /// Concatenated PEM certificate chain
+    #[value(name = "pem")]
+    Pem,
+}
+
+impl TrustRootFormat {
+    /// Detect the format from a file extension
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()? {
+            "json" => Some(TrustRootFormat::Json),
+            "jsonl" => Some(TrustRootFormat::Jsonl),
+            "pem" | "crt" => Some(TrustRootFormat::Pem),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum AuthorityKindArg {
+    /// Fulcio certificate authority ([intermediates..., root])
+    #[value(name = "ca")]
+    Ca,
+
+    /// Timestamp authority ([leaf, intermediates..., root])
+    #[value(name = "tsa")]
+    Tsa,
+}
@@ -0,0 +1,581 @@
+//! Trusted root management tool
+//!
+//! Converts Sigstore trusted roots between the official trusted_root.json
+//! format, the JSONL format consumed by the hosts, and raw PEM chains. Every
+//! conversion validates the input and prints the contained authorities with
+//! their validity windows. The lint command checks hand-edited JSONL files and
+//! points at the offending line and field. The sign and verify commands wrap a
+//! JSONL file in an envelope signed with a governance key and check one, for
+//! hosts configured with `--trust-roots-key`. The fetch command downloads the
+//! trusted root from a TUF repository, verifying the repository's metadata.
+//! The inspect and diff commands list the authorities and logs of a trusted
+//! root with the hashes of their certificates and keys, and show what an
+//! update changes, so it can be reviewed before the provers use it.
+
+mod cli;
+
+use anyhow::{bail, Context, Result};
+use clap::Parser;
+use cli::{
+    AuthorityKindArg, Commands, ConvertArgs, DiffArgs, FetchArgs, InspectArgs, LintArgs, SignArgs,
+    TrustRootFormat, VerifyArgs,
+};
+use sigstore_verifier::fetcher::jsonl::convert::{
+    authorities, cert_chain_to_pem, load_trusted_root_json, merge_trusted_roots, summarize_logs,
+    summarize_trusted_root, trusted_root_from_pem, trusted_roots_to_jsonl, validate_trusted_root,
+    AuthorityKind, AuthoritySummary, LogSummary,
+};
+use sigstore_verifier::fetcher::jsonl::diff::{diff_trusted_roots, TrustRootChange};
+use sigstore_verifier::fetcher::jsonl::governance::{trust_root_pae, TrustRootEnvelope, TrustRootKeys};
+use sigstore_verifier::fetcher::jsonl::lint::{lint_trusted_root_jsonl, Severity};
+use sigstore_verifier::fetcher::jsonl::parser::load_trusted_root_from_jsonl;
+use sigstore_verifier::fetcher::jsonl::types::TrustedRoot;
+use sigstore_verifier::fetcher::http::default_client;
+use sigstore_verifier::fetcher::tuf::fetch_trusted_root_via_tuf;
+use sigstore_zkvm_traits::freshness::TrustRootMetadata;
+use sigstore_zkvm_traits::store::unix_now;
+use std::fs;
+use std::path::Path;
+
+fn main() -> Result<()> {
+    let cli = cli::Cli::parse();
+
+    match cli.command {
+        Commands::Convert(args) => handle_convert(args),
+        Commands::Lint(args) => handle_lint(args),
+        Commands::Sign(args) => handle_sign(args),
+        Commands::Verify(args) => handle_verify(args),
+        Commands::Fetch(args) => handle_fetch(args),
+        Commands::Inspect(args) => handle_inspect(args),
+        Commands::Diff(args) => handle_diff(args),
+    }
+}
+
+/// Handle the convert command
+fn handle_convert(args: ConvertArgs) -> Result<()> {
+    // Step 1: Load the input in its source format
+    let from = args
+        .from
+        .or_else(|| TrustRootFormat::from_path(&args.input))
+        .context("Cannot detect input format, pass --from")?;
+    let content = fs::read_to_string(&args.input)
+        .with_context(|| format!("Failed to read {}", args.input.display()))?;
+
+    let roots = match from {
+        TrustRootFormat::Json => vec![load_trusted_root_json(&content)?],
+        TrustRootFormat::Jsonl => load_trusted_root_from_jsonl(&content)?,
+        TrustRootFormat::Pem => {
+            let uri = args.uri.as_deref().context("--uri is required for PEM input")?;
+            let kind = match args.kind {
+                AuthorityKindArg::Ca => AuthorityKind::CertificateAuthority,
+                AuthorityKindArg::Tsa => AuthorityKind::TimestampAuthority,
+            };
+            vec![trusted_root_from_pem(&content, kind, uri)?]
+        }
+    };
+
+    // Step 2: Validate and summarize
+    for (index, root) in roots.iter().enumerate() {
+        validate_trusted_root(root).with_context(|| format!("Trusted root #{} is invalid", index))?;
+    }
+    display_summary(&roots)?;
+
+    // Step 3: Write the output format
+    let Some(output) = args.output else {
+        return Ok(());
+    };
+    let to = args
+        .to
+        .or_else(|| TrustRootFormat::from_path(&output))
+        .unwrap_or(TrustRootFormat::Pem);
+
+    match to {
+        TrustRootFormat::Jsonl => {
+            fs::write(&output, trusted_roots_to_jsonl(&roots)?)
+                .with_context(|| format!("Failed to write {}", output.display()))?;
+            write_metadata(&output, &args.input, args.expires_in)?;
+        }
+        TrustRootFormat::Json => {
+            let merged = merge_trusted_roots(&roots).context("No trusted roots to convert")?;
+            fs::write(&output, serde_json::to_string_pretty(&merged)? + "\n")
+                .with_context(|| format!("Failed to write {}", output.display()))?;
+            write_metadata(&output, &args.input, args.expires_in)?;
+        }
+        TrustRootFormat::Pem => {
+            fs::create_dir_all(&output)
+                .with_context(|| format!("Failed to create {}", output.display()))?;
+            let mut index = 0;
+            for root in &roots {
+                for (kind, uri, _, chain, _) in authorities(root) {
+                    let host = uri.trim_start_matches("https://").split('/').next().unwrap_or(uri);
+                    let path = output.join(format!(
+                        "{}-{}-{}.pem",
+                        kind.to_string().to_lowercase(),
+                        index,
+                        host
+                    ));
+                    fs::write(&path, cert_chain_to_pem(chain)?)
+                        .with_context(|| format!("Failed to write {}", path.display()))?;
+                    index += 1;
+                }
+            }
+            if index == 0 {
+                bail!("Trusted root contains no authorities to export");
+            }
+        }
+    }
+
+    println!("\nWrote {}", output.display());
+    Ok(())
+}
+
+/// Handle the lint command
+fn handle_lint(args: LintArgs) -> Result<()> {
+    let mut errors = 0;
+    let mut warnings = 0;
+
+    for input in &args.inputs {
+        let content = fs::read_to_string(input)
+            .with_context(|| format!("Failed to read {}", input.display()))?;
+
+        for issue in lint_trusted_root_jsonl(&content) {
+            match issue.severity {
+                Severity::Error => errors += 1,
+                Severity::Warning => warnings += 1,
+            }
+            // file:line: severity: field: message, like compiler diagnostics
+            let location = if issue.path.is_empty() {
+                String::new()
+            } else {
+                format!("{}: ", issue.path)
+            };
+            println!(
+                "{}:{}: {}: {}{}",
+                input.display(),
+                issue.line,
+                issue.severity,
+                location,
+                issue.message
+            );
+        }
+    }
+
+    println!(
+        "\nChecked {} file(s): {} error(s), {} warning(s)",
+        args.inputs.len(),
+        errors,
+        warnings
+    );
+
+    if errors > 0 || (args.deny_warnings && warnings > 0) {
+        bail!("Trusted root lint failed");
+    }
+    Ok(())
+}
+
+/// Handle the sign command
+fn handle_sign(args: SignArgs) -> Result<()> {
+    // Step 1: Only sign trusted roots that load and validate
+    let jsonl = fs::read_to_string(&args.input)
+        .with_context(|| format!("Failed to read {}", args.input.display()))?;
+    let roots = load_trusted_root_from_jsonl(&jsonl)?;
+    for (index, root) in roots.iter().enumerate() {
+        validate_trusted_root(root).with_context(|| format!("Trusted root #{} is invalid", index))?;
+    }
+    display_summary(&roots)?;
+
+    // Step 2: Sign the DSSE pre-authentication encoding of the file
+    let key_pem = fs::read_to_string(&args.key)
+        .with_context(|| format!("Failed to read {}", args.key.display()))?;
+    let signature = sign_message(&key_pem, &trust_root_pae(&jsonl))?;
+
+    // Step 3: Write the envelope
+    let envelope = TrustRootEnvelope::new(&jsonl, args.keyid, &signature);
+    fs::write(&args.output, serde_json::to_string(&envelope)? + "\n")
+        .with_context(|| format!("Failed to write {}", args.output.display()))?;
+    write_metadata(&args.output, &args.input, args.expires_in)?;
+
+    println!("Wrote signed trusted root {}", args.output.display());
+    Ok(())
+}
+
+/// Handle the verify command
+fn handle_verify(args: VerifyArgs) -> Result<()> {
+    let mut keys = TrustRootKeys::default();
+    for path in &args.keys {
+        let pem = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let mut file_keys = TrustRootKeys::from_pem(&pem)
+            .with_context(|| format!("Invalid public key in {}", path.display()))?;
+        keys.append(&mut file_keys);
+    }
+
+    let content = fs::read_to_string(&args.input)
+        .with_context(|| format!("Failed to read {}", args.input.display()))?;
+    let envelope = TrustRootEnvelope::parse(&content)
+        .context("Input is not a signed trusted root envelope")?;
+    let jsonl = envelope.verify(&keys)?;
+    let roots = load_trusted_root_from_jsonl(&jsonl)?;
+    display_summary(&roots)?;
+    println!("Signature verified");
+
+    if let Some(output) = args.output {
+        fs::write(&output, jsonl)
+            .with_context(|| format!("Failed to write {}", output.display()))?;
+        println!("Wrote {}", output.display());
+    }
+    Ok(())
+}
+
+/// Handle the fetch command
+fn handle_fetch(args: FetchArgs) -> Result<()> {
+    // Step 1: Run the TUF update from the trusted root metadata
+    let root_json = fs::read(&args.tuf_root)
+        .with_context(|| format!("Failed to read {}", args.tuf_root.display()))?;
+    let update = fetch_trusted_root_via_tuf(&*default_client()?, &args.repository, &root_json)?;
+    if update.root != root_json {
+        fs::write(&args.tuf_root, &update.root)
+            .with_context(|| format!("Failed to write {}", args.tuf_root.display()))?;
+        println!("Updated {} to root version {}", args.tuf_root.display(), update.root_version);
+    }
+
+    // Step 2: Validate and summarize
+    let roots = vec![update.trusted_root];
+    validate_trusted_root(&roots[0]).context("Trusted root from the TUF repository is invalid")?;
+    display_summary(&roots)?;
+
+    // Step 3: Write the verified target, converted for a .jsonl output
+    let content = if TrustRootFormat::from_path(&args.output) == Some(TrustRootFormat::Jsonl) {
+        trusted_roots_to_jsonl(&roots)?.into_bytes()
+    } else {
+        update.trusted_root_json
+    };
+    fs::write(&args.output, content).with_context(|| format!("Failed to write {}", args.output.display()))?;
+    let now = unix_now();
+    TrustRootMetadata {
+        source: args.repository,
+        fetched_at: now,
+        expires: args.expires_in.map(|days| now + days * 86_400),
+    }
+    .save(&args.output)?;
+
+    println!("Wrote {}", args.output.display());
+    Ok(())
+}
+
+/// Handle the inspect command
+fn handle_inspect(args: InspectArgs) -> Result<()> {
+    let roots = load_roots(&args.input, args.from)?;
+
+    for (index, root) in roots.iter().enumerate() {
+        println!("=== Trusted Root #{} ({}) ===", index, root.media_type);
+        for authority in summarize_trusted_root(root)? {
+            print_authority(' ', &authority);
+        }
+        for log in summarize_logs(root)? {
+            print_log(' ', &log);
+        }
+        println!();
+    }
+    Ok(())
+}
+
+/// Handle the diff command
+fn handle_diff(args: DiffArgs) -> Result<()> {
+    let old = load_roots(&args.old, args.from)?;
+    let new = load_roots(&args.new, args.from)?;
+
+    let changes = diff_trusted_roots(&old, &new)?;
+    for change in &changes {
+        match change {
+            TrustRootChange::AuthorityAdded(authority) => print_authority('+', authority),
+            TrustRootChange::AuthorityRemoved(authority) => print_authority('-', authority),
+            TrustRootChange::AuthorityValidityChanged { old, new } => {
+                print_authority('~', new);
+                println!("  Was:      {}", validity(&old.valid_from, &old.valid_until));
+            }
+            TrustRootChange::LogAdded(log) => print_log('+', log),
+            TrustRootChange::LogRemoved(log) => print_log('-', log),
+            TrustRootChange::LogValidityChanged { old, new } => {
+                print_log('~', new);
+                println!("  Was:      {}", validity(&old.valid_from, &old.valid_until));
+            }
+        }
+    }
+
+    println!(
+        "\n{} change(s) between {} and {}",
+        changes.len(),
+        args.old.display(),
+        args.new.display()
+    );
+    if args.exit_code && !changes.is_empty() {
+        bail!("Trusted roots differ");
+    }
+    Ok(())
+}
+
+/// Load the documents of a trusted_root.json or JSONL file
+fn load_roots(path: &Path, from: Option<TrustRootFormat>) -> Result<Vec<TrustedRoot>> {
+    let format = from
+        .or_else(|| TrustRootFormat::from_path(path))
+        .with_context(|| format!("Cannot detect the format of {}, pass --from", path.display()))?;
+    let content = fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+
+    match format {
+        TrustRootFormat::Json => Ok(vec![load_trusted_root_json(&content)?]),
+        TrustRootFormat::Jsonl => Ok(load_trusted_root_from_jsonl(&content)?),
+        TrustRootFormat::Pem => bail!(
+            "{} is a PEM chain, which has no validity windows; convert it to JSONL first",
+            path.display()
+        ),
+    }
+}
+
+/// Print an authority with the SHA256 of each certificate, prefixed with a diff marker
+fn print_authority(marker: char, authority: &AuthoritySummary) {
+    println!("\n{} [{}] {}", marker, authority.kind, authority.uri);
+    println!("  Subject:  {}", authority.subject);
+    println!("  Valid:    {}", validity(&authority.valid_from, &authority.valid_until));
+    for (i, cert) in authority.certificates.iter().enumerate() {
+        println!("  [{}] {}", i, cert.subject);
+        println!("      {} -> {}", cert.not_before, cert.not_after);
+        println!("      sha256:{}", hex::encode(cert.sha256));
+    }
+}
+
+/// Print a log with the SHA256 of its key, prefixed with a diff marker
+fn print_log(marker: char, log: &LogSummary) {
+    println!("\n{} [{}] {}", marker, log.kind, log.base_url);
+    match log.key_hash {
+        Some(hash) => println!("  Key:      sha256:{}", hex::encode(hash)),
+        None => println!("  Key:      (none)"),
+    }
+    println!("  Valid:    {}", validity(&log.valid_from, &log.valid_until));
+}
+
+fn validity(from: &Option<String>, until: &Option<String>) -> String {
+    format!(
+        "{} -> {}",
+        from.as_deref().unwrap_or("-"),
+        until.as_deref().unwrap_or("(ongoing)")
+    )
+}
+
+/// Date a written trusted root for the hosts' freshness check
+///
+/// Without `expires_in` the file is dated by its modification time, so the
+/// metadata of an earlier output is removed.
+fn write_metadata(output: &Path, input: &Path, expires_in: Option<u64>) -> Result<()> {
+    let Some(days) = expires_in else {
+        let path = TrustRootMetadata::path_for(output);
+        if path.exists() {
+            fs::remove_file(&path).with_context(|| format!("Failed to remove {}", path.display()))?;
+        }
+        return Ok(());
+    };
+    let now = unix_now();
+    TrustRootMetadata {
+        source: input.display().to_string(),
+        fetched_at: now,
+        expires: Some(now + days * 86_400),
+    }
+    .save(output)
+}
+
+/// Sign `message` with a PKCS#8 P-256 or Ed25519 private key
+fn sign_message(key_pem: &str, message: &[u8]) -> Result<Vec<u8>> {
+    use p256::ecdsa::signature::Signer;
+    use p256::pkcs8::DecodePrivateKey;
+
+    if let Ok(key) = p256::ecdsa::SigningKey::from_pkcs8_pem(key_pem) {
+        let signature: p256::ecdsa::Signature = key.sign(message);
+        return Ok(signature.to_der().as_bytes().to_vec());
+    }
+    if let Ok(key) = ed25519_dalek::SigningKey::from_pkcs8_pem(key_pem) {
+        return Ok(key.sign(message).to_bytes().to_vec());
+    }
+    bail!("Unsupported private key: expected a PKCS#8 P-256 or Ed25519 key")
+}
+
+/// Print the authorities of each trusted root with their validity windows
+fn display_summary(roots: &[TrustedRoot]) -> Result<()> {
+    for (index, root) in roots.iter().enumerate() {
+        println!("=== Trusted Root #{} ({}) ===", index, root.media_type);
+        println!("Transparency logs: {}  CT logs: {}", root.tlogs.len(), root.ctlogs.len());
+
+        for authority in summarize_trusted_root(root)? {
+            println!("\n[{}] {}", authority.kind, authority.uri);
+            println!("  Subject:  {}", authority.subject);
+            println!(
+                "  Valid:    {} -> {}",
+                authority.valid_from.as_deref().unwrap_or("-"),
+                authority.valid_until.as_deref().unwrap_or("(ongoing)")
+            );
+            for (i, cert) in authority.certificates.iter().enumerate() {
+                println!("  [{}] {}", i, cert.subject);
+                println!("      {} -> {}", cert.not_before, cert.not_after);
+            }
+        }
+        println!();
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeSet;
+    use std::path::PathBuf;
+
+    fn sample_path() -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("../../samples/trusted_root.jsonl")
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("trust-root-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn convert(input: &Path, output: &Path, from: Option<TrustRootFormat>, to: TrustRootFormat) {
+        handle_convert(ConvertArgs {
+            input: input.to_path_buf(),
+            output: Some(output.to_path_buf()),
+            from,
+            to: Some(to),
+            kind: AuthorityKindArg::Ca,
+            uri: None,
+            expires_in: None,
+        })
+        .unwrap();
+    }
+
+    /// Every authority by kind, URI and certificate hashes, and every log by kind, URL and key hash
+    fn contents(roots: &[TrustedRoot]) -> (BTreeSet<String>, BTreeSet<String>) {
+        let mut authorities = BTreeSet::new();
+        let mut logs = BTreeSet::new();
+        for root in roots {
+            for authority in summarize_trusted_root(root).unwrap() {
+                let hashes: Vec<_> =
+                    authority.certificates.iter().map(|c| hex::encode(c.sha256)).collect();
+                let hashes = hashes.join(",");
+                authorities.insert(format!("{} {} {}", authority.kind, authority.uri, hashes));
+            }
+            for log in summarize_logs(root).unwrap() {
+                let key_hash = log.key_hash.map(hex::encode);
+                logs.insert(format!("{} {} {:?}", log.kind, log.base_url, key_hash));
+            }
+        }
+        (authorities, logs)
+    }
+
+    #[test]
+    fn test_convert_roundtrip() {
+        let dir = temp_dir("convert");
+        let sample = load_roots(&sample_path(), None).unwrap();
+
+        // JSONL -> trusted_root.json -> JSONL keeps every authority and log
+        let json = dir.join("trusted_root.json");
+        let jsonl = dir.join("roundtrip.jsonl");
+        convert(&sample_path(), &json, None, TrustRootFormat::Json);
+        convert(&json, &jsonl, None, TrustRootFormat::Jsonl);
+        let roundtrip = load_roots(&jsonl, None).unwrap();
+        assert_eq!(roundtrip.len(), 1);
+        assert_eq!(contents(&roundtrip), contents(&sample));
+        assert_eq!(contents(&load_roots(&json, None).unwrap()), contents(&sample));
+
+        // JSONL -> PEM chains -> JSONL keeps every certificate chain
+        let pem_dir = dir.join("pem");
+        convert(&sample_path(), &pem_dir, None, TrustRootFormat::Pem);
+        let mut index = 0;
+        for root in &sample {
+            for (kind, uri, _, chain, _) in authorities(root) {
+                let host = uri.trim_start_matches("https://").split('/').next().unwrap();
+                let kind_name = kind.to_string().to_lowercase();
+                let pem = pem_dir.join(format!("{}-{}-{}.pem", kind_name, index, host));
+                let rebuilt = dir.join(format!("{}.jsonl", index));
+                handle_convert(ConvertArgs {
+                    input: pem,
+                    output: Some(rebuilt.clone()),
+                    from: None,
+                    to: None,
+                    kind: match kind {
+                        AuthorityKind::CertificateAuthority => AuthorityKindArg::Ca,
+                        AuthorityKind::TimestampAuthority => AuthorityKindArg::Tsa,
+                    },
+                    uri: Some(uri.to_string()),
+                    expires_in: None,
+                })
+                .unwrap();
+
+                let rebuilt = load_roots(&rebuilt, None).unwrap();
+                let (_, _, _, rebuilt_chain, _) = authorities(&rebuilt[0]).next().unwrap();
+                let original: Vec<_> = chain.certificates.iter().map(|c| &c.raw_bytes).collect();
+                let converted: Vec<_> =
+                    rebuilt_chain.certificates.iter().map(|c| &c.raw_bytes).collect();
+                assert_eq!(converted, original, "{} {}", kind, uri);
+                index += 1;
+            }
+        }
+        assert_eq!(fs::read_dir(&pem_dir).unwrap().count(), index);
+        assert!(index > 0);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_inspect_and_diff_rotated_log_key() {
+        use base64::prelude::*;
+        use p256::pkcs8::EncodePublicKey;
+        use sigstore_verifier::crypto::hash::sha256;
+
+        let dir = temp_dir("diff");
+        let old = load_roots(&sample_path(), None).unwrap();
+
+        // The same trusted root with the key of its first Rekor log rotated
+        let key = p256::SecretKey::from_slice(&[7; 32]).unwrap().public_key();
+        let der = key.to_public_key_der().unwrap().into_vec();
+        let mut new = old.clone();
+        let log = &mut new[0].tlogs[0];
+        let old_key_hash = summarize_logs(&old[0]).unwrap()[0].key_hash;
+        log.public_key.as_mut().unwrap().raw_bytes = Some(BASE64_STANDARD.encode(&der));
+        log.log_id.as_mut().unwrap().key_id = BASE64_STANDARD.encode(sha256(&der));
+        let rotated = dir.join("rotated.jsonl");
+        fs::write(&rotated, trusted_roots_to_jsonl(&new).unwrap()).unwrap();
+
+        for path in [sample_path(), rotated.clone()] {
+            handle_inspect(InspectArgs { input: path, from: None }).unwrap();
+        }
+
+        let diff = |old: &Path, new: &Path, exit_code| {
+            handle_diff(DiffArgs {
+                old: old.to_path_buf(),
+                new: new.to_path_buf(),
+                from: None,
+                exit_code,
+            })
+        };
+        diff(&sample_path(), &sample_path(), true).unwrap();
+        diff(&sample_path(), &rotated, false).unwrap();
+        let err = diff(&sample_path(), &rotated, true).unwrap_err();
+        assert_eq!(err.to_string(), "Trusted roots differ");
+
+        // The rotation is one log removed and one added under the same URL
+        let changes = diff_trusted_roots(&old, &load_roots(&rotated, None).unwrap()).unwrap();
+        assert_eq!(changes.len(), 2, "{:?}", changes);
+        assert!(matches!(
+            &changes[0],
+            TrustRootChange::LogRemoved(log) if log.key_hash == old_key_hash
+        ));
+        assert!(matches!(
+            &changes[1],
+            TrustRootChange::LogAdded(log)
+                if log.key_hash == Some(sha256(&der)) && log.base_url == new[0].tlogs[0].base_url
+        ));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,254 @@
+//! Fixtures for consuming Sigstore verification proofs on-chain
+//!
+//! `SigstoreAttestationVerifier.verifyAndAttestWithZKProof(output, zkCoProcessor,
+//! proofBytes)` takes the journal the guest commits (`VerificationResult::as_slice()`)
+//! as `output`. An [`OnchainFixture`] bundles that journal with everything a
+//! Foundry or Hardhat test needs to call the contract with it: the co-processor
+//! value, the program ID, the proof, the journal digest the RiscZero verifier
+//! checks, the ABI-encoded calldata, and the decoded fields the call must return.
+//!
+//! Fixtures are built either from a real proof artifact, or in dev mode from a
+//! guest input alone: the journal is computed natively, exactly as the guest
+//! commits it, and the proof is one the zkVMs' mock verifiers accept.
+
+use alloy_sol_types::{sol, SolCall};
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sigstore_verifier::types::result::VerificationResult;
+use sigstore_zkvm_traits::types::ProverInput;
+use sigstore_zkvm_traits::utils::ProofArtifact;
+use sigstore_zkvm_traits::workflow::expected_journal;
+use std::fs;
+use std::path::Path;
+
+sol! {
+    /// `ZkCoProcessorType` is ABI-encoded as `uint8`
+    interface ISigstoreAttestationVerifier {
+        function verifyAndAttestWithZKProof(bytes calldata output, uint8 zkCoProcessor, bytes calldata proofBytes) external;
+    }
+}
+
+/// Program ID of dev mode fixtures
+///
+/// The contract rejects a zero program ID, so dev mode fixtures use `1`; the
+/// mock verifiers accept proofs for any program ID.
+pub const DEV_PROGRAM_ID: [u8; 32] = {
+    let mut id = [0u8; 32];
+    id[31] = 1;
+    id
+};
+
+/// `CARGO_PKG_VERSION` of the guest programs, committed in their journals
+///
+/// Must match the `version` of `crates/{risc0/guest,sp1/program,pico/program}/Cargo.toml`.
+pub const GUEST_VERSION: &str = "0.1.0";
+
+/// Values of the contract's `ZkCoProcessorType` enum
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Coprocessor {
+    RiscZero = 1,
+    Succinct = 2,
+    Pico = 3,
+}
+
+impl Coprocessor {
+    /// Co-processor of a proof artifact's `zkvm`
+    pub fn from_zkvm(zkvm: &str) -> Result<Self> {
+        match zkvm {
+            "risc0" => Ok(Self::RiscZero),
+            "sp1" => Ok(Self::Succinct),
+            "pico" => Ok(Self::Pico),
+            other => bail!("Unknown zkVM: {}", other),
+        }
+    }
+
+    /// `zkvm` of the co-processor's proof artifacts
+    pub fn zkvm(self) -> &'static str {
+        match self {
+            Self::RiscZero => "risc0",
+            Self::Succinct => "sp1",
+            Self::Pico => "pico",
+        }
+    }
+
+    /// Environment variable `script/Deploy.s.sol` and `script/Config.s.sol` read the program ID from
+    pub fn program_id_env(self) -> &'static str {
+        match self {
+            Self::RiscZero => "RISC_ZERO_IMAGE_ID",
+            Self::Succinct => "SP1_VKEY",
+            Self::Pico => "PICO_VKEY",
+        }
+    }
+
+    /// Proof of a dev mode fixture
+    ///
+    /// `SP1MockVerifier` accepts an empty proof. The contract decodes Pico proofs
+    /// as `uint256[8]` before calling the verifier, so a Pico mock gets eight zero
+    /// words. RiscZero's mock verifier only accepts the seal returned by its
+    /// `mockProve(imageId, journalDigest)`, which the test builds from
+    /// [`OnchainFixture::journal_digest`]; the proof is left empty.
+    pub fn dev_proof(self) -> Vec<u8> {
+        match self {
+            Self::RiscZero | Self::Succinct => Vec::new(),
+            Self::Pico => vec![0u8; 8 * 32],
+        }
+    }
+}
+
+/// Fields of the journal a test can assert on after the call
+///
+/// Digests are `0x`-prefixed hex; absent OIDC claims are empty strings, as the
+/// contract decodes them.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExpectedResult {
+    /// Signing time, in seconds since the Unix epoch
+    pub timestamp: u64,
+    pub subject_digest: String,
+    pub oidc_issuer: String,
+    pub oidc_subject: String,
+    pub oidc_workflow_ref: String,
+    pub oidc_repository: String,
+    pub input_digest: String,
+}
+
+impl ExpectedResult {
+    fn from_result(result: &VerificationResult) -> Self {
+        let identity = result.oidc_identity.as_ref();
+        let claim = |value: Option<&Option<String>>| value.cloned().flatten().unwrap_or_default();
+        Self {
+            timestamp: result.signing_time.timestamp() as u64,
+            subject_digest: to_hex(&result.subject_digest),
+            oidc_issuer: claim(identity.map(|identity| &identity.issuer)),
+            oidc_subject: claim(identity.map(|identity| &identity.subject)),
+            oidc_workflow_ref: claim(identity.map(|identity| &identity.workflow_ref)),
+            oidc_repository: claim(identity.map(|identity| &identity.repository)),
+            input_digest: to_hex(&result.input_digest),
+        }
+    }
+}
+
+/// Inputs of a `verifyAndAttestWithZKProof` call, and what it returns
+///
+/// Serialized as JSON with `0x`-prefixed hex byte strings, so Foundry reads
+/// each field with `vm.parseJson` (e.g. `abi.decode(vm.parseJson(json, ".journal"), (bytes))`)
+/// and Hardhat with `JSON.parse`. `zkvm`, `program_id`, `journal` and `proof`
+/// are named as in proof artifacts, so the reader of `contracts/test/fixtures`
+/// in `SigstoreTest.t.sol` accepts these fixtures too.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OnchainFixture {
+    pub zkvm: String,
+    /// `ZkCoProcessorType` value passed as `zkCoProcessor`
+    pub coprocessor: u8,
+    /// Program ID to configure with `setZkCoProcessorConfig`
+    pub program_id: String,
+    /// Environment variable the deployment scripts read `program_id` from
+    pub program_id_env: String,
+    /// Whether `proof` is a dev mode proof, accepted only by a mock verifier
+    pub dev_mode: bool,
+    /// `output` argument: the journal
+    pub journal: String,
+    /// SHA256 of the journal, as the RiscZero verifier is called with
+    pub journal_digest: String,
+    /// `proofBytes` argument
+    pub proof: String,
+    /// ABI-encoded `verifyAndAttestWithZKProof` call, e.g. for `cast send <verifier> <calldata>`
+    pub calldata: String,
+    pub expected: ExpectedResult,
+}
+
+impl OnchainFixture {
+    /// Dev mode fixture for `input`, without running a zkVM
+    ///
+    /// The journal is the one a guest of [`GUEST_VERSION`] commits for `input`
+    /// (see [`expected_journal`]), and the proof is [`Coprocessor::dev_proof`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the bundle fails verification
+    pub fn dev(input: &ProverInput, coprocessor: Coprocessor, program_id: [u8; 32]) -> Result<Self> {
+        let journal = expected_journal(input, GUEST_VERSION)?.as_slice();
+        Self::new(coprocessor, program_id, journal, coprocessor.dev_proof(), true)
+    }
+
+    /// Fixture for a proof artifact written by a host
+    ///
+    /// Artifacts proven in dev mode have an empty proof, which is replaced by
+    /// [`Coprocessor::dev_proof`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the zkVM is unknown, the program ID is not 32 bytes, or
+    /// the journal or proof cannot be decoded
+    pub fn from_artifact(artifact: &ProofArtifact) -> Result<Self> {
+        let coprocessor = Coprocessor::from_zkvm(&artifact.zkvm)?;
+        let program_id: [u8; 32] = decode_hex(&artifact.program_id)
+            .context("Invalid program ID in proof artifact")?
+            .try_into()
+            .map_err(|_| anyhow::anyhow!("Program ID in proof artifact is not 32 bytes"))?;
+        let proof = decode_hex(&artifact.proof).context("Invalid proof in proof artifact")?;
+        let dev_mode = proof.is_empty();
+        let proof = if dev_mode { coprocessor.dev_proof() } else { proof };
+        Self::new(coprocessor, program_id, artifact.journal()?, proof, dev_mode)
+    }
+
+    fn new(
+        coprocessor: Coprocessor,
+        program_id: [u8; 32],
+        journal: Vec<u8>,
+        proof: Vec<u8>,
+        dev_mode: bool,
+    ) -> Result<Self> {
+        let result = VerificationResult::from_slice(&journal)
+            .map_err(anyhow::Error::msg)
+            .context("Invalid journal")?;
+        let calldata = ISigstoreAttestationVerifier::verifyAndAttestWithZKProofCall {
+            output: journal.clone().into(),
+            zkCoProcessor: coprocessor as u8,
+            proofBytes: proof.clone().into(),
+        }
+        .abi_encode();
+
+        Ok(Self {
+            zkvm: coprocessor.zkvm().to_string(),
+            coprocessor: coprocessor as u8,
+            program_id: to_hex(&program_id),
+            program_id_env: coprocessor.program_id_env().to_string(),
+            dev_mode,
+            journal_digest: to_hex(&Sha256::digest(&journal)),
+            journal: to_hex(&journal),
+            proof: to_hex(&proof),
+            calldata: to_hex(&calldata),
+            expected: ExpectedResult::from_result(&result),
+        })
+    }
+
+    /// Decoded journal
+    pub fn journal(&self) -> Result<Vec<u8>> {
+        decode_hex(&self.journal).context("Invalid fixture journal")
+    }
+
+    /// Decoded calldata
+    pub fn calldata(&self) -> Result<Vec<u8>> {
+        decode_hex(&self.calldata).context("Invalid fixture calldata")
+    }
+
+    /// Write the fixture as pretty-printed JSON, creating the parent directory
+    pub fn write(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+            fs::create_dir_all(parent)
+                .context(format!("Failed to create directory: {}", parent.display()))?;
+        }
+        let json = serde_json::to_string_pretty(self).context("Failed to serialize fixture")?;
+        fs::write(path, json + "\n").context(format!("Failed to write fixture to: {}", path.display()))
+    }
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    format!("0x{}", hex::encode(bytes))
+}
+
+/// Decode `0x`-prefixed or bare hex
+fn decode_hex(value: &str) -> Result<Vec<u8>> {
+    Ok(hex::decode(value.strip_prefix("0x").unwrap_or(value))?)
+}
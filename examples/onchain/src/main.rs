@@ -0,0 +1,114 @@
+//! Writes on-chain fixtures for Foundry and Hardhat tests
+//!
+//! The dev command verifies a bundle natively and writes the journal a guest
+//! would commit for it, with a proof the mock verifiers accept, so contract
+//! tests can be written before any proof is generated. The artifact command
+//! converts a proof artifact written by a host.
+
+use anyhow::{Context, Result};
+use clap::{Parser, Subcommand, ValueEnum};
+use onchain_fixtures::{Coprocessor, OnchainFixture, DEV_PROGRAM_ID};
+use sigstore_verifier::fetcher::jsonl::governance::TrustRootKeys;
+use sigstore_verifier::types::result::VerificationOptions;
+use sigstore_zkvm_traits::utils::read_proof_artifact;
+use sigstore_zkvm_traits::workflow::{prepare_guest_input_local, TrustSource};
+use std::path::PathBuf;
+
+#[derive(Parser, Debug)]
+#[command(
+    name = "onchain-fixtures",
+    author,
+    version,
+    about = "Write fixtures for calling SigstoreAttestationVerifier from Foundry or Hardhat tests"
+)]
+struct Cli {
+    #[command(subcommand)]
+    command: Commands,
+}
+
+#[derive(Subcommand, Debug)]
+enum Commands {
+    /// Dev mode fixture for a bundle, without running a zkVM
+    Dev {
+        /// Path to the Sigstore bundle JSON
+        #[arg(long)]
+        bundle: PathBuf,
+
+        /// Path to the trusted root JSONL file
+        #[arg(long)]
+        trust_roots: PathBuf,
+
+        /// zkVM whose co-processor the fixture calls
+        #[arg(long, value_enum)]
+        zkvm: ZkVm,
+
+        /// Program ID (32-byte hex) to configure the mock verifier with
+        #[arg(long, default_value_t = format!("0x{}", hex::encode(DEV_PROGRAM_ID)))]
+        program_id: String,
+
+        /// Path to write the fixture to
+        #[arg(long, short)]
+        output: PathBuf,
+    },
+
+    /// Fixture for a proof artifact written by a host
+    Artifact {
+        /// Path to the proof artifact
+        #[arg(long)]
+        artifact: PathBuf,
+
+        /// Path to write the fixture to
+        #[arg(long, short)]
+        output: PathBuf,
+    },
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy)]
+enum ZkVm {
+    Risc0,
+    Sp1,
+    Pico,
+}
+
+impl From<ZkVm> for Coprocessor {
+    fn from(zkvm: ZkVm) -> Self {
+        match zkvm {
+            ZkVm::Risc0 => Coprocessor::RiscZero,
+            ZkVm::Sp1 => Coprocessor::Succinct,
+            ZkVm::Pico => Coprocessor::Pico,
+        }
+    }
+}
+
+fn main() -> Result<()> {
+    let (fixture, output) = match Cli::parse().command {
+        Commands::Dev {
+            bundle,
+            trust_roots,
+            zkvm,
+            program_id,
+            output,
+        } => {
+            let trust = TrustSource::TrustedRoot {
+                path: trust_roots,
+                keys: TrustRootKeys::default(),
+            };
+            let input = prepare_guest_input_local(&bundle, &trust, VerificationOptions::default())?;
+            let program_id: [u8; 32] = hex::decode(program_id.strip_prefix("0x").unwrap_or(&program_id))
+                .context("Invalid program ID")?
+                .try_into()
+                .map_err(|_| anyhow::anyhow!("Program ID is not 32 bytes"))?;
+            (OnchainFixture::dev(&input, zkvm.into(), program_id)?, output)
+        }
+        Commands::Artifact { artifact, output } => {
+            (OnchainFixture::from_artifact(&read_proof_artifact(&artifact)?)?, output)
+        }
+    };
+
+    fixture.write(&output)?;
+    println!("Fixture written to: {}", output.display());
+    println!("  zkVM:       {} (zkCoProcessor = {})", fixture.zkvm, fixture.coprocessor);
+    println!("  Program ID: {} ({})", fixture.program_id, fixture.program_id_env);
+    println!("  Dev mode:   {}", fixture.dev_mode);
+    Ok(())
+}
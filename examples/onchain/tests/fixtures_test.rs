@@ -0,0 +1,122 @@
+use alloy_sol_types::SolCall;
+use onchain_fixtures::{Coprocessor, ISigstoreAttestationVerifier, OnchainFixture, DEV_PROGRAM_ID, GUEST_VERSION};
+use sigstore_verifier::fetcher::jsonl::governance::TrustRootKeys;
+use sigstore_verifier::types::result::{VerificationOptions, VerificationResult};
+use sigstore_zkvm_traits::types::ProverInput;
+use sigstore_zkvm_traits::utils::ProofArtifact;
+use sigstore_zkvm_traits::workflow::{prepare_guest_input_local, verify_guest_input, TrustSource};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+fn repo_path(path: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("../..").join(path)
+}
+
+fn sample_input() -> ProverInput {
+    let trust = TrustSource::TrustedRoot {
+        path: repo_path("samples/trusted_root.jsonl"),
+        keys: TrustRootKeys::default(),
+    };
+    prepare_guest_input_local(
+        &repo_path("samples/actions-attest-build-provenance-attestation-13532655.sigstore.json"),
+        &trust,
+        VerificationOptions::default(),
+    )
+    .unwrap()
+}
+
+/// Decode the fixture's calldata as the contract would receive it
+fn decode_call(fixture: &OnchainFixture) -> ISigstoreAttestationVerifier::verifyAndAttestWithZKProofCall {
+    ISigstoreAttestationVerifier::verifyAndAttestWithZKProofCall::abi_decode(&fixture.calldata().unwrap()).unwrap()
+}
+
+#[test]
+fn test_dev_fixture_commits_guest_journal() {
+    let input = sample_input();
+    let fixture = OnchainFixture::dev(&input, Coprocessor::Succinct, DEV_PROGRAM_ID).unwrap();
+
+    let result = VerificationResult::from_slice(&fixture.journal().unwrap()).unwrap();
+    let native = verify_guest_input(&input).unwrap();
+    assert_eq!(result.guest_version, GUEST_VERSION);
+    assert_eq!(result.input_digest, input.digest().unwrap());
+    assert_eq!(result.subject_digest, native.subject_digest);
+    assert_eq!(result.certificate_hashes, native.certificate_hashes);
+
+    assert!(fixture.dev_mode);
+    assert_eq!(fixture.zkvm, "sp1");
+    assert_eq!(fixture.program_id_env, "SP1_VKEY");
+    assert_eq!(fixture.program_id, format!("0x{}01", "00".repeat(31)));
+    assert_eq!(fixture.expected.timestamp, native.signing_time.timestamp() as u64);
+    assert_eq!(fixture.expected.subject_digest, format!("0x{}", hex::encode(&native.subject_digest)));
+    assert_eq!(fixture.expected.oidc_issuer, "https://token.actions.githubusercontent.com");
+}
+
+#[test]
+fn test_calldata_encodes_call_arguments() {
+    let input = sample_input();
+    for coprocessor in [Coprocessor::RiscZero, Coprocessor::Succinct, Coprocessor::Pico] {
+        let fixture = OnchainFixture::dev(&input, coprocessor, DEV_PROGRAM_ID).unwrap();
+        let call = decode_call(&fixture);
+
+        assert_eq!(call.output.as_ref(), fixture.journal().unwrap().as_slice());
+        assert_eq!(call.zkCoProcessor, coprocessor as u8);
+        assert_eq!(call.proofBytes.as_ref(), coprocessor.dev_proof().as_slice());
+        assert_eq!(fixture.coprocessor, coprocessor as u8);
+    }
+
+    // The contract decodes Pico proofs as uint256[8] before calling the verifier
+    assert_eq!(Coprocessor::Pico.dev_proof().len(), 8 * 32);
+}
+
+fn artifact(zkvm: &str, journal: &str, proof: &str) -> ProofArtifact {
+    ProofArtifact {
+        zkvm: zkvm.to_string(),
+        program_id: format!("0x{}", "ab".repeat(32)),
+        circuit_version: "v5.0.0".to_string(),
+        journal: journal.to_string(),
+        proof: proof.to_string(),
+        tee_attestation: None,
+        annotations: BTreeMap::new(),
+        metadata: BTreeMap::new(),
+    }
+}
+
+#[test]
+fn test_fixture_from_artifact() {
+    let journal = OnchainFixture::dev(&sample_input(), Coprocessor::Succinct, DEV_PROGRAM_ID).unwrap().journal;
+
+    let fixture = OnchainFixture::from_artifact(&artifact("sp1", &journal, "0x1234")).unwrap();
+    assert!(!fixture.dev_mode);
+    assert_eq!(fixture.program_id, format!("0x{}", "ab".repeat(32)));
+    assert_eq!(fixture.journal, journal);
+    let call = decode_call(&fixture);
+    assert_eq!(call.zkCoProcessor, 2);
+    assert_eq!(call.proofBytes.as_ref(), [0x12, 0x34]);
+
+    // Hosts in dev mode write an empty proof
+    let fixture = OnchainFixture::from_artifact(&artifact("pico", &journal, "0x")).unwrap();
+    assert!(fixture.dev_mode);
+    assert_eq!(decode_call(&fixture).proofBytes.as_ref(), Coprocessor::Pico.dev_proof().as_slice());
+
+    assert!(OnchainFixture::from_artifact(&artifact("jolt", &journal, "0x")).is_err());
+    assert!(OnchainFixture::from_artifact(&artifact("sp1", "0x00", "0x")).is_err());
+}
+
+#[test]
+fn test_fixture_json_has_proof_artifact_fields() {
+    let fixture = OnchainFixture::dev(&sample_input(), Coprocessor::Pico, DEV_PROGRAM_ID).unwrap();
+    let dir = std::env::temp_dir().join(format!("onchain-fixtures-{}", std::process::id()));
+    let path = dir.join("pico-dev.json");
+    fixture.write(&path).unwrap();
+
+    let json = std::fs::read_to_string(&path).unwrap();
+    let parsed: OnchainFixture = serde_json::from_str(&json).unwrap();
+    assert_eq!(parsed, fixture);
+
+    // SigstoreTest.t.sol reads .journal and .proof of its fixtures
+    let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(value["zkvm"], "pico");
+    assert_eq!(value["journal"], fixture.journal);
+    assert_eq!(value["proof"], format!("0x{}", "00".repeat(8 * 32)));
+    std::fs::remove_dir_all(dir).unwrap();
+}